@@ -0,0 +1,9 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS quota_threshold_events;
+
+ALTER TABLE api_keys
+DROP CONSTRAINT IF EXISTS check_grace_overage_percent;
+
+ALTER TABLE api_keys
+DROP COLUMN IF EXISTS soft_thresholds,
+DROP COLUMN IF EXISTS grace_overage_percent;
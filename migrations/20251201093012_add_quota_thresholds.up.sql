@@ -0,0 +1,24 @@
+-- Add up migration script here
+ALTER TABLE api_keys
+ADD COLUMN soft_thresholds INTEGER[] NOT NULL DEFAULT '{80,100}'::INTEGER[],
+ADD COLUMN grace_overage_percent INTEGER NOT NULL DEFAULT 0;
+
+ALTER TABLE api_keys
+ADD CONSTRAINT check_grace_overage_percent
+CHECK (grace_overage_percent >= 0 AND grace_overage_percent <= 100);
+
+CREATE TABLE quota_threshold_events (
+    id BIGSERIAL PRIMARY KEY,
+    key_id VARCHAR(255) NOT NULL,
+    period TEXT NOT NULL CHECK (period IN ('daily', 'monthly')),
+    period_start DATE NOT NULL,
+    threshold_percent INTEGER NOT NULL,
+    usage_count INTEGER NOT NULL,
+    quota_limit INTEGER NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (key_id, period, period_start, threshold_percent),
+    FOREIGN KEY (key_id) REFERENCES api_keys(key_id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_quota_threshold_events_key_created
+ON quota_threshold_events(key_id, created_at DESC);
@@ -0,0 +1,7 @@
+-- Add down migration script here
+ALTER TABLE api_keys
+DROP CONSTRAINT IF EXISTS check_billing_anchor_day;
+
+ALTER TABLE api_keys
+DROP COLUMN IF EXISTS quota_timezone,
+DROP COLUMN IF EXISTS billing_anchor_day;
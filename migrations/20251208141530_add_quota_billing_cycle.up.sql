@@ -0,0 +1,8 @@
+-- Add up migration script here
+ALTER TABLE api_keys
+ADD COLUMN quota_timezone TEXT NOT NULL DEFAULT 'UTC',
+ADD COLUMN billing_anchor_day INTEGER NOT NULL DEFAULT 1;
+
+ALTER TABLE api_keys
+ADD CONSTRAINT check_billing_anchor_day
+CHECK (billing_anchor_day >= 1 AND billing_anchor_day <= 31);
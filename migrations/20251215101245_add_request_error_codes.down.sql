@@ -0,0 +1,6 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS idx_requests_path_ts;
+DROP INDEX IF EXISTS idx_requests_error_code;
+
+ALTER TABLE requests
+DROP COLUMN IF EXISTS error_code;
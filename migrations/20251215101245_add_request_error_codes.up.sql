@@ -0,0 +1,9 @@
+-- Add up migration script here
+ALTER TABLE requests
+ADD COLUMN IF NOT EXISTS error_code TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_requests_error_code
+ON requests(error_code, ts DESC) WHERE error_code IS NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_requests_path_ts
+ON requests(path, ts DESC);
@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS request_rollup_state;
+DROP TABLE IF EXISTS request_rollups_hour;
+DROP TABLE IF EXISTS request_rollups_minute;
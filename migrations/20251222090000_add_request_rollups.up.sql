@@ -0,0 +1,41 @@
+-- Add up migration script here
+-- Latency histograms use the fixed bucket bounds in models/rollups.rs:
+-- one count per bound (latency_ms < bound) plus a final overflow bucket.
+CREATE TABLE IF NOT EXISTS request_rollups_minute (
+    bucket_start TIMESTAMPTZ NOT NULL,
+    key_id TEXT NOT NULL DEFAULT '',
+    path TEXT NOT NULL,
+    method TEXT NOT NULL,
+    status INT NOT NULL,
+    error_code TEXT NOT NULL DEFAULT '',
+    request_count BIGINT NOT NULL,
+    latency_sum BIGINT NOT NULL,
+    latency_histogram BIGINT[] NOT NULL,
+    PRIMARY KEY (bucket_start, key_id, path, method, status, error_code)
+);
+
+CREATE INDEX IF NOT EXISTS idx_request_rollups_minute_key
+ON request_rollups_minute(key_id, bucket_start);
+
+CREATE TABLE IF NOT EXISTS request_rollups_hour (
+    bucket_start TIMESTAMPTZ NOT NULL,
+    key_id TEXT NOT NULL DEFAULT '',
+    path TEXT NOT NULL,
+    method TEXT NOT NULL,
+    status INT NOT NULL,
+    error_code TEXT NOT NULL DEFAULT '',
+    request_count BIGINT NOT NULL,
+    latency_sum BIGINT NOT NULL,
+    latency_histogram BIGINT[] NOT NULL,
+    PRIMARY KEY (bucket_start, key_id, path, method, status, error_code)
+);
+
+CREATE INDEX IF NOT EXISTS idx_request_rollups_hour_key
+ON request_rollups_hour(key_id, bucket_start);
+
+-- Everything before rolled_up_until has been aggregated at that granularity.
+CREATE TABLE IF NOT EXISTS request_rollup_state (
+    granularity TEXT PRIMARY KEY CHECK (granularity IN ('minute', 'hour')),
+    rolled_up_until TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
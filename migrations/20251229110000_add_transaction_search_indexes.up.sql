@@ -0,0 +1,8 @@
+-- Add up migration script here
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE INDEX IF NOT EXISTS idx_transactions_metadata
+ON transactions USING GIN (metadata jsonb_path_ops);
+
+CREATE INDEX IF NOT EXISTS idx_transactions_description_trgm
+ON transactions USING GIN (description gin_trgm_ops);
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS transaction_batch_jobs;
+DROP FUNCTION IF EXISTS increment_quota_usage_by(VARCHAR, DATE, INTEGER);
@@ -0,0 +1,42 @@
+-- Add up migration script here
+-- Charges several units of quota at once, e.g. one per item of a batch request.
+CREATE OR REPLACE FUNCTION increment_quota_usage_by(
+    p_key_id VARCHAR(255),
+    p_date DATE,
+    p_count INTEGER
+) RETURNS INTEGER AS $$
+DECLARE
+    v_count INTEGER;
+BEGIN
+    INSERT INTO quota_usage (key_id, usage_date, request_count, last_updated_at)
+    VALUES (p_key_id, p_date, p_count, NOW())
+    ON CONFLICT (key_id, usage_date)
+    DO UPDATE SET
+        request_count = quota_usage.request_count + p_count,
+        last_updated_at = NOW()
+    RETURNING request_count INTO v_count;
+
+    RETURN v_count;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Large batches are stored here and processed by the batch worker. `items`
+-- holds the submitted JSON until the job has run; `results` the per-item outcome.
+CREATE TABLE IF NOT EXISTS transaction_batch_jobs (
+    job_id TEXT PRIMARY KEY,
+    key_id TEXT,
+    mode TEXT NOT NULL CHECK (mode IN ('all_or_nothing', 'best_effort')),
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'completed', 'failed')),
+    total_items INT NOT NULL,
+    succeeded INT,
+    failed INT,
+    items JSONB,
+    results JSONB,
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_transaction_batch_jobs_pending
+ON transaction_batch_jobs(created_at)
+WHERE status = 'pending';
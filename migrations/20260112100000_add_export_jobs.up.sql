@@ -0,0 +1,24 @@
+-- Add up migration script here
+-- `query` is the raw query string of the export request; the worker parses
+-- the transaction filters from it again when the job runs.
+CREATE TABLE IF NOT EXISTS export_jobs (
+    job_id TEXT PRIMARY KEY,
+    key_id TEXT,
+    format TEXT NOT NULL CHECK (format IN ('csv', 'ndjson')),
+    query TEXT NOT NULL DEFAULT '',
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'completed', 'failed', 'expired')),
+    row_count BIGINT,
+    file_path TEXT,
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ,
+    expires_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_export_jobs_pending
+ON export_jobs(created_at)
+WHERE status = 'pending';
+
+CREATE INDEX IF NOT EXISTS idx_export_jobs_expires_at
+ON export_jobs(expires_at)
+WHERE status = 'completed';
@@ -0,0 +1,36 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS statement_imports (
+    import_id TEXT PRIMARY KEY,
+    key_id TEXT,
+    account_id TEXT REFERENCES accounts(account_id),
+    format TEXT NOT NULL CHECK (format IN ('csv', 'camt053')),
+    file_name TEXT,
+    line_count INT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_statement_imports_key
+ON statement_imports(key_id, created_at DESC);
+
+-- Amounts are signed as on the statement: credits positive, debits negative.
+CREATE TABLE IF NOT EXISTS statement_lines (
+    line_id TEXT PRIMARY KEY,
+    import_id TEXT NOT NULL REFERENCES statement_imports(import_id) ON DELETE CASCADE,
+    line_number INT NOT NULL,
+    booking_date DATE NOT NULL,
+    amount DOUBLE PRECISION NOT NULL,
+    currency TEXT NOT NULL,
+    reference TEXT,
+    description TEXT,
+    status TEXT NOT NULL DEFAULT 'unmatched' CHECK (status IN ('matched', 'unmatched', 'ambiguous')),
+    transaction_id TEXT,
+    match_method TEXT CHECK (match_method IS NULL OR match_method IN ('reference', 'amount', 'manual')),
+    candidate_ids TEXT[] NOT NULL DEFAULT '{}',
+    matched_at TIMESTAMPTZ,
+    UNIQUE (import_id, line_number)
+);
+
+-- A transaction can settle at most one statement line.
+CREATE UNIQUE INDEX IF NOT EXISTS idx_statement_lines_transaction
+ON statement_lines(transaction_id)
+WHERE transaction_id IS NOT NULL;
@@ -0,0 +1,12 @@
+-- Add up migration script here
+-- `balance` is the sum of the account's completed transactions created
+-- before the end of `snapshot_date` (UTC); `currency` is their MAX(currency),
+-- as in the live balance query.
+CREATE TABLE IF NOT EXISTS account_balance_snapshots (
+    account_id TEXT NOT NULL REFERENCES accounts(account_id) ON DELETE CASCADE,
+    snapshot_date DATE NOT NULL,
+    balance DOUBLE PRECISION NOT NULL,
+    currency TEXT,
+    computed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (account_id, snapshot_date)
+);
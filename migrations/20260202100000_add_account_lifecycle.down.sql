@@ -0,0 +1,7 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS account_status_events;
+
+ALTER TABLE accounts
+DROP COLUMN IF EXISTS status_changed_at,
+DROP COLUMN IF EXISTS status_reason,
+DROP COLUMN IF EXISTS status;
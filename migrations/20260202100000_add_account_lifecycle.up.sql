@@ -0,0 +1,21 @@
+-- Add up migration script here
+ALTER TABLE accounts
+ADD COLUMN IF NOT EXISTS status TEXT NOT NULL DEFAULT 'active'
+    CHECK (status IN ('active', 'frozen', 'closed')),
+ADD COLUMN IF NOT EXISTS status_reason TEXT,
+ADD COLUMN IF NOT EXISTS status_changed_at TIMESTAMPTZ;
+
+-- `changed_by` is the API key that made the change, or 'admin'.
+CREATE TABLE IF NOT EXISTS account_status_events (
+    event_id TEXT PRIMARY KEY,
+    account_id TEXT NOT NULL REFERENCES accounts(account_id) ON DELETE CASCADE,
+    from_status TEXT NOT NULL,
+    to_status TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    changed_by TEXT NOT NULL,
+    settlement_transaction_id TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_account_status_events_account
+ON account_status_events(account_id, created_at DESC);
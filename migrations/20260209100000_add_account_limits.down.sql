@@ -0,0 +1,14 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS idx_transactions_account_currency_created_at;
+DROP TABLE IF EXISTS account_currency_limits;
+DROP TABLE IF EXISTS account_limits;
+
+ALTER TABLE transactions
+DROP CONSTRAINT IF EXISTS transactions_failure_reason_check;
+
+-- NOT VALID keeps rows failed on a limit; only new rows are checked.
+ALTER TABLE transactions
+ADD CONSTRAINT transactions_failure_reason_check CHECK (
+    failure_reason IS NULL OR
+    failure_reason IN ('insufficient_funds', 'card_declined', 'risk_blocked', 'duplicate', 'internal_error')
+) NOT VALID;
@@ -0,0 +1,34 @@
+-- Add up migration script here
+ALTER TABLE transactions
+DROP CONSTRAINT IF EXISTS transactions_failure_reason_check;
+
+ALTER TABLE transactions
+ADD CONSTRAINT transactions_failure_reason_check CHECK (
+    failure_reason IS NULL OR failure_reason IN (
+        'insufficient_funds', 'card_declined', 'risk_blocked', 'duplicate', 'internal_error',
+        'invalid_account', 'network_error', 'timeout', 'fraud',
+        'amount_limit_exceeded', 'daily_limit_exceeded', 'monthly_limit_exceeded',
+        'velocity_limit_exceeded'
+    )
+);
+
+-- Transactions on an account with a row here lock it, serializing limit checks.
+CREATE TABLE IF NOT EXISTS account_limits (
+    account_id TEXT PRIMARY KEY REFERENCES accounts(account_id) ON DELETE CASCADE,
+    max_transactions_per_hour INT CHECK (max_transactions_per_hour > 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS account_currency_limits (
+    account_id TEXT NOT NULL REFERENCES account_limits(account_id) ON DELETE CASCADE,
+    currency TEXT NOT NULL,
+    max_amount DOUBLE PRECISION CHECK (max_amount > 0),
+    daily_outflow_limit DOUBLE PRECISION CHECK (daily_outflow_limit >= 0),
+    monthly_outflow_limit DOUBLE PRECISION CHECK (monthly_outflow_limit >= 0),
+    overdraft_limit DOUBLE PRECISION CHECK (overdraft_limit >= 0),
+    PRIMARY KEY (account_id, currency)
+);
+
+CREATE INDEX IF NOT EXISTS idx_transactions_account_currency_created_at
+ON transactions(account_id, currency, created_at DESC);
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS fraud_screenings;
+DROP TABLE IF EXISTS fraud_rules;
@@ -0,0 +1,32 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS fraud_rules (
+    rule_id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    condition JSONB NOT NULL,
+    action TEXT NOT NULL CHECK (action IN ('review', 'block')),
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- One row per transaction held for review or blocked. `review_status` is
+-- NULL for blocked transactions.
+CREATE TABLE IF NOT EXISTS fraud_screenings (
+    transaction_id TEXT PRIMARY KEY REFERENCES transactions(txn_id) ON DELETE CASCADE,
+    account_id TEXT NOT NULL,
+    decision TEXT NOT NULL CHECK (decision IN ('review', 'block')),
+    matched_rules JSONB NOT NULL DEFAULT '[]',
+    score DOUBLE PRECISION,
+    scorer_reason TEXT,
+    review_status TEXT CHECK (review_status IS NULL OR review_status IN ('pending', 'approved', 'rejected')),
+    review_note TEXT,
+    reviewed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_fraud_screenings_pending
+ON fraud_screenings(created_at)
+WHERE review_status = 'pending';
+
+CREATE INDEX IF NOT EXISTS idx_fraud_screenings_account
+ON fraud_screenings(account_id, created_at DESC);
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS dispute_evidence;
+DROP TABLE IF EXISTS disputes;
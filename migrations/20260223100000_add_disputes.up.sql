@@ -0,0 +1,42 @@
+-- Add up migration script here
+-- `chargeback_transaction_id` withdraws the disputed amount when the dispute
+-- is opened; `reversal_transaction_id` returns it when the dispute is won.
+CREATE TABLE IF NOT EXISTS disputes (
+    dispute_id TEXT PRIMARY KEY,
+    transaction_id TEXT NOT NULL REFERENCES transactions(txn_id),
+    account_id TEXT NOT NULL REFERENCES accounts(account_id) ON DELETE CASCADE,
+    amount DOUBLE PRECISION NOT NULL CHECK (amount > 0),
+    currency TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'needs_response'
+        CHECK (status IN ('needs_response', 'under_review', 'won', 'lost')),
+    evidence_due_by TIMESTAMPTZ NOT NULL,
+    chargeback_transaction_id TEXT NOT NULL,
+    reversal_transaction_id TEXT,
+    resolution_note TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    resolved_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_disputes_status_due
+ON disputes(status, evidence_due_by);
+
+CREATE INDEX IF NOT EXISTS idx_disputes_transaction
+ON disputes(transaction_id);
+
+CREATE TABLE IF NOT EXISTS dispute_evidence (
+    evidence_id TEXT PRIMARY KEY,
+    dispute_id TEXT NOT NULL REFERENCES disputes(dispute_id) ON DELETE CASCADE,
+    description TEXT NOT NULL,
+    metadata JSONB,
+    file_name TEXT,
+    content_type TEXT,
+    size_bytes BIGINT,
+    sha256 TEXT,
+    storage_path TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_dispute_evidence_dispute
+ON dispute_evidence(dispute_id, created_at);
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS schedule_runs;
+DROP TABLE IF EXISTS transaction_schedules;
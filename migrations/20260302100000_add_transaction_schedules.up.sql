@@ -0,0 +1,48 @@
+-- Add up migration script here
+-- `next_occurrence_at` is when the current occurrence is due; `next_run_at`
+-- is when it is next attempted, later than that while retries back off.
+CREATE TABLE IF NOT EXISTS transaction_schedules (
+    schedule_id TEXT PRIMARY KEY,
+    account_id TEXT NOT NULL REFERENCES accounts(account_id) ON DELETE CASCADE,
+    amount DOUBLE PRECISION NOT NULL CHECK (amount > 0),
+    currency TEXT NOT NULL,
+    transaction_type TEXT NOT NULL,
+    description TEXT,
+    metadata JSONB,
+    recurrence JSONB NOT NULL,
+    start_at TIMESTAMPTZ NOT NULL,
+    end_at TIMESTAMPTZ,
+    max_occurrences INTEGER CHECK (max_occurrences > 0),
+    retry_policy JSONB NOT NULL,
+    status TEXT NOT NULL DEFAULT 'active'
+        CHECK (status IN ('active', 'paused', 'cancelled', 'completed', 'failed')),
+    occurrences INTEGER NOT NULL DEFAULT 0,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_occurrence_at TIMESTAMPTZ,
+    next_run_at TIMESTAMPTZ,
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_transaction_schedules_due
+ON transaction_schedules(next_run_at)
+WHERE status = 'active';
+
+CREATE INDEX IF NOT EXISTS idx_transaction_schedules_account
+ON transaction_schedules(account_id, created_at DESC);
+
+CREATE TABLE IF NOT EXISTS schedule_runs (
+    run_id TEXT PRIMARY KEY,
+    schedule_id TEXT NOT NULL REFERENCES transaction_schedules(schedule_id) ON DELETE CASCADE,
+    occurrence_at TIMESTAMPTZ NOT NULL,
+    attempt INTEGER NOT NULL,
+    status TEXT NOT NULL CHECK (status IN ('created', 'retrying', 'failed')),
+    transaction_id TEXT,
+    error_code TEXT,
+    error_message TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_schedule_runs_schedule
+ON schedule_runs(schedule_id, created_at DESC);
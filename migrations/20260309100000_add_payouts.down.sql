@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS payouts;
+DROP TABLE IF EXISTS payout_batches;
+DROP TABLE IF EXISTS payout_destinations;
@@ -0,0 +1,67 @@
+-- Add up migration script here
+-- Bank details are encrypted by the application; only the last four digits
+-- of the account number or IBAN are kept in the clear for display.
+CREATE TABLE IF NOT EXISTS payout_destinations (
+    destination_id TEXT PRIMARY KEY,
+    account_id TEXT NOT NULL REFERENCES accounts(account_id) ON DELETE CASCADE,
+    kind TEXT NOT NULL CHECK (kind IN ('us_ach', 'sepa')),
+    currency TEXT NOT NULL,
+    label TEXT,
+    account_last4 TEXT NOT NULL,
+    encrypted_details BYTEA NOT NULL,
+    status TEXT NOT NULL DEFAULT 'active' CHECK (status IN ('active', 'disabled')),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_payout_destinations_account
+ON payout_destinations(account_id, created_at DESC);
+
+CREATE TABLE IF NOT EXISTS payout_batches (
+    batch_id TEXT PRIMARY KEY,
+    format TEXT NOT NULL CHECK (format IN ('nacha', 'sepa_pain001')),
+    currency TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'created'
+        CHECK (status IN ('created', 'sent', 'settled', 'returned')),
+    payout_count INTEGER NOT NULL,
+    total_amount DOUBLE PRECISION NOT NULL,
+    file_name TEXT NOT NULL,
+    file_path TEXT NOT NULL,
+    file_sha256 TEXT NOT NULL,
+    return_reason TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    sent_at TIMESTAMPTZ,
+    settled_at TIMESTAMPTZ,
+    returned_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_payout_batches_created
+ON payout_batches(created_at DESC);
+
+CREATE TABLE IF NOT EXISTS payouts (
+    payout_id TEXT PRIMARY KEY,
+    account_id TEXT NOT NULL REFERENCES accounts(account_id) ON DELETE CASCADE,
+    destination_id TEXT NOT NULL REFERENCES payout_destinations(destination_id),
+    amount DOUBLE PRECISION NOT NULL CHECK (amount > 0),
+    currency TEXT NOT NULL,
+    description TEXT,
+    status TEXT NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'batched', 'sent', 'settled', 'returned', 'failed')),
+    transaction_id TEXT NOT NULL,
+    batch_id TEXT REFERENCES payout_batches(batch_id),
+    return_reason TEXT,
+    return_transaction_id TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_payouts_pending
+ON payouts(currency, created_at)
+WHERE status = 'pending';
+
+CREATE INDEX IF NOT EXISTS idx_payouts_account
+ON payouts(account_id, created_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_payouts_batch
+ON payouts(batch_id);
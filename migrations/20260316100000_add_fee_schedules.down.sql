@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS transaction_fees;
+DROP TABLE IF EXISTS account_fee_assignments;
+DROP TABLE IF EXISTS fee_schedules;
@@ -0,0 +1,41 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS fee_schedules (
+    fee_schedule_id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    description TEXT,
+    plan TEXT,
+    rules JSONB NOT NULL DEFAULT '[]',
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- One active schedule prices each plan.
+CREATE UNIQUE INDEX IF NOT EXISTS idx_fee_schedules_active_plan
+ON fee_schedules(plan) WHERE active AND plan IS NOT NULL;
+
+CREATE TABLE IF NOT EXISTS account_fee_assignments (
+    account_id TEXT PRIMARY KEY REFERENCES accounts(account_id) ON DELETE CASCADE,
+    fee_schedule_id TEXT REFERENCES fee_schedules(fee_schedule_id),
+    plan TEXT,
+    assigned_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK ((fee_schedule_id IS NULL) <> (plan IS NULL))
+);
+
+-- Links each charged transaction to its fee transaction, with the
+-- breakdown as charged.
+CREATE TABLE IF NOT EXISTS transaction_fees (
+    transaction_id TEXT PRIMARY KEY REFERENCES transactions(txn_id) ON DELETE CASCADE,
+    fee_transaction_id TEXT NOT NULL UNIQUE REFERENCES transactions(txn_id) ON DELETE CASCADE,
+    fee_schedule_id TEXT NOT NULL REFERENCES fee_schedules(fee_schedule_id),
+    account_id TEXT NOT NULL,
+    transaction_type TEXT NOT NULL,
+    charged_amount DOUBLE PRECISION NOT NULL,
+    amount DOUBLE PRECISION NOT NULL,
+    currency TEXT NOT NULL,
+    breakdown JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_transaction_fees_created
+ON transaction_fees(created_at, currency);
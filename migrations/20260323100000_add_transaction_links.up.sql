@@ -0,0 +1,18 @@
+-- Add up migration script here
+-- Reversal and adjustment entries booked against earlier transactions. The
+-- original transaction is never changed; its links are read from here.
+CREATE TABLE IF NOT EXISTS transaction_links (
+    transaction_id TEXT PRIMARY KEY REFERENCES transactions(txn_id) ON DELETE CASCADE,
+    original_transaction_id TEXT NOT NULL REFERENCES transactions(txn_id) ON DELETE CASCADE,
+    kind TEXT NOT NULL CHECK (kind IN ('reversal', 'adjustment')),
+    reason TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (kind <> 'adjustment' OR reason IS NOT NULL)
+);
+
+CREATE INDEX IF NOT EXISTS idx_transaction_links_original
+ON transaction_links(original_transaction_id);
+
+-- A transaction is reversed at most once.
+CREATE UNIQUE INDEX IF NOT EXISTS idx_transaction_links_one_reversal
+ON transaction_links(original_transaction_id) WHERE kind = 'reversal';
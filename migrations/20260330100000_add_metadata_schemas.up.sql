@@ -0,0 +1,11 @@
+-- Add up migration script here
+-- JSON Schemas account and transaction metadata written with a key must match.
+CREATE TABLE IF NOT EXISTS metadata_schemas (
+    key_id VARCHAR(255) NOT NULL,
+    target TEXT NOT NULL CHECK (target IN ('account', 'transaction')),
+    schema JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (key_id, target),
+    FOREIGN KEY (key_id) REFERENCES api_keys(key_id) ON DELETE CASCADE
+);
@@ -1,30 +1,97 @@
 use anyhow::Result;
 use axum::{
     http::HeaderValue,
+    middleware,
     routing::get,
     Router,
 };
+use metrics_exporter_prometheus::PrometheusHandle;
 use std::sync::Arc;
 use std::time::Duration;
 use tower_http::{cors::CorsLayer, timeout::TimeoutLayer, trace::TraceLayer};
 
-use crate::handlers::health;
+use crate::handlers::{health, metrics};
+use crate::middleware::auth::require_admin_auth;
+use crate::models::balance::{spawn_balance_snapshot_worker, BalanceSnapshotSettings};
+use crate::models::batch::{spawn_batch_worker, BatchSettings};
+use crate::models::disputes::{spawn_dispute_deadline_worker, DisputeSettings};
+use crate::models::export::{spawn_export_worker, ExportSettings};
+use crate::models::fraud::FraudScreener;
+use crate::models::pagination::CursorSigner;
+use crate::models::payouts::PayoutCipher;
+use crate::models::rollups::{spawn_rollup_worker, RollupSettings};
+use crate::models::schedules::{spawn_schedule_worker, Clock, ScheduleSettings, SystemClock};
+use crate::observability::metrics::{init_metrics, spawn_upkeep, track_http_metrics};
+use crate::observability::request_log::{PgRequestLogSink, RequestLogSettings, RequestLogger};
+use crate::observability::telemetry::{make_request_span, record_response_status};
 use crate::{config::Config, db::PgPool, middleware::request_id::request_id_layers, openapi};
 
 #[derive(Clone)]
 pub struct AppState {
     pub pool: PgPool,
     pub config: Config,
+    pub metrics: PrometheusHandle,
+    pub request_logger: RequestLogger,
+    pub cursor_signer: CursorSigner,
+    pub fraud_screener: FraudScreener,
+    pub clock: Arc<dyn Clock>,
+    /// Encrypts payout bank details; `None` when `PAYOUT_ENCRYPTION_KEY` is unset.
+    pub payout_cipher: Option<PayoutCipher>,
 }
 
-pub async fn build_router(config: Config) -> Result<Router> {
+pub async fn build_state(config: Config) -> Result<Arc<AppState>> {
     let pool = crate::db::init_pool(&config.database_url).await?;
 
-    let state = Arc::new(AppState {
-        pool: pool.clone(),
-        config: config.clone(),
-    });
+    let metrics = init_metrics()?;
+    spawn_upkeep(metrics.clone());
+    spawn_rollup_worker(pool.clone(), RollupSettings::from_config(&config));
+    let fraud_screener = FraudScreener::default();
+    spawn_batch_worker(pool.clone(), BatchSettings::from_config(&config), fraud_screener.clone());
+    spawn_export_worker(pool.clone(), ExportSettings::from_config(&config));
+    spawn_balance_snapshot_worker(pool.clone(), BalanceSnapshotSettings::from_config(&config));
+    spawn_dispute_deadline_worker(pool.clone(), DisputeSettings::from_config(&config));
+    let clock: Arc<dyn Clock> = Arc::new(SystemClock);
+    spawn_schedule_worker(
+        pool.clone(),
+        ScheduleSettings::from_config(&config),
+        fraud_screener.clone(),
+        clock.clone(),
+    );
 
+    let request_logger = RequestLogger::spawn(
+        PgRequestLogSink::new(pool.clone()),
+        RequestLogSettings::from_config(&config),
+    );
+
+    let cursor_signer = match &config.cursor_secret {
+        Some(secret) => CursorSigner::new(secret.as_bytes()),
+        None => {
+            tracing::warn!("CURSOR_SECRET not set; pagination cursors will not survive a restart");
+            CursorSigner::random()
+        }
+    };
+
+    let payout_cipher = match &config.payout_encryption_key {
+        Some(key) => Some(PayoutCipher::from_base64(key).map_err(anyhow::Error::msg)?),
+        None => {
+            tracing::warn!("PAYOUT_ENCRYPTION_KEY not set; payout destinations are disabled");
+            None
+        }
+    };
+
+    Ok(Arc::new(AppState {
+        pool,
+        config,
+        metrics,
+        request_logger,
+        cursor_signer,
+        fraud_screener,
+        clock,
+        payout_cipher,
+    }))
+}
+
+pub fn build_router(state: Arc<AppState>) -> Result<Router> {
     let cors = CorsLayer::new()
         .allow_origin("*".parse::<HeaderValue>()?)
         .allow_methods(tower_http::cors::Any)
@@ -35,7 +102,7 @@ pub async fn build_router(config: Config) -> Result<Router> {
     let openapi_router = openapi::openapi_routes().with_state::<Arc<AppState>>(());
     let v1_router = api_v1_routes().with_state::<Arc<AppState>>(());
 
-    let app = Router::new()
+    let mut app = Router::new()
         .with_state::<Arc<AppState>>(())
         .merge(openapi_router)
         .nest("/v1", v1_router)
@@ -43,17 +110,42 @@ pub async fn build_router(config: Config) -> Result<Router> {
             "/health/live",
             get(health::health_live).with_state::<Arc<AppState>>(()),
         )
-        .route("/health/ready", get(health::health_ready))
+        .route("/health/ready", get(health::health_ready));
+
+    // Metrics are served on their own port when one is configured, otherwise
+    // they are exposed here behind admin authentication.
+    if state.config.metrics_port.is_none() {
+        app = app.merge(
+            Router::new()
+                .route("/metrics", get(metrics::metrics))
+                .route_layer(middleware::from_fn(require_admin_auth)),
+        );
+    }
+
+    let app = app
+        .layer(middleware::from_fn(track_http_metrics))
         .layer(TimeoutLayer::new(Duration::from_secs(30)))
         .layer(cors)
+        // Runs inside the request-id layers so the span can record the ID.
+        .layer(
+            TraceLayer::new_for_http()
+                .make_span_with(make_request_span)
+                .on_response(record_response_status),
+        )
         .layer(set_xrid)
         .layer(propagate_xrid)
-        .layer(TraceLayer::new_for_http())
         .with_state(state);
 
     Ok(app)
 }
 
+/// Router for the standalone metrics listener bound to `METRICS_PORT`.
+pub fn build_metrics_router(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/metrics", get(metrics::metrics))
+        .with_state(state)
+}
+
 fn api_v1_routes() -> Router {
     Router::new().route("/", get(|| async { "API v1" }))
-}
\ No newline at end of file
+}
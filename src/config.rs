@@ -1,12 +1,63 @@
 use anyhow::Result;
 use serde::Deserialize;
 
+use crate::observability::logging::LogFormat;
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct Config {
     pub port: u16,
     pub database_url: String,
     pub rate_limit_per_minute: u32,
     pub quota_daily_requests: u32,
+    pub metrics_port: Option<u16>,
+    pub otel_enabled: bool,
+    pub otel_exporter_endpoint: String,
+    pub otel_service_name: String,
+    #[serde(skip)]
+    pub log_format: LogFormat,
+    pub log_redact_fields: Vec<String>,
+    pub rollup_interval_secs: u64,
+    pub request_retention_days: i64,
+    pub minute_rollup_retention_days: i64,
+    pub request_log_buffer_size: usize,
+    pub request_log_batch_size: usize,
+    pub request_log_flush_ms: u64,
+    /// Key for signing pagination cursors. Without it a random key is used
+    /// and cursors stop working after a restart.
+    pub cursor_secret: Option<String>,
+    pub batch_max_items: usize,
+    pub batch_async_threshold: usize,
+    pub batch_max_body_bytes: usize,
+    pub batch_job_poll_ms: u64,
+    pub export_dir: String,
+    pub export_ttl_secs: u64,
+    pub export_async_threshold: i64,
+    pub export_job_poll_ms: u64,
+    pub statement_max_lines: usize,
+    pub reconciliation_date_tolerance_days: i64,
+    /// Metadata keys compared with statement references when matching.
+    pub reconciliation_reference_keys: Vec<String>,
+    pub account_statement_max_transactions: i64,
+    pub balance_snapshot_interval_secs: u64,
+    pub balance_snapshot_batch_size: i64,
+    pub dispute_evidence_dir: String,
+    pub dispute_evidence_max_bytes: usize,
+    pub dispute_response_days: i64,
+    pub dispute_deadline_interval_secs: u64,
+    pub schedule_poll_interval_secs: u64,
+    pub schedule_batch_size: i64,
+    pub schedule_max_retries: u32,
+    pub schedule_retry_backoff_secs: i64,
+    /// Base64 encoded 32 byte key encrypting payout bank details. Without it
+    /// payout destinations cannot be added or paid.
+    pub payout_encryption_key: Option<String>,
+    pub payout_file_dir: String,
+    pub payout_batch_max_items: i64,
+    pub payout_originator_name: String,
+    pub payout_originator_id: String,
+    pub payout_originator_routing: String,
+    pub payout_originator_iban: String,
+    pub payout_originator_bic: Option<String>,
 }
 
 pub fn load_config() -> Result<Config> {
@@ -22,6 +73,130 @@ pub fn load_config() -> Result<Config> {
         quota_daily_requests: std::env::var("QUOTA_DAILY_REQUESTS")
             .unwrap_or_else(|_| "5000".to_string())
             .parse()?,
+        metrics_port: std::env::var("METRICS_PORT")
+            .ok()
+            .map(|port| port.parse())
+            .transpose()?,
+        otel_enabled: std::env::var("OTEL_ENABLED")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()?,
+        otel_exporter_endpoint: std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT")
+            .unwrap_or_else(|_| "http://localhost:4318".to_string()),
+        otel_service_name: std::env::var("OTEL_SERVICE_NAME")
+            .unwrap_or_else(|_| "metered-finance-api".to_string()),
+        log_format: std::env::var("LOG_FORMAT")
+            .unwrap_or_else(|_| "text".to_string())
+            .parse()
+            .map_err(anyhow::Error::msg)?,
+        log_redact_fields: std::env::var("LOG_REDACT_FIELDS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|field| field.trim().to_string())
+            .filter(|field| !field.is_empty())
+            .collect(),
+        rollup_interval_secs: std::env::var("ROLLUP_INTERVAL_SECS")
+            .unwrap_or_else(|_| "60".to_string())
+            .parse()?,
+        request_retention_days: std::env::var("REQUEST_RETENTION_DAYS")
+            .unwrap_or_else(|_| "30".to_string())
+            .parse()?,
+        minute_rollup_retention_days: std::env::var("MINUTE_ROLLUP_RETENTION_DAYS")
+            .unwrap_or_else(|_| "14".to_string())
+            .parse()?,
+        request_log_buffer_size: std::env::var("REQUEST_LOG_BUFFER_SIZE")
+            .unwrap_or_else(|_| "10000".to_string())
+            .parse()?,
+        request_log_batch_size: std::env::var("REQUEST_LOG_BATCH_SIZE")
+            .unwrap_or_else(|_| "500".to_string())
+            .parse()?,
+        request_log_flush_ms: std::env::var("REQUEST_LOG_FLUSH_MS")
+            .unwrap_or_else(|_| "1000".to_string())
+            .parse()?,
+        cursor_secret: std::env::var("CURSOR_SECRET")
+            .ok()
+            .filter(|secret| !secret.is_empty()),
+        batch_max_items: std::env::var("BATCH_MAX_ITEMS")
+            .unwrap_or_else(|_| "5000".to_string())
+            .parse()?,
+        batch_async_threshold: std::env::var("BATCH_ASYNC_THRESHOLD")
+            .unwrap_or_else(|_| "500".to_string())
+            .parse()?,
+        batch_max_body_bytes: std::env::var("BATCH_MAX_BODY_BYTES")
+            .unwrap_or_else(|_| "10485760".to_string())
+            .parse()?,
+        batch_job_poll_ms: std::env::var("BATCH_JOB_POLL_MS")
+            .unwrap_or_else(|_| "1000".to_string())
+            .parse()?,
+        export_dir: std::env::var("EXPORT_DIR")
+            .unwrap_or_else(|_| "./exports".to_string()),
+        export_ttl_secs: std::env::var("EXPORT_TTL_SECS")
+            .unwrap_or_else(|_| "86400".to_string())
+            .parse()?,
+        export_async_threshold: std::env::var("EXPORT_ASYNC_THRESHOLD")
+            .unwrap_or_else(|_| "100000".to_string())
+            .parse()?,
+        export_job_poll_ms: std::env::var("EXPORT_JOB_POLL_MS")
+            .unwrap_or_else(|_| "1000".to_string())
+            .parse()?,
+        statement_max_lines: std::env::var("STATEMENT_MAX_LINES")
+            .unwrap_or_else(|_| "10000".to_string())
+            .parse()?,
+        reconciliation_date_tolerance_days: std::env::var("RECONCILIATION_DATE_TOLERANCE_DAYS")
+            .unwrap_or_else(|_| "3".to_string())
+            .parse()?,
+        reconciliation_reference_keys: std::env::var("RECONCILIATION_REFERENCE_KEYS")
+            .unwrap_or_else(|_| "reference,bank_reference".to_string())
+            .split(',')
+            .map(|key| key.trim().to_string())
+            .filter(|key| !key.is_empty())
+            .collect(),
+        account_statement_max_transactions: std::env::var("ACCOUNT_STATEMENT_MAX_TRANSACTIONS")
+            .unwrap_or_else(|_| "10000".to_string())
+            .parse()?,
+        balance_snapshot_interval_secs: std::env::var("BALANCE_SNAPSHOT_INTERVAL_SECS")
+            .unwrap_or_else(|_| "3600".to_string())
+            .parse()?,
+        balance_snapshot_batch_size: std::env::var("BALANCE_SNAPSHOT_BATCH_SIZE")
+            .unwrap_or_else(|_| "500".to_string())
+            .parse()?,
+        dispute_evidence_dir: std::env::var("DISPUTE_EVIDENCE_DIR")
+            .unwrap_or_else(|_| "./dispute_evidence".to_string()),
+        dispute_evidence_max_bytes: std::env::var("DISPUTE_EVIDENCE_MAX_BYTES")
+            .unwrap_or_else(|_| "5242880".to_string())
+            .parse()?,
+        dispute_response_days: std::env::var("DISPUTE_RESPONSE_DAYS")
+            .unwrap_or_else(|_| "7".to_string())
+            .parse()?,
+        dispute_deadline_interval_secs: std::env::var("DISPUTE_DEADLINE_INTERVAL_SECS")
+            .unwrap_or_else(|_| "300".to_string())
+            .parse()?,
+        schedule_poll_interval_secs: std::env::var("SCHEDULE_POLL_INTERVAL_SECS")
+            .unwrap_or_else(|_| "60".to_string())
+            .parse()?,
+        schedule_batch_size: std::env::var("SCHEDULE_BATCH_SIZE")
+            .unwrap_or_else(|_| "100".to_string())
+            .parse()?,
+        schedule_max_retries: std::env::var("SCHEDULE_MAX_RETRIES")
+            .unwrap_or_else(|_| "3".to_string())
+            .parse()?,
+        schedule_retry_backoff_secs: std::env::var("SCHEDULE_RETRY_BACKOFF_SECS")
+            .unwrap_or_else(|_| "3600".to_string())
+            .parse()?,
+        payout_encryption_key: std::env::var("PAYOUT_ENCRYPTION_KEY")
+            .ok()
+            .filter(|key| !key.is_empty()),
+        payout_file_dir: std::env::var("PAYOUT_FILE_DIR")
+            .unwrap_or_else(|_| "./payout_files".to_string()),
+        payout_batch_max_items: std::env::var("PAYOUT_BATCH_MAX_ITEMS")
+            .unwrap_or_else(|_| "1000".to_string())
+            .parse()?,
+        payout_originator_name: std::env::var("PAYOUT_ORIGINATOR_NAME").unwrap_or_default(),
+        payout_originator_id: std::env::var("PAYOUT_ORIGINATOR_ID").unwrap_or_default(),
+        payout_originator_routing: std::env::var("PAYOUT_ORIGINATOR_ROUTING").unwrap_or_default(),
+        payout_originator_iban: std::env::var("PAYOUT_ORIGINATOR_IBAN").unwrap_or_default(),
+        payout_originator_bic: std::env::var("PAYOUT_ORIGINATOR_BIC")
+            .ok()
+            .filter(|bic| !bic.is_empty()),
     };
 
     Ok(config)
@@ -8,9 +8,19 @@ use utoipa;
 
 use crate::{
     app::AppState,
-    middleware::{auth::ClientAuth, errors::AppError},
+    middleware::{
+        auth::{AdminAuth, ClientAuth},
+        errors::AppError,
+    },
     models::{
-        common::{ErrorResponse, PaginatedResponse, PaginationParams},
+        common::{ErrorResponse, PaginatedResponse, PaginationParams, SortOrder},
+        lifecycle::{
+            account_from_row, AccountLifecycleService, AccountRow, AccountStatusChangeResponse,
+            AccountStatusEventResponse, ChangeAccountStatusRequest, ACCOUNT_COLUMNS,
+        },
+        limits::{AccountLimitService, AccountLimitsResponse, SetAccountLimitsRequest},
+        metadata::{MetadataSchemaService, MetadataTarget},
+        pagination::{timestamp_key, KeysetPage},
         requests::{CreateAccountRequest, UpdateAccountRequest},
         responses::AccountResponse,
     },
@@ -19,7 +29,9 @@ use crate::{
 /// Create a new account
 ///
 /// Creates a new account with the specified account ID and optional metadata.
-/// The account ID must be unique across the system.
+/// The account ID must be unique across the system. Metadata must match the
+/// key's account metadata schema, if one is registered; errors carry the
+/// offending JSON path in `details.path`.
 #[utoipa::path(
     post,
     path = "/api/accounts",
@@ -38,9 +50,11 @@ use crate::{
 )]
 pub async fn create_account(
     State(state): State<Arc<AppState>>,
-    Extension(_auth): Extension<ClientAuth>,
+    Extension(auth): Extension<ClientAuth>,
     Json(req): Json<CreateAccountRequest>,
 ) -> Result<(StatusCode, Json<AccountResponse>), AppError> {
+    let schema = MetadataSchemaService::for_key(&state.pool, auth.context.key_id(), MetadataTarget::Account).await?;
+    req.validate_metadata(schema.as_ref())?;
     req.validate()
         .map_err(|e| AppError::ValidationError(e))?;
 
@@ -58,27 +72,20 @@ pub async fn create_account(
         )));
     }
 
-    let account = sqlx::query_as::<_, (String, Option<serde_json::Value>, chrono::DateTime<chrono::Utc>, chrono::DateTime<chrono::Utc>)>(
+    let account = sqlx::query_as::<_, AccountRow>(&format!(
         r#"
         INSERT INTO accounts (account_id, metadata, created_at, updated_at)
         VALUES ($1, $2, NOW(), NOW())
-        RETURNING account_id, metadata, created_at, updated_at
-        "#
-    )
+        RETURNING {}
+        "#,
+        ACCOUNT_COLUMNS
+    ))
     .bind(&req.account_id)
     .bind(&req.metadata)
     .fetch_one(&state.pool)
     .await?;
 
-    Ok((
-        StatusCode::CREATED,
-        Json(AccountResponse {
-            account_id: account.0,
-            metadata: account.1,
-            created_at: account.2,
-            updated_at: account.3,
-        }),
-    ))
+    Ok((StatusCode::CREATED, Json(account_from_row(account))))
 }
 
 /// Get account details
@@ -106,29 +113,26 @@ pub async fn get_account(
     Extension(_auth): Extension<ClientAuth>,
     Path(account_id): Path<String>,
 ) -> Result<Json<AccountResponse>, AppError> {
-    let account = sqlx::query_as::<_, (String, Option<serde_json::Value>, chrono::DateTime<chrono::Utc>, chrono::DateTime<chrono::Utc>)>(
+    let account = sqlx::query_as::<_, AccountRow>(&format!(
         r#"
-        SELECT account_id, metadata, created_at, updated_at
+        SELECT {}
         FROM accounts
         WHERE account_id = $1
-        "#
-    )
+        "#,
+        ACCOUNT_COLUMNS
+    ))
     .bind(&account_id)
     .fetch_optional(&state.pool)
     .await?
     .ok_or_else(|| AppError::account_not_found(&account_id))?;
 
-    Ok(Json(AccountResponse {
-        account_id: account.0,
-        metadata: account.1,
-        created_at: account.2,
-        updated_at: account.3,
-    }))
+    Ok(Json(account_from_row(account)))
 }
 
 /// List all accounts
 ///
-/// Retrieves a paginated list of all accounts. Use the cursor parameter for pagination.
+/// Retrieves a paginated list of all accounts, oldest first. Pass `next_cursor` or
+/// `prev_cursor` from a previous page as `cursor` to move forward or back.
 #[utoipa::path(
     get,
     path = "/api/accounts",
@@ -138,6 +142,7 @@ pub async fn get_account(
     ),
     responses(
         (status = 200, description = "List of accounts", body = PaginatedResponse<AccountResponse>),
+        (status = 400, description = "Invalid cursor", body = ErrorResponse),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
         (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
     ),
@@ -153,69 +158,51 @@ pub async fn list_accounts(
     params.validate()
         .map_err(|e| AppError::ValidationError(e.to_string()))?;
 
-    let limit = params.limit.unwrap_or(20) as i64;
-
-    let accounts = if let Some(cursor) = &params.cursor {
-        let decoded = cursor.decode_string()
-            .map_err(|e| AppError::InvalidInput(format!("Invalid cursor: {}", e)))?;
-
-        sqlx::query_as::<_, (String, Option<serde_json::Value>, chrono::DateTime<chrono::Utc>, chrono::DateTime<chrono::Utc>)>(
-            r#"
-            SELECT account_id, metadata, created_at, updated_at
-            FROM accounts
-            WHERE account_id > $1
-            ORDER BY account_id ASC
-            LIMIT $2
-            "#
-        )
-        .bind(&decoded)
-        .bind(limit + 1)
-        .fetch_all(&state.pool)
-        .await?
-    } else {
-        sqlx::query_as::<_, (String, Option<serde_json::Value>, chrono::DateTime<chrono::Utc>, chrono::DateTime<chrono::Utc>)>(
-            r#"
-            SELECT account_id, metadata, created_at, updated_at
-            FROM accounts
-            ORDER BY account_id ASC
-            LIMIT $1
-            "#
-        )
-        .bind(limit + 1)
-        .fetch_all(&state.pool)
-        .await?
-    };
-
-    let has_more = accounts.len() > limit as usize;
-    let items: Vec<AccountResponse> = accounts
-        .into_iter()
-        .take(limit as usize)
-        .map(|a| AccountResponse {
-            account_id: a.0,
-            metadata: a.1,
-            created_at: a.2,
-            updated_at: a.3,
-        })
-        .collect();
-
-    let next_cursor = if has_more {
-        items.last().map(|item| {
-            crate::models::common::Cursor::encode(&item.account_id)
-        })
-    } else {
-        None
-    };
-
-    Ok(Json(PaginatedResponse {
-        data: items,
-        has_more,
-        next_cursor,
-    }))
+    let page = KeysetPage::new(
+        &params,
+        &state.cursor_signer,
+        "accounts",
+        "created_at",
+        SortOrder::Asc,
+    )?;
+
+    let mut query = format!(
+        r#"
+        SELECT {}
+        FROM accounts
+        WHERE 1=1
+        "#,
+        ACCOUNT_COLUMNS
+    );
+    let mut bind_values: Vec<String> = vec![];
+
+    if let Some((condition, values)) = page.condition("created_at", "timestamptz", "account_id", 1) {
+        query.push_str(&condition);
+        bind_values.extend(values);
+    }
+
+    query.push_str(&page.order_by("created_at", "account_id"));
+    query.push_str(&format!(" LIMIT ${}::bigint", bind_values.len() + 1));
+    bind_values.push(page.fetch_limit().to_string());
+
+    let mut sql_query = sqlx::query_as::<_, AccountRow>(&query);
+    for value in &bind_values {
+        sql_query = sql_query.bind(value);
+    }
+    let accounts = sql_query.fetch_all(&state.pool).await?;
+
+    let items: Vec<AccountResponse> = accounts.into_iter().map(account_from_row).collect();
+
+    Ok(Json(page.paginate(items, &state.cursor_signer, |item| {
+        (timestamp_key(&item.created_at), item.account_id.clone())
+    })))
 }
 
 /// Update account metadata
 ///
-/// Updates the metadata for an existing account. The metadata can contain any valid JSON.
+/// Replaces the metadata of an existing account. The metadata must stay within
+/// the size limits and match the key's account metadata schema, if one is
+/// registered.
 #[utoipa::path(
     patch,
     path = "/api/accounts/{account_id}",
@@ -237,10 +224,14 @@ pub async fn list_accounts(
 )]
 pub async fn update_account(
     State(state): State<Arc<AppState>>,
-    Extension(_auth): Extension<ClientAuth>,
+    Extension(auth): Extension<ClientAuth>,
     Path(account_id): Path<String>,
     Json(req): Json<UpdateAccountRequest>,
 ) -> Result<Json<AccountResponse>, AppError> {
+    let schema = MetadataSchemaService::for_key(&state.pool, auth.context.key_id(), MetadataTarget::Account).await?;
+    req.validate_metadata(schema.as_ref())?;
+    req.validate().map_err(AppError::ValidationError)?;
+
     let exists = sqlx::query_scalar::<_, i64>(
         "SELECT COUNT(*) FROM accounts WHERE account_id = $1"
     )
@@ -252,23 +243,264 @@ pub async fn update_account(
         return Err(AppError::account_not_found(&account_id));
     }
 
-    let account = sqlx::query_as::<_, (String, Option<serde_json::Value>, chrono::DateTime<chrono::Utc>, chrono::DateTime<chrono::Utc>)>(
+    let account = sqlx::query_as::<_, AccountRow>(&format!(
         r#"
         UPDATE accounts
         SET metadata = $1, updated_at = NOW()
         WHERE account_id = $2
-        RETURNING account_id, metadata, created_at, updated_at
-        "#
-    )
+        RETURNING {}
+        "#,
+        ACCOUNT_COLUMNS
+    ))
     .bind(&req.metadata)
     .bind(&account_id)
     .fetch_one(&state.pool)
     .await?;
 
-    Ok(Json(AccountResponse {
-        account_id: account.0,
-        metadata: account.1,
-        created_at: account.2,
-        updated_at: account.3,
-    }))
-}
\ No newline at end of file
+    Ok(Json(account_from_row(account)))
+}
+
+/// Change account status
+///
+/// Freezes, unfreezes or closes an account. Frozen and closed accounts reject
+/// new transactions with `account_frozen` or `account_closed`. Closing needs a
+/// zero balance unless `settle` is set, in which case a completed adjustment
+/// bringing the balance to zero is booked with the closure. Accounts frozen by
+/// an administrator, and closed accounts, can only be changed through the
+/// admin API.
+#[utoipa::path(
+    post,
+    path = "/api/accounts/{account_id}/status",
+    tag = "accounts",
+    params(
+        ("account_id" = String, Path, description = "Account identifier")
+    ),
+    request_body = ChangeAccountStatusRequest,
+    responses(
+        (status = 200, description = "Status changed", body = AccountStatusChangeResponse),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Change requires an administrator", body = ErrorResponse),
+        (status = 404, description = "Account not found", body = ErrorResponse),
+        (status = 409, description = "Invalid transition or non-zero balance", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
+    )
+)]
+pub async fn change_account_status(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<ClientAuth>,
+    Path(account_id): Path<String>,
+    Json(req): Json<ChangeAccountStatusRequest>,
+) -> Result<Json<AccountStatusChangeResponse>, AppError> {
+    req.validate().map_err(AppError::ValidationError)?;
+
+    let key_id = auth.context.key_id().unwrap_or_default();
+    let change = AccountLifecycleService::change_status(&state.pool, &account_id, &req, Some(key_id)).await?;
+
+    Ok(Json(change))
+}
+
+/// Change account status (admin)
+///
+/// Like `POST /api/accounts/{account_id}/status`, but may also unfreeze or
+/// close accounts an administrator froze and reopen closed accounts.
+#[utoipa::path(
+    post,
+    path = "/api/admin/accounts/{account_id}/status",
+    tag = "accounts",
+    params(
+        ("account_id" = String, Path, description = "Account identifier")
+    ),
+    request_body = ChangeAccountStatusRequest,
+    responses(
+        (status = 200, description = "Status changed", body = AccountStatusChangeResponse),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Account not found", body = ErrorResponse),
+        (status = 409, description = "Invalid transition or non-zero balance", body = ErrorResponse),
+    ),
+    security(
+        ("AdminKeyAuth" = [])
+    )
+)]
+pub async fn admin_change_account_status(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<AdminAuth>,
+    Path(account_id): Path<String>,
+    Json(req): Json<ChangeAccountStatusRequest>,
+) -> Result<Json<AccountStatusChangeResponse>, AppError> {
+    req.validate().map_err(AppError::ValidationError)?;
+
+    let change = AccountLifecycleService::change_status(&state.pool, &account_id, &req, None).await?;
+
+    Ok(Json(change))
+}
+
+/// List account status changes
+///
+/// Returns every status change of an account with its reason, newest first.
+#[utoipa::path(
+    get,
+    path = "/api/accounts/{account_id}/status/history",
+    tag = "accounts",
+    params(
+        ("account_id" = String, Path, description = "Account identifier")
+    ),
+    responses(
+        (status = 200, description = "Status changes", body = Vec<AccountStatusEventResponse>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Account not found", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
+    )
+)]
+pub async fn get_account_status_history(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<ClientAuth>,
+    Path(account_id): Path<String>,
+) -> Result<Json<Vec<AccountStatusEventResponse>>, AppError> {
+    let exists = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM accounts WHERE account_id = $1"
+    )
+    .bind(&account_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    if exists == 0 {
+        return Err(AppError::account_not_found(&account_id));
+    }
+
+    let events = AccountLifecycleService::history(&state.pool, &account_id).await?;
+
+    Ok(Json(events))
+}
+
+/// Get account limits
+///
+/// Returns the risk limits configured on an account.
+#[utoipa::path(
+    get,
+    path = "/api/accounts/{account_id}/limits",
+    tag = "accounts",
+    params(
+        ("account_id" = String, Path, description = "Account identifier")
+    ),
+    responses(
+        (status = 200, description = "Account limits", body = AccountLimitsResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Account not found or has no limits", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
+    )
+)]
+pub async fn get_account_limits(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<ClientAuth>,
+    Path(account_id): Path<String>,
+) -> Result<Json<AccountLimitsResponse>, AppError> {
+    let exists = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM accounts WHERE account_id = $1"
+    )
+    .bind(&account_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    if exists == 0 {
+        return Err(AppError::account_not_found(&account_id));
+    }
+
+    let limits = AccountLimitService::get(&state.pool, &account_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Account '{}' has no limits", account_id)))?;
+
+    Ok(Json(limits))
+}
+
+/// Set account limits
+///
+/// Replaces the risk limits of an account. `max_transactions_per_hour` counts
+/// transactions of any currency created in the last hour; per-currency limits
+/// cap single amounts, completed outflows (refunds, fees, payouts, chargebacks
+/// and transfers) per UTC day and month, and how far outflows may take the
+/// account's net position below zero. Omitted limits are not enforced.
+#[utoipa::path(
+    put,
+    path = "/api/accounts/{account_id}/limits",
+    tag = "accounts",
+    params(
+        ("account_id" = String, Path, description = "Account identifier")
+    ),
+    request_body = SetAccountLimitsRequest,
+    responses(
+        (status = 200, description = "Limits updated", body = AccountLimitsResponse),
+        (status = 400, description = "Invalid limits", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Account not found", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
+    )
+)]
+pub async fn set_account_limits(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<ClientAuth>,
+    Path(account_id): Path<String>,
+    Json(req): Json<SetAccountLimitsRequest>,
+) -> Result<Json<AccountLimitsResponse>, AppError> {
+    req.validate().map_err(AppError::ValidationError)?;
+
+    let exists = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM accounts WHERE account_id = $1"
+    )
+    .bind(&account_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    if exists == 0 {
+        return Err(AppError::account_not_found(&account_id));
+    }
+
+    let limits = AccountLimitService::set(&state.pool, &account_id, &req).await?;
+
+    Ok(Json(limits))
+}
+
+/// Remove account limits
+///
+/// Removes every limit from an account.
+#[utoipa::path(
+    delete,
+    path = "/api/accounts/{account_id}/limits",
+    tag = "accounts",
+    params(
+        ("account_id" = String, Path, description = "Account identifier")
+    ),
+    responses(
+        (status = 204, description = "Limits removed"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Account not found or has no limits", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
+    )
+)]
+pub async fn delete_account_limits(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<ClientAuth>,
+    Path(account_id): Path<String>,
+) -> Result<StatusCode, AppError> {
+    if !AccountLimitService::remove(&state.pool, &account_id).await? {
+        return Err(AppError::NotFound(format!("Account '{}' has no limits", account_id)));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
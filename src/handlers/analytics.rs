@@ -10,8 +10,9 @@ use crate::{
     middleware::{auth::{AdminAuth, ClientAuth}, errors::AppError},
     models::{
         common::ErrorResponse,
-        analytics::{AnalyticsResponse, AnalyticsService, TimeRangeFilter},
+        analytics::{AnalyticsQuery, AnalyticsResponse, AnalyticsService},
         keys::AuthContext,
+        rollups::{AnalyticsSource, RollupSettings},
     },
 };
 
@@ -20,7 +21,7 @@ use crate::{
     path = "/api/analytics",
     tag = "analytics",
     params(
-        TimeRangeFilter
+        AnalyticsQuery
     ),
     responses(
         (status = 200, description = "Analytics retrieved successfully", body = AnalyticsResponse,
@@ -42,6 +43,9 @@ use crate::{
                         "method": "POST",
                         "request_count": 5234,
                         "avg_latency_ms": 52.3,
+                        "median_latency_ms": 41.0,
+                        "p95_latency_ms": 140.0,
+                        "p99_latency_ms": 310.5,
                         "error_rate": 2.1
                     }
                 ],
@@ -57,9 +61,17 @@ use crate::{
                         "percentage": 16.0
                     }
                 ],
+                "error_breakdown": [
+                    {
+                        "error_code": "validation_error",
+                        "count": 301,
+                        "percentage": 87.8
+                    }
+                ],
                 "hourly_volume": []
             })
         ),
+        (status = 400, description = "Invalid filters", body = ErrorResponse),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
         (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
     ),
@@ -70,7 +82,7 @@ use crate::{
 pub async fn get_own_analytics(
     State(state): State<Arc<AppState>>,
     Extension(auth): Extension<ClientAuth>,
-    Query(filter): Query<TimeRangeFilter>,
+    Query(query): Query<AnalyticsQuery>,
 ) -> Result<Json<AnalyticsResponse>, AppError> {
     let key_id = match &auth.context {
         AuthContext::Client { key_id, .. } => key_id,
@@ -81,49 +93,9 @@ pub async fn get_own_analytics(
         }
     };
 
-    let start = filter.start.unwrap_or_else(|| Utc::now() - chrono::Duration::days(7));
-    let end = filter.end.unwrap_or_else(|| Utc::now());
-
-    let overview = AnalyticsService::get_request_stats(&state.pool, Some(key_id), start, end)
-        .await
-        .map_err(|e| {
-            tracing::error!("Failed to get request stats: {}", e);
-            AppError::InternalError("Failed to retrieve analytics".to_string())
-        })?;
-
-    let top_endpoints = AnalyticsService::get_endpoint_stats(&state.pool, Some(key_id), start, end, 10)
-        .await
-        .map_err(|e| {
-            tracing::error!("Failed to get endpoint stats: {}", e);
-            AppError::InternalError("Failed to retrieve analytics".to_string())
-        })?;
-
-    let status_codes = AnalyticsService::get_status_code_stats(&state.pool, Some(key_id), start, end)
-        .await
-        .map_err(|e| {
-            tracing::error!("Failed to get status code stats: {}", e);
-            AppError::InternalError("Failed to retrieve analytics".to_string())
-        })?;
-
-    let hourly_volume = if end.signed_duration_since(start).num_hours() <= 168 {
-        Some(
-            AnalyticsService::get_hourly_volume(&state.pool, Some(key_id), start, end)
-                .await
-                .map_err(|e| {
-                    tracing::error!("Failed to get hourly volume: {}", e);
-                    AppError::InternalError("Failed to retrieve analytics".to_string())
-                })?,
-        )
-    } else {
-        None
-    };
+    let response = build_analytics(Some(key_id.as_str()), &state, &query, 10).await?;
 
-    Ok(Json(AnalyticsResponse {
-        overview,
-        top_endpoints,
-        status_codes,
-        hourly_volume,
-    }))
+    Ok(Json(response))
 }
 
 #[utoipa::path(
@@ -132,10 +104,11 @@ pub async fn get_own_analytics(
     tag = "analytics",
     params(
         ("key_id" = String, Path, description = "API key identifier"),
-        TimeRangeFilter
+        AnalyticsQuery
     ),
     responses(
         (status = 200, description = "Analytics retrieved successfully", body = AnalyticsResponse),
+        (status = 400, description = "Invalid filters", body = ErrorResponse),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
         (status = 404, description = "API key not found", body = ErrorResponse),
     ),
@@ -147,7 +120,7 @@ pub async fn get_key_analytics(
     State(state): State<Arc<AppState>>,
     Extension(_auth): Extension<AdminAuth>,
     Path(key_id): Path<String>,
-    Query(filter): Query<TimeRangeFilter>,
+    Query(query): Query<AnalyticsQuery>,
 ) -> Result<Json<AnalyticsResponse>, AppError> {
     let exists = sqlx::query_scalar::<_, i64>(
         "SELECT COUNT(*) FROM api_keys WHERE key_id = $1"
@@ -160,49 +133,9 @@ pub async fn get_key_analytics(
         return Err(AppError::not_found("API Key", &key_id));
     }
 
-    let start = filter.start.unwrap_or_else(|| Utc::now() - chrono::Duration::days(7));
-    let end = filter.end.unwrap_or_else(|| Utc::now());
-
-    let overview = AnalyticsService::get_request_stats(&state.pool, Some(&key_id), start, end)
-        .await
-        .map_err(|e| {
-            tracing::error!("Failed to get request stats: {}", e);
-            AppError::InternalError("Failed to retrieve analytics".to_string())
-        })?;
-
-    let top_endpoints = AnalyticsService::get_endpoint_stats(&state.pool, Some(&key_id), start, end, 10)
-        .await
-        .map_err(|e| {
-            tracing::error!("Failed to get endpoint stats: {}", e);
-            AppError::InternalError("Failed to retrieve analytics".to_string())
-        })?;
-
-    let status_codes = AnalyticsService::get_status_code_stats(&state.pool, Some(&key_id), start, end)
-        .await
-        .map_err(|e| {
-            tracing::error!("Failed to get status code stats: {}", e);
-            AppError::InternalError("Failed to retrieve analytics".to_string())
-        })?;
-
-    let hourly_volume = if end.signed_duration_since(start).num_hours() <= 168 {
-        Some(
-            AnalyticsService::get_hourly_volume(&state.pool, Some(&key_id), start, end)
-                .await
-                .map_err(|e| {
-                    tracing::error!("Failed to get hourly volume: {}", e);
-                    AppError::InternalError("Failed to retrieve analytics".to_string())
-                })?,
-        )
-    } else {
-        None
-    };
+    let response = build_analytics(Some(key_id.as_str()), &state, &query, 10).await?;
 
-    Ok(Json(AnalyticsResponse {
-        overview,
-        top_endpoints,
-        status_codes,
-        hourly_volume,
-    }))
+    Ok(Json(response))
 }
 
 #[utoipa::path(
@@ -210,10 +143,11 @@ pub async fn get_key_analytics(
     path = "/api/admin/analytics",
     tag = "analytics",
     params(
-        TimeRangeFilter
+        AnalyticsQuery
     ),
     responses(
         (status = 200, description = "System analytics retrieved successfully", body = AnalyticsResponse),
+        (status = 400, description = "Invalid filters", body = ErrorResponse),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
     ),
     security(
@@ -223,35 +157,62 @@ pub async fn get_key_analytics(
 pub async fn get_system_analytics(
     State(state): State<Arc<AppState>>,
     Extension(_auth): Extension<AdminAuth>,
-    Query(filter): Query<TimeRangeFilter>,
+    Query(query): Query<AnalyticsQuery>,
 ) -> Result<Json<AnalyticsResponse>, AppError> {
-    let start = filter.start.unwrap_or_else(|| Utc::now() - chrono::Duration::days(7));
-    let end = filter.end.unwrap_or_else(|| Utc::now());
+    let response = build_analytics(None, &state, &query, 20).await?;
 
-    let overview = AnalyticsService::get_request_stats(&state.pool, None, start, end)
+    Ok(Json(response))
+}
+
+async fn build_analytics(
+    key_id: Option<&str>,
+    state: &AppState,
+    query: &AnalyticsQuery,
+    endpoint_limit: i64,
+) -> Result<AnalyticsResponse, AppError> {
+    let now = Utc::now();
+    let mut filter = query
+        .resolve(key_id, now)
+        .map_err(AppError::ValidationError)?;
+    filter.source = AnalyticsSource::for_range(
+        filter.start,
+        filter.end,
+        query.bucket,
+        now,
+        &RollupSettings::from_config(&state.config),
+    );
+
+    let overview = AnalyticsService::get_request_stats(&state.pool, &filter)
         .await
         .map_err(|e| {
             tracing::error!("Failed to get request stats: {}", e);
             AppError::InternalError("Failed to retrieve analytics".to_string())
         })?;
 
-    let top_endpoints = AnalyticsService::get_endpoint_stats(&state.pool, None, start, end, 20)
+    let top_endpoints = AnalyticsService::get_endpoint_stats(&state.pool, &filter, endpoint_limit)
         .await
         .map_err(|e| {
             tracing::error!("Failed to get endpoint stats: {}", e);
             AppError::InternalError("Failed to retrieve analytics".to_string())
         })?;
 
-    let status_codes = AnalyticsService::get_status_code_stats(&state.pool, None, start, end)
+    let status_codes = AnalyticsService::get_status_code_stats(&state.pool, &filter)
         .await
         .map_err(|e| {
             tracing::error!("Failed to get status code stats: {}", e);
             AppError::InternalError("Failed to retrieve analytics".to_string())
         })?;
 
-    let hourly_volume = if end.signed_duration_since(start).num_hours() <= 168 {
+    let error_breakdown = AnalyticsService::get_error_breakdown(&state.pool, &filter)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to get error breakdown: {}", e);
+            AppError::InternalError("Failed to retrieve analytics".to_string())
+        })?;
+
+    let hourly_volume = if filter.end.signed_duration_since(filter.start).num_hours() <= 168 {
         Some(
-            AnalyticsService::get_hourly_volume(&state.pool, None, start, end)
+            AnalyticsService::get_hourly_volume(&state.pool, &filter)
                 .await
                 .map_err(|e| {
                     tracing::error!("Failed to get hourly volume: {}", e);
@@ -262,10 +223,26 @@ pub async fn get_system_analytics(
         None
     };
 
-    Ok(Json(AnalyticsResponse {
+    let volume = match query.bucket {
+        Some(bucket) => Some(
+            AnalyticsService::get_volume(&state.pool, &filter, bucket)
+                .await
+                .map_err(|e| {
+                    tracing::error!("Failed to get request volume: {}", e);
+                    AppError::InternalError("Failed to retrieve analytics".to_string())
+                })?,
+        ),
+        None => None,
+    };
+
+    Ok(AnalyticsResponse {
         overview,
         top_endpoints,
         status_codes,
+        error_breakdown,
         hourly_volume,
-    }))
-}
\ No newline at end of file
+        bucket: query.bucket,
+        volume,
+        source: filter.source,
+    })
+}
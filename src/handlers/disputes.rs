@@ -0,0 +1,316 @@
+use axum::{
+    body::Body,
+    extract::{rejection::QueryRejection, Path, Query, State},
+    http::{
+        header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+        StatusCode,
+    },
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use chrono::Utc;
+use std::sync::Arc;
+use tokio_util::io::ReaderStream;
+
+use crate::{
+    app::AppState,
+    middleware::{
+        auth::{AdminAuth, ClientAuth},
+        errors::AppError,
+    },
+    models::{
+        common::ErrorResponse,
+        disputes::{
+            AddDisputeEvidenceRequest, DisputeDetailResponse, DisputeEvidenceResponse,
+            DisputeListParams, DisputeResponse, DisputeService, DisputeSettings,
+            OpenDisputeRequest, ResolveDisputeRequest,
+        },
+    },
+};
+
+/// Get a dispute
+///
+/// Returns a dispute with the evidence attached to it.
+#[utoipa::path(
+    get,
+    path = "/api/disputes/{dispute_id}",
+    tag = "disputes",
+    params(
+        ("dispute_id" = String, Path, description = "Dispute identifier")
+    ),
+    responses(
+        (status = 200, description = "Dispute found", body = DisputeDetailResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Dispute not found", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
+    )
+)]
+pub async fn get_dispute(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<ClientAuth>,
+    Path(dispute_id): Path<String>,
+) -> Result<Json<DisputeDetailResponse>, AppError> {
+    let dispute = DisputeService::get(&state.pool, &dispute_id).await?;
+
+    Ok(Json(dispute))
+}
+
+/// Attach dispute evidence
+///
+/// Adds a piece of evidence to a dispute that still needs a response: a
+/// description, optional structured metadata and an optional file sent as
+/// base64. Files are limited to `DISPUTE_EVIDENCE_MAX_BYTES`.
+#[utoipa::path(
+    post,
+    path = "/api/disputes/{dispute_id}/evidence",
+    tag = "disputes",
+    params(
+        ("dispute_id" = String, Path, description = "Dispute identifier")
+    ),
+    request_body = AddDisputeEvidenceRequest,
+    responses(
+        (status = 201, description = "Evidence attached", body = DisputeEvidenceResponse),
+        (status = 400, description = "Invalid evidence", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Dispute not found", body = ErrorResponse),
+        (status = 409, description = "Dispute no longer accepts evidence", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
+    )
+)]
+pub async fn add_dispute_evidence(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<ClientAuth>,
+    Path(dispute_id): Path<String>,
+    Json(req): Json<AddDisputeEvidenceRequest>,
+) -> Result<(StatusCode, Json<DisputeEvidenceResponse>), AppError> {
+    req.validate().map_err(AppError::ValidationError)?;
+
+    let settings = DisputeSettings::from_config(&state.config);
+    let evidence = DisputeService::add_evidence(&state.pool, &dispute_id, &req, &settings).await?;
+
+    Ok((StatusCode::CREATED, Json(evidence)))
+}
+
+/// Download an evidence file
+#[utoipa::path(
+    get,
+    path = "/api/disputes/{dispute_id}/evidence/{evidence_id}/file",
+    tag = "disputes",
+    params(
+        ("dispute_id" = String, Path, description = "Dispute identifier"),
+        ("evidence_id" = String, Path, description = "Evidence identifier")
+    ),
+    responses(
+        (status = 200, description = "Evidence file content"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Evidence has no file", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
+    )
+)]
+pub async fn download_dispute_evidence(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<ClientAuth>,
+    Path((dispute_id, evidence_id)): Path<(String, String)>,
+) -> Result<Response, AppError> {
+    let (info, path) = DisputeService::evidence_file(&state.pool, &dispute_id, &evidence_id).await?;
+
+    let file = tokio::fs::File::open(&path).await.map_err(|e| {
+        AppError::InternalError(format!("Failed to open evidence {}: {}", evidence_id, e))
+    })?;
+
+    Ok((
+        [
+            (CONTENT_TYPE, info.content_type),
+            (
+                CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", info.file_name),
+            ),
+        ],
+        Body::from_stream(ReaderStream::new(file)),
+    )
+        .into_response())
+}
+
+/// Submit a dispute response
+///
+/// Submits the attached evidence before the deadline and moves the dispute
+/// to `under_review`. At least one piece of evidence is required.
+#[utoipa::path(
+    post,
+    path = "/api/disputes/{dispute_id}/submit",
+    tag = "disputes",
+    params(
+        ("dispute_id" = String, Path, description = "Dispute identifier")
+    ),
+    responses(
+        (status = 200, description = "Dispute submitted for review", body = DisputeResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Dispute not found", body = ErrorResponse),
+        (status = 409, description = "No evidence, deadline passed or dispute not awaiting a response", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
+    )
+)]
+pub async fn submit_dispute(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<ClientAuth>,
+    Path(dispute_id): Path<String>,
+) -> Result<Json<DisputeResponse>, AppError> {
+    let dispute = DisputeService::submit(&state.pool, &dispute_id).await?;
+
+    Ok(Json(dispute))
+}
+
+/// Accept a dispute
+///
+/// Concedes a dispute that still needs a response. The dispute is lost and
+/// the chargeback stands.
+#[utoipa::path(
+    post,
+    path = "/api/disputes/{dispute_id}/accept",
+    tag = "disputes",
+    params(
+        ("dispute_id" = String, Path, description = "Dispute identifier")
+    ),
+    responses(
+        (status = 200, description = "Dispute accepted", body = DisputeResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Dispute not found", body = ErrorResponse),
+        (status = 409, description = "Dispute not awaiting a response", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
+    )
+)]
+pub async fn accept_dispute(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<ClientAuth>,
+    Path(dispute_id): Path<String>,
+) -> Result<Json<DisputeResponse>, AppError> {
+    let dispute = DisputeService::accept(&state.pool, &dispute_id).await?;
+
+    Ok(Json(dispute))
+}
+
+/// Open a dispute
+///
+/// Opens a dispute against a completed payment and books a completed
+/// `chargeback` withdrawing the disputed amount from the account. The
+/// merchant has until `evidence_due_by` to respond; disputes still waiting
+/// after that are closed as lost. Reversed payments cannot be disputed.
+#[utoipa::path(
+    post,
+    path = "/api/admin/disputes",
+    tag = "disputes",
+    request_body = OpenDisputeRequest,
+    responses(
+        (status = 201, description = "Dispute opened", body = DisputeResponse),
+        (status = 400, description = "Payment cannot be disputed", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Transaction not found", body = ErrorResponse),
+        (status = 409, description = "Account closed or payment reversed", body = ErrorResponse),
+    ),
+    security(
+        ("AdminKeyAuth" = [])
+    )
+)]
+pub async fn open_dispute(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<AdminAuth>,
+    Json(req): Json<OpenDisputeRequest>,
+) -> Result<(StatusCode, Json<DisputeResponse>), AppError> {
+    req.validate(Utc::now()).map_err(AppError::ValidationError)?;
+
+    let settings = DisputeSettings::from_config(&state.config);
+    let dispute = DisputeService::open(&state.pool, &req, &settings).await?;
+
+    Ok((StatusCode::CREATED, Json(dispute)))
+}
+
+/// List disputes
+///
+/// Returns disputes by status, soonest evidence deadline first. Lists open
+/// disputes unless `status` is given.
+#[utoipa::path(
+    get,
+    path = "/api/admin/disputes",
+    tag = "disputes",
+    params(DisputeListParams),
+    responses(
+        (status = 200, description = "Disputes", body = Vec<DisputeResponse>),
+        (status = 400, description = "Invalid parameters", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+    ),
+    security(
+        ("AdminKeyAuth" = [])
+    )
+)]
+pub async fn list_disputes(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<AdminAuth>,
+    params: Result<Query<DisputeListParams>, QueryRejection>,
+) -> Result<Json<Vec<DisputeResponse>>, AppError> {
+    let Query(params) = params?;
+    let statuses = params.statuses().map_err(AppError::ValidationError)?;
+    let limit = params.limit().map_err(AppError::ValidationError)?;
+
+    let disputes = DisputeService::list(
+        &state.pool,
+        &statuses,
+        params.due_before,
+        params.account_id.as_deref(),
+        limit,
+    )
+    .await?;
+
+    Ok(Json(disputes))
+}
+
+/// Resolve a dispute
+///
+/// Records the outcome of an open dispute. A won dispute books a completed
+/// `adjustment` returning the disputed amount; a lost one keeps the
+/// chargeback.
+#[utoipa::path(
+    post,
+    path = "/api/admin/disputes/{dispute_id}/resolve",
+    tag = "disputes",
+    params(
+        ("dispute_id" = String, Path, description = "Dispute identifier")
+    ),
+    request_body = ResolveDisputeRequest,
+    responses(
+        (status = 200, description = "Dispute resolved", body = DisputeResponse),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Dispute not found", body = ErrorResponse),
+        (status = 409, description = "Dispute already resolved or account closed", body = ErrorResponse),
+    ),
+    security(
+        ("AdminKeyAuth" = [])
+    )
+)]
+pub async fn resolve_dispute(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<AdminAuth>,
+    Path(dispute_id): Path<String>,
+    Json(req): Json<ResolveDisputeRequest>,
+) -> Result<Json<DisputeResponse>, AppError> {
+    req.validate().map_err(AppError::ValidationError)?;
+
+    let dispute = DisputeService::resolve(&state.pool, &dispute_id, &req).await?;
+
+    Ok(Json(dispute))
+}
@@ -0,0 +1,205 @@
+use axum::{
+    body::Body,
+    extract::{rejection::QueryRejection, Path, Query, RawQuery, State},
+    http::{
+        header::{CONTENT_DISPOSITION, CONTENT_TYPE, LOCATION},
+        StatusCode,
+    },
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use std::sync::Arc;
+use tokio_util::io::ReaderStream;
+
+use crate::{
+    app::AppState,
+    middleware::{auth::ClientAuth, errors::AppError},
+    models::{
+        common::ErrorResponse,
+        export::{ExportJobResponse, ExportJobStatus, ExportParams, ExportService, ExportSettings},
+        finance::TransactionFilters,
+    },
+};
+
+/// Export transactions
+///
+/// Streams every transaction matching the filters of `GET /api/transactions`, in
+/// the same sort order, as CSV or NDJSON.
+///
+/// CSV files start with a header row; columns are, in order: `transaction_id`,
+/// `account_id`, `amount`, `currency`, `transaction_type`, `status`,
+/// `description`, `metadata` (JSON), `created_at` and `processed_at` (RFC 3339,
+/// UTC). Empty optional values are empty fields. NDJSON lines are
+/// `TransactionResponse` objects.
+///
+/// With `background=true`, or when more than `EXPORT_ASYNC_THRESHOLD` rows match,
+/// the file is written by a background job instead and `202` is returned with
+/// the job to poll.
+#[utoipa::path(
+    get,
+    path = "/api/transactions/export",
+    tag = "transactions",
+    params(
+        ExportParams,
+        TransactionFilters
+    ),
+    responses(
+        (status = 200, description = "Matching transactions", content(
+            (String = "text/csv"),
+            (String = "application/x-ndjson"),
+        )),
+        (status = 202, description = "Export accepted for background processing", body = ExportJobResponse),
+        (status = 400, description = "Invalid filters", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
+    )
+)]
+pub async fn export_transactions(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<ClientAuth>,
+    params: Result<Query<ExportParams>, QueryRejection>,
+    filters: Result<Query<TransactionFilters>, QueryRejection>,
+    RawQuery(query): RawQuery,
+) -> Result<Response, AppError> {
+    let Query(params) = params?;
+    let Query(filters) = filters?;
+    filters.validate()?;
+
+    let settings = ExportSettings::from_config(&state.config);
+    let background = params.background
+        || ExportService::count(&state.pool, &filters).await? > settings.async_threshold;
+
+    if background {
+        let job = ExportService::enqueue(
+            &state.pool,
+            auth.context.key_id(),
+            params.format,
+            query.as_deref().unwrap_or_default(),
+        )
+        .await?;
+        let location = format!("/api/transactions/exports/{}", job.job_id);
+
+        return Ok((StatusCode::ACCEPTED, [(LOCATION, location)], Json(job)).into_response());
+    }
+
+    // Rows are written into one end of an in-memory pipe as they arrive from
+    // the database and streamed to the client from the other.
+    let (reader, writer) = tokio::io::duplex(64 * 1024);
+    let pool = state.pool.clone();
+    let format = params.format;
+    tokio::spawn(async move {
+        if let Err(e) = ExportService::write(&pool, &filters, format, writer).await {
+            tracing::warn!("Transaction export stream ended early: {}", e);
+        }
+    });
+
+    Ok((
+        [
+            (CONTENT_TYPE, format.content_type().to_string()),
+            (
+                CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", format.file_name("transactions")),
+            ),
+        ],
+        Body::from_stream(ReaderStream::new(reader)),
+    )
+        .into_response())
+}
+
+/// Get an export job
+///
+/// Returns the status of a background export. Completed jobs include a
+/// `download_url` that works until `expires_at`.
+#[utoipa::path(
+    get,
+    path = "/api/transactions/exports/{job_id}",
+    tag = "transactions",
+    params(
+        ("job_id" = String, Path, description = "Export job identifier")
+    ),
+    responses(
+        (status = 200, description = "Export job", body = ExportJobResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Export job not found", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
+    )
+)]
+pub async fn get_export_job(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<ClientAuth>,
+    Path(job_id): Path<String>,
+) -> Result<Json<ExportJobResponse>, AppError> {
+    let (job, _) = ExportService::get_job(&state.pool, &job_id, auth.context.key_id())
+        .await?
+        .ok_or_else(|| AppError::not_found("Export job", &job_id))?;
+
+    Ok(Json(job))
+}
+
+/// Download an export
+///
+/// Streams the file written by a completed export job until it expires.
+#[utoipa::path(
+    get,
+    path = "/api/transactions/exports/{job_id}/download",
+    tag = "transactions",
+    params(
+        ("job_id" = String, Path, description = "Export job identifier")
+    ),
+    responses(
+        (status = 200, description = "Export file", content(
+            (String = "text/csv"),
+            (String = "application/x-ndjson"),
+        )),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Export not found, not finished or expired", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
+    )
+)]
+pub async fn download_export(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<ClientAuth>,
+    Path(job_id): Path<String>,
+) -> Result<Response, AppError> {
+    let (job, path) = ExportService::get_job(&state.pool, &job_id, auth.context.key_id())
+        .await?
+        .ok_or_else(|| AppError::not_found("Export job", &job_id))?;
+
+    let path = match (job.status, path) {
+        (ExportJobStatus::Completed, Some(path)) => path,
+        (ExportJobStatus::Expired, _) => {
+            return Err(AppError::NotFound(format!("Export '{}' has expired", job_id)))
+        }
+        _ => {
+            return Err(AppError::NotFound(format!(
+                "Export '{}' is not ready for download",
+                job_id
+            )))
+        }
+    };
+
+    let file = tokio::fs::File::open(&path)
+        .await
+        .map_err(|e| AppError::InternalError(format!("Failed to open export {}: {}", job_id, e)))?;
+
+    Ok((
+        [
+            (CONTENT_TYPE, job.format.content_type().to_string()),
+            (
+                CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", job.format.file_name(&job.job_id)),
+            ),
+        ],
+        Body::from_stream(ReaderStream::new(file)),
+    )
+        .into_response())
+}
@@ -0,0 +1,290 @@
+use axum::{
+    extract::{rejection::QueryRejection, Path, Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use chrono::Utc;
+use std::sync::Arc;
+
+use crate::{
+    app::AppState,
+    middleware::{
+        auth::{AdminAuth, ClientAuth},
+        errors::AppError,
+    },
+    models::{
+        common::ErrorResponse,
+        fees::{
+            AccountFeeScheduleResponse, AssignFeeScheduleRequest, FeeRevenueParams, FeeRevenueReport,
+            FeeScheduleRequest, FeeScheduleResponse, FeeService,
+        },
+    },
+};
+
+async fn ensure_account_exists(state: &AppState, account_id: &str) -> Result<(), AppError> {
+    let account_exists = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM accounts WHERE account_id = $1"
+    )
+    .bind(account_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    if account_exists == 0 {
+        return Err(AppError::account_not_found(account_id));
+    }
+
+    Ok(())
+}
+
+/// Create a fee schedule
+///
+/// A schedule holds one rule per transaction type and currency: a fixed fee,
+/// a percentage of the amount, or amount tiers each with their own fixed fee
+/// and percentage, optionally kept between `min_fee` and `max_fee`. Accounts
+/// are charged by the schedule assigned to them, or by the active schedule
+/// pricing their plan.
+#[utoipa::path(
+    post,
+    path = "/api/admin/fee-schedules",
+    tag = "fees",
+    request_body = FeeScheduleRequest,
+    responses(
+        (status = 201, description = "Fee schedule created", body = FeeScheduleResponse),
+        (status = 400, description = "Invalid schedule", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 409, description = "Plan already priced by an active schedule", body = ErrorResponse),
+    ),
+    security(
+        ("AdminKeyAuth" = [])
+    )
+)]
+pub async fn create_fee_schedule(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<AdminAuth>,
+    Json(req): Json<FeeScheduleRequest>,
+) -> Result<(StatusCode, Json<FeeScheduleResponse>), AppError> {
+    req.validate().map_err(AppError::ValidationError)?;
+
+    let schedule = FeeService::create_schedule(&state.pool, &req).await?;
+
+    Ok((StatusCode::CREATED, Json(schedule)))
+}
+
+/// List fee schedules
+///
+/// Returns all schedules, including inactive ones, newest first.
+#[utoipa::path(
+    get,
+    path = "/api/admin/fee-schedules",
+    tag = "fees",
+    responses(
+        (status = 200, description = "Fee schedules", body = Vec<FeeScheduleResponse>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+    ),
+    security(
+        ("AdminKeyAuth" = [])
+    )
+)]
+pub async fn list_fee_schedules(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<AdminAuth>,
+) -> Result<Json<Vec<FeeScheduleResponse>>, AppError> {
+    let schedules = FeeService::list_schedules(&state.pool).await?;
+
+    Ok(Json(schedules))
+}
+
+/// Get a fee schedule
+#[utoipa::path(
+    get,
+    path = "/api/admin/fee-schedules/{fee_schedule_id}",
+    tag = "fees",
+    params(
+        ("fee_schedule_id" = String, Path, description = "Fee schedule identifier")
+    ),
+    responses(
+        (status = 200, description = "Fee schedule", body = FeeScheduleResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Fee schedule not found", body = ErrorResponse),
+    ),
+    security(
+        ("AdminKeyAuth" = [])
+    )
+)]
+pub async fn get_fee_schedule(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<AdminAuth>,
+    Path(fee_schedule_id): Path<String>,
+) -> Result<Json<FeeScheduleResponse>, AppError> {
+    let schedule = FeeService::get_schedule(&state.pool, &fee_schedule_id).await?;
+
+    Ok(Json(schedule))
+}
+
+/// Replace a fee schedule
+///
+/// Applies to transactions completed from now on; fees already charged keep
+/// their breakdown. Set `active` to false to stop charging by the schedule.
+#[utoipa::path(
+    put,
+    path = "/api/admin/fee-schedules/{fee_schedule_id}",
+    tag = "fees",
+    params(
+        ("fee_schedule_id" = String, Path, description = "Fee schedule identifier")
+    ),
+    request_body = FeeScheduleRequest,
+    responses(
+        (status = 200, description = "Fee schedule updated", body = FeeScheduleResponse),
+        (status = 400, description = "Invalid schedule", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Fee schedule not found", body = ErrorResponse),
+        (status = 409, description = "Plan already priced by an active schedule", body = ErrorResponse),
+    ),
+    security(
+        ("AdminKeyAuth" = [])
+    )
+)]
+pub async fn update_fee_schedule(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<AdminAuth>,
+    Path(fee_schedule_id): Path<String>,
+    Json(req): Json<FeeScheduleRequest>,
+) -> Result<Json<FeeScheduleResponse>, AppError> {
+    req.validate().map_err(AppError::ValidationError)?;
+
+    let schedule = FeeService::update_schedule(&state.pool, &fee_schedule_id, &req).await?;
+
+    Ok(Json(schedule))
+}
+
+/// Assign a fee schedule to an account
+///
+/// Assigns either a schedule directly or a plan, replacing any earlier
+/// assignment.
+#[utoipa::path(
+    put,
+    path = "/api/admin/accounts/{account_id}/fee-schedule",
+    tag = "fees",
+    params(
+        ("account_id" = String, Path, description = "Account identifier")
+    ),
+    request_body = AssignFeeScheduleRequest,
+    responses(
+        (status = 200, description = "Fee schedule assigned", body = AccountFeeScheduleResponse),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Account or fee schedule not found", body = ErrorResponse),
+    ),
+    security(
+        ("AdminKeyAuth" = [])
+    )
+)]
+pub async fn assign_fee_schedule(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<AdminAuth>,
+    Path(account_id): Path<String>,
+    Json(req): Json<AssignFeeScheduleRequest>,
+) -> Result<Json<AccountFeeScheduleResponse>, AppError> {
+    req.validate().map_err(AppError::ValidationError)?;
+    ensure_account_exists(&state, &account_id).await?;
+
+    let assignment = FeeService::assign(&state.pool, &account_id, &req).await?;
+
+    Ok(Json(assignment))
+}
+
+/// Remove an account's fee schedule
+///
+/// The account is no longer charged fees.
+#[utoipa::path(
+    delete,
+    path = "/api/admin/accounts/{account_id}/fee-schedule",
+    tag = "fees",
+    params(
+        ("account_id" = String, Path, description = "Account identifier")
+    ),
+    responses(
+        (status = 200, description = "Fee schedule removed", body = AccountFeeScheduleResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Account not found", body = ErrorResponse),
+    ),
+    security(
+        ("AdminKeyAuth" = [])
+    )
+)]
+pub async fn unassign_fee_schedule(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<AdminAuth>,
+    Path(account_id): Path<String>,
+) -> Result<Json<AccountFeeScheduleResponse>, AppError> {
+    ensure_account_exists(&state, &account_id).await?;
+
+    let assignment = FeeService::unassign(&state.pool, &account_id).await?;
+
+    Ok(Json(assignment))
+}
+
+/// Get an account's fee schedule
+///
+/// Returns the account's assignment and the schedule its transactions are
+/// charged by.
+#[utoipa::path(
+    get,
+    path = "/api/accounts/{account_id}/fee-schedule",
+    tag = "fees",
+    params(
+        ("account_id" = String, Path, description = "Account identifier")
+    ),
+    responses(
+        (status = 200, description = "Account fee schedule", body = AccountFeeScheduleResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Account not found", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
+    )
+)]
+pub async fn get_account_fee_schedule(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<ClientAuth>,
+    Path(account_id): Path<String>,
+) -> Result<Json<AccountFeeScheduleResponse>, AppError> {
+    ensure_account_exists(&state, &account_id).await?;
+
+    let assignment = FeeService::account_schedule(&state.pool, &account_id).await?;
+
+    Ok(Json(assignment))
+}
+
+/// Fee revenue report
+///
+/// Sums completed fee transactions charged in the period, per currency and
+/// per currency, charged transaction type and schedule. The period defaults
+/// to the last 30 days.
+#[utoipa::path(
+    get,
+    path = "/api/admin/fees/revenue",
+    tag = "fees",
+    params(FeeRevenueParams),
+    responses(
+        (status = 200, description = "Fee revenue", body = FeeRevenueReport),
+        (status = 400, description = "Invalid parameters", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+    ),
+    security(
+        ("AdminKeyAuth" = [])
+    )
+)]
+pub async fn fee_revenue(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<AdminAuth>,
+    params: Result<Query<FeeRevenueParams>, QueryRejection>,
+) -> Result<Json<FeeRevenueReport>, AppError> {
+    let Query(params) = params?;
+    let (from, to) = params.period(Utc::now()).map_err(AppError::ValidationError)?;
+
+    let report = FeeService::revenue(&state.pool, from, to, params.currency).await?;
+
+    Ok(Json(report))
+}
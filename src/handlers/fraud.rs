@@ -0,0 +1,238 @@
+use axum::{
+    extract::{rejection::QueryRejection, Path, Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use std::sync::Arc;
+
+use crate::{
+    app::AppState,
+    middleware::{auth::AdminAuth, errors::AppError},
+    models::{
+        common::ErrorResponse,
+        fraud::{
+            FraudReviewParams, FraudReviewResponse, FraudReviewStatus, FraudRule,
+            FraudRuleRequest, FraudService, ResolveFraudReviewRequest,
+        },
+    },
+};
+
+/// List fraud rules
+///
+/// Returns every fraud screening rule, oldest first.
+#[utoipa::path(
+    get,
+    path = "/api/admin/fraud/rules",
+    tag = "fraud",
+    responses(
+        (status = 200, description = "Fraud rules", body = Vec<FraudRule>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+    ),
+    security(
+        ("AdminKeyAuth" = [])
+    )
+)]
+pub async fn list_fraud_rules(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<AdminAuth>,
+) -> Result<Json<Vec<FraudRule>>, AppError> {
+    let rules = FraudService::list_rules(&state.pool).await?;
+
+    Ok(Json(rules))
+}
+
+/// Create a fraud rule
+///
+/// Adds a rule to the screening run before every transaction is committed.
+/// The decision for a transaction is the most severe action of its matching
+/// enabled rules: `review` holds it as `pending`, `block` records it as
+/// `failed` with `failure_reason` `fraud`.
+#[utoipa::path(
+    post,
+    path = "/api/admin/fraud/rules",
+    tag = "fraud",
+    request_body = FraudRuleRequest,
+    responses(
+        (status = 201, description = "Rule created", body = FraudRule),
+        (status = 400, description = "Invalid rule", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+    ),
+    security(
+        ("AdminKeyAuth" = [])
+    )
+)]
+pub async fn create_fraud_rule(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<AdminAuth>,
+    Json(req): Json<FraudRuleRequest>,
+) -> Result<(StatusCode, Json<FraudRule>), AppError> {
+    req.validate().map_err(AppError::ValidationError)?;
+
+    let rule = FraudService::create_rule(&state.pool, &req).await?;
+
+    Ok((StatusCode::CREATED, Json(rule)))
+}
+
+/// Replace a fraud rule
+#[utoipa::path(
+    put,
+    path = "/api/admin/fraud/rules/{rule_id}",
+    tag = "fraud",
+    params(
+        ("rule_id" = String, Path, description = "Fraud rule identifier")
+    ),
+    request_body = FraudRuleRequest,
+    responses(
+        (status = 200, description = "Rule updated", body = FraudRule),
+        (status = 400, description = "Invalid rule", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Rule not found", body = ErrorResponse),
+    ),
+    security(
+        ("AdminKeyAuth" = [])
+    )
+)]
+pub async fn update_fraud_rule(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<AdminAuth>,
+    Path(rule_id): Path<String>,
+    Json(req): Json<FraudRuleRequest>,
+) -> Result<Json<FraudRule>, AppError> {
+    req.validate().map_err(AppError::ValidationError)?;
+
+    let rule = FraudService::update_rule(&state.pool, &rule_id, &req)
+        .await?
+        .ok_or_else(|| AppError::not_found("Fraud rule", &rule_id))?;
+
+    Ok(Json(rule))
+}
+
+/// Delete a fraud rule
+#[utoipa::path(
+    delete,
+    path = "/api/admin/fraud/rules/{rule_id}",
+    tag = "fraud",
+    params(
+        ("rule_id" = String, Path, description = "Fraud rule identifier")
+    ),
+    responses(
+        (status = 204, description = "Rule deleted"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Rule not found", body = ErrorResponse),
+    ),
+    security(
+        ("AdminKeyAuth" = [])
+    )
+)]
+pub async fn delete_fraud_rule(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<AdminAuth>,
+    Path(rule_id): Path<String>,
+) -> Result<StatusCode, AppError> {
+    if !FraudService::delete_rule(&state.pool, &rule_id).await? {
+        return Err(AppError::not_found("Fraud rule", &rule_id));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List fraud reviews
+///
+/// Returns transactions held by fraud screening with the rules they matched,
+/// oldest first. Lists the pending review queue unless `status` is given.
+#[utoipa::path(
+    get,
+    path = "/api/admin/fraud/reviews",
+    tag = "fraud",
+    params(FraudReviewParams),
+    responses(
+        (status = 200, description = "Fraud reviews", body = Vec<FraudReviewResponse>),
+        (status = 400, description = "Invalid parameters", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+    ),
+    security(
+        ("AdminKeyAuth" = [])
+    )
+)]
+pub async fn list_fraud_reviews(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<AdminAuth>,
+    params: Result<Query<FraudReviewParams>, QueryRejection>,
+) -> Result<Json<Vec<FraudReviewResponse>>, AppError> {
+    let Query(params) = params?;
+    let limit = params.limit().map_err(AppError::ValidationError)?;
+    let status = params.status.unwrap_or(FraudReviewStatus::Pending);
+
+    let reviews = FraudService::list_reviews(&state.pool, status, limit).await?;
+
+    Ok(Json(reviews))
+}
+
+/// Approve a held transaction
+///
+/// Completes a transaction waiting for fraud review. Fails with `409` if the
+/// account has since been frozen or closed.
+#[utoipa::path(
+    post,
+    path = "/api/admin/fraud/reviews/{transaction_id}/approve",
+    tag = "fraud",
+    params(
+        ("transaction_id" = String, Path, description = "Transaction identifier")
+    ),
+    request_body = ResolveFraudReviewRequest,
+    responses(
+        (status = 200, description = "Transaction approved", body = FraudReviewResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "No review for the transaction", body = ErrorResponse),
+        (status = 409, description = "Review already resolved or account not active", body = ErrorResponse),
+    ),
+    security(
+        ("AdminKeyAuth" = [])
+    )
+)]
+pub async fn approve_fraud_review(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<AdminAuth>,
+    Path(transaction_id): Path<String>,
+    req: Option<Json<ResolveFraudReviewRequest>>,
+) -> Result<Json<FraudReviewResponse>, AppError> {
+    let note = req.and_then(|Json(req)| req.note);
+    let review =
+        FraudService::resolve_review(&state.pool, &transaction_id, true, note.as_deref()).await?;
+
+    Ok(Json(review))
+}
+
+/// Reject a held transaction
+///
+/// Fails a transaction waiting for fraud review with `failure_reason` `fraud`.
+#[utoipa::path(
+    post,
+    path = "/api/admin/fraud/reviews/{transaction_id}/reject",
+    tag = "fraud",
+    params(
+        ("transaction_id" = String, Path, description = "Transaction identifier")
+    ),
+    request_body = ResolveFraudReviewRequest,
+    responses(
+        (status = 200, description = "Transaction rejected", body = FraudReviewResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "No review for the transaction", body = ErrorResponse),
+        (status = 409, description = "Review already resolved", body = ErrorResponse),
+    ),
+    security(
+        ("AdminKeyAuth" = [])
+    )
+)]
+pub async fn reject_fraud_review(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<AdminAuth>,
+    Path(transaction_id): Path<String>,
+    req: Option<Json<ResolveFraudReviewRequest>>,
+) -> Result<Json<FraudReviewResponse>, AppError> {
+    let note = req.and_then(|Json(req)| req.note);
+    let review =
+        FraudService::resolve_review(&state.pool, &transaction_id, false, note.as_deref()).await?;
+
+    Ok(Json(review))
+}
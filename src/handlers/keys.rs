@@ -9,8 +9,9 @@ use crate::{
     app::AppState,
     middleware::{auth::AdminAuth, errors::AppError},
     models::{
-        common::{ErrorResponse, PaginatedResponse, PaginationParams},
+        common::{ErrorResponse, PaginatedResponse, PaginationParams, SortOrder},
         keys::{ApiKeyGenerator, Scope},
+        pagination::{timestamp_key, KeysetPage},
         requests::{CreateApiKeyRequest, UpdateApiKeyRequest},
         responses::{KeyCreatedResponse, KeyInfoResponse},
     },
@@ -134,6 +135,7 @@ pub async fn create_api_key(
     ),
     responses(
         (status = 200, description = "List of API keys", body = PaginatedResponse<KeyInfoResponse>),
+        (status = 400, description = "Invalid cursor", body = ErrorResponse),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
     ),
     security(
@@ -148,71 +150,54 @@ pub async fn list_api_keys(
     params.validate()
         .map_err(|e| AppError::ValidationError(e.to_string()))?;
 
-    let limit = params.limit.unwrap_or(20) as i64;
-
-    let keys = if let Some(cursor) = &params.cursor {
-        let decoded = cursor.decode_string()
-            .map_err(|e| AppError::InvalidInput(format!("Invalid cursor: {}", e)))?;
-
-        sqlx::query_as::<_, (
-            String,
-            String,
-            String,
-            Vec<String>,
-            bool,
-            i32,
-            i32,
-            i32,
-            chrono::DateTime<chrono::Utc>,
-            Option<chrono::DateTime<chrono::Utc>>,
-        )>(
-            r#"
-            SELECT 
-                key_id, prefix, name, scopes, active,
-                rate_limit_per_minute, daily_quota, monthly_quota,
-                created_at, last_used_at
-            FROM api_keys
-            WHERE key_id > $1
-            ORDER BY key_id ASC
-            LIMIT $2
-            "#
-        )
-        .bind(&decoded)
-        .bind(limit + 1)
-        .fetch_all(&state.pool)
-        .await?
-    } else {
-        sqlx::query_as::<_, (
-            String,
-            String,
-            String,
-            Vec<String>,
-            bool,
-            i32,
-            i32,
-            i32,
-            chrono::DateTime<chrono::Utc>,
-            Option<chrono::DateTime<chrono::Utc>>,
-        )>(
-            r#"
-            SELECT 
-                key_id, prefix, name, scopes, active,
-                rate_limit_per_minute, daily_quota, monthly_quota,
-                created_at, last_used_at
-            FROM api_keys
-            ORDER BY key_id ASC
-            LIMIT $1
-            "#
-        )
-        .bind(limit + 1)
-        .fetch_all(&state.pool)
-        .await?
-    };
+    let page = KeysetPage::new(
+        &params,
+        &state.cursor_signer,
+        "api_keys",
+        "created_at",
+        SortOrder::Asc,
+    )?;
+
+    let mut query = String::from(
+        r#"
+        SELECT 
+            key_id, prefix, name, scopes, active,
+            rate_limit_per_minute, daily_quota, monthly_quota,
+            created_at, last_used_at
+        FROM api_keys
+        WHERE 1=1
+        "#
+    );
+    let mut bind_values: Vec<String> = vec![];
+
+    if let Some((condition, values)) = page.condition("created_at", "timestamptz", "key_id", 1) {
+        query.push_str(&condition);
+        bind_values.extend(values);
+    }
+
+    query.push_str(&page.order_by("created_at", "key_id"));
+    query.push_str(&format!(" LIMIT ${}::bigint", bind_values.len() + 1));
+    bind_values.push(page.fetch_limit().to_string());
+
+    let mut sql_query = sqlx::query_as::<_, (
+        String,
+        String,
+        String,
+        Vec<String>,
+        bool,
+        i32,
+        i32,
+        i32,
+        chrono::DateTime<chrono::Utc>,
+        Option<chrono::DateTime<chrono::Utc>>,
+    )>(&query);
+    for value in &bind_values {
+        sql_query = sql_query.bind(value);
+    }
+    let keys = sql_query.fetch_all(&state.pool).await?;
 
-    let has_more = keys.len() > limit as usize;
     let items: Vec<KeyInfoResponse> = keys
         .into_iter()
-        .take(limit as usize)
         .map(|k| KeyInfoResponse {
             key_id: k.0,
             prefix: k.1,
@@ -227,19 +212,9 @@ pub async fn list_api_keys(
         })
         .collect();
 
-    let next_cursor = if has_more {
-        items.last().map(|item| {
-            crate::models::common::Cursor::encode(&item.key_id)
-        })
-    } else {
-        None
-    };
-
-    Ok(Json(PaginatedResponse {
-        data: items,
-        has_more,
-        next_cursor,
-    }))
+    Ok(Json(page.paginate(items, &state.cursor_signer, |item| {
+        (timestamp_key(&item.created_at), item.key_id.clone())
+    })))
 }
 
 /// Get API key details
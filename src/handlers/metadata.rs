@@ -0,0 +1,171 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use std::sync::Arc;
+
+use crate::{
+    app::AppState,
+    middleware::{auth::ClientAuth, errors::AppError},
+    models::{
+        common::ErrorResponse,
+        keys::AuthContext,
+        metadata::{
+            MetadataSchemaResponse, MetadataSchemaService, MetadataTarget, RegisterMetadataSchemaRequest,
+        },
+    },
+};
+
+fn own_key_id(auth: &ClientAuth) -> Result<&str, AppError> {
+    match &auth.context {
+        AuthContext::Client { key_id, .. } => Ok(key_id),
+        AuthContext::Admin => Err(AppError::InvalidInput(
+            "Admin keys do not have metadata schemas".to_string(),
+        )),
+    }
+}
+
+fn parse_target(target: &str) -> Result<MetadataTarget, AppError> {
+    target.parse().map_err(AppError::ValidationError)
+}
+
+fn schema_not_found(target: MetadataTarget) -> AppError {
+    AppError::NotFound(format!("No {} metadata schema registered", target))
+}
+
+/// Register a metadata schema
+///
+/// Sets the JSON Schema that `metadata` of accounts (`account`) or
+/// transactions (`transaction`) created or updated with this key must match,
+/// replacing any earlier one. Metadata already stored is not re-checked.
+/// Metadata is also limited in size, nesting depth and key count whether or
+/// not a schema is registered; rejections carry the offending JSON path in
+/// `details.path`.
+#[utoipa::path(
+    put,
+    path = "/api/metadata-schemas/{target}",
+    tag = "metadata",
+    params(
+        ("target" = MetadataTarget, Path, description = "Metadata the schema applies to")
+    ),
+    request_body = RegisterMetadataSchemaRequest,
+    responses(
+        (status = 200, description = "Schema registered", body = MetadataSchemaResponse),
+        (status = 400, description = "Invalid JSON Schema", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
+    )
+)]
+pub async fn put_metadata_schema(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<ClientAuth>,
+    Path(target): Path<String>,
+    Json(req): Json<RegisterMetadataSchemaRequest>,
+) -> Result<Json<MetadataSchemaResponse>, AppError> {
+    let key_id = own_key_id(&auth)?;
+    let target = parse_target(&target)?;
+    req.validate().map_err(AppError::ValidationError)?;
+
+    let schema = MetadataSchemaService::put(&state.pool, key_id, target, &req.schema).await?;
+
+    Ok(Json(schema))
+}
+
+/// List metadata schemas
+///
+/// Returns the schemas registered for this key.
+#[utoipa::path(
+    get,
+    path = "/api/metadata-schemas",
+    tag = "metadata",
+    responses(
+        (status = 200, description = "Metadata schemas", body = Vec<MetadataSchemaResponse>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
+    )
+)]
+pub async fn list_metadata_schemas(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<ClientAuth>,
+) -> Result<Json<Vec<MetadataSchemaResponse>>, AppError> {
+    let key_id = own_key_id(&auth)?;
+
+    let schemas = MetadataSchemaService::list(&state.pool, key_id).await?;
+
+    Ok(Json(schemas))
+}
+
+/// Get a metadata schema
+#[utoipa::path(
+    get,
+    path = "/api/metadata-schemas/{target}",
+    tag = "metadata",
+    params(
+        ("target" = MetadataTarget, Path, description = "Metadata the schema applies to")
+    ),
+    responses(
+        (status = 200, description = "Metadata schema", body = MetadataSchemaResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "No schema registered", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
+    )
+)]
+pub async fn get_metadata_schema(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<ClientAuth>,
+    Path(target): Path<String>,
+) -> Result<Json<MetadataSchemaResponse>, AppError> {
+    let key_id = own_key_id(&auth)?;
+    let target = parse_target(&target)?;
+
+    let schema = MetadataSchemaService::get(&state.pool, key_id, target)
+        .await?
+        .ok_or_else(|| schema_not_found(target))?;
+
+    Ok(Json(schema))
+}
+
+/// Remove a metadata schema
+///
+/// Metadata is then only held to the size limits.
+#[utoipa::path(
+    delete,
+    path = "/api/metadata-schemas/{target}",
+    tag = "metadata",
+    params(
+        ("target" = MetadataTarget, Path, description = "Metadata the schema applies to")
+    ),
+    responses(
+        (status = 204, description = "Schema removed"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "No schema registered", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
+    )
+)]
+pub async fn delete_metadata_schema(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<ClientAuth>,
+    Path(target): Path<String>,
+) -> Result<StatusCode, AppError> {
+    let key_id = own_key_id(&auth)?;
+    let target = parse_target(&target)?;
+
+    if !MetadataSchemaService::delete(&state.pool, key_id, target).await? {
+        return Err(schema_not_found(target));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
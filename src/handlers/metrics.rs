@@ -0,0 +1,33 @@
+use axum::{
+    extract::State,
+    http::header,
+    response::IntoResponse,
+};
+use std::sync::Arc;
+
+use crate::{app::AppState, observability::metrics::record_db_pool};
+
+/// Prometheus metrics
+///
+/// Returns all application metrics in the Prometheus text exposition format.
+/// Requires the admin key unless metrics are served on a separate `METRICS_PORT`.
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "health",
+    responses(
+        (status = 200, description = "Metrics in Prometheus text format", content_type = "text/plain", body = String),
+        (status = 401, description = "Unauthorized", body = crate::models::common::ErrorResponse),
+    ),
+    security(
+        ("AdminKeyAuth" = [])
+    )
+)]
+pub async fn metrics(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    record_db_pool(&state.pool);
+
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        state.metrics.render(),
+    )
+}
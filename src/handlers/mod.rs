@@ -1,6 +1,16 @@
 pub mod accounts;
+pub mod disputes;
+pub mod exports;
+pub mod fees;
+pub mod fraud;
 pub mod health;
 pub mod keys;
+pub mod metadata;
+pub mod metrics;
+pub mod payouts;
+pub mod reconciliation;
+pub mod schedules;
+pub mod statements;
 pub mod transactions;
 pub mod usage;
 pub mod analytics;
\ No newline at end of file
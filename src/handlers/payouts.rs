@@ -0,0 +1,529 @@
+use axum::{
+    body::Body,
+    extract::{rejection::QueryRejection, Path, Query, State},
+    http::{
+        header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+        StatusCode,
+    },
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use std::sync::Arc;
+use tokio_util::io::ReaderStream;
+
+use crate::{
+    app::AppState,
+    middleware::{
+        auth::{AdminAuth, ClientAuth},
+        errors::AppError,
+    },
+    models::{
+        common::ErrorResponse,
+        payouts::{
+            BatchAction, CreatePayoutBatchRequest, CreatePayoutDestinationRequest,
+            CreatePayoutRequest, PayoutBatchDetailResponse, PayoutBatchListParams,
+            PayoutBatchResponse, PayoutCipher, PayoutDestinationResponse, PayoutError,
+            PayoutListParams, PayoutResponse, PayoutService, PayoutSettings, ReturnPayoutRequest,
+        },
+    },
+};
+
+fn payout_cipher(state: &AppState) -> Result<&PayoutCipher, AppError> {
+    state.payout_cipher.as_ref().ok_or_else(|| {
+        PayoutError::NotConfigured("PAYOUT_ENCRYPTION_KEY is not set".to_string()).into()
+    })
+}
+
+async fn ensure_account_exists(state: &AppState, account_id: &str) -> Result<(), AppError> {
+    let account_exists = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM accounts WHERE account_id = $1"
+    )
+    .bind(account_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    if account_exists == 0 {
+        return Err(AppError::account_not_found(account_id));
+    }
+
+    Ok(())
+}
+
+/// Add a payout destination
+///
+/// Stores a US bank account (`us_ach`, paid in USD) or a SEPA account
+/// (`sepa`, paid in EUR) for an account's payouts. Routing numbers and IBANs
+/// are checked, and the details are stored encrypted; responses only show
+/// the last four characters of the account number or IBAN.
+#[utoipa::path(
+    post,
+    path = "/api/accounts/{account_id}/payout-destinations",
+    tag = "payouts",
+    params(
+        ("account_id" = String, Path, description = "Account identifier")
+    ),
+    request_body = CreatePayoutDestinationRequest,
+    responses(
+        (status = 201, description = "Destination added", body = PayoutDestinationResponse),
+        (status = 400, description = "Invalid bank details", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Account not found", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
+    )
+)]
+pub async fn create_payout_destination(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<ClientAuth>,
+    Path(account_id): Path<String>,
+    Json(req): Json<CreatePayoutDestinationRequest>,
+) -> Result<(StatusCode, Json<PayoutDestinationResponse>), AppError> {
+    let details = req.validate().map_err(AppError::ValidationError)?;
+    ensure_account_exists(&state, &account_id).await?;
+
+    let destination = PayoutService::create_destination(
+        &state.pool,
+        payout_cipher(&state)?,
+        &account_id,
+        req.label.as_deref(),
+        &details,
+    )
+    .await?;
+
+    Ok((StatusCode::CREATED, Json(destination)))
+}
+
+/// List payout destinations
+///
+/// Returns the account's destinations, including disabled ones, newest first.
+#[utoipa::path(
+    get,
+    path = "/api/accounts/{account_id}/payout-destinations",
+    tag = "payouts",
+    params(
+        ("account_id" = String, Path, description = "Account identifier")
+    ),
+    responses(
+        (status = 200, description = "Payout destinations", body = Vec<PayoutDestinationResponse>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Account not found", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
+    )
+)]
+pub async fn list_payout_destinations(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<ClientAuth>,
+    Path(account_id): Path<String>,
+) -> Result<Json<Vec<PayoutDestinationResponse>>, AppError> {
+    ensure_account_exists(&state, &account_id).await?;
+
+    let destinations = PayoutService::list_destinations(&state.pool, &account_id).await?;
+
+    Ok(Json(destinations))
+}
+
+/// Disable a payout destination
+///
+/// No new payouts can be made to a disabled destination. Payouts already
+/// made to it are still paid.
+#[utoipa::path(
+    delete,
+    path = "/api/accounts/{account_id}/payout-destinations/{destination_id}",
+    tag = "payouts",
+    params(
+        ("account_id" = String, Path, description = "Account identifier"),
+        ("destination_id" = String, Path, description = "Payout destination identifier")
+    ),
+    responses(
+        (status = 200, description = "Destination disabled", body = PayoutDestinationResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Destination not found", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
+    )
+)]
+pub async fn delete_payout_destination(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<ClientAuth>,
+    Path((account_id, destination_id)): Path<(String, String)>,
+) -> Result<Json<PayoutDestinationResponse>, AppError> {
+    let destination = PayoutService::disable_destination(&state.pool, &account_id, &destination_id).await?;
+
+    Ok(Json(destination))
+}
+
+/// Create a payout
+///
+/// Debits the account with a `payout` transaction in the destination's
+/// currency, subject to account limits and fraud screening like
+/// `POST /api/transactions`. The payout stays `pending` until it is put in a
+/// settlement batch; one whose debit is held for review waits until it is
+/// approved.
+#[utoipa::path(
+    post,
+    path = "/api/payouts",
+    tag = "payouts",
+    request_body = CreatePayoutRequest,
+    responses(
+        (status = 201, description = "Payout created", body = PayoutResponse),
+        (status = 400, description = "Invalid input or destination disabled", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Account or destination not found", body = ErrorResponse),
+        (status = 409, description = "Account frozen or closed", body = ErrorResponse),
+        (status = 422, description = "Account limit exceeded or payout blocked", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
+    )
+)]
+pub async fn create_payout(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<ClientAuth>,
+    Json(req): Json<CreatePayoutRequest>,
+) -> Result<(StatusCode, Json<PayoutResponse>), AppError> {
+    req.validate().map_err(AppError::ValidationError)?;
+
+    let payout = PayoutService::create(&state.pool, &state.fraud_screener, &req).await?;
+
+    Ok((StatusCode::CREATED, Json(payout)))
+}
+
+/// Get a payout
+#[utoipa::path(
+    get,
+    path = "/api/payouts/{payout_id}",
+    tag = "payouts",
+    params(
+        ("payout_id" = String, Path, description = "Payout identifier")
+    ),
+    responses(
+        (status = 200, description = "Payout found", body = PayoutResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Payout not found", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
+    )
+)]
+pub async fn get_payout(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<ClientAuth>,
+    Path(payout_id): Path<String>,
+) -> Result<Json<PayoutResponse>, AppError> {
+    let payout = PayoutService::get(&state.pool, &payout_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Payout", &payout_id))?;
+
+    Ok(Json(payout))
+}
+
+/// List an account's payouts
+///
+/// Returns payouts newest first, optionally with one status.
+#[utoipa::path(
+    get,
+    path = "/api/accounts/{account_id}/payouts",
+    tag = "payouts",
+    params(
+        ("account_id" = String, Path, description = "Account identifier"),
+        PayoutListParams
+    ),
+    responses(
+        (status = 200, description = "Payouts", body = Vec<PayoutResponse>),
+        (status = 400, description = "Invalid parameters", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Account not found", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
+    )
+)]
+pub async fn list_account_payouts(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<ClientAuth>,
+    Path(account_id): Path<String>,
+    params: Result<Query<PayoutListParams>, QueryRejection>,
+) -> Result<Json<Vec<PayoutResponse>>, AppError> {
+    let Query(params) = params?;
+    let limit = params.limit().map_err(AppError::ValidationError)?;
+    ensure_account_exists(&state, &account_id).await?;
+
+    let payouts = PayoutService::list(&state.pool, &account_id, params.status, limit).await?;
+
+    Ok(Json(payouts))
+}
+
+/// Create a payout batch
+///
+/// Collects pending payouts in the format's currency, USD for `nacha` and
+/// EUR for `sepa_pain001`, and writes their settlement file to
+/// `PAYOUT_FILE_DIR`. The payouts move to `batched`.
+#[utoipa::path(
+    post,
+    path = "/api/admin/payouts/batches",
+    tag = "payouts",
+    request_body = CreatePayoutBatchRequest,
+    responses(
+        (status = 201, description = "Batch created", body = PayoutBatchDetailResponse),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 409, description = "No pending payouts", body = ErrorResponse),
+    ),
+    security(
+        ("AdminKeyAuth" = [])
+    )
+)]
+pub async fn create_payout_batch(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<AdminAuth>,
+    Json(req): Json<CreatePayoutBatchRequest>,
+) -> Result<(StatusCode, Json<PayoutBatchDetailResponse>), AppError> {
+    let settings = PayoutSettings::from_config(&state.config);
+    let batch =
+        PayoutService::create_batch(&state.pool, payout_cipher(&state)?, &settings, req.format).await?;
+
+    Ok((StatusCode::CREATED, Json(batch)))
+}
+
+/// List payout batches
+#[utoipa::path(
+    get,
+    path = "/api/admin/payouts/batches",
+    tag = "payouts",
+    params(PayoutBatchListParams),
+    responses(
+        (status = 200, description = "Payout batches", body = Vec<PayoutBatchResponse>),
+        (status = 400, description = "Invalid parameters", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+    ),
+    security(
+        ("AdminKeyAuth" = [])
+    )
+)]
+pub async fn list_payout_batches(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<AdminAuth>,
+    params: Result<Query<PayoutBatchListParams>, QueryRejection>,
+) -> Result<Json<Vec<PayoutBatchResponse>>, AppError> {
+    let Query(params) = params?;
+    let limit = params.limit().map_err(AppError::ValidationError)?;
+
+    let batches = PayoutService::list_batches(&state.pool, params.status, limit).await?;
+
+    Ok(Json(batches))
+}
+
+/// Get a payout batch
+#[utoipa::path(
+    get,
+    path = "/api/admin/payouts/batches/{batch_id}",
+    tag = "payouts",
+    params(
+        ("batch_id" = String, Path, description = "Payout batch identifier")
+    ),
+    responses(
+        (status = 200, description = "Batch with its payouts", body = PayoutBatchDetailResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Batch not found", body = ErrorResponse),
+    ),
+    security(
+        ("AdminKeyAuth" = [])
+    )
+)]
+pub async fn get_payout_batch(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<AdminAuth>,
+    Path(batch_id): Path<String>,
+) -> Result<Json<PayoutBatchDetailResponse>, AppError> {
+    let batch = PayoutService::get_batch(&state.pool, &batch_id).await?;
+
+    Ok(Json(batch))
+}
+
+/// Download a batch's settlement file
+#[utoipa::path(
+    get,
+    path = "/api/admin/payouts/batches/{batch_id}/file",
+    tag = "payouts",
+    params(
+        ("batch_id" = String, Path, description = "Payout batch identifier")
+    ),
+    responses(
+        (status = 200, description = "NACHA or pain.001 file content"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Batch not found", body = ErrorResponse),
+    ),
+    security(
+        ("AdminKeyAuth" = [])
+    )
+)]
+pub async fn download_payout_batch_file(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<AdminAuth>,
+    Path(batch_id): Path<String>,
+) -> Result<Response, AppError> {
+    let (batch, path) = PayoutService::batch_file(&state.pool, &batch_id).await?;
+
+    let file = tokio::fs::File::open(&path).await.map_err(|e| {
+        AppError::InternalError(format!("Failed to open payout file {}: {}", batch_id, e))
+    })?;
+
+    Ok((
+        [
+            (CONTENT_TYPE, batch.format.content_type().to_string()),
+            (
+                CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", batch.file_name),
+            ),
+        ],
+        Body::from_stream(ReaderStream::new(file)),
+    )
+        .into_response())
+}
+
+/// Mark a payout batch as sent
+///
+/// Records that the settlement file was submitted to the bank; its payouts
+/// move to `sent`.
+#[utoipa::path(
+    post,
+    path = "/api/admin/payouts/batches/{batch_id}/sent",
+    tag = "payouts",
+    params(
+        ("batch_id" = String, Path, description = "Payout batch identifier")
+    ),
+    responses(
+        (status = 200, description = "Batch sent", body = PayoutBatchDetailResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Batch not found", body = ErrorResponse),
+        (status = 409, description = "Batch already sent", body = ErrorResponse),
+    ),
+    security(
+        ("AdminKeyAuth" = [])
+    )
+)]
+pub async fn mark_payout_batch_sent(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<AdminAuth>,
+    Path(batch_id): Path<String>,
+) -> Result<Json<PayoutBatchDetailResponse>, AppError> {
+    let batch = PayoutService::update_batch(&state.pool, &batch_id, BatchAction::Send, None).await?;
+
+    Ok(Json(batch))
+}
+
+/// Mark a payout batch as settled
+///
+/// Records that the bank paid the batch; its sent payouts move to
+/// `settled`. Payouts returned individually stay returned.
+#[utoipa::path(
+    post,
+    path = "/api/admin/payouts/batches/{batch_id}/settled",
+    tag = "payouts",
+    params(
+        ("batch_id" = String, Path, description = "Payout batch identifier")
+    ),
+    responses(
+        (status = 200, description = "Batch settled", body = PayoutBatchDetailResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Batch not found", body = ErrorResponse),
+        (status = 409, description = "Batch not sent", body = ErrorResponse),
+    ),
+    security(
+        ("AdminKeyAuth" = [])
+    )
+)]
+pub async fn mark_payout_batch_settled(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<AdminAuth>,
+    Path(batch_id): Path<String>,
+) -> Result<Json<PayoutBatchDetailResponse>, AppError> {
+    let batch = PayoutService::update_batch(&state.pool, &batch_id, BatchAction::Settle, None).await?;
+
+    Ok(Json(batch))
+}
+
+/// Mark a payout batch as returned
+///
+/// Records that the bank returned the whole batch. Every sent or settled
+/// payout in it is returned and credited back to its account with a
+/// completed `adjustment` linked to the original debit.
+#[utoipa::path(
+    post,
+    path = "/api/admin/payouts/batches/{batch_id}/returned",
+    tag = "payouts",
+    params(
+        ("batch_id" = String, Path, description = "Payout batch identifier")
+    ),
+    request_body = ReturnPayoutRequest,
+    responses(
+        (status = 200, description = "Batch returned", body = PayoutBatchDetailResponse),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Batch not found", body = ErrorResponse),
+        (status = 409, description = "Batch not sent or an account is closed", body = ErrorResponse),
+    ),
+    security(
+        ("AdminKeyAuth" = [])
+    )
+)]
+pub async fn mark_payout_batch_returned(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<AdminAuth>,
+    Path(batch_id): Path<String>,
+    Json(req): Json<ReturnPayoutRequest>,
+) -> Result<Json<PayoutBatchDetailResponse>, AppError> {
+    req.validate().map_err(AppError::ValidationError)?;
+
+    let batch =
+        PayoutService::update_batch(&state.pool, &batch_id, BatchAction::Return, Some(req.reason.trim())).await?;
+
+    Ok(Json(batch))
+}
+
+/// Return a single payout
+///
+/// Records one sent or settled payout bounced by the receiving bank, e.g.
+/// for a closed account, and credits it back with a completed `adjustment`
+/// linked to the original debit.
+#[utoipa::path(
+    post,
+    path = "/api/admin/payouts/{payout_id}/return",
+    tag = "payouts",
+    params(
+        ("payout_id" = String, Path, description = "Payout identifier")
+    ),
+    request_body = ReturnPayoutRequest,
+    responses(
+        (status = 200, description = "Payout returned", body = PayoutResponse),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Payout not found", body = ErrorResponse),
+        (status = 409, description = "Payout not sent or account closed", body = ErrorResponse),
+    ),
+    security(
+        ("AdminKeyAuth" = [])
+    )
+)]
+pub async fn return_payout(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<AdminAuth>,
+    Path(payout_id): Path<String>,
+    Json(req): Json<ReturnPayoutRequest>,
+) -> Result<Json<PayoutResponse>, AppError> {
+    req.validate().map_err(AppError::ValidationError)?;
+
+    let payout = PayoutService::return_payout(&state.pool, &payout_id, &req.reason).await?;
+
+    Ok(Json(payout))
+}
@@ -0,0 +1,248 @@
+use axum::{
+    extract::{rejection::QueryRejection, Path, Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use std::sync::Arc;
+
+use crate::{
+    app::AppState,
+    middleware::{auth::ClientAuth, errors::AppError},
+    models::{
+        common::ErrorResponse,
+        reconciliation::{
+            parse_statement, ImportStatementRequest, ManualMatchError, ManualMatchRequest,
+            MatchSettings, ReconciliationReport, ReconciliationReportParams,
+            ReconciliationService, StatementLineResponse,
+        },
+    },
+};
+
+/// Import a bank statement
+///
+/// Uploads a statement as CSV (with a column mapping) or CAMT.053 XML and
+/// matches each entry to a completed transaction by amount, currency, booking
+/// date within `RECONCILIATION_DATE_TOLERANCE_DAYS` and, when the entry has a
+/// reference, the reference keys in transaction metadata. Returns the
+/// reconciliation report for the import.
+#[utoipa::path(
+    post,
+    path = "/api/reconciliation/imports",
+    tag = "reconciliation",
+    request_body = ImportStatementRequest,
+    responses(
+        (status = 201, description = "Statement imported and matched", body = ReconciliationReport),
+        (status = 400, description = "Statement could not be parsed", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Account not found", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
+    )
+)]
+pub async fn import_statement(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<ClientAuth>,
+    Json(req): Json<ImportStatementRequest>,
+) -> Result<(StatusCode, Json<ReconciliationReport>), AppError> {
+    let lines = parse_statement(
+        req.format,
+        &req.content,
+        req.mapping.as_ref(),
+        state.config.statement_max_lines,
+    )?;
+
+    if let Some(account_id) = &req.account_id {
+        let account_exists = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM accounts WHERE account_id = $1"
+        )
+        .bind(account_id)
+        .fetch_one(&state.pool)
+        .await?;
+
+        if account_exists == 0 {
+            return Err(AppError::account_not_found(account_id));
+        }
+    }
+
+    let report = ReconciliationService::import(
+        &state.pool,
+        auth.context.key_id(),
+        &req,
+        &lines,
+        &MatchSettings::from_config(&state.config),
+    )
+    .await?;
+
+    Ok((StatusCode::CREATED, Json(report)))
+}
+
+/// Get a reconciliation report
+///
+/// Lists the matched, unmatched and ambiguous lines of a statement import
+/// with totals. Ambiguous lines carry the transactions that fit them.
+#[utoipa::path(
+    get,
+    path = "/api/reconciliation/imports/{import_id}",
+    tag = "reconciliation",
+    params(
+        ("import_id" = String, Path, description = "Statement import identifier"),
+        ReconciliationReportParams
+    ),
+    responses(
+        (status = 200, description = "Reconciliation report", body = ReconciliationReport),
+        (status = 400, description = "Invalid status filter", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Statement import not found", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
+    )
+)]
+pub async fn get_reconciliation_report(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<ClientAuth>,
+    Path(import_id): Path<String>,
+    params: Result<Query<ReconciliationReportParams>, QueryRejection>,
+) -> Result<Json<ReconciliationReport>, AppError> {
+    let Query(params) = params?;
+
+    let report = ReconciliationService::report(
+        &state.pool,
+        &import_id,
+        auth.context.key_id(),
+        params.status,
+    )
+    .await?
+    .ok_or_else(|| AppError::not_found("Statement import", &import_id))?;
+
+    Ok(Json(report))
+}
+
+/// Re-run matching
+///
+/// Runs automatic matching again for the unmatched and ambiguous lines of an
+/// import, e.g. after missing transactions were created. Matched lines are kept.
+#[utoipa::path(
+    post,
+    path = "/api/reconciliation/imports/{import_id}/rematch",
+    tag = "reconciliation",
+    params(
+        ("import_id" = String, Path, description = "Statement import identifier")
+    ),
+    responses(
+        (status = 200, description = "Updated reconciliation report", body = ReconciliationReport),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Statement import not found", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
+    )
+)]
+pub async fn rematch_statement(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<ClientAuth>,
+    Path(import_id): Path<String>,
+) -> Result<Json<ReconciliationReport>, AppError> {
+    let key_id = auth.context.key_id();
+    let settings = MatchSettings::from_config(&state.config);
+
+    if !ReconciliationService::rematch(&state.pool, &import_id, key_id, &settings).await? {
+        return Err(AppError::not_found("Statement import", &import_id));
+    }
+
+    let report = ReconciliationService::report(&state.pool, &import_id, key_id, None)
+        .await?
+        .ok_or_else(|| AppError::not_found("Statement import", &import_id))?;
+
+    Ok(Json(report))
+}
+
+/// Match a statement line manually
+///
+/// Pairs a statement line with a transaction, replacing any automatic match.
+/// A transaction can only be matched to one line.
+#[utoipa::path(
+    post,
+    path = "/api/reconciliation/lines/{line_id}/match",
+    tag = "reconciliation",
+    params(
+        ("line_id" = String, Path, description = "Statement line identifier")
+    ),
+    request_body = ManualMatchRequest,
+    responses(
+        (status = 200, description = "Line matched", body = StatementLineResponse),
+        (status = 400, description = "Transaction belongs to another account", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Line or transaction not found", body = ErrorResponse),
+        (status = 409, description = "Transaction already matched to another line", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
+    )
+)]
+pub async fn match_statement_line(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<ClientAuth>,
+    Path(line_id): Path<String>,
+    Json(req): Json<ManualMatchRequest>,
+) -> Result<Json<StatementLineResponse>, AppError> {
+    let line = ReconciliationService::manual_match(
+        &state.pool,
+        &line_id,
+        &req.transaction_id,
+        auth.context.key_id(),
+    )
+    .await
+    .map_err(|e| match e {
+        ManualMatchError::LineNotFound => AppError::not_found("Statement line", &line_id),
+        ManualMatchError::TransactionNotFound => AppError::transaction_not_found(&req.transaction_id),
+        ManualMatchError::TransactionTaken(other) => AppError::Conflict(format!(
+            "Transaction '{}' is already matched to statement line '{}'",
+            req.transaction_id, other
+        )),
+        ManualMatchError::AccountMismatch => AppError::ValidationError(format!(
+            "Transaction '{}' does not belong to the statement's account",
+            req.transaction_id
+        )),
+        ManualMatchError::Database(e) => AppError::DatabaseError(e),
+    })?;
+
+    Ok(Json(line))
+}
+
+/// Unmatch a statement line
+///
+/// Removes the match of a statement line so it can be matched again.
+#[utoipa::path(
+    post,
+    path = "/api/reconciliation/lines/{line_id}/unmatch",
+    tag = "reconciliation",
+    params(
+        ("line_id" = String, Path, description = "Statement line identifier")
+    ),
+    responses(
+        (status = 200, description = "Line unmatched", body = StatementLineResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Statement line not found", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
+    )
+)]
+pub async fn unmatch_statement_line(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<ClientAuth>,
+    Path(line_id): Path<String>,
+) -> Result<Json<StatementLineResponse>, AppError> {
+    let line = ReconciliationService::unmatch(&state.pool, &line_id, auth.context.key_id())
+        .await?
+        .ok_or_else(|| AppError::not_found("Statement line", &line_id))?;
+
+    Ok(Json(line))
+}
@@ -0,0 +1,268 @@
+use axum::{
+    extract::{rejection::QueryRejection, Path, Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use std::sync::Arc;
+
+use crate::{
+    app::AppState,
+    middleware::{auth::ClientAuth, errors::AppError},
+    models::{
+        common::ErrorResponse,
+        metadata::{MetadataSchemaService, MetadataTarget},
+        schedules::{
+            CreateScheduleRequest, ScheduleAction, ScheduleListParams, ScheduleRunParams,
+            ScheduleRunResponse, ScheduleService, ScheduleSettings, TransactionScheduleResponse,
+        },
+    },
+};
+
+/// Create a transaction schedule
+///
+/// Schedules a transaction once at `start_at`, weekly, or monthly on a given
+/// day. A background worker creates each occurrence like
+/// `POST /api/transactions`, tagging it with `schedule_id` in its metadata.
+/// Occurrences failing on a limit or a frozen account are retried with
+/// exponential backoff; a closed or deleted account fails the schedule.
+#[utoipa::path(
+    post,
+    path = "/api/schedules",
+    tag = "schedules",
+    request_body = CreateScheduleRequest,
+    responses(
+        (status = 201, description = "Schedule created", body = TransactionScheduleResponse),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Account not found", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
+    )
+)]
+pub async fn create_schedule(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<ClientAuth>,
+    Json(req): Json<CreateScheduleRequest>,
+) -> Result<(StatusCode, Json<TransactionScheduleResponse>), AppError> {
+    let now = state.clock.now();
+    let schema =
+        MetadataSchemaService::for_key(&state.pool, auth.context.key_id(), MetadataTarget::Transaction).await?;
+    req.transaction.validate_metadata(schema.as_ref())?;
+    req.validate(now).map_err(AppError::ValidationError)?;
+
+    let account_exists = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM accounts WHERE account_id = $1"
+    )
+    .bind(&req.transaction.account_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    if account_exists == 0 {
+        return Err(AppError::account_not_found(&req.transaction.account_id));
+    }
+
+    let settings = ScheduleSettings::from_config(&state.config);
+    let schedule = ScheduleService::create(&state.pool, &req, settings.default_retry, now).await?;
+
+    Ok((StatusCode::CREATED, Json(schedule)))
+}
+
+/// List transaction schedules
+///
+/// Returns schedules newest first, optionally for one account or status.
+#[utoipa::path(
+    get,
+    path = "/api/schedules",
+    tag = "schedules",
+    params(ScheduleListParams),
+    responses(
+        (status = 200, description = "Schedules", body = Vec<TransactionScheduleResponse>),
+        (status = 400, description = "Invalid parameters", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
+    )
+)]
+pub async fn list_schedules(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<ClientAuth>,
+    params: Result<Query<ScheduleListParams>, QueryRejection>,
+) -> Result<Json<Vec<TransactionScheduleResponse>>, AppError> {
+    let Query(params) = params?;
+    let limit = params.limit().map_err(AppError::ValidationError)?;
+
+    let schedules =
+        ScheduleService::list(&state.pool, params.account_id.as_deref(), params.status, limit).await?;
+
+    Ok(Json(schedules))
+}
+
+/// Get a transaction schedule
+#[utoipa::path(
+    get,
+    path = "/api/schedules/{schedule_id}",
+    tag = "schedules",
+    params(
+        ("schedule_id" = String, Path, description = "Schedule identifier")
+    ),
+    responses(
+        (status = 200, description = "Schedule found", body = TransactionScheduleResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Schedule not found", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
+    )
+)]
+pub async fn get_schedule(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<ClientAuth>,
+    Path(schedule_id): Path<String>,
+) -> Result<Json<TransactionScheduleResponse>, AppError> {
+    let schedule = ScheduleService::get(&state.pool, &schedule_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Schedule", &schedule_id))?;
+
+    Ok(Json(schedule))
+}
+
+/// Get schedule run history
+///
+/// Returns every attempt at the schedule's occurrences, newest first, with
+/// the transaction it created or the error it failed with.
+#[utoipa::path(
+    get,
+    path = "/api/schedules/{schedule_id}/runs",
+    tag = "schedules",
+    params(
+        ("schedule_id" = String, Path, description = "Schedule identifier"),
+        ScheduleRunParams
+    ),
+    responses(
+        (status = 200, description = "Schedule runs", body = Vec<ScheduleRunResponse>),
+        (status = 400, description = "Invalid parameters", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Schedule not found", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
+    )
+)]
+pub async fn get_schedule_runs(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<ClientAuth>,
+    Path(schedule_id): Path<String>,
+    params: Result<Query<ScheduleRunParams>, QueryRejection>,
+) -> Result<Json<Vec<ScheduleRunResponse>>, AppError> {
+    let Query(params) = params?;
+    let limit = params.limit().map_err(AppError::ValidationError)?;
+
+    if ScheduleService::get(&state.pool, &schedule_id).await?.is_none() {
+        return Err(AppError::not_found("Schedule", &schedule_id));
+    }
+
+    let runs = ScheduleService::runs(&state.pool, &schedule_id, limit).await?;
+
+    Ok(Json(runs))
+}
+
+/// Pause a transaction schedule
+#[utoipa::path(
+    post,
+    path = "/api/schedules/{schedule_id}/pause",
+    tag = "schedules",
+    params(
+        ("schedule_id" = String, Path, description = "Schedule identifier")
+    ),
+    responses(
+        (status = 200, description = "Schedule paused", body = TransactionScheduleResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Schedule not found", body = ErrorResponse),
+        (status = 409, description = "Schedule is not active", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
+    )
+)]
+pub async fn pause_schedule(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<ClientAuth>,
+    Path(schedule_id): Path<String>,
+) -> Result<Json<TransactionScheduleResponse>, AppError> {
+    let schedule =
+        ScheduleService::change_status(&state.pool, &schedule_id, ScheduleAction::Pause, state.clock.now()).await?;
+
+    Ok(Json(schedule))
+}
+
+/// Resume a transaction schedule
+///
+/// Reactivates a paused schedule. Occurrences that fell due while it was
+/// paused are skipped, not created late.
+#[utoipa::path(
+    post,
+    path = "/api/schedules/{schedule_id}/resume",
+    tag = "schedules",
+    params(
+        ("schedule_id" = String, Path, description = "Schedule identifier")
+    ),
+    responses(
+        (status = 200, description = "Schedule resumed", body = TransactionScheduleResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Schedule not found", body = ErrorResponse),
+        (status = 409, description = "Schedule is not paused", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
+    )
+)]
+pub async fn resume_schedule(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<ClientAuth>,
+    Path(schedule_id): Path<String>,
+) -> Result<Json<TransactionScheduleResponse>, AppError> {
+    let schedule =
+        ScheduleService::change_status(&state.pool, &schedule_id, ScheduleAction::Resume, state.clock.now()).await?;
+
+    Ok(Json(schedule))
+}
+
+/// Cancel a transaction schedule
+///
+/// Stops an active or paused schedule for good.
+#[utoipa::path(
+    post,
+    path = "/api/schedules/{schedule_id}/cancel",
+    tag = "schedules",
+    params(
+        ("schedule_id" = String, Path, description = "Schedule identifier")
+    ),
+    responses(
+        (status = 200, description = "Schedule cancelled", body = TransactionScheduleResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Schedule not found", body = ErrorResponse),
+        (status = 409, description = "Schedule already ended", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
+    )
+)]
+pub async fn cancel_schedule(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<ClientAuth>,
+    Path(schedule_id): Path<String>,
+) -> Result<Json<TransactionScheduleResponse>, AppError> {
+    let schedule =
+        ScheduleService::change_status(&state.pool, &schedule_id, ScheduleAction::Cancel, state.clock.now()).await?;
+
+    Ok(Json(schedule))
+}
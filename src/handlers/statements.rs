@@ -0,0 +1,111 @@
+use axum::{
+    extract::{rejection::QueryRejection, Path, Query, State},
+    http::header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use std::sync::Arc;
+
+use crate::{
+    app::AppState,
+    middleware::{auth::ClientAuth, errors::AppError},
+    models::{
+        common::ErrorResponse,
+        export::ExportService,
+        statement::{
+            statement_filters, AccountStatement, AccountStatementFormat, AccountStatementParams,
+            StatementService,
+        },
+    },
+};
+
+/// Get an account statement
+///
+/// Returns the opening balance, every transaction created in the period with
+/// the running balance after it, totals per transaction type and the closing
+/// balance. Balances are calculated like `GET /api/accounts/{account_id}/balance`:
+/// only completed transactions count. Days are UTC.
+///
+/// `format=csv` returns a file with an opening balance row, a row per
+/// transaction, a total row per type and a closing balance row; `format=html`
+/// returns a printable page.
+#[utoipa::path(
+    get,
+    path = "/api/accounts/{account_id}/statements",
+    tag = "accounts",
+    params(
+        ("account_id" = String, Path, description = "Account identifier"),
+        AccountStatementParams
+    ),
+    responses(
+        (status = 200, description = "Account statement", content(
+            (AccountStatement = "application/json"),
+            (String = "text/csv"),
+            (String = "text/html"),
+        )),
+        (status = 400, description = "Invalid period or too many transactions", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Account not found", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
+    )
+)]
+pub async fn get_account_statement(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<ClientAuth>,
+    Path(account_id): Path<String>,
+    params: Result<Query<AccountStatementParams>, QueryRejection>,
+) -> Result<Response, AppError> {
+    let Query(params) = params?;
+    let period = params
+        .period(chrono::Utc::now().date_naive())
+        .map_err(AppError::ValidationError)?;
+
+    let account_exists = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM accounts WHERE account_id = $1"
+    )
+    .bind(&account_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    if account_exists == 0 {
+        return Err(AppError::account_not_found(&account_id));
+    }
+
+    let max = state.config.account_statement_max_transactions;
+    let filters = statement_filters(&account_id, period.0, period.1);
+    if ExportService::count(&state.pool, &filters).await? > max {
+        return Err(AppError::ValidationError(format!(
+            "Statement period has more than {} transactions; choose a shorter period or use /api/transactions/export",
+            max
+        )));
+    }
+
+    let statement = StatementService::generate(&state.pool, &account_id, period).await?;
+
+    Ok(render(statement, params.format))
+}
+
+fn render(statement: AccountStatement, format: AccountStatementFormat) -> Response {
+    match format {
+        AccountStatementFormat::Json => Json(statement).into_response(),
+        AccountStatementFormat::Csv => (
+            [
+                (CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                (
+                    CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"{}\"", statement.file_name(format)),
+                ),
+            ],
+            statement.to_csv(),
+        )
+            .into_response(),
+        AccountStatementFormat::Html => (
+            [(CONTENT_TYPE, "text/html; charset=utf-8".to_string())],
+            statement.to_html(),
+        )
+            .into_response(),
+    }
+}
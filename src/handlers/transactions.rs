@@ -1,6 +1,11 @@
 use axum::{
-    extract::{Path, Query, State},
-    http::StatusCode,
+    body::Bytes,
+    extract::{rejection::QueryRejection, Path, Query, State},
+    http::{
+        header::{CONTENT_TYPE, LOCATION},
+        HeaderMap, StatusCode,
+    },
+    response::{IntoResponse, Response},
     Extension, Json,
 };
 use std::sync::Arc;
@@ -9,17 +14,36 @@ use crate::{
     app::AppState,
     middleware::{auth::ClientAuth, errors::AppError},
     models::{
+        balance::{BalanceHistoryParams, BalanceHistoryResponse, BalanceParams, BalanceService},
+        batch::{
+            parse_batch, BatchFormat, BatchJobResponse, BatchParams, BatchResponse, BatchSettings,
+            TransactionBatchService,
+        },
         common::{ErrorResponse, PaginatedResponse, PaginationParams},
-        finance::{TransactionFilters, TransactionStatus},
+        fees::FeeService,
+        finance::TransactionFilters,
+        metadata::{MetadataSchemaService, MetadataTarget},
+        pagination::KeysetPage,
+        posting::TransactionService,
+        quota::QuotaService,
         requests::CreateTransactionRequest,
         responses::{BalanceResponse, TransactionResponse},
+        reversals::{AdjustTransactionRequest, ReversalService, ReverseTransactionRequest},
     },
+    observability::metrics::record_quota_usage_by,
 };
 
 /// Create a new transaction
 ///
 /// Creates a new financial transaction for the specified account.
 /// Supports multiple transaction types including payments, refunds, and payouts.
+/// A transaction that would break one of the account's limits is recorded as
+/// `failed` with a `failure_reason` and rejected with `422 limit_exceeded`; the
+/// error details carry its `transaction_id`. Fraud screening runs next: held
+/// transactions are created as `pending` until an administrator reviews them,
+/// blocked ones are recorded as `failed` and rejected with
+/// `422 transaction_blocked`. Metadata must match the key's transaction
+/// metadata schema, if one is registered.
 #[utoipa::path(
     post,
     path = "/api/transactions",
@@ -30,6 +54,8 @@ use crate::{
         (status = 400, description = "Invalid input", body = ErrorResponse),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
         (status = 404, description = "Account not found", body = ErrorResponse),
+        (status = 409, description = "Account is frozen or closed", body = ErrorResponse),
+        (status = 422, description = "Account limit exceeded or blocked by fraud screening; the transaction was recorded as failed", body = ErrorResponse),
         (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
     ),
     security(
@@ -38,76 +64,150 @@ use crate::{
 )]
 pub async fn create_transaction(
     State(state): State<Arc<AppState>>,
-    Extension(_auth): Extension<ClientAuth>,
+    Extension(auth): Extension<ClientAuth>,
     Json(req): Json<CreateTransactionRequest>,
 ) -> Result<(StatusCode, Json<TransactionResponse>), AppError> {
+    let schema =
+        MetadataSchemaService::for_key(&state.pool, auth.context.key_id(), MetadataTarget::Transaction).await?;
+    req.validate_metadata(schema.as_ref())?;
     req.validate()
         .map_err(|e| AppError::ValidationError(e))?;
 
-    let account_exists = sqlx::query_scalar::<_, i64>(
-        "SELECT COUNT(*) FROM accounts WHERE account_id = $1"
+    let transaction = TransactionService::create(&state.pool, &state.fraud_screener, &req).await?;
+
+    Ok((StatusCode::CREATED, Json(transaction)))
+}
+
+/// Create transactions in bulk
+///
+/// Accepts up to `BATCH_MAX_ITEMS` transactions as a JSON array or, with
+/// `Content-Type: application/x-ndjson`, one transaction per line. Each item is
+/// validated like `POST /api/transactions` and reported in `results` by index.
+/// In `best_effort` mode valid items are created and invalid ones fail alone; in
+/// `all_or_nothing` mode nothing is created if any item fails. Every item counts
+/// against the key's quota. Batches larger than `BATCH_ASYNC_THRESHOLD` return
+/// `202` with a job to poll at `/api/transactions/batch/jobs/{job_id}`.
+#[utoipa::path(
+    post,
+    path = "/api/transactions/batch",
+    tag = "transactions",
+    params(BatchParams),
+    request_body(
+        content = Vec<CreateTransactionRequest>,
+        content_type = "application/json",
+        description = "JSON array of transactions, or NDJSON with Content-Type application/x-ndjson"
+    ),
+    responses(
+        (status = 200, description = "Batch processed", body = BatchResponse),
+        (status = 202, description = "Batch accepted for background processing", body = BatchJobResponse),
+        (status = 400, description = "Malformed body, empty or oversized batch", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 429, description = "Rate limit or quota exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
     )
-    .bind(&req.account_id)
-    .fetch_one(&state.pool)
-    .await?;
+)]
+pub async fn create_transaction_batch(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<ClientAuth>,
+    params: Result<Query<BatchParams>, QueryRejection>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Response, AppError> {
+    let Query(params) = params?;
+    let settings = BatchSettings::from_config(&state.config);
+
+    let format = BatchFormat::from_content_type(
+        headers.get(CONTENT_TYPE).and_then(|value| value.to_str().ok()),
+    );
+    let items = parse_batch(&body, format, settings.max_items)?;
 
-    if account_exists == 0 {
-        return Err(AppError::account_not_found(&req.account_id));
+    if let Some(key_id) = auth.context.key_id() {
+        charge_batch_items(&state, key_id, items.len()).await?;
     }
 
-    let transaction_id = crate::models::finance::generate_transaction_id();
-
-    let transaction = sqlx::query_as::<_, (
-        String,
-        String,
-        f64,
-        String,
-        String,
-        String,
-        Option<String>,
-        Option<serde_json::Value>,
-        chrono::DateTime<chrono::Utc>,
-        Option<chrono::DateTime<chrono::Utc>>,
-    )>(
-        r#"
-        INSERT INTO transactions (
-            transaction_id, account_id, amount, currency, 
-            transaction_type, status, description, metadata,
-            created_at, processed_at
+    if items.len() > settings.async_threshold {
+        let job = TransactionBatchService::enqueue(
+            &state.pool,
+            auth.context.key_id(),
+            items,
+            params.mode,
         )
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, NOW(), NOW())
-        RETURNING 
-            transaction_id, account_id, amount, currency,
-            transaction_type, status, description, metadata,
-            created_at, processed_at
-        "#
+        .await?;
+        let location = format!("/api/transactions/batch/jobs/{}", job.job_id);
+
+        return Ok((StatusCode::ACCEPTED, [(LOCATION, location)], Json(job)).into_response());
+    }
+
+    let mut tx = state.pool.begin().await?;
+    let response = TransactionBatchService::process(
+        &mut tx,
+        &items,
+        params.mode,
+        auth.context.key_id(),
+        &state.fraud_screener,
     )
-    .bind(&transaction_id)
-    .bind(&req.account_id)
-    .bind(req.amount)
-    .bind(req.currency.to_string())
-    .bind(req.transaction_type.to_string())
-    .bind(TransactionStatus::Completed.to_string())
-    .bind(&req.description)
-    .bind(&req.metadata)
-    .fetch_one(&state.pool)
     .await?;
+    tx.commit().await?;
 
-    Ok((
-        StatusCode::CREATED,
-        Json(TransactionResponse {
-            transaction_id: transaction.0,
-            account_id: transaction.1,
-            amount: transaction.2,
-            currency: transaction.3.parse().unwrap_or_default(),
-            transaction_type: transaction.4.parse().unwrap_or_default(),
-            status: transaction.5.parse().unwrap_or_default(),
-            description: transaction.6,
-            metadata: transaction.7,
-            created_at: transaction.8,
-            processed_at: transaction.9,
-        }),
-    ))
+    Ok((StatusCode::OK, Json(response)).into_response())
+}
+
+/// Charges one quota unit per batch item. The rate limit middleware has
+/// already charged one for the request itself.
+async fn charge_batch_items(state: &AppState, key_id: &str, items: usize) -> Result<(), AppError> {
+    let extra = items.saturating_sub(1) as i32;
+    if extra == 0 {
+        return Ok(());
+    }
+
+    let quota = QuotaService::load_state(&state.pool, key_id).await?;
+    if !quota.has_capacity(extra) {
+        return Err(AppError::QuotaExceeded);
+    }
+
+    let today_count = QuotaService::increment_usage_by(&state.pool, key_id, quota.windows.today, extra).await?;
+    record_quota_usage_by(key_id, extra as u64);
+
+    if let Err(e) = QuotaService::record_threshold_crossings(&state.pool, key_id, &quota, today_count).await {
+        tracing::error!("Failed to record quota thresholds: {}", e);
+    }
+
+    Ok(())
+}
+
+/// Get a batch job
+///
+/// Returns the status of a batch accepted for background processing, with
+/// per-item results once it has completed.
+#[utoipa::path(
+    get,
+    path = "/api/transactions/batch/jobs/{job_id}",
+    tag = "transactions",
+    params(
+        ("job_id" = String, Path, description = "Batch job identifier")
+    ),
+    responses(
+        (status = 200, description = "Batch job", body = BatchJobResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Batch job not found", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
+    )
+)]
+pub async fn get_transaction_batch_job(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<ClientAuth>,
+    Path(job_id): Path<String>,
+) -> Result<Json<BatchJobResponse>, AppError> {
+    let job = TransactionBatchService::get_job(&state.pool, &job_id, auth.context.key_id())
+        .await?
+        .ok_or_else(|| AppError::not_found("Batch job", &job_id))?;
+
+    Ok(Json(job))
 }
 
 /// Get transaction details
@@ -146,12 +246,13 @@ pub async fn get_transaction(
         Option<serde_json::Value>,
         chrono::DateTime<chrono::Utc>,
         Option<chrono::DateTime<chrono::Utc>>,
+        Option<String>,
     )>(
         r#"
         SELECT 
             transaction_id, account_id, amount, currency,
             transaction_type, status, description, metadata,
-            created_at, processed_at
+            created_at, processed_at, failure_reason
         FROM transactions
         WHERE transaction_id = $1
         "#
@@ -161,7 +262,7 @@ pub async fn get_transaction(
     .await?
     .ok_or_else(|| AppError::transaction_not_found(&transaction_id))?;
 
-    Ok(Json(TransactionResponse {
+    let mut transaction = TransactionResponse {
         transaction_id: transaction.0,
         account_id: transaction.1,
         amount: transaction.2,
@@ -172,13 +273,110 @@ pub async fn get_transaction(
         metadata: transaction.7,
         created_at: transaction.8,
         processed_at: transaction.9,
-    }))
+        failure_reason: transaction.10.and_then(|r| r.parse().ok()),
+        fee: None,
+        links: None,
+    };
+    FeeService::attach(&state.pool, std::slice::from_mut(&mut transaction)).await?;
+    ReversalService::attach(&state.pool, std::slice::from_mut(&mut transaction)).await?;
+
+    Ok(Json(transaction))
+}
+
+/// Reverse a transaction
+///
+/// Books a completed `reversal` entry undoing a completed transaction: the
+/// opposite of its effect on the account, in the same currency. The original
+/// is left unchanged and shows the link in `links.reversed_by`. A transaction
+/// is reversed at most once; reversals and chargebacks cannot be reversed,
+/// nor can a payment with a dispute that was not won, since its chargeback
+/// already withdrew the funds. Fees charged on the transaction are not
+/// reversed with it.
+#[utoipa::path(
+    post,
+    path = "/api/transactions/{transaction_id}/reverse",
+    tag = "transactions",
+    params(
+        ("transaction_id" = String, Path, description = "Transaction identifier")
+    ),
+    request_body = ReverseTransactionRequest,
+    responses(
+        (status = 201, description = "Reversal booked", body = TransactionResponse),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Transaction not found", body = ErrorResponse),
+        (status = 409, description = "Transaction not completed, already reversed, disputed or not reversible, or account not active", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
+    )
+)]
+pub async fn reverse_transaction(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<ClientAuth>,
+    Path(transaction_id): Path<String>,
+    req: Option<Json<ReverseTransactionRequest>>,
+) -> Result<(StatusCode, Json<TransactionResponse>), AppError> {
+    let req = req.map(|Json(req)| req).unwrap_or_default();
+    req.validate().map_err(AppError::ValidationError)?;
+
+    let reversal = ReversalService::reverse(
+        &state.pool,
+        &transaction_id,
+        req.reason.as_deref().map(str::trim),
+    )
+    .await?;
+
+    Ok((StatusCode::CREATED, Json(reversal)))
+}
+
+/// Adjust a transaction
+///
+/// Books a completed `adjustment` entry correcting a completed transaction by
+/// a signed amount, with a mandatory reason. The original is left unchanged
+/// and lists its corrections in `links.adjusted_by`. Reversed transactions
+/// cannot be adjusted.
+#[utoipa::path(
+    post,
+    path = "/api/transactions/{transaction_id}/adjustments",
+    tag = "transactions",
+    params(
+        ("transaction_id" = String, Path, description = "Transaction identifier")
+    ),
+    request_body = AdjustTransactionRequest,
+    responses(
+        (status = 201, description = "Adjustment booked", body = TransactionResponse),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Transaction not found", body = ErrorResponse),
+        (status = 409, description = "Transaction not completed, reversed or not adjustable, or account not active", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
+    )
+)]
+pub async fn adjust_transaction(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<ClientAuth>,
+    Path(transaction_id): Path<String>,
+    Json(req): Json<AdjustTransactionRequest>,
+) -> Result<(StatusCode, Json<TransactionResponse>), AppError> {
+    req.validate().map_err(AppError::ValidationError)?;
+
+    let adjustment = ReversalService::adjust(&state.pool, &transaction_id, &req).await?;
+
+    Ok((StatusCode::CREATED, Json(adjustment)))
 }
 
 /// List all transactions
 ///
 /// Retrieves a paginated and filtered list of transactions.
-/// Supports filtering by account, status, type, currency, and date range.
+/// Supports filtering by account, status, type and currency (comma separated for
+/// several values), date and amount ranges, metadata containment and a text search
+/// over the description. Results are sorted by `created_at` or `amount` in either
+/// direction; `next_cursor` and `prev_cursor` are only valid for the same sort.
 #[utoipa::path(
     get,
     path = "/api/transactions",
@@ -189,6 +387,7 @@ pub async fn get_transaction(
     ),
     responses(
         (status = 200, description = "List of transactions", body = PaginatedResponse<TransactionResponse>),
+        (status = 400, description = "Invalid filters or cursor", body = ErrorResponse),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
         (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
     ),
@@ -200,74 +399,47 @@ pub async fn list_transactions(
     State(state): State<Arc<AppState>>,
     Extension(_auth): Extension<ClientAuth>,
     Query(params): Query<PaginationParams>,
-    Query(filters): Query<TransactionFilters>,
+    filters: Result<Query<TransactionFilters>, QueryRejection>,
 ) -> Result<Json<PaginatedResponse<TransactionResponse>>, AppError> {
+    let Query(filters) = filters?;
     params.validate()
         .map_err(|e| AppError::ValidationError(e.to_string()))?;
 
-    let limit = params.limit.unwrap_or(20) as i64;
+    filters.validate()?;
+
+    let (sort_by, sort_order) = filters.sort();
+    let (sort_column, sort_cast) = sort_by.column();
+    let page = KeysetPage::new(
+        &params,
+        &state.cursor_signer,
+        "transactions",
+        &sort_by.to_string(),
+        sort_order,
+    )?;
 
-    let mut query = String::from(
+    let (where_clause, mut bind_values) = filters.where_clause();
+    let mut query = format!(
         r#"
         SELECT 
             transaction_id, account_id, amount, currency,
             transaction_type, status, description, metadata,
-            created_at, processed_at
+            created_at, processed_at, failure_reason
         FROM transactions
-        WHERE 1=1
-        "#
+        {}
+        "#,
+        where_clause
     );
 
-    let mut bind_values: Vec<String> = vec![];
-    let mut param_count = 0;
-
-    if let Some(account_id) = &filters.account_id {
-        param_count += 1;
-        query.push_str(&format!(" AND account_id = ${}", param_count));
-        bind_values.push(account_id.clone());
-    }
-
-    if let Some(status) = &filters.status {
-        param_count += 1;
-        query.push_str(&format!(" AND status = ${}", param_count));
-        bind_values.push(status.to_string());
+    if let Some((condition, values)) =
+        page.condition(sort_column, sort_cast, "transaction_id", bind_values.len() + 1)
+    {
+        query.push_str(&condition);
+        bind_values.extend(values);
     }
 
-    if let Some(txn_type) = &filters.transaction_type {
-        param_count += 1;
-        query.push_str(&format!(" AND transaction_type = ${}", param_count));
-        bind_values.push(txn_type.to_string());
-    }
-
-    if let Some(currency) = &filters.currency {
-        param_count += 1;
-        query.push_str(&format!(" AND currency = ${}", param_count));
-        bind_values.push(currency.to_string());
-    }
-
-    if let Some(start) = &filters.created_after {
-        param_count += 1;
-        query.push_str(&format!(" AND created_at >= ${}", param_count));
-        bind_values.push(start.to_rfc3339());
-    }
-
-    if let Some(end) = &filters.created_before {
-        param_count += 1;
-        query.push_str(&format!(" AND created_at <= ${}", param_count));
-        bind_values.push(end.to_rfc3339());
-    }
-
-    if let Some(cursor) = &params.cursor {
-        let decoded = cursor.decode_string()
-            .map_err(|e| AppError::InvalidInput(format!("Invalid cursor: {}", e)))?;
-        param_count += 1;
-        query.push_str(&format!(" AND transaction_id > ${}", param_count));
-        bind_values.push(decoded);
-    }
-
-    param_count += 1;
-    query.push_str(&format!(" ORDER BY transaction_id ASC LIMIT ${}", param_count));
-    bind_values.push((limit + 1).to_string());
+    query.push_str(&page.order_by(sort_column, "transaction_id"));
+    query.push_str(&format!(" LIMIT ${}::bigint", bind_values.len() + 1));
+    bind_values.push(page.fetch_limit().to_string());
 
     let mut sql_query = sqlx::query_as::<_, (
         String,
@@ -280,6 +452,7 @@ pub async fn list_transactions(
         Option<serde_json::Value>,
         chrono::DateTime<chrono::Utc>,
         Option<chrono::DateTime<chrono::Utc>>,
+        Option<String>,
     )>(&query);
 
     for value in &bind_values {
@@ -288,10 +461,8 @@ pub async fn list_transactions(
 
     let transactions = sql_query.fetch_all(&state.pool).await?;
 
-    let has_more = transactions.len() > limit as usize;
-    let items: Vec<TransactionResponse> = transactions
+    let mut items: Vec<TransactionResponse> = transactions
         .into_iter()
-        .take(limit as usize)
         .map(|t| TransactionResponse {
             transaction_id: t.0,
             account_id: t.1,
@@ -303,22 +474,20 @@ pub async fn list_transactions(
             metadata: t.7,
             created_at: t.8,
             processed_at: t.9,
+            failure_reason: t.10.and_then(|r| r.parse().ok()),
+            fee: None,
+            links: None,
         })
         .collect();
+    FeeService::attach(&state.pool, &mut items).await?;
+    ReversalService::attach(&state.pool, &mut items).await?;
 
-    let next_cursor = if has_more {
-        items.last().map(|item| {
-            crate::models::common::Cursor::encode(&item.transaction_id)
-        })
-    } else {
-        None
-    };
-
-    Ok(Json(PaginatedResponse {
-        data: items,
-        has_more,
-        next_cursor,
-    }))
+    Ok(Json(page.paginate(items, &state.cursor_signer, |item| {
+        (
+            sort_by.value_of(&item.created_at, item.amount),
+            item.transaction_id.clone(),
+        )
+    })))
 }
 
 /// Get account transactions
@@ -334,6 +503,7 @@ pub async fn list_transactions(
     ),
     responses(
         (status = 200, description = "List of account transactions", body = PaginatedResponse<TransactionResponse>),
+        (status = 400, description = "Invalid cursor", body = ErrorResponse),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
         (status = 404, description = "Account not found", body = ErrorResponse),
         (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
@@ -366,24 +536,27 @@ pub async fn get_account_transactions(
         State(state),
         Extension(_auth),
         Query(params),
-        Query(filters),
+        Ok(Query(filters)),
     )
     .await
 }
 
 /// Get account balance
 ///
-/// Retrieves the current balance for a specific account.
-/// The balance is calculated from all completed transactions.
+/// Retrieves the balance of a specific account, now or at a past instant with
+/// `as_of`. The balance is calculated from completed transactions, starting
+/// from the latest daily snapshot before that instant.
 #[utoipa::path(
     get,
     path = "/api/accounts/{account_id}/balance",
     tag = "transactions",
     params(
-        ("account_id" = String, Path, description = "Account identifier")
+        ("account_id" = String, Path, description = "Account identifier"),
+        BalanceParams
     ),
     responses(
         (status = 200, description = "Account balance", body = BalanceResponse),
+        (status = 400, description = "Invalid as_of", body = ErrorResponse),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
         (status = 404, description = "Account not found", body = ErrorResponse),
         (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
@@ -396,7 +569,17 @@ pub async fn get_account_balance(
     State(state): State<Arc<AppState>>,
     Extension(_auth): Extension<ClientAuth>,
     Path(account_id): Path<String>,
+    params: Result<Query<BalanceParams>, QueryRejection>,
 ) -> Result<Json<BalanceResponse>, AppError> {
+    let Query(params) = params?;
+    let now = chrono::Utc::now();
+
+    if params.as_of.is_some_and(|as_of| as_of > now) {
+        return Err(AppError::ValidationError(
+            "as_of must not be in the future".to_string(),
+        ));
+    }
+
     let account_exists = sqlx::query_scalar::<_, i64>(
         "SELECT COUNT(*) FROM accounts WHERE account_id = $1"
     )
@@ -408,26 +591,63 @@ pub async fn get_account_balance(
         return Err(AppError::account_not_found(&account_id));
     }
 
-    let balance = sqlx::query_as::<_, (Option<f64>, Option<String>)>(
-        r#"
-        SELECT 
-            COALESCE(SUM(amount), 0.0) as balance,
-            MAX(currency) as currency
-        FROM transactions
-        WHERE account_id = $1 AND status = 'completed'
-        "#
+    let (balance, currency) =
+        BalanceService::balance_at(&state.pool, &account_id, params.as_of).await?;
+
+    Ok(Json(BalanceResponse {
+        account_id,
+        balance,
+        currency: currency.unwrap_or_default(),
+        as_of: params.as_of.unwrap_or(now),
+    }))
+}
+
+/// Get account balance history
+///
+/// Returns the end-of-day balance for every UTC day in the range, for
+/// charting. Ranges span at most 366 days.
+#[utoipa::path(
+    get,
+    path = "/api/accounts/{account_id}/balance/history",
+    tag = "transactions",
+    params(
+        ("account_id" = String, Path, description = "Account identifier"),
+        BalanceHistoryParams
+    ),
+    responses(
+        (status = 200, description = "Daily balances", body = BalanceHistoryResponse),
+        (status = 400, description = "Invalid range", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Account not found", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
+    )
+)]
+pub async fn get_account_balance_history(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<ClientAuth>,
+    Path(account_id): Path<String>,
+    params: Result<Query<BalanceHistoryParams>, QueryRejection>,
+) -> Result<Json<BalanceHistoryResponse>, AppError> {
+    let Query(params) = params?;
+    let period = params
+        .period(chrono::Utc::now().date_naive())
+        .map_err(AppError::ValidationError)?;
+
+    let account_exists = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM accounts WHERE account_id = $1"
     )
     .bind(&account_id)
     .fetch_one(&state.pool)
     .await?;
 
-    let balance_amount = balance.0.unwrap_or(0.0);
-    let currency = balance.1.unwrap_or_else(|| "USD".to_string());
+    if account_exists == 0 {
+        return Err(AppError::account_not_found(&account_id));
+    }
+
+    let history = BalanceService::history(&state.pool, &account_id, period).await?;
 
-    Ok(Json(BalanceResponse {
-        account_id,
-        balance: balance_amount,
-        currency: currency.parse().unwrap_or_default(),
-        as_of: chrono::Utc::now(),
-    }))
-}
\ No newline at end of file
+    Ok(Json(history))
+}
@@ -1,5 +1,7 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
+    http::header,
+    response::{IntoResponse, Response},
     Extension, Json,
 };
 use std::sync::Arc;
@@ -10,7 +12,11 @@ use crate::{
     models::{
         common::ErrorResponse,
         keys::AuthContext,
-        quota::QuotaService,
+        quota::{
+            BillingCycleSettings, QuotaAlertSettings, QuotaService, QuotaThresholdEvent, UsageHistoryFormat,
+            UsageHistoryParams, UsageHistoryResponse,
+        },
+        requests::{UpdateBillingCycleRequest, UpdateQuotaAlertsRequest},
         responses::UsageResponse,
     },
 };
@@ -36,7 +42,26 @@ use crate::{
                     "today": 1234,
                     "this_month": 45678,
                     "daily_remaining": 8766,
-                    "monthly_remaining": 254322
+                    "monthly_remaining": 254322,
+                    "timezone": "America/New_York",
+                    "day_start": "2024-01-15T05:00:00Z",
+                    "day_end": "2024-01-16T05:00:00Z",
+                    "period_start": "2024-01-01T05:00:00Z",
+                    "period_end": "2024-02-01T05:00:00Z"
+                },
+                "alerts": {
+                    "soft_thresholds": [80, 100],
+                    "grace_overage_percent": 10,
+                    "daily_overage": 0,
+                    "monthly_overage": 0,
+                    "thresholds": [
+                        {
+                            "period": "daily",
+                            "threshold_percent": 80,
+                            "reached": false,
+                            "reached_at": null
+                        }
+                    ]
                 }
             })
         ),
@@ -71,6 +96,7 @@ pub async fn get_own_usage(
         key_id: status.key_id,
         limits: status.limits,
         usage: status.usage,
+        alerts: status.alerts,
     }))
 }
 
@@ -121,5 +147,276 @@ pub async fn get_key_usage(
         key_id: status.key_id,
         limits: status.limits,
         usage: status.usage,
+        alerts: status.alerts,
     }))
-}
\ No newline at end of file
+}
+/// Update quota alert settings for an API key
+///
+/// Configures the soft thresholds (percentages of the daily and monthly quota) that
+/// emit an alert once per period, and the grace overage allowed past the hard limit.
+/// This endpoint is only accessible with admin authentication.
+#[utoipa::path(
+    patch,
+    path = "/api/admin/usage/{key_id}/alerts",
+    tag = "usage",
+    params(
+        ("key_id" = String, Path, description = "API key identifier")
+    ),
+    request_body = UpdateQuotaAlertsRequest,
+    responses(
+        (status = 200, description = "Quota alert settings updated successfully", body = QuotaAlertSettings),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "API key not found", body = ErrorResponse),
+    ),
+    security(
+        ("AdminKeyAuth" = [])
+    )
+)]
+pub async fn update_quota_alerts(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<AdminAuth>,
+    Path(key_id): Path<String>,
+    Json(req): Json<UpdateQuotaAlertsRequest>,
+) -> Result<Json<QuotaAlertSettings>, AppError> {
+    req.validate()
+        .map_err(AppError::ValidationError)?;
+
+    let exists = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM api_keys WHERE key_id = $1"
+    )
+    .bind(&key_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    if exists == 0 {
+        return Err(AppError::not_found("API Key", &key_id));
+    }
+
+    let current = QuotaService::get_alert_settings(&state.pool, &key_id).await?;
+    let settings = req.apply(current)
+        .map_err(AppError::ValidationError)?;
+
+    let updated = QuotaService::update_alert_settings(&state.pool, &key_id, &settings).await?;
+
+    Ok(Json(updated))
+}
+
+/// Update the quota billing cycle for an API key
+///
+/// Sets the IANA timezone used for daily quota resets and the day of the month on
+/// which the monthly quota period starts.
+/// This endpoint is only accessible with admin authentication.
+#[utoipa::path(
+    patch,
+    path = "/api/admin/usage/{key_id}/billing-cycle",
+    tag = "usage",
+    params(
+        ("key_id" = String, Path, description = "API key identifier")
+    ),
+    request_body = UpdateBillingCycleRequest,
+    responses(
+        (status = 200, description = "Billing cycle updated successfully", body = BillingCycleSettings),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "API key not found", body = ErrorResponse),
+    ),
+    security(
+        ("AdminKeyAuth" = [])
+    )
+)]
+pub async fn update_billing_cycle(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<AdminAuth>,
+    Path(key_id): Path<String>,
+    Json(req): Json<UpdateBillingCycleRequest>,
+) -> Result<Json<BillingCycleSettings>, AppError> {
+    req.validate()
+        .map_err(AppError::ValidationError)?;
+
+    let exists = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM api_keys WHERE key_id = $1"
+    )
+    .bind(&key_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    if exists == 0 {
+        return Err(AppError::not_found("API Key", &key_id));
+    }
+
+    let current = QuotaService::get_billing_cycle(&state.pool, &key_id).await?;
+    let settings = req.apply(current)
+        .map_err(AppError::ValidationError)?;
+
+    let updated = QuotaService::update_billing_cycle(&state.pool, &key_id, &settings).await?;
+
+    Ok(Json(updated))
+}
+
+/// List quota alert events for an API key
+///
+/// Returns the most recent soft threshold crossings recorded for the key, newest first.
+/// This endpoint is only accessible with admin authentication.
+#[utoipa::path(
+    get,
+    path = "/api/admin/usage/{key_id}/alerts",
+    tag = "usage",
+    params(
+        ("key_id" = String, Path, description = "API key identifier")
+    ),
+    responses(
+        (status = 200, description = "Quota alert events", body = Vec<QuotaThresholdEvent>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "API key not found", body = ErrorResponse),
+    ),
+    security(
+        ("AdminKeyAuth" = [])
+    )
+)]
+pub async fn list_quota_alert_events(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<AdminAuth>,
+    Path(key_id): Path<String>,
+) -> Result<Json<Vec<QuotaThresholdEvent>>, AppError> {
+    let exists = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM api_keys WHERE key_id = $1"
+    )
+    .bind(&key_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    if exists == 0 {
+        return Err(AppError::not_found("API Key", &key_id));
+    }
+
+    let events = QuotaService::list_threshold_events(&state.pool, &key_id, 100).await?;
+
+    Ok(Json(events))
+}
+
+/// Get own usage history
+///
+/// Returns a zero-filled daily or monthly series of request counts for the authenticated
+/// API key. Pass `format=csv` to download the series as CSV.
+#[utoipa::path(
+    get,
+    path = "/api/usage/history",
+    tag = "usage",
+    params(
+        UsageHistoryParams
+    ),
+    responses(
+        (status = 200, description = "Usage history retrieved successfully", content(
+            (UsageHistoryResponse = "application/json"),
+            (String = "text/csv")
+        )),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+    ),
+    security(
+        ("ApiKeyAuth" = [])
+    )
+)]
+pub async fn get_own_usage_history(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<ClientAuth>,
+    Query(params): Query<UsageHistoryParams>,
+) -> Result<Response, AppError> {
+    let key_id = match &auth.context {
+        AuthContext::Client { key_id, .. } => key_id,
+        AuthContext::Admin => {
+            return Err(AppError::InvalidInput(
+                "Admin keys do not have quota usage".to_string(),
+            ));
+        }
+    };
+
+    usage_history_response(&state, key_id, &params).await
+}
+
+/// Get usage history for a specific API key
+///
+/// Returns a zero-filled daily or monthly series of request counts for any API key.
+/// Pass `format=csv` to download the series as CSV.
+/// This endpoint is only accessible with admin authentication.
+#[utoipa::path(
+    get,
+    path = "/api/admin/usage/{key_id}/history",
+    tag = "usage",
+    params(
+        ("key_id" = String, Path, description = "API key identifier"),
+        UsageHistoryParams
+    ),
+    responses(
+        (status = 200, description = "Usage history retrieved successfully", content(
+            (UsageHistoryResponse = "application/json"),
+            (String = "text/csv")
+        )),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "API key not found", body = ErrorResponse),
+    ),
+    security(
+        ("AdminKeyAuth" = [])
+    )
+)]
+pub async fn get_key_usage_history(
+    State(state): State<Arc<AppState>>,
+    Extension(_auth): Extension<AdminAuth>,
+    Path(key_id): Path<String>,
+    Query(params): Query<UsageHistoryParams>,
+) -> Result<Response, AppError> {
+    let exists = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM api_keys WHERE key_id = $1"
+    )
+    .bind(&key_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    if exists == 0 {
+        return Err(AppError::not_found("API Key", &key_id));
+    }
+
+    usage_history_response(&state, &key_id, &params).await
+}
+
+async fn usage_history_response(
+    state: &Arc<AppState>,
+    key_id: &str,
+    params: &UsageHistoryParams,
+) -> Result<Response, AppError> {
+    let today = QuotaService::get_billing_cycle(&state.pool, key_id)
+        .await?
+        .local_date(chrono::Utc::now());
+
+    let (from, to, granularity) = params
+        .resolve(today)
+        .map_err(AppError::ValidationError)?;
+
+    let history = QuotaService::get_usage_history(&state.pool, key_id, from, to, granularity)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to get usage history: {}", e);
+            AppError::InternalError("Failed to retrieve usage data".to_string())
+        })?;
+
+    match params.format.unwrap_or_default() {
+        UsageHistoryFormat::Json => Ok(Json(history).into_response()),
+        UsageHistoryFormat::Csv => {
+            let filename = format!(
+                "attachment; filename=\"usage_{}_{}_{}.csv\"",
+                key_id, from, to
+            );
+            Ok((
+                [
+                    (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                    (header::CONTENT_DISPOSITION, filename),
+                ],
+                history.to_csv(),
+            )
+                .into_response())
+        }
+    }
+}
@@ -4,5 +4,6 @@ pub mod db;
 pub mod handlers;
 pub mod middleware;
 pub mod models;
+pub mod observability;
 pub mod openapi;
 pub mod routes;
\ No newline at end of file
@@ -1,6 +1,5 @@
 use anyhow::Result;
 use tracing::info;
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 mod app;
 mod config;
@@ -8,34 +7,46 @@ mod db;
 mod handlers;
 mod middleware;
 mod models;
+mod observability;
 mod openapi;
 mod routes;
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize tracing
-    tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "info,axum=info,tower_http=info".into()),
-        )
-        .with(tracing_subscriber::fmt::layer()
-            .with_target(true)
-            .with_thread_ids(true)
-            .with_line_number(true)
-        )
-        .init();
-
     // Load environment variables
     dotenvy::dotenv().ok();
 
-    info!("Starting Metered Finance API Server");
-
     // Load configuration
     let config = config::load_config()?;
+
+    // Initialize tracing
+    let telemetry = observability::telemetry::init_telemetry(&config)?;
+
+    info!("Starting Metered Finance API Server");
+    if config.otel_enabled {
+        info!("Exporting traces to {}", config.otel_exporter_endpoint);
+    }
+
     let port = config.port;
+    let metrics_port = config.metrics_port;
+
+    let state = app::build_state(config).await?;
 
-    let app = app::build_router(config).await?;
+    if let Some(metrics_port) = metrics_port {
+        let metrics_addr = std::net::SocketAddr::from(([0, 0, 0, 0], metrics_port));
+        let metrics_listener = tokio::net::TcpListener::bind(metrics_addr).await?;
+        let metrics_app = app::build_metrics_router(state.clone());
+
+        info!("Metrics available at http://{}/metrics", metrics_addr);
+
+        tokio::spawn(async move {
+            if let Err(e) = axum::serve(metrics_listener, metrics_app).await {
+                tracing::error!("Metrics server error: {}", e);
+            }
+        });
+    }
+
+    let app = app::build_router(state.clone())?;
 
     let addr = std::net::SocketAddr::from(([0, 0, 0, 0], port));
     let listener = tokio::net::TcpListener::bind(addr).await?;
@@ -45,7 +56,47 @@ async fn main() -> Result<()> {
     info!("Health check available at http://{}/health/live", addr);
     info!("API endpoints available at http://{}/api/*", addr);
 
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown_signal())
+        .await?;
+
+    info!("Flushing request logs");
+    state.request_logger.shutdown().await;
+
+    telemetry.shutdown();
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Resolves on Ctrl+C or SIGTERM so in-flight requests can finish.
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!("Failed to listen for Ctrl+C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                tracing::error!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+
+    info!("Shutdown signal received");
+}
@@ -9,14 +9,21 @@ use std::sync::Arc;
 use crate::{
     app::AppState,
     models::keys::{ApiKeyGenerator, AuthContext, Scope},
+    observability::{metrics::record_auth_failure, telemetry::record_key_id},
 };
 
+fn auth_failure(reason: &'static str, status: StatusCode, message: &str) -> (StatusCode, String) {
+    record_auth_failure(reason);
+    (status, message.to_string())
+}
+
 #[derive(Debug, Clone)]
 pub struct ClientAuth {
     pub context: AuthContext,
 }
 
 impl ClientAuth {
+    #[tracing::instrument(name = "auth.client", skip_all, fields(key_id = tracing::field::Empty))]
     pub async fn from_request(
         state: &Arc<AppState>,
         headers: &axum::http::HeaderMap,
@@ -24,15 +31,13 @@ impl ClientAuth {
         let api_key = headers
             .get("X-Api-Key")
             .and_then(|h| h.to_str().ok())
-            .ok_or((
-                StatusCode::UNAUTHORIZED,
-                "Missing X-Api-Key header".to_string(),
-            ))?;
+            .ok_or_else(|| {
+                auth_failure("missing_key", StatusCode::UNAUTHORIZED, "Missing X-Api-Key header")
+            })?;
 
-        let prefix = ApiKeyGenerator::extract_prefix(api_key).ok_or((
-            StatusCode::UNAUTHORIZED,
-            "Invalid API key format".to_string(),
-        ))?;
+        let prefix = ApiKeyGenerator::extract_prefix(api_key).ok_or_else(|| {
+            auth_failure("invalid_format", StatusCode::UNAUTHORIZED, "Invalid API key format")
+        })?;
 
         let result = sqlx::query_as::<_, (String, String, Vec<String>, bool)>(
             r#"
@@ -46,28 +51,32 @@ impl ClientAuth {
         .await
         .map_err(|e| {
             tracing::error!("Database error during API key lookup: {}", e);
-            (
+            auth_failure(
+                "internal_error",
                 StatusCode::INTERNAL_SERVER_ERROR,
-                "Authentication failed".to_string(),
+                "Authentication failed",
             )
         })?;
 
-        let (key_id, secret_hash, scopes_raw, active) = result.ok_or((
-            StatusCode::UNAUTHORIZED,
-            "Invalid API key".to_string(),
-        ))?;
+        let (key_id, secret_hash, scopes_raw, active) = result.ok_or_else(|| {
+            auth_failure("unknown_key", StatusCode::UNAUTHORIZED, "Invalid API key")
+        })?;
+
+        tracing::Span::current().record("key_id", key_id.as_str());
 
         if !ApiKeyGenerator::verify_secret(api_key, &secret_hash) {
-            return Err((
+            return Err(auth_failure(
+                "invalid_secret",
                 StatusCode::UNAUTHORIZED,
-                "Invalid API key".to_string(),
+                "Invalid API key",
             ));
         }
 
         if !active {
-            return Err((
+            return Err(auth_failure(
+                "inactive",
                 StatusCode::UNAUTHORIZED,
-                "API key is inactive".to_string(),
+                "API key is inactive",
             ));
         }
 
@@ -102,27 +111,33 @@ pub struct AdminAuth {
 }
 
 impl AdminAuth {
+    #[tracing::instrument(name = "auth.admin", skip_all)]
     pub fn from_request(headers: &axum::http::HeaderMap) -> Result<Self, (StatusCode, String)> {
         let admin_key = headers
             .get("X-Admin-Key")
             .and_then(|h| h.to_str().ok())
-            .ok_or((
-                StatusCode::UNAUTHORIZED,
-                "Missing X-Admin-Key header".to_string(),
-            ))?;
+            .ok_or_else(|| {
+                auth_failure(
+                    "missing_admin_key",
+                    StatusCode::UNAUTHORIZED,
+                    "Missing X-Admin-Key header",
+                )
+            })?;
 
         let expected_admin_key = std::env::var("ADMIN_KEY").map_err(|_| {
             tracing::error!("ADMIN_KEY not configured");
-            (
+            auth_failure(
+                "admin_key_not_configured",
                 StatusCode::INTERNAL_SERVER_ERROR,
-                "Server configuration error".to_string(),
+                "Server configuration error",
             )
         })?;
 
         if admin_key != expected_admin_key {
-            return Err((
+            return Err(auth_failure(
+                "invalid_admin_key",
                 StatusCode::UNAUTHORIZED,
-                "Invalid admin key".to_string(),
+                "Invalid admin key",
             ));
         }
 
@@ -161,6 +176,9 @@ pub async fn require_client_auth(
     next: Next,
 ) -> Result<Response, (StatusCode, String)> {
     let auth = ClientAuth::from_request(&state, req.headers()).await?;
+    if let AuthContext::Client { key_id, .. } = &auth.context {
+        record_key_id(key_id);
+    }
     req.extensions_mut().insert(auth);
     Ok(next.run(req).await)
 }
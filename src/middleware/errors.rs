@@ -14,9 +14,21 @@ pub enum AppError {
     InvalidApiKey,
     
     ValidationError(String),
+    /// Metadata broke a limit or the caller's schema; details carry the paths.
+    InvalidMetadata(crate::models::metadata::InvalidMetadata),
     InvalidInput(String),
     
     NotFound(String),
+    Conflict(String),
+    
+    AccountFrozen(String),
+    AccountClosed(String),
+    InvalidStatusTransition(String),
+    NonZeroBalance { message: String, balance: f64, currency: String },
+    /// The transaction broke an account limit and was recorded as failed.
+    LimitExceeded { message: String, transaction_id: String, failure_reason: String, limit: f64 },
+    /// Fraud screening blocked the transaction and it was recorded as failed.
+    TransactionBlocked { transaction_id: String },
     
     RateLimitExceeded,
     QuotaExceeded,
@@ -54,6 +66,12 @@ impl IntoResponse for AppError {
                 msg,
                 None,
             ),
+            AppError::InvalidMetadata(error) => (
+                StatusCode::BAD_REQUEST,
+                ErrorCode::ValidationError,
+                error.to_string(),
+                Some(error.details()),
+            ),
             AppError::InvalidInput(msg) => (
                 StatusCode::BAD_REQUEST,
                 ErrorCode::InvalidInput,
@@ -67,6 +85,59 @@ impl IntoResponse for AppError {
                 msg,
                 None,
             ),
+            AppError::Conflict(msg) => (
+                StatusCode::CONFLICT,
+                ErrorCode::AlreadyExists,
+                msg,
+                None,
+            ),
+            
+            AppError::AccountFrozen(msg) => (
+                StatusCode::CONFLICT,
+                ErrorCode::AccountFrozen,
+                msg,
+                None,
+            ),
+            AppError::AccountClosed(msg) => (
+                StatusCode::CONFLICT,
+                ErrorCode::AccountClosed,
+                msg,
+                None,
+            ),
+            AppError::InvalidStatusTransition(msg) => (
+                StatusCode::CONFLICT,
+                ErrorCode::InvalidStatusTransition,
+                msg,
+                None,
+            ),
+            AppError::NonZeroBalance { message, balance, currency } => (
+                StatusCode::CONFLICT,
+                ErrorCode::NonZeroBalance,
+                message,
+                Some(json!({
+                    "balance": balance,
+                    "currency": currency
+                })),
+            ),
+            AppError::LimitExceeded { message, transaction_id, failure_reason, limit } => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                ErrorCode::LimitExceeded,
+                message,
+                Some(json!({
+                    "transaction_id": transaction_id,
+                    "failure_reason": failure_reason,
+                    "limit": limit
+                })),
+            ),
+            AppError::TransactionBlocked { transaction_id } => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                ErrorCode::TransactionBlocked,
+                "Transaction was declined by fraud screening".to_string(),
+                Some(json!({
+                    "transaction_id": transaction_id,
+                    "failure_reason": crate::models::finance::FailureReason::Fraud
+                })),
+            ),
             
             AppError::RateLimitExceeded => (
                 StatusCode::TOO_MANY_REQUESTS,
@@ -112,7 +183,10 @@ impl IntoResponse for AppError {
             },
         };
 
-        (status, Json(error_response)).into_response()
+        let mut response = (status, Json(error_response)).into_response();
+        // Lets the request logger record the code without parsing the body.
+        response.extensions_mut().insert(error_code);
+        response
     }
 }
 
@@ -122,6 +196,186 @@ impl From<sqlx::Error> for AppError {
     }
 }
 
+impl From<axum::extract::rejection::QueryRejection> for AppError {
+    fn from(rejection: axum::extract::rejection::QueryRejection) -> Self {
+        AppError::ValidationError(rejection.body_text())
+    }
+}
+
+impl From<crate::models::pagination::CursorError> for AppError {
+    fn from(error: crate::models::pagination::CursorError) -> Self {
+        AppError::InvalidInput(format!("Invalid cursor: {}", error))
+    }
+}
+
+impl From<crate::models::batch::BatchError> for AppError {
+    fn from(error: crate::models::batch::BatchError) -> Self {
+        AppError::ValidationError(error.to_string())
+    }
+}
+
+impl From<crate::models::reconciliation::StatementError> for AppError {
+    fn from(error: crate::models::reconciliation::StatementError) -> Self {
+        AppError::ValidationError(error.to_string())
+    }
+}
+
+impl From<crate::models::lifecycle::LifecycleError> for AppError {
+    fn from(error: crate::models::lifecycle::LifecycleError) -> Self {
+        use crate::models::lifecycle::LifecycleError;
+
+        let message = error.to_string();
+        match error {
+            LifecycleError::AccountNotFound(_) => AppError::NotFound(message),
+            LifecycleError::InvalidTransition { .. } => AppError::InvalidStatusTransition(message),
+            LifecycleError::AdminOnly(_) => AppError::Forbidden(message),
+            LifecycleError::NonZeroBalance { balance, currency } => AppError::NonZeroBalance {
+                message,
+                balance,
+                currency: currency.to_string(),
+            },
+            LifecycleError::Database(e) => AppError::DatabaseError(e),
+        }
+    }
+}
+
+impl From<crate::models::fraud::FraudReviewError> for AppError {
+    fn from(error: crate::models::fraud::FraudReviewError) -> Self {
+        use crate::models::fraud::FraudReviewError;
+
+        let message = error.to_string();
+        match error {
+            FraudReviewError::NotFound(_) => AppError::NotFound(message),
+            FraudReviewError::AlreadyResolved(_) => AppError::Conflict(message),
+            FraudReviewError::AccountUnavailable { status, .. } => match status {
+                crate::models::lifecycle::AccountStatus::Closed => AppError::AccountClosed(message),
+                _ => AppError::AccountFrozen(message),
+            },
+            FraudReviewError::Database(e) => AppError::DatabaseError(e),
+        }
+    }
+}
+
+impl From<crate::models::posting::PostingError> for AppError {
+    fn from(error: crate::models::posting::PostingError) -> Self {
+        use crate::models::posting::PostingError;
+
+        match error {
+            PostingError::AccountNotFound(account_id) => AppError::account_not_found(&account_id),
+            PostingError::AccountUnavailable { account_id, status } => {
+                AppError::account_unavailable(&account_id, status)
+            }
+            PostingError::LimitExceeded { transaction_id, violation } => {
+                AppError::limit_exceeded(&transaction_id, &violation)
+            }
+            PostingError::Blocked { transaction_id } => AppError::TransactionBlocked { transaction_id },
+            PostingError::Database(e) => AppError::DatabaseError(e),
+        }
+    }
+}
+
+impl From<crate::models::schedules::ScheduleError> for AppError {
+    fn from(error: crate::models::schedules::ScheduleError) -> Self {
+        use crate::models::schedules::ScheduleError;
+
+        let message = error.to_string();
+        match error {
+            ScheduleError::NotFound(_) => AppError::NotFound(message),
+            ScheduleError::InvalidTransition { .. } => AppError::InvalidStatusTransition(message),
+            ScheduleError::Database(e) => AppError::DatabaseError(e),
+        }
+    }
+}
+
+impl From<crate::models::disputes::DisputeError> for AppError {
+    fn from(error: crate::models::disputes::DisputeError) -> Self {
+        use crate::models::disputes::DisputeError;
+
+        let message = error.to_string();
+        match error {
+            DisputeError::NotFound(_)
+            | DisputeError::EvidenceNotFound(_)
+            | DisputeError::TransactionNotFound(_) => AppError::NotFound(message),
+            DisputeError::NotDisputable(_)
+            | DisputeError::AmountExceedsPayment { .. }
+            | DisputeError::InvalidEvidence(_) => AppError::ValidationError(message),
+            DisputeError::Reversed { .. } => AppError::Conflict(message),
+            DisputeError::InvalidTransition { .. }
+            | DisputeError::NotAcceptingEvidence(_)
+            | DisputeError::DeadlinePassed(_)
+            | DisputeError::NoEvidence => AppError::InvalidStatusTransition(message),
+            DisputeError::AccountUnavailable { .. } => AppError::AccountClosed(message),
+            DisputeError::Storage(e) => AppError::InternalError(format!("Evidence storage error: {}", e)),
+            DisputeError::Database(e) => AppError::DatabaseError(e),
+        }
+    }
+}
+
+impl From<crate::models::payouts::PayoutError> for AppError {
+    fn from(error: crate::models::payouts::PayoutError) -> Self {
+        use crate::models::payouts::PayoutError;
+
+        let message = error.to_string();
+        match error {
+            PayoutError::DestinationNotFound(_)
+            | PayoutError::NotFound(_)
+            | PayoutError::BatchNotFound(_) => AppError::NotFound(message),
+            PayoutError::DestinationDisabled(_) | PayoutError::InvalidFile(_) => {
+                AppError::ValidationError(message)
+            }
+            PayoutError::NoPendingPayouts(_) => AppError::Conflict(message),
+            PayoutError::InvalidBatchTransition { .. } | PayoutError::NotReturnable { .. } => {
+                AppError::InvalidStatusTransition(message)
+            }
+            PayoutError::AccountUnavailable { .. } => AppError::AccountClosed(message),
+            PayoutError::Posting(e) => e.into(),
+            PayoutError::NotConfigured(_) | PayoutError::Crypto(_) => AppError::InternalError(message),
+            PayoutError::Storage(e) => AppError::InternalError(format!("Payout file storage error: {}", e)),
+            PayoutError::Database(e) => AppError::DatabaseError(e),
+        }
+    }
+}
+
+impl From<crate::models::fees::FeeError> for AppError {
+    fn from(error: crate::models::fees::FeeError) -> Self {
+        use crate::models::fees::FeeError;
+
+        let message = error.to_string();
+        match error {
+            FeeError::NotFound(_) => AppError::NotFound(message),
+            FeeError::PlanTaken(_) => AppError::Conflict(message),
+            FeeError::Database(e) => AppError::DatabaseError(e),
+        }
+    }
+}
+
+impl From<crate::models::reversals::ReversalError> for AppError {
+    fn from(error: crate::models::reversals::ReversalError) -> Self {
+        use crate::models::reversals::ReversalError;
+
+        let message = error.to_string();
+        match error {
+            ReversalError::NotFound(transaction_id) => AppError::transaction_not_found(&transaction_id),
+            ReversalError::NotCompleted { .. } => AppError::InvalidStatusTransition(message),
+            ReversalError::NotReversible { .. }
+            | ReversalError::AlreadyReversed { .. }
+            | ReversalError::Disputed { .. } => {
+                AppError::Conflict(message)
+            }
+            ReversalError::AccountUnavailable { account_id, status } => {
+                AppError::account_unavailable(&account_id, status)
+            }
+            ReversalError::Database(e) => AppError::DatabaseError(e),
+        }
+    }
+}
+
+impl From<crate::models::metadata::InvalidMetadata> for AppError {
+    fn from(error: crate::models::metadata::InvalidMetadata) -> Self {
+        AppError::InvalidMetadata(error)
+    }
+}
+
 impl From<crate::models::common::ValidationError> for AppError {
     fn from(error: crate::models::common::ValidationError) -> Self {
         AppError::ValidationError(error.to_string())
@@ -152,6 +406,27 @@ impl AppError {
     pub fn transaction_not_found(txn_id: &str) -> Self {
         AppError::NotFound(format!("Transaction '{}' not found", txn_id))
     }
+    
+    /// Rejection for transactions on an account that is not active.
+    pub fn account_unavailable(account_id: &str, status: crate::models::lifecycle::AccountStatus) -> Self {
+        use crate::models::lifecycle::{unavailable_message, AccountStatus};
+
+        let message = unavailable_message(account_id, status);
+        match status {
+            AccountStatus::Closed => AppError::AccountClosed(message),
+            _ => AppError::AccountFrozen(message),
+        }
+    }
+
+    /// Rejection for a transaction recorded as failed because it broke a limit.
+    pub fn limit_exceeded(transaction_id: &str, violation: &crate::models::limits::LimitViolation) -> Self {
+        AppError::LimitExceeded {
+            message: violation.to_string(),
+            transaction_id: transaction_id.to_string(),
+            failure_reason: violation.reason.to_string(),
+            limit: violation.limit,
+        }
+    }
 }
 
 impl std::fmt::Display for AppError {
@@ -161,8 +436,18 @@ impl std::fmt::Display for AppError {
             AppError::Forbidden(msg) => write!(f, "Forbidden: {}", msg),
             AppError::InvalidApiKey => write!(f, "Invalid API key"),
             AppError::ValidationError(msg) => write!(f, "Validation error: {}", msg),
+            AppError::InvalidMetadata(error) => write!(f, "Validation error: {}", error),
             AppError::InvalidInput(msg) => write!(f, "Invalid input: {}", msg),
             AppError::NotFound(msg) => write!(f, "Not found: {}", msg),
+            AppError::Conflict(msg) => write!(f, "Conflict: {}", msg),
+            AppError::AccountFrozen(msg) => write!(f, "Account frozen: {}", msg),
+            AppError::AccountClosed(msg) => write!(f, "Account closed: {}", msg),
+            AppError::InvalidStatusTransition(msg) => write!(f, "Invalid status transition: {}", msg),
+            AppError::NonZeroBalance { message, .. } => write!(f, "Non-zero balance: {}", message),
+            AppError::LimitExceeded { message, .. } => write!(f, "Limit exceeded: {}", message),
+            AppError::TransactionBlocked { transaction_id } => {
+                write!(f, "Transaction blocked: {}", transaction_id)
+            }
             AppError::RateLimitExceeded => write!(f, "Rate limit exceeded"),
             AppError::QuotaExceeded => write!(f, "Quota exceeded"),
             AppError::InternalError(msg) => write!(f, "Internal error: {}", msg),
@@ -10,6 +10,7 @@ use crate::{
     app::AppState,
     middleware::auth::ClientAuth,
     models::quota::{QuotaService, RateLimitService},
+    observability::metrics::{record_quota_rejection, record_quota_usage, record_rate_limit_rejection},
 };
 
 pub async fn check_rate_limit_and_quota(
@@ -32,10 +33,42 @@ pub async fn check_rate_limit_and_quota(
         }
     };
 
-    let limits = QuotaService::get_limits(&state.pool, key_id)
+    enforce_rate_limit_and_quota(&state, key_id).await?;
+
+    Ok(next.run(req).await)
+}
+
+pub async fn check_rate_limit_only(
+    State(state): State<Arc<AppState>>,
+    req: Request<axum::body::Body>,
+    next: Next,
+) -> Result<Response, (StatusCode, String)> {
+    let auth = req
+        .extensions()
+        .get::<ClientAuth>()
+        .ok_or((
+            StatusCode::UNAUTHORIZED,
+            "Authentication required".to_string(),
+        ))?;
+
+    let key_id = match &auth.context {
+        crate::models::keys::AuthContext::Client { key_id, .. } => key_id,
+        crate::models::keys::AuthContext::Admin => {
+            return Ok(next.run(req).await);
+        }
+    };
+
+    enforce_rate_limit(&state, key_id).await?;
+
+    Ok(next.run(req).await)
+}
+
+#[tracing::instrument(name = "quota.check", skip(state))]
+async fn enforce_rate_limit_and_quota(state: &AppState, key_id: &str) -> Result<(), (StatusCode, String)> {
+    let quota = QuotaService::load_state(&state.pool, key_id)
         .await
         .map_err(|e| {
-            tracing::error!("Failed to get quota limits: {}", e);
+            tracing::error!("Failed to load quota state: {}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 "Failed to check rate limit".to_string(),
@@ -45,7 +78,7 @@ pub async fn check_rate_limit_and_quota(
     let within_rate_limit = RateLimitService::check_rate_limit(
         &state.pool,
         key_id,
-        limits.rate_limit_per_minute,
+        quota.limits.rate_limit_per_minute,
     )
     .await
     .map_err(|e| {
@@ -57,76 +90,50 @@ pub async fn check_rate_limit_and_quota(
     })?;
 
     if !within_rate_limit {
+        record_rate_limit_rejection(key_id);
         return Err((
             StatusCode::TOO_MANY_REQUESTS,
-            format!("Rate limit exceeded. Limit: {} requests per minute", limits.rate_limit_per_minute),
+            format!("Rate limit exceeded. Limit: {} requests per minute", quota.limits.rate_limit_per_minute),
         ));
     }
 
-    let within_daily_quota = QuotaService::check_daily_quota(&state.pool, key_id)
-        .await
-        .map_err(|e| {
-            tracing::error!("Failed to check daily quota: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Failed to check quota".to_string(),
-            )
-        })?;
-
-    if !within_daily_quota {
+    if !quota.within_daily_quota() {
+        record_quota_rejection(key_id, "daily");
         return Err((
             StatusCode::TOO_MANY_REQUESTS,
             "Daily quota exceeded".to_string(),
         ));
     }
 
-    let within_monthly_quota = QuotaService::check_monthly_quota(&state.pool, key_id)
-        .await
-        .map_err(|e| {
-            tracing::error!("Failed to check monthly quota: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Failed to check quota".to_string(),
-            )
-        })?;
-
-    if !within_monthly_quota {
+    if !quota.within_monthly_quota() {
+        record_quota_rejection(key_id, "monthly");
         return Err((
             StatusCode::TOO_MANY_REQUESTS,
             "Monthly quota exceeded".to_string(),
         ));
     }
 
-    QuotaService::increment_usage(&state.pool, key_id)
+    let today_count = match QuotaService::increment_usage(&state.pool, key_id, quota.windows.today).await {
+        Ok(count) => count,
+        Err(e) => {
+            tracing::error!("Failed to increment quota: {}", e);
+            return Ok(());
+        }
+    };
+    record_quota_usage(key_id);
+
+    QuotaService::record_threshold_crossings(&state.pool, key_id, &quota, today_count)
         .await
         .map_err(|e| {
-            tracing::error!("Failed to increment quota: {}", e);
+            tracing::error!("Failed to record quota thresholds: {}", e);
         })
         .ok();
 
-    Ok(next.run(req).await)
+    Ok(())
 }
 
-pub async fn check_rate_limit_only(
-    State(state): State<Arc<AppState>>,
-    req: Request<axum::body::Body>,
-    next: Next,
-) -> Result<Response, (StatusCode, String)> {
-    let auth = req
-        .extensions()
-        .get::<ClientAuth>()
-        .ok_or((
-            StatusCode::UNAUTHORIZED,
-            "Authentication required".to_string(),
-        ))?;
-
-    let key_id = match &auth.context {
-        crate::models::keys::AuthContext::Client { key_id, .. } => key_id,
-        crate::models::keys::AuthContext::Admin => {
-            return Ok(next.run(req).await);
-        }
-    };
-
+#[tracing::instrument(name = "rate_limit.check", skip(state))]
+async fn enforce_rate_limit(state: &AppState, key_id: &str) -> Result<(), (StatusCode, String)> {
     let limits = QuotaService::get_limits(&state.pool, key_id)
         .await
         .map_err(|e| {
@@ -152,11 +159,12 @@ pub async fn check_rate_limit_only(
     })?;
 
     if !within_rate_limit {
+        record_rate_limit_rejection(key_id);
         return Err((
             StatusCode::TOO_MANY_REQUESTS,
             format!("Rate limit exceeded. Limit: {} requests per minute", limits.rate_limit_per_minute),
         ));
     }
 
-    Ok(next.run(req).await)
-}
\ No newline at end of file
+    Ok(())
+}
@@ -3,13 +3,15 @@ use axum::{
     middleware::Next,
     response::Response,
 };
+use chrono::Utc;
 use std::sync::Arc;
 use std::time::Instant;
 
 use crate::{
     app::AppState,
     middleware::auth::ClientAuth,
-    models::keys::AuthContext,
+    models::{common::ErrorCode, keys::AuthContext},
+    observability::request_log::RequestLogEntry,
 };
 
 pub async fn log_request(
@@ -18,6 +20,7 @@ pub async fn log_request(
     next: Next,
 ) -> Response {
     let start = Instant::now();
+    let received_at = Utc::now();
     
     let method = request.method().to_string();
     let path = request.uri().path().to_string();
@@ -33,57 +36,31 @@ pub async fn log_request(
     
     let latency_ms = start.elapsed().as_millis() as i32;
     let status = response.status().as_u16() as i32;
+    let error_code = if response.status().is_client_error() || response.status().is_server_error() {
+        response
+            .extensions()
+            .get::<ErrorCode>()
+            .copied()
+            .or_else(|| ErrorCode::from_status(response.status()))
+            .map(|code| code.to_string())
+    } else {
+        None
+    };
     
-    let pool = state.pool.clone();
-    let path_clone = path.clone();
-    let method_clone = method.clone();
-    
-    tokio::spawn(async move {
-        if let Err(e) = log_request_to_db(
-            &pool,
-            key_id.as_deref(),
-            None,
-            &path_clone,
-            &method_clone,
-            status,
-            latency_ms,
-        )
-        .await
-        {
-            tracing::error!("Failed to log request: {}", e);
-        }
+    state.request_logger.log(RequestLogEntry {
+        key_id,
+        account_id: None,
+        path,
+        method,
+        status,
+        latency_ms,
+        error_code,
+        ts: received_at,
     });
     
     response
 }
 
-async fn log_request_to_db(
-    pool: &sqlx::PgPool,
-    key_id: Option<&str>,
-    account_id: Option<&str>,
-    path: &str,
-    method: &str,
-    status: i32,
-    latency_ms: i32,
-) -> Result<(), sqlx::Error> {
-    sqlx::query(
-        r#"
-        INSERT INTO requests (key_id, account_id, path, method, status, latency_ms, ts, created_at)
-        VALUES ($1, $2, $3, $4, $5, $6, NOW(), NOW())
-        "#
-    )
-    .bind(key_id)
-    .bind(account_id)
-    .bind(path)
-    .bind(method)
-    .bind(status)
-    .bind(latency_ms)
-    .execute(pool)
-    .await?;
-    
-    Ok(())
-}
-
 pub async fn extract_account_context(
     request: Request,
     next: Next,
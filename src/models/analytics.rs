@@ -2,6 +2,8 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
+use crate::models::rollups::{AnalyticsSource, RollupAnalytics};
+
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct RequestStats {
     pub total_requests: i64,
@@ -35,6 +37,12 @@ pub struct EndpointStats {
     
     pub avg_latency_ms: f64,
     
+    pub median_latency_ms: Option<f64>,
+    
+    pub p95_latency_ms: Option<f64>,
+    
+    pub p99_latency_ms: Option<f64>,
+    
     pub error_rate: f64,
 }
 
@@ -57,6 +65,32 @@ pub struct HourlyVolume {
     pub avg_latency_ms: f64,
 }
 
+/// Errors grouped by the `code` returned in the error body. Requests logged
+/// before error codes were recorded are reported as `unknown`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ErrorCodeStats {
+    pub error_code: String,
+    
+    pub count: i64,
+    
+    /// Share of all failed requests in the range.
+    pub percentage: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct VolumeBucket {
+    #[schema(value_type = String, format = DateTime)]
+    pub bucket_start: DateTime<Utc>,
+    
+    pub request_count: i64,
+    
+    pub error_count: i64,
+    
+    pub avg_latency_ms: f64,
+    
+    pub p95_latency_ms: Option<f64>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct AnalyticsResponse {
     pub overview: RequestStats,
@@ -65,8 +99,21 @@ pub struct AnalyticsResponse {
     
     pub status_codes: Vec<StatusCodeStats>,
     
+    pub error_breakdown: Vec<ErrorCodeStats>,
+    
     #[serde(skip_serializing_if = "Option::is_none")]
     pub hourly_volume: Option<Vec<HourlyVolume>>,
+    
+    /// Volume series at the requested `bucket`; only present when `bucket` is set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bucket: Option<TimeBucket>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub volume: Option<Vec<VolumeBucket>>,
+
+    /// Whether figures come from raw requests or from minute/hour rollups.
+    /// Rollup percentiles are estimated from latency histograms.
+    pub source: AnalyticsSource,
 }
 
 #[derive(Debug, Clone, Deserialize, utoipa::IntoParams, ToSchema)]
@@ -91,136 +138,357 @@ impl Default for TimeRangeFilter {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeBucket {
+    Minute,
+    #[default]
+    Hour,
+    Day,
+}
+
+impl std::fmt::Display for TimeBucket {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimeBucket::Minute => write!(f, "minute"),
+            TimeBucket::Hour => write!(f, "hour"),
+            TimeBucket::Day => write!(f, "day"),
+        }
+    }
+}
+
+impl std::str::FromStr for TimeBucket {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "minute" => Ok(TimeBucket::Minute),
+            "hour" => Ok(TimeBucket::Hour),
+            "day" => Ok(TimeBucket::Day),
+            _ => Err(format!("Invalid time bucket: {}", s)),
+        }
+    }
+}
+
+impl TimeBucket {
+    pub const MAX_BUCKETS: i64 = 1500;
+
+    pub fn duration(&self) -> chrono::Duration {
+        match self {
+            TimeBucket::Minute => chrono::Duration::minutes(1),
+            TimeBucket::Hour => chrono::Duration::hours(1),
+            TimeBucket::Day => chrono::Duration::days(1),
+        }
+    }
+
+    /// Number of buckets needed to cover `[start, end]`.
+    pub fn bucket_count(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> i64 {
+        let span = end.signed_duration_since(start).num_seconds().max(0);
+        span / self.duration().num_seconds() + 1
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum StatusClass {
+    #[serde(rename = "2xx")]
+    Success,
+    #[serde(rename = "3xx")]
+    Redirection,
+    #[serde(rename = "4xx")]
+    ClientError,
+    #[serde(rename = "5xx")]
+    ServerError,
+}
+
+impl std::fmt::Display for StatusClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StatusClass::Success => write!(f, "2xx"),
+            StatusClass::Redirection => write!(f, "3xx"),
+            StatusClass::ClientError => write!(f, "4xx"),
+            StatusClass::ServerError => write!(f, "5xx"),
+        }
+    }
+}
+
+impl std::str::FromStr for StatusClass {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "2xx" => Ok(StatusClass::Success),
+            "3xx" => Ok(StatusClass::Redirection),
+            "4xx" => Ok(StatusClass::ClientError),
+            "5xx" => Ok(StatusClass::ServerError),
+            _ => Err(format!("Invalid status class: {}", s)),
+        }
+    }
+}
+
+impl StatusClass {
+    /// Inclusive lower and exclusive upper bound of the class.
+    pub fn range(&self) -> (i32, i32) {
+        match self {
+            StatusClass::Success => (200, 300),
+            StatusClass::Redirection => (300, 400),
+            StatusClass::ClientError => (400, 500),
+            StatusClass::ServerError => (500, 600),
+        }
+    }
+}
+
+const HTTP_METHODS: &[&str] = &["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS"];
+
+#[derive(Debug, Clone, Default, Deserialize, utoipa::IntoParams, ToSchema)]
+pub struct AnalyticsQuery {
+    /// Start of the range. Defaults to 7 days before `end`.
+    #[serde(default)]
+    #[param(value_type = Option<String>, format = DateTime)]
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub start: Option<DateTime<Utc>>,
+    
+    /// End of the range. Defaults to now.
+    #[serde(default)]
+    #[param(value_type = Option<String>, format = DateTime)]
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub end: Option<DateTime<Utc>>,
+    
+    /// Request path. A trailing `*` matches every path with that prefix.
+    #[serde(default)]
+    pub path: Option<String>,
+    
+    /// HTTP method, e.g. `POST`.
+    #[serde(default)]
+    pub method: Option<String>,
+    
+    #[serde(default)]
+    #[param(value_type = Option<String>, example = "5xx")]
+    pub status_class: Option<StatusClass>,
+    
+    /// Adds a volume series at this granularity to the response.
+    #[serde(default)]
+    pub bucket: Option<TimeBucket>,
+}
+
+impl AnalyticsQuery {
+    /// Resolves defaults against `now` and validates the filters.
+    pub fn resolve(&self, key_id: Option<&str>, now: DateTime<Utc>) -> Result<RequestFilter, String> {
+        let end = self.end.unwrap_or(now);
+        let start = self.start.unwrap_or(end - chrono::Duration::days(7));
+
+        if start > end {
+            return Err("'start' must not be after 'end'".to_string());
+        }
+
+        let method = match &self.method {
+            Some(method) => {
+                let method = method.to_uppercase();
+                if !HTTP_METHODS.contains(&method.as_str()) {
+                    return Err(format!("Invalid method: {}", method));
+                }
+                Some(method)
+            }
+            None => None,
+        };
+
+        let path = match &self.path {
+            Some(path) if !path.starts_with('/') => {
+                return Err("'path' must start with '/'".to_string());
+            }
+            path => path.clone(),
+        };
+
+        if let Some(bucket) = self.bucket {
+            if bucket.bucket_count(start, end) > TimeBucket::MAX_BUCKETS {
+                return Err(format!(
+                    "Range too large: at most {} {} buckets are allowed",
+                    TimeBucket::MAX_BUCKETS,
+                    bucket
+                ));
+            }
+        }
+
+        Ok(RequestFilter {
+            key_id: key_id.map(str::to_string),
+            start,
+            end,
+            path,
+            method,
+            status_class: self.status_class,
+            source: AnalyticsSource::Raw,
+        })
+    }
+}
+
+/// Which `requests` rows an analytics query covers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestFilter {
+    pub key_id: Option<String>,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub path: Option<String>,
+    pub method: Option<String>,
+    pub status_class: Option<StatusClass>,
+    pub source: AnalyticsSource,
+}
+
+impl RequestFilter {
+    pub fn new(key_id: Option<&str>, start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        Self {
+            key_id: key_id.map(str::to_string),
+            start,
+            end,
+            path: None,
+            method: None,
+            status_class: None,
+            source: AnalyticsSource::Raw,
+        }
+    }
+
+    /// Builds the `WHERE` clause and its bind values, numbered from `$1`.
+    pub fn where_clause(&self) -> (String, Vec<String>) {
+        let mut clause = String::from("WHERE ts >= $1::timestamptz AND ts <= $2::timestamptz");
+        let mut bind_values = vec![self.start.to_rfc3339(), self.end.to_rfc3339()];
+        let mut param_count = 2;
+
+        if let Some(key_id) = &self.key_id {
+            param_count += 1;
+            clause.push_str(&format!(" AND key_id = ${}", param_count));
+            bind_values.push(key_id.clone());
+        }
+
+        if let Some(path) = &self.path {
+            param_count += 1;
+            match path.strip_suffix('*') {
+                Some(prefix) => {
+                    clause.push_str(&format!(" AND starts_with(path, ${})", param_count));
+                    bind_values.push(prefix.to_string());
+                }
+                None => {
+                    clause.push_str(&format!(" AND path = ${}", param_count));
+                    bind_values.push(path.clone());
+                }
+            }
+        }
+
+        if let Some(method) = &self.method {
+            param_count += 1;
+            clause.push_str(&format!(" AND method = ${}", param_count));
+            bind_values.push(method.clone());
+        }
+
+        if let Some(status_class) = &self.status_class {
+            let (low, high) = status_class.range();
+            clause.push_str(&format!(
+                " AND status >= ${}::int AND status < ${}::int",
+                param_count + 1,
+                param_count + 2
+            ));
+            bind_values.push(low.to_string());
+            bind_values.push(high.to_string());
+        }
+
+        (clause, bind_values)
+    }
+}
+
 pub struct AnalyticsService;
 
 impl AnalyticsService {
     pub async fn get_request_stats(
         pool: &sqlx::PgPool,
-        key_id: Option<&str>,
-        start: DateTime<Utc>,
-        end: DateTime<Utc>,
+        filter: &RequestFilter,
     ) -> Result<RequestStats, sqlx::Error> {
-        let stats = if let Some(key_id) = key_id {
-            sqlx::query_as::<_, (i64, i64, i64, Option<f64>)>(
-                r#"
-                SELECT 
-                    COUNT(*) as total_requests,
-                    COUNT(*) FILTER (WHERE status >= 200 AND status < 300) as successful_requests,
-                    COUNT(*) FILTER (WHERE status >= 400) as failed_requests,
-                    AVG(latency_ms) as avg_latency_ms
-                FROM requests
-                WHERE key_id = $1 
-                    AND ts >= $2 
-                    AND ts <= $3
-                "#
-            )
-            .bind(key_id)
-            .bind(start)
-            .bind(end)
-            .fetch_one(pool)
-            .await?
-        } else {
-            sqlx::query_as::<_, (i64, i64, i64, Option<f64>)>(
-                r#"
-                SELECT 
-                    COUNT(*) as total_requests,
-                    COUNT(*) FILTER (WHERE status >= 200 AND status < 300) as successful_requests,
-                    COUNT(*) FILTER (WHERE status >= 400) as failed_requests,
-                    AVG(latency_ms) as avg_latency_ms
-                FROM requests
-                WHERE ts >= $1 
-                    AND ts <= $2
-                "#
-            )
-            .bind(start)
-            .bind(end)
-            .fetch_one(pool)
-            .await?
-        };
+        if filter.source != AnalyticsSource::Raw {
+            return RollupAnalytics::get_request_stats(pool, filter).await;
+        }
+
+        let (where_clause, bind_values) = filter.where_clause();
+        let query = format!(
+            r#"
+            SELECT 
+                COUNT(*) as total_requests,
+                COUNT(*) FILTER (WHERE status >= 200 AND status < 300) as successful_requests,
+                COUNT(*) FILTER (WHERE status >= 400) as failed_requests,
+                AVG(latency_ms)::FLOAT8 as avg_latency_ms,
+                percentile_cont(0.5) WITHIN GROUP (ORDER BY latency_ms) as median_latency_ms,
+                percentile_cont(0.95) WITHIN GROUP (ORDER BY latency_ms) as p95_latency_ms,
+                percentile_cont(0.99) WITHIN GROUP (ORDER BY latency_ms) as p99_latency_ms
+            FROM requests
+            {}
+            "#,
+            where_clause
+        );
+
+        let mut sql_query = sqlx::query_as::<_, (i64, i64, i64, Option<f64>, Option<f64>, Option<f64>, Option<f64>)>(&query);
+        for value in &bind_values {
+            sql_query = sql_query.bind(value);
+        }
+        let stats = sql_query.fetch_one(pool).await?;
 
         Ok(RequestStats {
             total_requests: stats.0,
             successful_requests: stats.1,
             failed_requests: stats.2,
             avg_latency_ms: stats.3.unwrap_or(0.0),
-            median_latency_ms: None,
-            p95_latency_ms: None,
-            p99_latency_ms: None,
-            period_start: start,
-            period_end: end,
+            median_latency_ms: stats.4,
+            p95_latency_ms: stats.5,
+            p99_latency_ms: stats.6,
+            period_start: filter.start,
+            period_end: filter.end,
         })
     }
 
     pub async fn get_endpoint_stats(
         pool: &sqlx::PgPool,
-        key_id: Option<&str>,
-        start: DateTime<Utc>,
-        end: DateTime<Utc>,
+        filter: &RequestFilter,
         limit: i64,
     ) -> Result<Vec<EndpointStats>, sqlx::Error> {
-        let stats = if let Some(key_id) = key_id {
-            sqlx::query_as::<_, (String, String, i64, Option<f64>, i64)>(
-                r#"
-                SELECT 
-                    path,
-                    method,
-                    COUNT(*) as request_count,
-                    AVG(latency_ms) as avg_latency_ms,
-                    COUNT(*) FILTER (WHERE status >= 400) as error_count
-                FROM requests
-                WHERE key_id = $1 
-                    AND ts >= $2 
-                    AND ts <= $3
-                GROUP BY path, method
-                ORDER BY request_count DESC
-                LIMIT $4
-                "#
-            )
-            .bind(key_id)
-            .bind(start)
-            .bind(end)
-            .bind(limit)
-            .fetch_all(pool)
-            .await?
-        } else {
-            sqlx::query_as::<_, (String, String, i64, Option<f64>, i64)>(
-                r#"
-                SELECT 
-                    path,
-                    method,
-                    COUNT(*) as request_count,
-                    AVG(latency_ms) as avg_latency_ms,
-                    COUNT(*) FILTER (WHERE status >= 400) as error_count
-                FROM requests
-                WHERE ts >= $1 
-                    AND ts <= $2
-                GROUP BY path, method
-                ORDER BY request_count DESC
-                LIMIT $3
-                "#
-            )
-            .bind(start)
-            .bind(end)
-            .bind(limit)
-            .fetch_all(pool)
-            .await?
-        };
+        if filter.source != AnalyticsSource::Raw {
+            return RollupAnalytics::get_endpoint_stats(pool, filter, limit).await;
+        }
+
+        let (where_clause, mut bind_values) = filter.where_clause();
+        let query = format!(
+            r#"
+            SELECT 
+                path,
+                method,
+                COUNT(*) as request_count,
+                AVG(latency_ms)::FLOAT8 as avg_latency_ms,
+                percentile_cont(0.5) WITHIN GROUP (ORDER BY latency_ms) as median_latency_ms,
+                percentile_cont(0.95) WITHIN GROUP (ORDER BY latency_ms) as p95_latency_ms,
+                percentile_cont(0.99) WITHIN GROUP (ORDER BY latency_ms) as p99_latency_ms,
+                COUNT(*) FILTER (WHERE status >= 400) as error_count
+            FROM requests
+            {}
+            GROUP BY path, method
+            ORDER BY request_count DESC
+            LIMIT ${}::bigint
+            "#,
+            where_clause,
+            bind_values.len() + 1
+        );
+        bind_values.push(limit.to_string());
+
+        let mut sql_query = sqlx::query_as::<_, (String, String, i64, Option<f64>, Option<f64>, Option<f64>, Option<f64>, i64)>(&query);
+        for value in &bind_values {
+            sql_query = sql_query.bind(value);
+        }
+        let stats = sql_query.fetch_all(pool).await?;
 
         Ok(stats
             .into_iter()
-            .map(|(path, method, count, avg_latency, error_count)| {
-                let error_rate = if count > 0 {
-                    (error_count as f64 / count as f64) * 100.0
-                } else {
-                    0.0
-                };
-                
+            .map(|(path, method, count, avg_latency, median, p95, p99, error_count)| {
                 EndpointStats {
                     path,
                     method,
                     request_count: count,
                     avg_latency_ms: avg_latency.unwrap_or(0.0),
-                    error_rate,
+                    median_latency_ms: median,
+                    p95_latency_ms: p95,
+                    p99_latency_ms: p99,
+                    error_rate: percentage(error_count, count),
                 }
             })
             .collect())
@@ -228,134 +496,144 @@ impl AnalyticsService {
 
     pub async fn get_status_code_stats(
         pool: &sqlx::PgPool,
-        key_id: Option<&str>,
-        start: DateTime<Utc>,
-        end: DateTime<Utc>,
+        filter: &RequestFilter,
     ) -> Result<Vec<StatusCodeStats>, sqlx::Error> {
-        let total_count = if let Some(key_id) = key_id {
-            sqlx::query_scalar::<_, i64>(
-                "SELECT COUNT(*) FROM requests WHERE key_id = $1 AND ts >= $2 AND ts <= $3"
-            )
-            .bind(key_id)
-            .bind(start)
-            .bind(end)
-            .fetch_one(pool)
-            .await?
-        } else {
-            sqlx::query_scalar::<_, i64>(
-                "SELECT COUNT(*) FROM requests WHERE ts >= $1 AND ts <= $2"
-            )
-            .bind(start)
-            .bind(end)
-            .fetch_one(pool)
-            .await?
-        };
+        if filter.source != AnalyticsSource::Raw {
+            return RollupAnalytics::get_status_code_stats(pool, filter).await;
+        }
 
-        let stats = if let Some(key_id) = key_id {
-            sqlx::query_as::<_, (i32, i64)>(
-                r#"
-                SELECT status, COUNT(*) as count
-                FROM requests
-                WHERE key_id = $1 
-                    AND ts >= $2 
-                    AND ts <= $3
-                GROUP BY status
-                ORDER BY count DESC
-                "#
-            )
-            .bind(key_id)
-            .bind(start)
-            .bind(end)
-            .fetch_all(pool)
-            .await?
-        } else {
-            sqlx::query_as::<_, (i32, i64)>(
-                r#"
-                SELECT status, COUNT(*) as count
-                FROM requests
-                WHERE ts >= $1 
-                    AND ts <= $2
-                GROUP BY status
-                ORDER BY count DESC
-                "#
-            )
-            .bind(start)
-            .bind(end)
-            .fetch_all(pool)
-            .await?
-        };
+        let (where_clause, bind_values) = filter.where_clause();
+        let query = format!(
+            r#"
+            SELECT status, COUNT(*) as count, SUM(COUNT(*)) OVER ()::BIGINT as total
+            FROM requests
+            {}
+            GROUP BY status
+            ORDER BY count DESC
+            "#,
+            where_clause
+        );
+
+        let mut sql_query = sqlx::query_as::<_, (i32, i64, i64)>(&query);
+        for value in &bind_values {
+            sql_query = sql_query.bind(value);
+        }
+        let stats = sql_query.fetch_all(pool).await?;
 
         Ok(stats
             .into_iter()
-            .map(|(status, count)| {
-                let percentage = if total_count > 0 {
-                    (count as f64 / total_count as f64) * 100.0
-                } else {
-                    0.0
-                };
-                
-                StatusCodeStats {
-                    status_code: status,
-                    count,
-                    percentage,
-                }
+            .map(|(status, count, total)| StatusCodeStats {
+                status_code: status,
+                count,
+                percentage: percentage(count, total),
             })
             .collect())
     }
 
-    pub async fn get_hourly_volume(
+    pub async fn get_error_breakdown(
         pool: &sqlx::PgPool,
-        key_id: Option<&str>,
-        start: DateTime<Utc>,
-        end: DateTime<Utc>,
-    ) -> Result<Vec<HourlyVolume>, sqlx::Error> {
-        let stats = if let Some(key_id) = key_id {
-            sqlx::query_as::<_, (DateTime<Utc>, i64, Option<f64>)>(
-                r#"
-                SELECT 
-                    date_trunc('hour', ts) as hour,
-                    COUNT(*) as request_count,
-                    AVG(latency_ms) as avg_latency_ms
-                FROM requests
-                WHERE key_id = $1 
-                    AND ts >= $2 
-                    AND ts <= $3
-                GROUP BY hour
-                ORDER BY hour ASC
-                "#
-            )
-            .bind(key_id)
-            .bind(start)
-            .bind(end)
-            .fetch_all(pool)
-            .await?
-        } else {
-            sqlx::query_as::<_, (DateTime<Utc>, i64, Option<f64>)>(
-                r#"
-                SELECT 
-                    date_trunc('hour', ts) as hour,
-                    COUNT(*) as request_count,
-                    AVG(latency_ms) as avg_latency_ms
-                FROM requests
-                WHERE ts >= $1 
-                    AND ts <= $2
-                GROUP BY hour
-                ORDER BY hour ASC
-                "#
-            )
-            .bind(start)
-            .bind(end)
-            .fetch_all(pool)
-            .await?
-        };
+        filter: &RequestFilter,
+    ) -> Result<Vec<ErrorCodeStats>, sqlx::Error> {
+        if filter.source != AnalyticsSource::Raw {
+            return RollupAnalytics::get_error_breakdown(pool, filter).await;
+        }
+
+        let (where_clause, bind_values) = filter.where_clause();
+        let query = format!(
+            r#"
+            SELECT
+                COALESCE(error_code, 'unknown') as error_code,
+                COUNT(*) as count,
+                SUM(COUNT(*)) OVER ()::BIGINT as total
+            FROM requests
+            {} AND status >= 400
+            GROUP BY 1
+            ORDER BY count DESC
+            "#,
+            where_clause
+        );
+
+        let mut sql_query = sqlx::query_as::<_, (String, i64, i64)>(&query);
+        for value in &bind_values {
+            sql_query = sql_query.bind(value);
+        }
+        let stats = sql_query.fetch_all(pool).await?;
 
         Ok(stats
             .into_iter()
-            .map(|(hour, count, avg_latency)| HourlyVolume {
-                hour,
-                request_count: count,
+            .map(|(error_code, count, total)| ErrorCodeStats {
+                error_code,
+                count,
+                percentage: percentage(count, total),
+            })
+            .collect())
+    }
+
+    pub async fn get_volume(
+        pool: &sqlx::PgPool,
+        filter: &RequestFilter,
+        bucket: TimeBucket,
+    ) -> Result<Vec<VolumeBucket>, sqlx::Error> {
+        if filter.source != AnalyticsSource::Raw {
+            return RollupAnalytics::get_volume(pool, filter, bucket).await;
+        }
+
+        let (where_clause, bind_values) = filter.where_clause();
+        let query = format!(
+            r#"
+            SELECT 
+                date_trunc('{}', ts) as bucket_start,
+                COUNT(*) as request_count,
+                COUNT(*) FILTER (WHERE status >= 400) as error_count,
+                AVG(latency_ms)::FLOAT8 as avg_latency_ms,
+                percentile_cont(0.95) WITHIN GROUP (ORDER BY latency_ms) as p95_latency_ms
+            FROM requests
+            {}
+            GROUP BY bucket_start
+            ORDER BY bucket_start ASC
+            "#,
+            bucket,
+            where_clause
+        );
+
+        let mut sql_query = sqlx::query_as::<_, (DateTime<Utc>, i64, i64, Option<f64>, Option<f64>)>(&query);
+        for value in &bind_values {
+            sql_query = sql_query.bind(value);
+        }
+        let stats = sql_query.fetch_all(pool).await?;
+
+        Ok(stats
+            .into_iter()
+            .map(|(bucket_start, request_count, error_count, avg_latency, p95)| VolumeBucket {
+                bucket_start,
+                request_count,
+                error_count,
                 avg_latency_ms: avg_latency.unwrap_or(0.0),
+                p95_latency_ms: p95,
+            })
+            .collect())
+    }
+
+    pub async fn get_hourly_volume(
+        pool: &sqlx::PgPool,
+        filter: &RequestFilter,
+    ) -> Result<Vec<HourlyVolume>, sqlx::Error> {
+        Ok(Self::get_volume(pool, filter, TimeBucket::Hour)
+            .await?
+            .into_iter()
+            .map(|volume| HourlyVolume {
+                hour: volume.bucket_start,
+                request_count: volume.request_count,
+                avg_latency_ms: volume.avg_latency_ms,
             })
             .collect())
     }
-}
\ No newline at end of file
+}
+
+pub(crate) fn percentage(count: i64, total: i64) -> f64 {
+    if total > 0 {
+        (count as f64 / total as f64) * 100.0
+    } else {
+        0.0
+    }
+}
@@ -0,0 +1,408 @@
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    config::Config,
+    db::PgPool,
+    models::{finance::Currency, statement::start_of_day},
+    observability::metrics::record_job_outcome,
+};
+
+/// Days are only snapshotted once they ended this long ago, so transactions
+/// still committing around midnight are not missed.
+const SNAPSHOT_LAG: Duration = Duration::minutes(5);
+
+/// Longest range `GET /api/accounts/{account_id}/balance/history` returns.
+pub const MAX_HISTORY_DAYS: i64 = 366;
+
+/// Balance effect of the selected transactions. Amounts are stored signed
+/// (see `TransactionType::ledger_amount`), so only completed ones are summed.
+pub(crate) const BALANCE_SUM: &str = "COALESCE(SUM(amount) FILTER (WHERE status = 'completed'), 0.0)";
+
+#[derive(Debug, Clone)]
+pub struct BalanceSnapshotSettings {
+    pub interval: std::time::Duration,
+    /// Accounts brought up to date per run.
+    pub batch_size: i64,
+}
+
+impl BalanceSnapshotSettings {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            interval: std::time::Duration::from_secs(config.balance_snapshot_interval_secs),
+            batch_size: config.balance_snapshot_batch_size,
+        }
+    }
+}
+
+impl Default for BalanceSnapshotSettings {
+    fn default() -> Self {
+        Self {
+            interval: std::time::Duration::from_secs(3600),
+            batch_size: 500,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct BalanceParams {
+    /// Balance at this instant instead of now; only transactions created before it count
+    #[serde(default)]
+    #[param(value_type = Option<String>, format = DateTime)]
+    pub as_of: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct BalanceHistoryParams {
+    /// First day (UTC, inclusive); defaults to 29 days before `to`
+    #[serde(default)]
+    #[param(value_type = Option<String>, format = Date, example = "2024-03-01")]
+    pub from: Option<NaiveDate>,
+    /// Last day (UTC, inclusive); defaults to today
+    #[serde(default)]
+    #[param(value_type = Option<String>, format = Date, example = "2024-03-31")]
+    pub to: Option<NaiveDate>,
+}
+
+impl BalanceHistoryParams {
+    /// The inclusive first and last day of the series.
+    pub fn period(&self, today: NaiveDate) -> Result<(NaiveDate, NaiveDate), String> {
+        let to = self.to.unwrap_or(today);
+        let from = self.from.unwrap_or(to - Duration::days(29));
+
+        if from > to {
+            return Err("from must not be later than to".to_string());
+        }
+        if (to - from).num_days() >= MAX_HISTORY_DAYS {
+            return Err(format!("Balance history must not span more than {} days", MAX_HISTORY_DAYS));
+        }
+
+        Ok((from, to))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct BalanceHistoryPoint {
+    #[schema(value_type = String, format = Date)]
+    pub date: NaiveDate,
+    /// Balance at the end of the day.
+    pub balance: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BalanceHistoryResponse {
+    pub account_id: String,
+    pub currency: Currency,
+    #[schema(value_type = String, format = Date)]
+    pub from: NaiveDate,
+    #[schema(value_type = String, format = Date)]
+    pub to: NaiveDate,
+    /// One point per day from `from` to `to`.
+    pub points: Vec<BalanceHistoryPoint>,
+}
+
+/// The last day that may be snapshotted at `now`.
+pub fn last_snapshot_day(now: DateTime<Utc>) -> NaiveDate {
+    (now - SNAPSHOT_LAG).date_naive() - Duration::days(1)
+}
+
+/// End-of-day balances from `from` to `to`, starting at `opening` and adding
+/// the per-day sums. Days without transactions repeat the previous balance.
+pub fn daily_balances(
+    from: NaiveDate,
+    to: NaiveDate,
+    opening: f64,
+    daily_sums: &[(NaiveDate, f64)],
+) -> Vec<BalanceHistoryPoint> {
+    let mut balance = opening;
+
+    from.iter_days()
+        .take_while(|date| *date <= to)
+        .map(|date| {
+            balance += daily_sums
+                .iter()
+                .filter(|(day, _)| *day == date)
+                .map(|(_, amount)| amount)
+                .sum::<f64>();
+            BalanceHistoryPoint { date, balance }
+        })
+        .collect()
+}
+
+pub struct BalanceService;
+
+impl BalanceService {
+    /// Sum of the account's completed transactions created before `at` (all
+    /// of them without it) and their currency, `None` without any.
+    ///
+    /// Starts from the latest snapshot that ends by `at` and only scans the
+    /// transactions created after it.
+    pub async fn balance_at<'e, E>(
+        executor: E,
+        account_id: &str,
+        at: Option<DateTime<Utc>>,
+    ) -> Result<(f64, Option<Currency>), sqlx::Error>
+    where
+        E: sqlx::Executor<'e, Database = sqlx::Postgres>,
+    {
+        let (balance, currency) = sqlx::query_as::<_, (f64, Option<String>)>(&format!(
+            r#"
+            WITH snapshot AS (
+                SELECT
+                    (snapshot_date + 1)::timestamp AT TIME ZONE 'UTC' AS ends_at,
+                    balance,
+                    currency
+                FROM account_balance_snapshots
+                WHERE account_id = $1
+                  AND ($2::timestamptz IS NULL OR (snapshot_date + 1)::timestamp AT TIME ZONE 'UTC' <= $2)
+                ORDER BY snapshot_date DESC
+                LIMIT 1
+            ),
+            tail AS (
+                SELECT
+                    {} AS amount,
+                    MAX(currency) AS currency
+                FROM transactions
+                WHERE account_id = $1 AND status = 'completed'
+                  AND created_at >= COALESCE((SELECT ends_at FROM snapshot), '-infinity'::timestamptz)
+                  AND ($2::timestamptz IS NULL OR created_at < $2)
+            )
+            SELECT
+                COALESCE((SELECT balance FROM snapshot), 0.0) + tail.amount,
+                GREATEST((SELECT currency FROM snapshot), tail.currency)
+            FROM tail
+            "#,
+            BALANCE_SUM
+        ))
+        .bind(account_id)
+        .bind(at)
+        .fetch_one(executor)
+        .await?;
+
+        Ok((balance, currency.and_then(|c| c.parse().ok())))
+    }
+
+    /// Sums of completed transactions per UTC day from `from` to `to`, and
+    /// their highest currency.
+    async fn daily_sums<'e, E>(
+        executor: E,
+        account_id: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<(NaiveDate, f64, String)>, sqlx::Error>
+    where
+        E: sqlx::Executor<'e, Database = sqlx::Postgres>,
+    {
+        sqlx::query_as::<_, (NaiveDate, f64, String)>(&format!(
+            r#"
+            SELECT
+                (created_at AT TIME ZONE 'UTC')::date AS day,
+                {},
+                MAX(currency)
+            FROM transactions
+            WHERE account_id = $1 AND status = 'completed'
+              AND created_at >= $2 AND created_at < $3
+            GROUP BY day
+            ORDER BY day
+            "#,
+            BALANCE_SUM
+        ))
+        .bind(account_id)
+        .bind(start_of_day(from))
+        .bind(start_of_day(to + Duration::days(1)))
+        .fetch_all(executor)
+        .await
+    }
+
+    pub async fn history(
+        pool: &PgPool,
+        account_id: &str,
+        (from, to): (NaiveDate, NaiveDate),
+    ) -> Result<BalanceHistoryResponse, sqlx::Error> {
+        let (opening, currency) = Self::balance_at(pool, account_id, Some(start_of_day(from))).await?;
+        let sums = Self::daily_sums(pool, account_id, from, to).await?;
+
+        let currency = currency
+            .or_else(|| sums.first().and_then(|(_, _, c)| c.parse().ok()))
+            .unwrap_or_default();
+        let sums: Vec<(NaiveDate, f64)> = sums.into_iter().map(|(day, sum, _)| (day, sum)).collect();
+
+        Ok(BalanceHistoryResponse {
+            account_id: account_id.to_string(),
+            currency,
+            from,
+            to,
+            points: daily_balances(from, to, opening, &sums),
+        })
+    }
+
+    /// Takes the account's snapshot lock until the end of the database
+    /// transaction, so snapshots are not written from balances a concurrent
+    /// `invalidate_snapshots` is about to change.
+    async fn lock_snapshots(conn: &mut sqlx::PgConnection, account_id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext('account_balance_snapshots'), hashtext($1))")
+            .bind(account_id)
+            .execute(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Drops the account's snapshots from the day of `created_at` on, for a
+    /// transaction created then whose status changes later (a pending one
+    /// approved in fraud review). Runs in the caller's database transaction;
+    /// the snapshot job writes them again.
+    pub async fn invalidate_snapshots(
+        conn: &mut sqlx::PgConnection,
+        account_id: &str,
+        created_at: DateTime<Utc>,
+    ) -> Result<u64, sqlx::Error> {
+        Self::lock_snapshots(&mut *conn, account_id).await?;
+
+        let result = sqlx::query(
+            "DELETE FROM account_balance_snapshots WHERE account_id = $1 AND snapshot_date >= $2"
+        )
+        .bind(account_id)
+        .bind(created_at.date_naive())
+        .execute(conn)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Writes the missing daily snapshots of one account up to `through`.
+    /// Returns the number of snapshots written.
+    pub async fn refresh_account(
+        pool: &PgPool,
+        account_id: &str,
+        through: NaiveDate,
+    ) -> Result<u64, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        Self::lock_snapshots(&mut tx, account_id).await?;
+
+        let start = sqlx::query_scalar::<_, Option<NaiveDate>>(
+            r#"
+            SELECT COALESCE(
+                (SELECT MAX(snapshot_date) + 1 FROM account_balance_snapshots WHERE account_id = $1),
+                (SELECT MIN(created_at AT TIME ZONE 'UTC')::date FROM transactions
+                 WHERE account_id = $1 AND status = 'completed')
+            )
+            "#
+        )
+        .bind(account_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let Some(start) = start.filter(|start| *start <= through) else {
+            return Ok(0);
+        };
+
+        let (opening, opening_currency) =
+            Self::balance_at(&mut *tx, account_id, Some(start_of_day(start))).await?;
+        let sums = Self::daily_sums(&mut *tx, account_id, start, through).await?;
+
+        // Snapshot currencies follow the live query's MAX(currency) up to each day.
+        let mut currency = opening_currency.map(|c| c.to_string());
+        let mut currencies = Vec::new();
+        let daily: Vec<(NaiveDate, f64)> = sums.iter().map(|(day, sum, _)| (*day, *sum)).collect();
+        let points = daily_balances(start, through, opening, &daily);
+        for point in &points {
+            if let Some((_, _, day_currency)) = sums.iter().find(|(day, _, _)| *day == point.date) {
+                if currency.as_deref().is_none_or(|c| day_currency.as_str() > c) {
+                    currency = Some(day_currency.clone());
+                }
+            }
+            currencies.push(currency.clone());
+        }
+
+        let dates: Vec<NaiveDate> = points.iter().map(|point| point.date).collect();
+        let balances: Vec<f64> = points.iter().map(|point| point.balance).collect();
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO account_balance_snapshots (account_id, snapshot_date, balance, currency)
+            SELECT $1, snapshot_date, balance, currency
+            FROM UNNEST($2::date[], $3::float8[], $4::text[]) AS s(snapshot_date, balance, currency)
+            ON CONFLICT (account_id, snapshot_date) DO UPDATE
+            SET balance = EXCLUDED.balance, currency = EXCLUDED.currency, computed_at = NOW()
+            "#
+        )
+        .bind(account_id)
+        .bind(&dates)
+        .bind(&balances)
+        .bind(&currencies)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Brings up to `batch_size` accounts with completed transactions up to
+    /// date. Returns the number of snapshots written.
+    pub async fn refresh_snapshots(
+        pool: &PgPool,
+        settings: &BalanceSnapshotSettings,
+        now: DateTime<Utc>,
+    ) -> Result<u64, sqlx::Error> {
+        let through = last_snapshot_day(now);
+
+        let accounts = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT a.account_id
+            FROM accounts a
+            LEFT JOIN (
+                SELECT account_id, MAX(snapshot_date) AS last_date
+                FROM account_balance_snapshots
+                GROUP BY account_id
+            ) s ON s.account_id = a.account_id
+            WHERE (s.last_date IS NULL OR s.last_date < $1)
+              AND EXISTS (
+                  SELECT 1 FROM transactions t
+                  WHERE t.account_id = a.account_id AND t.status = 'completed'
+                    AND t.created_at < $2
+              )
+            ORDER BY s.last_date NULLS FIRST, a.account_id
+            LIMIT $3
+            "#
+        )
+        .bind(through)
+        .bind(start_of_day(through + Duration::days(1)))
+        .bind(settings.batch_size)
+        .fetch_all(pool)
+        .await?;
+
+        let mut written = 0;
+        for account_id in accounts {
+            written += Self::refresh_account(pool, &account_id, through).await?;
+        }
+
+        Ok(written)
+    }
+}
+
+pub fn spawn_balance_snapshot_worker(pool: PgPool, settings: BalanceSnapshotSettings) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(settings.interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            match BalanceService::refresh_snapshots(&pool, &settings, Utc::now()).await {
+                Ok(written) => {
+                    if written > 0 {
+                        tracing::info!("Wrote {} account balance snapshots", written);
+                    }
+                    record_job_outcome("balance_snapshot", "success");
+                }
+                Err(e) => {
+                    tracing::error!("Balance snapshot run failed: {}", e);
+                    record_job_outcome("balance_snapshot", "failure");
+                }
+            }
+        }
+    });
+}
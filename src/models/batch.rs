@@ -0,0 +1,762 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    config::Config,
+    db::PgPool,
+    models::{
+        common::{ErrorCode, ErrorDetail},
+        fees::FeeService,
+        finance::{generate_transaction_id, TransactionStatus},
+        lifecycle::{check_account_status, AccountLifecycleService},
+        fraud::FraudScreener,
+        limits::{apply_limits, AccountLimitService},
+        metadata::{InvalidMetadata, MetadataSchema, MetadataSchemaService, MetadataTarget},
+        requests::CreateTransactionRequest,
+        responses::TransactionResponse,
+    },
+    observability::metrics::{record_batch_items, record_job_outcome, record_transaction_created},
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchMode {
+    /// Nothing is created unless every item is valid.
+    AllOrNothing,
+    /// Valid items are created; invalid ones are reported individually.
+    #[default]
+    BestEffort,
+}
+
+impl std::fmt::Display for BatchMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BatchMode::AllOrNothing => write!(f, "all_or_nothing"),
+            BatchMode::BestEffort => write!(f, "best_effort"),
+        }
+    }
+}
+
+impl std::str::FromStr for BatchMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "all_or_nothing" => Ok(BatchMode::AllOrNothing),
+            "best_effort" => Ok(BatchMode::BestEffort),
+            _ => Err(format!("Invalid batch mode: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, IntoParams, ToSchema)]
+#[into_params(parameter_in = Query)]
+pub struct BatchParams {
+    /// `best_effort` (default) or `all_or_nothing`
+    #[serde(default)]
+    pub mode: BatchMode,
+}
+
+/// Body encoding of a batch request, chosen by `Content-Type`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatchFormat {
+    /// A JSON array of transactions.
+    Json,
+    /// One JSON transaction per line.
+    Ndjson,
+}
+
+impl BatchFormat {
+    pub fn from_content_type(content_type: Option<&str>) -> Self {
+        let mime = content_type
+            .and_then(|value| value.split(';').next())
+            .map(|value| value.trim().to_lowercase());
+
+        match mime.as_deref() {
+            Some("application/x-ndjson" | "application/ndjson" | "application/jsonl") => {
+                BatchFormat::Ndjson
+            }
+            _ => BatchFormat::Json,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchError {
+    Empty,
+    TooManyItems(usize),
+    InvalidBody(String),
+}
+
+impl std::fmt::Display for BatchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BatchError::Empty => write!(f, "Batch must contain at least one transaction"),
+            BatchError::TooManyItems(max) => {
+                write!(f, "Batch must not exceed {} transactions", max)
+            }
+            BatchError::InvalidBody(msg) => write!(f, "Invalid batch body: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for BatchError {}
+
+/// Splits a batch body into raw items. Items are only checked for being JSON
+/// here; [`decode_items`] reports schema errors per item.
+pub fn parse_batch(
+    body: &[u8],
+    format: BatchFormat,
+    max_items: usize,
+) -> Result<Vec<serde_json::Value>, BatchError> {
+    let items = match format {
+        BatchFormat::Json => serde_json::from_slice::<Vec<serde_json::Value>>(body)
+            .map_err(|e| BatchError::InvalidBody(format!("expected a JSON array: {}", e)))?,
+        BatchFormat::Ndjson => {
+            let body = std::str::from_utf8(body)
+                .map_err(|_| BatchError::InvalidBody("body is not valid UTF-8".to_string()))?;
+
+            body.lines()
+                .enumerate()
+                .filter(|(_, line)| !line.trim().is_empty())
+                .map(|(n, line)| {
+                    serde_json::from_str(line).map_err(|e| {
+                        BatchError::InvalidBody(format!("line {} is not valid JSON: {}", n + 1, e))
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        }
+    };
+
+    if items.is_empty() {
+        return Err(BatchError::Empty);
+    }
+    if items.len() > max_items {
+        return Err(BatchError::TooManyItems(max_items));
+    }
+
+    Ok(items)
+}
+
+/// Decodes and validates each item with [`CreateTransactionRequest::validate`].
+pub fn decode_items(
+    items: &[serde_json::Value],
+) -> Vec<Result<CreateTransactionRequest, ErrorDetail>> {
+    items
+        .iter()
+        .map(|item| {
+            let request = CreateTransactionRequest::deserialize(item)
+                .map_err(|e| item_error(ErrorCode::ValidationError, e.to_string()))?;
+            request.validate_metadata(None).map_err(metadata_error)?;
+            request
+                .validate()
+                .map_err(|e| item_error(ErrorCode::ValidationError, e))?;
+            Ok(request)
+        })
+        .collect()
+}
+
+/// Rejects items whose account is not in `existing_accounts`.
+pub fn check_accounts(
+    items: Vec<Result<CreateTransactionRequest, ErrorDetail>>,
+    existing_accounts: &HashSet<String>,
+) -> Vec<Result<CreateTransactionRequest, ErrorDetail>> {
+    items
+        .into_iter()
+        .map(|item| match item {
+            Ok(request) if !existing_accounts.contains(&request.account_id) => Err(item_error(
+                ErrorCode::NotFound,
+                format!("Account '{}' not found", request.account_id),
+            )),
+            other => other,
+        })
+        .collect()
+}
+
+/// Rejects items whose metadata does not match the key's transaction schema.
+pub fn check_metadata_schema(
+    items: Vec<Result<CreateTransactionRequest, ErrorDetail>>,
+    schema: &MetadataSchema,
+) -> Vec<Result<CreateTransactionRequest, ErrorDetail>> {
+    items
+        .into_iter()
+        .map(|item| {
+            let request = item?;
+            request.validate_metadata(Some(schema)).map_err(metadata_error)?;
+            Ok(request)
+        })
+        .collect()
+}
+
+fn metadata_error(error: InvalidMetadata) -> ErrorDetail {
+    ErrorDetail {
+        code: ErrorCode::ValidationError.to_string(),
+        message: error.to_string(),
+        details: Some(error.details()),
+    }
+}
+
+fn item_error(code: ErrorCode, message: String) -> ErrorDetail {
+    ErrorDetail {
+        code: code.to_string(),
+        message,
+        details: None,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchItemStatus {
+    Created,
+    Failed,
+    /// Valid, but not created because another item failed in `all_or_nothing` mode.
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BatchItemResult {
+    /// Position of the item in the request, starting at 0.
+    pub index: usize,
+    pub status: BatchItemStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction: Option<TransactionResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<ErrorDetail>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BatchResponse {
+    pub mode: BatchMode,
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub results: Vec<BatchItemResult>,
+}
+
+impl BatchResponse {
+    pub fn new(mode: BatchMode, results: Vec<BatchItemResult>) -> Self {
+        let succeeded = results
+            .iter()
+            .filter(|result| result.status == BatchItemStatus::Created)
+            .count();
+
+        Self {
+            mode,
+            total: results.len(),
+            succeeded,
+            failed: results.len() - succeeded,
+            results,
+        }
+    }
+
+    /// Response for a batch where nothing was created: invalid items are
+    /// reported as failed and the rest as skipped.
+    pub fn rejected(
+        mode: BatchMode,
+        items: &[Result<CreateTransactionRequest, ErrorDetail>],
+    ) -> Self {
+        let results = items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| match item {
+                Ok(_) => BatchItemResult {
+                    index,
+                    status: BatchItemStatus::Skipped,
+                    transaction: None,
+                    error: None,
+                },
+                Err(error) => BatchItemResult {
+                    index,
+                    status: BatchItemStatus::Failed,
+                    transaction: None,
+                    error: Some(error.clone()),
+                },
+            })
+            .collect();
+
+        Self::new(mode, results)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchJobStatus {
+    Pending,
+    Completed,
+    Failed,
+}
+
+impl std::fmt::Display for BatchJobStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BatchJobStatus::Pending => write!(f, "pending"),
+            BatchJobStatus::Completed => write!(f, "completed"),
+            BatchJobStatus::Failed => write!(f, "failed"),
+        }
+    }
+}
+
+impl std::str::FromStr for BatchJobStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "pending" => Ok(BatchJobStatus::Pending),
+            "completed" => Ok(BatchJobStatus::Completed),
+            "failed" => Ok(BatchJobStatus::Failed),
+            _ => Err(format!("Invalid batch job status: {}", s)),
+        }
+    }
+}
+
+/// A batch accepted for background processing.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BatchJobResponse {
+    pub job_id: String,
+    pub mode: BatchMode,
+    pub status: BatchJobStatus,
+    pub total_items: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub succeeded: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failed: Option<i32>,
+    /// Per-item results once the job has completed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub results: Option<Vec<BatchItemResult>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[schema(value_type = String, format = DateTime)]
+    pub created_at: DateTime<Utc>,
+    #[schema(value_type = String, format = DateTime)]
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct BatchSettings {
+    /// Largest batch accepted in one request.
+    pub max_items: usize,
+    /// Batches with more items than this are processed as a background job.
+    pub async_threshold: usize,
+    /// How often the worker looks for pending jobs.
+    pub poll_interval: Duration,
+}
+
+impl BatchSettings {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            max_items: config.batch_max_items.max(1),
+            async_threshold: config.batch_async_threshold,
+            poll_interval: Duration::from_millis(config.batch_job_poll_ms.max(1)),
+        }
+    }
+}
+
+impl Default for BatchSettings {
+    fn default() -> Self {
+        Self {
+            max_items: 5_000,
+            async_threshold: 500,
+            poll_interval: Duration::from_secs(1),
+        }
+    }
+}
+
+pub fn generate_batch_job_id() -> String {
+    use rand::Rng;
+    let timestamp = Utc::now().timestamp();
+    let random: u32 = rand::rng().random();
+    format!("batch_{}_{:08x}", timestamp, random)
+}
+
+type TransactionRow = (
+    String,
+    String,
+    f64,
+    String,
+    String,
+    String,
+    Option<String>,
+    Option<serde_json::Value>,
+    DateTime<Utc>,
+    Option<DateTime<Utc>>,
+    Option<String>,
+);
+
+pub struct TransactionBatchService;
+
+impl TransactionBatchService {
+    /// Validates, limit-checks and fraud-screens the items of a batch and
+    /// creates them in a single database transaction on `conn`; the caller
+    /// commits. Item metadata is checked against `key_id`'s transaction schema.
+    pub async fn process(
+        conn: &mut sqlx::PgConnection,
+        items: &[serde_json::Value],
+        mode: BatchMode,
+        key_id: Option<&str>,
+        screener: &FraudScreener,
+    ) -> Result<BatchResponse, sqlx::Error> {
+        let decoded = decode_items(items);
+        let decoded = match MetadataSchemaService::for_key(&mut *conn, key_id, MetadataTarget::Transaction).await? {
+            Some(schema) => check_metadata_schema(decoded, &schema),
+            None => decoded,
+        };
+
+        let account_ids: Vec<String> = decoded
+            .iter()
+            .filter_map(|item| item.as_ref().ok())
+            .map(|request| request.account_id.clone())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+
+        let statuses = AccountLifecycleService::lock_statuses(&mut *conn, &account_ids).await?;
+        let existing: HashSet<String> = statuses.keys().cloned().collect();
+
+        let checked = check_account_status(check_accounts(decoded, &existing), &statuses);
+
+        let limits = AccountLimitService::lock(&mut *conn, &account_ids).await?;
+        let checked = if limits.is_empty() {
+            checked
+        } else {
+            let limited: Vec<String> = limits.keys().cloned().collect();
+            let mut usage = AccountLimitService::usage(&mut *conn, &limited, Utc::now()).await?;
+            apply_limits(checked, &limits, &mut usage)
+        };
+        let checked = screener.screen_items(&mut *conn, checked).await?;
+
+        if mode == BatchMode::AllOrNothing && checked.iter().any(Result::is_err) {
+            let response = BatchResponse::rejected(mode, &checked);
+            record_batch_items(&mode.to_string(), response.succeeded, response.failed);
+            return Ok(response);
+        }
+
+        let created = Self::insert(&mut *conn, &checked).await?;
+
+        let results = checked
+            .into_iter()
+            .zip(created)
+            .enumerate()
+            .map(|(index, (item, transaction))| match (item, transaction) {
+                (Ok(_), Some(transaction)) => BatchItemResult {
+                    index,
+                    status: BatchItemStatus::Created,
+                    transaction: Some(transaction),
+                    error: None,
+                },
+                (Err(error), _) => BatchItemResult {
+                    index,
+                    status: BatchItemStatus::Failed,
+                    transaction: None,
+                    error: Some(error),
+                },
+                (Ok(_), None) => BatchItemResult {
+                    index,
+                    status: BatchItemStatus::Failed,
+                    transaction: None,
+                    error: Some(item_error(
+                        ErrorCode::InternalError,
+                        "Transaction was not created".to_string(),
+                    )),
+                },
+            })
+            .collect();
+
+        let response = BatchResponse::new(mode, results);
+        record_batch_items(&mode.to_string(), response.succeeded, response.failed);
+        Ok(response)
+    }
+
+    /// Inserts the valid items with one multi-row INSERT and returns the
+    /// created transaction for each position, `None` for invalid items.
+    async fn insert(
+        conn: &mut sqlx::PgConnection,
+        items: &[Result<CreateTransactionRequest, ErrorDetail>],
+    ) -> Result<Vec<Option<TransactionResponse>>, sqlx::Error> {
+        let mut seen = HashSet::new();
+        let ids: Vec<Option<String>> = items
+            .iter()
+            .map(|item| {
+                item.is_ok().then(|| loop {
+                    let id = generate_transaction_id();
+                    if seen.insert(id.clone()) {
+                        break id;
+                    }
+                })
+            })
+            .collect();
+
+        let valid: Vec<(&String, &CreateTransactionRequest)> = ids
+            .iter()
+            .zip(items)
+            .filter_map(|(id, item)| Some((id.as_ref()?, item.as_ref().ok()?)))
+            .collect();
+
+        if valid.is_empty() {
+            return Ok(vec![None; items.len()]);
+        }
+
+        let rows = sqlx::query_as::<_, TransactionRow>(
+            r#"
+            INSERT INTO transactions (
+                transaction_id, account_id, amount, currency,
+                transaction_type, status, description, metadata,
+                created_at, processed_at
+            )
+            SELECT transaction_id, account_id, amount, currency,
+                   transaction_type, $6, description, metadata,
+                   NOW(), NOW()
+            FROM UNNEST(
+                $1::text[], $2::text[], $3::float8[], $4::text[],
+                $5::text[], $7::text[], $8::jsonb[]
+            ) AS t(transaction_id, account_id, amount, currency, transaction_type, description, metadata)
+            RETURNING
+                transaction_id, account_id, amount, currency,
+                transaction_type, status, description, metadata,
+                created_at, processed_at, failure_reason
+            "#
+        )
+        .bind(valid.iter().map(|(id, _)| id.to_string()).collect::<Vec<_>>())
+        .bind(valid.iter().map(|(_, req)| req.account_id.clone()).collect::<Vec<_>>())
+        .bind(valid.iter().map(|(_, req)| req.transaction_type.ledger_amount(req.amount)).collect::<Vec<_>>())
+        .bind(valid.iter().map(|(_, req)| req.currency.to_string()).collect::<Vec<_>>())
+        .bind(valid.iter().map(|(_, req)| req.transaction_type.to_string()).collect::<Vec<_>>())
+        .bind(TransactionStatus::Completed.to_string())
+        .bind(valid.iter().map(|(_, req)| req.description.clone()).collect::<Vec<_>>())
+        .bind(valid.iter().map(|(_, req)| req.metadata.clone()).collect::<Vec<_>>())
+        .fetch_all(&mut *conn)
+        .await?;
+
+        let mut created: HashMap<String, TransactionResponse> = rows
+            .into_iter()
+            .map(|t| {
+                record_transaction_created(&t.4, &t.3);
+                (
+                    t.0.clone(),
+                    TransactionResponse {
+                        transaction_id: t.0,
+                        account_id: t.1,
+                        amount: t.2,
+                        currency: t.3.parse().unwrap_or_default(),
+                        transaction_type: t.4.parse().unwrap_or_default(),
+                        status: t.5.parse().unwrap_or_default(),
+                        description: t.6,
+                        metadata: t.7,
+                        created_at: t.8,
+                        processed_at: t.9,
+                        failure_reason: t.10.and_then(|r| r.parse().ok()),
+                        fee: None,
+                        links: None,
+                    },
+                )
+            })
+            .collect();
+
+        for transaction in created.values_mut() {
+            transaction.fee = FeeService::charge(&mut *conn, transaction).await?;
+        }
+
+        Ok(ids
+            .into_iter()
+            .map(|id| id.and_then(|id| created.remove(&id)))
+            .collect())
+    }
+
+    /// Stores a batch for the background worker.
+    pub async fn enqueue(
+        pool: &PgPool,
+        key_id: Option<&str>,
+        items: Vec<serde_json::Value>,
+        mode: BatchMode,
+    ) -> Result<BatchJobResponse, sqlx::Error> {
+        let job_id = generate_batch_job_id();
+        let total_items = items.len() as i32;
+
+        let created_at = sqlx::query_scalar::<_, DateTime<Utc>>(
+            r#"
+            INSERT INTO transaction_batch_jobs (job_id, key_id, mode, status, total_items, items)
+            VALUES ($1, $2, $3, 'pending', $4, $5)
+            RETURNING created_at
+            "#
+        )
+        .bind(&job_id)
+        .bind(key_id)
+        .bind(mode.to_string())
+        .bind(total_items)
+        .bind(serde_json::Value::Array(items))
+        .fetch_one(pool)
+        .await?;
+
+        Ok(BatchJobResponse {
+            job_id,
+            mode,
+            status: BatchJobStatus::Pending,
+            total_items,
+            succeeded: None,
+            failed: None,
+            results: None,
+            error: None,
+            created_at,
+            completed_at: None,
+        })
+    }
+
+    /// Fetches a job, limited to jobs submitted by `key_id` unless `None` (admin).
+    pub async fn get_job(
+        pool: &PgPool,
+        job_id: &str,
+        key_id: Option<&str>,
+    ) -> Result<Option<BatchJobResponse>, sqlx::Error> {
+        let row = sqlx::query_as::<_, (
+            String,
+            String,
+            String,
+            i32,
+            Option<i32>,
+            Option<i32>,
+            Option<serde_json::Value>,
+            Option<String>,
+            DateTime<Utc>,
+            Option<DateTime<Utc>>,
+        )>(
+            r#"
+            SELECT job_id, mode, status, total_items, succeeded, failed,
+                   results, error, created_at, completed_at
+            FROM transaction_batch_jobs
+            WHERE job_id = $1 AND ($2::text IS NULL OR key_id = $2)
+            "#
+        )
+        .bind(job_id)
+        .bind(key_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(row.map(|row| BatchJobResponse {
+            job_id: row.0,
+            mode: row.1.parse().unwrap_or_default(),
+            status: row.2.parse().unwrap_or(BatchJobStatus::Pending),
+            total_items: row.3,
+            succeeded: row.4,
+            failed: row.5,
+            results: row.6.and_then(|results| serde_json::from_value(results).ok()),
+            error: row.7,
+            created_at: row.8,
+            completed_at: row.9,
+        }))
+    }
+
+    /// Claims and runs the oldest pending job. The job row stays locked until
+    /// its transactions and results are committed together, so a crash leaves
+    /// it pending rather than half-applied; a job that errors is marked failed.
+    /// Returns `false` when there is nothing to do.
+    pub async fn run_next_job(pool: &PgPool, screener: &FraudScreener) -> Result<bool, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        let job = sqlx::query_as::<_, (String, String, Option<serde_json::Value>, Option<String>)>(
+            r#"
+            SELECT job_id, mode, items, key_id
+            FROM transaction_batch_jobs
+            WHERE status = 'pending'
+            ORDER BY created_at
+            LIMIT 1
+            FOR UPDATE SKIP LOCKED
+            "#
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some((job_id, mode, items, key_id)) = job else {
+            return Ok(false);
+        };
+
+        let mode: BatchMode = mode.parse().unwrap_or_default();
+        let items = match items {
+            Some(serde_json::Value::Array(items)) => items,
+            _ => Vec::new(),
+        };
+
+        match Self::complete_job(&mut tx, &job_id, &items, mode, key_id.as_deref(), screener).await {
+            Ok(()) => {
+                tx.commit().await?;
+                Ok(true)
+            }
+            Err(e) => {
+                // Nothing from the failed attempt is kept; record why instead.
+                tx.rollback().await?;
+                Self::fail_job(pool, &job_id, &e.to_string()).await?;
+                Err(e)
+            }
+        }
+    }
+
+    async fn complete_job(
+        conn: &mut sqlx::PgConnection,
+        job_id: &str,
+        items: &[serde_json::Value],
+        mode: BatchMode,
+        key_id: Option<&str>,
+        screener: &FraudScreener,
+    ) -> Result<(), sqlx::Error> {
+        let response = Self::process(&mut *conn, items, mode, key_id, screener).await?;
+
+        sqlx::query(
+            r#"
+            UPDATE transaction_batch_jobs
+            SET status = 'completed', succeeded = $2, failed = $3,
+                results = $4, items = NULL, completed_at = NOW()
+            WHERE job_id = $1
+            "#
+        )
+        .bind(job_id)
+        .bind(response.succeeded as i32)
+        .bind(response.failed as i32)
+        .bind(serde_json::to_value(&response.results).unwrap_or_default())
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn fail_job(pool: &PgPool, job_id: &str, error: &str) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE transaction_batch_jobs
+            SET status = 'failed', error = $2, items = NULL, completed_at = NOW()
+            WHERE job_id = $1
+            "#
+        )
+        .bind(job_id)
+        .bind(error)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// Processes pending batch jobs for the life of the process.
+pub fn spawn_batch_worker(pool: PgPool, settings: BatchSettings, screener: FraudScreener) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(settings.poll_interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            loop {
+                match TransactionBatchService::run_next_job(&pool, &screener).await {
+                    Ok(true) => record_job_outcome("transaction_batch", "success"),
+                    Ok(false) => break,
+                    Err(e) => {
+                        tracing::error!("Transaction batch job failed: {}", e);
+                        record_job_outcome("transaction_batch", "failure");
+                        break;
+                    }
+                }
+            }
+        }
+    });
+}
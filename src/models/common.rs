@@ -1,68 +1,10 @@
-use base64::{engine::general_purpose::STANDARD as BASE64_STANDARD, Engine};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use utoipa::{IntoParams, ToSchema};
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+/// Opaque, signed pagination token; see [`crate::models::pagination`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 pub struct Cursor(pub String);
 
-impl Cursor {
-    pub fn new(timestamp: &time::OffsetDateTime, id: &str) -> Self {
-        let encoded = format!("{}|{}", timestamp.unix_timestamp(), id);
-        Self(BASE64_STANDARD.encode(encoded.as_bytes()))
-    }
-
-    pub fn encode(id: &str) -> Self {
-        Self(BASE64_STANDARD.encode(id.as_bytes()))
-    }
-
-    pub fn decode_string(&self) -> Result<String, CursorError> {
-        let decoded = BASE64_STANDARD
-            .decode(self.0.as_bytes())
-            .map_err(|_| CursorError::InvalidFormat)?;
-        
-        String::from_utf8(decoded)
-            .map_err(|_| CursorError::InvalidFormat)
-    }
-
-    pub fn decode(&self) -> Result<(time::OffsetDateTime, String), CursorError> {
-        let decoded = BASE64_STANDARD
-            .decode(self.0.as_bytes())
-            .map_err(|_| CursorError::InvalidFormat)?;
-
-        let decoded_str = std::str::from_utf8(&decoded)
-            .map_err(|_| CursorError::InvalidFormat)?;
-
-        let parts: Vec<&str> = decoded_str.split('|').collect();
-        if parts.len() != 2 {
-            return Err(CursorError::InvalidFormat);
-        }
-
-        let timestamp = parts[0]
-            .parse::<i64>()
-            .map_err(|_| CursorError::InvalidFormat)?;
-        
-        let dt = time::OffsetDateTime::from_unix_timestamp(timestamp)
-            .map_err(|_| CursorError::InvalidFormat)?;
-
-        Ok((dt, parts[1].to_string()))
-    }
-}
-
-#[derive(Debug, Clone)]
-pub enum CursorError {
-    InvalidFormat,
-}
-
-impl std::fmt::Display for CursorError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            CursorError::InvalidFormat => write!(f, "Invalid cursor format"),
-        }
-    }
-}
-
-impl std::error::Error for CursorError {}
-
 #[derive(Debug, Clone, Serialize, Deserialize, IntoParams, ToSchema)]
 pub struct PaginationParams {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -84,12 +26,86 @@ impl PaginationParams {
     }
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+impl std::fmt::Display for SortOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SortOrder::Asc => write!(f, "asc"),
+            SortOrder::Desc => write!(f, "desc"),
+        }
+    }
+}
+
+impl std::str::FromStr for SortOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "asc" => Ok(SortOrder::Asc),
+            "desc" => Ok(SortOrder::Desc),
+            _ => Err(format!("Invalid sort order: {}", s)),
+        }
+    }
+}
+
+impl SortOrder {
+    pub fn sql(&self) -> &'static str {
+        match self {
+            SortOrder::Asc => "ASC",
+            SortOrder::Desc => "DESC",
+        }
+    }
+
+    /// Keyset comparison selecting rows after the cursor in this order.
+    pub fn after_operator(&self) -> &'static str {
+        match self {
+            SortOrder::Asc => ">",
+            SortOrder::Desc => "<",
+        }
+    }
+}
+
+/// Deserializes a comma-separated query value such as `status=pending,failed`.
+pub fn deserialize_comma_separated<'de, D, T>(deserializer: D) -> Result<Option<Vec<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    let Some(raw) = Option::<String>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+
+    let values = raw
+        .split(',')
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(|value| value.parse::<T>().map_err(serde::de::Error::custom))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if values.is_empty() {
+        return Err(serde::de::Error::custom("expected at least one value"));
+    }
+
+    Ok(Some(values))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct PaginatedResponse<T> {
     pub data: Vec<T>,
     pub has_more: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub next_cursor: Option<Cursor>,
+    /// Cursor for the page before this one, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prev_cursor: Option<Cursor>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -133,6 +149,13 @@ pub enum ErrorCode {
     NotFound,
     AlreadyExists,
     
+    AccountFrozen,
+    AccountClosed,
+    InvalidStatusTransition,
+    NonZeroBalance,
+    LimitExceeded,
+    TransactionBlocked,
+    
     RateLimitExceeded,
     QuotaExceeded,
     
@@ -151,6 +174,12 @@ impl std::fmt::Display for ErrorCode {
             ErrorCode::InvalidInput => write!(f, "invalid_input"),
             ErrorCode::NotFound => write!(f, "not_found"),
             ErrorCode::AlreadyExists => write!(f, "already_exists"),
+            ErrorCode::AccountFrozen => write!(f, "account_frozen"),
+            ErrorCode::AccountClosed => write!(f, "account_closed"),
+            ErrorCode::InvalidStatusTransition => write!(f, "invalid_status_transition"),
+            ErrorCode::NonZeroBalance => write!(f, "nonzero_balance"),
+            ErrorCode::LimitExceeded => write!(f, "limit_exceeded"),
+            ErrorCode::TransactionBlocked => write!(f, "transaction_blocked"),
             ErrorCode::RateLimitExceeded => write!(f, "rate_limit_exceeded"),
             ErrorCode::QuotaExceeded => write!(f, "quota_exceeded"),
             ErrorCode::InternalError => write!(f, "internal_error"),
@@ -158,4 +187,22 @@ impl std::fmt::Display for ErrorCode {
             ErrorCode::ServiceUnavailable => write!(f, "service_unavailable"),
         }
     }
+}
+
+impl ErrorCode {
+    /// Best-effort code for error responses that were not produced by
+    /// `AppError`, such as rejections from the auth and rate limit middleware.
+    pub fn from_status(status: http::StatusCode) -> Option<Self> {
+        match status.as_u16() {
+            400 | 422 => Some(ErrorCode::InvalidInput),
+            401 => Some(ErrorCode::Unauthorized),
+            403 => Some(ErrorCode::Forbidden),
+            404 => Some(ErrorCode::NotFound),
+            409 => Some(ErrorCode::AlreadyExists),
+            429 => Some(ErrorCode::RateLimitExceeded),
+            503 => Some(ErrorCode::ServiceUnavailable),
+            500..=599 => Some(ErrorCode::InternalError),
+            _ => None,
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,1120 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    config::Config,
+    db::PgPool,
+    models::{
+        finance::{generate_transaction_id, Currency, TransactionStatus, TransactionType},
+        lifecycle::{AccountLifecycleService, AccountStatus},
+        reversals::TransactionLinkKind,
+    },
+    observability::metrics::{record_job_outcome, record_transaction_created},
+};
+
+const MAX_REASON_LENGTH: usize = 500;
+const MAX_DESCRIPTION_LENGTH: usize = 1000;
+const MAX_FILE_NAME_LENGTH: usize = 255;
+
+const DEFAULT_LIST_LIMIT: i64 = 100;
+const MAX_LIST_LIMIT: i64 = 500;
+
+/// Remaining disputable amounts smaller than this count as nothing left.
+const AMOUNT_EPSILON: f64 = 0.005;
+
+/// `resolution_note` of disputes lost because no evidence was submitted.
+pub const EXPIRED_NOTE: &str = "No evidence was submitted before the deadline";
+
+#[derive(Debug, Clone)]
+pub struct DisputeSettings {
+    /// Directory evidence files are stored in, one subdirectory per dispute.
+    pub evidence_dir: PathBuf,
+    /// Largest evidence file accepted, after base64 decoding.
+    pub max_evidence_bytes: usize,
+    /// Deadline for evidence when the opener does not give one.
+    pub response_window: Duration,
+    /// How often overdue disputes are closed as lost.
+    pub deadline_interval: std::time::Duration,
+}
+
+impl DisputeSettings {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            evidence_dir: PathBuf::from(&config.dispute_evidence_dir),
+            max_evidence_bytes: config.dispute_evidence_max_bytes,
+            response_window: Duration::days(config.dispute_response_days),
+            deadline_interval: std::time::Duration::from_secs(config.dispute_deadline_interval_secs),
+        }
+    }
+
+    /// Request body limit for evidence uploads, allowing for base64 overhead.
+    pub fn max_evidence_body_bytes(&self) -> usize {
+        self.max_evidence_bytes / 3 * 4 + 64 * 1024
+    }
+}
+
+impl Default for DisputeSettings {
+    fn default() -> Self {
+        Self {
+            evidence_dir: PathBuf::from("./dispute_evidence"),
+            max_evidence_bytes: 5 * 1024 * 1024,
+            response_window: Duration::days(7),
+            deadline_interval: std::time::Duration::from_secs(300),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DisputeStatus {
+    /// Waiting for the merchant to submit evidence or accept the dispute.
+    NeedsResponse,
+    /// Evidence was submitted and the outcome is pending.
+    UnderReview,
+    /// The disputed amount was returned to the account.
+    Won,
+    /// The chargeback stands.
+    Lost,
+}
+
+impl std::fmt::Display for DisputeStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DisputeStatus::NeedsResponse => write!(f, "needs_response"),
+            DisputeStatus::UnderReview => write!(f, "under_review"),
+            DisputeStatus::Won => write!(f, "won"),
+            DisputeStatus::Lost => write!(f, "lost"),
+        }
+    }
+}
+
+impl std::str::FromStr for DisputeStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "needs_response" => Ok(DisputeStatus::NeedsResponse),
+            "under_review" => Ok(DisputeStatus::UnderReview),
+            "won" => Ok(DisputeStatus::Won),
+            "lost" => Ok(DisputeStatus::Lost),
+            _ => Err(format!("Invalid dispute status: {}", s)),
+        }
+    }
+}
+
+impl DisputeStatus {
+    pub const OPEN: [DisputeStatus; 2] = [DisputeStatus::NeedsResponse, DisputeStatus::UnderReview];
+
+    pub fn is_open(&self) -> bool {
+        Self::OPEN.contains(self)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DisputeOutcome {
+    Won,
+    Lost,
+}
+
+impl From<DisputeOutcome> for DisputeStatus {
+    fn from(outcome: DisputeOutcome) -> Self {
+        match outcome {
+            DisputeOutcome::Won => DisputeStatus::Won,
+            DisputeOutcome::Lost => DisputeStatus::Lost,
+        }
+    }
+}
+
+/// Something that moves a dispute to another status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisputeAction {
+    /// The merchant submits its evidence for review.
+    Submit,
+    /// The merchant accepts the chargeback.
+    Accept,
+    /// An administrator records the outcome.
+    Resolve(DisputeOutcome),
+    /// The evidence deadline passed without a response.
+    Expire,
+}
+
+impl std::fmt::Display for DisputeAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DisputeAction::Submit => write!(f, "submit"),
+            DisputeAction::Accept => write!(f, "accept"),
+            DisputeAction::Resolve(DisputeOutcome::Won) => write!(f, "resolve as won"),
+            DisputeAction::Resolve(DisputeOutcome::Lost) => write!(f, "resolve as lost"),
+            DisputeAction::Expire => write!(f, "expire"),
+        }
+    }
+}
+
+/// Status a dispute in `from` moves to on `action`.
+///
+/// Merchants respond while the dispute needs a response: submitting moves it
+/// under review, accepting loses it. Administrators can resolve any open
+/// dispute.
+pub fn transition(from: DisputeStatus, action: DisputeAction) -> Result<DisputeStatus, DisputeError> {
+    match (from, action) {
+        (DisputeStatus::NeedsResponse, DisputeAction::Submit) => Ok(DisputeStatus::UnderReview),
+        (DisputeStatus::NeedsResponse, DisputeAction::Accept) => Ok(DisputeStatus::Lost),
+        (DisputeStatus::NeedsResponse, DisputeAction::Expire) => Ok(DisputeStatus::Lost),
+        (from, DisputeAction::Resolve(outcome)) if from.is_open() => Ok(outcome.into()),
+        (from, action) => Err(DisputeError::InvalidTransition { from, action }),
+    }
+}
+
+/// Checks that the merchant can still respond to a dispute at `now`.
+pub fn check_response_window(
+    status: DisputeStatus,
+    evidence_due_by: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Result<(), DisputeError> {
+    if status != DisputeStatus::NeedsResponse {
+        return Err(DisputeError::NotAcceptingEvidence(status));
+    }
+    if now > evidence_due_by {
+        return Err(DisputeError::DeadlinePassed(evidence_due_by));
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct OpenDisputeRequest {
+    /// Completed payment being disputed
+    #[schema(example = "txn_1705320000_a1b2c3d4")]
+    pub transaction_id: String,
+
+    #[schema(example = "Cardholder does not recognize the charge")]
+    pub reason: String,
+
+    /// Disputed amount; defaults to what is left of the payment after earlier disputes
+    #[serde(default)]
+    pub amount: Option<f64>,
+
+    /// Deadline for evidence; defaults to `DISPUTE_RESPONSE_DAYS` from now
+    #[serde(default)]
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub evidence_due_by: Option<DateTime<Utc>>,
+}
+
+impl OpenDisputeRequest {
+    pub fn validate(&self, now: DateTime<Utc>) -> Result<(), String> {
+        if self.transaction_id.trim().is_empty() {
+            return Err("transaction_id cannot be empty".to_string());
+        }
+
+        validate_text("Reason", &self.reason, MAX_REASON_LENGTH)?;
+
+        if let Some(amount) = self.amount {
+            if !amount.is_finite() || amount <= 0.0 {
+                return Err("Amount must be greater than zero".to_string());
+            }
+        }
+
+        if self.evidence_due_by.is_some_and(|due| due <= now) {
+            return Err("evidence_due_by must be in the future".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+/// Rejects disputing a payment that a reversal already undid.
+pub fn check_not_reversed(transaction_id: &str, reversed_by: Option<&str>) -> Result<(), DisputeError> {
+    match reversed_by {
+        Some(reversed_by) => Err(DisputeError::Reversed {
+            transaction_id: transaction_id.to_string(),
+            reversed_by: reversed_by.to_string(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Amount a new dispute on a payment would cover. `disputed` is the total of
+/// the payment's disputes that were not won.
+pub fn disputable_amount(
+    payment_amount: f64,
+    disputed: f64,
+    requested: Option<f64>,
+    currency: Currency,
+) -> Result<f64, DisputeError> {
+    let available = payment_amount.abs() - disputed;
+
+    match requested {
+        _ if available < AMOUNT_EPSILON => Err(DisputeError::AmountExceedsPayment { available: 0.0, currency }),
+        Some(amount) if amount > available + AMOUNT_EPSILON => {
+            Err(DisputeError::AmountExceedsPayment { available, currency })
+        }
+        Some(amount) => Ok(amount),
+        None => Ok(available),
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DisputeResponse {
+    pub dispute_id: String,
+    /// The disputed payment
+    pub transaction_id: String,
+    pub account_id: String,
+    pub amount: f64,
+    pub currency: Currency,
+    pub reason: String,
+    pub status: DisputeStatus,
+    #[schema(value_type = String, format = DateTime)]
+    pub evidence_due_by: DateTime<Utc>,
+    /// Chargeback that withdrew the disputed amount when the dispute was opened
+    pub chargeback_transaction_id: String,
+    /// Adjustment that returned the amount when the dispute was won
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reversal_transaction_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolution_note: Option<String>,
+    #[schema(value_type = String, format = DateTime)]
+    pub created_at: DateTime<Utc>,
+    #[schema(value_type = String, format = DateTime)]
+    pub updated_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DisputeDetailResponse {
+    pub dispute: DisputeResponse,
+    /// Evidence attached to the dispute, oldest first
+    pub evidence: Vec<DisputeEvidenceResponse>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct EvidenceFileUpload {
+    #[schema(example = "receipt.pdf")]
+    pub file_name: String,
+
+    #[schema(example = "application/pdf")]
+    pub content_type: String,
+
+    /// File content, base64 encoded
+    pub content_base64: String,
+}
+
+impl EvidenceFileUpload {
+    /// Decodes the content, checking it against `max_bytes`.
+    pub fn decode(&self, max_bytes: usize) -> Result<Vec<u8>, DisputeError> {
+        let content = STANDARD
+            .decode(self.content_base64.trim())
+            .map_err(|_| DisputeError::InvalidEvidence("content_base64 is not valid base64".to_string()))?;
+
+        if content.is_empty() {
+            return Err(DisputeError::InvalidEvidence("Evidence file is empty".to_string()));
+        }
+        if content.len() > max_bytes {
+            return Err(DisputeError::InvalidEvidence(format!(
+                "Evidence file must not exceed {} bytes",
+                max_bytes
+            )));
+        }
+
+        Ok(content)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AddDisputeEvidenceRequest {
+    #[schema(example = "Signed delivery receipt")]
+    pub description: String,
+
+    /// Structured evidence such as tracking numbers or customer correspondence
+    #[serde(default)]
+    pub metadata: Option<serde_json::Value>,
+
+    #[serde(default)]
+    pub file: Option<EvidenceFileUpload>,
+}
+
+impl AddDisputeEvidenceRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        validate_text("Description", &self.description, MAX_DESCRIPTION_LENGTH)?;
+
+        if self.metadata.as_ref().is_some_and(|metadata| !metadata.is_object()) {
+            return Err("Evidence metadata must be a JSON object".to_string());
+        }
+
+        if let Some(file) = &self.file {
+            if sanitize_file_name(&file.file_name).is_none() {
+                return Err("Evidence file_name is invalid".to_string());
+            }
+            if file.content_type.trim().is_empty() || !file.content_type.contains('/') {
+                return Err("Evidence content_type must be a MIME type".to_string());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Reduces a client supplied file name to a safe base name, or `None` if
+/// nothing usable is left.
+pub fn sanitize_file_name(name: &str) -> Option<String> {
+    let base = name.rsplit(['/', '\\']).next().unwrap_or_default();
+    let sanitized: String = base
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') { c } else { '_' })
+        .take(MAX_FILE_NAME_LENGTH)
+        .collect();
+    let sanitized = sanitized.trim_start_matches('.').to_string();
+
+    if sanitized.is_empty() {
+        None
+    } else {
+        Some(sanitized)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct EvidenceFileInfo {
+    pub file_name: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    /// Hex encoded SHA-256 of the stored content
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DisputeEvidenceResponse {
+    pub evidence_id: String,
+    pub dispute_id: String,
+    pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Value>,
+    /// Attached file, downloadable from `/api/disputes/{dispute_id}/evidence/{evidence_id}/file`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<EvidenceFileInfo>,
+    #[schema(value_type = String, format = DateTime)]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ResolveDisputeRequest {
+    pub outcome: DisputeOutcome,
+
+    #[serde(default)]
+    #[schema(example = "Issuer accepted the delivery receipt")]
+    pub note: Option<String>,
+}
+
+impl ResolveDisputeRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        match &self.note {
+            Some(note) if note.chars().count() > MAX_REASON_LENGTH => {
+                Err(format!("Note must not exceed {} characters", MAX_REASON_LENGTH))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams, ToSchema)]
+#[into_params(parameter_in = Query)]
+pub struct DisputeListParams {
+    /// Comma-separated statuses to list; open disputes (`needs_response,under_review`) by default
+    #[serde(default)]
+    pub status: Option<String>,
+
+    /// Only disputes whose evidence is due before this instant
+    #[serde(default)]
+    #[param(value_type = Option<String>, format = DateTime)]
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub due_before: Option<DateTime<Utc>>,
+
+    #[serde(default)]
+    pub account_id: Option<String>,
+
+    /// Maximum number of disputes to return (default 100, at most 500)
+    #[serde(default)]
+    pub limit: Option<i64>,
+}
+
+impl DisputeListParams {
+    pub fn statuses(&self) -> Result<Vec<DisputeStatus>, String> {
+        match self.status.as_deref().map(str::trim) {
+            None | Some("") => Ok(DisputeStatus::OPEN.to_vec()),
+            Some(statuses) => statuses.split(',').map(|s| s.trim().parse()).collect(),
+        }
+    }
+
+    pub fn limit(&self) -> Result<i64, String> {
+        match self.limit {
+            None => Ok(DEFAULT_LIST_LIMIT),
+            Some(limit) if (1..=MAX_LIST_LIMIT).contains(&limit) => Ok(limit),
+            Some(_) => Err(format!("limit must be between 1 and {}", MAX_LIST_LIMIT)),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum DisputeError {
+    NotFound(String),
+    EvidenceNotFound(String),
+    TransactionNotFound(String),
+    /// Only completed payments can be disputed.
+    NotDisputable(String),
+    /// The payment was reversed, so its funds were already returned.
+    Reversed {
+        transaction_id: String,
+        reversed_by: String,
+    },
+    AmountExceedsPayment {
+        available: f64,
+        currency: Currency,
+    },
+    InvalidTransition {
+        from: DisputeStatus,
+        action: DisputeAction,
+    },
+    NotAcceptingEvidence(DisputeStatus),
+    DeadlinePassed(DateTime<Utc>),
+    /// Evidence has to be attached before the dispute can be submitted.
+    NoEvidence,
+    InvalidEvidence(String),
+    AccountUnavailable {
+        account_id: String,
+        status: AccountStatus,
+    },
+    Storage(std::io::Error),
+    Database(sqlx::Error),
+}
+
+impl From<sqlx::Error> for DisputeError {
+    fn from(error: sqlx::Error) -> Self {
+        DisputeError::Database(error)
+    }
+}
+
+impl From<std::io::Error> for DisputeError {
+    fn from(error: std::io::Error) -> Self {
+        DisputeError::Storage(error)
+    }
+}
+
+impl std::fmt::Display for DisputeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DisputeError::NotFound(dispute_id) => write!(f, "Dispute '{}' not found", dispute_id),
+            DisputeError::EvidenceNotFound(evidence_id) => {
+                write!(f, "Evidence file '{}' not found", evidence_id)
+            }
+            DisputeError::TransactionNotFound(transaction_id) => {
+                write!(f, "Transaction '{}' not found", transaction_id)
+            }
+            DisputeError::NotDisputable(transaction_id) => write!(
+                f,
+                "Transaction '{}' is not a completed payment and cannot be disputed",
+                transaction_id
+            ),
+            DisputeError::Reversed { transaction_id, reversed_by } => write!(
+                f,
+                "Transaction '{}' was reversed by '{}' and cannot be disputed",
+                transaction_id, reversed_by
+            ),
+            DisputeError::AmountExceedsPayment { available, currency } => write!(
+                f,
+                "Only {:.2} {} of the payment can still be disputed",
+                available, currency
+            ),
+            DisputeError::InvalidTransition { from, action } => {
+                write!(f, "Cannot {} a dispute that is {}", action, from)
+            }
+            DisputeError::NotAcceptingEvidence(status) => {
+                write!(f, "Dispute is {} and no longer accepts a response", status)
+            }
+            DisputeError::DeadlinePassed(due) => {
+                write!(f, "The evidence deadline passed at {}", due.to_rfc3339())
+            }
+            DisputeError::NoEvidence => write!(f, "Attach evidence before submitting the dispute"),
+            DisputeError::InvalidEvidence(msg) => write!(f, "{}", msg),
+            DisputeError::AccountUnavailable { account_id, status } => write!(
+                f,
+                "Account '{}' is {}; the dispute cannot be booked against it",
+                account_id, status
+            ),
+            DisputeError::Storage(e) => write!(f, "Evidence storage error: {}", e),
+            DisputeError::Database(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+fn validate_text(field: &str, value: &str, max_length: usize) -> Result<(), String> {
+    if value.trim().is_empty() {
+        return Err(format!("{} cannot be empty", field));
+    }
+
+    if value.chars().count() > max_length {
+        return Err(format!("{} must not exceed {} characters", field, max_length));
+    }
+
+    Ok(())
+}
+
+pub fn generate_dispute_id() -> String {
+    use rand::Rng;
+    let timestamp = Utc::now().timestamp();
+    let random: u32 = rand::rng().random();
+    format!("dp_{}_{:08x}", timestamp, random)
+}
+
+fn generate_evidence_id() -> String {
+    use rand::Rng;
+    let timestamp = Utc::now().timestamp();
+    let random: u32 = rand::rng().random();
+    format!("dpe_{}_{:08x}", timestamp, random)
+}
+
+const DISPUTE_COLUMNS: &str = r#"
+    dispute_id, transaction_id, account_id, amount, currency, reason, status,
+    evidence_due_by, chargeback_transaction_id, reversal_transaction_id,
+    resolution_note, created_at, updated_at, resolved_at
+"#;
+
+type DisputeRow = (
+    String,
+    String,
+    String,
+    f64,
+    String,
+    String,
+    String,
+    DateTime<Utc>,
+    String,
+    Option<String>,
+    Option<String>,
+    DateTime<Utc>,
+    DateTime<Utc>,
+    Option<DateTime<Utc>>,
+);
+
+fn dispute_from_row(row: DisputeRow) -> DisputeResponse {
+    DisputeResponse {
+        dispute_id: row.0,
+        transaction_id: row.1,
+        account_id: row.2,
+        amount: row.3,
+        currency: row.4.parse().unwrap_or_default(),
+        reason: row.5,
+        status: row.6.parse().unwrap_or(DisputeStatus::NeedsResponse),
+        evidence_due_by: row.7,
+        chargeback_transaction_id: row.8,
+        reversal_transaction_id: row.9,
+        resolution_note: row.10,
+        created_at: row.11,
+        updated_at: row.12,
+        resolved_at: row.13,
+    }
+}
+
+const EVIDENCE_COLUMNS: &str =
+    "evidence_id, dispute_id, description, metadata, file_name, content_type, size_bytes, sha256, created_at";
+
+type EvidenceRow = (
+    String,
+    String,
+    String,
+    Option<serde_json::Value>,
+    Option<String>,
+    Option<String>,
+    Option<i64>,
+    Option<String>,
+    DateTime<Utc>,
+);
+
+fn evidence_from_row(row: EvidenceRow) -> DisputeEvidenceResponse {
+    let file = match (row.4, row.5, row.6, row.7) {
+        (Some(file_name), Some(content_type), Some(size_bytes), Some(sha256)) => Some(EvidenceFileInfo {
+            file_name,
+            content_type,
+            size_bytes,
+            sha256,
+        }),
+        _ => None,
+    };
+
+    DisputeEvidenceResponse {
+        evidence_id: row.0,
+        dispute_id: row.1,
+        description: row.2,
+        metadata: row.3,
+        file,
+        created_at: row.8,
+    }
+}
+
+pub struct DisputeService;
+
+impl DisputeService {
+    /// Opens a dispute against a completed payment and books a completed
+    /// chargeback withdrawing the disputed amount from the account.
+    pub async fn open(
+        pool: &PgPool,
+        req: &OpenDisputeRequest,
+        settings: &DisputeSettings,
+    ) -> Result<DisputeResponse, DisputeError> {
+        let mut tx = pool.begin().await?;
+
+        // Locking the payment serializes disputes against it, so two cannot
+        // both claim the remaining amount.
+        let (account_id, amount, currency, transaction_type, status) =
+            sqlx::query_as::<_, (String, f64, String, String, String)>(
+                r#"
+                SELECT account_id, amount, currency, transaction_type, status
+                FROM transactions
+                WHERE transaction_id = $1
+                FOR UPDATE
+                "#
+            )
+            .bind(&req.transaction_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| DisputeError::TransactionNotFound(req.transaction_id.clone()))?;
+
+        if transaction_type != TransactionType::Payment.to_string()
+            || status != TransactionStatus::Completed.to_string()
+        {
+            return Err(DisputeError::NotDisputable(req.transaction_id.clone()));
+        }
+        let currency: Currency = currency.parse().unwrap_or_default();
+
+        let reversed_by = sqlx::query_scalar::<_, String>(
+            "SELECT transaction_id FROM transaction_links WHERE original_transaction_id = $1 AND kind = $2"
+        )
+        .bind(&req.transaction_id)
+        .bind(TransactionLinkKind::Reversal.to_string())
+        .fetch_optional(&mut *tx)
+        .await?;
+        check_not_reversed(&req.transaction_id, reversed_by.as_deref())?;
+
+        let disputed = sqlx::query_scalar::<_, f64>(
+            "SELECT COALESCE(SUM(amount), 0) FROM disputes WHERE transaction_id = $1 AND status <> 'won'"
+        )
+        .bind(&req.transaction_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        let dispute_amount = disputable_amount(amount, disputed, req.amount, currency)?;
+
+        Self::check_account(&mut tx, &account_id).await?;
+
+        let dispute_id = generate_dispute_id();
+        let chargeback_id = generate_transaction_id();
+        sqlx::query(
+            r#"
+            INSERT INTO transactions (
+                transaction_id, account_id, amount, currency,
+                transaction_type, status, description, metadata,
+                created_at, processed_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, NOW(), NOW())
+            "#
+        )
+        .bind(&chargeback_id)
+        .bind(&account_id)
+        .bind(-dispute_amount)
+        .bind(currency.to_string())
+        .bind(TransactionType::Chargeback.to_string())
+        .bind(TransactionStatus::Completed.to_string())
+        .bind(format!("Chargeback for dispute {}", dispute_id))
+        .bind(json!({ "dispute_id": dispute_id, "disputed_transaction_id": req.transaction_id }))
+        .execute(&mut *tx)
+        .await?;
+
+        let due_by = req
+            .evidence_due_by
+            .unwrap_or_else(|| Utc::now() + settings.response_window);
+        let row = sqlx::query_as::<_, DisputeRow>(&format!(
+            r#"
+            INSERT INTO disputes (
+                dispute_id, transaction_id, account_id, amount, currency,
+                reason, status, evidence_due_by, chargeback_transaction_id
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING {}
+            "#,
+            DISPUTE_COLUMNS
+        ))
+        .bind(&dispute_id)
+        .bind(&req.transaction_id)
+        .bind(&account_id)
+        .bind(dispute_amount)
+        .bind(currency.to_string())
+        .bind(req.reason.trim())
+        .bind(DisputeStatus::NeedsResponse.to_string())
+        .bind(due_by)
+        .bind(&chargeback_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        record_transaction_created(&TransactionType::Chargeback.to_string(), &currency.to_string());
+
+        Ok(dispute_from_row(row))
+    }
+
+    /// Rejects booking against a closed account. Frozen accounts still take
+    /// dispute entries, which are imposed rather than requested.
+    async fn check_account(conn: &mut sqlx::PgConnection, account_id: &str) -> Result<(), DisputeError> {
+        match AccountLifecycleService::lock_status(conn, account_id).await? {
+            Some(AccountStatus::Closed) => Err(DisputeError::AccountUnavailable {
+                account_id: account_id.to_string(),
+                status: AccountStatus::Closed,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    async fn lock(conn: &mut sqlx::PgConnection, dispute_id: &str) -> Result<DisputeResponse, DisputeError> {
+        let row = sqlx::query_as::<_, DisputeRow>(&format!(
+            "SELECT {} FROM disputes WHERE dispute_id = $1 FOR UPDATE",
+            DISPUTE_COLUMNS
+        ))
+        .bind(dispute_id)
+        .fetch_optional(conn)
+        .await?
+        .ok_or_else(|| DisputeError::NotFound(dispute_id.to_string()))?;
+
+        Ok(dispute_from_row(row))
+    }
+
+    pub async fn get(pool: &PgPool, dispute_id: &str) -> Result<DisputeDetailResponse, DisputeError> {
+        let row = sqlx::query_as::<_, DisputeRow>(&format!(
+            "SELECT {} FROM disputes WHERE dispute_id = $1",
+            DISPUTE_COLUMNS
+        ))
+        .bind(dispute_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| DisputeError::NotFound(dispute_id.to_string()))?;
+
+        let evidence = sqlx::query_as::<_, EvidenceRow>(&format!(
+            "SELECT {} FROM dispute_evidence WHERE dispute_id = $1 ORDER BY created_at, evidence_id",
+            EVIDENCE_COLUMNS
+        ))
+        .bind(dispute_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(DisputeDetailResponse {
+            dispute: dispute_from_row(row),
+            evidence: evidence.into_iter().map(evidence_from_row).collect(),
+        })
+    }
+
+    /// Disputes in any of `statuses`, soonest deadline first.
+    pub async fn list(
+        pool: &PgPool,
+        statuses: &[DisputeStatus],
+        due_before: Option<DateTime<Utc>>,
+        account_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<DisputeResponse>, sqlx::Error> {
+        let statuses: Vec<String> = statuses.iter().map(|s| s.to_string()).collect();
+
+        let rows = sqlx::query_as::<_, DisputeRow>(&format!(
+            r#"
+            SELECT {}
+            FROM disputes
+            WHERE status = ANY($1)
+              AND ($2::timestamptz IS NULL OR evidence_due_by < $2)
+              AND ($3::text IS NULL OR account_id = $3)
+            ORDER BY evidence_due_by ASC, dispute_id ASC
+            LIMIT $4
+            "#,
+            DISPUTE_COLUMNS
+        ))
+        .bind(&statuses)
+        .bind(due_before)
+        .bind(account_id)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows.into_iter().map(dispute_from_row).collect())
+    }
+
+    /// Attaches evidence to a dispute still waiting for a response. A file is
+    /// stored under `evidence_dir/<dispute_id>/<evidence_id>` before the
+    /// evidence row is written and removed again if that fails.
+    pub async fn add_evidence(
+        pool: &PgPool,
+        dispute_id: &str,
+        req: &AddDisputeEvidenceRequest,
+        settings: &DisputeSettings,
+    ) -> Result<DisputeEvidenceResponse, DisputeError> {
+        let content = req
+            .file
+            .as_ref()
+            .map(|file| file.decode(settings.max_evidence_bytes))
+            .transpose()?;
+
+        let mut tx = pool.begin().await?;
+        let dispute = Self::lock(&mut tx, dispute_id).await?;
+        check_response_window(dispute.status, dispute.evidence_due_by, Utc::now())?;
+
+        let evidence_id = generate_evidence_id();
+        let stored = match (&req.file, &content) {
+            (Some(file), Some(content)) => {
+                let path = settings.evidence_dir.join(dispute_id).join(&evidence_id);
+                write_evidence_file(&path, content).await?;
+                Some((file, content, path))
+            }
+            _ => None,
+        };
+
+        let result = sqlx::query_as::<_, EvidenceRow>(&format!(
+            r#"
+            INSERT INTO dispute_evidence (
+                evidence_id, dispute_id, description, metadata, file_name,
+                content_type, size_bytes, sha256, storage_path
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING {}
+            "#,
+            EVIDENCE_COLUMNS
+        ))
+        .bind(&evidence_id)
+        .bind(dispute_id)
+        .bind(req.description.trim())
+        .bind(&req.metadata)
+        .bind(stored.as_ref().and_then(|(file, _, _)| sanitize_file_name(&file.file_name)))
+        .bind(stored.as_ref().map(|(file, _, _)| file.content_type.trim()))
+        .bind(stored.as_ref().map(|(_, content, _)| content.len() as i64))
+        .bind(stored.as_ref().map(|(_, content, _)| format!("{:x}", Sha256::digest(content))))
+        .bind(stored.as_ref().map(|(_, _, path)| path.to_string_lossy().to_string()))
+        .fetch_one(&mut *tx)
+        .await;
+
+        let row = match result {
+            Ok(row) => row,
+            Err(e) => {
+                if let Some((_, _, path)) = &stored {
+                    let _ = tokio::fs::remove_file(path).await;
+                }
+                return Err(e.into());
+            }
+        };
+
+        sqlx::query("UPDATE disputes SET updated_at = NOW() WHERE dispute_id = $1")
+            .bind(dispute_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(evidence_from_row(row))
+    }
+
+    /// File of an evidence item and where it is stored.
+    pub async fn evidence_file(
+        pool: &PgPool,
+        dispute_id: &str,
+        evidence_id: &str,
+    ) -> Result<(EvidenceFileInfo, PathBuf), DisputeError> {
+        let row = sqlx::query_as::<_, (Option<String>, Option<String>, Option<i64>, Option<String>, Option<String>)>(
+            r#"
+            SELECT file_name, content_type, size_bytes, sha256, storage_path
+            FROM dispute_evidence
+            WHERE dispute_id = $1 AND evidence_id = $2
+            "#
+        )
+        .bind(dispute_id)
+        .bind(evidence_id)
+        .fetch_optional(pool)
+        .await?;
+
+        match row {
+            Some((Some(file_name), Some(content_type), Some(size_bytes), Some(sha256), Some(path))) => Ok((
+                EvidenceFileInfo {
+                    file_name,
+                    content_type,
+                    size_bytes,
+                    sha256,
+                },
+                PathBuf::from(path),
+            )),
+            _ => Err(DisputeError::EvidenceNotFound(evidence_id.to_string())),
+        }
+    }
+
+    /// Submits the attached evidence for review.
+    pub async fn submit(pool: &PgPool, dispute_id: &str) -> Result<DisputeResponse, DisputeError> {
+        let mut tx = pool.begin().await?;
+        let dispute = Self::lock(&mut tx, dispute_id).await?;
+        check_response_window(dispute.status, dispute.evidence_due_by, Utc::now())?;
+        let to = transition(dispute.status, DisputeAction::Submit)?;
+
+        let evidence = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM dispute_evidence WHERE dispute_id = $1"
+        )
+        .bind(dispute_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        if evidence == 0 {
+            return Err(DisputeError::NoEvidence);
+        }
+
+        let dispute = Self::set_status(&mut tx, dispute_id, to, None, None).await?;
+        tx.commit().await?;
+
+        Ok(dispute)
+    }
+
+    /// Accepts the chargeback; the dispute is lost and the funds stay withdrawn.
+    pub async fn accept(pool: &PgPool, dispute_id: &str) -> Result<DisputeResponse, DisputeError> {
+        let mut tx = pool.begin().await?;
+        let dispute = Self::lock(&mut tx, dispute_id).await?;
+        let to = transition(dispute.status, DisputeAction::Accept)?;
+
+        let dispute = Self::set_status(&mut tx, dispute_id, to, Some("Accepted by merchant"), None).await?;
+        tx.commit().await?;
+
+        Ok(dispute)
+    }
+
+    /// Records the outcome of an open dispute. Winning books a completed
+    /// adjustment returning the disputed amount to the account.
+    pub async fn resolve(
+        pool: &PgPool,
+        dispute_id: &str,
+        req: &ResolveDisputeRequest,
+    ) -> Result<DisputeResponse, DisputeError> {
+        let mut tx = pool.begin().await?;
+        let dispute = Self::lock(&mut tx, dispute_id).await?;
+        let to = transition(dispute.status, DisputeAction::Resolve(req.outcome))?;
+
+        let mut reversal = None;
+        if to == DisputeStatus::Won {
+            Self::check_account(&mut tx, &dispute.account_id).await?;
+
+            let transaction_id = generate_transaction_id();
+            sqlx::query(
+                r#"
+                INSERT INTO transactions (
+                    transaction_id, account_id, amount, currency,
+                    transaction_type, status, description, metadata,
+                    created_at, processed_at
+                )
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, NOW(), NOW())
+                "#
+            )
+            .bind(&transaction_id)
+            .bind(&dispute.account_id)
+            .bind(dispute.amount)
+            .bind(dispute.currency.to_string())
+            .bind(TransactionType::Adjustment.to_string())
+            .bind(TransactionStatus::Completed.to_string())
+            .bind(format!("Dispute {} won", dispute_id))
+            .bind(json!({ "dispute_id": dispute_id, "reverses": dispute.chargeback_transaction_id }))
+            .execute(&mut *tx)
+            .await?;
+            reversal = Some(transaction_id);
+        }
+
+        let note = req.note.as_deref().map(str::trim).filter(|note| !note.is_empty());
+        let resolved = Self::set_status(&mut tx, dispute_id, to, note, reversal.as_deref()).await?;
+        tx.commit().await?;
+
+        if reversal.is_some() {
+            record_transaction_created(&TransactionType::Adjustment.to_string(), &dispute.currency.to_string());
+        }
+
+        Ok(resolved)
+    }
+
+    async fn set_status(
+        conn: &mut sqlx::PgConnection,
+        dispute_id: &str,
+        status: DisputeStatus,
+        note: Option<&str>,
+        reversal_transaction_id: Option<&str>,
+    ) -> Result<DisputeResponse, sqlx::Error> {
+        let row = sqlx::query_as::<_, DisputeRow>(&format!(
+            r#"
+            UPDATE disputes
+            SET status = $2,
+                resolution_note = COALESCE($3, resolution_note),
+                reversal_transaction_id = COALESCE($4, reversal_transaction_id),
+                resolved_at = CASE WHEN $5 THEN NOW() ELSE resolved_at END,
+                updated_at = NOW()
+            WHERE dispute_id = $1
+            RETURNING {}
+            "#,
+            DISPUTE_COLUMNS
+        ))
+        .bind(dispute_id)
+        .bind(status.to_string())
+        .bind(note)
+        .bind(reversal_transaction_id)
+        .bind(!status.is_open())
+        .fetch_one(conn)
+        .await?;
+
+        Ok(dispute_from_row(row))
+    }
+
+    /// Closes disputes still waiting for a response after their deadline as
+    /// lost. Returns how many were closed.
+    pub async fn expire_overdue(pool: &PgPool, now: DateTime<Utc>) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE disputes
+            SET status = $1, resolution_note = $2, resolved_at = NOW(), updated_at = NOW()
+            WHERE status = $3 AND evidence_due_by < $4
+            "#
+        )
+        .bind(DisputeStatus::Lost.to_string())
+        .bind(EXPIRED_NOTE)
+        .bind(DisputeStatus::NeedsResponse.to_string())
+        .bind(now)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+async fn write_evidence_file(path: &Path, content: &[u8]) -> Result<(), std::io::Error> {
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir).await?;
+    }
+
+    tokio::fs::write(path, content).await
+}
+
+pub fn spawn_dispute_deadline_worker(pool: PgPool, settings: DisputeSettings) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(settings.deadline_interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            match DisputeService::expire_overdue(&pool, Utc::now()).await {
+                Ok(expired) => {
+                    if expired > 0 {
+                        tracing::info!("Closed {} overdue disputes as lost", expired);
+                    }
+                    record_job_outcome("dispute_deadline", "success");
+                }
+                Err(e) => {
+                    tracing::error!("Dispute deadline run failed: {}", e);
+                    record_job_outcome("dispute_deadline", "failure");
+                }
+            }
+        }
+    });
+}
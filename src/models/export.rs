@@ -0,0 +1,588 @@
+use axum::{extract::Query, http::Uri};
+use chrono::{DateTime, SecondsFormat, Utc};
+use futures_util::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use std::{path::PathBuf, time::Duration};
+use tokio::io::{AsyncWrite, AsyncWriteExt, BufWriter};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    config::Config,
+    db::PgPool,
+    models::{finance::TransactionFilters, responses::TransactionResponse},
+    observability::metrics::record_job_outcome,
+};
+
+/// CSV columns, in order. NDJSON lines carry the same fields as
+/// `TransactionResponse`.
+pub const EXPORT_COLUMNS: &[&str] = &[
+    "transaction_id",
+    "account_id",
+    "amount",
+    "currency",
+    "transaction_type",
+    "status",
+    "description",
+    "metadata",
+    "created_at",
+    "processed_at",
+];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    /// Header row followed by one row per transaction, columns as in `EXPORT_COLUMNS`.
+    #[default]
+    Csv,
+    /// One `TransactionResponse` JSON object per line.
+    Ndjson,
+}
+
+impl std::fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExportFormat::Csv => write!(f, "csv"),
+            ExportFormat::Ndjson => write!(f, "ndjson"),
+        }
+    }
+}
+
+impl std::str::FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "csv" => Ok(ExportFormat::Csv),
+            "ndjson" => Ok(ExportFormat::Ndjson),
+            _ => Err(format!("Invalid export format: {}", s)),
+        }
+    }
+}
+
+impl ExportFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Ndjson => "application/x-ndjson",
+        }
+    }
+
+    pub fn file_name(&self, stem: &str) -> String {
+        format!("{}.{}", stem, self)
+    }
+
+    /// Text written before the first row.
+    pub fn header(&self) -> String {
+        match self {
+            ExportFormat::Csv => format!("{}\n", EXPORT_COLUMNS.join(",")),
+            ExportFormat::Ndjson => String::new(),
+        }
+    }
+
+    /// One transaction as a line of output, including the trailing newline.
+    pub fn row(&self, transaction: &TransactionResponse) -> String {
+        match self {
+            ExportFormat::Csv => {
+                let fields = [
+                    transaction.transaction_id.clone(),
+                    transaction.account_id.clone(),
+                    transaction.amount.to_string(),
+                    transaction.currency.to_string(),
+                    transaction.transaction_type.to_string(),
+                    transaction.status.to_string(),
+                    transaction.description.clone().unwrap_or_default(),
+                    transaction
+                        .metadata
+                        .as_ref()
+                        .map(|metadata| metadata.to_string())
+                        .unwrap_or_default(),
+                    export_timestamp(&transaction.created_at),
+                    transaction
+                        .processed_at
+                        .as_ref()
+                        .map(export_timestamp)
+                        .unwrap_or_default(),
+                ];
+
+                let mut line = fields
+                    .iter()
+                    .map(|field| csv_field(field))
+                    .collect::<Vec<_>>()
+                    .join(",");
+                line.push('\n');
+                line
+            }
+            ExportFormat::Ndjson => {
+                let mut line = serde_json::to_string(transaction).unwrap_or_default();
+                line.push('\n');
+                line
+            }
+        }
+    }
+}
+
+fn export_timestamp(ts: &DateTime<Utc>) -> String {
+    ts.to_rfc3339_opts(SecondsFormat::Micros, true)
+}
+
+/// Quotes a CSV field when it contains a delimiter, quote or line break.
+/// Fields starting with a formula character are prefixed with `'` so
+/// spreadsheets do not evaluate them.
+pub fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) && value.parse::<f64>().is_err() {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ExportParams {
+    /// `csv` (default) or `ndjson`
+    #[serde(default)]
+    pub format: ExportFormat,
+    /// Write the file in the background and return a job to poll
+    #[serde(default)]
+    pub background: bool,
+}
+
+/// Parses the transaction filters of an export from its raw query string.
+pub fn parse_export_filters(query: &str) -> Result<TransactionFilters, String> {
+    let uri: Uri = format!("/?{}", query)
+        .parse()
+        .map_err(|e: axum::http::uri::InvalidUri| e.to_string())?;
+
+    Query::<TransactionFilters>::try_from_uri(&uri)
+        .map(|Query(filters)| filters)
+        .map_err(|e| e.body_text())
+}
+
+/// Query selecting every transaction matching `filters` in their sort order.
+pub fn export_query(filters: &TransactionFilters) -> (String, Vec<String>) {
+    let (where_clause, bind_values) = filters.where_clause();
+    let (sort_by, sort_order) = filters.sort();
+    let (sort_column, _) = sort_by.column();
+
+    let query = format!(
+        r#"
+        SELECT
+            transaction_id, account_id, amount, currency,
+            transaction_type, status, description, metadata,
+            created_at, processed_at, failure_reason
+        FROM transactions
+        {}
+        ORDER BY {} {}, transaction_id {}
+        "#,
+        where_clause,
+        sort_column,
+        sort_order.sql(),
+        sort_order.sql()
+    );
+
+    (query, bind_values)
+}
+
+pub(crate) type TransactionRow = (
+    String,
+    String,
+    f64,
+    String,
+    String,
+    String,
+    Option<String>,
+    Option<serde_json::Value>,
+    DateTime<Utc>,
+    Option<DateTime<Utc>>,
+    Option<String>,
+);
+
+pub(crate) fn transaction_from_row(t: TransactionRow) -> TransactionResponse {
+    TransactionResponse {
+        transaction_id: t.0,
+        account_id: t.1,
+        amount: t.2,
+        currency: t.3.parse().unwrap_or_default(),
+        transaction_type: t.4.parse().unwrap_or_default(),
+        status: t.5.parse().unwrap_or_default(),
+        description: t.6,
+        metadata: t.7,
+        created_at: t.8,
+        processed_at: t.9,
+        failure_reason: t.10.and_then(|r| r.parse().ok()),
+        fee: None,
+        links: None,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportJobStatus {
+    Pending,
+    Completed,
+    Failed,
+    /// The file was removed after `expires_at`.
+    Expired,
+}
+
+impl std::fmt::Display for ExportJobStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExportJobStatus::Pending => write!(f, "pending"),
+            ExportJobStatus::Completed => write!(f, "completed"),
+            ExportJobStatus::Failed => write!(f, "failed"),
+            ExportJobStatus::Expired => write!(f, "expired"),
+        }
+    }
+}
+
+impl std::str::FromStr for ExportJobStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "pending" => Ok(ExportJobStatus::Pending),
+            "completed" => Ok(ExportJobStatus::Completed),
+            "failed" => Ok(ExportJobStatus::Failed),
+            "expired" => Ok(ExportJobStatus::Expired),
+            _ => Err(format!("Invalid export job status: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ExportJobResponse {
+    pub job_id: String,
+    pub format: ExportFormat,
+    pub status: ExportJobStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub row_count: Option<i64>,
+    /// Where to fetch the file while the job is completed and unexpired.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub download_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[schema(value_type = String, format = DateTime)]
+    pub created_at: DateTime<Utc>,
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+pub fn export_download_url(job_id: &str) -> String {
+    format!("/api/transactions/exports/{}/download", job_id)
+}
+
+#[derive(Debug, Clone)]
+pub struct ExportSettings {
+    /// Directory export files are written to.
+    pub dir: PathBuf,
+    /// How long a finished export can be downloaded.
+    pub ttl: Duration,
+    /// Exports matching more rows than this run as a background job.
+    pub async_threshold: i64,
+    /// How often the worker looks for pending and expired jobs.
+    pub poll_interval: Duration,
+}
+
+impl ExportSettings {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            dir: PathBuf::from(&config.export_dir),
+            ttl: Duration::from_secs(config.export_ttl_secs),
+            async_threshold: config.export_async_threshold,
+            poll_interval: Duration::from_millis(config.export_job_poll_ms.max(1)),
+        }
+    }
+}
+
+impl Default for ExportSettings {
+    fn default() -> Self {
+        Self {
+            dir: PathBuf::from("./exports"),
+            ttl: Duration::from_secs(24 * 60 * 60),
+            async_threshold: 100_000,
+            poll_interval: Duration::from_secs(1),
+        }
+    }
+}
+
+pub fn generate_export_job_id() -> String {
+    use rand::Rng;
+    let timestamp = Utc::now().timestamp();
+    let random: u32 = rand::rng().random();
+    format!("exp_{}_{:08x}", timestamp, random)
+}
+
+pub struct ExportService;
+
+impl ExportService {
+    pub async fn count(pool: &PgPool, filters: &TransactionFilters) -> Result<i64, sqlx::Error> {
+        let (where_clause, bind_values) = filters.where_clause();
+        let query = format!("SELECT COUNT(*) FROM transactions {}", where_clause);
+
+        let mut sql_query = sqlx::query_scalar::<_, i64>(&query);
+        for value in &bind_values {
+            sql_query = sql_query.bind(value);
+        }
+
+        sql_query.fetch_one(pool).await
+    }
+
+    /// Streams every matching transaction into `writer` row by row and returns
+    /// the number of rows written.
+    pub async fn write<W: AsyncWrite + Unpin>(
+        pool: &PgPool,
+        filters: &TransactionFilters,
+        format: ExportFormat,
+        writer: W,
+    ) -> Result<i64, anyhow::Error> {
+        let mut writer = BufWriter::new(writer);
+        writer.write_all(format.header().as_bytes()).await?;
+
+        let (query, bind_values) = export_query(filters);
+        let mut sql_query = sqlx::query_as::<_, TransactionRow>(&query);
+        for value in &bind_values {
+            sql_query = sql_query.bind(value);
+        }
+
+        let mut rows = sql_query.fetch(pool);
+        let mut count = 0;
+        while let Some(row) = rows.try_next().await? {
+            let transaction = transaction_from_row(row);
+            writer.write_all(format.row(&transaction).as_bytes()).await?;
+            count += 1;
+        }
+
+        writer.flush().await?;
+        Ok(count)
+    }
+
+    pub async fn enqueue(
+        pool: &PgPool,
+        key_id: Option<&str>,
+        format: ExportFormat,
+        query: &str,
+    ) -> Result<ExportJobResponse, sqlx::Error> {
+        let job_id = generate_export_job_id();
+
+        let created_at = sqlx::query_scalar::<_, DateTime<Utc>>(
+            r#"
+            INSERT INTO export_jobs (job_id, key_id, format, query, status)
+            VALUES ($1, $2, $3, $4, 'pending')
+            RETURNING created_at
+            "#
+        )
+        .bind(&job_id)
+        .bind(key_id)
+        .bind(format.to_string())
+        .bind(query)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(ExportJobResponse {
+            job_id,
+            format,
+            status: ExportJobStatus::Pending,
+            row_count: None,
+            download_url: None,
+            expires_at: None,
+            error: None,
+            created_at,
+            completed_at: None,
+        })
+    }
+
+    /// Fetches a job with its file path, limited to jobs created by `key_id`
+    /// unless `None` (admin).
+    pub async fn get_job(
+        pool: &PgPool,
+        job_id: &str,
+        key_id: Option<&str>,
+    ) -> Result<Option<(ExportJobResponse, Option<String>)>, sqlx::Error> {
+        let row = sqlx::query_as::<_, (
+            String,
+            String,
+            String,
+            Option<i64>,
+            Option<String>,
+            Option<DateTime<Utc>>,
+            Option<String>,
+            DateTime<Utc>,
+            Option<DateTime<Utc>>,
+        )>(
+            r#"
+            SELECT job_id, format, status, row_count, file_path,
+                   expires_at, error, created_at, completed_at
+            FROM export_jobs
+            WHERE job_id = $1 AND ($2::text IS NULL OR key_id = $2)
+            "#
+        )
+        .bind(job_id)
+        .bind(key_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(row.map(|row| {
+            let mut status = row.2.parse().unwrap_or(ExportJobStatus::Pending);
+            // The worker removes expired files periodically; report them as
+            // expired as soon as the deadline passes.
+            if status == ExportJobStatus::Completed
+                && row.5.is_some_and(|expires_at| expires_at <= Utc::now())
+            {
+                status = ExportJobStatus::Expired;
+            }
+
+            let job = ExportJobResponse {
+                download_url: (status == ExportJobStatus::Completed)
+                    .then(|| export_download_url(&row.0)),
+                job_id: row.0,
+                format: row.1.parse().unwrap_or_default(),
+                status,
+                row_count: row.3,
+                expires_at: row.5,
+                error: row.6,
+                created_at: row.7,
+                completed_at: row.8,
+            };
+            (job, row.4)
+        }))
+    }
+
+    /// Claims and runs the oldest pending job, writing its file under
+    /// `settings.dir`. Returns `false` when there is nothing to do.
+    pub async fn run_next_job(pool: &PgPool, settings: &ExportSettings) -> Result<bool, anyhow::Error> {
+        let mut tx = pool.begin().await?;
+
+        let job = sqlx::query_as::<_, (String, String, String)>(
+            r#"
+            SELECT job_id, format, query
+            FROM export_jobs
+            WHERE status = 'pending'
+            ORDER BY created_at
+            LIMIT 1
+            FOR UPDATE SKIP LOCKED
+            "#
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some((job_id, format, query)) = job else {
+            return Ok(false);
+        };
+
+        let format: ExportFormat = format.parse().unwrap_or_default();
+        let path = settings.dir.join(format.file_name(&job_id));
+
+        match Self::write_file(pool, &query, format, &path).await {
+            Ok(row_count) => {
+                sqlx::query(
+                    r#"
+                    UPDATE export_jobs
+                    SET status = 'completed', row_count = $2, file_path = $3,
+                        completed_at = NOW(), expires_at = NOW() + make_interval(secs => $4)
+                    WHERE job_id = $1
+                    "#
+                )
+                .bind(&job_id)
+                .bind(row_count)
+                .bind(path.to_string_lossy().to_string())
+                .bind(settings.ttl.as_secs_f64())
+                .execute(&mut *tx)
+                .await?;
+                tx.commit().await?;
+                Ok(true)
+            }
+            Err(e) => {
+                let _ = tokio::fs::remove_file(&path).await;
+                sqlx::query(
+                    r#"
+                    UPDATE export_jobs
+                    SET status = 'failed', error = $2, completed_at = NOW()
+                    WHERE job_id = $1
+                    "#
+                )
+                .bind(&job_id)
+                .bind(e.to_string())
+                .execute(&mut *tx)
+                .await?;
+                tx.commit().await?;
+                Err(e)
+            }
+        }
+    }
+
+    async fn write_file(
+        pool: &PgPool,
+        query: &str,
+        format: ExportFormat,
+        path: &std::path::Path,
+    ) -> Result<i64, anyhow::Error> {
+        let filters = parse_export_filters(query).map_err(anyhow::Error::msg)?;
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+
+        let file = tokio::fs::File::create(path).await?;
+        Self::write(pool, &filters, format, file).await
+    }
+
+    /// Deletes the files of exports past their expiry.
+    pub async fn remove_expired(pool: &PgPool) -> Result<u64, sqlx::Error> {
+        let expired = sqlx::query_as::<_, (String, Option<String>)>(
+            r#"
+            UPDATE export_jobs
+            SET status = 'expired', file_path = NULL
+            WHERE status = 'completed' AND expires_at <= NOW()
+            RETURNING job_id, file_path
+            "#
+        )
+        .fetch_all(pool)
+        .await?;
+
+        for (job_id, path) in &expired {
+            if let Some(path) = path {
+                if let Err(e) = tokio::fs::remove_file(path).await {
+                    tracing::warn!("Failed to remove export file for {}: {}", job_id, e);
+                }
+            }
+        }
+
+        Ok(expired.len() as u64)
+    }
+}
+
+/// Runs pending export jobs and removes expired files for the life of the process.
+pub fn spawn_export_worker(pool: PgPool, settings: ExportSettings) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(settings.poll_interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            loop {
+                match ExportService::run_next_job(&pool, &settings).await {
+                    Ok(true) => record_job_outcome("transaction_export", "success"),
+                    Ok(false) => break,
+                    Err(e) => {
+                        tracing::error!("Transaction export job failed: {}", e);
+                        record_job_outcome("transaction_export", "failure");
+                        break;
+                    }
+                }
+            }
+
+            if let Err(e) = ExportService::remove_expired(&pool).await {
+                tracing::error!("Failed to remove expired exports: {}", e);
+            }
+        }
+    });
+}
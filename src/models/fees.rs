@@ -0,0 +1,848 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    db::PgPool,
+    models::{
+        finance::{generate_transaction_id, Currency, TransactionStatus, TransactionType},
+        responses::TransactionResponse,
+    },
+    observability::metrics::record_transaction_created,
+};
+
+const MAX_NAME_LENGTH: usize = 100;
+const MAX_DESCRIPTION_LENGTH: usize = 500;
+const MAX_PLAN_LENGTH: usize = 64;
+const MAX_RULES: usize = 100;
+const MAX_TIERS: usize = 20;
+
+/// Fees smaller than this round to nothing and are not charged.
+const FEE_EPSILON: f64 = 0.005;
+
+/// Default window of the revenue report.
+const DEFAULT_REPORT_DAYS: i64 = 30;
+
+fn round_cents(amount: f64) -> f64 {
+    (amount * 100.0).round() / 100.0
+}
+
+/// Price band of a tiered rule, covering amounts up to `up_to`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct FeeTier {
+    /// Largest transaction amount in the tier; the last tier has none
+    #[serde(default)]
+    #[schema(example = 1000.0)]
+    pub up_to: Option<f64>,
+
+    #[serde(default)]
+    #[schema(example = 0.30)]
+    pub fixed: f64,
+
+    /// Percentage of the transaction amount, e.g. `2.9` for 2.9%
+    #[serde(default)]
+    #[schema(example = 2.9)]
+    pub percentage: f64,
+}
+
+/// Fee for one transaction type in one currency. A rule charges `fixed`
+/// plus `percentage` of the amount, or, with `tiers`, the fixed and
+/// percentage of the tier the whole amount falls in. The result is then
+/// raised to `min_fee` or lowered to `max_fee`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct FeeRule {
+    pub transaction_type: TransactionType,
+    pub currency: Currency,
+
+    #[serde(default)]
+    pub fixed: f64,
+
+    /// Percentage of the transaction amount, e.g. `2.9` for 2.9%
+    #[serde(default)]
+    pub percentage: f64,
+
+    /// Amount bands in ascending order; replaces `fixed` and `percentage`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tiers: Vec<FeeTier>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_fee: Option<f64>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_fee: Option<f64>,
+}
+
+fn validate_charge(label: &str, fixed: f64, percentage: f64) -> Result<(), String> {
+    if !fixed.is_finite() || fixed < 0.0 {
+        return Err(format!("{}: fixed must not be negative", label));
+    }
+    if !percentage.is_finite() || !(0.0..=100.0).contains(&percentage) {
+        return Err(format!("{}: percentage must be between 0 and 100", label));
+    }
+
+    Ok(())
+}
+
+impl FeeRule {
+    pub fn validate(&self) -> Result<(), String> {
+        let label = format!("Rule for {} in {}", self.transaction_type, self.currency);
+
+        if matches!(self.transaction_type, TransactionType::Fee | TransactionType::Reversal) {
+            return Err(format!("Fees cannot be charged on {} transactions", self.transaction_type));
+        }
+
+        validate_charge(&label, self.fixed, self.percentage)?;
+
+        if !self.tiers.is_empty() {
+            if self.fixed != 0.0 || self.percentage != 0.0 {
+                return Err(format!("{}: set fixed and percentage per tier, not on a tiered rule", label));
+            }
+            if self.tiers.len() > MAX_TIERS {
+                return Err(format!("{}: at most {} tiers are allowed", label, MAX_TIERS));
+            }
+
+            let mut previous = 0.0;
+            for (index, tier) in self.tiers.iter().enumerate() {
+                let last = index == self.tiers.len() - 1;
+                validate_charge(&format!("{}, tier {}", label, index + 1), tier.fixed, tier.percentage)?;
+
+                match tier.up_to {
+                    None if !last => {
+                        return Err(format!("{}: only the last tier can be unbounded", label));
+                    }
+                    None => {}
+                    Some(_) if last => {
+                        return Err(format!("{}: the last tier must be unbounded", label));
+                    }
+                    Some(up_to) if !up_to.is_finite() || up_to <= previous => {
+                        return Err(format!("{}: tier bounds must be positive and ascending", label));
+                    }
+                    Some(up_to) => previous = up_to,
+                }
+            }
+        }
+
+        for (name, cap) in [("min_fee", self.min_fee), ("max_fee", self.max_fee)] {
+            if cap.is_some_and(|cap| !cap.is_finite() || cap < 0.0) {
+                return Err(format!("{}: {} must not be negative", label, name));
+            }
+        }
+        if let (Some(min), Some(max)) = (self.min_fee, self.max_fee) {
+            if min > max {
+                return Err(format!("{}: min_fee must not exceed max_fee", label));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Fee on a transaction of `amount`, or `None` if nothing is charged.
+    pub fn compute(&self, amount: f64) -> Option<FeeBreakdown> {
+        let amount = amount.abs();
+
+        let (tier, fixed, percentage) = match self
+            .tiers
+            .iter()
+            .position(|tier| tier.up_to.is_none_or(|up_to| amount <= up_to))
+        {
+            Some(index) => (Some(index + 1), self.tiers[index].fixed, self.tiers[index].percentage),
+            None => (None, self.fixed, self.percentage),
+        };
+
+        let percentage_amount = amount * percentage / 100.0;
+        let subtotal = fixed + percentage_amount;
+
+        let (total, capped) = match (self.min_fee, self.max_fee) {
+            (Some(min), _) if subtotal < min => (min, Some(FeeCap::Min)),
+            (_, Some(max)) if subtotal > max => (max, Some(FeeCap::Max)),
+            _ => (subtotal, None),
+        };
+        let total = round_cents(total);
+
+        if total < FEE_EPSILON {
+            return None;
+        }
+
+        Some(FeeBreakdown {
+            tier,
+            fixed,
+            percentage,
+            percentage_amount: round_cents(percentage_amount),
+            subtotal: round_cents(subtotal),
+            capped,
+            total,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeCap {
+    /// Raised to the rule's `min_fee`.
+    Min,
+    /// Lowered to the rule's `max_fee`.
+    Max,
+}
+
+/// How a fee was worked out.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct FeeBreakdown {
+    /// 1-based tier the amount fell in, for tiered rules
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tier: Option<usize>,
+    pub fixed: f64,
+    pub percentage: f64,
+    pub percentage_amount: f64,
+    /// `fixed` plus `percentage_amount`, before caps
+    pub subtotal: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capped: Option<FeeCap>,
+    /// Fee charged
+    pub total: f64,
+}
+
+impl FeeBreakdown {
+    /// Amount of the `fee` transaction, which debits the account.
+    pub fn ledger_amount(&self) -> f64 {
+        TransactionType::Fee.ledger_amount(self.total)
+    }
+}
+
+/// Fee charged on a transaction, shown on the transaction it was charged on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct TransactionFee {
+    /// The completed `fee` transaction
+    pub fee_transaction_id: String,
+    pub fee_schedule_id: String,
+    pub amount: f64,
+    pub currency: Currency,
+    pub breakdown: FeeBreakdown,
+}
+
+/// Rule for `transaction_type` in `currency`, if the rules have one.
+pub fn find_rule(rules: &[FeeRule], transaction_type: TransactionType, currency: Currency) -> Option<&FeeRule> {
+    rules
+        .iter()
+        .find(|rule| rule.transaction_type == transaction_type && rule.currency == currency)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FeeScheduleRequest {
+    #[schema(example = "Standard")]
+    pub name: String,
+
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// Plan the schedule prices; accounts on the plan are charged by it
+    #[serde(default)]
+    #[schema(example = "standard")]
+    pub plan: Option<String>,
+
+    pub rules: Vec<FeeRule>,
+
+    #[serde(default = "default_active")]
+    pub active: bool,
+}
+
+fn default_active() -> bool {
+    true
+}
+
+impl FeeScheduleRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Name cannot be empty".to_string());
+        }
+        if self.name.chars().count() > MAX_NAME_LENGTH {
+            return Err(format!("Name must not exceed {} characters", MAX_NAME_LENGTH));
+        }
+        if self
+            .description
+            .as_ref()
+            .is_some_and(|description| description.chars().count() > MAX_DESCRIPTION_LENGTH)
+        {
+            return Err(format!("Description must not exceed {} characters", MAX_DESCRIPTION_LENGTH));
+        }
+        if let Some(plan) = &self.plan {
+            validate_plan(plan)?;
+        }
+
+        if self.rules.len() > MAX_RULES {
+            return Err(format!("At most {} rules are allowed", MAX_RULES));
+        }
+        for (index, rule) in self.rules.iter().enumerate() {
+            rule.validate()?;
+            if find_rule(&self.rules[..index], rule.transaction_type, rule.currency).is_some() {
+                return Err(format!(
+                    "Only one rule per transaction type and currency is allowed ({} in {})",
+                    rule.transaction_type, rule.currency
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub fn validate_plan(plan: &str) -> Result<(), String> {
+    if plan.is_empty() || plan.len() > MAX_PLAN_LENGTH {
+        return Err(format!("Plan must be 1 to {} characters", MAX_PLAN_LENGTH));
+    }
+    if !plan.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+        return Err("Plan must contain only alphanumeric characters, underscores, and hyphens".to_string());
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FeeScheduleResponse {
+    pub fee_schedule_id: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plan: Option<String>,
+    pub rules: Vec<FeeRule>,
+    pub active: bool,
+    #[schema(value_type = String, format = DateTime)]
+    pub created_at: DateTime<Utc>,
+    #[schema(value_type = String, format = DateTime)]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Assigns an account a fee schedule directly or through a plan; exactly
+/// one of the two is given.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AssignFeeScheduleRequest {
+    #[serde(default)]
+    pub fee_schedule_id: Option<String>,
+
+    #[serde(default)]
+    #[schema(example = "standard")]
+    pub plan: Option<String>,
+}
+
+impl AssignFeeScheduleRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        match (&self.fee_schedule_id, &self.plan) {
+            (Some(_), None) => Ok(()),
+            (None, Some(plan)) => validate_plan(plan),
+            _ => Err("Give exactly one of fee_schedule_id and plan".to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AccountFeeScheduleResponse {
+    pub account_id: String,
+    /// Schedule assigned directly
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_schedule_id: Option<String>,
+    /// Plan assigned instead of a schedule
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plan: Option<String>,
+    /// Active schedule charging the account's transactions, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effective_schedule: Option<FeeScheduleResponse>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams, ToSchema)]
+#[into_params(parameter_in = Query)]
+pub struct FeeRevenueParams {
+    /// Start of the period (inclusive); defaults to 30 days before `to`
+    #[serde(default)]
+    #[param(value_type = Option<String>, format = DateTime)]
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub from: Option<DateTime<Utc>>,
+
+    /// End of the period (exclusive); defaults to now
+    #[serde(default)]
+    #[param(value_type = Option<String>, format = DateTime)]
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub to: Option<DateTime<Utc>>,
+
+    #[serde(default)]
+    pub currency: Option<Currency>,
+}
+
+impl FeeRevenueParams {
+    pub fn period(&self, now: DateTime<Utc>) -> Result<(DateTime<Utc>, DateTime<Utc>), String> {
+        let to = self.to.unwrap_or(now);
+        let from = self.from.unwrap_or(to - Duration::days(DEFAULT_REPORT_DAYS));
+
+        if from >= to {
+            return Err("from must be before to".to_string());
+        }
+
+        Ok((from, to))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FeeRevenueLine {
+    pub currency: Currency,
+    /// Type of the transactions the fees were charged on
+    pub transaction_type: TransactionType,
+    pub fee_schedule_id: String,
+    pub fee_count: i64,
+    pub fee_total: f64,
+    /// Total amount of the transactions the fees were charged on
+    pub charged_volume: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FeeRevenueTotal {
+    pub currency: Currency,
+    pub fee_count: i64,
+    pub fee_total: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FeeRevenueReport {
+    #[schema(value_type = String, format = DateTime)]
+    pub from: DateTime<Utc>,
+    #[schema(value_type = String, format = DateTime)]
+    pub to: DateTime<Utc>,
+    /// Revenue per currency
+    pub totals: Vec<FeeRevenueTotal>,
+    /// Revenue per currency, transaction type and schedule
+    pub lines: Vec<FeeRevenueLine>,
+}
+
+/// Sums report lines into per-currency totals.
+pub fn revenue_totals(lines: &[FeeRevenueLine]) -> Vec<FeeRevenueTotal> {
+    let mut totals: Vec<FeeRevenueTotal> = Vec::new();
+    for line in lines {
+        match totals.iter_mut().find(|total| total.currency == line.currency) {
+            Some(total) => {
+                total.fee_count += line.fee_count;
+                total.fee_total = round_cents(total.fee_total + line.fee_total);
+            }
+            None => totals.push(FeeRevenueTotal {
+                currency: line.currency,
+                fee_count: line.fee_count,
+                fee_total: round_cents(line.fee_total),
+            }),
+        }
+    }
+    totals.sort_by_key(|total| total.currency.to_string());
+    totals
+}
+
+#[derive(Debug)]
+pub enum FeeError {
+    NotFound(String),
+    /// Another active schedule already prices the plan.
+    PlanTaken(String),
+    Database(sqlx::Error),
+}
+
+impl From<sqlx::Error> for FeeError {
+    fn from(error: sqlx::Error) -> Self {
+        FeeError::Database(error)
+    }
+}
+
+impl std::fmt::Display for FeeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FeeError::NotFound(fee_schedule_id) => write!(f, "Fee schedule '{}' not found", fee_schedule_id),
+            FeeError::PlanTaken(plan) => {
+                write!(f, "Another active fee schedule already prices plan '{}'", plan)
+            }
+            FeeError::Database(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+pub fn generate_fee_schedule_id() -> String {
+    use rand::Rng;
+    let timestamp = Utc::now().timestamp();
+    let random: u32 = rand::rng().random();
+    format!("fsch_{}_{:08x}", timestamp, random)
+}
+
+const SCHEDULE_COLUMNS: &str = "fee_schedule_id, name, description, plan, rules, active, created_at, updated_at";
+
+type ScheduleRow = (
+    String,
+    String,
+    Option<String>,
+    Option<String>,
+    serde_json::Value,
+    bool,
+    DateTime<Utc>,
+    DateTime<Utc>,
+);
+
+fn schedule_from_row(row: ScheduleRow) -> FeeScheduleResponse {
+    FeeScheduleResponse {
+        fee_schedule_id: row.0,
+        name: row.1,
+        description: row.2,
+        plan: row.3,
+        rules: serde_json::from_value(row.4).unwrap_or_default(),
+        active: row.5,
+        created_at: row.6,
+        updated_at: row.7,
+    }
+}
+
+fn is_plan_conflict(error: &sqlx::Error) -> bool {
+    matches!(error, sqlx::Error::Database(e) if e.constraint() == Some("idx_fee_schedules_active_plan"))
+}
+
+pub struct FeeService;
+
+impl FeeService {
+    pub async fn create_schedule(pool: &PgPool, req: &FeeScheduleRequest) -> Result<FeeScheduleResponse, FeeError> {
+        let row = sqlx::query_as::<_, ScheduleRow>(&format!(
+            r#"
+            INSERT INTO fee_schedules (fee_schedule_id, name, description, plan, rules, active)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING {}
+            "#,
+            SCHEDULE_COLUMNS
+        ))
+        .bind(generate_fee_schedule_id())
+        .bind(req.name.trim())
+        .bind(&req.description)
+        .bind(&req.plan)
+        .bind(json!(req.rules))
+        .bind(req.active)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| Self::schedule_error(e, req))?;
+
+        Ok(schedule_from_row(row))
+    }
+
+    /// Replaces a schedule. Fees already charged keep the breakdown they were
+    /// charged with.
+    pub async fn update_schedule(
+        pool: &PgPool,
+        fee_schedule_id: &str,
+        req: &FeeScheduleRequest,
+    ) -> Result<FeeScheduleResponse, FeeError> {
+        let row = sqlx::query_as::<_, ScheduleRow>(&format!(
+            r#"
+            UPDATE fee_schedules
+            SET name = $2, description = $3, plan = $4, rules = $5, active = $6, updated_at = NOW()
+            WHERE fee_schedule_id = $1
+            RETURNING {}
+            "#,
+            SCHEDULE_COLUMNS
+        ))
+        .bind(fee_schedule_id)
+        .bind(req.name.trim())
+        .bind(&req.description)
+        .bind(&req.plan)
+        .bind(json!(req.rules))
+        .bind(req.active)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| Self::schedule_error(e, req))?
+        .ok_or_else(|| FeeError::NotFound(fee_schedule_id.to_string()))?;
+
+        Ok(schedule_from_row(row))
+    }
+
+    fn schedule_error(error: sqlx::Error, req: &FeeScheduleRequest) -> FeeError {
+        match &req.plan {
+            Some(plan) if is_plan_conflict(&error) => FeeError::PlanTaken(plan.clone()),
+            _ => error.into(),
+        }
+    }
+
+    pub async fn get_schedule(pool: &PgPool, fee_schedule_id: &str) -> Result<FeeScheduleResponse, FeeError> {
+        let row = sqlx::query_as::<_, ScheduleRow>(&format!(
+            "SELECT {} FROM fee_schedules WHERE fee_schedule_id = $1",
+            SCHEDULE_COLUMNS
+        ))
+        .bind(fee_schedule_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| FeeError::NotFound(fee_schedule_id.to_string()))?;
+
+        Ok(schedule_from_row(row))
+    }
+
+    pub async fn list_schedules(pool: &PgPool) -> Result<Vec<FeeScheduleResponse>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, ScheduleRow>(&format!(
+            "SELECT {} FROM fee_schedules ORDER BY created_at DESC, fee_schedule_id",
+            SCHEDULE_COLUMNS
+        ))
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows.into_iter().map(schedule_from_row).collect())
+    }
+
+    pub async fn assign(
+        pool: &PgPool,
+        account_id: &str,
+        req: &AssignFeeScheduleRequest,
+    ) -> Result<AccountFeeScheduleResponse, FeeError> {
+        if let Some(fee_schedule_id) = &req.fee_schedule_id {
+            Self::get_schedule(pool, fee_schedule_id).await?;
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO account_fee_assignments (account_id, fee_schedule_id, plan)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (account_id) DO UPDATE
+            SET fee_schedule_id = EXCLUDED.fee_schedule_id, plan = EXCLUDED.plan, assigned_at = NOW()
+            "#
+        )
+        .bind(account_id)
+        .bind(&req.fee_schedule_id)
+        .bind(&req.plan)
+        .execute(pool)
+        .await?;
+
+        Self::account_schedule(pool, account_id).await
+    }
+
+    pub async fn unassign(pool: &PgPool, account_id: &str) -> Result<AccountFeeScheduleResponse, FeeError> {
+        sqlx::query("DELETE FROM account_fee_assignments WHERE account_id = $1")
+            .bind(account_id)
+            .execute(pool)
+            .await?;
+
+        Self::account_schedule(pool, account_id).await
+    }
+
+    pub async fn account_schedule(pool: &PgPool, account_id: &str) -> Result<AccountFeeScheduleResponse, FeeError> {
+        let assignment = sqlx::query_as::<_, (Option<String>, Option<String>)>(
+            "SELECT fee_schedule_id, plan FROM account_fee_assignments WHERE account_id = $1"
+        )
+        .bind(account_id)
+        .fetch_optional(pool)
+        .await?;
+
+        let mut conn = pool.acquire().await?;
+        let effective_schedule = Self::effective_schedule(&mut conn, account_id).await?;
+        let (fee_schedule_id, plan) = assignment.unwrap_or_default();
+
+        Ok(AccountFeeScheduleResponse {
+            account_id: account_id.to_string(),
+            fee_schedule_id,
+            plan,
+            effective_schedule,
+        })
+    }
+
+    /// Active schedule charging an account: the one assigned to it, or the
+    /// one pricing its plan.
+    async fn effective_schedule(
+        conn: &mut sqlx::PgConnection,
+        account_id: &str,
+    ) -> Result<Option<FeeScheduleResponse>, sqlx::Error> {
+        let row = sqlx::query_as::<_, ScheduleRow>(&format!(
+            r#"
+            SELECT {}
+            FROM fee_schedules
+            WHERE active
+              AND EXISTS (
+                  SELECT 1
+                  FROM account_fee_assignments a
+                  WHERE a.account_id = $1
+                    AND (
+                        a.fee_schedule_id = fee_schedules.fee_schedule_id
+                        OR (a.fee_schedule_id IS NULL AND a.plan = fee_schedules.plan)
+                    )
+              )
+            LIMIT 1
+            "#,
+            SCHEDULE_COLUMNS
+        ))
+        .bind(account_id)
+        .fetch_optional(conn)
+        .await?;
+
+        Ok(row.map(schedule_from_row))
+    }
+
+    /// Charges the fee for a completed transaction, if its account's
+    /// schedule has a rule for its type and currency: a completed `fee`
+    /// transaction is booked and linked to it. Runs in the caller's database
+    /// transaction.
+    pub async fn charge(
+        conn: &mut sqlx::PgConnection,
+        transaction: &TransactionResponse,
+    ) -> Result<Option<TransactionFee>, sqlx::Error> {
+        if transaction.status != TransactionStatus::Completed
+            || transaction.transaction_type == TransactionType::Fee
+        {
+            return Ok(None);
+        }
+
+        let Some(schedule) = Self::effective_schedule(&mut *conn, &transaction.account_id).await? else {
+            return Ok(None);
+        };
+        let Some(breakdown) = find_rule(&schedule.rules, transaction.transaction_type, transaction.currency)
+            .and_then(|rule| rule.compute(transaction.amount.abs()))
+        else {
+            return Ok(None);
+        };
+
+        let fee_transaction_id = generate_transaction_id();
+        sqlx::query(
+            r#"
+            INSERT INTO transactions (
+                transaction_id, account_id, amount, currency,
+                transaction_type, status, description, metadata,
+                created_at, processed_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, NOW(), NOW())
+            "#
+        )
+        .bind(&fee_transaction_id)
+        .bind(&transaction.account_id)
+        .bind(breakdown.ledger_amount())
+        .bind(transaction.currency.to_string())
+        .bind(TransactionType::Fee.to_string())
+        .bind(TransactionStatus::Completed.to_string())
+        .bind(format!("Fee for {}", transaction.transaction_id))
+        .bind(json!({
+            "fee_for": transaction.transaction_id,
+            "fee_schedule_id": schedule.fee_schedule_id,
+        }))
+        .execute(&mut *conn)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO transaction_fees (
+                transaction_id, fee_transaction_id, fee_schedule_id, account_id,
+                transaction_type, charged_amount, amount, currency, breakdown
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            "#
+        )
+        .bind(&transaction.transaction_id)
+        .bind(&fee_transaction_id)
+        .bind(&schedule.fee_schedule_id)
+        .bind(&transaction.account_id)
+        .bind(transaction.transaction_type.to_string())
+        .bind(transaction.amount.abs())
+        .bind(breakdown.total)
+        .bind(transaction.currency.to_string())
+        .bind(json!(breakdown))
+        .execute(&mut *conn)
+        .await?;
+
+        record_transaction_created(&TransactionType::Fee.to_string(), &transaction.currency.to_string());
+
+        Ok(Some(TransactionFee {
+            fee_transaction_id,
+            fee_schedule_id: schedule.fee_schedule_id,
+            amount: breakdown.total,
+            currency: transaction.currency,
+            breakdown,
+        }))
+    }
+
+    /// Fees charged on the given transactions, by transaction ID.
+    pub async fn fees_for(
+        pool: &PgPool,
+        transaction_ids: &[String],
+    ) -> Result<HashMap<String, TransactionFee>, sqlx::Error> {
+        if transaction_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let rows = sqlx::query_as::<_, (String, String, String, f64, String, serde_json::Value)>(
+            r#"
+            SELECT transaction_id, fee_transaction_id, fee_schedule_id, amount, currency, breakdown
+            FROM transaction_fees
+            WHERE transaction_id = ANY($1)
+            "#
+        )
+        .bind(transaction_ids)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                let breakdown = serde_json::from_value(row.5).ok()?;
+                Some((
+                    row.0,
+                    TransactionFee {
+                        fee_transaction_id: row.1,
+                        fee_schedule_id: row.2,
+                        amount: row.3,
+                        currency: row.4.parse().unwrap_or_default(),
+                        breakdown,
+                    },
+                ))
+            })
+            .collect())
+    }
+
+    /// Attaches charged fees to transactions loaded without them.
+    pub async fn attach(pool: &PgPool, transactions: &mut [TransactionResponse]) -> Result<(), sqlx::Error> {
+        let ids: Vec<String> = transactions.iter().map(|t| t.transaction_id.clone()).collect();
+        let mut fees = Self::fees_for(pool, &ids).await?;
+
+        for transaction in transactions {
+            transaction.fee = fees.remove(&transaction.transaction_id);
+        }
+
+        Ok(())
+    }
+
+    /// Fee revenue in `[from, to)` per currency, charged transaction type
+    /// and schedule.
+    pub async fn revenue(
+        pool: &PgPool,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        currency: Option<Currency>,
+    ) -> Result<FeeRevenueReport, sqlx::Error> {
+        let rows = sqlx::query_as::<_, (String, String, String, i64, f64, f64)>(
+            r#"
+            SELECT f.currency, f.transaction_type, f.fee_schedule_id,
+                   COUNT(*), COALESCE(SUM(f.amount), 0), COALESCE(SUM(f.charged_amount), 0)
+            FROM transaction_fees f
+            JOIN transactions t ON t.transaction_id = f.fee_transaction_id
+            WHERE t.status = $1
+              AND f.created_at >= $2 AND f.created_at < $3
+              AND ($4::text IS NULL OR f.currency = $4)
+            GROUP BY f.currency, f.transaction_type, f.fee_schedule_id
+            ORDER BY f.currency, f.transaction_type, f.fee_schedule_id
+            "#
+        )
+        .bind(TransactionStatus::Completed.to_string())
+        .bind(from)
+        .bind(to)
+        .bind(currency.map(|c| c.to_string()))
+        .fetch_all(pool)
+        .await?;
+
+        let lines: Vec<FeeRevenueLine> = rows
+            .into_iter()
+            .map(|row| FeeRevenueLine {
+                currency: row.0.parse().unwrap_or_default(),
+                transaction_type: row.1.parse().unwrap_or_default(),
+                fee_schedule_id: row.2,
+                fee_count: row.3,
+                fee_total: round_cents(row.4),
+                charged_volume: round_cents(row.5),
+            })
+            .collect();
+
+        Ok(FeeRevenueReport {
+            from,
+            to,
+            totals: revenue_totals(&lines),
+            lines,
+        })
+    }
+}
@@ -3,6 +3,11 @@ use rand::Rng;
 use serde::{Deserialize, Serialize};
 use utoipa::{IntoParams, ToSchema};
 
+use crate::models::{
+    common::{deserialize_comma_separated, SortOrder},
+    pagination::timestamp_key,
+};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum TransactionType {
@@ -13,6 +18,8 @@ pub enum TransactionType {
     Payout,
     Chargeback,
     Transfer,
+    /// Equal and opposite entry undoing a completed transaction.
+    Reversal,
 }
 
 impl Default for TransactionType {
@@ -33,6 +40,7 @@ impl std::str::FromStr for TransactionType {
             "payout" => Ok(TransactionType::Payout),
             "chargeback" => Ok(TransactionType::Chargeback),
             "transfer" => Ok(TransactionType::Transfer),
+            "reversal" => Ok(TransactionType::Reversal),
             _ => Err(format!("Invalid transaction type: {}", s)),
         }
     }
@@ -48,6 +56,33 @@ impl std::fmt::Display for TransactionType {
             TransactionType::Payout => write!(f, "payout"),
             TransactionType::Chargeback => write!(f, "chargeback"),
             TransactionType::Transfer => write!(f, "transfer"),
+            TransactionType::Reversal => write!(f, "reversal"),
+        }
+    }
+}
+
+impl TransactionType {
+    /// Types that move money out of the account.
+    pub const OUTFLOWS: [TransactionType; 5] = [
+        TransactionType::Refund,
+        TransactionType::Fee,
+        TransactionType::Payout,
+        TransactionType::Chargeback,
+        TransactionType::Transfer,
+    ];
+
+    pub fn is_outflow(&self) -> bool {
+        Self::OUTFLOWS.contains(self)
+    }
+
+    /// Amount as stored for a transaction of this type requested for
+    /// `amount`. Stored amounts are signed by their effect on the balance,
+    /// outflows negative, so a balance is the plain sum of them.
+    pub fn ledger_amount(&self, amount: f64) -> f64 {
+        if self.is_outflow() {
+            -amount.abs()
+        } else {
+            amount
         }
     }
 }
@@ -103,9 +138,48 @@ pub enum FailureReason {
     NetworkError,
     Timeout,
     Fraud,
+    AmountLimitExceeded,
+    DailyLimitExceeded,
+    MonthlyLimitExceeded,
+    VelocityLimitExceeded,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+impl std::str::FromStr for FailureReason {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "insufficient_funds" => Ok(FailureReason::InsufficientFunds),
+            "invalid_account" => Ok(FailureReason::InvalidAccount),
+            "network_error" => Ok(FailureReason::NetworkError),
+            "timeout" => Ok(FailureReason::Timeout),
+            "fraud" => Ok(FailureReason::Fraud),
+            "amount_limit_exceeded" => Ok(FailureReason::AmountLimitExceeded),
+            "daily_limit_exceeded" => Ok(FailureReason::DailyLimitExceeded),
+            "monthly_limit_exceeded" => Ok(FailureReason::MonthlyLimitExceeded),
+            "velocity_limit_exceeded" => Ok(FailureReason::VelocityLimitExceeded),
+            _ => Err(format!("Invalid failure reason: {}", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for FailureReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FailureReason::InsufficientFunds => write!(f, "insufficient_funds"),
+            FailureReason::InvalidAccount => write!(f, "invalid_account"),
+            FailureReason::NetworkError => write!(f, "network_error"),
+            FailureReason::Timeout => write!(f, "timeout"),
+            FailureReason::Fraud => write!(f, "fraud"),
+            FailureReason::AmountLimitExceeded => write!(f, "amount_limit_exceeded"),
+            FailureReason::DailyLimitExceeded => write!(f, "daily_limit_exceeded"),
+            FailureReason::MonthlyLimitExceeded => write!(f, "monthly_limit_exceeded"),
+            FailureReason::VelocityLimitExceeded => write!(f, "velocity_limit_exceeded"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
 pub enum Currency {
     USD,
     EUR,
@@ -192,19 +266,78 @@ pub struct Transaction {
     pub processed_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, IntoParams, ToSchema)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionSortField {
+    #[default]
+    CreatedAt,
+    Amount,
+}
+
+impl std::fmt::Display for TransactionSortField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TransactionSortField::CreatedAt => write!(f, "created_at"),
+            TransactionSortField::Amount => write!(f, "amount"),
+        }
+    }
+}
+
+impl std::str::FromStr for TransactionSortField {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "created_at" => Ok(TransactionSortField::CreatedAt),
+            "amount" => Ok(TransactionSortField::Amount),
+            _ => Err(format!("Invalid sort field: {}", s)),
+        }
+    }
+}
+
+impl TransactionSortField {
+    /// Column and cast used for keyset comparisons on this field.
+    pub fn column(&self) -> (&'static str, &'static str) {
+        match self {
+            TransactionSortField::CreatedAt => ("created_at", "timestamptz"),
+            TransactionSortField::Amount => ("amount", "float8"),
+        }
+    }
+
+    /// Sort value of a transaction, as stored in a cursor.
+    pub fn value_of(&self, created_at: &DateTime<Utc>, amount: f64) -> String {
+        match self {
+            TransactionSortField::CreatedAt => timestamp_key(created_at),
+            TransactionSortField::Amount => amount.to_string(),
+        }
+    }
+}
+
+/// Longest accepted `q` search term.
+pub const MAX_SEARCH_LENGTH: usize = 200;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams, ToSchema)]
 pub struct TransactionFilters {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub account_id: Option<String>,
     
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub status: Option<TransactionStatus>,
+    /// One or more statuses, comma separated (e.g. `pending,failed`)
+    #[serde(default, deserialize_with = "deserialize_comma_separated", skip_serializing_if = "Option::is_none")]
+    #[param(value_type = Option<String>, example = "pending,failed")]
+    #[schema(value_type = Option<Vec<TransactionStatus>>)]
+    pub status: Option<Vec<TransactionStatus>>,
     
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub transaction_type: Option<TransactionType>,
+    /// One or more transaction types, comma separated
+    #[serde(default, deserialize_with = "deserialize_comma_separated", skip_serializing_if = "Option::is_none")]
+    #[param(value_type = Option<String>, example = "payment,refund")]
+    #[schema(value_type = Option<Vec<TransactionType>>)]
+    pub transaction_type: Option<Vec<TransactionType>>,
     
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub currency: Option<Currency>,
+    /// One or more currencies, comma separated
+    #[serde(default, deserialize_with = "deserialize_comma_separated", skip_serializing_if = "Option::is_none")]
+    #[param(value_type = Option<String>, example = "USD,EUR")]
+    #[schema(value_type = Option<Vec<Currency>>)]
+    pub currency: Option<Vec<Currency>>,
     
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[param(value_type = Option<String>, format = DateTime)]
@@ -215,18 +348,157 @@ pub struct TransactionFilters {
     #[param(value_type = Option<String>, format = DateTime)]
     #[schema(value_type = Option<String>, format = DateTime)]
     pub created_before: Option<DateTime<Utc>>,
+
+    /// Inclusive lower bound on `amount`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_amount: Option<f64>,
+
+    /// Inclusive upper bound on `amount`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_amount: Option<f64>,
+
+    /// JSON object the transaction metadata must contain (e.g. `{"order_id":"ord_42"}`)
+    #[serde(default, deserialize_with = "deserialize_metadata_filter", skip_serializing_if = "Option::is_none")]
+    #[param(value_type = Option<String>, example = r#"{"order_id":"ord_42"}"#)]
+    #[schema(value_type = Option<Object>)]
+    pub metadata: Option<serde_json::Value>,
+
+    /// Case-insensitive text search over `description`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub q: Option<String>,
+
+    /// Sort field (default `created_at`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sort_by: Option<TransactionSortField>,
+
+    /// Sort direction (default `desc`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sort_order: Option<SortOrder>,
 }
 
-impl Default for TransactionFilters {
-    fn default() -> Self {
-        Self {
-            account_id: None,
-            status: None,
-            transaction_type: None,
-            currency: None,
-            created_after: None,
-            created_before: None,
+impl TransactionFilters {
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if let (Some(min), Some(max)) = (self.min_amount, self.max_amount) {
+            if min > max {
+                return Err(ValidationError::InvalidAmountRange);
+            }
+        }
+
+        if [self.min_amount, self.max_amount]
+            .iter()
+            .flatten()
+            .any(|amount| !amount.is_finite())
+        {
+            return Err(ValidationError::InvalidAmountRange);
+        }
+
+        if let (Some(after), Some(before)) = (self.created_after, self.created_before) {
+            if after > before {
+                return Err(ValidationError::InvalidDateRange);
+            }
+        }
+
+        if let Some(q) = &self.q {
+            if q.trim().is_empty() || q.chars().count() > MAX_SEARCH_LENGTH {
+                return Err(ValidationError::InvalidSearchQuery);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn sort(&self) -> (TransactionSortField, SortOrder) {
+        (
+            self.sort_by.unwrap_or_default(),
+            self.sort_order.unwrap_or_default(),
+        )
+    }
+
+    /// Builds the `WHERE` clause and its bind values, numbered from `$1`.
+    pub fn where_clause(&self) -> (String, Vec<String>) {
+        let mut clause = String::from("WHERE 1=1");
+        let mut bind_values: Vec<String> = vec![];
+
+        let mut push = |clause: &mut String, condition: &str, value: String| {
+            bind_values.push(value);
+            clause.push_str(&condition.replace("$?", &format!("${}", bind_values.len())));
+        };
+
+        if let Some(account_id) = &self.account_id {
+            push(&mut clause, " AND account_id = $?", account_id.clone());
+        }
+
+        let lists = [
+            ("status", self.status.as_ref().map(|v| to_strings(v))),
+            ("transaction_type", self.transaction_type.as_ref().map(|v| to_strings(v))),
+            ("currency", self.currency.as_ref().map(|v| to_strings(v))),
+        ];
+        for (column, values) in lists {
+            if let Some(values) = values {
+                clause.push_str(&format!(" AND {} IN (", column));
+                for (i, value) in values.into_iter().enumerate() {
+                    let separator = if i == 0 { "$?" } else { ", $?" };
+                    push(&mut clause, separator, value);
+                }
+                clause.push(')');
+            }
+        }
+
+        if let Some(start) = &self.created_after {
+            push(&mut clause, " AND created_at >= $?::timestamptz", start.to_rfc3339());
+        }
+
+        if let Some(end) = &self.created_before {
+            push(&mut clause, " AND created_at <= $?::timestamptz", end.to_rfc3339());
+        }
+
+        if let Some(min) = self.min_amount {
+            push(&mut clause, " AND amount >= $?::float8", min.to_string());
+        }
+
+        if let Some(max) = self.max_amount {
+            push(&mut clause, " AND amount <= $?::float8", max.to_string());
         }
+
+        if let Some(metadata) = &self.metadata {
+            push(&mut clause, " AND metadata @> $?::jsonb", metadata.to_string());
+        }
+
+        if let Some(q) = &self.q {
+            push(
+                &mut clause,
+                " AND description ILIKE $?",
+                format!("%{}%", escape_like(q.trim())),
+            );
+        }
+
+        (clause, bind_values)
+    }
+}
+
+fn to_strings<T: std::fmt::Display>(values: &[T]) -> Vec<String> {
+    values.iter().map(ToString::to_string).collect()
+}
+
+/// Escapes `LIKE` wildcards so user input only matches literally.
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+fn deserialize_metadata_filter<'de, D>(deserializer: D) -> Result<Option<serde_json::Value>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let Some(raw) = Option::<String>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+
+    match serde_json::from_str::<serde_json::Value>(&raw) {
+        Ok(value @ serde_json::Value::Object(_)) => Ok(Some(value)),
+        _ => Err(serde::de::Error::custom("metadata must be a JSON object")),
     }
 }
 
@@ -238,6 +510,9 @@ pub enum ValidationError {
     InvalidCurrency,
     InvalidTransactionType,
     InvalidStatus,
+    InvalidAmountRange,
+    InvalidDateRange,
+    InvalidSearchQuery,
 }
 
 impl std::fmt::Display for ValidationError {
@@ -249,6 +524,15 @@ impl std::fmt::Display for ValidationError {
             ValidationError::InvalidCurrency => write!(f, "Invalid currency code"),
             ValidationError::InvalidTransactionType => write!(f, "Invalid transaction type"),
             ValidationError::InvalidStatus => write!(f, "Invalid transaction status"),
+            ValidationError::InvalidAmountRange => {
+                write!(f, "min_amount and max_amount must be finite, with min_amount <= max_amount")
+            }
+            ValidationError::InvalidDateRange => {
+                write!(f, "created_after must not be later than created_before")
+            }
+            ValidationError::InvalidSearchQuery => {
+                write!(f, "q must be between 1 and {} characters", MAX_SEARCH_LENGTH)
+            }
         }
     }
 }
@@ -0,0 +1,991 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    db::PgPool,
+    models::{
+        balance::BalanceService,
+        common::{ErrorCode, ErrorDetail},
+        export::{transaction_from_row, TransactionRow},
+        fees::FeeService,
+        finance::{Currency, FailureReason, TransactionStatus, TransactionType},
+        lifecycle::AccountStatus,
+        requests::CreateTransactionRequest,
+        responses::TransactionResponse,
+    },
+    observability::metrics::record_fraud_decision,
+};
+
+const MAX_RULE_NAME_LENGTH: usize = 200;
+const MAX_DENY_LIST_VALUES: usize = 10_000;
+/// Longest velocity window a rule may use.
+const MAX_VELOCITY_WINDOW_SECS: i64 = 30 * 24 * 3600;
+/// How long an external scorer may take before it is skipped.
+const SCORER_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+pub const DEFAULT_REVIEW_QUEUE_LIMIT: i64 = 100;
+pub const MAX_REVIEW_QUEUE_LIMIT: i64 = 500;
+
+/// Outcome of screening a transaction, ordered from least to most severe.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FraudDecision {
+    #[default]
+    Allow,
+    /// The transaction is created as `pending` and waits in the review queue.
+    Review,
+    /// The transaction is recorded as `failed` with `failure_reason` `fraud`.
+    Block,
+}
+
+impl std::fmt::Display for FraudDecision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FraudDecision::Allow => write!(f, "allow"),
+            FraudDecision::Review => write!(f, "review"),
+            FraudDecision::Block => write!(f, "block"),
+        }
+    }
+}
+
+impl std::str::FromStr for FraudDecision {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "allow" => Ok(FraudDecision::Allow),
+            "review" => Ok(FraudDecision::Review),
+            "block" => Ok(FraudDecision::Block),
+            _ => Err(format!("Invalid fraud decision: {}", s)),
+        }
+    }
+}
+
+/// What a matching rule does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FraudAction {
+    Review,
+    Block,
+}
+
+impl FraudAction {
+    pub fn decision(&self) -> FraudDecision {
+        match self {
+            FraudAction::Review => FraudDecision::Review,
+            FraudAction::Block => FraudDecision::Block,
+        }
+    }
+}
+
+impl std::fmt::Display for FraudAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.decision().fmt(f)
+    }
+}
+
+impl std::str::FromStr for FraudAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "review" => Ok(FraudAction::Review),
+            "block" => Ok(FraudAction::Block),
+            _ => Err(format!("Invalid fraud action: {}", s)),
+        }
+    }
+}
+
+/// When a rule matches a transaction.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FraudCondition {
+    /// Amount is at least `amount`, in `currency` if given.
+    AmountAtLeast {
+        amount: f64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        currency: Option<Currency>,
+        /// Only these types; all types if empty.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        transaction_types: Vec<TransactionType>,
+    },
+    /// The account already created `max_transactions` or more transactions
+    /// in the last `window_secs` seconds. Failed transactions do not count.
+    Velocity {
+        max_transactions: i64,
+        window_secs: i64,
+    },
+    /// The account was created less than `max_age_secs` seconds ago and the
+    /// amount is at least `min_amount`.
+    NewAccount {
+        max_age_secs: i64,
+        #[serde(default)]
+        min_amount: f64,
+    },
+    /// Metadata `key` equals one of `values`.
+    Metadata {
+        key: String,
+        #[schema(value_type = Vec<Object>)]
+        values: Vec<serde_json::Value>,
+    },
+    /// `field` is listed in `values`. `field` is `account_id` or
+    /// `metadata.<key>` for a string metadata value.
+    DenyList {
+        field: String,
+        values: Vec<String>,
+    },
+}
+
+impl FraudCondition {
+    fn validate(&self) -> Result<(), String> {
+        match self {
+            FraudCondition::AmountAtLeast { amount, .. } => {
+                if !amount.is_finite() || *amount <= 0.0 {
+                    return Err("amount must be greater than 0".to_string());
+                }
+            }
+            FraudCondition::Velocity { max_transactions, window_secs } => {
+                if *max_transactions < 1 {
+                    return Err("max_transactions must be at least 1".to_string());
+                }
+                if *window_secs < 1 || *window_secs > MAX_VELOCITY_WINDOW_SECS {
+                    return Err(format!(
+                        "window_secs must be between 1 and {}",
+                        MAX_VELOCITY_WINDOW_SECS
+                    ));
+                }
+            }
+            FraudCondition::NewAccount { max_age_secs, min_amount } => {
+                if *max_age_secs < 1 {
+                    return Err("max_age_secs must be at least 1".to_string());
+                }
+                if !min_amount.is_finite() || *min_amount < 0.0 {
+                    return Err("min_amount must be a non-negative number".to_string());
+                }
+            }
+            FraudCondition::Metadata { key, values } => {
+                if key.trim().is_empty() {
+                    return Err("key cannot be empty".to_string());
+                }
+                if values.is_empty() {
+                    return Err("values cannot be empty".to_string());
+                }
+            }
+            FraudCondition::DenyList { field, values } => {
+                if field != "account_id" && deny_list_metadata_key(field).is_none() {
+                    return Err("field must be account_id or metadata.<key>".to_string());
+                }
+                if values.is_empty() || values.len() > MAX_DENY_LIST_VALUES {
+                    return Err(format!(
+                        "values must have between 1 and {} entries",
+                        MAX_DENY_LIST_VALUES
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn matches(&self, req: &CreateTransactionRequest, ctx: &ScreeningContext) -> bool {
+        match self {
+            FraudCondition::AmountAtLeast { amount, currency, transaction_types } => {
+                req.amount >= *amount
+                    && currency.is_none_or(|c| c == req.currency)
+                    && (transaction_types.is_empty()
+                        || transaction_types.contains(&req.transaction_type))
+            }
+            FraudCondition::Velocity { max_transactions, window_secs } => {
+                ctx.recent_transactions.get(window_secs).copied().unwrap_or(0) >= *max_transactions
+            }
+            FraudCondition::NewAccount { max_age_secs, min_amount } => {
+                req.amount >= *min_amount
+                    && ctx.account_created_at.is_some_and(|created| {
+                        ctx.now - created < Duration::seconds(*max_age_secs)
+                    })
+            }
+            FraudCondition::Metadata { key, values } => req
+                .metadata
+                .as_ref()
+                .and_then(|metadata| metadata.get(key))
+                .is_some_and(|value| values.contains(value)),
+            FraudCondition::DenyList { field, values } => {
+                let value = match deny_list_metadata_key(field) {
+                    Some(key) => req
+                        .metadata
+                        .as_ref()
+                        .and_then(|metadata| metadata.get(key))
+                        .and_then(|value| value.as_str()),
+                    None => Some(req.account_id.as_str()),
+                };
+                value.is_some_and(|value| values.iter().any(|listed| listed == value))
+            }
+        }
+    }
+}
+
+fn deny_list_metadata_key(field: &str) -> Option<&str> {
+    field.strip_prefix("metadata.").filter(|key| !key.is_empty())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FraudRule {
+    pub rule_id: String,
+    pub name: String,
+    pub condition: FraudCondition,
+    pub action: FraudAction,
+    pub enabled: bool,
+    #[schema(value_type = String, format = DateTime)]
+    pub created_at: DateTime<Utc>,
+    #[schema(value_type = String, format = DateTime)]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FraudRuleRequest {
+    #[schema(example = "Large payouts")]
+    pub name: String,
+    pub condition: FraudCondition,
+    pub action: FraudAction,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl FraudRuleRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Name cannot be empty".to_string());
+        }
+
+        if self.name.chars().count() > MAX_RULE_NAME_LENGTH {
+            return Err(format!("Name must not exceed {} characters", MAX_RULE_NAME_LENGTH));
+        }
+
+        self.condition.validate()
+    }
+}
+
+/// What the rules know about a transaction beyond the request itself.
+#[derive(Debug, Clone, Default)]
+pub struct ScreeningContext {
+    pub now: DateTime<Utc>,
+    pub account_created_at: Option<DateTime<Utc>>,
+    /// Transactions the account created in each velocity window, keyed by
+    /// the window length in seconds.
+    pub recent_transactions: HashMap<i64, i64>,
+}
+
+impl ScreeningContext {
+    /// Counts an accepted transaction, so later items of the same batch see it.
+    pub fn record(&mut self) {
+        for count in self.recent_transactions.values_mut() {
+            *count += 1;
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct FraudRuleMatch {
+    pub rule_id: String,
+    pub name: String,
+    pub action: FraudAction,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ScreeningResult {
+    pub decision: FraudDecision,
+    pub matched_rules: Vec<FraudRuleMatch>,
+    /// Score reported by the external scorer, if one is configured.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scorer_reason: Option<String>,
+}
+
+impl ScreeningResult {
+    /// Takes the more severe of the current decision and a scorer's verdict.
+    pub fn merge(&mut self, verdict: ScorerVerdict) {
+        self.decision = self.decision.max(verdict.decision);
+        self.score = verdict.score;
+        self.scorer_reason = verdict.reason;
+    }
+}
+
+/// Evaluates the enabled rules against a transaction. The decision is the
+/// most severe action of the matching rules, `allow` if none match.
+pub fn evaluate(
+    rules: &[FraudRule],
+    req: &CreateTransactionRequest,
+    ctx: &ScreeningContext,
+) -> ScreeningResult {
+    let matched_rules: Vec<FraudRuleMatch> = rules
+        .iter()
+        .filter(|rule| rule.enabled && rule.condition.matches(req, ctx))
+        .map(|rule| FraudRuleMatch {
+            rule_id: rule.rule_id.clone(),
+            name: rule.name.clone(),
+            action: rule.action,
+        })
+        .collect();
+
+    ScreeningResult {
+        decision: matched_rules
+            .iter()
+            .map(|rule| rule.action.decision())
+            .max()
+            .unwrap_or_default(),
+        matched_rules,
+        score: None,
+        scorer_reason: None,
+    }
+}
+
+/// Velocity windows used by the enabled rules, in seconds.
+pub fn velocity_windows(rules: &[FraudRule]) -> Vec<i64> {
+    rules
+        .iter()
+        .filter(|rule| rule.enabled)
+        .filter_map(|rule| match rule.condition {
+            FraudCondition::Velocity { window_secs, .. } => Some(window_secs),
+            _ => None,
+        })
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// Error reported for batch items that fraud screening did not allow. Which
+/// rules matched is not disclosed to clients.
+pub fn declined_detail() -> ErrorDetail {
+    ErrorDetail {
+        code: ErrorCode::TransactionBlocked.to_string(),
+        message: "Transaction was declined by fraud screening".to_string(),
+        details: None,
+    }
+}
+
+/// Verdict of an external scorer.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScorerVerdict {
+    pub decision: FraudDecision,
+    pub score: Option<f64>,
+    pub reason: Option<String>,
+}
+
+/// External fraud scoring service consulted after the rules. It sees the
+/// rules' result and can only make the decision stricter.
+#[async_trait]
+pub trait FraudScorer: Send + Sync + 'static {
+    async fn score(
+        &self,
+        req: &CreateTransactionRequest,
+        rules: &ScreeningResult,
+    ) -> Result<ScorerVerdict, String>;
+}
+
+/// Screens transactions before they are committed: runs the admin-defined
+/// rules, then the external scorer if one is configured.
+#[derive(Clone, Default)]
+pub struct FraudScreener {
+    scorer: Option<Arc<dyn FraudScorer>>,
+}
+
+impl FraudScreener {
+    pub fn with_scorer(scorer: Arc<dyn FraudScorer>) -> Self {
+        Self { scorer: Some(scorer) }
+    }
+
+    /// Screens a single transaction on `conn`.
+    pub async fn screen(
+        &self,
+        conn: &mut sqlx::PgConnection,
+        req: &CreateTransactionRequest,
+    ) -> Result<ScreeningResult, sqlx::Error> {
+        let rules = FraudService::enabled_rules(&mut *conn).await?;
+        let account_ids = [req.account_id.clone()];
+        let contexts =
+            FraudService::contexts(&mut *conn, &account_ids, &velocity_windows(&rules), Utc::now())
+                .await?;
+        let ctx = contexts.get(&req.account_id).cloned().unwrap_or_default();
+
+        let mut result = evaluate(&rules, req, &ctx);
+        self.consult_scorer(req, &mut result).await;
+        record_fraud_decision(&result.decision.to_string());
+
+        Ok(result)
+    }
+
+    /// Rejects batch items that are not allowed. Batches have no review
+    /// queue, so items that would be held for review are rejected too.
+    pub async fn screen_items(
+        &self,
+        conn: &mut sqlx::PgConnection,
+        items: Vec<Result<CreateTransactionRequest, ErrorDetail>>,
+    ) -> Result<Vec<Result<CreateTransactionRequest, ErrorDetail>>, sqlx::Error> {
+        let rules = FraudService::enabled_rules(&mut *conn).await?;
+        if rules.is_empty() && self.scorer.is_none() {
+            return Ok(items);
+        }
+
+        let account_ids: Vec<String> = items
+            .iter()
+            .filter_map(|item| item.as_ref().ok())
+            .map(|request| request.account_id.clone())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        let mut contexts =
+            FraudService::contexts(&mut *conn, &account_ids, &velocity_windows(&rules), Utc::now())
+                .await?;
+
+        let mut screened = Vec::with_capacity(items.len());
+        for item in items {
+            let Ok(request) = item else {
+                screened.push(item);
+                continue;
+            };
+
+            let ctx = contexts.entry(request.account_id.clone()).or_default();
+            let mut result = evaluate(&rules, &request, ctx);
+            self.consult_scorer(&request, &mut result).await;
+            record_fraud_decision(&result.decision.to_string());
+
+            if result.decision == FraudDecision::Allow {
+                ctx.record();
+                screened.push(Ok(request));
+            } else {
+                screened.push(Err(declined_detail()));
+            }
+        }
+
+        Ok(screened)
+    }
+
+    /// Merges the scorer's verdict into `result`. A scorer that fails or
+    /// times out is logged and skipped so an outage cannot stop payments.
+    async fn consult_scorer(&self, req: &CreateTransactionRequest, result: &mut ScreeningResult) {
+        let Some(scorer) = &self.scorer else {
+            return;
+        };
+
+        match tokio::time::timeout(SCORER_TIMEOUT, scorer.score(req, result)).await {
+            Ok(Ok(verdict)) => result.merge(verdict),
+            Ok(Err(e)) => tracing::warn!("Fraud scorer failed: {}", e),
+            Err(_) => tracing::warn!("Fraud scorer timed out after {:?}", SCORER_TIMEOUT),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FraudReviewStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+impl std::fmt::Display for FraudReviewStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FraudReviewStatus::Pending => write!(f, "pending"),
+            FraudReviewStatus::Approved => write!(f, "approved"),
+            FraudReviewStatus::Rejected => write!(f, "rejected"),
+        }
+    }
+}
+
+impl std::str::FromStr for FraudReviewStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "pending" => Ok(FraudReviewStatus::Pending),
+            "approved" => Ok(FraudReviewStatus::Approved),
+            "rejected" => Ok(FraudReviewStatus::Rejected),
+            _ => Err(format!("Invalid review status: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams, ToSchema)]
+#[into_params(parameter_in = Query)]
+pub struct FraudReviewParams {
+    /// Review status to list; `pending` by default
+    #[serde(default)]
+    pub status: Option<FraudReviewStatus>,
+
+    /// Maximum number of reviews to return (default 100, at most 500)
+    #[serde(default)]
+    pub limit: Option<i64>,
+}
+
+impl FraudReviewParams {
+    pub fn limit(&self) -> Result<i64, String> {
+        match self.limit {
+            None => Ok(DEFAULT_REVIEW_QUEUE_LIMIT),
+            Some(limit) if (1..=MAX_REVIEW_QUEUE_LIMIT).contains(&limit) => Ok(limit),
+            Some(_) => Err(format!("limit must be between 1 and {}", MAX_REVIEW_QUEUE_LIMIT)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FraudReviewResponse {
+    pub transaction: TransactionResponse,
+    pub decision: FraudDecision,
+    pub matched_rules: Vec<FraudRuleMatch>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scorer_reason: Option<String>,
+    pub review_status: FraudReviewStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub review_note: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub reviewed_at: Option<DateTime<Utc>>,
+    #[schema(value_type = String, format = DateTime)]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct ResolveFraudReviewRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "Customer confirmed the payout by phone")]
+    pub note: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum FraudReviewError {
+    NotFound(String),
+    /// The review has already been approved or rejected.
+    AlreadyResolved(String),
+    AccountUnavailable {
+        account_id: String,
+        status: AccountStatus,
+    },
+    Database(sqlx::Error),
+}
+
+impl From<sqlx::Error> for FraudReviewError {
+    fn from(error: sqlx::Error) -> Self {
+        FraudReviewError::Database(error)
+    }
+}
+
+impl std::fmt::Display for FraudReviewError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FraudReviewError::NotFound(transaction_id) => {
+                write!(f, "No fraud review for transaction '{}'", transaction_id)
+            }
+            FraudReviewError::AlreadyResolved(transaction_id) => {
+                write!(f, "Fraud review for transaction '{}' is already resolved", transaction_id)
+            }
+            FraudReviewError::AccountUnavailable { account_id, status } => write!(
+                f,
+                "Account '{}' is {}; the transaction cannot be approved",
+                account_id, status
+            ),
+            FraudReviewError::Database(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+pub fn generate_fraud_rule_id() -> String {
+    use rand::Rng;
+    let timestamp = Utc::now().timestamp();
+    let random: u32 = rand::rng().random();
+    format!("frule_{}_{:08x}", timestamp, random)
+}
+
+type RuleRow = (
+    String,
+    String,
+    serde_json::Value,
+    String,
+    bool,
+    DateTime<Utc>,
+    DateTime<Utc>,
+);
+
+fn rule_from_row(row: RuleRow) -> Option<FraudRule> {
+    Some(FraudRule {
+        rule_id: row.0,
+        name: row.1,
+        condition: serde_json::from_value(row.2).ok()?,
+        action: row.3.parse().ok()?,
+        enabled: row.4,
+        created_at: row.5,
+        updated_at: row.6,
+    })
+}
+
+const RULE_COLUMNS: &str = "rule_id, name, condition, action, enabled, created_at, updated_at";
+
+type ScreeningRow = (
+    String,
+    String,
+    serde_json::Value,
+    Option<f64>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<DateTime<Utc>>,
+    DateTime<Utc>,
+);
+
+const SCREENING_COLUMNS: &str = "transaction_id, decision, matched_rules, score, scorer_reason, \
+     review_status, review_note, reviewed_at, created_at";
+
+pub struct FraudService;
+
+impl FraudService {
+    pub async fn list_rules(pool: &PgPool) -> Result<Vec<FraudRule>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, RuleRow>(&format!(
+            "SELECT {} FROM fraud_rules ORDER BY created_at, rule_id",
+            RULE_COLUMNS
+        ))
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows.into_iter().filter_map(rule_from_row).collect())
+    }
+
+    async fn enabled_rules(conn: &mut sqlx::PgConnection) -> Result<Vec<FraudRule>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, RuleRow>(&format!(
+            "SELECT {} FROM fraud_rules WHERE enabled ORDER BY created_at, rule_id",
+            RULE_COLUMNS
+        ))
+        .fetch_all(conn)
+        .await?;
+
+        Ok(rows.into_iter().filter_map(rule_from_row).collect())
+    }
+
+    pub async fn create_rule(pool: &PgPool, req: &FraudRuleRequest) -> Result<FraudRule, sqlx::Error> {
+        let row = sqlx::query_as::<_, RuleRow>(&format!(
+            r#"
+            INSERT INTO fraud_rules (rule_id, name, condition, action, enabled)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING {}
+            "#,
+            RULE_COLUMNS
+        ))
+        .bind(generate_fraud_rule_id())
+        .bind(req.name.trim())
+        .bind(serde_json::to_value(&req.condition).unwrap_or_default())
+        .bind(req.action.to_string())
+        .bind(req.enabled)
+        .fetch_one(pool)
+        .await?;
+
+        rule_from_row(row).ok_or(sqlx::Error::RowNotFound)
+    }
+
+    pub async fn update_rule(
+        pool: &PgPool,
+        rule_id: &str,
+        req: &FraudRuleRequest,
+    ) -> Result<Option<FraudRule>, sqlx::Error> {
+        let row = sqlx::query_as::<_, RuleRow>(&format!(
+            r#"
+            UPDATE fraud_rules
+            SET name = $2, condition = $3, action = $4, enabled = $5, updated_at = NOW()
+            WHERE rule_id = $1
+            RETURNING {}
+            "#,
+            RULE_COLUMNS
+        ))
+        .bind(rule_id)
+        .bind(req.name.trim())
+        .bind(serde_json::to_value(&req.condition).unwrap_or_default())
+        .bind(req.action.to_string())
+        .bind(req.enabled)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(row.and_then(rule_from_row))
+    }
+
+    pub async fn delete_rule(pool: &PgPool, rule_id: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM fraud_rules WHERE rule_id = $1")
+            .bind(rule_id)
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Account age and recent transaction counts of the given accounts.
+    async fn contexts(
+        conn: &mut sqlx::PgConnection,
+        account_ids: &[String],
+        windows: &[i64],
+        now: DateTime<Utc>,
+    ) -> Result<HashMap<String, ScreeningContext>, sqlx::Error> {
+        let accounts = sqlx::query_as::<_, (String, DateTime<Utc>)>(
+            "SELECT account_id, created_at FROM accounts WHERE account_id = ANY($1)"
+        )
+        .bind(account_ids)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        let mut contexts: HashMap<String, ScreeningContext> = accounts
+            .into_iter()
+            .map(|(account_id, created_at)| {
+                (
+                    account_id,
+                    ScreeningContext {
+                        now,
+                        account_created_at: Some(created_at),
+                        recent_transactions: windows.iter().map(|w| (*w, 0)).collect(),
+                    },
+                )
+            })
+            .collect();
+
+        if windows.is_empty() {
+            return Ok(contexts);
+        }
+
+        let counts = sqlx::query_as::<_, (String, i64, i64)>(
+            r#"
+            SELECT t.account_id, w.window_secs, COUNT(*)
+            FROM UNNEST($2::bigint[]) AS w(window_secs)
+            JOIN transactions t
+              ON t.account_id = ANY($1)
+             AND t.status <> 'failed'
+             AND t.created_at >= $3 - make_interval(secs => w.window_secs)
+            GROUP BY t.account_id, w.window_secs
+            "#
+        )
+        .bind(account_ids)
+        .bind(windows)
+        .bind(now)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        for (account_id, window, count) in counts {
+            if let Some(ctx) = contexts.get_mut(&account_id) {
+                ctx.recent_transactions.insert(window, count);
+            }
+        }
+
+        Ok(contexts)
+    }
+
+    /// Records the screening of a transaction held for review or blocked.
+    pub async fn record_screening(
+        conn: &mut sqlx::PgConnection,
+        transaction: &TransactionResponse,
+        result: &ScreeningResult,
+    ) -> Result<(), sqlx::Error> {
+        let review_status = (result.decision == FraudDecision::Review)
+            .then(|| FraudReviewStatus::Pending.to_string());
+
+        sqlx::query(
+            r#"
+            INSERT INTO fraud_screenings (
+                transaction_id, account_id, decision, matched_rules,
+                score, scorer_reason, review_status
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#
+        )
+        .bind(&transaction.transaction_id)
+        .bind(&transaction.account_id)
+        .bind(result.decision.to_string())
+        .bind(serde_json::to_value(&result.matched_rules).unwrap_or_default())
+        .bind(result.score)
+        .bind(&result.scorer_reason)
+        .bind(review_status)
+        .execute(conn)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Reviews in `status`, oldest first.
+    pub async fn list_reviews(
+        pool: &PgPool,
+        status: FraudReviewStatus,
+        limit: i64,
+    ) -> Result<Vec<FraudReviewResponse>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, ScreeningRow>(&format!(
+            r#"
+            SELECT {}
+            FROM fraud_screenings
+            WHERE review_status = $1
+            ORDER BY created_at, transaction_id
+            LIMIT $2
+            "#,
+            SCREENING_COLUMNS
+        ))
+        .bind(status.to_string())
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+        let mut conn = pool.acquire().await?;
+        Self::with_transactions(&mut conn, rows).await
+    }
+
+    async fn with_transactions(
+        conn: &mut sqlx::PgConnection,
+        rows: Vec<ScreeningRow>,
+    ) -> Result<Vec<FraudReviewResponse>, sqlx::Error> {
+        let ids: Vec<String> = rows.iter().map(|row| row.0.clone()).collect();
+        let mut transactions: HashMap<String, TransactionResponse> =
+            sqlx::query_as::<_, TransactionRow>(
+                r#"
+                SELECT
+                    transaction_id, account_id, amount, currency,
+                    transaction_type, status, description, metadata,
+                    created_at, processed_at, failure_reason
+                FROM transactions
+                WHERE transaction_id = ANY($1)
+                "#
+            )
+            .bind(&ids)
+            .fetch_all(conn)
+            .await?
+            .into_iter()
+            .map(|row| (row.0.clone(), transaction_from_row(row)))
+            .collect();
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                Some(FraudReviewResponse {
+                    transaction: transactions.remove(&row.0)?,
+                    decision: row.1.parse().unwrap_or_default(),
+                    matched_rules: serde_json::from_value(row.2).unwrap_or_default(),
+                    score: row.3,
+                    scorer_reason: row.4,
+                    review_status: row.5?.parse().ok()?,
+                    review_note: row.6,
+                    reviewed_at: row.7,
+                    created_at: row.8,
+                })
+            })
+            .collect())
+    }
+
+    /// Approves or rejects a pending review. Approval completes the
+    /// transaction if its account is still active; rejection fails it with
+    /// `failure_reason` `fraud`.
+    pub async fn resolve_review(
+        pool: &PgPool,
+        transaction_id: &str,
+        approve: bool,
+        note: Option<&str>,
+    ) -> Result<FraudReviewResponse, FraudReviewError> {
+        let mut tx = pool.begin().await?;
+
+        let current = sqlx::query_as::<_, (String, Option<String>)>(
+            r#"
+            SELECT account_id, review_status
+            FROM fraud_screenings
+            WHERE transaction_id = $1
+            FOR UPDATE
+            "#
+        )
+        .bind(transaction_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let account_id = match current {
+            None | Some((_, None)) => {
+                return Err(FraudReviewError::NotFound(transaction_id.to_string()));
+            }
+            Some((account_id, Some(status))) if status == FraudReviewStatus::Pending.to_string() => {
+                account_id
+            }
+            Some(_) => return Err(FraudReviewError::AlreadyResolved(transaction_id.to_string())),
+        };
+
+        let (status, failure_reason, review_status) = if approve {
+            let account_status = crate::models::lifecycle::AccountLifecycleService::lock_status(
+                &mut tx,
+                &account_id,
+            )
+            .await?
+            .unwrap_or(AccountStatus::Closed);
+
+            if !account_status.accepts_transactions() {
+                return Err(FraudReviewError::AccountUnavailable {
+                    account_id,
+                    status: account_status,
+                });
+            }
+
+            (TransactionStatus::Completed, None, FraudReviewStatus::Approved)
+        } else {
+            (
+                TransactionStatus::Failed,
+                Some(FailureReason::Fraud.to_string()),
+                FraudReviewStatus::Rejected,
+            )
+        };
+
+        let transaction = sqlx::query_as::<_, TransactionRow>(
+            r#"
+            UPDATE transactions
+            SET status = $2, failure_reason = $3, processed_at = NOW()
+            WHERE transaction_id = $1
+            RETURNING
+                transaction_id, account_id, amount, currency,
+                transaction_type, status, description, metadata,
+                created_at, processed_at, failure_reason
+            "#
+        )
+        .bind(transaction_id)
+        .bind(status.to_string())
+        .bind(failure_reason)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let transaction = transaction_from_row(transaction);
+        if approve {
+            // It now counts towards balances from the day it was created.
+            BalanceService::invalidate_snapshots(&mut tx, &transaction.account_id, transaction.created_at).await?;
+        }
+
+        // An approved transaction is charged its fee as it completes.
+        FeeService::charge(&mut tx, &transaction).await?;
+
+        let row = sqlx::query_as::<_, ScreeningRow>(&format!(
+            r#"
+            UPDATE fraud_screenings
+            SET review_status = $2, review_note = $3, reviewed_at = NOW()
+            WHERE transaction_id = $1
+            RETURNING {}
+            "#,
+            SCREENING_COLUMNS
+        ))
+        .bind(transaction_id)
+        .bind(review_status.to_string())
+        .bind(note.map(str::trim).filter(|note| !note.is_empty()))
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let review = Self::with_transactions(&mut tx, vec![row])
+            .await?
+            .pop()
+            .ok_or_else(|| FraudReviewError::NotFound(transaction_id.to_string()))?;
+
+        tx.commit().await?;
+
+        Ok(review)
+    }
+}
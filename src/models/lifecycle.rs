@@ -0,0 +1,476 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use utoipa::ToSchema;
+
+use crate::{
+    db::PgPool,
+    models::{
+        balance::BalanceService,
+        common::{ErrorCode, ErrorDetail},
+        finance::{generate_transaction_id, Currency, TransactionStatus, TransactionType},
+        requests::CreateTransactionRequest,
+        responses::AccountResponse,
+    },
+    observability::metrics::record_transaction_created,
+};
+
+/// Balances closer to zero than this count as settled.
+const BALANCE_EPSILON: f64 = 0.005;
+
+const MAX_REASON_LENGTH: usize = 500;
+
+/// `changed_by` of status changes made through the admin API.
+pub const ADMIN_ACTOR: &str = "admin";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountStatus {
+    #[default]
+    Active,
+    /// No transactions are accepted until the account is unfrozen.
+    Frozen,
+    /// No transactions are accepted; only an administrator can reopen it.
+    Closed,
+}
+
+impl std::fmt::Display for AccountStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AccountStatus::Active => write!(f, "active"),
+            AccountStatus::Frozen => write!(f, "frozen"),
+            AccountStatus::Closed => write!(f, "closed"),
+        }
+    }
+}
+
+impl std::str::FromStr for AccountStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "active" => Ok(AccountStatus::Active),
+            "frozen" => Ok(AccountStatus::Frozen),
+            "closed" => Ok(AccountStatus::Closed),
+            _ => Err(format!("Invalid account status: {}", s)),
+        }
+    }
+}
+
+impl AccountStatus {
+    pub fn accepts_transactions(&self) -> bool {
+        *self == AccountStatus::Active
+    }
+}
+
+/// Columns selected for an `AccountResponse`, in `AccountRow` order.
+pub const ACCOUNT_COLUMNS: &str =
+    "account_id, metadata, created_at, updated_at, status, status_reason, status_changed_at";
+
+pub type AccountRow = (
+    String,
+    Option<serde_json::Value>,
+    DateTime<Utc>,
+    DateTime<Utc>,
+    String,
+    Option<String>,
+    Option<DateTime<Utc>>,
+);
+
+pub fn account_from_row(row: AccountRow) -> AccountResponse {
+    AccountResponse {
+        account_id: row.0,
+        metadata: row.1,
+        created_at: row.2,
+        updated_at: row.3,
+        status: row.4.parse().unwrap_or_default(),
+        status_reason: row.5,
+        status_changed_at: row.6,
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ChangeAccountStatusRequest {
+    pub status: AccountStatus,
+
+    #[schema(example = "Customer requested closure")]
+    pub reason: String,
+
+    /// When closing an account with a non-zero balance, book a settlement
+    /// transaction that brings it to zero instead of rejecting the request.
+    #[serde(default)]
+    pub settle: bool,
+}
+
+impl ChangeAccountStatusRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.reason.trim().is_empty() {
+            return Err("Reason cannot be empty".to_string());
+        }
+
+        if self.reason.chars().count() > MAX_REASON_LENGTH {
+            return Err(format!("Reason must not exceed {} characters", MAX_REASON_LENGTH));
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AccountStatusEventResponse {
+    pub event_id: String,
+    pub account_id: String,
+    pub from_status: AccountStatus,
+    pub to_status: AccountStatus,
+    pub reason: String,
+    /// API key that made the change, or `admin`.
+    pub changed_by: String,
+    /// Transaction that zeroed the balance on closure.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub settlement_transaction_id: Option<String>,
+    #[schema(value_type = String, format = DateTime)]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AccountStatusChangeResponse {
+    pub account: AccountResponse,
+    pub event: AccountStatusEventResponse,
+}
+
+#[derive(Debug)]
+pub enum LifecycleError {
+    AccountNotFound(String),
+    InvalidTransition {
+        from: AccountStatus,
+        to: AccountStatus,
+    },
+    /// The change is only allowed through the admin API.
+    AdminOnly(String),
+    NonZeroBalance {
+        balance: f64,
+        currency: Currency,
+    },
+    Database(sqlx::Error),
+}
+
+impl From<sqlx::Error> for LifecycleError {
+    fn from(error: sqlx::Error) -> Self {
+        LifecycleError::Database(error)
+    }
+}
+
+impl std::fmt::Display for LifecycleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LifecycleError::AccountNotFound(account_id) => {
+                write!(f, "Account '{}' not found", account_id)
+            }
+            LifecycleError::InvalidTransition { from, to } => {
+                write!(f, "Cannot change account status from {} to {}", from, to)
+            }
+            LifecycleError::AdminOnly(msg) => write!(f, "{}", msg),
+            LifecycleError::NonZeroBalance { balance, currency } => write!(
+                f,
+                "Account balance is {:.2} {}; settle it before closing or pass settle=true",
+                balance, currency
+            ),
+            LifecycleError::Database(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+/// Checks whether an account may move from `from` to `to`.
+///
+/// Clients may freeze, unfreeze and close their accounts, but not unfreeze or
+/// close an account an administrator froze. Only administrators reopen
+/// closed accounts.
+pub fn check_transition(
+    from: AccountStatus,
+    to: AccountStatus,
+    by_admin: bool,
+    frozen_by_admin: bool,
+) -> Result<(), LifecycleError> {
+    match (from, to) {
+        (AccountStatus::Active, AccountStatus::Frozen) | (AccountStatus::Active, AccountStatus::Closed) => Ok(()),
+        (AccountStatus::Frozen, AccountStatus::Active) | (AccountStatus::Frozen, AccountStatus::Closed) => {
+            if frozen_by_admin && !by_admin {
+                Err(LifecycleError::AdminOnly(
+                    "Account was frozen by an administrator and can only be changed by one".to_string(),
+                ))
+            } else {
+                Ok(())
+            }
+        }
+        (AccountStatus::Closed, AccountStatus::Active) => {
+            if by_admin {
+                Ok(())
+            } else {
+                Err(LifecycleError::AdminOnly(
+                    "Only an administrator can reopen a closed account".to_string(),
+                ))
+            }
+        }
+        (from, to) => Err(LifecycleError::InvalidTransition { from, to }),
+    }
+}
+
+/// Rejects items whose account does not accept transactions. Accounts
+/// missing from `statuses` are left to `check_accounts`.
+pub fn check_account_status(
+    items: Vec<Result<CreateTransactionRequest, ErrorDetail>>,
+    statuses: &HashMap<String, AccountStatus>,
+) -> Vec<Result<CreateTransactionRequest, ErrorDetail>> {
+    items
+        .into_iter()
+        .map(|item| match item {
+            Ok(request) => match statuses.get(&request.account_id) {
+                Some(status) if !status.accepts_transactions() => Err(ErrorDetail {
+                    code: unavailable_code(*status).to_string(),
+                    message: unavailable_message(&request.account_id, *status),
+                    details: None,
+                }),
+                _ => Ok(request),
+            },
+            other => other,
+        })
+        .collect()
+}
+
+/// Error code for transactions on an account in `status`.
+pub fn unavailable_code(status: AccountStatus) -> ErrorCode {
+    match status {
+        AccountStatus::Closed => ErrorCode::AccountClosed,
+        _ => ErrorCode::AccountFrozen,
+    }
+}
+
+pub fn unavailable_message(account_id: &str, status: AccountStatus) -> String {
+    format!("Account '{}' is {} and does not accept transactions", account_id, status)
+}
+
+fn generate_event_id() -> String {
+    use rand::Rng;
+    let timestamp = Utc::now().timestamp();
+    let random: u32 = rand::rng().random();
+    format!("ase_{}_{:08x}", timestamp, random)
+}
+
+type EventRow = (
+    String,
+    String,
+    String,
+    String,
+    String,
+    String,
+    Option<String>,
+    DateTime<Utc>,
+);
+
+fn event_from_row(row: EventRow) -> AccountStatusEventResponse {
+    AccountStatusEventResponse {
+        event_id: row.0,
+        account_id: row.1,
+        from_status: row.2.parse().unwrap_or_default(),
+        to_status: row.3.parse().unwrap_or_default(),
+        reason: row.4,
+        changed_by: row.5,
+        settlement_transaction_id: row.6,
+        created_at: row.7,
+    }
+}
+
+pub struct AccountLifecycleService;
+
+impl AccountLifecycleService {
+    /// Status of an account, locking it against status changes until the
+    /// surrounding database transaction ends. Call before inserting
+    /// transactions so a concurrent freeze or closure cannot interleave.
+    pub async fn lock_status(
+        conn: &mut sqlx::PgConnection,
+        account_id: &str,
+    ) -> Result<Option<AccountStatus>, sqlx::Error> {
+        let status = sqlx::query_scalar::<_, String>(
+            "SELECT status FROM accounts WHERE account_id = $1 FOR SHARE"
+        )
+        .bind(account_id)
+        .fetch_optional(conn)
+        .await?;
+
+        Ok(status.map(|status| status.parse().unwrap_or_default()))
+    }
+
+    /// Statuses of the given accounts, locked like `lock_status`.
+    pub async fn lock_statuses(
+        conn: &mut sqlx::PgConnection,
+        account_ids: &[String],
+    ) -> Result<HashMap<String, AccountStatus>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, (String, String)>(
+            "SELECT account_id, status FROM accounts WHERE account_id = ANY($1) ORDER BY account_id FOR SHARE"
+        )
+        .bind(account_ids)
+        .fetch_all(conn)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(account_id, status)| (account_id, status.parse().unwrap_or_default()))
+            .collect())
+    }
+
+    /// Moves an account to `req.status` and records the change. `changed_by`
+    /// is the client's key id, `None` for the admin API.
+    ///
+    /// Closing requires a zero balance; with `settle` a completed adjustment
+    /// of the opposite amount is booked first.
+    pub async fn change_status(
+        pool: &PgPool,
+        account_id: &str,
+        req: &ChangeAccountStatusRequest,
+        changed_by: Option<&str>,
+    ) -> Result<AccountStatusChangeResponse, LifecycleError> {
+        let mut tx = pool.begin().await?;
+
+        let from = sqlx::query_scalar::<_, String>(
+            "SELECT status FROM accounts WHERE account_id = $1 FOR UPDATE"
+        )
+        .bind(account_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| LifecycleError::AccountNotFound(account_id.to_string()))?
+        .parse()
+        .unwrap_or_default();
+
+        let frozen_by_admin = from == AccountStatus::Frozen
+            && sqlx::query_scalar::<_, String>(
+                r#"
+                SELECT changed_by FROM account_status_events
+                WHERE account_id = $1 AND to_status = 'frozen'
+                ORDER BY created_at DESC
+                LIMIT 1
+                "#
+            )
+            .bind(account_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .is_some_and(|actor| actor == ADMIN_ACTOR);
+
+        check_transition(from, req.status, changed_by.is_none(), frozen_by_admin)?;
+
+        let mut settlement = None;
+        if req.status == AccountStatus::Closed {
+            let (balance, currency) = BalanceService::balance_at(&mut *tx, account_id, None).await?;
+            let currency = currency.unwrap_or_default();
+
+            if balance.abs() >= BALANCE_EPSILON {
+                if !req.settle {
+                    return Err(LifecycleError::NonZeroBalance { balance, currency });
+                }
+                settlement = Some(Self::settle(&mut tx, account_id, balance, currency, &req.reason).await?);
+            }
+        }
+
+        let account = sqlx::query_as::<_, AccountRow>(&format!(
+            r#"
+            UPDATE accounts
+            SET status = $1, status_reason = $2, status_changed_at = NOW(), updated_at = NOW()
+            WHERE account_id = $3
+            RETURNING {}
+            "#,
+            ACCOUNT_COLUMNS
+        ))
+        .bind(req.status.to_string())
+        .bind(req.reason.trim())
+        .bind(account_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let event = sqlx::query_as::<_, EventRow>(
+            r#"
+            INSERT INTO account_status_events (
+                event_id, account_id, from_status, to_status, reason,
+                changed_by, settlement_transaction_id
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING event_id, account_id, from_status, to_status, reason,
+                      changed_by, settlement_transaction_id, created_at
+            "#
+        )
+        .bind(generate_event_id())
+        .bind(account_id)
+        .bind(from.to_string())
+        .bind(req.status.to_string())
+        .bind(req.reason.trim())
+        .bind(changed_by.unwrap_or(ADMIN_ACTOR))
+        .bind(settlement.as_ref().map(|(id, _)| id))
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        if let Some((_, currency)) = settlement {
+            record_transaction_created(&TransactionType::Adjustment.to_string(), &currency.to_string());
+        }
+
+        Ok(AccountStatusChangeResponse {
+            account: account_from_row(account),
+            event: event_from_row(event),
+        })
+    }
+
+    /// Books a completed adjustment that brings `balance` to zero.
+    async fn settle(
+        conn: &mut sqlx::PgConnection,
+        account_id: &str,
+        balance: f64,
+        currency: Currency,
+        reason: &str,
+    ) -> Result<(String, Currency), sqlx::Error> {
+        let transaction_id = generate_transaction_id();
+
+        sqlx::query(
+            r#"
+            INSERT INTO transactions (
+                transaction_id, account_id, amount, currency,
+                transaction_type, status, description, metadata,
+                created_at, processed_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, NOW(), NOW())
+            "#
+        )
+        .bind(&transaction_id)
+        .bind(account_id)
+        .bind(-balance)
+        .bind(currency.to_string())
+        .bind(TransactionType::Adjustment.to_string())
+        .bind(TransactionStatus::Completed.to_string())
+        .bind("Account closure settlement")
+        .bind(json!({ "settlement": "account_closure", "reason": reason.trim() }))
+        .execute(conn)
+        .await?;
+
+        Ok((transaction_id, currency))
+    }
+
+    /// Status changes of an account, newest first.
+    pub async fn history(
+        pool: &PgPool,
+        account_id: &str,
+    ) -> Result<Vec<AccountStatusEventResponse>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, EventRow>(
+            r#"
+            SELECT event_id, account_id, from_status, to_status, reason,
+                   changed_by, settlement_transaction_id, created_at
+            FROM account_status_events
+            WHERE account_id = $1
+            ORDER BY created_at DESC, event_id DESC
+            "#
+        )
+        .bind(account_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows.into_iter().map(event_from_row).collect())
+    }
+}
@@ -0,0 +1,547 @@
+use chrono::{DateTime, Datelike, Duration, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use utoipa::ToSchema;
+
+use crate::{
+    db::PgPool,
+    models::{
+        balance::BALANCE_SUM,
+        common::{ErrorCode, ErrorDetail},
+        finance::{Currency, FailureReason, TransactionType},
+        requests::CreateTransactionRequest,
+    },
+};
+
+/// Limits applying to transactions in one currency. Unset limits are not
+/// enforced.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct CurrencyLimits {
+    pub currency: Currency,
+
+    /// Largest amount of a single transaction of any type.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = 5000.0)]
+    pub max_amount: Option<f64>,
+
+    /// Total of completed outflows per UTC day.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = 10000.0)]
+    pub daily_outflow_limit: Option<f64>,
+
+    /// Total of completed outflows per UTC calendar month.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = 100000.0)]
+    pub monthly_outflow_limit: Option<f64>,
+
+    /// How far below zero an outflow may take the net position; `0` forbids
+    /// going negative.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = 0.0)]
+    pub overdraft_limit: Option<f64>,
+}
+
+impl CurrencyLimits {
+    fn validate(&self) -> Result<(), String> {
+        let limits = [
+            ("max_amount", self.max_amount),
+            ("daily_outflow_limit", self.daily_outflow_limit),
+            ("monthly_outflow_limit", self.monthly_outflow_limit),
+            ("overdraft_limit", self.overdraft_limit),
+        ];
+
+        for (name, value) in limits {
+            if let Some(value) = value {
+                if !value.is_finite() || value < 0.0 {
+                    return Err(format!(
+                        "{} for {} must be a non-negative number",
+                        name, self.currency
+                    ));
+                }
+            }
+        }
+
+        if self.max_amount == Some(0.0) {
+            return Err(format!("max_amount for {} must be greater than 0", self.currency));
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct SetAccountLimitsRequest {
+    /// Most transactions the account may create in any rolling hour, across
+    /// currencies. Failed transactions do not count.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = 100)]
+    pub max_transactions_per_hour: Option<i32>,
+
+    /// Limits per currency; currencies not listed have none.
+    #[serde(default)]
+    pub currencies: Vec<CurrencyLimits>,
+}
+
+impl SetAccountLimitsRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(max) = self.max_transactions_per_hour {
+            if max < 1 {
+                return Err("max_transactions_per_hour must be at least 1".to_string());
+            }
+        }
+
+        let mut seen = HashSet::new();
+        for limits in &self.currencies {
+            if !seen.insert(limits.currency) {
+                return Err(format!("Limits for {} are listed more than once", limits.currency));
+            }
+            limits.validate()?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AccountLimitsResponse {
+    pub account_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_transactions_per_hour: Option<i32>,
+    pub currencies: Vec<CurrencyLimits>,
+    #[schema(value_type = String, format = DateTime)]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl AccountLimitsResponse {
+    pub fn for_currency(&self, currency: Currency) -> Option<&CurrencyLimits> {
+        self.currencies.iter().find(|limits| limits.currency == currency)
+    }
+}
+
+/// Outflows and net position of an account in one currency.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CurrencyUsage {
+    pub daily_outflow: f64,
+    pub monthly_outflow: f64,
+    /// Sum of the completed amounts, as for the account balance.
+    pub net_position: f64,
+}
+
+/// What an account has used of its limits so far.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LimitUsage {
+    pub transactions_last_hour: i64,
+    pub currencies: HashMap<Currency, CurrencyUsage>,
+}
+
+impl LimitUsage {
+    /// Adds a transaction created as completed, so later items of the same
+    /// batch see it.
+    pub fn record(&mut self, req: &CreateTransactionRequest) {
+        self.transactions_last_hour += 1;
+
+        let usage = self.currencies.entry(req.currency).or_default();
+        if req.transaction_type.is_outflow() {
+            usage.daily_outflow += req.amount;
+            usage.monthly_outflow += req.amount;
+        }
+        usage.net_position += req.transaction_type.ledger_amount(req.amount);
+    }
+}
+
+/// A limit a transaction would break.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LimitViolation {
+    pub reason: FailureReason,
+    pub limit: f64,
+    pub currency: Currency,
+}
+
+impl std::fmt::Display for LimitViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.reason {
+            FailureReason::AmountLimitExceeded => write!(
+                f,
+                "Amount exceeds the account's limit of {:.2} {} per transaction",
+                self.limit, self.currency
+            ),
+            FailureReason::DailyLimitExceeded => write!(
+                f,
+                "Transaction would exceed the account's daily outflow limit of {:.2} {}",
+                self.limit, self.currency
+            ),
+            FailureReason::MonthlyLimitExceeded => write!(
+                f,
+                "Transaction would exceed the account's monthly outflow limit of {:.2} {}",
+                self.limit, self.currency
+            ),
+            FailureReason::VelocityLimitExceeded => write!(
+                f,
+                "Account has reached its limit of {} transactions per hour",
+                self.limit
+            ),
+            _ => write!(
+                f,
+                "Insufficient funds: transaction would exceed the account's overdraft limit of {:.2} {}",
+                self.limit, self.currency
+            ),
+        }
+    }
+}
+
+impl LimitViolation {
+    pub fn detail(&self) -> ErrorDetail {
+        ErrorDetail {
+            code: ErrorCode::LimitExceeded.to_string(),
+            message: self.to_string(),
+            details: Some(json!({
+                "failure_reason": self.reason,
+                "limit": self.limit,
+            })),
+        }
+    }
+}
+
+/// Checks a transaction against the account's limits, in the order amount,
+/// velocity, daily and monthly outflow, overdraft.
+pub fn check_limits(
+    limits: &AccountLimitsResponse,
+    usage: &LimitUsage,
+    req: &CreateTransactionRequest,
+) -> Result<(), LimitViolation> {
+    let violation = |reason, limit| LimitViolation {
+        reason,
+        limit,
+        currency: req.currency,
+    };
+    let currency_limits = limits.for_currency(req.currency);
+
+    if let Some(max) = currency_limits.and_then(|l| l.max_amount) {
+        if req.amount > max {
+            return Err(violation(FailureReason::AmountLimitExceeded, max));
+        }
+    }
+
+    if let Some(max) = limits.max_transactions_per_hour {
+        if usage.transactions_last_hour >= i64::from(max) {
+            return Err(violation(FailureReason::VelocityLimitExceeded, f64::from(max)));
+        }
+    }
+
+    let Some(currency_limits) = currency_limits else {
+        return Ok(());
+    };
+    if !req.transaction_type.is_outflow() {
+        return Ok(());
+    }
+
+    let used = usage.currencies.get(&req.currency).copied().unwrap_or_default();
+
+    if let Some(limit) = currency_limits.daily_outflow_limit {
+        if used.daily_outflow + req.amount > limit {
+            return Err(violation(FailureReason::DailyLimitExceeded, limit));
+        }
+    }
+
+    if let Some(limit) = currency_limits.monthly_outflow_limit {
+        if used.monthly_outflow + req.amount > limit {
+            return Err(violation(FailureReason::MonthlyLimitExceeded, limit));
+        }
+    }
+
+    if let Some(limit) = currency_limits.overdraft_limit {
+        if used.net_position + req.transaction_type.ledger_amount(req.amount) < -limit {
+            return Err(violation(FailureReason::InsufficientFunds, limit));
+        }
+    }
+
+    Ok(())
+}
+
+/// Rejects batch items that would break their account's limits, counting
+/// earlier accepted items of the batch against later ones.
+pub fn apply_limits(
+    items: Vec<Result<CreateTransactionRequest, ErrorDetail>>,
+    limits: &HashMap<String, AccountLimitsResponse>,
+    usage: &mut HashMap<String, LimitUsage>,
+) -> Vec<Result<CreateTransactionRequest, ErrorDetail>> {
+    items
+        .into_iter()
+        .map(|item| {
+            let request = item?;
+            let Some(account_limits) = limits.get(&request.account_id) else {
+                return Ok(request);
+            };
+
+            let account_usage = usage.entry(request.account_id.clone()).or_default();
+            match check_limits(account_limits, account_usage, &request) {
+                Ok(()) => {
+                    account_usage.record(&request);
+                    Ok(request)
+                }
+                Err(violation) => Err(violation.detail()),
+            }
+        })
+        .collect()
+}
+
+/// Starts of the rolling hour, the UTC day and the UTC month containing `now`.
+pub fn usage_windows(now: DateTime<Utc>) -> (DateTime<Utc>, DateTime<Utc>, DateTime<Utc>) {
+    let day = now.date_naive();
+    let day_start = Utc.from_utc_datetime(&day.and_hms_opt(0, 0, 0).unwrap_or_default());
+    let month_start = Utc.from_utc_datetime(
+        &day.with_day(1)
+            .unwrap_or(day)
+            .and_hms_opt(0, 0, 0)
+            .unwrap_or_default(),
+    );
+
+    (now - Duration::hours(1), day_start, month_start)
+}
+
+type CurrencyLimitRow = (
+    String,
+    String,
+    Option<f64>,
+    Option<f64>,
+    Option<f64>,
+    Option<f64>,
+);
+
+pub struct AccountLimitService;
+
+impl AccountLimitService {
+    pub async fn get(
+        pool: &PgPool,
+        account_id: &str,
+    ) -> Result<Option<AccountLimitsResponse>, sqlx::Error> {
+        let mut conn = pool.acquire().await?;
+        let mut limits = Self::load(&mut conn, &[account_id.to_string()], false).await?;
+        Ok(limits.remove(account_id))
+    }
+
+    /// Replaces the limits of an account.
+    pub async fn set(
+        pool: &PgPool,
+        account_id: &str,
+        req: &SetAccountLimitsRequest,
+    ) -> Result<AccountLimitsResponse, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO account_limits (account_id, max_transactions_per_hour)
+            VALUES ($1, $2)
+            ON CONFLICT (account_id) DO UPDATE SET
+                max_transactions_per_hour = EXCLUDED.max_transactions_per_hour,
+                updated_at = NOW()
+            "#
+        )
+        .bind(account_id)
+        .bind(req.max_transactions_per_hour)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("DELETE FROM account_currency_limits WHERE account_id = $1")
+            .bind(account_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO account_currency_limits (
+                account_id, currency, max_amount, daily_outflow_limit,
+                monthly_outflow_limit, overdraft_limit
+            )
+            SELECT $1, * FROM UNNEST(
+                $2::text[], $3::float8[], $4::float8[], $5::float8[], $6::float8[]
+            )
+            "#
+        )
+        .bind(account_id)
+        .bind(req.currencies.iter().map(|l| l.currency.to_string()).collect::<Vec<_>>())
+        .bind(req.currencies.iter().map(|l| l.max_amount).collect::<Vec<_>>())
+        .bind(req.currencies.iter().map(|l| l.daily_outflow_limit).collect::<Vec<_>>())
+        .bind(req.currencies.iter().map(|l| l.monthly_outflow_limit).collect::<Vec<_>>())
+        .bind(req.currencies.iter().map(|l| l.overdraft_limit).collect::<Vec<_>>())
+        .execute(&mut *tx)
+        .await?;
+
+        let limits = Self::load(&mut tx, &[account_id.to_string()], false).await?;
+        tx.commit().await?;
+
+        limits
+            .into_values()
+            .next()
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    /// Removes every limit of an account. Returns whether it had any.
+    pub async fn remove(pool: &PgPool, account_id: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM account_limits WHERE account_id = $1")
+            .bind(account_id)
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Limits of the given accounts that have any, locking them until the
+    /// surrounding database transaction ends. Transactions on the same
+    /// account therefore check their limits one at a time.
+    pub async fn lock(
+        conn: &mut sqlx::PgConnection,
+        account_ids: &[String],
+    ) -> Result<HashMap<String, AccountLimitsResponse>, sqlx::Error> {
+        Self::load(conn, account_ids, true).await
+    }
+
+    async fn load(
+        conn: &mut sqlx::PgConnection,
+        account_ids: &[String],
+        for_update: bool,
+    ) -> Result<HashMap<String, AccountLimitsResponse>, sqlx::Error> {
+        let query = format!(
+            r#"
+            SELECT account_id, max_transactions_per_hour, updated_at
+            FROM account_limits
+            WHERE account_id = ANY($1)
+            ORDER BY account_id
+            {}
+            "#,
+            if for_update { "FOR UPDATE" } else { "" }
+        );
+
+        let rows = sqlx::query_as::<_, (String, Option<i32>, DateTime<Utc>)>(&query)
+            .bind(account_ids)
+            .fetch_all(&mut *conn)
+            .await?;
+
+        if rows.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let mut limits: HashMap<String, AccountLimitsResponse> = rows
+            .into_iter()
+            .map(|(account_id, max_transactions_per_hour, updated_at)| {
+                (
+                    account_id.clone(),
+                    AccountLimitsResponse {
+                        account_id,
+                        max_transactions_per_hour,
+                        currencies: Vec::new(),
+                        updated_at,
+                    },
+                )
+            })
+            .collect();
+
+        let currency_rows = sqlx::query_as::<_, CurrencyLimitRow>(
+            r#"
+            SELECT account_id, currency, max_amount, daily_outflow_limit,
+                   monthly_outflow_limit, overdraft_limit
+            FROM account_currency_limits
+            WHERE account_id = ANY($1)
+            ORDER BY account_id, currency
+            "#
+        )
+        .bind(account_ids)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        for row in currency_rows {
+            let Ok(currency) = row.1.parse() else {
+                continue;
+            };
+            if let Some(account) = limits.get_mut(&row.0) {
+                account.currencies.push(CurrencyLimits {
+                    currency,
+                    max_amount: row.2,
+                    daily_outflow_limit: row.3,
+                    monthly_outflow_limit: row.4,
+                    overdraft_limit: row.5,
+                });
+            }
+        }
+
+        Ok(limits)
+    }
+
+    /// Usage of the given accounts at `now`. Run after `lock` so it cannot
+    /// change before the new transactions are inserted.
+    pub async fn usage(
+        conn: &mut sqlx::PgConnection,
+        account_ids: &[String],
+        now: DateTime<Utc>,
+    ) -> Result<HashMap<String, LimitUsage>, sqlx::Error> {
+        let (hour_start, day_start, month_start) = usage_windows(now);
+        let outflows: Vec<String> = TransactionType::OUTFLOWS
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        let rows = sqlx::query_as::<_, (String, String, i64, f64, f64, f64)>(&format!(
+            r#"
+            SELECT
+                account_id,
+                currency,
+                COUNT(*) FILTER (WHERE created_at >= $2 AND status <> 'failed'),
+                COALESCE(SUM(ABS(amount)) FILTER (
+                    WHERE status = 'completed' AND transaction_type = ANY($5) AND created_at >= $3
+                ), 0.0),
+                COALESCE(SUM(ABS(amount)) FILTER (
+                    WHERE status = 'completed' AND transaction_type = ANY($5) AND created_at >= $4
+                ), 0.0),
+                {}
+            FROM transactions
+            WHERE account_id = ANY($1)
+            GROUP BY account_id, currency
+            "#,
+            BALANCE_SUM
+        ))
+        .bind(account_ids)
+        .bind(hour_start)
+        .bind(day_start)
+        .bind(month_start)
+        .bind(&outflows)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        let mut usage: HashMap<String, LimitUsage> = HashMap::new();
+        for (account_id, currency, count, daily, monthly, net) in rows {
+            let account = usage.entry(account_id).or_default();
+            account.transactions_last_hour += count;
+            if let Ok(currency) = currency.parse() {
+                account.currencies.insert(
+                    currency,
+                    CurrencyUsage {
+                        daily_outflow: daily,
+                        monthly_outflow: monthly,
+                        net_position: net,
+                    },
+                );
+            }
+        }
+
+        Ok(usage)
+    }
+
+    /// Locks the account's limits and checks `req` against them. Returns the
+    /// limit it would break; accounts without limits always pass.
+    pub async fn check(
+        conn: &mut sqlx::PgConnection,
+        req: &CreateTransactionRequest,
+    ) -> Result<Option<LimitViolation>, sqlx::Error> {
+        let account_ids = [req.account_id.clone()];
+        let limits = Self::lock(&mut *conn, &account_ids).await?;
+        let Some(account_limits) = limits.get(&req.account_id) else {
+            return Ok(None);
+        };
+
+        let usage = Self::usage(&mut *conn, &account_ids, Utc::now()).await?;
+        let account_usage = usage.get(&req.account_id).cloned().unwrap_or_default();
+
+        Ok(check_limits(account_limits, &account_usage, req).err())
+    }
+}
@@ -0,0 +1,384 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use utoipa::ToSchema;
+
+use crate::db::PgPool;
+
+/// Deepest nesting of objects and arrays; a flat object is depth 1.
+pub const MAX_METADATA_DEPTH: usize = 5;
+/// Largest serialized size of a metadata document.
+pub const MAX_METADATA_BYTES: usize = 16 * 1024;
+/// Most object keys in a metadata document, counted at every level.
+pub const MAX_METADATA_KEYS: usize = 100;
+
+const MAX_SCHEMA_BYTES: usize = 64 * 1024;
+/// Schema violations reported per document.
+const MAX_REPORTED_VIOLATIONS: usize = 10;
+
+const ROOT_PATH: &str = "$.metadata";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MetadataTarget {
+    Account,
+    Transaction,
+}
+
+impl std::fmt::Display for MetadataTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MetadataTarget::Account => write!(f, "account"),
+            MetadataTarget::Transaction => write!(f, "transaction"),
+        }
+    }
+}
+
+impl std::str::FromStr for MetadataTarget {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "account" => Ok(MetadataTarget::Account),
+            "transaction" => Ok(MetadataTarget::Transaction),
+            _ => Err(format!("Invalid metadata target: {}", s)),
+        }
+    }
+}
+
+/// A place in a metadata document that broke a limit or the schema.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct MetadataViolation {
+    /// JSON path of the offending value, e.g. `$.metadata.customer.tags[2]`
+    pub path: String,
+    pub message: String,
+}
+
+/// Metadata that broke a limit or the tenant's schema. Never empty.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidMetadata {
+    pub violations: Vec<MetadataViolation>,
+}
+
+impl InvalidMetadata {
+    fn single(path: String, message: String) -> Self {
+        InvalidMetadata {
+            violations: vec![MetadataViolation { path, message }],
+        }
+    }
+
+    /// Path of the first violation.
+    pub fn path(&self) -> &str {
+        self.violations.first().map_or(ROOT_PATH, |violation| violation.path.as_str())
+    }
+
+    /// `ErrorDetail.details` for the error: the first offending path and
+    /// every violation found.
+    pub fn details(&self) -> Value {
+        json!({
+            "path": self.path(),
+            "violations": self.violations,
+        })
+    }
+}
+
+impl std::fmt::Display for InvalidMetadata {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.violations.first() {
+            Some(first) => {
+                write!(f, "Invalid metadata at {}: {}", first.path, first.message)?;
+                if self.violations.len() > 1 {
+                    write!(f, " (and {} more)", self.violations.len() - 1)?;
+                }
+                Ok(())
+            }
+            None => write!(f, "Invalid metadata"),
+        }
+    }
+}
+
+fn is_plain_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn child_path(path: &str, key: &str) -> String {
+    if is_plain_key(key) {
+        format!("{}.{}", path, key)
+    } else {
+        format!("{}['{}']", path, key.replace('\\', "\\\\").replace('\'', "\\'"))
+    }
+}
+
+/// JSON path under `$.metadata` of the value a JSON pointer names in
+/// `document`; array indexes are told from numeric keys by the document.
+pub fn pointer_to_path(document: &Value, pointer: &str) -> String {
+    let mut path = ROOT_PATH.to_string();
+    let mut current = Some(document);
+
+    for token in pointer.split('/').skip(1) {
+        let token = token.replace("~1", "/").replace("~0", "~");
+        match current {
+            Some(Value::Array(items)) => {
+                path.push_str(&format!("[{}]", token));
+                current = token.parse::<usize>().ok().and_then(|index| items.get(index));
+            }
+            Some(Value::Object(map)) => {
+                path = child_path(&path, &token);
+                current = map.get(&token);
+            }
+            _ => {
+                path = child_path(&path, &token);
+                current = None;
+            }
+        }
+    }
+
+    path
+}
+
+/// Checks a metadata document against the depth, size and key-count limits.
+pub fn check_limits(metadata: &Value) -> Result<(), InvalidMetadata> {
+    let size = serde_json::to_vec(metadata).map_or(0, |bytes| bytes.len());
+    if size > MAX_METADATA_BYTES {
+        return Err(InvalidMetadata::single(
+            ROOT_PATH.to_string(),
+            format!("Metadata must not exceed {} bytes (got {})", MAX_METADATA_BYTES, size),
+        ));
+    }
+
+    let mut keys = 0;
+    walk(metadata, ROOT_PATH.to_string(), 0, &mut keys)
+}
+
+fn walk(value: &Value, path: String, depth: usize, keys: &mut usize) -> Result<(), InvalidMetadata> {
+    let children: Vec<(String, &Value)> = match value {
+        Value::Object(map) => {
+            *keys += map.len();
+            if *keys > MAX_METADATA_KEYS {
+                return Err(InvalidMetadata::single(
+                    path,
+                    format!("Metadata must not have more than {} keys", MAX_METADATA_KEYS),
+                ));
+            }
+            map.iter().map(|(key, child)| (child_path(&path, key), child)).collect()
+        }
+        Value::Array(items) => items
+            .iter()
+            .enumerate()
+            .map(|(index, child)| (format!("{}[{}]", path, index), child))
+            .collect(),
+        _ => return Ok(()),
+    };
+
+    if depth + 1 > MAX_METADATA_DEPTH {
+        return Err(InvalidMetadata::single(
+            path,
+            format!("Metadata must not be nested more than {} levels deep", MAX_METADATA_DEPTH),
+        ));
+    }
+
+    for (child_path, child) in children {
+        walk(child, child_path, depth + 1, keys)?;
+    }
+
+    Ok(())
+}
+
+/// A tenant's compiled JSON Schema for one kind of metadata.
+pub struct MetadataSchema {
+    validator: jsonschema::Validator,
+}
+
+impl MetadataSchema {
+    pub fn compile(schema: &Value) -> Result<Self, String> {
+        let size = serde_json::to_vec(schema).map_or(0, |bytes| bytes.len());
+        if size > MAX_SCHEMA_BYTES {
+            return Err(format!("Schema must not exceed {} bytes", MAX_SCHEMA_BYTES));
+        }
+        if !schema.is_object() {
+            return Err("Schema must be a JSON object".to_string());
+        }
+
+        let validator = jsonschema::validator_for(schema).map_err(|e| format!("Invalid JSON Schema: {}", e))?;
+
+        Ok(MetadataSchema { validator })
+    }
+
+    pub fn check(&self, metadata: &Value) -> Result<(), InvalidMetadata> {
+        let violations: Vec<MetadataViolation> = self
+            .validator
+            .iter_errors(metadata)
+            .take(MAX_REPORTED_VIOLATIONS)
+            .map(|error| MetadataViolation {
+                path: pointer_to_path(metadata, error.instance_path.as_str()),
+                message: error.to_string(),
+            })
+            .collect();
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(InvalidMetadata { violations })
+        }
+    }
+}
+
+/// Checks metadata against the limits and, if the tenant registered one,
+/// its schema. Missing metadata is checked against the schema as an empty
+/// object, so schemas can require keys.
+pub fn check_metadata(metadata: Option<&Value>, schema: Option<&MetadataSchema>) -> Result<(), InvalidMetadata> {
+    if let Some(metadata) = metadata {
+        check_limits(metadata)?;
+    }
+
+    match (schema, metadata) {
+        (Some(schema), Some(metadata)) => schema.check(metadata),
+        (Some(schema), None) => schema.check(&json!({})),
+        (None, _) => Ok(()),
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RegisterMetadataSchemaRequest {
+    /// JSON Schema (draft 4 to 2020-12) the metadata must match
+    #[schema(value_type = Object)]
+    pub schema: Value,
+}
+
+impl RegisterMetadataSchemaRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        MetadataSchema::compile(&self.schema).map(|_| ())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MetadataSchemaResponse {
+    pub target: MetadataTarget,
+    #[schema(value_type = Object)]
+    pub schema: Value,
+    #[schema(value_type = String, format = DateTime)]
+    pub created_at: DateTime<Utc>,
+    #[schema(value_type = String, format = DateTime)]
+    pub updated_at: DateTime<Utc>,
+}
+
+const SCHEMA_COLUMNS: &str = "target, schema, created_at, updated_at";
+
+type SchemaRow = (String, Value, DateTime<Utc>, DateTime<Utc>);
+
+fn schema_from_row(row: SchemaRow) -> MetadataSchemaResponse {
+    MetadataSchemaResponse {
+        target: row.0.parse().unwrap_or(MetadataTarget::Account),
+        schema: row.1,
+        created_at: row.2,
+        updated_at: row.3,
+    }
+}
+
+pub struct MetadataSchemaService;
+
+impl MetadataSchemaService {
+    /// Registers or replaces the key's schema for `target`. Metadata already
+    /// stored is not re-checked.
+    pub async fn put(
+        pool: &PgPool,
+        key_id: &str,
+        target: MetadataTarget,
+        schema: &Value,
+    ) -> Result<MetadataSchemaResponse, sqlx::Error> {
+        let row = sqlx::query_as::<_, SchemaRow>(&format!(
+            r#"
+            INSERT INTO metadata_schemas (key_id, target, schema)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (key_id, target) DO UPDATE
+            SET schema = EXCLUDED.schema, updated_at = NOW()
+            RETURNING {}
+            "#,
+            SCHEMA_COLUMNS
+        ))
+        .bind(key_id)
+        .bind(target.to_string())
+        .bind(schema)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(schema_from_row(row))
+    }
+
+    pub async fn get(
+        pool: &PgPool,
+        key_id: &str,
+        target: MetadataTarget,
+    ) -> Result<Option<MetadataSchemaResponse>, sqlx::Error> {
+        let row = sqlx::query_as::<_, SchemaRow>(&format!(
+            "SELECT {} FROM metadata_schemas WHERE key_id = $1 AND target = $2",
+            SCHEMA_COLUMNS
+        ))
+        .bind(key_id)
+        .bind(target.to_string())
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(row.map(schema_from_row))
+    }
+
+    pub async fn list(pool: &PgPool, key_id: &str) -> Result<Vec<MetadataSchemaResponse>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, SchemaRow>(&format!(
+            "SELECT {} FROM metadata_schemas WHERE key_id = $1 ORDER BY target",
+            SCHEMA_COLUMNS
+        ))
+        .bind(key_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows.into_iter().map(schema_from_row).collect())
+    }
+
+    /// Removes the key's schema for `target`; returns whether there was one.
+    pub async fn delete(pool: &PgPool, key_id: &str, target: MetadataTarget) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM metadata_schemas WHERE key_id = $1 AND target = $2")
+            .bind(key_id)
+            .bind(target.to_string())
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Compiled schema metadata written with `key_id` must match, if any.
+    /// Administrators (no key) have none.
+    pub async fn for_key<'e, E>(
+        executor: E,
+        key_id: Option<&str>,
+        target: MetadataTarget,
+    ) -> Result<Option<MetadataSchema>, sqlx::Error>
+    where
+        E: sqlx::Executor<'e, Database = sqlx::Postgres>,
+    {
+        let Some(key_id) = key_id else {
+            return Ok(None);
+        };
+
+        let schema = sqlx::query_scalar::<_, Value>(
+            "SELECT schema FROM metadata_schemas WHERE key_id = $1 AND target = $2"
+        )
+        .bind(key_id)
+        .bind(target.to_string())
+        .fetch_optional(executor)
+        .await?;
+
+        // Schemas are checked when registered; one that no longer compiles
+        // is skipped rather than rejecting every write.
+        Ok(schema.and_then(|schema| match MetadataSchema::compile(&schema) {
+            Ok(schema) => Some(schema),
+            Err(e) => {
+                tracing::warn!("Skipping metadata schema of key {}: {}", key_id, e);
+                None
+            }
+        }))
+    }
+}
@@ -1,7 +1,24 @@
+pub mod balance;
+pub mod batch;
 pub mod common;
+pub mod disputes;
+pub mod export;
+pub mod fees;
 pub mod finance;
+pub mod fraud;
 pub mod keys;
+pub mod lifecycle;
+pub mod limits;
+pub mod metadata;
+pub mod pagination;
+pub mod payouts;
+pub mod posting;
 pub mod quota;
+pub mod reconciliation;
 pub mod requests;
+pub mod reversals;
+pub mod schedules;
 pub mod responses;
-pub mod analytics;
\ No newline at end of file
+pub mod statement;
+pub mod analytics;
+pub mod rollups;
\ No newline at end of file
@@ -0,0 +1,286 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, SecondsFormat, Utc};
+use hmac::{Hmac, Mac};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::sync::Arc;
+
+use crate::models::common::{Cursor, PaginatedResponse, PaginationParams, SortOrder};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Version written into every cursor. Bump it when the payload changes so
+/// old cursors are rejected instead of misread.
+pub const CURSOR_VERSION: u8 = 1;
+
+const DEFAULT_LIMIT: u32 = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PageDirection {
+    /// Rows after the position, in sort order.
+    Next,
+    /// Rows before the position, in sort order.
+    Prev,
+}
+
+/// What a cursor points at: the listing it belongs to, the row it is
+/// relative to (sort key plus unique tie-breaker) and the paging direction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CursorPosition {
+    #[serde(rename = "v")]
+    pub version: u8,
+    /// Listing the cursor was issued for, e.g. `transactions`.
+    #[serde(rename = "r")]
+    pub resource: String,
+    #[serde(rename = "s")]
+    pub sort: String,
+    #[serde(rename = "o")]
+    pub order: SortOrder,
+    #[serde(rename = "k")]
+    pub key: String,
+    #[serde(rename = "id")]
+    pub id: String,
+    #[serde(rename = "d")]
+    pub direction: PageDirection,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CursorError {
+    InvalidFormat,
+    InvalidSignature,
+    UnsupportedVersion(u8),
+    /// The cursor was issued for another listing or sort.
+    Mismatch,
+}
+
+impl std::fmt::Display for CursorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CursorError::InvalidFormat => write!(f, "Invalid cursor format"),
+            CursorError::InvalidSignature => write!(f, "Cursor signature does not match"),
+            CursorError::UnsupportedVersion(version) => {
+                write!(f, "Unsupported cursor version {}", version)
+            }
+            CursorError::Mismatch => write!(
+                f,
+                "Cursor was issued for a different listing or sort; restart from the first page"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CursorError {}
+
+/// Signs and verifies cursors so clients cannot forge positions.
+///
+/// A cursor is `base64url(json payload) "." base64url(HMAC-SHA256)`.
+#[derive(Clone)]
+pub struct CursorSigner {
+    key: Arc<[u8]>,
+}
+
+impl CursorSigner {
+    pub fn new(secret: &[u8]) -> Self {
+        Self { key: secret.into() }
+    }
+
+    /// Signer with a per-process key; cursors stop working after a restart.
+    pub fn random() -> Self {
+        let key: [u8; 32] = rand::rng().random();
+        Self::new(&key)
+    }
+
+    pub fn sign(&self, position: &CursorPosition) -> Cursor {
+        let payload =
+            URL_SAFE_NO_PAD.encode(serde_json::to_vec(position).unwrap_or_default());
+        let signature = URL_SAFE_NO_PAD.encode(self.mac(payload.as_bytes()).finalize().into_bytes());
+
+        Cursor(format!("{}.{}", payload, signature))
+    }
+
+    pub fn verify(&self, cursor: &Cursor) -> Result<CursorPosition, CursorError> {
+        let (payload, signature) = cursor
+            .0
+            .split_once('.')
+            .ok_or(CursorError::InvalidFormat)?;
+        let signature = URL_SAFE_NO_PAD
+            .decode(signature)
+            .map_err(|_| CursorError::InvalidFormat)?;
+
+        self.mac(payload.as_bytes())
+            .verify_slice(&signature)
+            .map_err(|_| CursorError::InvalidSignature)?;
+
+        let payload = URL_SAFE_NO_PAD
+            .decode(payload)
+            .map_err(|_| CursorError::InvalidFormat)?;
+        let position: CursorPosition =
+            serde_json::from_slice(&payload).map_err(|_| CursorError::InvalidFormat)?;
+
+        if position.version != CURSOR_VERSION {
+            return Err(CursorError::UnsupportedVersion(position.version));
+        }
+
+        Ok(position)
+    }
+
+    fn mac(&self, payload: &[u8]) -> HmacSha256 {
+        let mut mac = HmacSha256::new_from_slice(&self.key).expect("HMAC accepts any key length");
+        mac.update(payload);
+        mac
+    }
+}
+
+/// Cursor key for timestamp sort columns, at the database's microsecond precision.
+pub fn timestamp_key(ts: &DateTime<Utc>) -> String {
+    ts.to_rfc3339_opts(SecondsFormat::Micros, true)
+}
+
+/// One page of a keyset-paginated listing ordered by a sort column with a
+/// unique tie-breaker column.
+#[derive(Debug, Clone)]
+pub struct KeysetPage {
+    pub resource: &'static str,
+    pub sort: String,
+    pub order: SortOrder,
+    pub position: Option<CursorPosition>,
+    pub limit: i64,
+}
+
+impl KeysetPage {
+    /// Validates `params` and resolves its cursor, which must have been issued
+    /// for the same resource and sort.
+    pub fn new(
+        params: &PaginationParams,
+        signer: &CursorSigner,
+        resource: &'static str,
+        sort: &str,
+        order: SortOrder,
+    ) -> Result<Self, CursorError> {
+        let position = params
+            .cursor
+            .as_ref()
+            .map(|cursor| signer.verify(cursor))
+            .transpose()?;
+
+        if let Some(position) = &position {
+            if position.resource != resource || position.sort != sort || position.order != order {
+                return Err(CursorError::Mismatch);
+            }
+        }
+
+        Ok(Self {
+            resource,
+            sort: sort.to_string(),
+            order,
+            position,
+            limit: params.limit.unwrap_or(DEFAULT_LIMIT) as i64,
+        })
+    }
+
+    fn direction(&self) -> PageDirection {
+        self.position
+            .as_ref()
+            .map(|position| position.direction)
+            .unwrap_or(PageDirection::Next)
+    }
+
+    /// Order rows are fetched in: reversed when paging backwards.
+    fn scan_order(&self) -> SortOrder {
+        match (self.direction(), self.order) {
+            (PageDirection::Next, order) => order,
+            (PageDirection::Prev, SortOrder::Asc) => SortOrder::Desc,
+            (PageDirection::Prev, SortOrder::Desc) => SortOrder::Asc,
+        }
+    }
+
+    /// Keyset condition (` AND (column, id) > ($n::cast, $n+1)`) and its bind
+    /// values, or `None` on the first page.
+    pub fn condition(
+        &self,
+        column: &str,
+        cast: &str,
+        id_column: &str,
+        first_param: usize,
+    ) -> Option<(String, Vec<String>)> {
+        let position = self.position.as_ref()?;
+        Some((
+            format!(
+                " AND ({}, {}) {} (${}::{}, ${})",
+                column,
+                id_column,
+                self.scan_order().after_operator(),
+                first_param,
+                cast,
+                first_param + 1
+            ),
+            vec![position.key.clone(), position.id.clone()],
+        ))
+    }
+
+    pub fn order_by(&self, column: &str, id_column: &str) -> String {
+        let order = self.scan_order().sql();
+        format!(" ORDER BY {} {}, {} {}", column, order, id_column, order)
+    }
+
+    /// Rows to fetch: one extra shows whether another page follows.
+    pub fn fetch_limit(&self) -> i64 {
+        self.limit + 1
+    }
+
+    /// Builds the response from rows fetched with [`Self::condition`],
+    /// [`Self::order_by`] and [`Self::fetch_limit`]. `key_of` returns the sort
+    /// key and tie-breaker of a row.
+    pub fn paginate<T>(
+        &self,
+        mut rows: Vec<T>,
+        signer: &CursorSigner,
+        key_of: impl Fn(&T) -> (String, String),
+    ) -> PaginatedResponse<T> {
+        let has_extra = rows.len() > self.limit as usize;
+        rows.truncate(self.limit as usize);
+
+        let direction = self.direction();
+        if direction == PageDirection::Prev {
+            rows.reverse();
+        }
+
+        // Paging forward from a cursor means rows exist before this page, and
+        // paging backward means the cursor row itself follows it.
+        let (more_after, more_before) = match direction {
+            PageDirection::Next => (has_extra, self.position.is_some()),
+            PageDirection::Prev => (true, has_extra),
+        };
+
+        let cursor_at = |row: Option<&T>, direction: PageDirection| {
+            row.map(|row| {
+                let (key, id) = key_of(row);
+                signer.sign(&CursorPosition {
+                    version: CURSOR_VERSION,
+                    resource: self.resource.to_string(),
+                    sort: self.sort.clone(),
+                    order: self.order,
+                    key,
+                    id,
+                    direction,
+                })
+            })
+        };
+
+        let next_cursor = more_after
+            .then(|| cursor_at(rows.last(), PageDirection::Next))
+            .flatten();
+        let prev_cursor = more_before
+            .then(|| cursor_at(rows.first(), PageDirection::Prev))
+            .flatten();
+
+        PaginatedResponse {
+            has_more: next_cursor.is_some(),
+            data: rows,
+            next_cursor,
+            prev_cursor,
+        }
+    }
+}
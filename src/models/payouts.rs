@@ -0,0 +1,1949 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chacha20poly1305::{
+    aead::{Aead, KeyInit, Payload},
+    ChaCha20Poly1305, Nonce,
+};
+use chrono::{DateTime, Duration, Utc};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    config::Config,
+    db::PgPool,
+    models::{
+        finance::{generate_transaction_id, Currency, TransactionStatus, TransactionType},
+        fraud::FraudScreener,
+        lifecycle::{AccountLifecycleService, AccountStatus},
+        posting::{PostingError, TransactionService},
+        requests::CreateTransactionRequest,
+    },
+    observability::metrics::record_transaction_created,
+};
+
+const MAX_LABEL_LENGTH: usize = 100;
+const MAX_HOLDER_NAME_LENGTH: usize = 70;
+const MAX_DESCRIPTION_LENGTH: usize = 140;
+const MAX_REASON_LENGTH: usize = 500;
+
+const DEFAULT_LIST_LIMIT: i64 = 100;
+const MAX_LIST_LIMIT: i64 = 500;
+
+/// Largest single entry a NACHA entry detail record can carry.
+const MAX_NACHA_ENTRY_CENTS: i64 = 9_999_999_999;
+
+/// Prefix of encrypted bank details, bumped if the scheme changes.
+const CIPHER_VERSION: u8 = 1;
+const NONCE_LENGTH: usize = 12;
+
+#[derive(Debug, Clone, Default)]
+pub struct Originator {
+    /// Company name printed on NACHA files and as the SEPA debtor.
+    pub name: String,
+    /// NACHA company identification, usually `1` followed by the EIN.
+    pub company_id: String,
+    /// Routing number of the bank the NACHA file is sent to.
+    pub routing_number: String,
+    /// IBAN debited for SEPA credit transfers.
+    pub iban: String,
+    pub bic: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PayoutSettings {
+    /// Directory settlement files are written to.
+    pub file_dir: PathBuf,
+    /// Most payouts put into one batch.
+    pub batch_max_items: i64,
+    pub originator: Originator,
+}
+
+impl PayoutSettings {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            file_dir: PathBuf::from(&config.payout_file_dir),
+            batch_max_items: config.payout_batch_max_items,
+            originator: Originator {
+                name: config.payout_originator_name.clone(),
+                company_id: config.payout_originator_id.clone(),
+                routing_number: config.payout_originator_routing.clone(),
+                iban: config.payout_originator_iban.clone(),
+                bic: config.payout_originator_bic.clone(),
+            },
+        }
+    }
+}
+
+impl Default for PayoutSettings {
+    fn default() -> Self {
+        Self {
+            file_dir: PathBuf::from("./payout_files"),
+            batch_max_items: 1000,
+            originator: Originator::default(),
+        }
+    }
+}
+
+/// Encrypts bank details at rest with ChaCha20-Poly1305. The destination ID
+/// is bound in as associated data, so ciphertext cannot be moved between
+/// destinations.
+#[derive(Clone)]
+pub struct PayoutCipher {
+    cipher: ChaCha20Poly1305,
+}
+
+impl PayoutCipher {
+    pub fn new(key: &[u8]) -> Result<Self, String> {
+        let cipher = ChaCha20Poly1305::new_from_slice(key)
+            .map_err(|_| "Payout encryption key must be 32 bytes".to_string())?;
+        Ok(Self { cipher })
+    }
+
+    /// Cipher for a base64 encoded 32 byte key, as `PAYOUT_ENCRYPTION_KEY` holds.
+    pub fn from_base64(key: &str) -> Result<Self, String> {
+        let key = STANDARD
+            .decode(key.trim())
+            .map_err(|_| "Payout encryption key is not valid base64".to_string())?;
+        Self::new(&key)
+    }
+
+    pub fn encrypt(&self, details: &BankDetails, destination_id: &str) -> Result<Vec<u8>, PayoutError> {
+        let plaintext = serde_json::to_vec(details)
+            .map_err(|e| PayoutError::Crypto(format!("Failed to encode bank details: {}", e)))?;
+        let nonce: [u8; NONCE_LENGTH] = rand::rng().random();
+
+        let ciphertext = self
+            .cipher
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &plaintext,
+                    aad: destination_id.as_bytes(),
+                },
+            )
+            .map_err(|_| PayoutError::Crypto("Failed to encrypt bank details".to_string()))?;
+
+        let mut sealed = Vec::with_capacity(1 + NONCE_LENGTH + ciphertext.len());
+        sealed.push(CIPHER_VERSION);
+        sealed.extend_from_slice(&nonce);
+        sealed.extend_from_slice(&ciphertext);
+        Ok(sealed)
+    }
+
+    pub fn decrypt(&self, sealed: &[u8], destination_id: &str) -> Result<BankDetails, PayoutError> {
+        let undecryptable =
+            || PayoutError::Crypto(format!("Bank details of destination '{}' cannot be decrypted", destination_id));
+
+        if sealed.len() <= 1 + NONCE_LENGTH || sealed[0] != CIPHER_VERSION {
+            return Err(undecryptable());
+        }
+        let (nonce, ciphertext) = sealed[1..].split_at(NONCE_LENGTH);
+
+        let plaintext = self
+            .cipher
+            .decrypt(
+                Nonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: destination_id.as_bytes(),
+                },
+            )
+            .map_err(|_| undecryptable())?;
+
+        serde_json::from_slice(&plaintext).map_err(|_| undecryptable())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AchAccountType {
+    Checking,
+    Savings,
+}
+
+impl AchAccountType {
+    /// NACHA transaction code for a credit to this kind of account.
+    pub fn credit_code(&self) -> &'static str {
+        match self {
+            AchAccountType::Checking => "22",
+            AchAccountType::Savings => "32",
+        }
+    }
+}
+
+/// Where a payout is sent. Only ever stored encrypted.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BankDetails {
+    /// US bank account paid by ACH credit, in USD.
+    UsAch {
+        #[schema(example = "011000015")]
+        routing_number: String,
+        #[schema(example = "123456789")]
+        account_number: String,
+        account_type: AchAccountType,
+        #[schema(example = "Jane Doe")]
+        account_holder_name: String,
+    },
+    /// Account paid by SEPA credit transfer, in EUR.
+    Sepa {
+        #[schema(example = "DE89370400440532013000")]
+        iban: String,
+        #[serde(default)]
+        #[schema(example = "COBADEFFXXX")]
+        bic: Option<String>,
+        #[schema(example = "Jane Doe")]
+        account_holder_name: String,
+    },
+}
+
+impl std::fmt::Debug for BankDetails {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Keeps account numbers out of logs.
+        write!(f, "BankDetails({}, ****{})", self.kind(), self.last4())
+    }
+}
+
+impl BankDetails {
+    pub fn kind(&self) -> DestinationKind {
+        match self {
+            BankDetails::UsAch { .. } => DestinationKind::UsAch,
+            BankDetails::Sepa { .. } => DestinationKind::Sepa,
+        }
+    }
+
+    pub fn account_holder_name(&self) -> &str {
+        match self {
+            BankDetails::UsAch { account_holder_name, .. } | BankDetails::Sepa { account_holder_name, .. } => {
+                account_holder_name
+            }
+        }
+    }
+
+    /// Last four characters of the account number or IBAN.
+    pub fn last4(&self) -> String {
+        let account = match self {
+            BankDetails::UsAch { account_number, .. } => account_number,
+            BankDetails::Sepa { iban, .. } => iban,
+        };
+        let chars: Vec<char> = account.chars().collect();
+        chars[chars.len().saturating_sub(4)..].iter().collect()
+    }
+
+    /// Checks the details and returns them normalized: whitespace removed
+    /// from numbers, IBAN and BIC upper-cased.
+    pub fn normalized(&self) -> Result<BankDetails, String> {
+        let holder = self.account_holder_name().trim();
+        if holder.is_empty() {
+            return Err("account_holder_name cannot be empty".to_string());
+        }
+        if holder.chars().count() > MAX_HOLDER_NAME_LENGTH {
+            return Err(format!(
+                "account_holder_name must not exceed {} characters",
+                MAX_HOLDER_NAME_LENGTH
+            ));
+        }
+
+        match self {
+            BankDetails::UsAch {
+                routing_number,
+                account_number,
+                account_type,
+                ..
+            } => {
+                let routing_number = compact(routing_number);
+                validate_routing_number(&routing_number)?;
+
+                let account_number = compact(account_number);
+                if account_number.is_empty()
+                    || account_number.len() > 17
+                    || !account_number.chars().all(|c| c.is_ascii_digit())
+                {
+                    return Err("account_number must be 1 to 17 digits".to_string());
+                }
+
+                Ok(BankDetails::UsAch {
+                    routing_number,
+                    account_number,
+                    account_type: *account_type,
+                    account_holder_name: holder.to_string(),
+                })
+            }
+            BankDetails::Sepa { iban, bic, .. } => {
+                let iban = compact(iban).to_ascii_uppercase();
+                validate_iban(&iban)?;
+
+                let bic = bic
+                    .as_deref()
+                    .map(|bic| compact(bic).to_ascii_uppercase())
+                    .filter(|bic| !bic.is_empty());
+                if let Some(bic) = &bic {
+                    validate_bic(bic)?;
+                }
+
+                Ok(BankDetails::Sepa {
+                    iban,
+                    bic,
+                    account_holder_name: holder.to_string(),
+                })
+            }
+        }
+    }
+}
+
+fn compact(value: &str) -> String {
+    value.chars().filter(|c| !c.is_whitespace()).collect()
+}
+
+/// Checks a nine digit ABA routing number against its check digit.
+pub fn validate_routing_number(routing_number: &str) -> Result<(), String> {
+    let digits: Vec<u32> = routing_number.chars().filter_map(|c| c.to_digit(10)).collect();
+    if digits.len() != 9 || routing_number.len() != 9 {
+        return Err("routing_number must be 9 digits".to_string());
+    }
+
+    let checksum: u32 = digits
+        .iter()
+        .zip([3, 7, 1].iter().cycle())
+        .map(|(digit, weight)| digit * weight)
+        .sum();
+    if !checksum.is_multiple_of(10) {
+        return Err("routing_number has an invalid check digit".to_string());
+    }
+
+    Ok(())
+}
+
+/// Checks an IBAN's shape and its ISO 7064 mod 97 check digits.
+pub fn validate_iban(iban: &str) -> Result<(), String> {
+    if !(15..=34).contains(&iban.len()) || !iban.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err("iban must be 15 to 34 letters and digits".to_string());
+    }
+    let (country, rest) = iban.split_at(2);
+    if !country.chars().all(|c| c.is_ascii_uppercase()) || !rest[..2].chars().all(|c| c.is_ascii_digit()) {
+        return Err("iban must start with a country code and two check digits".to_string());
+    }
+
+    let remainder = iban[4..]
+        .chars()
+        .chain(iban[..4].chars())
+        .fold(0u32, |acc, c| {
+            let value = c.to_digit(36).unwrap_or(0);
+            if value < 10 {
+                (acc * 10 + value) % 97
+            } else {
+                (acc * 100 + value) % 97
+            }
+        });
+    if remainder != 1 {
+        return Err("iban has invalid check digits".to_string());
+    }
+
+    Ok(())
+}
+
+/// Checks a BIC: bank, country and location code with an optional branch.
+pub fn validate_bic(bic: &str) -> Result<(), String> {
+    let valid = (bic.len() == 8 || bic.len() == 11)
+        && bic[..6].chars().all(|c| c.is_ascii_uppercase())
+        && bic[6..].chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit());
+    if !valid {
+        return Err("bic must be 8 or 11 characters".to_string());
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DestinationKind {
+    UsAch,
+    Sepa,
+}
+
+impl std::fmt::Display for DestinationKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DestinationKind::UsAch => write!(f, "us_ach"),
+            DestinationKind::Sepa => write!(f, "sepa"),
+        }
+    }
+}
+
+impl std::str::FromStr for DestinationKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "us_ach" => Ok(DestinationKind::UsAch),
+            "sepa" => Ok(DestinationKind::Sepa),
+            _ => Err(format!("Invalid destination kind: {}", s)),
+        }
+    }
+}
+
+impl DestinationKind {
+    /// Currency payouts to this kind of destination are made in.
+    pub fn currency(&self) -> Currency {
+        self.file_format().currency()
+    }
+
+    pub fn file_format(&self) -> PayoutFileFormat {
+        match self {
+            DestinationKind::UsAch => PayoutFileFormat::Nacha,
+            DestinationKind::Sepa => PayoutFileFormat::SepaPain001,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PayoutFileFormat {
+    /// NACHA ACH file of PPD credits, for USD payouts.
+    Nacha,
+    /// ISO 20022 pain.001.001.03 credit transfer initiation, for EUR payouts.
+    SepaPain001,
+}
+
+impl std::fmt::Display for PayoutFileFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PayoutFileFormat::Nacha => write!(f, "nacha"),
+            PayoutFileFormat::SepaPain001 => write!(f, "sepa_pain001"),
+        }
+    }
+}
+
+impl std::str::FromStr for PayoutFileFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "nacha" => Ok(PayoutFileFormat::Nacha),
+            "sepa_pain001" => Ok(PayoutFileFormat::SepaPain001),
+            _ => Err(format!("Invalid payout file format: {}", s)),
+        }
+    }
+}
+
+impl PayoutFileFormat {
+    pub fn currency(&self) -> Currency {
+        match self {
+            PayoutFileFormat::Nacha => Currency::USD,
+            PayoutFileFormat::SepaPain001 => Currency::EUR,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            PayoutFileFormat::Nacha => "ach",
+            PayoutFileFormat::SepaPain001 => "xml",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            PayoutFileFormat::Nacha => "text/plain",
+            PayoutFileFormat::SepaPain001 => "application/xml",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DestinationStatus {
+    Active,
+    /// Removed by the account holder; no new payouts can be made to it.
+    Disabled,
+}
+
+impl std::fmt::Display for DestinationStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DestinationStatus::Active => write!(f, "active"),
+            DestinationStatus::Disabled => write!(f, "disabled"),
+        }
+    }
+}
+
+impl std::str::FromStr for DestinationStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "active" => Ok(DestinationStatus::Active),
+            "disabled" => Ok(DestinationStatus::Disabled),
+            _ => Err(format!("Invalid destination status: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreatePayoutDestinationRequest {
+    #[serde(default)]
+    #[schema(example = "Operating account")]
+    pub label: Option<String>,
+
+    pub details: BankDetails,
+}
+
+impl CreatePayoutDestinationRequest {
+    /// Validates the request, returning the normalized bank details.
+    pub fn validate(&self) -> Result<BankDetails, String> {
+        if let Some(label) = &self.label {
+            if label.chars().count() > MAX_LABEL_LENGTH {
+                return Err(format!("Label must not exceed {} characters", MAX_LABEL_LENGTH));
+            }
+        }
+
+        self.details.normalized()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PayoutDestinationResponse {
+    pub destination_id: String,
+    pub account_id: String,
+    pub kind: DestinationKind,
+    /// Currency payouts to the destination are made in
+    pub currency: Currency,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Last four characters of the account number or IBAN
+    #[schema(example = "3000")]
+    pub account_last4: String,
+    pub status: DestinationStatus,
+    #[schema(value_type = String, format = DateTime)]
+    pub created_at: DateTime<Utc>,
+    #[schema(value_type = String, format = DateTime)]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreatePayoutRequest {
+    #[schema(example = "user_123")]
+    pub account_id: String,
+
+    #[schema(example = "pod_1705320000_a1b2c3d4")]
+    pub destination_id: String,
+
+    /// Amount to pay out, in the destination's currency
+    #[schema(example = 250.0)]
+    pub amount: f64,
+
+    #[serde(default)]
+    #[schema(example = "Weekly settlement")]
+    pub description: Option<String>,
+
+    #[serde(default)]
+    pub metadata: Option<serde_json::Value>,
+}
+
+impl CreatePayoutRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.destination_id.trim().is_empty() {
+            return Err("destination_id cannot be empty".to_string());
+        }
+
+        if let Some(description) = &self.description {
+            if description.chars().count() > MAX_DESCRIPTION_LENGTH {
+                return Err(format!(
+                    "Description must not exceed {} characters",
+                    MAX_DESCRIPTION_LENGTH
+                ));
+            }
+        }
+
+        if self.metadata.as_ref().is_some_and(|metadata| !metadata.is_object()) {
+            return Err("Payout metadata must be a JSON object".to_string());
+        }
+
+        // Amount and account checks are the ledger entry's.
+        self.transaction(Currency::USD, "").validate()
+    }
+
+    /// The `payout` transaction debiting the account, tagged with the payout
+    /// and destination in its metadata. Like other outflows it is stored
+    /// negative.
+    pub fn transaction(&self, currency: Currency, payout_id: &str) -> CreateTransactionRequest {
+        let mut metadata = match &self.metadata {
+            Some(serde_json::Value::Object(map)) => map.clone(),
+            _ => serde_json::Map::new(),
+        };
+        metadata.insert("payout_id".to_string(), json!(payout_id));
+        metadata.insert("destination_id".to_string(), json!(self.destination_id));
+
+        CreateTransactionRequest {
+            account_id: self.account_id.clone(),
+            amount: self.amount,
+            currency,
+            transaction_type: TransactionType::Payout,
+            description: Some(
+                self.description
+                    .clone()
+                    .unwrap_or_else(|| format!("Payout {}", payout_id)),
+            ),
+            metadata: Some(serde_json::Value::Object(metadata)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PayoutStatus {
+    /// Debited and waiting for the next batch.
+    Pending,
+    /// In a settlement file that has not been sent yet.
+    Batched,
+    /// Submitted to the bank.
+    Sent,
+    /// Confirmed paid by the bank.
+    Settled,
+    /// Bounced by the receiving bank; the amount was credited back.
+    Returned,
+    /// The debit was declined by fraud review; nothing is paid out.
+    Failed,
+}
+
+impl std::fmt::Display for PayoutStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PayoutStatus::Pending => write!(f, "pending"),
+            PayoutStatus::Batched => write!(f, "batched"),
+            PayoutStatus::Sent => write!(f, "sent"),
+            PayoutStatus::Settled => write!(f, "settled"),
+            PayoutStatus::Returned => write!(f, "returned"),
+            PayoutStatus::Failed => write!(f, "failed"),
+        }
+    }
+}
+
+impl std::str::FromStr for PayoutStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "pending" => Ok(PayoutStatus::Pending),
+            "batched" => Ok(PayoutStatus::Batched),
+            "sent" => Ok(PayoutStatus::Sent),
+            "settled" => Ok(PayoutStatus::Settled),
+            "returned" => Ok(PayoutStatus::Returned),
+            "failed" => Ok(PayoutStatus::Failed),
+            _ => Err(format!("Invalid payout status: {}", s)),
+        }
+    }
+}
+
+impl PayoutStatus {
+    /// Whether the bank has the payout, so it can come back as a return.
+    pub fn is_returnable(&self) -> bool {
+        matches!(self, PayoutStatus::Sent | PayoutStatus::Settled)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PayoutResponse {
+    pub payout_id: String,
+    pub account_id: String,
+    pub destination_id: String,
+    pub amount: f64,
+    pub currency: Currency,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub status: PayoutStatus,
+    /// The `payout` transaction that debited the account
+    pub transaction_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub batch_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub return_reason: Option<String>,
+    /// Adjustment that credited the amount back when the payout was returned
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub return_transaction_id: Option<String>,
+    #[schema(value_type = String, format = DateTime)]
+    pub created_at: DateTime<Utc>,
+    #[schema(value_type = String, format = DateTime)]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl PayoutResponse {
+    /// Amount of the adjustment booked when the payout is returned, undoing
+    /// the debit.
+    pub fn return_amount(&self) -> f64 {
+        -TransactionType::Payout.ledger_amount(self.amount)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PayoutBatchStatus {
+    /// The settlement file was written and waits to be submitted.
+    Created,
+    Sent,
+    Settled,
+    /// The bank returned the whole batch.
+    Returned,
+}
+
+impl std::fmt::Display for PayoutBatchStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PayoutBatchStatus::Created => write!(f, "created"),
+            PayoutBatchStatus::Sent => write!(f, "sent"),
+            PayoutBatchStatus::Settled => write!(f, "settled"),
+            PayoutBatchStatus::Returned => write!(f, "returned"),
+        }
+    }
+}
+
+impl std::str::FromStr for PayoutBatchStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "created" => Ok(PayoutBatchStatus::Created),
+            "sent" => Ok(PayoutBatchStatus::Sent),
+            "settled" => Ok(PayoutBatchStatus::Settled),
+            "returned" => Ok(PayoutBatchStatus::Returned),
+            _ => Err(format!("Invalid payout batch status: {}", s)),
+        }
+    }
+}
+
+/// Something the bank reports about a batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatchAction {
+    Send,
+    Settle,
+    Return,
+}
+
+impl std::fmt::Display for BatchAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BatchAction::Send => write!(f, "mark as sent"),
+            BatchAction::Settle => write!(f, "mark as settled"),
+            BatchAction::Return => write!(f, "mark as returned"),
+        }
+    }
+}
+
+/// Status a batch in `from` moves to on `action`. Batches are sent once,
+/// settle after being sent, and can be returned once the bank has them.
+pub fn batch_transition(from: PayoutBatchStatus, action: BatchAction) -> Result<PayoutBatchStatus, PayoutError> {
+    match (from, action) {
+        (PayoutBatchStatus::Created, BatchAction::Send) => Ok(PayoutBatchStatus::Sent),
+        (PayoutBatchStatus::Sent, BatchAction::Settle) => Ok(PayoutBatchStatus::Settled),
+        (PayoutBatchStatus::Sent | PayoutBatchStatus::Settled, BatchAction::Return) => {
+            Ok(PayoutBatchStatus::Returned)
+        }
+        (from, action) => Err(PayoutError::InvalidBatchTransition { from, action }),
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreatePayoutBatchRequest {
+    /// File format; picks the currency, USD for `nacha` and EUR for `sepa_pain001`
+    pub format: PayoutFileFormat,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReturnPayoutRequest {
+    #[schema(example = "R03 No account/unable to locate account")]
+    pub reason: String,
+}
+
+impl ReturnPayoutRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.reason.trim().is_empty() {
+            return Err("Reason cannot be empty".to_string());
+        }
+
+        if self.reason.chars().count() > MAX_REASON_LENGTH {
+            return Err(format!("Reason must not exceed {} characters", MAX_REASON_LENGTH));
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PayoutBatchResponse {
+    pub batch_id: String,
+    pub format: PayoutFileFormat,
+    pub currency: Currency,
+    pub status: PayoutBatchStatus,
+    pub payout_count: i32,
+    pub total_amount: f64,
+    /// Settlement file, downloadable from `/api/admin/payouts/batches/{batch_id}/file`
+    pub file_name: String,
+    /// Hex encoded SHA-256 of the settlement file
+    pub file_sha256: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub return_reason: Option<String>,
+    #[schema(value_type = String, format = DateTime)]
+    pub created_at: DateTime<Utc>,
+    #[schema(value_type = String, format = DateTime)]
+    pub updated_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub sent_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub settled_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub returned_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PayoutBatchDetailResponse {
+    pub batch: PayoutBatchResponse,
+    pub payouts: Vec<PayoutResponse>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams, ToSchema)]
+#[into_params(parameter_in = Query)]
+pub struct PayoutListParams {
+    #[serde(default)]
+    pub status: Option<PayoutStatus>,
+
+    /// Maximum number of items to return (default 100, at most 500)
+    #[serde(default)]
+    pub limit: Option<i64>,
+}
+
+impl PayoutListParams {
+    pub fn limit(&self) -> Result<i64, String> {
+        list_limit(self.limit)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams, ToSchema)]
+#[into_params(parameter_in = Query)]
+pub struct PayoutBatchListParams {
+    #[serde(default)]
+    pub status: Option<PayoutBatchStatus>,
+
+    /// Maximum number of items to return (default 100, at most 500)
+    #[serde(default)]
+    pub limit: Option<i64>,
+}
+
+impl PayoutBatchListParams {
+    pub fn limit(&self) -> Result<i64, String> {
+        list_limit(self.limit)
+    }
+}
+
+fn list_limit(limit: Option<i64>) -> Result<i64, String> {
+    match limit {
+        None => Ok(DEFAULT_LIST_LIMIT),
+        Some(limit) if (1..=MAX_LIST_LIMIT).contains(&limit) => Ok(limit),
+        Some(_) => Err(format!("limit must be between 1 and {}", MAX_LIST_LIMIT)),
+    }
+}
+
+#[derive(Debug)]
+pub enum PayoutError {
+    DestinationNotFound(String),
+    DestinationDisabled(String),
+    NotFound(String),
+    BatchNotFound(String),
+    NoPendingPayouts(PayoutFileFormat),
+    InvalidBatchTransition {
+        from: PayoutBatchStatus,
+        action: BatchAction,
+    },
+    NotReturnable {
+        payout_id: String,
+        status: PayoutStatus,
+    },
+    AccountUnavailable {
+        account_id: String,
+        status: AccountStatus,
+    },
+    /// The ledger debit was refused or recorded as failed.
+    Posting(PostingError),
+    /// Something needed to write settlement files is not configured.
+    NotConfigured(String),
+    /// The settlement file cannot represent the batch.
+    InvalidFile(String),
+    Crypto(String),
+    Storage(std::io::Error),
+    Database(sqlx::Error),
+}
+
+impl From<sqlx::Error> for PayoutError {
+    fn from(error: sqlx::Error) -> Self {
+        PayoutError::Database(error)
+    }
+}
+
+impl From<std::io::Error> for PayoutError {
+    fn from(error: std::io::Error) -> Self {
+        PayoutError::Storage(error)
+    }
+}
+
+impl From<PostingError> for PayoutError {
+    fn from(error: PostingError) -> Self {
+        PayoutError::Posting(error)
+    }
+}
+
+impl std::fmt::Display for PayoutError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PayoutError::DestinationNotFound(destination_id) => {
+                write!(f, "Payout destination '{}' not found", destination_id)
+            }
+            PayoutError::DestinationDisabled(destination_id) => {
+                write!(f, "Payout destination '{}' is disabled", destination_id)
+            }
+            PayoutError::NotFound(payout_id) => write!(f, "Payout '{}' not found", payout_id),
+            PayoutError::BatchNotFound(batch_id) => write!(f, "Payout batch '{}' not found", batch_id),
+            PayoutError::NoPendingPayouts(format) => {
+                write!(f, "No pending {} payouts to batch", format.currency())
+            }
+            PayoutError::InvalidBatchTransition { from, action } => {
+                write!(f, "Cannot {} a payout batch that is {}", action, from)
+            }
+            PayoutError::NotReturnable { payout_id, status } => write!(
+                f,
+                "Payout '{}' is {}; only sent or settled payouts can be returned",
+                payout_id, status
+            ),
+            PayoutError::AccountUnavailable { account_id, status } => write!(
+                f,
+                "Account '{}' is {}; the return cannot be booked against it",
+                account_id, status
+            ),
+            PayoutError::Posting(e) => write!(f, "{}", e),
+            PayoutError::NotConfigured(msg) => write!(f, "Payouts are not configured: {}", msg),
+            PayoutError::InvalidFile(msg) => write!(f, "{}", msg),
+            PayoutError::Crypto(msg) => write!(f, "{}", msg),
+            PayoutError::Storage(e) => write!(f, "Payout file storage error: {}", e),
+            PayoutError::Database(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+/// One credit in a settlement file.
+#[derive(Debug, Clone)]
+pub struct SettlementEntry {
+    pub payout_id: String,
+    pub amount: f64,
+    pub description: Option<String>,
+    pub details: BankDetails,
+}
+
+fn to_cents(amount: f64) -> i64 {
+    (amount * 100.0).round() as i64
+}
+
+/// Left-justified, space padded alphanumeric NACHA field.
+fn alpha(value: &str, width: usize) -> String {
+    let cleaned: String = value
+        .chars()
+        .map(|c| if c.is_ascii_graphic() || c == ' ' { c.to_ascii_uppercase() } else { ' ' })
+        .take(width)
+        .collect();
+    format!("{:<width$}", cleaned, width = width)
+}
+
+/// Right-justified, zero padded numeric NACHA field.
+fn numeric(value: i64, width: usize) -> String {
+    let value = format!("{:0width$}", value, width = width);
+    value[value.len() - width..].to_string()
+}
+
+/// The last 15 letters and digits of a payout ID, which fit the NACHA
+/// individual identification field while keeping its random suffix.
+fn nacha_reference(payout_id: &str) -> String {
+    let compacted: Vec<char> = payout_id.chars().filter(|c| c.is_ascii_alphanumeric()).collect();
+    compacted[compacted.len().saturating_sub(15)..].iter().collect()
+}
+
+/// Writes a NACHA file with one PPD batch crediting every entry. Records are
+/// 94 characters and the file is padded to a multiple of ten records.
+pub fn nacha_file(
+    originator: &Originator,
+    batch_id: &str,
+    entries: &[SettlementEntry],
+    now: DateTime<Utc>,
+) -> Result<String, PayoutError> {
+    if validate_routing_number(&originator.routing_number).is_err() {
+        return Err(PayoutError::NotConfigured(
+            "PAYOUT_ORIGINATOR_ROUTING must be a valid routing number".to_string(),
+        ));
+    }
+    if originator.company_id.trim().is_empty() || originator.name.trim().is_empty() {
+        return Err(PayoutError::NotConfigured(
+            "PAYOUT_ORIGINATOR_ID and PAYOUT_ORIGINATOR_NAME are required for NACHA files".to_string(),
+        ));
+    }
+
+    let odfi = &originator.routing_number[..8];
+    let effective_date = (now + Duration::days(1)).format("%y%m%d").to_string();
+    let mut records = Vec::with_capacity(entries.len() + 4);
+
+    records.push(format!(
+        "101 {}{:>10}{}{}A094101{}{}{}",
+        originator.routing_number,
+        alpha(&originator.company_id, 10).trim_end(),
+        now.format("%y%m%d"),
+        now.format("%H%M"),
+        alpha("", 23),
+        alpha(&originator.name, 23),
+        alpha(&batch_id.replace('_', ""), 8),
+    ));
+    records.push(format!(
+        "5220{}{}{}PPD{}{}{}   1{}{}",
+        alpha(&originator.name, 16),
+        alpha(batch_id, 20),
+        alpha(&originator.company_id, 10),
+        alpha("PAYOUT", 10),
+        alpha("", 6),
+        effective_date,
+        odfi,
+        numeric(1, 7),
+    ));
+
+    let mut entry_hash = 0i64;
+    let mut total_cents = 0i64;
+    for (index, entry) in entries.iter().enumerate() {
+        let BankDetails::UsAch {
+            routing_number,
+            account_number,
+            account_type,
+            account_holder_name,
+        } = &entry.details
+        else {
+            return Err(PayoutError::InvalidFile(format!(
+                "Payout '{}' is not to a US bank account",
+                entry.payout_id
+            )));
+        };
+
+        let cents = to_cents(entry.amount);
+        if cents > MAX_NACHA_ENTRY_CENTS {
+            return Err(PayoutError::InvalidFile(format!(
+                "Payout '{}' is too large for a NACHA entry",
+                entry.payout_id
+            )));
+        }
+        entry_hash += routing_number[..8].parse::<i64>().unwrap_or(0);
+        total_cents += cents;
+
+        records.push(format!(
+            "6{}{}{}{}{}{}  0{}{}",
+            account_type.credit_code(),
+            routing_number,
+            alpha(account_number, 17),
+            numeric(cents, 10),
+            alpha(&nacha_reference(&entry.payout_id), 15),
+            alpha(account_holder_name, 22),
+            odfi,
+            numeric(index as i64 + 1, 7),
+        ));
+    }
+
+    let entry_hash = numeric(entry_hash % 10_000_000_000, 10);
+    records.push(format!(
+        "8220{}{}{}{}{}{}{}{}{}",
+        numeric(entries.len() as i64, 6),
+        entry_hash,
+        numeric(0, 12),
+        numeric(total_cents, 12),
+        alpha(&originator.company_id, 10),
+        alpha("", 19),
+        alpha("", 6),
+        odfi,
+        numeric(1, 7),
+    ));
+
+    let record_count = records.len() + 1;
+    let block_count = record_count.div_ceil(10);
+    records.push(format!(
+        "9{}{}{}{}{}{}{}",
+        numeric(1, 6),
+        numeric(block_count as i64, 6),
+        numeric(entries.len() as i64, 8),
+        entry_hash,
+        numeric(0, 12),
+        numeric(total_cents, 12),
+        alpha("", 39),
+    ));
+    while records.len() % 10 != 0 {
+        records.push("9".repeat(94));
+    }
+
+    let mut file = records.join("\n");
+    file.push('\n');
+    Ok(file)
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn xml_text(value: &str, max_length: usize) -> String {
+    xml_escape(&value.chars().take(max_length).collect::<String>())
+}
+
+fn sepa_amount(cents: i64) -> String {
+    format!("{}.{:02}", cents / 100, cents % 100)
+}
+
+/// Writes a pain.001.001.03 credit transfer initiation with one payment
+/// information block paying every entry.
+pub fn sepa_pain001_file(
+    originator: &Originator,
+    batch_id: &str,
+    entries: &[SettlementEntry],
+    now: DateTime<Utc>,
+) -> Result<String, PayoutError> {
+    if validate_iban(&originator.iban).is_err() || originator.name.trim().is_empty() {
+        return Err(PayoutError::NotConfigured(
+            "PAYOUT_ORIGINATOR_NAME and a valid PAYOUT_ORIGINATOR_IBAN are required for SEPA files".to_string(),
+        ));
+    }
+
+    let mut transfers = String::new();
+    let mut total_cents = 0i64;
+    for entry in entries {
+        let BankDetails::Sepa {
+            iban,
+            bic,
+            account_holder_name,
+        } = &entry.details
+        else {
+            return Err(PayoutError::InvalidFile(format!(
+                "Payout '{}' is not to a SEPA account",
+                entry.payout_id
+            )));
+        };
+
+        let cents = to_cents(entry.amount);
+        total_cents += cents;
+
+        let agent = bic
+            .as_ref()
+            .map(|bic| format!("        <CdtrAgt><FinInstnId><BIC>{}</BIC></FinInstnId></CdtrAgt>\n", bic))
+            .unwrap_or_default();
+        let remittance = entry
+            .description
+            .as_deref()
+            .map(|description| {
+                format!("        <RmtInf><Ustrd>{}</Ustrd></RmtInf>\n", xml_text(description, 140))
+            })
+            .unwrap_or_default();
+
+        transfers.push_str(&format!(
+            concat!(
+                "      <CdtTrfTxInf>\n",
+                "        <PmtId><EndToEndId>{}</EndToEndId></PmtId>\n",
+                "        <Amt><InstdAmt Ccy=\"EUR\">{}</InstdAmt></Amt>\n",
+                "{}",
+                "        <Cdtr><Nm>{}</Nm></Cdtr>\n",
+                "        <CdtrAcct><Id><IBAN>{}</IBAN></Id></CdtrAcct>\n",
+                "{}",
+                "      </CdtTrfTxInf>\n",
+            ),
+            xml_text(&entry.payout_id, 35),
+            sepa_amount(cents),
+            agent,
+            xml_text(account_holder_name, 70),
+            iban,
+            remittance,
+        ));
+    }
+
+    let debtor_agent = match &originator.bic {
+        Some(bic) => format!("<BIC>{}</BIC>", xml_escape(bic)),
+        None => "<Othr><Id>NOTPROVIDED</Id></Othr>".to_string(),
+    };
+    let count = entries.len();
+    let total = sepa_amount(total_cents);
+    let message_id = xml_text(batch_id, 35);
+    let name = xml_text(&originator.name, 70);
+
+    Ok(format!(
+        concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<Document xmlns=\"urn:iso:std:iso:20022:tech:xsd:pain.001.001.03\">\n",
+            "  <CstmrCdtTrfInitn>\n",
+            "    <GrpHdr>\n",
+            "      <MsgId>{id}</MsgId>\n",
+            "      <CreDtTm>{created}</CreDtTm>\n",
+            "      <NbOfTxs>{count}</NbOfTxs>\n",
+            "      <CtrlSum>{total}</CtrlSum>\n",
+            "      <InitgPty><Nm>{name}</Nm></InitgPty>\n",
+            "    </GrpHdr>\n",
+            "    <PmtInf>\n",
+            "      <PmtInfId>{id}</PmtInfId>\n",
+            "      <PmtMtd>TRF</PmtMtd>\n",
+            "      <NbOfTxs>{count}</NbOfTxs>\n",
+            "      <CtrlSum>{total}</CtrlSum>\n",
+            "      <PmtTpInf><SvcLvl><Cd>SEPA</Cd></SvcLvl></PmtTpInf>\n",
+            "      <ReqdExctnDt>{execution}</ReqdExctnDt>\n",
+            "      <Dbtr><Nm>{name}</Nm></Dbtr>\n",
+            "      <DbtrAcct><Id><IBAN>{iban}</IBAN></Id></DbtrAcct>\n",
+            "      <DbtrAgt><FinInstnId>{agent}</FinInstnId></DbtrAgt>\n",
+            "      <ChrgBr>SLEV</ChrgBr>\n",
+            "{transfers}",
+            "    </PmtInf>\n",
+            "  </CstmrCdtTrfInitn>\n",
+            "</Document>\n",
+        ),
+        id = message_id,
+        created = now.format("%Y-%m-%dT%H:%M:%S"),
+        count = count,
+        total = total,
+        name = name,
+        execution = (now + Duration::days(1)).format("%Y-%m-%d"),
+        iban = xml_escape(&originator.iban),
+        agent = debtor_agent,
+        transfers = transfers,
+    ))
+}
+
+/// Settlement file for `entries` in `format`.
+pub fn settlement_file(
+    format: PayoutFileFormat,
+    originator: &Originator,
+    batch_id: &str,
+    entries: &[SettlementEntry],
+    now: DateTime<Utc>,
+) -> Result<String, PayoutError> {
+    match format {
+        PayoutFileFormat::Nacha => nacha_file(originator, batch_id, entries, now),
+        PayoutFileFormat::SepaPain001 => sepa_pain001_file(originator, batch_id, entries, now),
+    }
+}
+
+pub fn generate_destination_id() -> String {
+    let timestamp = Utc::now().timestamp();
+    let random: u32 = rand::rng().random();
+    format!("pod_{}_{:08x}", timestamp, random)
+}
+
+pub fn generate_payout_id() -> String {
+    let timestamp = Utc::now().timestamp();
+    let random: u32 = rand::rng().random();
+    format!("po_{}_{:08x}", timestamp, random)
+}
+
+fn generate_batch_id() -> String {
+    let timestamp = Utc::now().timestamp();
+    let random: u32 = rand::rng().random();
+    format!("pob_{}_{:08x}", timestamp, random)
+}
+
+const DESTINATION_COLUMNS: &str =
+    "destination_id, account_id, kind, currency, label, account_last4, status, created_at, updated_at";
+
+type DestinationRow = (
+    String,
+    String,
+    String,
+    String,
+    Option<String>,
+    String,
+    String,
+    DateTime<Utc>,
+    DateTime<Utc>,
+);
+
+fn destination_from_row(row: DestinationRow) -> PayoutDestinationResponse {
+    PayoutDestinationResponse {
+        destination_id: row.0,
+        account_id: row.1,
+        kind: row.2.parse().unwrap_or(DestinationKind::UsAch),
+        currency: row.3.parse().unwrap_or_default(),
+        label: row.4,
+        account_last4: row.5,
+        status: row.6.parse().unwrap_or(DestinationStatus::Active),
+        created_at: row.7,
+        updated_at: row.8,
+    }
+}
+
+const PAYOUT_COLUMNS: &str = r#"
+    payout_id, account_id, destination_id, amount, currency, description, status,
+    transaction_id, batch_id, return_reason, return_transaction_id, created_at, updated_at
+"#;
+
+type PayoutRow = (
+    String,
+    String,
+    String,
+    f64,
+    String,
+    Option<String>,
+    String,
+    String,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    DateTime<Utc>,
+    DateTime<Utc>,
+);
+
+fn payout_from_row(row: PayoutRow) -> PayoutResponse {
+    PayoutResponse {
+        payout_id: row.0,
+        account_id: row.1,
+        destination_id: row.2,
+        amount: row.3,
+        currency: row.4.parse().unwrap_or_default(),
+        description: row.5,
+        status: row.6.parse().unwrap_or(PayoutStatus::Pending),
+        transaction_id: row.7,
+        batch_id: row.8,
+        return_reason: row.9,
+        return_transaction_id: row.10,
+        created_at: row.11,
+        updated_at: row.12,
+    }
+}
+
+const BATCH_COLUMNS: &str = r#"
+    batch_id, format, currency, status, payout_count, total_amount, file_name,
+    file_sha256, return_reason, created_at, updated_at, sent_at, settled_at, returned_at
+"#;
+
+type BatchRow = (
+    String,
+    String,
+    String,
+    String,
+    i32,
+    f64,
+    String,
+    String,
+    Option<String>,
+    DateTime<Utc>,
+    DateTime<Utc>,
+    Option<DateTime<Utc>>,
+    Option<DateTime<Utc>>,
+    Option<DateTime<Utc>>,
+);
+
+fn batch_from_row(row: BatchRow) -> PayoutBatchResponse {
+    PayoutBatchResponse {
+        batch_id: row.0,
+        format: row.1.parse().unwrap_or(PayoutFileFormat::Nacha),
+        currency: row.2.parse().unwrap_or_default(),
+        status: row.3.parse().unwrap_or(PayoutBatchStatus::Created),
+        payout_count: row.4,
+        total_amount: row.5,
+        file_name: row.6,
+        file_sha256: row.7,
+        return_reason: row.8,
+        created_at: row.9,
+        updated_at: row.10,
+        sent_at: row.11,
+        settled_at: row.12,
+        returned_at: row.13,
+    }
+}
+
+/// Settlement file written for a new batch.
+struct BatchFile {
+    name: String,
+    path: String,
+    sha256: String,
+}
+
+pub struct PayoutService;
+
+impl PayoutService {
+    /// Stores a destination for an account with its details encrypted.
+    pub async fn create_destination(
+        pool: &PgPool,
+        cipher: &PayoutCipher,
+        account_id: &str,
+        label: Option<&str>,
+        details: &BankDetails,
+    ) -> Result<PayoutDestinationResponse, PayoutError> {
+        let destination_id = generate_destination_id();
+        let encrypted = cipher.encrypt(details, &destination_id)?;
+        let kind = details.kind();
+
+        let row = sqlx::query_as::<_, DestinationRow>(&format!(
+            r#"
+            INSERT INTO payout_destinations (
+                destination_id, account_id, kind, currency, label, account_last4, encrypted_details
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING {}
+            "#,
+            DESTINATION_COLUMNS
+        ))
+        .bind(&destination_id)
+        .bind(account_id)
+        .bind(kind.to_string())
+        .bind(kind.currency().to_string())
+        .bind(label.map(str::trim).filter(|label| !label.is_empty()))
+        .bind(details.last4())
+        .bind(&encrypted)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(destination_from_row(row))
+    }
+
+    pub async fn list_destinations(
+        pool: &PgPool,
+        account_id: &str,
+    ) -> Result<Vec<PayoutDestinationResponse>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, DestinationRow>(&format!(
+            "SELECT {} FROM payout_destinations WHERE account_id = $1 ORDER BY created_at DESC, destination_id",
+            DESTINATION_COLUMNS
+        ))
+        .bind(account_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows.into_iter().map(destination_from_row).collect())
+    }
+
+    /// Disables a destination. Payouts already made to it are still paid.
+    pub async fn disable_destination(
+        pool: &PgPool,
+        account_id: &str,
+        destination_id: &str,
+    ) -> Result<PayoutDestinationResponse, PayoutError> {
+        let row = sqlx::query_as::<_, DestinationRow>(&format!(
+            r#"
+            UPDATE payout_destinations
+            SET status = $3, updated_at = NOW()
+            WHERE destination_id = $1 AND account_id = $2
+            RETURNING {}
+            "#,
+            DESTINATION_COLUMNS
+        ))
+        .bind(destination_id)
+        .bind(account_id)
+        .bind(DestinationStatus::Disabled.to_string())
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| PayoutError::DestinationNotFound(destination_id.to_string()))?;
+
+        Ok(destination_from_row(row))
+    }
+
+    /// Creates a payout: a `payout` transaction debits the account the way
+    /// `POST /api/transactions` would, and the payout waits as `pending` for
+    /// the next batch. Debits refused by limits or screening are kept as
+    /// failed transactions and no payout is created.
+    pub async fn create(
+        pool: &PgPool,
+        screener: &FraudScreener,
+        req: &CreatePayoutRequest,
+    ) -> Result<PayoutResponse, PayoutError> {
+        let mut tx = pool.begin().await?;
+
+        let (currency, status) = sqlx::query_as::<_, (String, String)>(
+            r#"
+            SELECT currency, status
+            FROM payout_destinations
+            WHERE destination_id = $1 AND account_id = $2
+            FOR SHARE
+            "#
+        )
+        .bind(&req.destination_id)
+        .bind(&req.account_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| PayoutError::DestinationNotFound(req.destination_id.clone()))?;
+
+        if status != DestinationStatus::Active.to_string() {
+            return Err(PayoutError::DestinationDisabled(req.destination_id.clone()));
+        }
+        let currency: Currency = currency.parse().unwrap_or_default();
+
+        let payout_id = generate_payout_id();
+        let debit_req = req.transaction(currency, &payout_id);
+        let debit = match TransactionService::post(&mut tx, screener, &debit_req).await {
+            Ok(debit) => debit,
+            Err(e) => {
+                // Transactions rejected by limits or screening are kept as failed.
+                if e.transaction_id().is_some() {
+                    tx.commit().await?;
+                }
+                return Err(e.into());
+            }
+        };
+
+        let row = sqlx::query_as::<_, PayoutRow>(&format!(
+            r#"
+            INSERT INTO payouts (
+                payout_id, account_id, destination_id, amount, currency,
+                description, status, transaction_id
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING {}
+            "#,
+            PAYOUT_COLUMNS
+        ))
+        .bind(&payout_id)
+        .bind(&req.account_id)
+        .bind(&req.destination_id)
+        .bind(req.amount)
+        .bind(currency.to_string())
+        .bind(&debit.description)
+        .bind(PayoutStatus::Pending.to_string())
+        .bind(&debit.transaction_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        record_transaction_created(&TransactionType::Payout.to_string(), &currency.to_string());
+
+        Ok(payout_from_row(row))
+    }
+
+    pub async fn get(pool: &PgPool, payout_id: &str) -> Result<Option<PayoutResponse>, sqlx::Error> {
+        let row = sqlx::query_as::<_, PayoutRow>(&format!(
+            "SELECT {} FROM payouts WHERE payout_id = $1",
+            PAYOUT_COLUMNS
+        ))
+        .bind(payout_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(row.map(payout_from_row))
+    }
+
+    /// An account's payouts, newest first.
+    pub async fn list(
+        pool: &PgPool,
+        account_id: &str,
+        status: Option<PayoutStatus>,
+        limit: i64,
+    ) -> Result<Vec<PayoutResponse>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, PayoutRow>(&format!(
+            r#"
+            SELECT {}
+            FROM payouts
+            WHERE account_id = $1 AND ($2::text IS NULL OR status = $2)
+            ORDER BY created_at DESC, payout_id DESC
+            LIMIT $3
+            "#,
+            PAYOUT_COLUMNS
+        ))
+        .bind(account_id)
+        .bind(status.map(|s| s.to_string()))
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows.into_iter().map(payout_from_row).collect())
+    }
+
+    /// Batches pending payouts in the format's currency whose debit has
+    /// completed and writes their settlement file. Payouts whose debit was
+    /// declined in fraud review are marked failed on the way.
+    pub async fn create_batch(
+        pool: &PgPool,
+        cipher: &PayoutCipher,
+        settings: &PayoutSettings,
+        format: PayoutFileFormat,
+    ) -> Result<PayoutBatchDetailResponse, PayoutError> {
+        let currency = format.currency();
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            UPDATE payouts p
+            SET status = $1, updated_at = NOW()
+            FROM transactions t
+            WHERE t.transaction_id = p.transaction_id
+              AND p.status = $2
+              AND t.status = $3
+            "#
+        )
+        .bind(PayoutStatus::Failed.to_string())
+        .bind(PayoutStatus::Pending.to_string())
+        .bind(TransactionStatus::Failed.to_string())
+        .execute(&mut *tx)
+        .await?;
+
+        // Payouts held for fraud review wait for a later batch.
+        let pending = sqlx::query_as::<_, (String, f64, Option<String>, String, Vec<u8>)>(
+            r#"
+            SELECT p.payout_id, p.amount, p.description, d.destination_id, d.encrypted_details
+            FROM payouts p
+            JOIN transactions t ON t.transaction_id = p.transaction_id
+            JOIN payout_destinations d ON d.destination_id = p.destination_id
+            WHERE p.status = $1 AND p.currency = $2 AND t.status = $3
+            ORDER BY p.created_at, p.payout_id
+            LIMIT $4
+            FOR UPDATE OF p SKIP LOCKED
+            "#
+        )
+        .bind(PayoutStatus::Pending.to_string())
+        .bind(currency.to_string())
+        .bind(TransactionStatus::Completed.to_string())
+        .bind(settings.batch_max_items)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        if pending.is_empty() {
+            tx.commit().await?;
+            return Err(PayoutError::NoPendingPayouts(format));
+        }
+
+        let entries = pending
+            .into_iter()
+            .map(|(payout_id, amount, description, destination_id, encrypted)| {
+                Ok(SettlementEntry {
+                    payout_id,
+                    amount,
+                    description,
+                    details: cipher.decrypt(&encrypted, &destination_id)?,
+                })
+            })
+            .collect::<Result<Vec<_>, PayoutError>>()?;
+
+        let batch_id = generate_batch_id();
+        let content = settlement_file(format, &settings.originator, &batch_id, &entries, Utc::now())?;
+        let file_name = format!("{}.{}", batch_id, format.extension());
+        let path = settings.file_dir.join(&file_name);
+        tokio::fs::create_dir_all(&settings.file_dir).await?;
+        tokio::fs::write(&path, &content).await?;
+
+        let total_cents: i64 = entries.iter().map(|entry| to_cents(entry.amount)).sum();
+        let payout_ids: Vec<String> = entries.into_iter().map(|entry| entry.payout_id).collect();
+
+        let file = BatchFile {
+            name: file_name,
+            path: path.to_string_lossy().to_string(),
+            sha256: format!("{:x}", Sha256::digest(content.as_bytes())),
+        };
+        let result =
+            Self::record_batch(&mut tx, &batch_id, format, &payout_ids, total_cents as f64 / 100.0, &file).await;
+
+        let detail = match result {
+            Ok(detail) => detail,
+            Err(e) => {
+                let _ = tokio::fs::remove_file(&path).await;
+                return Err(e.into());
+            }
+        };
+
+        if let Err(e) = tx.commit().await {
+            let _ = tokio::fs::remove_file(&path).await;
+            return Err(e.into());
+        }
+
+        Ok(detail)
+    }
+
+    async fn record_batch(
+        conn: &mut sqlx::PgConnection,
+        batch_id: &str,
+        format: PayoutFileFormat,
+        payout_ids: &[String],
+        total_amount: f64,
+        file: &BatchFile,
+    ) -> Result<PayoutBatchDetailResponse, sqlx::Error> {
+        let batch = sqlx::query_as::<_, BatchRow>(&format!(
+            r#"
+            INSERT INTO payout_batches (
+                batch_id, format, currency, status, payout_count, total_amount,
+                file_name, file_path, file_sha256
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING {}
+            "#,
+            BATCH_COLUMNS
+        ))
+        .bind(batch_id)
+        .bind(format.to_string())
+        .bind(format.currency().to_string())
+        .bind(PayoutBatchStatus::Created.to_string())
+        .bind(payout_ids.len() as i32)
+        .bind(total_amount)
+        .bind(&file.name)
+        .bind(&file.path)
+        .bind(&file.sha256)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        let payouts = sqlx::query_as::<_, PayoutRow>(&format!(
+            r#"
+            UPDATE payouts
+            SET status = $1, batch_id = $2, updated_at = NOW()
+            WHERE payout_id = ANY($3)
+            RETURNING {}
+            "#,
+            PAYOUT_COLUMNS
+        ))
+        .bind(PayoutStatus::Batched.to_string())
+        .bind(batch_id)
+        .bind(payout_ids)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        let mut payouts: Vec<PayoutResponse> = payouts.into_iter().map(payout_from_row).collect();
+        payouts.sort_by(|a, b| (a.created_at, &a.payout_id).cmp(&(b.created_at, &b.payout_id)));
+
+        Ok(PayoutBatchDetailResponse {
+            batch: batch_from_row(batch),
+            payouts,
+        })
+    }
+
+    /// Batches newest first.
+    pub async fn list_batches(
+        pool: &PgPool,
+        status: Option<PayoutBatchStatus>,
+        limit: i64,
+    ) -> Result<Vec<PayoutBatchResponse>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, BatchRow>(&format!(
+            r#"
+            SELECT {}
+            FROM payout_batches
+            WHERE ($1::text IS NULL OR status = $1)
+            ORDER BY created_at DESC, batch_id DESC
+            LIMIT $2
+            "#,
+            BATCH_COLUMNS
+        ))
+        .bind(status.map(|s| s.to_string()))
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows.into_iter().map(batch_from_row).collect())
+    }
+
+    pub async fn get_batch(pool: &PgPool, batch_id: &str) -> Result<PayoutBatchDetailResponse, PayoutError> {
+        let batch = sqlx::query_as::<_, BatchRow>(&format!(
+            "SELECT {} FROM payout_batches WHERE batch_id = $1",
+            BATCH_COLUMNS
+        ))
+        .bind(batch_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| PayoutError::BatchNotFound(batch_id.to_string()))?;
+
+        let payouts = sqlx::query_as::<_, PayoutRow>(&format!(
+            "SELECT {} FROM payouts WHERE batch_id = $1 ORDER BY created_at, payout_id",
+            PAYOUT_COLUMNS
+        ))
+        .bind(batch_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(PayoutBatchDetailResponse {
+            batch: batch_from_row(batch),
+            payouts: payouts.into_iter().map(payout_from_row).collect(),
+        })
+    }
+
+    /// Settlement file of a batch and where it is stored.
+    pub async fn batch_file(
+        pool: &PgPool,
+        batch_id: &str,
+    ) -> Result<(PayoutBatchResponse, PathBuf), PayoutError> {
+        let row = sqlx::query_as::<_, BatchRow>(&format!(
+            "SELECT {} FROM payout_batches WHERE batch_id = $1",
+            BATCH_COLUMNS
+        ))
+        .bind(batch_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| PayoutError::BatchNotFound(batch_id.to_string()))?;
+
+        let path = sqlx::query_scalar::<_, String>("SELECT file_path FROM payout_batches WHERE batch_id = $1")
+            .bind(batch_id)
+            .fetch_one(pool)
+            .await?;
+
+        Ok((batch_from_row(row), PathBuf::from(path)))
+    }
+
+    /// Records what the bank reported for a batch. Sending and settling move
+    /// its payouts along; returning credits every payout in it back.
+    pub async fn update_batch(
+        pool: &PgPool,
+        batch_id: &str,
+        action: BatchAction,
+        reason: Option<&str>,
+    ) -> Result<PayoutBatchDetailResponse, PayoutError> {
+        let mut tx = pool.begin().await?;
+
+        let status = sqlx::query_scalar::<_, String>(
+            "SELECT status FROM payout_batches WHERE batch_id = $1 FOR UPDATE"
+        )
+        .bind(batch_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| PayoutError::BatchNotFound(batch_id.to_string()))?;
+        let from: PayoutBatchStatus = status.parse().unwrap_or(PayoutBatchStatus::Created);
+        let to = batch_transition(from, action)?;
+
+        let mut returned = Vec::new();
+        match action {
+            BatchAction::Send | BatchAction::Settle => {
+                let (payout_from, payout_to) = match action {
+                    BatchAction::Send => (PayoutStatus::Batched, PayoutStatus::Sent),
+                    _ => (PayoutStatus::Sent, PayoutStatus::Settled),
+                };
+                sqlx::query(
+                    "UPDATE payouts SET status = $1, updated_at = NOW() WHERE batch_id = $2 AND status = $3"
+                )
+                .bind(payout_to.to_string())
+                .bind(batch_id)
+                .bind(payout_from.to_string())
+                .execute(&mut *tx)
+                .await?;
+            }
+            BatchAction::Return => {
+                let payouts = sqlx::query_as::<_, PayoutRow>(&format!(
+                    "SELECT {} FROM payouts WHERE batch_id = $1 ORDER BY created_at, payout_id FOR UPDATE",
+                    PAYOUT_COLUMNS
+                ))
+                .bind(batch_id)
+                .fetch_all(&mut *tx)
+                .await?;
+
+                for payout in payouts.into_iter().map(payout_from_row) {
+                    if payout.status.is_returnable() {
+                        Self::book_return(&mut tx, &payout, reason.unwrap_or_default()).await?;
+                        returned.push(payout.currency);
+                    }
+                }
+            }
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE payout_batches
+            SET status = $2,
+                return_reason = COALESCE($3, return_reason),
+                sent_at = CASE WHEN $2 = 'sent' THEN NOW() ELSE sent_at END,
+                settled_at = CASE WHEN $2 = 'settled' THEN NOW() ELSE settled_at END,
+                returned_at = CASE WHEN $2 = 'returned' THEN NOW() ELSE returned_at END,
+                updated_at = NOW()
+            WHERE batch_id = $1
+            "#
+        )
+        .bind(batch_id)
+        .bind(to.to_string())
+        .bind(reason)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        for currency in returned {
+            record_transaction_created(&TransactionType::Adjustment.to_string(), &currency.to_string());
+        }
+
+        Self::get_batch(pool, batch_id).await
+    }
+
+    /// Records a single payout returned by the receiving bank and credits
+    /// the amount back to the account.
+    pub async fn return_payout(
+        pool: &PgPool,
+        payout_id: &str,
+        reason: &str,
+    ) -> Result<PayoutResponse, PayoutError> {
+        let mut tx = pool.begin().await?;
+
+        let payout = sqlx::query_as::<_, PayoutRow>(&format!(
+            "SELECT {} FROM payouts WHERE payout_id = $1 FOR UPDATE",
+            PAYOUT_COLUMNS
+        ))
+        .bind(payout_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .map(payout_from_row)
+        .ok_or_else(|| PayoutError::NotFound(payout_id.to_string()))?;
+
+        if !payout.status.is_returnable() {
+            return Err(PayoutError::NotReturnable {
+                payout_id: payout_id.to_string(),
+                status: payout.status,
+            });
+        }
+
+        let returned = Self::book_return(&mut tx, &payout, reason).await?;
+        tx.commit().await?;
+
+        record_transaction_created(&TransactionType::Adjustment.to_string(), &returned.currency.to_string());
+
+        Ok(returned)
+    }
+
+    /// Books a completed adjustment crediting a returned payout back and
+    /// marks the payout returned. The original debit is left untouched.
+    async fn book_return(
+        conn: &mut sqlx::PgConnection,
+        payout: &PayoutResponse,
+        reason: &str,
+    ) -> Result<PayoutResponse, PayoutError> {
+        if let Some(AccountStatus::Closed) = AccountLifecycleService::lock_status(&mut *conn, &payout.account_id).await? {
+            return Err(PayoutError::AccountUnavailable {
+                account_id: payout.account_id.clone(),
+                status: AccountStatus::Closed,
+            });
+        }
+
+        let transaction_id = generate_transaction_id();
+        sqlx::query(
+            r#"
+            INSERT INTO transactions (
+                transaction_id, account_id, amount, currency,
+                transaction_type, status, description, metadata,
+                created_at, processed_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, NOW(), NOW())
+            "#
+        )
+        .bind(&transaction_id)
+        .bind(&payout.account_id)
+        .bind(payout.return_amount())
+        .bind(payout.currency.to_string())
+        .bind(TransactionType::Adjustment.to_string())
+        .bind(TransactionStatus::Completed.to_string())
+        .bind(format!("Payout {} returned", payout.payout_id))
+        .bind(json!({ "payout_id": payout.payout_id, "reverses": payout.transaction_id }))
+        .execute(&mut *conn)
+        .await?;
+
+        let row = sqlx::query_as::<_, PayoutRow>(&format!(
+            r#"
+            UPDATE payouts
+            SET status = $2, return_reason = $3, return_transaction_id = $4, updated_at = NOW()
+            WHERE payout_id = $1
+            RETURNING {}
+            "#,
+            PAYOUT_COLUMNS
+        ))
+        .bind(&payout.payout_id)
+        .bind(PayoutStatus::Returned.to_string())
+        .bind(reason.trim())
+        .bind(&transaction_id)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        Ok(payout_from_row(row))
+    }
+}
@@ -0,0 +1,229 @@
+use crate::{
+    db::PgPool,
+    models::{
+        common::ErrorCode,
+        fees::FeeService,
+        finance::{generate_transaction_id, FailureReason, TransactionStatus},
+        fraud::{FraudDecision, FraudScreener, FraudService},
+        lifecycle::{unavailable_code, unavailable_message, AccountLifecycleService, AccountStatus},
+        limits::{AccountLimitService, LimitViolation},
+        requests::CreateTransactionRequest,
+        responses::TransactionResponse,
+    },
+    observability::metrics::record_transaction_created,
+};
+
+/// Why a transaction was not created, or was recorded as failed.
+#[derive(Debug)]
+pub enum PostingError {
+    AccountNotFound(String),
+    AccountUnavailable {
+        account_id: String,
+        status: AccountStatus,
+    },
+    /// Recorded as failed because it broke an account limit.
+    LimitExceeded {
+        transaction_id: String,
+        violation: LimitViolation,
+    },
+    /// Recorded as failed by fraud screening.
+    Blocked {
+        transaction_id: String,
+    },
+    Database(sqlx::Error),
+}
+
+impl From<sqlx::Error> for PostingError {
+    fn from(error: sqlx::Error) -> Self {
+        PostingError::Database(error)
+    }
+}
+
+impl std::fmt::Display for PostingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PostingError::AccountNotFound(account_id) => {
+                write!(f, "Account '{}' not found", account_id)
+            }
+            PostingError::AccountUnavailable { account_id, status } => {
+                write!(f, "{}", unavailable_message(account_id, *status))
+            }
+            PostingError::LimitExceeded { violation, .. } => write!(f, "{}", violation),
+            PostingError::Blocked { .. } => write!(f, "Transaction was declined by fraud screening"),
+            PostingError::Database(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl PostingError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            PostingError::AccountNotFound(_) => ErrorCode::NotFound,
+            PostingError::AccountUnavailable { status, .. } => unavailable_code(*status),
+            PostingError::LimitExceeded { .. } => ErrorCode::LimitExceeded,
+            PostingError::Blocked { .. } => ErrorCode::TransactionBlocked,
+            PostingError::Database(_) => ErrorCode::DatabaseError,
+        }
+    }
+
+    /// Transaction recorded as failed, if one was.
+    pub fn transaction_id(&self) -> Option<&str> {
+        match self {
+            PostingError::LimitExceeded { transaction_id, .. }
+            | PostingError::Blocked { transaction_id } => Some(transaction_id),
+            _ => None,
+        }
+    }
+}
+
+pub struct TransactionService;
+
+impl TransactionService {
+    /// Creates a single validated transaction the way `POST /api/transactions`
+    /// does: the account must accept transactions, limits are enforced and
+    /// fraud screening decides whether it completes, is held as `pending` or
+    /// is recorded as failed.
+    pub async fn create(
+        pool: &PgPool,
+        screener: &FraudScreener,
+        req: &CreateTransactionRequest,
+    ) -> Result<TransactionResponse, PostingError> {
+        let mut tx = pool.begin().await?;
+        let result = Self::post(&mut tx, screener, req).await;
+
+        // Transactions rejected by limits or screening are kept as failed.
+        let recorded = match &result {
+            Ok(_) => true,
+            Err(e) => e.transaction_id().is_some(),
+        };
+        if recorded {
+            tx.commit().await?;
+        }
+
+        if let Ok(transaction) = &result {
+            record_transaction_created(
+                &transaction.transaction_type.to_string(),
+                &transaction.currency.to_string(),
+            );
+        }
+
+        result
+    }
+
+    /// Like `create`, but inside the caller's database transaction, which
+    /// the caller commits. Failed transactions recorded for a
+    /// `LimitExceeded` or `Blocked` error are only kept if it does.
+    pub async fn post(
+        conn: &mut sqlx::PgConnection,
+        screener: &FraudScreener,
+        req: &CreateTransactionRequest,
+    ) -> Result<TransactionResponse, PostingError> {
+        // The account stays locked against freezing or closure, and its limits
+        // against concurrent transactions, until the insert commits.
+        match AccountLifecycleService::lock_status(&mut *conn, &req.account_id).await? {
+            None => return Err(PostingError::AccountNotFound(req.account_id.clone())),
+            Some(status) if !status.accepts_transactions() => {
+                return Err(PostingError::AccountUnavailable {
+                    account_id: req.account_id.clone(),
+                    status,
+                });
+            }
+            Some(_) => {}
+        }
+
+        if let Some(violation) = AccountLimitService::check(&mut *conn, req).await? {
+            let failed = Self::insert(&mut *conn, req, TransactionStatus::Failed, Some(violation.reason)).await?;
+
+            return Err(PostingError::LimitExceeded {
+                transaction_id: failed.transaction_id,
+                violation,
+            });
+        }
+
+        let screening = screener.screen(&mut *conn, req).await?;
+        match screening.decision {
+            FraudDecision::Allow => {
+                let mut completed = Self::insert(&mut *conn, req, TransactionStatus::Completed, None).await?;
+                completed.fee = FeeService::charge(&mut *conn, &completed).await?;
+                Ok(completed)
+            }
+            FraudDecision::Review => {
+                let held = Self::insert(&mut *conn, req, TransactionStatus::Pending, None).await?;
+                FraudService::record_screening(&mut *conn, &held, &screening).await?;
+                Ok(held)
+            }
+            FraudDecision::Block => {
+                let blocked =
+                    Self::insert(&mut *conn, req, TransactionStatus::Failed, Some(FailureReason::Fraud)).await?;
+                FraudService::record_screening(&mut *conn, &blocked, &screening).await?;
+
+                Err(PostingError::Blocked {
+                    transaction_id: blocked.transaction_id,
+                })
+            }
+        }
+    }
+
+    async fn insert(
+        conn: &mut sqlx::PgConnection,
+        req: &CreateTransactionRequest,
+        status: TransactionStatus,
+        failure_reason: Option<FailureReason>,
+    ) -> Result<TransactionResponse, sqlx::Error> {
+        let transaction_id = generate_transaction_id();
+
+        let transaction = sqlx::query_as::<_, (
+            String,
+            String,
+            f64,
+            String,
+            String,
+            String,
+            Option<String>,
+            Option<serde_json::Value>,
+            chrono::DateTime<chrono::Utc>,
+            Option<chrono::DateTime<chrono::Utc>>,
+            Option<String>,
+        )>(
+            r#"
+            INSERT INTO transactions (
+                transaction_id, account_id, amount, currency,
+                transaction_type, status, description, metadata,
+                created_at, processed_at, failure_reason
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, NOW(), NOW(), $9)
+            RETURNING
+                transaction_id, account_id, amount, currency,
+                transaction_type, status, description, metadata,
+                created_at, processed_at, failure_reason
+            "#
+        )
+        .bind(&transaction_id)
+        .bind(&req.account_id)
+        .bind(req.transaction_type.ledger_amount(req.amount))
+        .bind(req.currency.to_string())
+        .bind(req.transaction_type.to_string())
+        .bind(status.to_string())
+        .bind(&req.description)
+        .bind(&req.metadata)
+        .bind(failure_reason.map(|reason| reason.to_string()))
+        .fetch_one(conn)
+        .await?;
+
+        Ok(TransactionResponse {
+            transaction_id: transaction.0,
+            account_id: transaction.1,
+            amount: transaction.2,
+            currency: transaction.3.parse().unwrap_or_default(),
+            transaction_type: transaction.4.parse().unwrap_or_default(),
+            status: transaction.5.parse().unwrap_or_default(),
+            description: transaction.6,
+            metadata: transaction.7,
+            created_at: transaction.8,
+            processed_at: transaction.9,
+            failure_reason: transaction.10.and_then(|r| r.parse().ok()),
+            fee: None,
+            links: None,
+        })
+    }
+}
@@ -1,3 +1,5 @@
+use chrono::{DateTime, Datelike, Months, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use utoipa::ToSchema;
@@ -32,6 +34,144 @@ pub struct QuotaStatus {
     pub key_id: String,
     pub limits: QuotaLimits,
     pub usage: QuotaUsageStats,
+    pub alerts: QuotaAlertStatus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QuotaPeriod {
+    Daily,
+    Monthly,
+}
+
+impl std::fmt::Display for QuotaPeriod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QuotaPeriod::Daily => write!(f, "daily"),
+            QuotaPeriod::Monthly => write!(f, "monthly"),
+        }
+    }
+}
+
+impl std::str::FromStr for QuotaPeriod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "daily" => Ok(QuotaPeriod::Daily),
+            "monthly" => Ok(QuotaPeriod::Monthly),
+            _ => Err(format!("Invalid quota period: {}", s)),
+        }
+    }
+}
+
+/// Soft threshold and grace overage configuration for an API key.
+///
+/// Thresholds are percentages of the daily and monthly quota. Each one fires a
+/// single alert per period. The grace overage extends the hard limit by a
+/// percentage of the quota; requests in that band are allowed but counted as
+/// overage instead of being rejected.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct QuotaAlertSettings {
+    #[schema(example = json!([80, 100]))]
+    pub soft_thresholds: Vec<i32>,
+    #[schema(example = 10)]
+    pub grace_overage_percent: i32,
+}
+
+impl Default for QuotaAlertSettings {
+    fn default() -> Self {
+        Self {
+            soft_thresholds: vec![80, 100],
+            grace_overage_percent: 0,
+        }
+    }
+}
+
+impl QuotaAlertSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.soft_thresholds.len() > 10 {
+            return Err("At most 10 soft thresholds are allowed".to_string());
+        }
+
+        if self.soft_thresholds.iter().any(|t| !(1..=200).contains(t)) {
+            return Err("Soft thresholds must be between 1 and 200 percent".to_string());
+        }
+
+        let mut unique = self.soft_thresholds.clone();
+        unique.sort_unstable();
+        unique.dedup();
+        if unique.len() != self.soft_thresholds.len() {
+            return Err("Duplicate soft thresholds not allowed".to_string());
+        }
+
+        if !(0..=100).contains(&self.grace_overage_percent) {
+            return Err("Grace overage must be between 0 and 100 percent".to_string());
+        }
+
+        Ok(())
+    }
+
+    /// Request count at which a quota starts rejecting, including grace.
+    pub fn hard_limit(&self, quota: i32) -> i32 {
+        let grace = (quota as i64 * self.grace_overage_percent as i64) / 100;
+        (quota as i64 + grace).min(i32::MAX as i64) as i32
+    }
+
+    /// Thresholds reached by `usage` against `quota`, in ascending order.
+    pub fn crossed_thresholds(&self, usage: i32, quota: i32) -> Vec<i32> {
+        if quota <= 0 {
+            return vec![];
+        }
+
+        let mut crossed: Vec<i32> = self
+            .soft_thresholds
+            .iter()
+            .copied()
+            .filter(|t| usage as i64 * 100 >= *t as i64 * quota as i64)
+            .collect();
+        crossed.sort_unstable();
+        crossed
+    }
+
+    /// Thresholds first reached when usage goes from `before` to `after`.
+    pub fn thresholds_crossed_between(&self, before: i32, after: i32, quota: i32) -> Vec<i32> {
+        self.crossed_thresholds(after, quota)
+            .into_iter()
+            .filter(|t| (before as i64) * 100 < *t as i64 * quota as i64)
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct QuotaThresholdState {
+    pub period: QuotaPeriod,
+    pub threshold_percent: i32,
+    pub reached: bool,
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub reached_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct QuotaAlertStatus {
+    pub soft_thresholds: Vec<i32>,
+    pub grace_overage_percent: i32,
+    pub daily_overage: i32,
+    pub monthly_overage: i32,
+    pub thresholds: Vec<QuotaThresholdState>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct QuotaThresholdEvent {
+    pub key_id: String,
+    pub period: QuotaPeriod,
+    #[schema(value_type = String, format = Date, example = "2024-01-15")]
+    pub period_start: String,
+    pub threshold_percent: i32,
+    pub usage_count: i32,
+    pub quota_limit: i32,
+    #[schema(value_type = String, format = DateTime)]
+    pub created_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -40,19 +180,413 @@ pub struct QuotaUsageStats {
     pub this_month: i32,
     pub daily_remaining: i32,
     pub monthly_remaining: i32,
+    #[schema(example = "America/New_York")]
+    pub timezone: String,
+    #[schema(value_type = String, format = DateTime)]
+    pub day_start: DateTime<Utc>,
+    #[schema(value_type = String, format = DateTime)]
+    pub day_end: DateTime<Utc>,
+    #[schema(value_type = String, format = DateTime)]
+    pub period_start: DateTime<Utc>,
+    #[schema(value_type = String, format = DateTime)]
+    pub period_end: DateTime<Utc>,
+}
+
+/// Timezone and billing anchor that define when an API key's quotas reset.
+///
+/// Daily quotas reset at local midnight in `timezone`. Monthly quotas run from
+/// `billing_anchor_day` to the same day of the next month, clamped to the last
+/// day of shorter months.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct BillingCycleSettings {
+    #[schema(example = "America/New_York")]
+    pub timezone: String,
+    #[schema(example = 15, minimum = 1, maximum = 31)]
+    pub billing_anchor_day: i32,
+}
+
+impl Default for BillingCycleSettings {
+    fn default() -> Self {
+        Self {
+            timezone: "UTC".to_string(),
+            billing_anchor_day: 1,
+        }
+    }
+}
+
+/// Current daily and billing-period windows for a key.
+///
+/// Dates are local to the key's timezone; instants are the matching UTC
+/// boundaries. End values are exclusive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuotaWindows {
+    pub today: NaiveDate,
+    pub period_start_date: NaiveDate,
+    pub period_end_date: NaiveDate,
+    pub day_start: DateTime<Utc>,
+    pub day_end: DateTime<Utc>,
+    pub period_start: DateTime<Utc>,
+    pub period_end: DateTime<Utc>,
+}
+
+/// Limits, alert settings and current usage of a key, loaded once per request
+/// by [`QuotaService::load_state`].
+#[derive(Debug, Clone)]
+pub struct QuotaState {
+    pub limits: QuotaLimits,
+    pub settings: QuotaAlertSettings,
+    pub windows: QuotaWindows,
+    pub usage: QuotaUsageStats,
+}
+
+/// A soft threshold reached by a usage increment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThresholdCrossing {
+    pub period: QuotaPeriod,
+    pub period_start: NaiveDate,
+    pub threshold_percent: i32,
+    pub usage_count: i32,
+    pub quota_limit: i32,
+}
+
+impl QuotaState {
+    pub fn within_daily_quota(&self) -> bool {
+        self.usage.today < self.settings.hard_limit(self.limits.daily_quota)
+    }
+
+    pub fn within_monthly_quota(&self) -> bool {
+        self.usage.this_month < self.settings.hard_limit(self.limits.monthly_quota)
+    }
+
+    /// Whether `count` more units fit in both the daily and monthly quota.
+    pub fn has_capacity(&self, count: i32) -> bool {
+        self.usage.today + count <= self.settings.hard_limit(self.limits.daily_quota)
+            && self.usage.this_month + count <= self.settings.hard_limit(self.limits.monthly_quota)
+    }
+
+    /// Thresholds crossed once today's count has gone from the loaded usage to
+    /// `today_count`. The month grows by the same amount.
+    pub fn crossings(&self, today_count: i32) -> Vec<ThresholdCrossing> {
+        let added = today_count - self.usage.today;
+        let mut crossings = Vec::new();
+
+        for (period, period_start, before, quota) in [
+            (QuotaPeriod::Daily, self.windows.today, self.usage.today, self.limits.daily_quota),
+            (
+                QuotaPeriod::Monthly,
+                self.windows.period_start_date,
+                self.usage.this_month,
+                self.limits.monthly_quota,
+            ),
+        ] {
+            let after = before + added;
+            for threshold in self.settings.thresholds_crossed_between(before, after, quota) {
+                crossings.push(ThresholdCrossing {
+                    period,
+                    period_start,
+                    threshold_percent: threshold,
+                    usage_count: after,
+                    quota_limit: quota,
+                });
+            }
+        }
+
+        crossings
+    }
+}
+
+impl BillingCycleSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.timezone.parse::<Tz>().is_err() {
+            return Err(format!("Invalid IANA timezone: {}", self.timezone));
+        }
+
+        if !(1..=31).contains(&self.billing_anchor_day) {
+            return Err("Billing anchor day must be between 1 and 31".to_string());
+        }
+
+        Ok(())
+    }
+
+    fn tz(&self) -> Tz {
+        self.timezone.parse().unwrap_or(Tz::UTC)
+    }
+
+    /// Calendar date in the key's timezone at the instant `now`.
+    pub fn local_date(&self, now: DateTime<Utc>) -> NaiveDate {
+        now.with_timezone(&self.tz()).date_naive()
+    }
+
+    /// Billing period containing `date`, as `[start, end)` local dates.
+    pub fn billing_period(&self, date: NaiveDate) -> (NaiveDate, NaiveDate) {
+        let anchor = self.billing_anchor_day.clamp(1, 31) as u32;
+        let this_month = anchor_in_month(date.year(), date.month(), anchor);
+
+        let start = if date >= this_month {
+            this_month
+        } else {
+            let prev = first_of_month(date)
+                .checked_sub_months(Months::new(1))
+                .unwrap_or(date);
+            anchor_in_month(prev.year(), prev.month(), anchor)
+        };
+
+        let next = first_of_month(start)
+            .checked_add_months(Months::new(1))
+            .unwrap_or(start);
+        let end = anchor_in_month(next.year(), next.month(), anchor);
+
+        (start, end)
+    }
+
+    /// UTC instant of local midnight on `date`, skipping forward over DST gaps.
+    pub fn local_midnight(&self, date: NaiveDate) -> DateTime<Utc> {
+        let tz = self.tz();
+        (0..4)
+            .filter_map(|hour| date.and_hms_opt(hour, 0, 0))
+            .find_map(|local| tz.from_local_datetime(&local).earliest())
+            .map(|dt| dt.with_timezone(&Utc))
+            .unwrap_or_else(|| date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc())
+    }
+
+    pub fn windows(&self, now: DateTime<Utc>) -> QuotaWindows {
+        let today = self.local_date(now);
+        let tomorrow = today.succ_opt().unwrap_or(today);
+        let (period_start_date, period_end_date) = self.billing_period(today);
+
+        QuotaWindows {
+            today,
+            period_start_date,
+            period_end_date,
+            day_start: self.local_midnight(today),
+            day_end: self.local_midnight(tomorrow),
+            period_start: self.local_midnight(period_start_date),
+            period_end: self.local_midnight(period_end_date),
+        }
+    }
+}
+
+fn first_of_month(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).unwrap_or(date)
+}
+
+/// Day `anchor` of the month, or its last day if the month is shorter.
+pub(crate) fn anchor_in_month(year: i32, month: u32, anchor: u32) -> NaiveDate {
+    let first = NaiveDate::from_ymd_opt(year, month, 1).unwrap_or_default();
+    let last_day = first
+        .checked_add_months(Months::new(1))
+        .and_then(|next| next.pred_opt())
+        .map(|d| d.day())
+        .unwrap_or(28);
+
+    NaiveDate::from_ymd_opt(year, month, anchor.min(last_day)).unwrap_or(first)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageGranularity {
+    #[default]
+    Day,
+    Month,
+}
+
+impl std::fmt::Display for UsageGranularity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UsageGranularity::Day => write!(f, "day"),
+            UsageGranularity::Month => write!(f, "month"),
+        }
+    }
+}
+
+impl UsageGranularity {
+    /// Start of the bucket containing `date`.
+    pub fn truncate(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            UsageGranularity::Day => date,
+            UsageGranularity::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+
+    /// Start of the bucket following the one starting at `date`.
+    pub fn next(&self, date: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            UsageGranularity::Day => date.succ_opt(),
+            UsageGranularity::Month => date.checked_add_months(Months::new(1)),
+        }
+    }
+
+    fn max_buckets(&self) -> usize {
+        match self {
+            UsageGranularity::Day => 366,
+            UsageGranularity::Month => 120,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageHistoryFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, utoipa::IntoParams, ToSchema)]
+pub struct UsageHistoryParams {
+    /// First day of the range (inclusive). Defaults to 29 days, or 11 months, before `to`.
+    #[serde(default)]
+    #[param(value_type = Option<String>, format = Date)]
+    #[schema(value_type = Option<String>, format = Date)]
+    pub from: Option<NaiveDate>,
+
+    /// Last day of the range (inclusive). Defaults to today.
+    #[serde(default)]
+    #[param(value_type = Option<String>, format = Date)]
+    #[schema(value_type = Option<String>, format = Date)]
+    pub to: Option<NaiveDate>,
+
+    #[serde(default)]
+    pub granularity: Option<UsageGranularity>,
+
+    #[serde(default)]
+    pub format: Option<UsageHistoryFormat>,
+}
+
+impl UsageHistoryParams {
+    /// Resolves the requested range against `today`, returning `(from, to, granularity)`.
+    pub fn resolve(
+        &self,
+        today: NaiveDate,
+    ) -> Result<(NaiveDate, NaiveDate, UsageGranularity), String> {
+        let granularity = self.granularity.unwrap_or_default();
+        let to = self.to.unwrap_or(today);
+        let from = match self.from {
+            Some(from) => from,
+            None => match granularity {
+                UsageGranularity::Day => to - chrono::Duration::days(29),
+                UsageGranularity::Month => granularity
+                    .truncate(to)
+                    .checked_sub_months(Months::new(11))
+                    .unwrap_or(to),
+            },
+        };
+
+        if from > to {
+            return Err("'from' must not be after 'to'".to_string());
+        }
+
+        let buckets = fill_usage_series(from, to, granularity, &[]).len();
+        if buckets > granularity.max_buckets() {
+            return Err(format!(
+                "Range too large: at most {} {} buckets are allowed",
+                granularity.max_buckets(),
+                granularity
+            ));
+        }
+
+        Ok((from, to, granularity))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct UsageHistoryPoint {
+    #[schema(value_type = String, format = Date, example = "2024-01-01")]
+    pub period_start: NaiveDate,
+    pub request_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UsageHistoryResponse {
+    pub key_id: String,
+    pub granularity: UsageGranularity,
+    #[schema(value_type = String, format = Date)]
+    pub from: NaiveDate,
+    #[schema(value_type = String, format = Date)]
+    pub to: NaiveDate,
+    pub total_requests: i64,
+    pub data: Vec<UsageHistoryPoint>,
+}
+
+impl UsageHistoryResponse {
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("key_id,period_start,request_count\n");
+        for point in &self.data {
+            csv.push_str(&format!(
+                "{},{},{}\n",
+                self.key_id, point.period_start, point.request_count
+            ));
+        }
+        csv
+    }
+}
+
+/// Builds a contiguous series of buckets from `from` to `to`, taking counts from
+/// `rows` (keyed by bucket start) and zero-filling every bucket with no usage.
+pub fn fill_usage_series(
+    from: NaiveDate,
+    to: NaiveDate,
+    granularity: UsageGranularity,
+    rows: &[(NaiveDate, i64)],
+) -> Vec<UsageHistoryPoint> {
+    let mut series = Vec::new();
+    let mut cursor = granularity.truncate(from);
+    let last = granularity.truncate(to);
+
+    while cursor <= last {
+        let request_count = rows
+            .iter()
+            .filter(|(date, _)| *date == cursor)
+            .map(|(_, count)| *count)
+            .sum();
+
+        series.push(UsageHistoryPoint {
+            period_start: cursor,
+            request_count,
+        });
+
+        match granularity.next(cursor) {
+            Some(next) => cursor = next,
+            None => break,
+        }
+    }
+
+    series
 }
 
 pub struct QuotaService;
 
 impl QuotaService {
+    /// Charges one unit on the key's local `today`; returns the day's count.
     pub async fn increment_usage(
         pool: &PgPool,
         key_id: &str,
+        today: NaiveDate,
     ) -> Result<i32, sqlx::Error> {
         let result = sqlx::query_scalar::<_, i32>(
-            "SELECT increment_quota_usage($1, CURRENT_DATE)"
+            "SELECT increment_quota_usage($1, $2)"
         )
         .bind(key_id)
+        .bind(today)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(result)
+    }
+
+    /// Charges `count` units at once, e.g. one per item of a batch request.
+    pub async fn increment_usage_by(
+        pool: &PgPool,
+        key_id: &str,
+        today: NaiveDate,
+        count: i32,
+    ) -> Result<i32, sqlx::Error> {
+        let result = sqlx::query_scalar::<_, i32>(
+            "SELECT increment_quota_usage_by($1, $2, $3)"
+        )
+        .bind(key_id)
+        .bind(today)
+        .bind(count)
         .fetch_one(pool)
         .await?;
 
@@ -62,16 +596,77 @@ impl QuotaService {
     pub async fn get_usage(
         pool: &PgPool,
         key_id: &str,
+    ) -> Result<QuotaUsageStats, sqlx::Error> {
+        let cycle = Self::get_billing_cycle(pool, key_id).await?;
+        let windows = cycle.windows(Utc::now());
+        let limits = Self::get_limits(pool, key_id).await?;
+
+        Self::usage_in(pool, key_id, &cycle, &windows, &limits).await
+    }
+
+    /// Loads everything quota enforcement needs for a key: one read of the
+    /// key's settings and two of its usage.
+    pub async fn load_state(pool: &PgPool, key_id: &str) -> Result<QuotaState, sqlx::Error> {
+        let row = sqlx::query_as::<_, (i32, i32, i32, Vec<i32>, i32, String, i32)>(
+            r#"
+            SELECT rate_limit_per_minute, daily_quota, monthly_quota,
+                   soft_thresholds, grace_overage_percent,
+                   quota_timezone, billing_anchor_day
+            FROM api_keys
+            WHERE key_id = $1
+            "#
+        )
+        .bind(key_id)
+        .fetch_optional(pool)
+        .await?;
+
+        let (limits, settings, cycle) = match row {
+            Some((rate_limit, daily, monthly, soft_thresholds, grace_overage_percent, timezone, billing_anchor_day)) => (
+                QuotaLimits {
+                    rate_limit_per_minute: rate_limit,
+                    daily_quota: daily,
+                    monthly_quota: monthly,
+                },
+                QuotaAlertSettings {
+                    soft_thresholds,
+                    grace_overage_percent,
+                },
+                BillingCycleSettings {
+                    timezone,
+                    billing_anchor_day,
+                },
+            ),
+            None => Default::default(),
+        };
+
+        let windows = cycle.windows(Utc::now());
+        let usage = Self::usage_in(pool, key_id, &cycle, &windows, &limits).await?;
+
+        Ok(QuotaState {
+            limits,
+            settings,
+            windows,
+            usage,
+        })
+    }
+
+    async fn usage_in(
+        pool: &PgPool,
+        key_id: &str,
+        cycle: &BillingCycleSettings,
+        windows: &QuotaWindows,
+        limits: &QuotaLimits,
     ) -> Result<QuotaUsageStats, sqlx::Error> {
         // Get today's usage
         let today = sqlx::query_scalar::<_, Option<i32>>(
             r#"
             SELECT request_count
             FROM quota_usage
-            WHERE key_id = $1 AND usage_date = CURRENT_DATE
+            WHERE key_id = $1 AND usage_date = $2
             "#
         )
         .bind(key_id)
+        .bind(windows.today)
         .fetch_optional(pool)
         .await?
         .flatten()
@@ -79,26 +674,31 @@ impl QuotaService {
 
         let this_month = sqlx::query_scalar::<_, Option<i32>>(
             r#"
-            SELECT COALESCE(SUM(request_count), 0)
+            SELECT COALESCE(SUM(request_count), 0)::INTEGER
             FROM quota_usage
             WHERE key_id = $1
-            AND usage_date >= DATE_TRUNC('month', CURRENT_DATE)
-            AND usage_date < DATE_TRUNC('month', CURRENT_DATE) + INTERVAL '1 month'
+            AND usage_date >= $2
+            AND usage_date < $3
             "#
         )
         .bind(key_id)
+        .bind(windows.period_start_date)
+        .bind(windows.period_end_date)
         .fetch_optional(pool)
         .await?
         .flatten()
         .unwrap_or(0);
 
-        let limits = Self::get_limits(pool, key_id).await?;
-
         Ok(QuotaUsageStats {
             today,
             this_month,
             daily_remaining: (limits.daily_quota - today).max(0),
             monthly_remaining: (limits.monthly_quota - this_month).max(0),
+            timezone: cycle.timezone.clone(),
+            day_start: windows.day_start,
+            day_end: windows.day_end,
+            period_start: windows.period_start,
+            period_end: windows.period_end,
         })
     }
 
@@ -127,37 +727,307 @@ impl QuotaService {
         }
     }
 
-    pub async fn check_daily_quota(
+    pub async fn get_status(
         pool: &PgPool,
         key_id: &str,
-    ) -> Result<bool, sqlx::Error> {
-        let usage = Self::get_usage(pool, key_id).await?;
+    ) -> Result<QuotaStatus, sqlx::Error> {
         let limits = Self::get_limits(pool, key_id).await?;
-        
-        Ok(usage.today < limits.daily_quota)
+        let usage = Self::get_usage(pool, key_id).await?;
+        let alerts = Self::get_alert_status(pool, key_id, &limits, &usage).await?;
+
+        Ok(QuotaStatus {
+            key_id: key_id.to_string(),
+            limits,
+            usage,
+            alerts,
+        })
     }
 
-    pub async fn check_monthly_quota(
+    pub async fn get_alert_settings(
         pool: &PgPool,
         key_id: &str,
-    ) -> Result<bool, sqlx::Error> {
-        let usage = Self::get_usage(pool, key_id).await?;
-        let limits = Self::get_limits(pool, key_id).await?;
-        
-        Ok(usage.this_month < limits.monthly_quota)
+    ) -> Result<QuotaAlertSettings, sqlx::Error> {
+        let result = sqlx::query_as::<_, (Vec<i32>, i32)>(
+            r#"
+            SELECT soft_thresholds, grace_overage_percent
+            FROM api_keys
+            WHERE key_id = $1
+            "#
+        )
+        .bind(key_id)
+        .fetch_optional(pool)
+        .await?;
+
+        match result {
+            Some((soft_thresholds, grace_overage_percent)) => Ok(QuotaAlertSettings {
+                soft_thresholds,
+                grace_overage_percent,
+            }),
+            None => Ok(QuotaAlertSettings::default()),
+        }
     }
 
-    pub async fn get_status(
+    pub async fn update_alert_settings(
         pool: &PgPool,
         key_id: &str,
-    ) -> Result<QuotaStatus, sqlx::Error> {
-        let limits = Self::get_limits(pool, key_id).await?;
-        let usage = Self::get_usage(pool, key_id).await?;
+        settings: &QuotaAlertSettings,
+    ) -> Result<QuotaAlertSettings, sqlx::Error> {
+        let (soft_thresholds, grace_overage_percent) = sqlx::query_as::<_, (Vec<i32>, i32)>(
+            r#"
+            UPDATE api_keys
+            SET soft_thresholds = $2, grace_overage_percent = $3
+            WHERE key_id = $1
+            RETURNING soft_thresholds, grace_overage_percent
+            "#
+        )
+        .bind(key_id)
+        .bind(&settings.soft_thresholds)
+        .bind(settings.grace_overage_percent)
+        .fetch_one(pool)
+        .await?;
 
-        Ok(QuotaStatus {
+        Ok(QuotaAlertSettings {
+            soft_thresholds,
+            grace_overage_percent,
+        })
+    }
+
+    pub async fn get_billing_cycle(
+        pool: &PgPool,
+        key_id: &str,
+    ) -> Result<BillingCycleSettings, sqlx::Error> {
+        let result = sqlx::query_as::<_, (String, i32)>(
+            r#"
+            SELECT quota_timezone, billing_anchor_day
+            FROM api_keys
+            WHERE key_id = $1
+            "#
+        )
+        .bind(key_id)
+        .fetch_optional(pool)
+        .await?;
+
+        match result {
+            Some((timezone, billing_anchor_day)) => Ok(BillingCycleSettings {
+                timezone,
+                billing_anchor_day,
+            }),
+            None => Ok(BillingCycleSettings::default()),
+        }
+    }
+
+    pub async fn update_billing_cycle(
+        pool: &PgPool,
+        key_id: &str,
+        settings: &BillingCycleSettings,
+    ) -> Result<BillingCycleSettings, sqlx::Error> {
+        let (timezone, billing_anchor_day) = sqlx::query_as::<_, (String, i32)>(
+            r#"
+            UPDATE api_keys
+            SET quota_timezone = $2, billing_anchor_day = $3
+            WHERE key_id = $1
+            RETURNING quota_timezone, billing_anchor_day
+            "#
+        )
+        .bind(key_id)
+        .bind(&settings.timezone)
+        .bind(settings.billing_anchor_day)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(BillingCycleSettings {
+            timezone,
+            billing_anchor_day,
+        })
+    }
+
+    /// Records the thresholds crossed by an increment that took today's count
+    /// from `state.usage.today` to `today_count`.
+    ///
+    /// Events are unique per key, period and threshold, so concurrent requests
+    /// crossing the same threshold emit it once. Returns the newly recorded
+    /// events.
+    pub async fn record_threshold_crossings(
+        pool: &PgPool,
+        key_id: &str,
+        state: &QuotaState,
+        today_count: i32,
+    ) -> Result<Vec<QuotaThresholdEvent>, sqlx::Error> {
+        let mut events = Vec::new();
+
+        for crossing in state.crossings(today_count) {
+            let inserted = sqlx::query_as::<_, (String, DateTime<Utc>)>(
+                r#"
+                INSERT INTO quota_threshold_events (
+                    key_id, period, period_start, threshold_percent,
+                    usage_count, quota_limit, created_at
+                )
+                VALUES ($1, $2, $3, $4, $5, $6, NOW())
+                ON CONFLICT (key_id, period, period_start, threshold_percent) DO NOTHING
+                RETURNING period_start::TEXT, created_at
+                "#
+            )
+            .bind(key_id)
+            .bind(crossing.period.to_string())
+            .bind(crossing.period_start)
+            .bind(crossing.threshold_percent)
+            .bind(crossing.usage_count)
+            .bind(crossing.quota_limit)
+            .fetch_optional(pool)
+            .await?;
+
+            if let Some((period_start, created_at)) = inserted {
+                tracing::warn!(
+                    key_id = %key_id,
+                    period = %crossing.period,
+                    threshold_percent = crossing.threshold_percent,
+                    usage_count = crossing.usage_count,
+                    quota_limit = crossing.quota_limit,
+                    "Quota threshold reached"
+                );
+
+                events.push(QuotaThresholdEvent {
+                    key_id: key_id.to_string(),
+                    period: crossing.period,
+                    period_start,
+                    threshold_percent: crossing.threshold_percent,
+                    usage_count: crossing.usage_count,
+                    quota_limit: crossing.quota_limit,
+                    created_at,
+                });
+            }
+        }
+
+        Ok(events)
+    }
+
+    pub async fn get_alert_status(
+        pool: &PgPool,
+        key_id: &str,
+        limits: &QuotaLimits,
+        usage: &QuotaUsageStats,
+    ) -> Result<QuotaAlertStatus, sqlx::Error> {
+        let settings = Self::get_alert_settings(pool, key_id).await?;
+        let windows = Self::get_billing_cycle(pool, key_id)
+            .await?
+            .windows(Utc::now());
+
+        let reached = sqlx::query_as::<_, (String, i32, DateTime<Utc>)>(
+            r#"
+            SELECT period, threshold_percent, created_at
+            FROM quota_threshold_events
+            WHERE key_id = $1
+            AND (
+                (period = 'daily' AND period_start = $2)
+                OR (period = 'monthly' AND period_start = $3)
+            )
+            "#
+        )
+        .bind(key_id)
+        .bind(windows.today)
+        .bind(windows.period_start_date)
+        .fetch_all(pool)
+        .await?;
+
+        let mut thresholds = Vec::new();
+        for period in [QuotaPeriod::Daily, QuotaPeriod::Monthly] {
+            let mut sorted = settings.soft_thresholds.clone();
+            sorted.sort_unstable();
+
+            for threshold in sorted {
+                let reached_at = reached
+                    .iter()
+                    .find(|(p, t, _)| *p == period.to_string() && *t == threshold)
+                    .map(|(_, _, at)| *at);
+
+                thresholds.push(QuotaThresholdState {
+                    period,
+                    threshold_percent: threshold,
+                    reached: reached_at.is_some(),
+                    reached_at,
+                });
+            }
+        }
+
+        Ok(QuotaAlertStatus {
+            daily_overage: (usage.today - limits.daily_quota).max(0),
+            monthly_overage: (usage.this_month - limits.monthly_quota).max(0),
+            soft_thresholds: settings.soft_thresholds,
+            grace_overage_percent: settings.grace_overage_percent,
+            thresholds,
+        })
+    }
+
+    pub async fn list_threshold_events(
+        pool: &PgPool,
+        key_id: &str,
+        limit: i64,
+    ) -> Result<Vec<QuotaThresholdEvent>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, (String, String, String, i32, i32, i32, DateTime<Utc>)>(
+            r#"
+            SELECT key_id, period, period_start::TEXT, threshold_percent,
+                   usage_count, quota_limit, created_at
+            FROM quota_threshold_events
+            WHERE key_id = $1
+            ORDER BY created_at DESC, id DESC
+            LIMIT $2
+            "#
+        )
+        .bind(key_id)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| QuotaThresholdEvent {
+                key_id: r.0,
+                period: r.1.parse().unwrap_or(QuotaPeriod::Daily),
+                period_start: r.2,
+                threshold_percent: r.3,
+                usage_count: r.4,
+                quota_limit: r.5,
+                created_at: r.6,
+            })
+            .collect())
+    }
+
+    pub async fn get_usage_history(
+        pool: &PgPool,
+        key_id: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+        granularity: UsageGranularity,
+    ) -> Result<UsageHistoryResponse, sqlx::Error> {
+        let rows = sqlx::query_as::<_, (NaiveDate, i64)>(
+            r#"
+            SELECT DATE_TRUNC($4, usage_date)::DATE AS period_start,
+                   SUM(request_count)::BIGINT AS request_count
+            FROM quota_usage
+            WHERE key_id = $1
+            AND usage_date >= $2
+            AND usage_date <= $3
+            GROUP BY period_start
+            ORDER BY period_start ASC
+            "#
+        )
+        .bind(key_id)
+        .bind(from)
+        .bind(to)
+        .bind(granularity.to_string())
+        .fetch_all(pool)
+        .await?;
+
+        let data = fill_usage_series(from, to, granularity, &rows);
+        let total_requests = data.iter().map(|p| p.request_count).sum();
+
+        Ok(UsageHistoryResponse {
             key_id: key_id.to_string(),
-            limits,
-            usage,
+            granularity,
+            from,
+            to,
+            total_requests,
+            data,
         })
     }
 }
@@ -0,0 +1,1071 @@
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    config::Config,
+    db::PgPool,
+    models::finance::Currency,
+};
+
+/// Amounts closer than this are considered equal.
+const AMOUNT_EPSILON: f64 = 0.005;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StatementFormat {
+    /// Delimited text described by a `CsvColumnMapping`.
+    Csv,
+    /// ISO 20022 `camt.053` bank-to-customer statement XML.
+    Camt053,
+}
+
+impl std::fmt::Display for StatementFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StatementFormat::Csv => write!(f, "csv"),
+            StatementFormat::Camt053 => write!(f, "camt053"),
+        }
+    }
+}
+
+impl std::str::FromStr for StatementFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "csv" => Ok(StatementFormat::Csv),
+            "camt053" | "camt.053" => Ok(StatementFormat::Camt053),
+            _ => Err(format!("Invalid statement format: {}", s)),
+        }
+    }
+}
+
+/// Which CSV columns (by header name) hold each statement field.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CsvColumnMapping {
+    #[schema(example = "Booking Date")]
+    pub date: String,
+
+    /// Signed amount; negative values are debits.
+    #[schema(example = "Amount")]
+    pub amount: String,
+
+    /// Column holding the ISO currency code; `default_currency` is used without it.
+    #[serde(default)]
+    pub currency: Option<String>,
+
+    /// Column holding the bank or payment reference.
+    #[serde(default)]
+    pub reference: Option<String>,
+
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// `chrono` format of the date column.
+    #[serde(default = "default_date_format")]
+    #[schema(example = "%Y-%m-%d")]
+    pub date_format: String,
+
+    #[serde(default)]
+    pub default_currency: Option<Currency>,
+
+    #[serde(default = "default_delimiter")]
+    #[schema(value_type = String, example = ",")]
+    pub delimiter: char,
+}
+
+fn default_date_format() -> String {
+    "%Y-%m-%d".to_string()
+}
+
+fn default_delimiter() -> char {
+    ','
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StatementError {
+    MissingMapping,
+    MissingColumn(String),
+    InvalidLine { line: usize, message: String },
+    InvalidXml(String),
+    Empty,
+    TooManyLines(usize),
+}
+
+impl std::fmt::Display for StatementError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StatementError::MissingMapping => write!(f, "CSV statements require a column mapping"),
+            StatementError::MissingColumn(column) => {
+                write!(f, "Column '{}' not found in statement header", column)
+            }
+            StatementError::InvalidLine { line, message } => {
+                write!(f, "Statement line {}: {}", line, message)
+            }
+            StatementError::InvalidXml(msg) => write!(f, "Invalid CAMT.053 statement: {}", msg),
+            StatementError::Empty => write!(f, "Statement contains no entries"),
+            StatementError::TooManyLines(max) => {
+                write!(f, "Statement must not exceed {} entries", max)
+            }
+        }
+    }
+}
+
+impl std::error::Error for StatementError {}
+
+/// One entry of an uploaded statement.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedStatementLine {
+    pub booking_date: NaiveDate,
+    /// Signed amount: credits positive, debits negative.
+    pub amount: f64,
+    pub currency: Currency,
+    pub reference: Option<String>,
+    pub description: Option<String>,
+}
+
+pub fn parse_statement(
+    format: StatementFormat,
+    content: &str,
+    mapping: Option<&CsvColumnMapping>,
+    max_lines: usize,
+) -> Result<Vec<ParsedStatementLine>, StatementError> {
+    let lines = match format {
+        StatementFormat::Csv => {
+            parse_csv_statement(content, mapping.ok_or(StatementError::MissingMapping)?)?
+        }
+        StatementFormat::Camt053 => parse_camt053(content)?,
+    };
+
+    if lines.is_empty() {
+        return Err(StatementError::Empty);
+    }
+    if lines.len() > max_lines {
+        return Err(StatementError::TooManyLines(max_lines));
+    }
+
+    Ok(lines)
+}
+
+pub fn parse_csv_statement(
+    content: &str,
+    mapping: &CsvColumnMapping,
+) -> Result<Vec<ParsedStatementLine>, StatementError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(mapping.delimiter as u8)
+        .trim(csv::Trim::All)
+        .from_reader(content.as_bytes());
+
+    let headers = reader
+        .headers()
+        .map_err(|e| StatementError::InvalidLine { line: 1, message: e.to_string() })?
+        .clone();
+    let column = |name: &str| {
+        headers
+            .iter()
+            .position(|header| header.eq_ignore_ascii_case(name))
+            .ok_or_else(|| StatementError::MissingColumn(name.to_string()))
+    };
+
+    let date_col = column(&mapping.date)?;
+    let amount_col = column(&mapping.amount)?;
+    let currency_col = mapping.currency.as_deref().map(column).transpose()?;
+    let reference_col = mapping.reference.as_deref().map(column).transpose()?;
+    let description_col = mapping.description.as_deref().map(column).transpose()?;
+
+    let mut lines = Vec::new();
+    for (n, record) in reader.records().enumerate() {
+        // Line numbers count the header row.
+        let line = n + 2;
+        let invalid = |message: String| StatementError::InvalidLine { line, message };
+        let record = record.map_err(|e| invalid(e.to_string()))?;
+        let field = |index: usize| record.get(index).unwrap_or_default();
+        let optional = |index: Option<usize>| {
+            index
+                .map(field)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+
+        let booking_date = NaiveDate::parse_from_str(field(date_col), &mapping.date_format)
+            .map_err(|e| invalid(format!("invalid date '{}': {}", field(date_col), e)))?;
+        let amount = parse_amount(field(amount_col))
+            .ok_or_else(|| invalid(format!("invalid amount '{}'", field(amount_col))))?;
+        let currency = match currency_col {
+            Some(index) => field(index).parse().map_err(invalid)?,
+            None => mapping
+                .default_currency
+                .ok_or_else(|| invalid("no currency column or default_currency".to_string()))?,
+        };
+
+        lines.push(ParsedStatementLine {
+            booking_date,
+            amount,
+            currency,
+            reference: optional(reference_col),
+            description: optional(description_col),
+        });
+    }
+
+    Ok(lines)
+}
+
+/// Parses amounts such as `-1,234.50`, `(12.00)` and `+7`.
+fn parse_amount(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let (negative, value) = match value.strip_prefix('(').and_then(|v| v.strip_suffix(')')) {
+        Some(inner) => (true, inner),
+        None => (false, value),
+    };
+
+    let amount: f64 = value.replace([',', ' '], "").parse().ok()?;
+    amount.is_finite().then_some(if negative { -amount } else { amount })
+}
+
+/// Reads the `Ntry` elements of every `Stmt` in a camt.053 document. The
+/// reference is the first of `AcctSvcrRef`, `EndToEndId` or `InstrId`; the
+/// description comes from unstructured remittance information.
+pub fn parse_camt053(content: &str) -> Result<Vec<ParsedStatementLine>, StatementError> {
+    let doc = roxmltree::Document::parse(content)
+        .map_err(|e| StatementError::InvalidXml(e.to_string()))?;
+
+    let entries = doc
+        .descendants()
+        .filter(|node| is_element(node, "Ntry"));
+
+    let mut lines = Vec::new();
+    for (n, entry) in entries.enumerate() {
+        let invalid = |message: String| StatementError::InvalidLine { line: n + 1, message };
+
+        let amount_node = child(entry, "Amt").ok_or_else(|| invalid("missing Amt".to_string()))?;
+        let amount: f64 = amount_node
+            .text()
+            .unwrap_or_default()
+            .trim()
+            .parse()
+            .map_err(|_| invalid("invalid Amt".to_string()))?;
+        let currency: Currency = amount_node
+            .attribute("Ccy")
+            .ok_or_else(|| invalid("missing Amt currency".to_string()))?
+            .parse()
+            .map_err(invalid)?;
+
+        let debit = child_text(entry, &["CdtDbtInd"]).as_deref() == Some("DBIT");
+
+        let date = child_text(entry, &["BookgDt", "Dt"])
+            .or_else(|| child_text(entry, &["BookgDt", "DtTm"]))
+            .or_else(|| child_text(entry, &["ValDt", "Dt"]))
+            .ok_or_else(|| invalid("missing BookgDt".to_string()))?;
+        let booking_date = NaiveDate::parse_from_str(date.get(..10).unwrap_or(&date), "%Y-%m-%d")
+            .map_err(|_| invalid(format!("invalid booking date '{}'", date)))?;
+
+        let reference = child_text(entry, &["AcctSvcrRef"])
+            .or_else(|| descendant_text(entry, "EndToEndId").filter(|r| r != "NOTPROVIDED"))
+            .or_else(|| descendant_text(entry, "InstrId"));
+        let description = descendant_text(entry, "Ustrd")
+            .or_else(|| child_text(entry, &["AddtlNtryInf"]));
+
+        lines.push(ParsedStatementLine {
+            booking_date,
+            amount: if debit { -amount } else { amount },
+            currency,
+            reference,
+            description,
+        });
+    }
+
+    Ok(lines)
+}
+
+/// Element name check that ignores the namespace, which differs between
+/// camt.053 versions.
+fn is_element(node: &roxmltree::Node, name: &str) -> bool {
+    node.is_element() && node.tag_name().name() == name
+}
+
+fn child<'a, 'input>(node: roxmltree::Node<'a, 'input>, name: &str) -> Option<roxmltree::Node<'a, 'input>> {
+    node.children().find(|child| is_element(child, name))
+}
+
+fn child_text(node: roxmltree::Node, path: &[&str]) -> Option<String> {
+    let mut current = node;
+    for name in path {
+        current = child(current, name)?;
+    }
+    non_empty(current.text())
+}
+
+fn descendant_text(node: roxmltree::Node, name: &str) -> Option<String> {
+    node.descendants()
+        .find(|descendant| is_element(descendant, name))
+        .and_then(|descendant| non_empty(descendant.text()))
+}
+
+fn non_empty(text: Option<&str>) -> Option<String> {
+    text.map(str::trim)
+        .filter(|text| !text.is_empty())
+        .map(str::to_string)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchStatus {
+    Matched,
+    Unmatched,
+    /// Several transactions fit equally well; resolve with a manual match.
+    Ambiguous,
+}
+
+impl std::fmt::Display for MatchStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MatchStatus::Matched => write!(f, "matched"),
+            MatchStatus::Unmatched => write!(f, "unmatched"),
+            MatchStatus::Ambiguous => write!(f, "ambiguous"),
+        }
+    }
+}
+
+impl std::str::FromStr for MatchStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "matched" => Ok(MatchStatus::Matched),
+            "unmatched" => Ok(MatchStatus::Unmatched),
+            "ambiguous" => Ok(MatchStatus::Ambiguous),
+            _ => Err(format!("Invalid match status: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchMethod {
+    /// Amount, currency, date and a reference in metadata all agree.
+    Reference,
+    /// Amount, currency and date agree and only one transaction fits.
+    Amount,
+    Manual,
+}
+
+impl std::fmt::Display for MatchMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MatchMethod::Reference => write!(f, "reference"),
+            MatchMethod::Amount => write!(f, "amount"),
+            MatchMethod::Manual => write!(f, "manual"),
+        }
+    }
+}
+
+impl std::str::FromStr for MatchMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "reference" => Ok(MatchMethod::Reference),
+            "amount" => Ok(MatchMethod::Amount),
+            "manual" => Ok(MatchMethod::Manual),
+            _ => Err(format!("Invalid match method: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MatchSettings {
+    /// Days a transaction may be created before or after the booking date.
+    pub date_tolerance_days: i64,
+    /// Metadata keys compared with the statement reference.
+    pub reference_keys: Vec<String>,
+}
+
+impl MatchSettings {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            date_tolerance_days: config.reconciliation_date_tolerance_days.max(0),
+            reference_keys: config.reconciliation_reference_keys.clone(),
+        }
+    }
+}
+
+impl Default for MatchSettings {
+    fn default() -> Self {
+        Self {
+            date_tolerance_days: 3,
+            reference_keys: vec!["reference".to_string(), "bank_reference".to_string()],
+        }
+    }
+}
+
+/// A transaction that a statement line may be matched to.
+#[derive(Debug, Clone)]
+pub struct MatchCandidate {
+    pub transaction_id: String,
+    pub amount: f64,
+    pub currency: Currency,
+    pub created_at: DateTime<Utc>,
+    pub metadata: Option<serde_json::Value>,
+}
+
+impl MatchCandidate {
+    fn fits(&self, line: &ParsedStatementLine, settings: &MatchSettings) -> bool {
+        let days = (self.created_at.date_naive() - line.booking_date).num_days().abs();
+
+        self.currency == line.currency
+            && (self.amount.abs() - line.amount.abs()).abs() < AMOUNT_EPSILON
+            && days <= settings.date_tolerance_days
+    }
+
+    fn has_reference(&self, reference: &str, settings: &MatchSettings) -> bool {
+        let Some(metadata) = &self.metadata else {
+            return false;
+        };
+
+        settings.reference_keys.iter().any(|key| match metadata.get(key) {
+            Some(serde_json::Value::String(value)) => value.trim().eq_ignore_ascii_case(reference.trim()),
+            Some(serde_json::Value::Number(value)) => value.to_string() == reference.trim(),
+            _ => false,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MatchOutcome {
+    Matched {
+        transaction_id: String,
+        method: MatchMethod,
+    },
+    Ambiguous(Vec<String>),
+    Unmatched,
+}
+
+/// Pairs statement lines with candidates, in line order. A transaction is
+/// matched to at most one line.
+///
+/// A line matches on amount (ignoring sign), currency and booking date within
+/// the tolerance. When its reference equals one of `reference_keys` in a fitting
+/// transaction's metadata, only those transactions are considered.
+pub fn match_lines(
+    lines: &[ParsedStatementLine],
+    candidates: &[MatchCandidate],
+    settings: &MatchSettings,
+) -> Vec<MatchOutcome> {
+    let mut used = HashSet::new();
+
+    lines
+        .iter()
+        .map(|line| {
+            let fitting: Vec<&MatchCandidate> = candidates
+                .iter()
+                .filter(|candidate| !used.contains(&candidate.transaction_id))
+                .filter(|candidate| candidate.fits(line, settings))
+                .collect();
+
+            let by_reference: Vec<&MatchCandidate> = match &line.reference {
+                Some(reference) => fitting
+                    .iter()
+                    .copied()
+                    .filter(|candidate| candidate.has_reference(reference, settings))
+                    .collect(),
+                None => Vec::new(),
+            };
+
+            let (pool, method) = if by_reference.is_empty() {
+                (fitting, MatchMethod::Amount)
+            } else {
+                (by_reference, MatchMethod::Reference)
+            };
+
+            match pool.as_slice() {
+                [] => MatchOutcome::Unmatched,
+                [candidate] => {
+                    used.insert(candidate.transaction_id.clone());
+                    MatchOutcome::Matched {
+                        transaction_id: candidate.transaction_id.clone(),
+                        method,
+                    }
+                }
+                several => MatchOutcome::Ambiguous(
+                    several
+                        .iter()
+                        .map(|candidate| candidate.transaction_id.clone())
+                        .collect(),
+                ),
+            }
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ImportStatementRequest {
+    pub format: StatementFormat,
+
+    /// Only transactions of this account are matched; all accounts if omitted.
+    #[serde(default)]
+    pub account_id: Option<String>,
+
+    #[serde(default)]
+    #[schema(example = "statement-2024-03.csv")]
+    pub file_name: Option<String>,
+
+    /// The statement file as text.
+    pub content: String,
+
+    /// Required for `csv` statements.
+    #[serde(default)]
+    pub mapping: Option<CsvColumnMapping>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ManualMatchRequest {
+    pub transaction_id: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ReconciliationReportParams {
+    /// Only include lines with this status
+    #[serde(default)]
+    pub status: Option<MatchStatus>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct StatementImportResponse {
+    pub import_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_id: Option<String>,
+    pub format: StatementFormat,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_name: Option<String>,
+    pub line_count: i32,
+    #[schema(value_type = String, format = DateTime)]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct StatementLineResponse {
+    pub line_id: String,
+    pub import_id: String,
+    /// Position of the entry in the statement, starting at 1.
+    pub line_number: i32,
+    #[schema(value_type = String, format = Date)]
+    pub booking_date: NaiveDate,
+    pub amount: f64,
+    pub currency: Currency,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub status: MatchStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub match_method: Option<MatchMethod>,
+    /// Transactions that fit an ambiguous line.
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub candidate_ids: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub matched_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ReconciliationSummary {
+    pub total: usize,
+    pub matched: usize,
+    pub unmatched: usize,
+    pub ambiguous: usize,
+    /// Sum of the absolute amounts of matched lines.
+    pub matched_amount: f64,
+    /// Sum of the absolute amounts of unmatched and ambiguous lines.
+    pub open_amount: f64,
+}
+
+impl ReconciliationSummary {
+    pub fn from_lines(lines: &[StatementLineResponse]) -> Self {
+        lines.iter().fold(Self::default(), |mut summary, line| {
+            summary.total += 1;
+            match line.status {
+                MatchStatus::Matched => {
+                    summary.matched += 1;
+                    summary.matched_amount += line.amount.abs();
+                }
+                MatchStatus::Unmatched => {
+                    summary.unmatched += 1;
+                    summary.open_amount += line.amount.abs();
+                }
+                MatchStatus::Ambiguous => {
+                    summary.ambiguous += 1;
+                    summary.open_amount += line.amount.abs();
+                }
+            }
+            summary
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReconciliationReport {
+    pub import: StatementImportResponse,
+    /// Counts over every line, regardless of the `status` filter.
+    pub summary: ReconciliationSummary,
+    pub lines: Vec<StatementLineResponse>,
+}
+
+#[derive(Debug)]
+pub enum ManualMatchError {
+    LineNotFound,
+    TransactionNotFound,
+    /// The transaction is already matched to another statement line.
+    TransactionTaken(String),
+    /// The transaction belongs to another account than the statement.
+    AccountMismatch,
+    Database(sqlx::Error),
+}
+
+impl From<sqlx::Error> for ManualMatchError {
+    fn from(error: sqlx::Error) -> Self {
+        ManualMatchError::Database(error)
+    }
+}
+
+pub fn generate_import_id() -> String {
+    use rand::Rng;
+    let timestamp = Utc::now().timestamp();
+    let random: u32 = rand::rng().random();
+    format!("stmt_{}_{:08x}", timestamp, random)
+}
+
+fn generate_line_id() -> String {
+    use rand::Rng;
+    let random: u64 = rand::rng().random();
+    format!("stl_{:016x}", random)
+}
+
+type LineRow = (
+    String,
+    String,
+    i32,
+    NaiveDate,
+    f64,
+    String,
+    Option<String>,
+    Option<String>,
+    String,
+    Option<String>,
+    Option<String>,
+    Vec<String>,
+    Option<DateTime<Utc>>,
+);
+
+const LINE_COLUMNS: &str = "line_id, import_id, line_number, booking_date, amount, currency, \
+     reference, description, status, transaction_id, match_method, candidate_ids, matched_at";
+
+fn line_from_row(row: LineRow) -> StatementLineResponse {
+    StatementLineResponse {
+        line_id: row.0,
+        import_id: row.1,
+        line_number: row.2,
+        booking_date: row.3,
+        amount: row.4,
+        currency: row.5.parse().unwrap_or_default(),
+        reference: row.6,
+        description: row.7,
+        status: row.8.parse().unwrap_or(MatchStatus::Unmatched),
+        transaction_id: row.9,
+        match_method: row.10.and_then(|method| method.parse().ok()),
+        candidate_ids: row.11,
+        matched_at: row.12,
+    }
+}
+
+impl From<&StatementLineResponse> for ParsedStatementLine {
+    fn from(line: &StatementLineResponse) -> Self {
+        ParsedStatementLine {
+            booking_date: line.booking_date,
+            amount: line.amount,
+            currency: line.currency,
+            reference: line.reference.clone(),
+            description: line.description.clone(),
+        }
+    }
+}
+
+pub struct ReconciliationService;
+
+impl ReconciliationService {
+    /// Stores a parsed statement and runs automatic matching over it.
+    pub async fn import(
+        pool: &PgPool,
+        key_id: Option<&str>,
+        req: &ImportStatementRequest,
+        lines: &[ParsedStatementLine],
+        settings: &MatchSettings,
+    ) -> Result<ReconciliationReport, sqlx::Error> {
+        let import_id = generate_import_id();
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO statement_imports (import_id, key_id, account_id, format, file_name, line_count)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#
+        )
+        .bind(&import_id)
+        .bind(key_id)
+        .bind(&req.account_id)
+        .bind(req.format.to_string())
+        .bind(&req.file_name)
+        .bind(lines.len() as i32)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO statement_lines (
+                line_id, import_id, line_number, booking_date, amount,
+                currency, reference, description, status
+            )
+            SELECT line_id, $2, line_number, booking_date, amount,
+                   currency, reference, description, 'unmatched'
+            FROM UNNEST($1::text[], $3::int[], $4::date[], $5::float8[], $6::text[], $7::text[], $8::text[])
+                AS t(line_id, line_number, booking_date, amount, currency, reference, description)
+            "#
+        )
+        .bind(lines.iter().map(|_| generate_line_id()).collect::<Vec<_>>())
+        .bind(&import_id)
+        .bind((1..=lines.len() as i32).collect::<Vec<_>>())
+        .bind(lines.iter().map(|line| line.booking_date).collect::<Vec<_>>())
+        .bind(lines.iter().map(|line| line.amount).collect::<Vec<_>>())
+        .bind(lines.iter().map(|line| line.currency.to_string()).collect::<Vec<_>>())
+        .bind(lines.iter().map(|line| line.reference.clone()).collect::<Vec<_>>())
+        .bind(lines.iter().map(|line| line.description.clone()).collect::<Vec<_>>())
+        .execute(&mut *tx)
+        .await?;
+
+        Self::auto_match(&mut tx, &import_id, req.account_id.as_deref(), settings).await?;
+        tx.commit().await?;
+
+        Self::report(pool, &import_id, key_id, None)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    /// Re-runs automatic matching for the unmatched and ambiguous lines of an
+    /// import. Returns `false` if the import does not exist.
+    pub async fn rematch(
+        pool: &PgPool,
+        import_id: &str,
+        key_id: Option<&str>,
+        settings: &MatchSettings,
+    ) -> Result<bool, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        let account_id = sqlx::query_scalar::<_, Option<String>>(
+            r#"
+            SELECT account_id FROM statement_imports
+            WHERE import_id = $1 AND ($2::text IS NULL OR key_id = $2)
+            FOR UPDATE
+            "#
+        )
+        .bind(import_id)
+        .bind(key_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(account_id) = account_id else {
+            return Ok(false);
+        };
+
+        Self::auto_match(&mut tx, import_id, account_id.as_deref(), settings).await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    async fn auto_match(
+        conn: &mut sqlx::PgConnection,
+        import_id: &str,
+        account_id: Option<&str>,
+        settings: &MatchSettings,
+    ) -> Result<(), sqlx::Error> {
+        let open: Vec<StatementLineResponse> = sqlx::query_as::<_, LineRow>(&format!(
+            "SELECT {} FROM statement_lines WHERE import_id = $1 AND status <> 'matched' ORDER BY line_number",
+            LINE_COLUMNS
+        ))
+        .bind(import_id)
+        .fetch_all(&mut *conn)
+        .await?
+        .into_iter()
+        .map(line_from_row)
+        .collect();
+
+        let (Some(first), Some(last)) = (
+            open.iter().map(|line| line.booking_date).min(),
+            open.iter().map(|line| line.booking_date).max(),
+        ) else {
+            return Ok(());
+        };
+
+        let tolerance = Duration::days(settings.date_tolerance_days);
+        let from = (first - tolerance).and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+        let until = (last + tolerance + Duration::days(1))
+            .and_hms_opt(0, 0, 0)
+            .unwrap_or_default()
+            .and_utc();
+        let currencies: Vec<String> = open
+            .iter()
+            .map(|line| line.currency.to_string())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+
+        let candidates: Vec<MatchCandidate> = sqlx::query_as::<_, (
+            String,
+            f64,
+            String,
+            DateTime<Utc>,
+            Option<serde_json::Value>,
+        )>(
+            r#"
+            SELECT t.transaction_id, t.amount, t.currency, t.created_at, t.metadata
+            FROM transactions t
+            WHERE t.status = 'completed'
+            AND t.created_at >= $1 AND t.created_at < $2
+            AND t.currency = ANY($3)
+            AND ($4::text IS NULL OR t.account_id = $4)
+            AND NOT EXISTS (
+                SELECT 1 FROM statement_lines l WHERE l.transaction_id = t.transaction_id
+            )
+            ORDER BY t.created_at, t.transaction_id
+            "#
+        )
+        .bind(from)
+        .bind(until)
+        .bind(&currencies)
+        .bind(account_id)
+        .fetch_all(&mut *conn)
+        .await?
+        .into_iter()
+        .map(|row| MatchCandidate {
+            transaction_id: row.0,
+            amount: row.1,
+            currency: row.2.parse().unwrap_or_default(),
+            created_at: row.3,
+            metadata: row.4,
+        })
+        .collect();
+
+        let parsed: Vec<ParsedStatementLine> = open.iter().map(ParsedStatementLine::from).collect();
+        let outcomes = match_lines(&parsed, &candidates, settings);
+
+        for (line, outcome) in open.iter().zip(outcomes) {
+            let (status, transaction_id, method, candidate_ids) = match outcome {
+                MatchOutcome::Matched { transaction_id, method } => {
+                    (MatchStatus::Matched, Some(transaction_id), Some(method), Vec::new())
+                }
+                MatchOutcome::Ambiguous(ids) => (MatchStatus::Ambiguous, None, None, ids),
+                MatchOutcome::Unmatched => (MatchStatus::Unmatched, None, None, Vec::new()),
+            };
+
+            sqlx::query(
+                r#"
+                UPDATE statement_lines
+                SET status = $2, transaction_id = $3, match_method = $4, candidate_ids = $5,
+                    matched_at = CASE WHEN $2 = 'matched' THEN NOW() END
+                WHERE line_id = $1
+                "#
+            )
+            .bind(&line.line_id)
+            .bind(status.to_string())
+            .bind(transaction_id)
+            .bind(method.map(|method| method.to_string()))
+            .bind(candidate_ids)
+            .execute(&mut *conn)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn report(
+        pool: &PgPool,
+        import_id: &str,
+        key_id: Option<&str>,
+        status: Option<MatchStatus>,
+    ) -> Result<Option<ReconciliationReport>, sqlx::Error> {
+        let import = sqlx::query_as::<_, (
+            String,
+            Option<String>,
+            String,
+            Option<String>,
+            i32,
+            DateTime<Utc>,
+        )>(
+            r#"
+            SELECT import_id, account_id, format, file_name, line_count, created_at
+            FROM statement_imports
+            WHERE import_id = $1 AND ($2::text IS NULL OR key_id = $2)
+            "#
+        )
+        .bind(import_id)
+        .bind(key_id)
+        .fetch_optional(pool)
+        .await?;
+
+        let Some(import) = import else {
+            return Ok(None);
+        };
+
+        let lines: Vec<StatementLineResponse> = sqlx::query_as::<_, LineRow>(&format!(
+            "SELECT {} FROM statement_lines WHERE import_id = $1 ORDER BY line_number",
+            LINE_COLUMNS
+        ))
+        .bind(import_id)
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(line_from_row)
+        .collect();
+
+        let summary = ReconciliationSummary::from_lines(&lines);
+        let lines = lines
+            .into_iter()
+            .filter(|line| status.is_none_or(|status| line.status == status))
+            .collect();
+
+        Ok(Some(ReconciliationReport {
+            import: StatementImportResponse {
+                import_id: import.0,
+                account_id: import.1,
+                format: import.2.parse().unwrap_or(StatementFormat::Csv),
+                file_name: import.3,
+                line_count: import.4,
+                created_at: import.5,
+            },
+            summary,
+            lines,
+        }))
+    }
+
+    /// Line with `line_id` and the account of its import, if visible to `key_id`.
+    async fn find_line(
+        conn: &mut sqlx::PgConnection,
+        line_id: &str,
+        key_id: Option<&str>,
+    ) -> Result<Option<(StatementLineResponse, Option<String>)>, sqlx::Error> {
+        let row = sqlx::query_as::<_, (String, Option<String>)>(
+            r#"
+            SELECT l.line_id, i.account_id
+            FROM statement_lines l
+            JOIN statement_imports i ON i.import_id = l.import_id
+            WHERE l.line_id = $1 AND ($2::text IS NULL OR i.key_id = $2)
+            FOR UPDATE OF l
+            "#
+        )
+        .bind(line_id)
+        .bind(key_id)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        let Some((line_id, account_id)) = row else {
+            return Ok(None);
+        };
+
+        let line = sqlx::query_as::<_, LineRow>(&format!(
+            "SELECT {} FROM statement_lines WHERE line_id = $1",
+            LINE_COLUMNS
+        ))
+        .bind(&line_id)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        Ok(Some((line_from_row(line), account_id)))
+    }
+
+    pub async fn manual_match(
+        pool: &PgPool,
+        line_id: &str,
+        transaction_id: &str,
+        key_id: Option<&str>,
+    ) -> Result<StatementLineResponse, ManualMatchError> {
+        let mut tx = pool.begin().await?;
+
+        let (line, import_account) = Self::find_line(&mut tx, line_id, key_id)
+            .await?
+            .ok_or(ManualMatchError::LineNotFound)?;
+
+        let transaction_account = sqlx::query_scalar::<_, String>(
+            "SELECT account_id FROM transactions WHERE transaction_id = $1"
+        )
+        .bind(transaction_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(ManualMatchError::TransactionNotFound)?;
+
+        if import_account.is_some_and(|account| account != transaction_account) {
+            return Err(ManualMatchError::AccountMismatch);
+        }
+
+        let taken_by = sqlx::query_scalar::<_, String>(
+            "SELECT line_id FROM statement_lines WHERE transaction_id = $1 AND line_id <> $2"
+        )
+        .bind(transaction_id)
+        .bind(&line.line_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        if let Some(other) = taken_by {
+            return Err(ManualMatchError::TransactionTaken(other));
+        }
+
+        let row = sqlx::query_as::<_, LineRow>(&format!(
+            r#"
+            UPDATE statement_lines
+            SET status = 'matched', transaction_id = $2, match_method = 'manual',
+                candidate_ids = '{{}}', matched_at = NOW()
+            WHERE line_id = $1
+            RETURNING {}
+            "#,
+            LINE_COLUMNS
+        ))
+        .bind(&line.line_id)
+        .bind(transaction_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(line_from_row(row))
+    }
+
+    pub async fn unmatch(
+        pool: &PgPool,
+        line_id: &str,
+        key_id: Option<&str>,
+    ) -> Result<Option<StatementLineResponse>, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        if Self::find_line(&mut tx, line_id, key_id).await?.is_none() {
+            return Ok(None);
+        }
+
+        let row = sqlx::query_as::<_, LineRow>(&format!(
+            r#"
+            UPDATE statement_lines
+            SET status = 'unmatched', transaction_id = NULL, match_method = NULL,
+                candidate_ids = '{{}}', matched_at = NULL
+            WHERE line_id = $1
+            RETURNING {}
+            "#,
+            LINE_COLUMNS
+        ))
+        .bind(line_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(Some(line_from_row(row)))
+    }
+}
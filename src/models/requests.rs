@@ -3,6 +3,8 @@ use utoipa::ToSchema;
 
 use super::finance::{Currency, TransactionType};
 use super::keys::Scope;
+use super::metadata::{check_metadata, InvalidMetadata, MetadataSchema};
+use super::quota::{BillingCycleSettings, QuotaAlertSettings};
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct CreateAccountRequest {
@@ -31,8 +33,15 @@ impl CreateAccountRequest {
             return Err("Account ID must contain only alphanumeric characters, underscores, and hyphens".to_string());
         }
         
+        self.validate_metadata(None).map_err(|e| e.to_string())?;
+        
         Ok(())
     }
+
+    /// Checks the metadata against the limits and the caller's account schema.
+    pub fn validate_metadata(&self, schema: Option<&MetadataSchema>) -> Result<(), InvalidMetadata> {
+        check_metadata(self.metadata.as_ref(), schema)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -40,6 +49,17 @@ pub struct UpdateAccountRequest {
     pub metadata: serde_json::Value,
 }
 
+impl UpdateAccountRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        self.validate_metadata(None).map_err(|e| e.to_string())
+    }
+
+    /// Checks the metadata against the limits and the caller's account schema.
+    pub fn validate_metadata(&self, schema: Option<&MetadataSchema>) -> Result<(), InvalidMetadata> {
+        check_metadata(Some(&self.metadata), schema)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct CreateTransactionRequest {
     #[schema(example = "user_123")]
@@ -66,6 +86,10 @@ impl CreateTransactionRequest {
             return Err("Account ID cannot be empty".to_string());
         }
         
+        if self.transaction_type == TransactionType::Reversal {
+            return Err("Reversals are created by reversing a transaction".to_string());
+        }
+        
         if self.amount <= 0.0 {
             return Err("Amount must be positive".to_string());
         }
@@ -86,8 +110,16 @@ impl CreateTransactionRequest {
             }
         }
         
+        self.validate_metadata(None).map_err(|e| e.to_string())?;
+        
         Ok(())
     }
+
+    /// Checks the metadata against the limits and the caller's transaction
+    /// schema.
+    pub fn validate_metadata(&self, schema: Option<&MetadataSchema>) -> Result<(), InvalidMetadata> {
+        check_metadata(self.metadata.as_ref(), schema)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -193,4 +225,76 @@ impl UpdateApiKeyRequest {
         
         Ok(())
     }
-}
\ No newline at end of file
+}
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpdateQuotaAlertsRequest {
+    #[serde(default)]
+    #[schema(example = json!([80, 100]))]
+    pub soft_thresholds: Option<Vec<i32>>,
+    
+    #[serde(default)]
+    #[schema(example = 10)]
+    pub grace_overage_percent: Option<i32>,
+}
+
+impl UpdateQuotaAlertsRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.soft_thresholds.is_none() && self.grace_overage_percent.is_none() {
+            return Err("No fields to update".to_string());
+        }
+        
+        Ok(())
+    }
+
+    /// Applies the requested changes on top of the current settings.
+    pub fn apply(&self, current: QuotaAlertSettings) -> Result<QuotaAlertSettings, String> {
+        let settings = QuotaAlertSettings {
+            soft_thresholds: self
+                .soft_thresholds
+                .clone()
+                .unwrap_or(current.soft_thresholds),
+            grace_overage_percent: self
+                .grace_overage_percent
+                .unwrap_or(current.grace_overage_percent),
+        };
+        
+        settings.validate()?;
+        
+        Ok(settings)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpdateBillingCycleRequest {
+    #[serde(default)]
+    #[schema(example = "America/New_York")]
+    pub timezone: Option<String>,
+    
+    #[serde(default)]
+    #[schema(example = 15)]
+    pub billing_anchor_day: Option<i32>,
+}
+
+impl UpdateBillingCycleRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.timezone.is_none() && self.billing_anchor_day.is_none() {
+            return Err("No fields to update".to_string());
+        }
+        
+        Ok(())
+    }
+
+    /// Applies the requested changes on top of the current settings.
+    pub fn apply(&self, current: BillingCycleSettings) -> Result<BillingCycleSettings, String> {
+        let settings = BillingCycleSettings {
+            timezone: self.timezone.clone().unwrap_or(current.timezone),
+            billing_anchor_day: self
+                .billing_anchor_day
+                .unwrap_or(current.billing_anchor_day),
+        };
+        
+        settings.validate()?;
+        
+        Ok(settings)
+    }
+}
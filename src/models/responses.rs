@@ -2,9 +2,12 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
-use super::finance::{Currency, TransactionStatus, TransactionType};
+use super::fees::TransactionFee;
+use super::finance::{Currency, FailureReason, TransactionStatus, TransactionType};
 use super::keys::Scope;
-use super::quota::{QuotaLimits, QuotaUsageStats};
+use super::lifecycle::AccountStatus;
+use super::quota::{QuotaAlertStatus, QuotaLimits, QuotaUsageStats};
+use super::reversals::TransactionLinks;
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct AccountResponse {
@@ -15,6 +18,14 @@ pub struct AccountResponse {
     pub created_at: DateTime<Utc>,
     #[schema(value_type = String, format = DateTime)]
     pub updated_at: DateTime<Utc>,
+    #[serde(default)]
+    pub status: AccountStatus,
+    /// Reason given for the latest status change.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub status_changed_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -33,6 +44,15 @@ pub struct TransactionResponse {
     pub created_at: DateTime<Utc>,
     #[schema(value_type = String, format = DateTime)]
     pub processed_at: Option<DateTime<Utc>>,
+    /// Why a failed transaction was rejected.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure_reason: Option<FailureReason>,
+    /// Fee charged on the transaction by its account's fee schedule.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee: Option<TransactionFee>,
+    /// Reversal and adjustment entries linked to the transaction.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub links: Option<TransactionLinks>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -81,4 +101,5 @@ pub struct UsageResponse {
     pub key_id: String,
     pub limits: QuotaLimits,
     pub usage: QuotaUsageStats,
+    pub alerts: QuotaAlertStatus,
 }
\ No newline at end of file
@@ -0,0 +1,506 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use utoipa::ToSchema;
+
+use crate::{
+    db::PgPool,
+    models::{
+        disputes::DisputeStatus,
+        export::{transaction_from_row, TransactionRow},
+        finance::{generate_transaction_id, TransactionStatus, TransactionType},
+        lifecycle::{unavailable_message, AccountLifecycleService, AccountStatus},
+        responses::TransactionResponse,
+    },
+    observability::metrics::record_transaction_created,
+};
+
+const MAX_REASON_LENGTH: usize = 500;
+const MAX_DESCRIPTION_LENGTH: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionLinkKind {
+    /// An equal and opposite entry undoing the original.
+    Reversal,
+    /// A correcting entry with a reason.
+    Adjustment,
+}
+
+impl std::fmt::Display for TransactionLinkKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TransactionLinkKind::Reversal => write!(f, "reversal"),
+            TransactionLinkKind::Adjustment => write!(f, "adjustment"),
+        }
+    }
+}
+
+impl std::str::FromStr for TransactionLinkKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "reversal" => Ok(TransactionLinkKind::Reversal),
+            "adjustment" => Ok(TransactionLinkKind::Adjustment),
+            _ => Err(format!("Invalid transaction link kind: {}", s)),
+        }
+    }
+}
+
+/// Entry booked against an earlier transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactionLink {
+    /// The reversal or adjustment entry
+    pub transaction_id: String,
+    pub original_transaction_id: String,
+    pub kind: TransactionLinkKind,
+    pub reason: Option<String>,
+}
+
+/// Reversal and adjustment entries linked to a transaction.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct TransactionLinks {
+    /// Transaction this entry reverses
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reverses: Option<String>,
+
+    /// Entry reversing this transaction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reversed_by: Option<String>,
+
+    /// Transaction this entry corrects
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub adjusts: Option<String>,
+
+    /// Entries correcting this transaction, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub adjusted_by: Vec<String>,
+
+    /// Reason given for this reversal or adjustment
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Links of `transaction_id` among `links`, or `None` if it has none.
+pub fn links_for(transaction_id: &str, links: &[TransactionLink]) -> Option<TransactionLinks> {
+    let mut result = TransactionLinks::default();
+    let mut found = false;
+
+    for link in links {
+        if link.transaction_id == transaction_id {
+            match link.kind {
+                TransactionLinkKind::Reversal => result.reverses = Some(link.original_transaction_id.clone()),
+                TransactionLinkKind::Adjustment => result.adjusts = Some(link.original_transaction_id.clone()),
+            }
+            result.reason = link.reason.clone();
+            found = true;
+        } else if link.original_transaction_id == transaction_id {
+            match link.kind {
+                TransactionLinkKind::Reversal => result.reversed_by = Some(link.transaction_id.clone()),
+                TransactionLinkKind::Adjustment => result.adjusted_by.push(link.transaction_id.clone()),
+            }
+            found = true;
+        }
+    }
+
+    found.then_some(result)
+}
+
+fn validate_reason(reason: &str) -> Result<(), String> {
+    if reason.trim().is_empty() {
+        return Err("Reason cannot be empty".to_string());
+    }
+    if reason.chars().count() > MAX_REASON_LENGTH {
+        return Err(format!("Reason must not exceed {} characters", MAX_REASON_LENGTH));
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct ReverseTransactionRequest {
+    #[serde(default)]
+    #[schema(example = "Duplicate charge")]
+    pub reason: Option<String>,
+}
+
+impl ReverseTransactionRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        match &self.reason {
+            Some(reason) => validate_reason(reason),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AdjustTransactionRequest {
+    /// Signed correction: positive credits the account, negative debits it
+    #[schema(example = -10.00)]
+    pub amount: f64,
+
+    #[schema(example = "Customer was overcharged by 10.00")]
+    pub reason: String,
+
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+impl AdjustTransactionRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.amount.is_finite() || self.amount == 0.0 {
+            return Err("Amount must be a non-zero number".to_string());
+        }
+        if (self.amount * 100.0).round() / 100.0 != self.amount {
+            return Err("Amount must have at most 2 decimal places".to_string());
+        }
+
+        validate_reason(&self.reason)?;
+
+        if self
+            .description
+            .as_ref()
+            .is_some_and(|description| description.len() > MAX_DESCRIPTION_LENGTH)
+        {
+            return Err(format!("Description must not exceed {} characters", MAX_DESCRIPTION_LENGTH));
+        }
+
+        Ok(())
+    }
+}
+
+/// Amount of the entry undoing a transaction stored with `amount`. Stored
+/// amounts are signed by their effect on the balance, so it is the negation.
+pub fn reversal_amount(amount: f64) -> f64 {
+    -amount
+}
+
+#[derive(Debug)]
+pub enum ReversalError {
+    NotFound(String),
+    NotCompleted {
+        transaction_id: String,
+        status: TransactionStatus,
+    },
+    /// Reversals and chargebacks are not reversed or adjusted here.
+    NotReversible {
+        transaction_id: String,
+        transaction_type: TransactionType,
+    },
+    AlreadyReversed {
+        transaction_id: String,
+        reversed_by: String,
+    },
+    /// A dispute that was not won has already withdrawn the funds.
+    Disputed {
+        transaction_id: String,
+        dispute_id: String,
+        status: DisputeStatus,
+    },
+    AccountUnavailable {
+        account_id: String,
+        status: AccountStatus,
+    },
+    Database(sqlx::Error),
+}
+
+impl From<sqlx::Error> for ReversalError {
+    fn from(error: sqlx::Error) -> Self {
+        ReversalError::Database(error)
+    }
+}
+
+impl std::fmt::Display for ReversalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReversalError::NotFound(transaction_id) => write!(f, "Transaction '{}' not found", transaction_id),
+            ReversalError::NotCompleted { transaction_id, status } => write!(
+                f,
+                "Transaction '{}' is {}; only completed transactions can be reversed or adjusted",
+                transaction_id, status
+            ),
+            ReversalError::NotReversible { transaction_id, transaction_type } => match transaction_type {
+                TransactionType::Chargeback => write!(
+                    f,
+                    "Transaction '{}' is a chargeback; resolve its dispute instead",
+                    transaction_id
+                ),
+                _ => write!(
+                    f,
+                    "Transaction '{}' is a {} and cannot be reversed or adjusted",
+                    transaction_id, transaction_type
+                ),
+            },
+            ReversalError::AlreadyReversed { transaction_id, reversed_by } => write!(
+                f,
+                "Transaction '{}' was already reversed by '{}'",
+                transaction_id, reversed_by
+            ),
+            ReversalError::Disputed { transaction_id, dispute_id, status } => write!(
+                f,
+                "Transaction '{}' has a dispute '{}' that is {}; it cannot be reversed unless the dispute is won",
+                transaction_id, dispute_id, status
+            ),
+            ReversalError::AccountUnavailable { account_id, status } => {
+                write!(f, "{}", unavailable_message(account_id, *status))
+            }
+            ReversalError::Database(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+/// Whether a reversal or adjustment can be booked against `transaction`,
+/// given the entry that already reversed it, if any.
+pub fn check_correctable(transaction: &TransactionResponse, reversed_by: Option<&str>) -> Result<(), ReversalError> {
+    if transaction.status != TransactionStatus::Completed {
+        return Err(ReversalError::NotCompleted {
+            transaction_id: transaction.transaction_id.clone(),
+            status: transaction.status,
+        });
+    }
+
+    if matches!(
+        transaction.transaction_type,
+        TransactionType::Reversal | TransactionType::Chargeback
+    ) {
+        return Err(ReversalError::NotReversible {
+            transaction_id: transaction.transaction_id.clone(),
+            transaction_type: transaction.transaction_type,
+        });
+    }
+
+    if let Some(reversed_by) = reversed_by {
+        return Err(ReversalError::AlreadyReversed {
+            transaction_id: transaction.transaction_id.clone(),
+            reversed_by: reversed_by.to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Whether a reversal can be booked against a transaction with `disputes`.
+/// The chargeback of a dispute that was not won already took the funds back,
+/// so reversing too would debit the account twice.
+pub fn check_undisputed(transaction_id: &str, disputes: &[(String, DisputeStatus)]) -> Result<(), ReversalError> {
+    match disputes.iter().find(|(_, status)| *status != DisputeStatus::Won) {
+        Some((dispute_id, status)) => Err(ReversalError::Disputed {
+            transaction_id: transaction_id.to_string(),
+            dispute_id: dispute_id.clone(),
+            status: *status,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Entry to book against an original transaction.
+struct Correction<'a> {
+    kind: TransactionLinkKind,
+    transaction_type: TransactionType,
+    amount: f64,
+    description: String,
+    reason: Option<&'a str>,
+}
+
+pub struct ReversalService;
+
+impl ReversalService {
+    /// Books an equal and opposite `reversal` entry for a completed
+    /// transaction. A transaction is reversed at most once.
+    pub async fn reverse(
+        pool: &PgPool,
+        transaction_id: &str,
+        reason: Option<&str>,
+    ) -> Result<TransactionResponse, ReversalError> {
+        Self::book(pool, transaction_id, |original| Correction {
+            kind: TransactionLinkKind::Reversal,
+            transaction_type: TransactionType::Reversal,
+            amount: reversal_amount(original.amount),
+            description: format!("Reversal of {}", original.transaction_id),
+            reason,
+        })
+        .await
+    }
+
+    /// Books a correcting `adjustment` entry of the requested amount against
+    /// a completed transaction that was not reversed.
+    pub async fn adjust(
+        pool: &PgPool,
+        transaction_id: &str,
+        req: &AdjustTransactionRequest,
+    ) -> Result<TransactionResponse, ReversalError> {
+        Self::book(pool, transaction_id, |original| Correction {
+            kind: TransactionLinkKind::Adjustment,
+            transaction_type: TransactionType::Adjustment,
+            amount: req.amount,
+            description: req
+                .description
+                .clone()
+                .unwrap_or_else(|| format!("Adjustment of {}", original.transaction_id)),
+            reason: Some(req.reason.trim()),
+        })
+        .await
+    }
+
+    /// Books the entry `correction` builds against the original transaction,
+    /// which is locked but never changed.
+    async fn book<'a>(
+        pool: &PgPool,
+        transaction_id: &str,
+        correction: impl FnOnce(&TransactionResponse) -> Correction<'a>,
+    ) -> Result<TransactionResponse, ReversalError> {
+        let mut tx = pool.begin().await?;
+
+        let original = sqlx::query_as::<_, TransactionRow>(
+            r#"
+            SELECT
+                transaction_id, account_id, amount, currency,
+                transaction_type, status, description, metadata,
+                created_at, processed_at, failure_reason
+            FROM transactions
+            WHERE transaction_id = $1
+            FOR UPDATE
+            "#
+        )
+        .bind(transaction_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .map(transaction_from_row)
+        .ok_or_else(|| ReversalError::NotFound(transaction_id.to_string()))?;
+
+        let reversed_by = sqlx::query_scalar::<_, String>(
+            "SELECT transaction_id FROM transaction_links WHERE original_transaction_id = $1 AND kind = $2"
+        )
+        .bind(transaction_id)
+        .bind(TransactionLinkKind::Reversal.to_string())
+        .fetch_optional(&mut *tx)
+        .await?;
+        check_correctable(&original, reversed_by.as_deref())?;
+
+        let correction = correction(&original);
+        if correction.kind == TransactionLinkKind::Reversal {
+            let disputes = sqlx::query_as::<_, (String, String)>(
+                "SELECT dispute_id, status FROM disputes WHERE transaction_id = $1 ORDER BY created_at"
+            )
+            .bind(transaction_id)
+            .fetch_all(&mut *tx)
+            .await?
+            .into_iter()
+            .map(|(dispute_id, status)| (dispute_id, status.parse().unwrap_or(DisputeStatus::NeedsResponse)))
+            .collect::<Vec<_>>();
+            check_undisputed(transaction_id, &disputes)?;
+        }
+
+        match AccountLifecycleService::lock_status(&mut tx, &original.account_id).await? {
+            Some(status) if !status.accepts_transactions() => {
+                return Err(ReversalError::AccountUnavailable {
+                    account_id: original.account_id,
+                    status,
+                });
+            }
+            _ => {}
+        }
+
+        let mut metadata = match correction.kind {
+            TransactionLinkKind::Reversal => json!({ "reverses": original.transaction_id }),
+            TransactionLinkKind::Adjustment => json!({ "adjusts": original.transaction_id }),
+        };
+        if let Some(reason) = correction.reason {
+            metadata["reason"] = json!(reason);
+        }
+
+        let row = sqlx::query_as::<_, TransactionRow>(
+            r#"
+            INSERT INTO transactions (
+                transaction_id, account_id, amount, currency,
+                transaction_type, status, description, metadata,
+                created_at, processed_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, NOW(), NOW())
+            RETURNING
+                transaction_id, account_id, amount, currency,
+                transaction_type, status, description, metadata,
+                created_at, processed_at, failure_reason
+            "#
+        )
+        .bind(generate_transaction_id())
+        .bind(&original.account_id)
+        .bind(correction.amount)
+        .bind(original.currency.to_string())
+        .bind(correction.transaction_type.to_string())
+        .bind(TransactionStatus::Completed.to_string())
+        .bind(&correction.description)
+        .bind(metadata)
+        .fetch_one(&mut *tx)
+        .await?;
+        let mut entry = transaction_from_row(row);
+
+        sqlx::query(
+            r#"
+            INSERT INTO transaction_links (transaction_id, original_transaction_id, kind, reason)
+            VALUES ($1, $2, $3, $4)
+            "#
+        )
+        .bind(&entry.transaction_id)
+        .bind(&original.transaction_id)
+        .bind(correction.kind.to_string())
+        .bind(correction.reason)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        record_transaction_created(&entry.transaction_type.to_string(), &entry.currency.to_string());
+
+        entry.links = links_for(
+            &entry.transaction_id,
+            &[TransactionLink {
+                transaction_id: entry.transaction_id.clone(),
+                original_transaction_id: original.transaction_id,
+                kind: correction.kind,
+                reason: correction.reason.map(str::to_string),
+            }],
+        );
+
+        Ok(entry)
+    }
+
+    /// Attaches reversal and adjustment links to transactions loaded
+    /// without them.
+    pub async fn attach(pool: &PgPool, transactions: &mut [TransactionResponse]) -> Result<(), sqlx::Error> {
+        if transactions.is_empty() {
+            return Ok(());
+        }
+
+        let ids: Vec<String> = transactions.iter().map(|t| t.transaction_id.clone()).collect();
+        let rows = sqlx::query_as::<_, (String, String, String, Option<String>)>(
+            r#"
+            SELECT transaction_id, original_transaction_id, kind, reason
+            FROM transaction_links
+            WHERE transaction_id = ANY($1) OR original_transaction_id = ANY($1)
+            ORDER BY created_at, transaction_id
+            "#
+        )
+        .bind(&ids)
+        .fetch_all(pool)
+        .await?;
+
+        let links: Vec<TransactionLink> = rows
+            .into_iter()
+            .filter_map(|row| {
+                Some(TransactionLink {
+                    transaction_id: row.0,
+                    original_transaction_id: row.1,
+                    kind: row.2.parse().ok()?,
+                    reason: row.3,
+                })
+            })
+            .collect();
+
+        for transaction in transactions {
+            transaction.links = links_for(&transaction.transaction_id, &links);
+        }
+
+        Ok(())
+    }
+}
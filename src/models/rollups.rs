@@ -0,0 +1,754 @@
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use utoipa::ToSchema;
+
+use crate::{
+    config::Config,
+    models::analytics::{
+        percentage, EndpointStats, ErrorCodeStats, RequestFilter, RequestStats, StatusCodeStats,
+        TimeBucket, VolumeBucket,
+    },
+    observability::metrics::record_job_outcome,
+};
+
+/// Upper bounds (exclusive, in ms) of the latency histogram buckets stored in
+/// rollups. A final overflow bucket counts everything at or above the last bound.
+pub const LATENCY_BOUNDS_MS: [i64; 13] = [
+    5, 10, 25, 50, 100, 250, 500, 1_000, 2_500, 5_000, 10_000, 30_000, 60_000,
+];
+
+/// Buckets are only rolled up once they are this far in the past, so requests
+/// that are logged slightly late still land in their bucket.
+const ROLLUP_LAG: Duration = Duration::minutes(2);
+
+const MAX_MINUTE_SPAN: Duration = Duration::hours(6);
+const MAX_HOUR_SPAN: Duration = Duration::days(7);
+const PRUNE_BATCH_SIZE: i64 = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RollupGranularity {
+    Minute,
+    Hour,
+}
+
+impl std::fmt::Display for RollupGranularity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RollupGranularity::Minute => write!(f, "minute"),
+            RollupGranularity::Hour => write!(f, "hour"),
+        }
+    }
+}
+
+impl RollupGranularity {
+    pub fn duration(&self) -> Duration {
+        match self {
+            RollupGranularity::Minute => Duration::minutes(1),
+            RollupGranularity::Hour => Duration::hours(1),
+        }
+    }
+
+    /// Start of the bucket containing `ts`.
+    pub fn truncate(&self, ts: DateTime<Utc>) -> DateTime<Utc> {
+        ts.duration_trunc(self.duration()).unwrap_or(ts)
+    }
+}
+
+/// Where an analytics query reads from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AnalyticsSource {
+    /// Raw `requests` rows; exact percentiles.
+    #[default]
+    Raw,
+    /// Per-minute rollups plus raw rows not yet rolled up.
+    Minute,
+    /// Per-hour rollups plus newer minute rollups and raw rows.
+    Hour,
+}
+
+impl AnalyticsSource {
+    /// Picks the coarsest source that still answers the query well: raw rows
+    /// for short ranges, minute rollups up to three days, hour rollups beyond.
+    /// Falls back to rollups when raw rows or minute rollups have been pruned.
+    pub fn for_range(
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        bucket: Option<TimeBucket>,
+        now: DateTime<Utc>,
+        settings: &RollupSettings,
+    ) -> Self {
+        let span = end.signed_duration_since(start);
+
+        let mut source = if span <= Duration::hours(6) {
+            AnalyticsSource::Raw
+        } else if span <= Duration::days(3) || bucket == Some(TimeBucket::Minute) {
+            AnalyticsSource::Minute
+        } else {
+            AnalyticsSource::Hour
+        };
+
+        if source == AnalyticsSource::Raw
+            && start < now - Duration::days(settings.request_retention_days)
+        {
+            source = AnalyticsSource::Minute;
+        }
+
+        if source == AnalyticsSource::Minute
+            && start < now - Duration::days(settings.minute_retention_days)
+        {
+            source = AnalyticsSource::Hour;
+        }
+
+        source
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RollupSettings {
+    pub interval: std::time::Duration,
+    /// Raw `requests` rows older than this are deleted once rolled up.
+    pub request_retention_days: i64,
+    /// Minute rollups older than this are deleted once rolled into hours.
+    pub minute_retention_days: i64,
+}
+
+impl RollupSettings {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            interval: std::time::Duration::from_secs(config.rollup_interval_secs),
+            request_retention_days: config.request_retention_days,
+            minute_retention_days: config.minute_rollup_retention_days,
+        }
+    }
+}
+
+impl Default for RollupSettings {
+    fn default() -> Self {
+        Self {
+            interval: std::time::Duration::from_secs(60),
+            request_retention_days: 30,
+            minute_retention_days: 14,
+        }
+    }
+}
+
+/// Counts per latency bucket, mergeable by element-wise addition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LatencyHistogram(pub Vec<i64>);
+
+impl LatencyHistogram {
+    pub fn total(&self) -> i64 {
+        self.0.iter().sum()
+    }
+
+    /// Estimates the `q` quantile by interpolating linearly inside the bucket
+    /// that contains it. Values in the overflow bucket report its lower bound.
+    pub fn percentile(&self, q: f64) -> Option<f64> {
+        let total = self.total();
+        if total == 0 {
+            return None;
+        }
+
+        let rank = q.clamp(0.0, 1.0) * total as f64;
+        let mut seen = 0i64;
+
+        for (index, &count) in self.0.iter().enumerate() {
+            if count == 0 {
+                continue;
+            }
+
+            let lower = if index == 0 { 0 } else { LATENCY_BOUNDS_MS[index - 1] } as f64;
+            if seen as f64 + count as f64 >= rank {
+                return match LATENCY_BOUNDS_MS.get(index) {
+                    Some(&upper) => {
+                        let fraction = (rank - seen as f64) / count as f64;
+                        Some(lower + fraction * (upper as f64 - lower))
+                    }
+                    None => Some(lower),
+                };
+            }
+            seen += count;
+        }
+
+        LATENCY_BOUNDS_MS.last().map(|&bound| bound as f64)
+    }
+}
+
+/// SQL building a histogram array from raw `latency_ms` values.
+pub fn histogram_sql(column: &str) -> String {
+    let mut buckets = Vec::with_capacity(LATENCY_BOUNDS_MS.len() + 1);
+    let mut lower: Option<i64> = None;
+
+    for bound in LATENCY_BOUNDS_MS {
+        buckets.push(match lower {
+            Some(lower) => format!(
+                "COUNT(*) FILTER (WHERE {c} >= {lower} AND {c} < {bound})",
+                c = column
+            ),
+            None => format!("COUNT(*) FILTER (WHERE {} < {})", column, bound),
+        });
+        lower = Some(bound);
+    }
+    buckets.push(format!(
+        "COUNT(*) FILTER (WHERE {} >= {})",
+        column,
+        LATENCY_BOUNDS_MS[LATENCY_BOUNDS_MS.len() - 1]
+    ));
+
+    format!("ARRAY[{}]::BIGINT[]", buckets.join(", "))
+}
+
+/// SQL merging histogram arrays across grouped rows.
+pub fn histogram_sum_sql(column: &str) -> String {
+    let buckets = (1..=LATENCY_BOUNDS_MS.len() + 1)
+        .map(|i| format!("COALESCE(SUM({}[{}]), 0)", column, i))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    format!("ARRAY[{}]::BIGINT[]", buckets)
+}
+
+/// Next range to roll up, in whole buckets, or `None` when caught up.
+///
+/// Starts at the watermark, or at the earliest unprocessed data on the first
+/// run, and is capped at `max_span` so a single run stays short.
+pub fn rollup_window(
+    granularity: RollupGranularity,
+    watermark: Option<DateTime<Utc>>,
+    earliest: Option<DateTime<Utc>>,
+    until: DateTime<Utc>,
+    max_span: Duration,
+) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    let until = granularity.truncate(until);
+    let from = granularity.truncate(watermark.or(earliest)?);
+
+    if from >= until {
+        return None;
+    }
+
+    Some((from, until.min(from + max_span)))
+}
+
+pub struct RollupService;
+
+impl RollupService {
+    pub async fn get_watermark(
+        pool: &PgPool,
+        granularity: RollupGranularity,
+    ) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+        sqlx::query_scalar::<_, DateTime<Utc>>(
+            "SELECT rolled_up_until FROM request_rollup_state WHERE granularity = $1",
+        )
+        .bind(granularity.to_string())
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Aggregates closed minutes of raw `requests` into minute rollups.
+    /// Returns the number of rollup rows written.
+    pub async fn rollup_minutes(pool: &PgPool, now: DateTime<Utc>) -> Result<u64, sqlx::Error> {
+        let granularity = RollupGranularity::Minute;
+        let watermark = Self::get_watermark(pool, granularity).await?;
+        let earliest = if watermark.is_none() {
+            sqlx::query_scalar::<_, Option<DateTime<Utc>>>("SELECT MIN(ts) FROM requests")
+                .fetch_one(pool)
+                .await?
+        } else {
+            None
+        };
+
+        let until = now - ROLLUP_LAG;
+        let Some((from, to)) = rollup_window(granularity, watermark, earliest, until, MAX_MINUTE_SPAN)
+        else {
+            if watermark.is_none() {
+                Self::set_watermark(pool, granularity, granularity.truncate(until)).await?;
+            }
+            return Ok(0);
+        };
+
+        let query = format!(
+            r#"
+            INSERT INTO request_rollups_minute (
+                bucket_start, key_id, path, method, status, error_code,
+                request_count, latency_sum, latency_histogram
+            )
+            SELECT
+                date_trunc('minute', ts),
+                COALESCE(key_id, ''),
+                path,
+                method,
+                status,
+                COALESCE(error_code, ''),
+                COUNT(*),
+                COALESCE(SUM(latency_ms), 0),
+                {}
+            FROM requests
+            WHERE ts >= $1 AND ts < $2
+            GROUP BY 1, 2, 3, 4, 5, 6
+            ON CONFLICT (bucket_start, key_id, path, method, status, error_code) DO UPDATE SET
+                request_count = EXCLUDED.request_count,
+                latency_sum = EXCLUDED.latency_sum,
+                latency_histogram = EXCLUDED.latency_histogram
+            "#,
+            histogram_sql("latency_ms")
+        );
+
+        let mut tx = pool.begin().await?;
+        let written = sqlx::query(&query)
+            .bind(from)
+            .bind(to)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        Self::set_watermark(&mut *tx, granularity, to).await?;
+        tx.commit().await?;
+
+        Ok(written)
+    }
+
+    /// Merges minute rollups of closed hours into hour rollups. Only hours
+    /// whose minutes have all been rolled up are processed.
+    pub async fn rollup_hours(pool: &PgPool) -> Result<u64, sqlx::Error> {
+        let granularity = RollupGranularity::Hour;
+        let Some(minutes_until) = Self::get_watermark(pool, RollupGranularity::Minute).await? else {
+            return Ok(0);
+        };
+
+        let watermark = Self::get_watermark(pool, granularity).await?;
+        let earliest = if watermark.is_none() {
+            Some(
+                sqlx::query_scalar::<_, Option<DateTime<Utc>>>(
+                    "SELECT MIN(bucket_start) FROM request_rollups_minute",
+                )
+                .fetch_one(pool)
+                .await?
+                .unwrap_or(minutes_until),
+            )
+        } else {
+            None
+        };
+
+        let Some((from, to)) =
+            rollup_window(granularity, watermark, earliest, minutes_until, MAX_HOUR_SPAN)
+        else {
+            return Ok(0);
+        };
+
+        let query = format!(
+            r#"
+            INSERT INTO request_rollups_hour (
+                bucket_start, key_id, path, method, status, error_code,
+                request_count, latency_sum, latency_histogram
+            )
+            SELECT
+                date_trunc('hour', bucket_start),
+                key_id,
+                path,
+                method,
+                status,
+                error_code,
+                SUM(request_count)::BIGINT,
+                SUM(latency_sum)::BIGINT,
+                {}
+            FROM request_rollups_minute
+            WHERE bucket_start >= $1 AND bucket_start < $2
+            GROUP BY 1, 2, 3, 4, 5, 6
+            ON CONFLICT (bucket_start, key_id, path, method, status, error_code) DO UPDATE SET
+                request_count = EXCLUDED.request_count,
+                latency_sum = EXCLUDED.latency_sum,
+                latency_histogram = EXCLUDED.latency_histogram
+            "#,
+            histogram_sum_sql("latency_histogram")
+        );
+
+        let mut tx = pool.begin().await?;
+        let written = sqlx::query(&query)
+            .bind(from)
+            .bind(to)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        Self::set_watermark(&mut *tx, granularity, to).await?;
+        tx.commit().await?;
+
+        Ok(written)
+    }
+
+    /// Deletes raw requests and minute rollups past their retention, but never
+    /// anything that has not been rolled up to the next level yet.
+    pub async fn prune(
+        pool: &PgPool,
+        now: DateTime<Utc>,
+        settings: &RollupSettings,
+    ) -> Result<u64, sqlx::Error> {
+        let mut deleted = 0;
+
+        if let Some(minutes_until) = Self::get_watermark(pool, RollupGranularity::Minute).await? {
+            let cutoff = minutes_until.min(now - Duration::days(settings.request_retention_days));
+            loop {
+                let batch = sqlx::query(
+                    r#"
+                    DELETE FROM requests
+                    WHERE id IN (
+                        SELECT id FROM requests WHERE ts < $1 LIMIT $2
+                    )
+                    "#,
+                )
+                .bind(cutoff)
+                .bind(PRUNE_BATCH_SIZE)
+                .execute(pool)
+                .await?
+                .rows_affected();
+
+                deleted += batch;
+                if batch < PRUNE_BATCH_SIZE as u64 {
+                    break;
+                }
+            }
+        }
+
+        if let Some(hours_until) = Self::get_watermark(pool, RollupGranularity::Hour).await? {
+            let cutoff = hours_until.min(now - Duration::days(settings.minute_retention_days));
+            deleted += sqlx::query("DELETE FROM request_rollups_minute WHERE bucket_start < $1")
+                .bind(cutoff)
+                .execute(pool)
+                .await?
+                .rows_affected();
+        }
+
+        Ok(deleted)
+    }
+
+    async fn set_watermark<'e, E>(
+        executor: E,
+        granularity: RollupGranularity,
+        until: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error>
+    where
+        E: sqlx::Executor<'e, Database = sqlx::Postgres>,
+    {
+        sqlx::query(
+            r#"
+            INSERT INTO request_rollup_state (granularity, rolled_up_until, updated_at)
+            VALUES ($1, $2, NOW())
+            ON CONFLICT (granularity) DO UPDATE SET
+                rolled_up_until = EXCLUDED.rolled_up_until,
+                updated_at = NOW()
+            "#,
+        )
+        .bind(granularity.to_string())
+        .bind(until)
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Runs one rollup pass: minutes, then hours, then retention.
+    pub async fn run_once(pool: &PgPool, settings: &RollupSettings) -> Result<(), sqlx::Error> {
+        let now = Utc::now();
+
+        let minutes = Self::rollup_minutes(pool, now).await?;
+        let hours = Self::rollup_hours(pool).await?;
+        let pruned = Self::prune(pool, now, settings).await?;
+
+        tracing::debug!(minutes, hours, pruned, "Request rollup pass finished");
+
+        Ok(())
+    }
+}
+
+/// Runs the rollup job on `settings.interval` for the life of the process.
+pub fn spawn_rollup_worker(pool: PgPool, settings: RollupSettings) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(settings.interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            match RollupService::run_once(&pool, &settings).await {
+                Ok(()) => record_job_outcome("request_rollup", "success"),
+                Err(e) => {
+                    tracing::error!("Request rollup failed: {}", e);
+                    record_job_outcome("request_rollup", "failure");
+                }
+            }
+        }
+    });
+}
+
+/// `WITH` clause exposing a `src` relation with the columns of `requests`
+/// that analytics filters use (`ts`, `key_id`, `path`, `method`, `status`)
+/// plus pre-aggregated `request_count`, `latency_sum` and `latency_histogram`.
+fn source_cte(source: AnalyticsSource) -> String {
+    let columns = "r.bucket_start AS ts, NULLIF(r.key_id, '') AS key_id, r.path, r.method, r.status, \
+                   NULLIF(r.error_code, '') AS error_code, r.request_count, r.latency_sum, r.latency_histogram";
+
+    let mut parts = Vec::new();
+    if source == AnalyticsSource::Hour {
+        parts.push(format!(
+            "SELECT {} FROM request_rollups_hour r, wm WHERE r.bucket_start < wm.hour_until",
+            columns
+        ));
+        parts.push(format!(
+            "SELECT {} FROM request_rollups_minute r, wm \
+             WHERE r.bucket_start >= wm.hour_until AND r.bucket_start < wm.minute_until",
+            columns
+        ));
+    } else {
+        parts.push(format!(
+            "SELECT {} FROM request_rollups_minute r, wm WHERE r.bucket_start < wm.minute_until",
+            columns
+        ));
+    }
+    parts.push(format!(
+        r#"SELECT date_trunc('minute', q.ts) AS ts, q.key_id, q.path, q.method, q.status, q.error_code,
+                COUNT(*)::BIGINT AS request_count, COALESCE(SUM(q.latency_ms), 0)::BIGINT AS latency_sum,
+                {} AS latency_histogram
+            FROM requests q, wm
+            WHERE q.ts >= wm.minute_until
+            GROUP BY 1, 2, 3, 4, 5, 6"#,
+        histogram_sql("q.latency_ms")
+    ));
+
+    format!(
+        r#"
+        WITH wm AS (
+            SELECT
+                COALESCE(MAX(rolled_up_until) FILTER (WHERE granularity = 'minute'), '-infinity'::timestamptz) AS minute_until,
+                COALESCE(MAX(rolled_up_until) FILTER (WHERE granularity = 'hour'), '-infinity'::timestamptz) AS hour_until
+            FROM request_rollup_state
+        ),
+        src AS (
+            {}
+        )
+        "#,
+        parts.join("\n            UNION ALL\n            ")
+    )
+}
+
+/// Analytics read from rollups. Percentiles are estimated from the merged
+/// latency histograms; time filters apply to bucket starts.
+pub struct RollupAnalytics;
+
+impl RollupAnalytics {
+    pub async fn get_request_stats(
+        pool: &PgPool,
+        filter: &RequestFilter,
+    ) -> Result<RequestStats, sqlx::Error> {
+        let (where_clause, bind_values) = filter.where_clause();
+        let query = format!(
+            r#"
+            {}
+            SELECT
+                COALESCE(SUM(request_count), 0)::BIGINT,
+                COALESCE(SUM(request_count) FILTER (WHERE status >= 200 AND status < 300), 0)::BIGINT,
+                COALESCE(SUM(request_count) FILTER (WHERE status >= 400), 0)::BIGINT,
+                SUM(latency_sum)::FLOAT8 / NULLIF(SUM(request_count), 0)::FLOAT8,
+                {}
+            FROM src
+            {}
+            "#,
+            source_cte(filter.source),
+            histogram_sum_sql("latency_histogram"),
+            where_clause
+        );
+
+        let mut sql_query = sqlx::query_as::<_, (i64, i64, i64, Option<f64>, Vec<i64>)>(&query);
+        for value in &bind_values {
+            sql_query = sql_query.bind(value);
+        }
+        let stats = sql_query.fetch_one(pool).await?;
+        let histogram = LatencyHistogram(stats.4);
+
+        Ok(RequestStats {
+            total_requests: stats.0,
+            successful_requests: stats.1,
+            failed_requests: stats.2,
+            avg_latency_ms: stats.3.unwrap_or(0.0),
+            median_latency_ms: histogram.percentile(0.5),
+            p95_latency_ms: histogram.percentile(0.95),
+            p99_latency_ms: histogram.percentile(0.99),
+            period_start: filter.start,
+            period_end: filter.end,
+        })
+    }
+
+    pub async fn get_endpoint_stats(
+        pool: &PgPool,
+        filter: &RequestFilter,
+        limit: i64,
+    ) -> Result<Vec<EndpointStats>, sqlx::Error> {
+        let (where_clause, mut bind_values) = filter.where_clause();
+        let query = format!(
+            r#"
+            {}
+            SELECT
+                path,
+                method,
+                SUM(request_count)::BIGINT AS request_count,
+                SUM(latency_sum)::FLOAT8 / NULLIF(SUM(request_count), 0)::FLOAT8,
+                {},
+                COALESCE(SUM(request_count) FILTER (WHERE status >= 400), 0)::BIGINT
+            FROM src
+            {}
+            GROUP BY path, method
+            ORDER BY request_count DESC
+            LIMIT ${}::bigint
+            "#,
+            source_cte(filter.source),
+            histogram_sum_sql("latency_histogram"),
+            where_clause,
+            bind_values.len() + 1
+        );
+        bind_values.push(limit.to_string());
+
+        let mut sql_query =
+            sqlx::query_as::<_, (String, String, i64, Option<f64>, Vec<i64>, i64)>(&query);
+        for value in &bind_values {
+            sql_query = sql_query.bind(value);
+        }
+        let stats = sql_query.fetch_all(pool).await?;
+
+        Ok(stats
+            .into_iter()
+            .map(|(path, method, count, avg_latency, histogram, error_count)| {
+                let histogram = LatencyHistogram(histogram);
+                EndpointStats {
+                    path,
+                    method,
+                    request_count: count,
+                    avg_latency_ms: avg_latency.unwrap_or(0.0),
+                    median_latency_ms: histogram.percentile(0.5),
+                    p95_latency_ms: histogram.percentile(0.95),
+                    p99_latency_ms: histogram.percentile(0.99),
+                    error_rate: percentage(error_count, count),
+                }
+            })
+            .collect())
+    }
+
+    pub async fn get_status_code_stats(
+        pool: &PgPool,
+        filter: &RequestFilter,
+    ) -> Result<Vec<StatusCodeStats>, sqlx::Error> {
+        let (where_clause, bind_values) = filter.where_clause();
+        let query = format!(
+            r#"
+            {}
+            SELECT
+                status,
+                SUM(request_count)::BIGINT AS count,
+                SUM(SUM(request_count)) OVER ()::BIGINT
+            FROM src
+            {}
+            GROUP BY status
+            ORDER BY count DESC
+            "#,
+            source_cte(filter.source),
+            where_clause
+        );
+
+        let mut sql_query = sqlx::query_as::<_, (i32, i64, i64)>(&query);
+        for value in &bind_values {
+            sql_query = sql_query.bind(value);
+        }
+        let stats = sql_query.fetch_all(pool).await?;
+
+        Ok(stats
+            .into_iter()
+            .map(|(status, count, total)| StatusCodeStats {
+                status_code: status,
+                count,
+                percentage: percentage(count, total),
+            })
+            .collect())
+    }
+
+    pub async fn get_error_breakdown(
+        pool: &PgPool,
+        filter: &RequestFilter,
+    ) -> Result<Vec<ErrorCodeStats>, sqlx::Error> {
+        let (where_clause, bind_values) = filter.where_clause();
+        let query = format!(
+            r#"
+            {}
+            SELECT
+                COALESCE(error_code, 'unknown'),
+                SUM(request_count)::BIGINT AS count,
+                SUM(SUM(request_count)) OVER ()::BIGINT
+            FROM src
+            {} AND status >= 400
+            GROUP BY 1
+            ORDER BY count DESC
+            "#,
+            source_cte(filter.source),
+            where_clause
+        );
+
+        let mut sql_query = sqlx::query_as::<_, (String, i64, i64)>(&query);
+        for value in &bind_values {
+            sql_query = sql_query.bind(value);
+        }
+        let stats = sql_query.fetch_all(pool).await?;
+
+        Ok(stats
+            .into_iter()
+            .map(|(error_code, count, total)| ErrorCodeStats {
+                error_code,
+                count,
+                percentage: percentage(count, total),
+            })
+            .collect())
+    }
+
+    pub async fn get_volume(
+        pool: &PgPool,
+        filter: &RequestFilter,
+        bucket: TimeBucket,
+    ) -> Result<Vec<VolumeBucket>, sqlx::Error> {
+        let (where_clause, bind_values) = filter.where_clause();
+        let query = format!(
+            r#"
+            {}
+            SELECT
+                date_trunc('{}', ts) AS bucket_start,
+                SUM(request_count)::BIGINT,
+                COALESCE(SUM(request_count) FILTER (WHERE status >= 400), 0)::BIGINT,
+                SUM(latency_sum)::FLOAT8 / NULLIF(SUM(request_count), 0)::FLOAT8,
+                {}
+            FROM src
+            {}
+            GROUP BY bucket_start
+            ORDER BY bucket_start ASC
+            "#,
+            source_cte(filter.source),
+            bucket,
+            histogram_sum_sql("latency_histogram"),
+            where_clause
+        );
+
+        let mut sql_query =
+            sqlx::query_as::<_, (DateTime<Utc>, i64, i64, Option<f64>, Vec<i64>)>(&query);
+        for value in &bind_values {
+            sql_query = sql_query.bind(value);
+        }
+        let stats = sql_query.fetch_all(pool).await?;
+
+        Ok(stats
+            .into_iter()
+            .map(|(bucket_start, request_count, error_count, avg_latency, histogram)| {
+                VolumeBucket {
+                    bucket_start,
+                    request_count,
+                    error_count,
+                    avg_latency_ms: avg_latency.unwrap_or(0.0),
+                    p95_latency_ms: LatencyHistogram(histogram).percentile(0.95),
+                }
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,1094 @@
+use chrono::{DateTime, Datelike, Duration, Months, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+use std::sync::{Arc, Mutex};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    config::Config,
+    db::PgPool,
+    models::{
+        common::ErrorCode,
+        fraud::FraudScreener,
+        lifecycle::AccountStatus,
+        posting::{PostingError, TransactionService},
+        quota::anchor_in_month,
+        requests::CreateTransactionRequest,
+    },
+    observability::metrics::{record_job_outcome, record_transaction_created},
+};
+
+const MAX_WEEKLY_INTERVAL: u32 = 52;
+const MAX_MONTHLY_INTERVAL: u32 = 12;
+const MAX_RETRIES: u32 = 10;
+const MIN_BACKOFF_SECS: i64 = 60;
+
+/// Longest wait between two retries, however many came before.
+const MAX_BACKOFF: Duration = Duration::days(7);
+
+const DEFAULT_LIST_LIMIT: i64 = 100;
+const MAX_LIST_LIMIT: i64 = 500;
+
+/// Source of the current time for the scheduler, replaceable in tests.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock that only moves when told to.
+#[derive(Debug)]
+pub struct ManualClock(Mutex<DateTime<Utc>>);
+
+impl ManualClock {
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self(Mutex::new(now))
+    }
+
+    pub fn set(&self, now: DateTime<Utc>) {
+        *self.0.lock().unwrap_or_else(|e| e.into_inner()) = now;
+    }
+
+    pub fn advance(&self, by: Duration) {
+        let mut now = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        *now += by;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ScheduleSettings {
+    /// How often the worker looks for due schedules.
+    pub poll_interval: std::time::Duration,
+    /// Schedules run per poll.
+    pub batch_size: i64,
+    /// Retry policy of schedules created without one.
+    pub default_retry: RetryPolicy,
+}
+
+impl ScheduleSettings {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            poll_interval: std::time::Duration::from_secs(config.schedule_poll_interval_secs.max(1)),
+            batch_size: config.schedule_batch_size,
+            default_retry: RetryPolicy {
+                max_retries: config.schedule_max_retries,
+                backoff_secs: config.schedule_retry_backoff_secs,
+            },
+        }
+    }
+}
+
+impl Default for ScheduleSettings {
+    fn default() -> Self {
+        Self {
+            poll_interval: std::time::Duration::from_secs(60),
+            batch_size: 100,
+            default_retry: RetryPolicy::default(),
+        }
+    }
+}
+
+fn default_interval() -> u32 {
+    1
+}
+
+/// When a schedule's occurrences fall. Times of day come from `start_at`,
+/// in UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Recurrence {
+    /// A single occurrence at `start_at`.
+    Once,
+    /// Every `interval` weeks, starting at `start_at`.
+    Weekly {
+        #[serde(default = "default_interval")]
+        interval: u32,
+    },
+    /// On `day` of every `interval` months, from the first such day at or
+    /// after `start_at`. Months shorter than `day` use their last day.
+    Monthly {
+        day: u32,
+        #[serde(default = "default_interval")]
+        interval: u32,
+    },
+}
+
+impl Recurrence {
+    pub fn validate(&self) -> Result<(), String> {
+        match *self {
+            Recurrence::Once => Ok(()),
+            Recurrence::Weekly { interval } => {
+                if !(1..=MAX_WEEKLY_INTERVAL).contains(&interval) {
+                    return Err(format!("Weekly interval must be between 1 and {}", MAX_WEEKLY_INTERVAL));
+                }
+                Ok(())
+            }
+            Recurrence::Monthly { day, interval } => {
+                if !(1..=31).contains(&day) {
+                    return Err("Monthly day must be between 1 and 31".to_string());
+                }
+                if !(1..=MAX_MONTHLY_INTERVAL).contains(&interval) {
+                    return Err(format!("Monthly interval must be between 1 and {}", MAX_MONTHLY_INTERVAL));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    pub fn first_occurrence(&self, start_at: DateTime<Utc>) -> DateTime<Utc> {
+        match *self {
+            Recurrence::Once | Recurrence::Weekly { .. } => start_at,
+            Recurrence::Monthly { day, .. } => {
+                let this_month = on_day(start_at, start_at, day, 0);
+                if this_month >= start_at {
+                    this_month
+                } else {
+                    on_day(start_at, start_at, day, 1)
+                }
+            }
+        }
+    }
+
+    /// The occurrence after `previous`, or `None` once there are no more.
+    pub fn next_occurrence(&self, start_at: DateTime<Utc>, previous: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match *self {
+            Recurrence::Once => None,
+            Recurrence::Weekly { interval } => Some(previous + Duration::weeks(interval as i64)),
+            Recurrence::Monthly { day, interval } => Some(on_day(previous, start_at, day, interval)),
+        }
+    }
+}
+
+/// `day` of the month `months` after the month of `base`, at the time of day
+/// of `start_at`.
+fn on_day(base: DateTime<Utc>, start_at: DateTime<Utc>, day: u32, months: u32) -> DateTime<Utc> {
+    let first = base
+        .date_naive()
+        .with_day(1)
+        .and_then(|first| first.checked_add_months(Months::new(months)))
+        .unwrap_or(base.date_naive());
+
+    anchor_in_month(first.year(), first.month(), day)
+        .and_time(start_at.time())
+        .and_utc()
+}
+
+/// How failed occurrences are retried. The `n`th retry waits
+/// `backoff_secs * 2^(n-1)`, at most a week, and is dropped if it would run
+/// after the next occurrence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct RetryPolicy {
+    #[schema(example = 3)]
+    pub max_retries: u32,
+
+    #[schema(example = 3600)]
+    pub backoff_secs: i64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            backoff_secs: 3600,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_retries > MAX_RETRIES {
+            return Err(format!("max_retries must not exceed {}", MAX_RETRIES));
+        }
+        if self.backoff_secs < MIN_BACKOFF_SECS {
+            return Err(format!("backoff_secs must be at least {}", MIN_BACKOFF_SECS));
+        }
+
+        Ok(())
+    }
+
+    /// Wait before retry number `retry` (from 1), or `None` past the last.
+    pub fn delay(&self, retry: u32) -> Option<Duration> {
+        if retry == 0 || retry > self.max_retries {
+            return None;
+        }
+
+        let factor = 1i64.checked_shl(retry - 1).unwrap_or(i64::MAX);
+        let secs = self.backoff_secs.saturating_mul(factor);
+
+        Some(Duration::seconds(secs).min(MAX_BACKOFF))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduleStatus {
+    Active,
+    Paused,
+    Cancelled,
+    /// Every occurrence has run.
+    Completed,
+    /// Stopped because the account no longer exists or was closed.
+    Failed,
+}
+
+impl std::fmt::Display for ScheduleStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScheduleStatus::Active => write!(f, "active"),
+            ScheduleStatus::Paused => write!(f, "paused"),
+            ScheduleStatus::Cancelled => write!(f, "cancelled"),
+            ScheduleStatus::Completed => write!(f, "completed"),
+            ScheduleStatus::Failed => write!(f, "failed"),
+        }
+    }
+}
+
+impl std::str::FromStr for ScheduleStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "active" => Ok(ScheduleStatus::Active),
+            "paused" => Ok(ScheduleStatus::Paused),
+            "cancelled" => Ok(ScheduleStatus::Cancelled),
+            "completed" => Ok(ScheduleStatus::Completed),
+            "failed" => Ok(ScheduleStatus::Failed),
+            _ => Err(format!("Invalid schedule status: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduleAction {
+    Pause,
+    Resume,
+    Cancel,
+}
+
+impl std::fmt::Display for ScheduleAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScheduleAction::Pause => write!(f, "pause"),
+            ScheduleAction::Resume => write!(f, "resume"),
+            ScheduleAction::Cancel => write!(f, "cancel"),
+        }
+    }
+}
+
+impl ScheduleStatus {
+    pub fn apply(self, action: ScheduleAction) -> Result<ScheduleStatus, ScheduleError> {
+        match (self, action) {
+            (ScheduleStatus::Active, ScheduleAction::Pause) => Ok(ScheduleStatus::Paused),
+            (ScheduleStatus::Paused, ScheduleAction::Resume) => Ok(ScheduleStatus::Active),
+            (ScheduleStatus::Active | ScheduleStatus::Paused, ScheduleAction::Cancel) => {
+                Ok(ScheduleStatus::Cancelled)
+            }
+            (from, action) => Err(ScheduleError::InvalidTransition { from, action }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduleRunStatus {
+    /// The transaction was created (it may still be held for fraud review).
+    Created,
+    /// The attempt failed and the occurrence will be retried.
+    Retrying,
+    /// The attempt failed and the occurrence was given up.
+    Failed,
+}
+
+impl std::fmt::Display for ScheduleRunStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScheduleRunStatus::Created => write!(f, "created"),
+            ScheduleRunStatus::Retrying => write!(f, "retrying"),
+            ScheduleRunStatus::Failed => write!(f, "failed"),
+        }
+    }
+}
+
+impl std::str::FromStr for ScheduleRunStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "created" => Ok(ScheduleRunStatus::Created),
+            "retrying" => Ok(ScheduleRunStatus::Retrying),
+            "failed" => Ok(ScheduleRunStatus::Failed),
+            _ => Err(format!("Invalid schedule run status: {}", s)),
+        }
+    }
+}
+
+/// Result of one attempt at an occurrence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Attempt {
+    Created,
+    /// Failed in a way that may pass later, such as a limit or a frozen account.
+    Retryable,
+    /// Failed and retrying would not help; the next occurrence still runs.
+    Skipped,
+    /// Failed and no occurrence can ever succeed.
+    Fatal,
+}
+
+impl Attempt {
+    pub fn from_error(error: &PostingError) -> Self {
+        match error {
+            PostingError::LimitExceeded { .. } | PostingError::Database(_) => Attempt::Retryable,
+            PostingError::AccountUnavailable { status, .. } if *status != AccountStatus::Closed => {
+                Attempt::Retryable
+            }
+            PostingError::Blocked { .. } => Attempt::Skipped,
+            PostingError::AccountUnavailable { .. } | PostingError::AccountNotFound(_) => Attempt::Fatal,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateScheduleRequest {
+    /// Transaction created at every occurrence
+    pub transaction: CreateTransactionRequest,
+
+    pub recurrence: Recurrence,
+
+    /// Start of the schedule; defaults to now
+    #[serde(default)]
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub start_at: Option<DateTime<Utc>>,
+
+    /// No occurrences after this instant
+    #[serde(default)]
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub end_at: Option<DateTime<Utc>>,
+
+    /// Stop after this many occurrences
+    #[serde(default)]
+    pub max_occurrences: Option<i32>,
+
+    /// Defaults to `SCHEDULE_MAX_RETRIES` retries backing off from `SCHEDULE_RETRY_BACKOFF_SECS`
+    #[serde(default)]
+    pub retry: Option<RetryPolicy>,
+}
+
+impl CreateScheduleRequest {
+    pub fn validate(&self, now: DateTime<Utc>) -> Result<(), String> {
+        self.transaction.validate()?;
+        self.recurrence.validate()?;
+
+        if let Some(retry) = &self.retry {
+            retry.validate()?;
+        }
+
+        let start_at = self.start_at.unwrap_or(now);
+        if start_at < now {
+            return Err("start_at must not be in the past".to_string());
+        }
+
+        if self
+            .end_at
+            .is_some_and(|end_at| end_at < self.recurrence.first_occurrence(start_at))
+        {
+            return Err("end_at must not be before the first occurrence".to_string());
+        }
+
+        if self.max_occurrences.is_some_and(|max| max < 1) {
+            return Err("max_occurrences must be at least 1".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TransactionScheduleResponse {
+    pub schedule_id: String,
+    pub transaction: CreateTransactionRequest,
+    pub recurrence: Recurrence,
+    #[schema(value_type = String, format = DateTime)]
+    pub start_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub end_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_occurrences: Option<i32>,
+    pub retry: RetryPolicy,
+    pub status: ScheduleStatus,
+    /// Occurrences run so far, successful or not
+    pub occurrences: i32,
+    /// Failed attempts at the current occurrence
+    pub attempts: i32,
+    /// When the current occurrence is due
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub next_occurrence_at: Option<DateTime<Utc>>,
+    /// When it is next attempted; later than `next_occurrence_at` while retrying
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub next_run_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    #[schema(value_type = String, format = DateTime)]
+    pub created_at: DateTime<Utc>,
+    #[schema(value_type = String, format = DateTime)]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Schedule state after an attempt.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduleStep {
+    pub run_status: ScheduleRunStatus,
+    pub status: ScheduleStatus,
+    pub occurrences: i32,
+    pub attempts: i32,
+    pub next_occurrence_at: Option<DateTime<Utc>>,
+    pub next_run_at: Option<DateTime<Utc>>,
+}
+
+impl TransactionScheduleResponse {
+    /// The transaction to create for the current occurrence, tagged with the
+    /// schedule in its metadata.
+    pub fn scheduled_request(&self) -> CreateTransactionRequest {
+        let mut req = self.transaction.clone();
+        let mut metadata = match req.metadata.take() {
+            Some(serde_json::Value::Object(map)) => map,
+            _ => serde_json::Map::new(),
+        };
+        metadata.insert("schedule_id".to_string(), self.schedule_id.clone().into());
+        req.metadata = Some(serde_json::Value::Object(metadata));
+        req
+    }
+
+    /// Where the schedule goes after `attempt` at its current occurrence.
+    pub fn step(&self, attempt: Attempt, now: DateTime<Utc>) -> ScheduleStep {
+        match attempt {
+            Attempt::Created => self.advance(ScheduleRunStatus::Created),
+            Attempt::Skipped => self.advance(ScheduleRunStatus::Failed),
+            Attempt::Fatal => ScheduleStep {
+                run_status: ScheduleRunStatus::Failed,
+                status: ScheduleStatus::Failed,
+                occurrences: self.occurrences + 1,
+                attempts: 0,
+                next_occurrence_at: None,
+                next_run_at: None,
+            },
+            Attempt::Retryable => {
+                let attempts = self.attempts + 1;
+                let retry_at = self.retry.delay(attempts as u32).map(|delay| now + delay);
+                let next = self.following_occurrence();
+
+                match retry_at {
+                    Some(retry_at) if next.is_none_or(|next| retry_at < next) => ScheduleStep {
+                        run_status: ScheduleRunStatus::Retrying,
+                        status: ScheduleStatus::Active,
+                        occurrences: self.occurrences,
+                        attempts,
+                        next_occurrence_at: self.next_occurrence_at,
+                        next_run_at: Some(retry_at),
+                    },
+                    _ => self.advance(ScheduleRunStatus::Failed),
+                }
+            }
+        }
+    }
+
+    /// The occurrence after the current one, if the schedule has one.
+    fn following_occurrence(&self) -> Option<DateTime<Utc>> {
+        if self.max_occurrences.is_some_and(|max| self.occurrences + 1 >= max) {
+            return None;
+        }
+
+        self.next_occurrence_at
+            .and_then(|current| self.recurrence.next_occurrence(self.start_at, current))
+            .filter(|next| self.end_at.is_none_or(|end_at| *next <= end_at))
+    }
+
+    fn advance(&self, run_status: ScheduleRunStatus) -> ScheduleStep {
+        let next = self.following_occurrence();
+
+        ScheduleStep {
+            run_status,
+            status: if next.is_some() {
+                ScheduleStatus::Active
+            } else {
+                ScheduleStatus::Completed
+            },
+            occurrences: self.occurrences + 1,
+            attempts: 0,
+            next_occurrence_at: next,
+            next_run_at: next,
+        }
+    }
+
+    /// First occurrence at or after `now` when resuming; occurrences missed
+    /// while paused are skipped. `None` if the schedule has none left.
+    pub fn resume_occurrence(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut next = self.next_occurrence_at?;
+        while next < now {
+            next = self.recurrence.next_occurrence(self.start_at, next)?;
+        }
+
+        self.end_at.is_none_or(|end_at| next <= end_at).then_some(next)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ScheduleRunResponse {
+    pub run_id: String,
+    pub schedule_id: String,
+    #[schema(value_type = String, format = DateTime)]
+    pub occurrence_at: DateTime<Utc>,
+    /// 1 for the first attempt at the occurrence
+    pub attempt: i32,
+    pub status: ScheduleRunStatus,
+    /// Transaction created, or recorded as failed by limits or fraud screening
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_message: Option<String>,
+    #[schema(value_type = String, format = DateTime)]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams, ToSchema)]
+#[into_params(parameter_in = Query)]
+pub struct ScheduleListParams {
+    #[serde(default)]
+    pub account_id: Option<String>,
+
+    #[serde(default)]
+    pub status: Option<ScheduleStatus>,
+
+    /// Maximum number of items to return (default 100, at most 500)
+    #[serde(default)]
+    pub limit: Option<i64>,
+}
+
+impl ScheduleListParams {
+    pub fn limit(&self) -> Result<i64, String> {
+        list_limit(self.limit)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams, ToSchema)]
+#[into_params(parameter_in = Query)]
+pub struct ScheduleRunParams {
+    /// Maximum number of runs to return (default 100, at most 500)
+    #[serde(default)]
+    pub limit: Option<i64>,
+}
+
+impl ScheduleRunParams {
+    pub fn limit(&self) -> Result<i64, String> {
+        list_limit(self.limit)
+    }
+}
+
+fn list_limit(limit: Option<i64>) -> Result<i64, String> {
+    match limit {
+        None => Ok(DEFAULT_LIST_LIMIT),
+        Some(limit) if (1..=MAX_LIST_LIMIT).contains(&limit) => Ok(limit),
+        Some(_) => Err(format!("limit must be between 1 and {}", MAX_LIST_LIMIT)),
+    }
+}
+
+#[derive(Debug)]
+pub enum ScheduleError {
+    NotFound(String),
+    InvalidTransition {
+        from: ScheduleStatus,
+        action: ScheduleAction,
+    },
+    Database(sqlx::Error),
+}
+
+impl From<sqlx::Error> for ScheduleError {
+    fn from(error: sqlx::Error) -> Self {
+        ScheduleError::Database(error)
+    }
+}
+
+impl std::fmt::Display for ScheduleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScheduleError::NotFound(schedule_id) => write!(f, "Schedule '{}' not found", schedule_id),
+            ScheduleError::InvalidTransition { from, action } => {
+                write!(f, "Cannot {} a schedule that is {}", action, from)
+            }
+            ScheduleError::Database(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+pub fn generate_schedule_id() -> String {
+    use rand::Rng;
+    let timestamp = Utc::now().timestamp();
+    let random: u32 = rand::rng().random();
+    format!("sch_{}_{:08x}", timestamp, random)
+}
+
+fn generate_run_id() -> String {
+    use rand::Rng;
+    let timestamp = Utc::now().timestamp();
+    let random: u32 = rand::rng().random();
+    format!("schr_{}_{:08x}", timestamp, random)
+}
+
+const SCHEDULE_COLUMNS: &str = r#"
+    schedule_id, account_id, amount, currency, transaction_type, description,
+    metadata, recurrence, start_at, end_at, max_occurrences, retry_policy,
+    status, occurrences, attempts, next_occurrence_at, next_run_at,
+    last_error, created_at, updated_at
+"#;
+
+/// Too wide for a tuple, unlike the rows elsewhere.
+#[derive(sqlx::FromRow)]
+struct ScheduleRow {
+    schedule_id: String,
+    account_id: String,
+    amount: f64,
+    currency: String,
+    transaction_type: String,
+    description: Option<String>,
+    metadata: Option<serde_json::Value>,
+    recurrence: Json<Recurrence>,
+    start_at: DateTime<Utc>,
+    end_at: Option<DateTime<Utc>>,
+    max_occurrences: Option<i32>,
+    retry_policy: Json<RetryPolicy>,
+    status: String,
+    occurrences: i32,
+    attempts: i32,
+    next_occurrence_at: Option<DateTime<Utc>>,
+    next_run_at: Option<DateTime<Utc>>,
+    last_error: Option<String>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+fn schedule_from_row(row: ScheduleRow) -> TransactionScheduleResponse {
+    TransactionScheduleResponse {
+        schedule_id: row.schedule_id,
+        transaction: CreateTransactionRequest {
+            account_id: row.account_id,
+            amount: row.amount,
+            currency: row.currency.parse().unwrap_or_default(),
+            transaction_type: row.transaction_type.parse().unwrap_or_default(),
+            description: row.description,
+            metadata: row.metadata,
+        },
+        recurrence: row.recurrence.0,
+        start_at: row.start_at,
+        end_at: row.end_at,
+        max_occurrences: row.max_occurrences,
+        retry: row.retry_policy.0,
+        status: row.status.parse().unwrap_or(ScheduleStatus::Active),
+        occurrences: row.occurrences,
+        attempts: row.attempts,
+        next_occurrence_at: row.next_occurrence_at,
+        next_run_at: row.next_run_at,
+        last_error: row.last_error,
+        created_at: row.created_at,
+        updated_at: row.updated_at,
+    }
+}
+
+type RunRow = (
+    String,
+    String,
+    DateTime<Utc>,
+    i32,
+    String,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    DateTime<Utc>,
+);
+
+fn run_from_row(row: RunRow) -> ScheduleRunResponse {
+    ScheduleRunResponse {
+        run_id: row.0,
+        schedule_id: row.1,
+        occurrence_at: row.2,
+        attempt: row.3,
+        status: row.4.parse().unwrap_or(ScheduleRunStatus::Failed),
+        transaction_id: row.5,
+        error_code: row.6,
+        error_message: row.7,
+        created_at: row.8,
+    }
+}
+
+pub struct ScheduleService;
+
+impl ScheduleService {
+    pub async fn create(
+        pool: &PgPool,
+        req: &CreateScheduleRequest,
+        default_retry: RetryPolicy,
+        now: DateTime<Utc>,
+    ) -> Result<TransactionScheduleResponse, sqlx::Error> {
+        let start_at = req.start_at.unwrap_or(now);
+        let first = req.recurrence.first_occurrence(start_at);
+        let txn = &req.transaction;
+
+        let row = sqlx::query_as::<_, ScheduleRow>(&format!(
+            r#"
+            INSERT INTO transaction_schedules (
+                schedule_id, account_id, amount, currency, transaction_type,
+                description, metadata, recurrence, start_at, end_at,
+                max_occurrences, retry_policy, status, next_occurrence_at, next_run_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $14)
+            RETURNING {}
+            "#,
+            SCHEDULE_COLUMNS
+        ))
+        .bind(generate_schedule_id())
+        .bind(&txn.account_id)
+        .bind(txn.amount)
+        .bind(txn.currency.to_string())
+        .bind(txn.transaction_type.to_string())
+        .bind(&txn.description)
+        .bind(&txn.metadata)
+        .bind(Json(req.recurrence))
+        .bind(start_at)
+        .bind(req.end_at)
+        .bind(req.max_occurrences)
+        .bind(Json(req.retry.unwrap_or(default_retry)))
+        .bind(ScheduleStatus::Active.to_string())
+        .bind(first)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(schedule_from_row(row))
+    }
+
+    pub async fn get(pool: &PgPool, schedule_id: &str) -> Result<Option<TransactionScheduleResponse>, sqlx::Error> {
+        let row = sqlx::query_as::<_, ScheduleRow>(&format!(
+            "SELECT {} FROM transaction_schedules WHERE schedule_id = $1",
+            SCHEDULE_COLUMNS
+        ))
+        .bind(schedule_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(row.map(schedule_from_row))
+    }
+
+    /// Schedules, newest first.
+    pub async fn list(
+        pool: &PgPool,
+        account_id: Option<&str>,
+        status: Option<ScheduleStatus>,
+        limit: i64,
+    ) -> Result<Vec<TransactionScheduleResponse>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, ScheduleRow>(&format!(
+            r#"
+            SELECT {}
+            FROM transaction_schedules
+            WHERE ($1::text IS NULL OR account_id = $1)
+              AND ($2::text IS NULL OR status = $2)
+            ORDER BY created_at DESC, schedule_id DESC
+            LIMIT $3
+            "#,
+            SCHEDULE_COLUMNS
+        ))
+        .bind(account_id)
+        .bind(status.map(|s| s.to_string()))
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows.into_iter().map(schedule_from_row).collect())
+    }
+
+    /// Attempts of a schedule, newest first.
+    pub async fn runs(pool: &PgPool, schedule_id: &str, limit: i64) -> Result<Vec<ScheduleRunResponse>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, RunRow>(
+            r#"
+            SELECT run_id, schedule_id, occurrence_at, attempt, status,
+                   transaction_id, error_code, error_message, created_at
+            FROM schedule_runs
+            WHERE schedule_id = $1
+            ORDER BY created_at DESC, run_id DESC
+            LIMIT $2
+            "#
+        )
+        .bind(schedule_id)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows.into_iter().map(run_from_row).collect())
+    }
+
+    /// Pauses, resumes or cancels a schedule. Resuming skips occurrences
+    /// missed while paused and starts the current one's retries afresh.
+    pub async fn change_status(
+        pool: &PgPool,
+        schedule_id: &str,
+        action: ScheduleAction,
+        now: DateTime<Utc>,
+    ) -> Result<TransactionScheduleResponse, ScheduleError> {
+        let mut tx = pool.begin().await?;
+
+        let schedule = sqlx::query_as::<_, ScheduleRow>(&format!(
+            "SELECT {} FROM transaction_schedules WHERE schedule_id = $1 FOR UPDATE",
+            SCHEDULE_COLUMNS
+        ))
+        .bind(schedule_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .map(schedule_from_row)
+        .ok_or_else(|| ScheduleError::NotFound(schedule_id.to_string()))?;
+
+        let mut status = schedule.status.apply(action)?;
+        let (next_occurrence_at, next_run_at, attempts) = match action {
+            ScheduleAction::Pause => (schedule.next_occurrence_at, schedule.next_run_at, schedule.attempts),
+            ScheduleAction::Resume => {
+                let next = schedule.resume_occurrence(now);
+                if next.is_none() {
+                    status = ScheduleStatus::Completed;
+                }
+                (next, next, 0)
+            }
+            ScheduleAction::Cancel => (None, None, schedule.attempts),
+        };
+
+        let row = sqlx::query_as::<_, ScheduleRow>(&format!(
+            r#"
+            UPDATE transaction_schedules
+            SET status = $2, next_occurrence_at = $3, next_run_at = $4,
+                attempts = $5, updated_at = NOW()
+            WHERE schedule_id = $1
+            RETURNING {}
+            "#,
+            SCHEDULE_COLUMNS
+        ))
+        .bind(schedule_id)
+        .bind(status.to_string())
+        .bind(next_occurrence_at)
+        .bind(next_run_at)
+        .bind(attempts)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(schedule_from_row(row))
+    }
+
+    /// Runs up to `batch_size` schedules due at `now`. Returns how many ran.
+    pub async fn run_due(
+        pool: &PgPool,
+        screener: &FraudScreener,
+        settings: &ScheduleSettings,
+        now: DateTime<Utc>,
+    ) -> Result<u64, sqlx::Error> {
+        let due = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT schedule_id
+            FROM transaction_schedules
+            WHERE status = 'active' AND next_run_at <= $1
+            ORDER BY next_run_at, schedule_id
+            LIMIT $2
+            "#
+        )
+        .bind(now)
+        .bind(settings.batch_size)
+        .fetch_all(pool)
+        .await?;
+
+        let mut ran = 0;
+        for schedule_id in due {
+            match Self::run_one(pool, screener, &schedule_id, now).await {
+                Ok(true) => ran += 1,
+                Ok(false) => {}
+                Err(e) => tracing::error!("Schedule {} failed to run: {}", schedule_id, e),
+            }
+        }
+
+        Ok(ran)
+    }
+
+    /// Attempts the current occurrence of a due schedule. The transaction,
+    /// the run record and the schedule update commit together, so an
+    /// occurrence is never created twice. A database error rolls the attempt
+    /// back and records it as a failed run in a fresh transaction, backing off
+    /// like any retryable failure. Returns `false` if the schedule is no
+    /// longer due or another worker holds it.
+    pub async fn run_one(
+        pool: &PgPool,
+        screener: &FraudScreener,
+        schedule_id: &str,
+        now: DateTime<Utc>,
+    ) -> Result<bool, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        let Some(schedule) = Self::lock_due(&mut tx, schedule_id, now).await? else {
+            return Ok(false);
+        };
+
+        let result = TransactionService::post(&mut tx, screener, &schedule.scheduled_request()).await;
+        let (attempt, transaction_id, error) = match &result {
+            Ok(transaction) => (Attempt::Created, Some(transaction.transaction_id.clone()), None),
+            Err(PostingError::Database(e)) => {
+                // The database transaction is unusable; record the attempt in a new one.
+                tracing::warn!("Schedule {} attempt failed: {}", schedule_id, e);
+                tx.rollback().await?;
+                tx = pool.begin().await?;
+
+                let Some(schedule) = Self::lock_due(&mut tx, schedule_id, now).await? else {
+                    return Ok(false);
+                };
+                let error = (ErrorCode::DatabaseError.to_string(), "Transaction could not be created".to_string());
+                Self::record_attempt(&mut tx, &schedule, Attempt::Retryable, None, Some(error), now).await?;
+                tx.commit().await?;
+
+                return Ok(true);
+            }
+            Err(e) => (
+                Attempt::from_error(e),
+                e.transaction_id().map(str::to_string),
+                Some((e.code().to_string(), e.to_string())),
+            ),
+        };
+
+        Self::record_attempt(&mut tx, &schedule, attempt, transaction_id, error, now).await?;
+
+        tx.commit().await?;
+
+        if let Ok(transaction) = &result {
+            record_transaction_created(
+                &transaction.transaction_type.to_string(),
+                &transaction.currency.to_string(),
+            );
+        }
+
+        Ok(true)
+    }
+
+    /// Locks an active schedule whose current occurrence is due at `now`.
+    async fn lock_due(
+        conn: &mut sqlx::PgConnection,
+        schedule_id: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Option<TransactionScheduleResponse>, sqlx::Error> {
+        let schedule = sqlx::query_as::<_, ScheduleRow>(&format!(
+            r#"
+            SELECT {}
+            FROM transaction_schedules
+            WHERE schedule_id = $1 AND status = 'active' AND next_run_at <= $2
+            FOR UPDATE SKIP LOCKED
+            "#,
+            SCHEDULE_COLUMNS
+        ))
+        .bind(schedule_id)
+        .bind(now)
+        .fetch_optional(&mut *conn)
+        .await?
+        .map(schedule_from_row);
+
+        Ok(schedule.filter(|schedule| schedule.next_occurrence_at.is_some()))
+    }
+
+    /// Records a run of the schedule's current occurrence and moves the
+    /// schedule on by [`TransactionScheduleResponse::step`].
+    async fn record_attempt(
+        conn: &mut sqlx::PgConnection,
+        schedule: &TransactionScheduleResponse,
+        attempt: Attempt,
+        transaction_id: Option<String>,
+        error: Option<(String, String)>,
+        now: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        let step = schedule.step(attempt, now);
+
+        sqlx::query(
+            r#"
+            INSERT INTO schedule_runs (
+                run_id, schedule_id, occurrence_at, attempt, status,
+                transaction_id, error_code, error_message
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#
+        )
+        .bind(generate_run_id())
+        .bind(&schedule.schedule_id)
+        .bind(schedule.next_occurrence_at)
+        .bind(schedule.attempts + 1)
+        .bind(step.run_status.to_string())
+        .bind(&transaction_id)
+        .bind(error.as_ref().map(|(code, _)| code))
+        .bind(error.as_ref().map(|(_, message)| message))
+        .execute(&mut *conn)
+        .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE transaction_schedules
+            SET status = $2, occurrences = $3, attempts = $4,
+                next_occurrence_at = $5, next_run_at = $6,
+                last_error = COALESCE($7, last_error), updated_at = NOW()
+            WHERE schedule_id = $1
+            "#
+        )
+        .bind(&schedule.schedule_id)
+        .bind(step.status.to_string())
+        .bind(step.occurrences)
+        .bind(step.attempts)
+        .bind(step.next_occurrence_at)
+        .bind(step.next_run_at)
+        .bind(error.as_ref().map(|(_, message)| message))
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+}
+
+pub fn spawn_schedule_worker(
+    pool: PgPool,
+    settings: ScheduleSettings,
+    screener: FraudScreener,
+    clock: Arc<dyn Clock>,
+) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(settings.poll_interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            match ScheduleService::run_due(&pool, &screener, &settings, clock.now()).await {
+                Ok(ran) => {
+                    if ran > 0 {
+                        tracing::info!("Ran {} scheduled transactions", ran);
+                    }
+                    record_job_outcome("transaction_schedules", "success");
+                }
+                Err(e) => {
+                    tracing::error!("Scheduled transaction run failed: {}", e);
+                    record_job_outcome("transaction_schedules", "failure");
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,394 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDate, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    db::PgPool,
+    models::{
+        balance::BalanceService,
+        common::SortOrder,
+        export::{csv_field, export_query, transaction_from_row, TransactionRow},
+        finance::{
+            Currency, TransactionFilters, TransactionSortField, TransactionStatus, TransactionType,
+        },
+        responses::TransactionResponse,
+    },
+};
+
+/// CSV columns of an account statement, in order.
+pub const STATEMENT_COLUMNS: &[&str] = &[
+    "date",
+    "transaction_id",
+    "transaction_type",
+    "status",
+    "description",
+    "amount",
+    "balance",
+];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountStatementFormat {
+    #[default]
+    Json,
+    /// Opening balance row, one row per transaction, per-type total rows and a
+    /// closing balance row, columns as in `STATEMENT_COLUMNS`.
+    Csv,
+    /// A printable HTML page.
+    Html,
+}
+
+impl std::fmt::Display for AccountStatementFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AccountStatementFormat::Json => write!(f, "json"),
+            AccountStatementFormat::Csv => write!(f, "csv"),
+            AccountStatementFormat::Html => write!(f, "html"),
+        }
+    }
+}
+
+impl std::str::FromStr for AccountStatementFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(AccountStatementFormat::Json),
+            "csv" => Ok(AccountStatementFormat::Csv),
+            "html" => Ok(AccountStatementFormat::Html),
+            _ => Err(format!("Invalid statement format: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AccountStatementParams {
+    /// First day of the period (UTC, inclusive); defaults to the first day of `to`'s month
+    #[serde(default)]
+    #[param(value_type = Option<String>, format = Date, example = "2024-03-01")]
+    pub from: Option<NaiveDate>,
+    /// Last day of the period (UTC, inclusive); defaults to today
+    #[serde(default)]
+    #[param(value_type = Option<String>, format = Date, example = "2024-03-31")]
+    pub to: Option<NaiveDate>,
+    /// `json` (default), `csv` or `html`
+    #[serde(default)]
+    pub format: AccountStatementFormat,
+}
+
+impl AccountStatementParams {
+    /// The inclusive first and last day of the period.
+    pub fn period(&self, today: NaiveDate) -> Result<(NaiveDate, NaiveDate), String> {
+        let to = self.to.unwrap_or(today);
+        let from = self.from.unwrap_or_else(|| to.with_day(1).unwrap_or(to));
+
+        if from > to {
+            return Err("from must not be later than to".to_string());
+        }
+
+        Ok((from, to))
+    }
+}
+
+/// Start of `date` in UTC.
+pub fn start_of_day(date: NaiveDate) -> DateTime<Utc> {
+    date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc()
+}
+
+/// Filters selecting the account's transactions created on the days from
+/// `from` to `to`, oldest first.
+pub fn statement_filters(account_id: &str, from: NaiveDate, to: NaiveDate) -> TransactionFilters {
+    TransactionFilters {
+        account_id: Some(account_id.to_string()),
+        created_after: Some(start_of_day(from)),
+        // `created_before` is inclusive; timestamps are stored with microsecond precision.
+        created_before: Some(start_of_day(to + Duration::days(1)) - Duration::microseconds(1)),
+        sort_by: Some(TransactionSortField::CreatedAt),
+        sort_order: Some(SortOrder::Asc),
+        ..TransactionFilters::default()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct StatementEntry {
+    #[serde(flatten)]
+    pub transaction: TransactionResponse,
+    /// Balance after this transaction. Only completed transactions change it.
+    pub running_balance: f64,
+}
+
+/// Completed transactions of one type within the period.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct TransactionTypeTotal {
+    pub transaction_type: TransactionType,
+    pub count: usize,
+    pub amount: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AccountStatement {
+    pub account_id: String,
+    pub currency: Currency,
+    #[schema(value_type = String, format = Date)]
+    pub from: NaiveDate,
+    #[schema(value_type = String, format = Date)]
+    pub to: NaiveDate,
+    /// Balance before the first day of the period.
+    pub opening_balance: f64,
+    pub closing_balance: f64,
+    pub totals: Vec<TransactionTypeTotal>,
+    /// Every transaction of the period, oldest first.
+    pub transactions: Vec<StatementEntry>,
+    #[schema(value_type = String, format = DateTime)]
+    pub generated_at: DateTime<Utc>,
+}
+
+impl AccountStatement {
+    /// Builds a statement from the opening balance and the period's
+    /// transactions, oldest first. Like the account balance, only completed
+    /// transactions count towards running balances and totals.
+    pub fn build(
+        account_id: String,
+        currency: Currency,
+        (from, to): (NaiveDate, NaiveDate),
+        opening_balance: f64,
+        transactions: Vec<TransactionResponse>,
+        generated_at: DateTime<Utc>,
+    ) -> Self {
+        let mut balance = opening_balance;
+        let mut totals: Vec<TransactionTypeTotal> = Vec::new();
+
+        let transactions = transactions
+            .into_iter()
+            .map(|transaction| {
+                if transaction.status == TransactionStatus::Completed {
+                    balance += transaction.amount;
+
+                    match totals
+                        .iter_mut()
+                        .find(|total| total.transaction_type == transaction.transaction_type)
+                    {
+                        Some(total) => {
+                            total.count += 1;
+                            total.amount += transaction.amount;
+                        }
+                        None => totals.push(TransactionTypeTotal {
+                            transaction_type: transaction.transaction_type,
+                            count: 1,
+                            amount: transaction.amount,
+                        }),
+                    }
+                }
+
+                StatementEntry {
+                    transaction,
+                    running_balance: balance,
+                }
+            })
+            .collect();
+
+        Self {
+            account_id,
+            currency,
+            from,
+            to,
+            opening_balance,
+            closing_balance: balance,
+            totals,
+            transactions,
+            generated_at,
+        }
+    }
+
+    pub fn file_name(&self, format: AccountStatementFormat) -> String {
+        format!("statement_{}_{}_{}.{}", self.account_id, self.from, self.to, format)
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut rows = vec![STATEMENT_COLUMNS.join(",")];
+        let mut push = |fields: [String; 7]| {
+            rows.push(fields.iter().map(|field| csv_field(field)).collect::<Vec<_>>().join(","));
+        };
+
+        push([
+            self.from.to_string(),
+            String::new(),
+            String::new(),
+            String::new(),
+            "Opening balance".to_string(),
+            String::new(),
+            self.opening_balance.to_string(),
+        ]);
+
+        for entry in &self.transactions {
+            let t = &entry.transaction;
+            push([
+                t.created_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+                t.transaction_id.clone(),
+                t.transaction_type.to_string(),
+                t.status.to_string(),
+                t.description.clone().unwrap_or_default(),
+                t.amount.to_string(),
+                entry.running_balance.to_string(),
+            ]);
+        }
+
+        for total in &self.totals {
+            push([
+                String::new(),
+                String::new(),
+                total.transaction_type.to_string(),
+                TransactionStatus::Completed.to_string(),
+                format!("Total ({} transactions)", total.count),
+                total.amount.to_string(),
+                String::new(),
+            ]);
+        }
+
+        push([
+            self.to.to_string(),
+            String::new(),
+            String::new(),
+            String::new(),
+            "Closing balance".to_string(),
+            String::new(),
+            self.closing_balance.to_string(),
+        ]);
+
+        let mut csv = rows.join("\n");
+        csv.push('\n');
+        csv
+    }
+
+    pub fn to_html(&self) -> String {
+        let money = |amount: f64| format!("{:.2} {}", amount, self.currency);
+
+        let mut rows = String::new();
+        for entry in &self.transactions {
+            let t = &entry.transaction;
+            rows.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>\n",
+                t.created_at.format("%Y-%m-%d %H:%M"),
+                html_escape(&t.transaction_id),
+                t.transaction_type,
+                t.status,
+                html_escape(t.description.as_deref().unwrap_or_default()),
+                money(t.amount),
+                money(entry.running_balance),
+            ));
+        }
+
+        let mut totals = String::new();
+        for total in &self.totals {
+            totals.push_str(&format!(
+                "<tr><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>\n",
+                total.transaction_type,
+                total.count,
+                money(total.amount),
+            ));
+        }
+
+        format!(
+            r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Statement {account} {from} to {to}</title>
+<style>
+body {{ font-family: sans-serif; margin: 2em; }}
+table {{ border-collapse: collapse; margin-bottom: 1.5em; }}
+th, td {{ border-bottom: 1px solid #ccc; padding: 4px 8px; text-align: left; }}
+.num {{ text-align: right; }}
+</style>
+</head>
+<body>
+<h1>Account statement</h1>
+<p>Account <strong>{account}</strong>, {from} to {to}</p>
+<p>Opening balance: <strong>{opening}</strong></p>
+<table>
+<tr><th>Date</th><th>Transaction</th><th>Type</th><th>Status</th><th>Description</th><th class="num">Amount</th><th class="num">Balance</th></tr>
+{rows}</table>
+<h2>Totals</h2>
+<table>
+<tr><th>Type</th><th class="num">Count</th><th class="num">Amount</th></tr>
+{totals}</table>
+<p>Closing balance: <strong>{closing}</strong></p>
+<p><small>Generated {generated}</small></p>
+</body>
+</html>
+"#,
+            account = html_escape(&self.account_id),
+            from = self.from,
+            to = self.to,
+            opening = money(self.opening_balance),
+            closing = money(self.closing_balance),
+            rows = rows,
+            totals = totals,
+            generated = self.generated_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+        )
+    }
+}
+
+pub fn html_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+pub struct StatementService;
+
+impl StatementService {
+    pub async fn transactions(
+        pool: &PgPool,
+        filters: &TransactionFilters,
+    ) -> Result<Vec<TransactionResponse>, sqlx::Error> {
+        let (query, bind_values) = export_query(filters);
+        let mut sql_query = sqlx::query_as::<_, TransactionRow>(&query);
+        for value in &bind_values {
+            sql_query = sql_query.bind(value);
+        }
+
+        Ok(sql_query
+            .fetch_all(pool)
+            .await?
+            .into_iter()
+            .map(transaction_from_row)
+            .collect())
+    }
+
+    pub async fn generate(
+        pool: &PgPool,
+        account_id: &str,
+        (from, to): (NaiveDate, NaiveDate),
+    ) -> Result<AccountStatement, sqlx::Error> {
+        let (opening_balance, balance_currency) =
+            BalanceService::balance_at(pool, account_id, Some(start_of_day(from))).await?;
+        let transactions =
+            Self::transactions(pool, &statement_filters(account_id, from, to)).await?;
+
+        // Without earlier completed transactions, the period's first
+        // transaction determines the currency.
+        let currency = balance_currency
+            .or_else(|| transactions.first().map(|t| t.currency))
+            .unwrap_or_default();
+
+        Ok(AccountStatement::build(
+            account_id.to_string(),
+            currency,
+            (from, to),
+            opening_balance,
+            transactions,
+            Utc::now(),
+        ))
+    }
+}
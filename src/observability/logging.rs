@@ -0,0 +1,376 @@
+use chrono::{SecondsFormat, Utc};
+use regex::{Captures, Regex};
+use serde_json::{Map, Value};
+use std::{fmt, io, str::FromStr, sync::Arc};
+use tracing::{
+    field::{Field, Visit},
+    span, Event, Subscriber,
+};
+use tracing_subscriber::{
+    fmt::{format::Writer, FmtContext, FormatEvent, FormatFields, MakeWriter},
+    layer::Context,
+    registry::LookupSpan,
+    Layer,
+};
+
+/// Name of the root span created for every HTTP request.
+pub const REQUEST_SPAN_NAME: &str = "request";
+
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Field and header names whose values are always redacted.
+const SENSITIVE_FIELDS: &[&str] = &[
+    "x-api-key",
+    "x-admin-key",
+    "api_key",
+    "admin_key",
+    "secret",
+    "secret_hash",
+    "password",
+    "authorization",
+];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" | "pretty" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(format!("Invalid log format: {}", s)),
+        }
+    }
+}
+
+/// Scrubs secrets from rendered log lines.
+///
+/// Values of sensitive fields (headers, secret hashes and configured metadata
+/// keys) are replaced whether they appear as `key=value`, `key: value` or JSON,
+/// including JSON escaped inside another JSON string. API keys, argon2 hashes
+/// and configured literal secrets are replaced wherever they appear.
+#[derive(Debug, Clone)]
+pub struct Redactor {
+    fields: Regex,
+    api_keys: Regex,
+    secret_hashes: Regex,
+    literals: Vec<String>,
+}
+
+impl Redactor {
+    pub fn new(extra_fields: &[String], literals: &[String]) -> Self {
+        let names = SENSITIVE_FIELDS
+            .iter()
+            .map(|name| name.to_string())
+            .chain(extra_fields.iter().map(|name| name.trim().to_string()))
+            .filter(|name| !name.is_empty())
+            // `-` and `_` are interchangeable so `x-api-key` also covers `x_api_key`.
+            .map(|name| {
+                name.split(['-', '_'])
+                    .map(regex::escape)
+                    .collect::<Vec<_>>()
+                    .join("[-_]")
+            })
+            .collect::<Vec<_>>()
+            .join("|");
+
+        // Text output may wrap field names and separators in ANSI colour codes.
+        let ansi = r"(?:\x1b\[[0-9;]*m)*";
+        let fields = Regex::new(&format!(
+            r#"(?i)(\\?"?(?:{names})\\?"?{ansi}\s*[:=]{ansi}\s*)(\\"(?:[^\\]|\\[^"])*?\\"|"(?:[^"\\]|\\.)*"|[^\s,;&}}\]"\\\x1b]+)"#,
+        ))
+        .expect("valid redaction pattern");
+
+        Self {
+            fields,
+            api_keys: Regex::new(r"(sk_[A-Za-z]+_[A-Za-z0-9]+)_[A-Za-z0-9]{8,}")
+                .expect("valid api key pattern"),
+            secret_hashes: Regex::new(r#"\$argon2(?:id|i|d)\$[^\s"'\\]+"#)
+                .expect("valid hash pattern"),
+            literals: literals
+                .iter()
+                .filter(|literal| literal.len() >= 8)
+                .cloned()
+                .collect(),
+        }
+    }
+
+    pub fn redact(&self, line: &str) -> String {
+        let mut redacted = line.to_string();
+
+        for literal in &self.literals {
+            redacted = redacted.replace(literal.as_str(), REDACTED);
+        }
+
+        let redacted = self
+            .secret_hashes
+            .replace_all(&redacted, REDACTED)
+            .into_owned();
+        let redacted = self
+            .api_keys
+            .replace_all(&redacted, format!("${{1}}_{}", REDACTED))
+            .into_owned();
+
+        self.fields
+            .replace_all(&redacted, |caps: &Captures| {
+                let value = &caps[2];
+                let replacement = if value.starts_with("\\\"") {
+                    format!("\\\"{}\\\"", REDACTED)
+                } else if value.starts_with('"') {
+                    format!("\"{}\"", REDACTED)
+                } else {
+                    REDACTED.to_string()
+                };
+                format!("{}{}", &caps[1], replacement)
+            })
+            .into_owned()
+    }
+}
+
+/// `MakeWriter` that redacts each formatted event before it reaches the
+/// underlying writer.
+#[derive(Clone)]
+pub struct RedactingMakeWriter<M> {
+    inner: M,
+    redactor: Arc<Redactor>,
+}
+
+impl<M> RedactingMakeWriter<M> {
+    pub fn new(inner: M, redactor: Arc<Redactor>) -> Self {
+        Self { inner, redactor }
+    }
+}
+
+impl<'a, M> MakeWriter<'a> for RedactingMakeWriter<M>
+where
+    M: MakeWriter<'a>,
+{
+    type Writer = RedactingWriter<M::Writer>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RedactingWriter {
+            inner: self.inner.make_writer(),
+            redactor: self.redactor.clone(),
+            buffer: Vec::new(),
+        }
+    }
+}
+
+/// Buffers one event and writes the redacted text when dropped.
+pub struct RedactingWriter<W: io::Write> {
+    inner: W,
+    redactor: Arc<Redactor>,
+    buffer: Vec<u8>,
+}
+
+impl<W: io::Write> io::Write for RedactingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.buffer.is_empty() {
+            return self.inner.flush();
+        }
+
+        let line = String::from_utf8_lossy(&self.buffer);
+        let redacted = self.redactor.redact(&line);
+        self.buffer.clear();
+        self.inner.write_all(redacted.as_bytes())?;
+        self.inner.flush()
+    }
+}
+
+impl<W: io::Write> Drop for RedactingWriter<W> {
+    fn drop(&mut self) {
+        let _ = io::Write::flush(self);
+    }
+}
+
+/// Request attributes copied from the root request span so every log line
+/// emitted while handling the request can carry them.
+#[derive(Debug, Clone, Default)]
+pub struct RequestContext {
+    pub request_id: Option<String>,
+    pub key_id: Option<String>,
+    pub account_id: Option<String>,
+    pub route: Option<String>,
+}
+
+impl Visit for RequestContext {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.record(field, value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.record(field, format!("{:?}", value).trim_matches('"').to_string());
+    }
+}
+
+impl RequestContext {
+    fn record(&mut self, field: &Field, value: String) {
+        if value.is_empty() {
+            return;
+        }
+
+        match field.name() {
+            "request_id" => self.request_id = Some(value),
+            "key_id" => self.key_id = Some(value),
+            "account_id" => self.account_id = Some(value),
+            "http.route" => self.route = Some(value),
+            _ => {}
+        }
+    }
+
+    fn insert_into(&self, line: &mut Map<String, Value>) {
+        let fields = [
+            ("request_id", &self.request_id),
+            ("key_id", &self.key_id),
+            ("account_id", &self.account_id),
+            ("route", &self.route),
+        ];
+        for (name, value) in fields {
+            if let Some(value) = value {
+                line.insert(name.to_string(), Value::String(value.clone()));
+            }
+        }
+    }
+}
+
+/// Tracks [`RequestContext`] on request spans, including fields recorded
+/// after the span was created (such as `key_id` once authenticated).
+pub struct RequestContextLayer;
+
+impl<S> Layer<S> for RequestContextLayer
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        if attrs.metadata().name() != REQUEST_SPAN_NAME {
+            return;
+        }
+
+        if let Some(span) = ctx.span(id) {
+            let mut request = RequestContext::default();
+            attrs.record(&mut request);
+            span.extensions_mut().insert(request);
+        }
+    }
+
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(request) = span.extensions_mut().get_mut::<RequestContext>() {
+                values.record(request);
+            }
+        }
+    }
+}
+
+/// One JSON object per line with the event fields flattened alongside the
+/// request context of the enclosing request span.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonRequestFormat;
+
+impl<S, N> FormatEvent<S, N> for JsonRequestFormat
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let metadata = event.metadata();
+        let mut fields = JsonFields::default();
+        event.record(&mut fields);
+
+        let mut line = Map::new();
+        line.insert(
+            "timestamp".to_string(),
+            Value::String(Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true)),
+        );
+        line.insert("level".to_string(), Value::String(metadata.level().to_string()));
+        line.insert("target".to_string(), Value::String(metadata.target().to_string()));
+
+        if let Some(scope) = ctx.event_scope() {
+            for span in scope.from_root() {
+                if let Some(request) = span.extensions().get::<RequestContext>() {
+                    request.insert_into(&mut line);
+                }
+            }
+        }
+        if let Some(span) = ctx.lookup_current() {
+            line.insert("span".to_string(), Value::String(span.name().to_string()));
+        }
+
+        for (name, value) in fields.0 {
+            line.entry(name).or_insert(value);
+        }
+
+        writeln!(writer, "{}", Value::Object(line))
+    }
+}
+
+#[derive(Default)]
+struct JsonFields(Map<String, Value>);
+
+impl Visit for JsonFields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0
+            .insert(field.name().to_string(), Value::String(format!("{:?}", value)));
+    }
+}
+
+/// Formatting layer for the given log format, writing through the redactor.
+pub fn fmt_layer<S, M>(
+    format: LogFormat,
+    writer: RedactingMakeWriter<M>,
+) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+    M: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    match format {
+        LogFormat::Text => Box::new(
+            tracing_subscriber::fmt::layer()
+                .with_target(true)
+                .with_thread_ids(true)
+                .with_line_number(true)
+                .with_writer(writer),
+        ),
+        LogFormat::Json => Box::new(
+            tracing_subscriber::fmt::layer()
+                .event_format(JsonRequestFormat)
+                .with_writer(writer),
+        ),
+    }
+}
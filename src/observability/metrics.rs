@@ -0,0 +1,199 @@
+use anyhow::Result;
+use axum::{
+    extract::{MatchedPath, Request},
+    middleware::Next,
+    response::Response,
+};
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+use once_cell::sync::OnceCell;
+use std::time::{Duration, Instant};
+
+use crate::db::PgPool;
+
+pub const HTTP_REQUESTS_TOTAL: &str = "http_requests_total";
+pub const HTTP_REQUEST_DURATION_SECONDS: &str = "http_request_duration_seconds";
+pub const HTTP_REQUESTS_IN_PROGRESS: &str = "http_requests_in_progress";
+pub const DB_POOL_CONNECTIONS: &str = "db_pool_connections";
+pub const DB_POOL_MAX_CONNECTIONS: &str = "db_pool_max_connections";
+pub const RATE_LIMIT_REJECTIONS_TOTAL: &str = "rate_limit_rejections_total";
+pub const QUOTA_REJECTIONS_TOTAL: &str = "quota_rejections_total";
+pub const QUOTA_USAGE_TOTAL: &str = "quota_usage_total";
+pub const AUTH_FAILURES_TOTAL: &str = "auth_failures_total";
+pub const TRANSACTIONS_CREATED_TOTAL: &str = "transactions_created_total";
+pub const BACKGROUND_JOBS_TOTAL: &str = "background_jobs_total";
+pub const REQUEST_LOG_QUEUE_DEPTH: &str = "request_log_queue_depth";
+pub const REQUEST_LOG_DROPPED_TOTAL: &str = "request_log_dropped_total";
+pub const REQUEST_LOG_BATCH_SIZE: &str = "request_log_batch_size";
+pub const REQUEST_LOG_FLUSH_DURATION_SECONDS: &str = "request_log_flush_duration_seconds";
+pub const TRANSACTION_BATCH_ITEMS_TOTAL: &str = "transaction_batch_items_total";
+pub const FRAUD_DECISIONS_TOTAL: &str = "fraud_decisions_total";
+
+/// Route label used for requests that did not match any route, so arbitrary
+/// paths from scanners cannot create new series.
+pub const UNMATCHED_ROUTE: &str = "unmatched";
+
+const LATENCY_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0,
+];
+
+static HANDLE: OnceCell<PrometheusHandle> = OnceCell::new();
+
+/// Installs the global Prometheus recorder, or returns the existing handle if
+/// it has already been installed.
+pub fn init_metrics() -> Result<PrometheusHandle> {
+    let handle = HANDLE.get_or_try_init(|| -> Result<PrometheusHandle> {
+        let handle = PrometheusBuilder::new()
+            .set_buckets_for_metric(
+                Matcher::Full(HTTP_REQUEST_DURATION_SECONDS.to_string()),
+                LATENCY_BUCKETS,
+            )?
+            .install_recorder()?;
+
+        describe_metrics();
+
+        Ok(handle)
+    })?;
+
+    Ok(handle.clone())
+}
+
+/// Periodically drains histogram samples so memory stays bounded between scrapes.
+pub fn spawn_upkeep(handle: PrometheusHandle) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(5));
+        loop {
+            interval.tick().await;
+            handle.run_upkeep();
+        }
+    });
+}
+
+fn describe_metrics() {
+    metrics::describe_counter!(HTTP_REQUESTS_TOTAL, "Total HTTP requests by route template, method and status");
+    metrics::describe_histogram!(
+        HTTP_REQUEST_DURATION_SECONDS,
+        metrics::Unit::Seconds,
+        "HTTP request latency by route template, method and status"
+    );
+    metrics::describe_gauge!(HTTP_REQUESTS_IN_PROGRESS, "HTTP requests currently being served");
+    metrics::describe_gauge!(DB_POOL_CONNECTIONS, "Database pool connections by state");
+    metrics::describe_gauge!(DB_POOL_MAX_CONNECTIONS, "Maximum database pool connections");
+    metrics::describe_counter!(RATE_LIMIT_REJECTIONS_TOTAL, "Requests rejected by the per-minute rate limit");
+    metrics::describe_counter!(QUOTA_REJECTIONS_TOTAL, "Requests rejected by daily or monthly quotas");
+    metrics::describe_counter!(QUOTA_USAGE_TOTAL, "Requests counted against API key quotas");
+    metrics::describe_counter!(AUTH_FAILURES_TOTAL, "Authentication failures by reason");
+    metrics::describe_counter!(TRANSACTIONS_CREATED_TOTAL, "Transactions created by type and currency");
+    metrics::describe_counter!(BACKGROUND_JOBS_TOTAL, "Background job runs by job and outcome");
+    metrics::describe_gauge!(REQUEST_LOG_QUEUE_DEPTH, "Request log entries waiting to be written");
+    metrics::describe_counter!(REQUEST_LOG_DROPPED_TOTAL, "Request log entries dropped by reason");
+    metrics::describe_histogram!(REQUEST_LOG_BATCH_SIZE, "Request log entries per batch insert");
+    metrics::describe_histogram!(
+        REQUEST_LOG_FLUSH_DURATION_SECONDS,
+        metrics::Unit::Seconds,
+        "Time spent writing a request log batch"
+    );
+    metrics::describe_counter!(TRANSACTION_BATCH_ITEMS_TOTAL, "Batch transaction items by mode and outcome");
+    metrics::describe_counter!(FRAUD_DECISIONS_TOTAL, "Fraud screening decisions by outcome");
+}
+
+/// Records request count, latency and in-flight requests.
+///
+/// Must be applied with `Router::layer` so the matched route template is
+/// available; raw paths are never used as labels.
+pub async fn track_http_metrics(req: Request, next: Next) -> Response {
+    let start = Instant::now();
+    let method = req.method().to_string();
+    let route = route_label(req.extensions().get::<MatchedPath>());
+
+    metrics::gauge!(HTTP_REQUESTS_IN_PROGRESS).increment(1.0);
+    let response = next.run(req).await;
+    metrics::gauge!(HTTP_REQUESTS_IN_PROGRESS).decrement(1.0);
+
+    let labels = [
+        ("method", method),
+        ("route", route),
+        ("status", response.status().as_u16().to_string()),
+    ];
+    metrics::counter!(HTTP_REQUESTS_TOTAL, &labels).increment(1);
+    metrics::histogram!(HTTP_REQUEST_DURATION_SECONDS, &labels)
+        .record(start.elapsed().as_secs_f64());
+
+    response
+}
+
+pub fn route_label(matched: Option<&MatchedPath>) -> String {
+    matched
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| UNMATCHED_ROUTE.to_string())
+}
+
+pub fn record_db_pool(pool: &PgPool) {
+    let size = pool.size() as f64;
+    let idle = pool.num_idle() as f64;
+
+    metrics::gauge!(DB_POOL_CONNECTIONS, "state" => "idle").set(idle);
+    metrics::gauge!(DB_POOL_CONNECTIONS, "state" => "active").set((size - idle).max(0.0));
+    metrics::gauge!(DB_POOL_MAX_CONNECTIONS).set(pool.options().get_max_connections() as f64);
+}
+
+pub fn record_rate_limit_rejection(key_id: &str) {
+    metrics::counter!(RATE_LIMIT_REJECTIONS_TOTAL, "key_id" => key_id.to_string()).increment(1);
+}
+
+pub fn record_quota_rejection(key_id: &str, period: &str) {
+    metrics::counter!(
+        QUOTA_REJECTIONS_TOTAL,
+        "key_id" => key_id.to_string(),
+        "period" => period.to_string()
+    )
+    .increment(1);
+}
+
+pub fn record_quota_usage(key_id: &str) {
+    record_quota_usage_by(key_id, 1);
+}
+
+pub fn record_quota_usage_by(key_id: &str, count: u64) {
+    metrics::counter!(QUOTA_USAGE_TOTAL, "key_id" => key_id.to_string()).increment(count);
+}
+
+pub fn record_auth_failure(reason: &'static str) {
+    metrics::counter!(AUTH_FAILURES_TOTAL, "reason" => reason).increment(1);
+}
+
+pub fn record_transaction_created(transaction_type: &str, currency: &str) {
+    metrics::counter!(
+        TRANSACTIONS_CREATED_TOTAL,
+        "transaction_type" => transaction_type.to_string(),
+        "currency" => currency.to_string()
+    )
+    .increment(1);
+}
+
+pub fn record_batch_items(mode: &str, succeeded: usize, failed: usize) {
+    metrics::counter!(TRANSACTION_BATCH_ITEMS_TOTAL, "mode" => mode.to_string(), "outcome" => "created")
+        .increment(succeeded as u64);
+    metrics::counter!(TRANSACTION_BATCH_ITEMS_TOTAL, "mode" => mode.to_string(), "outcome" => "failed")
+        .increment(failed as u64);
+}
+
+pub fn record_fraud_decision(decision: &str) {
+    metrics::counter!(FRAUD_DECISIONS_TOTAL, "decision" => decision.to_string()).increment(1);
+}
+
+pub fn record_job_outcome(job: &'static str, outcome: &'static str) {
+    metrics::counter!(BACKGROUND_JOBS_TOTAL, "job" => job, "outcome" => outcome).increment(1);
+}
+
+pub fn record_request_log_queue_depth(depth: usize) {
+    metrics::gauge!(REQUEST_LOG_QUEUE_DEPTH).set(depth as f64);
+}
+
+pub fn record_request_log_dropped(reason: &'static str, count: u64) {
+    metrics::counter!(REQUEST_LOG_DROPPED_TOTAL, "reason" => reason).increment(count);
+}
+
+pub fn record_request_log_flush(batch_size: usize, elapsed: Duration) {
+    metrics::histogram!(REQUEST_LOG_BATCH_SIZE).record(batch_size as f64);
+    metrics::histogram!(REQUEST_LOG_FLUSH_DURATION_SECONDS).record(elapsed.as_secs_f64());
+}
@@ -0,0 +1,4 @@
+pub mod logging;
+pub mod metrics;
+pub mod request_log;
+pub mod telemetry;
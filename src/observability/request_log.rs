@@ -0,0 +1,251 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tokio::{
+    sync::{mpsc, oneshot},
+    task::JoinHandle,
+};
+
+use crate::{config::Config, db::PgPool, observability::metrics};
+
+/// One row of the `requests` log.
+#[derive(Debug, Clone)]
+pub struct RequestLogEntry {
+    pub key_id: Option<String>,
+    pub account_id: Option<String>,
+    pub path: String,
+    pub method: String,
+    pub status: i32,
+    pub latency_ms: i32,
+    pub error_code: Option<String>,
+    /// When the request was received; entries may be written some time later.
+    pub ts: DateTime<Utc>,
+}
+
+/// Destination for batches of request log entries.
+#[async_trait]
+pub trait RequestLogSink: Send + Sync + 'static {
+    async fn write_batch(&self, batch: &[RequestLogEntry]) -> Result<(), sqlx::Error>;
+}
+
+/// Writes batches to the `requests` table with a single multi-row INSERT.
+pub struct PgRequestLogSink {
+    pool: PgPool,
+}
+
+impl PgRequestLogSink {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl RequestLogSink for PgRequestLogSink {
+    async fn write_batch(&self, batch: &[RequestLogEntry]) -> Result<(), sqlx::Error> {
+        let mut key_ids = Vec::with_capacity(batch.len());
+        let mut account_ids = Vec::with_capacity(batch.len());
+        let mut paths = Vec::with_capacity(batch.len());
+        let mut methods = Vec::with_capacity(batch.len());
+        let mut statuses = Vec::with_capacity(batch.len());
+        let mut latencies = Vec::with_capacity(batch.len());
+        let mut error_codes = Vec::with_capacity(batch.len());
+        let mut timestamps = Vec::with_capacity(batch.len());
+
+        for entry in batch {
+            key_ids.push(entry.key_id.clone());
+            account_ids.push(entry.account_id.clone());
+            paths.push(entry.path.clone());
+            methods.push(entry.method.clone());
+            statuses.push(entry.status);
+            latencies.push(entry.latency_ms);
+            error_codes.push(entry.error_code.clone());
+            timestamps.push(entry.ts);
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO requests (key_id, account_id, path, method, status, latency_ms, error_code, ts, created_at)
+            SELECT key_id, account_id, path, method, status, latency_ms, error_code, ts, NOW()
+            FROM UNNEST(
+                $1::text[], $2::text[], $3::text[], $4::text[],
+                $5::int[], $6::int[], $7::text[], $8::timestamptz[]
+            ) AS t(key_id, account_id, path, method, status, latency_ms, error_code, ts)
+            "#,
+        )
+        .bind(key_ids)
+        .bind(account_ids)
+        .bind(paths)
+        .bind(methods)
+        .bind(statuses)
+        .bind(latencies)
+        .bind(error_codes)
+        .bind(timestamps)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RequestLogSettings {
+    /// Entries that can wait in the channel before new ones are dropped.
+    pub buffer_size: usize,
+    /// A batch is written as soon as it reaches this many entries.
+    pub batch_size: usize,
+    /// Partial batches are written at least this often.
+    pub flush_interval: Duration,
+}
+
+impl RequestLogSettings {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            buffer_size: config.request_log_buffer_size.max(1),
+            batch_size: config.request_log_batch_size.max(1),
+            flush_interval: Duration::from_millis(config.request_log_flush_ms.max(1)),
+        }
+    }
+}
+
+impl Default for RequestLogSettings {
+    fn default() -> Self {
+        Self {
+            buffer_size: 10_000,
+            batch_size: 500,
+            flush_interval: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Handle for queueing request log entries to the background writer.
+///
+/// Logging never blocks a request: when the buffer is full the entry is
+/// dropped and counted in `request_log_dropped_total`.
+#[derive(Clone)]
+pub struct RequestLogger {
+    sender: mpsc::Sender<RequestLogEntry>,
+    writer: Arc<Mutex<Option<WriterHandle>>>,
+}
+
+struct WriterHandle {
+    shutdown: oneshot::Sender<()>,
+    task: JoinHandle<()>,
+}
+
+impl RequestLogger {
+    /// Starts the background writer and returns a handle to it.
+    pub fn spawn<S: RequestLogSink>(sink: S, settings: RequestLogSettings) -> Self {
+        let (sender, receiver) = mpsc::channel(settings.buffer_size);
+        let (shutdown, shutdown_rx) = oneshot::channel();
+        let task = tokio::spawn(run_writer(sink, receiver, shutdown_rx, settings));
+
+        Self {
+            sender,
+            writer: Arc::new(Mutex::new(Some(WriterHandle { shutdown, task }))),
+        }
+    }
+
+    /// Queues an entry without waiting. Returns `false` if it was dropped.
+    pub fn log(&self, entry: RequestLogEntry) -> bool {
+        match self.sender.try_send(entry) {
+            Ok(()) => {
+                metrics::record_request_log_queue_depth(self.queue_depth());
+                true
+            }
+            Err(mpsc::error::TrySendError::Full(_)) => {
+                metrics::record_request_log_dropped("buffer_full", 1);
+                false
+            }
+            Err(mpsc::error::TrySendError::Closed(_)) => {
+                metrics::record_request_log_dropped("shutdown", 1);
+                false
+            }
+        }
+    }
+
+    /// Entries waiting in the channel.
+    pub fn queue_depth(&self) -> usize {
+        self.sender.max_capacity() - self.sender.capacity()
+    }
+
+    /// Stops the writer after flushing everything queued so far. Entries
+    /// logged afterwards are dropped. Safe to call more than once.
+    pub async fn shutdown(&self) {
+        let handle = self.writer.lock().ok().and_then(|mut writer| writer.take());
+
+        if let Some(WriterHandle { shutdown, task }) = handle {
+            let _ = shutdown.send(());
+            if let Err(e) = task.await {
+                tracing::error!("Request log writer failed: {}", e);
+            }
+        }
+    }
+}
+
+async fn run_writer<S: RequestLogSink>(
+    sink: S,
+    mut receiver: mpsc::Receiver<RequestLogEntry>,
+    mut shutdown: oneshot::Receiver<()>,
+    settings: RequestLogSettings,
+) {
+    let mut batch = Vec::with_capacity(settings.batch_size);
+    let mut interval = tokio::time::interval_at(
+        tokio::time::Instant::now() + settings.flush_interval,
+        settings.flush_interval,
+    );
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            received = receiver.recv() => match received {
+                Some(entry) => {
+                    batch.push(entry);
+                    if batch.len() >= settings.batch_size {
+                        flush(&sink, &mut batch).await;
+                    }
+                }
+                None => break,
+            },
+            _ = interval.tick() => {
+                if !batch.is_empty() {
+                    flush(&sink, &mut batch).await;
+                }
+            }
+            _ = &mut shutdown => break,
+        }
+        metrics::record_request_log_queue_depth(receiver.len());
+    }
+
+    // Drain whatever is still queued, then write it out.
+    receiver.close();
+    while let Some(entry) = receiver.recv().await {
+        batch.push(entry);
+        if batch.len() >= settings.batch_size {
+            flush(&sink, &mut batch).await;
+        }
+    }
+    if !batch.is_empty() {
+        flush(&sink, &mut batch).await;
+    }
+    metrics::record_request_log_queue_depth(0);
+}
+
+async fn flush<S: RequestLogSink>(sink: &S, batch: &mut Vec<RequestLogEntry>) {
+    let started = Instant::now();
+    let result = sink.write_batch(batch).await;
+    metrics::record_request_log_flush(batch.len(), started.elapsed());
+
+    match result {
+        Ok(()) => metrics::record_job_outcome("request_log", "success"),
+        Err(e) => {
+            tracing::error!("Failed to write {} request log entries: {}", batch.len(), e);
+            metrics::record_job_outcome("request_log", "failure");
+            metrics::record_request_log_dropped("write_failed", batch.len() as u64);
+        }
+    }
+
+    batch.clear();
+}
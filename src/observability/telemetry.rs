@@ -0,0 +1,283 @@
+use anyhow::Result;
+use axum::{extract::MatchedPath, http::Request};
+use opentelemetry::{
+    global,
+    trace::{Span as _, SpanKind, Tracer as _, TracerProvider as _},
+    Context, KeyValue,
+};
+use opentelemetry_http::HeaderExtractor;
+use opentelemetry_otlp::{Protocol, SpanExporter, WithExportConfig};
+use opentelemetry_sdk::{
+    propagation::TraceContextPropagator,
+    trace::{SdkTracerProvider, Tracer},
+    Resource,
+};
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+use tracing::{field::Visit, Level, Span, Subscriber};
+use tower_http::trace::{DefaultOnResponse, OnResponse};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::{
+    filter::Targets, layer::Context as LayerContext, layer::SubscriberExt,
+    registry::LookupSpan, util::SubscriberInitExt, EnvFilter, Layer,
+};
+
+use crate::{
+    config::Config,
+    middleware::request_logging::extract_account_from_path,
+    observability::{
+        logging::{fmt_layer, RedactingMakeWriter, Redactor, RequestContextLayer, REQUEST_SPAN_NAME},
+        metrics::route_label,
+    },
+};
+
+pub const TRACER_NAME: &str = "metered-finance-api";
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+const SQLX_QUERY_TARGET: &str = "sqlx::query";
+const DEFAULT_LOG_FILTER: &str = "info,axum=info,tower_http=info";
+
+/// Keeps the tracer provider alive for the lifetime of the process and
+/// flushes pending spans on shutdown.
+pub struct TelemetryGuard {
+    provider: Option<SdkTracerProvider>,
+}
+
+impl TelemetryGuard {
+    pub fn shutdown(self) {
+        if let Some(provider) = self.provider {
+            if let Err(e) = provider.shutdown() {
+                tracing::error!("Failed to shut down tracer provider: {}", e);
+            }
+        }
+    }
+}
+
+/// Builds a tracer provider that batches spans to an OTLP/HTTP collector.
+///
+/// `endpoint` is the collector base URL (e.g. `http://localhost:4318`);
+/// spans are posted to `{endpoint}/v1/traces`.
+pub fn build_tracer_provider(endpoint: &str, service_name: &str) -> Result<SdkTracerProvider> {
+    let exporter = SpanExporter::builder()
+        .with_http()
+        .with_protocol(Protocol::HttpBinary)
+        .with_endpoint(format!("{}/v1/traces", endpoint.trim_end_matches('/')))
+        .build()?;
+
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(
+            Resource::builder()
+                .with_service_name(service_name.to_string())
+                .build(),
+        )
+        .build();
+
+    Ok(provider)
+}
+
+/// Layers exporting spans to OpenTelemetry: one bridging `tracing` spans and
+/// one turning sqlx query events into client spans.
+pub fn otel_layers<S>(tracer: Tracer) -> impl Layer<S>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    let spans = tracing_opentelemetry::layer()
+        .with_tracer(tracer.clone())
+        .with_filter(otel_span_filter());
+    let queries = SqlxQuerySpanLayer::new(tracer)
+        .with_filter(Targets::new().with_target(SQLX_QUERY_TARGET, Level::DEBUG));
+
+    spans.and_then(queries)
+}
+
+fn otel_span_filter() -> EnvFilter {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| DEFAULT_LOG_FILTER.into());
+    match "sqlx::query=off".parse() {
+        Ok(directive) => filter.add_directive(directive),
+        Err(_) => filter,
+    }
+}
+
+/// Installs the global subscriber. Logs are written as text or JSON through
+/// the redactor; OTLP export is only enabled when `OTEL_ENABLED=true`.
+pub fn init_telemetry(config: &Config) -> Result<TelemetryGuard> {
+    global::set_text_map_propagator(TraceContextPropagator::new());
+
+    let redactor = Redactor::new(
+        &config.log_redact_fields,
+        &std::env::var("ADMIN_KEY").into_iter().collect::<Vec<_>>(),
+    );
+    let fmt_filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| DEFAULT_LOG_FILTER.into());
+    let fmt_layer = fmt_layer(
+        config.log_format,
+        RedactingMakeWriter::new(std::io::stdout, Arc::new(redactor)),
+    )
+    .with_filter(fmt_filter);
+
+    let provider = if config.otel_enabled {
+        Some(build_tracer_provider(
+            &config.otel_exporter_endpoint,
+            &config.otel_service_name,
+        )?)
+    } else {
+        None
+    };
+
+    let otel_layer = provider
+        .as_ref()
+        .map(|provider| otel_layers(provider.tracer(TRACER_NAME)));
+
+    tracing_subscriber::registry()
+        .with(RequestContextLayer)
+        .with(fmt_layer)
+        .with(otel_layer)
+        .try_init()?;
+
+    if let Some(provider) = &provider {
+        global::set_tracer_provider(provider.clone());
+    }
+
+    Ok(TelemetryGuard { provider })
+}
+
+/// Extracts a remote parent from W3C `traceparent`/`tracestate` headers.
+pub fn extract_trace_context(headers: &http::HeaderMap) -> Context {
+    global::get_text_map_propagator(|propagator| propagator.extract(&HeaderExtractor(headers)))
+}
+
+/// Root span for an incoming request, used with `TraceLayer::make_span_with`.
+///
+/// `key_id` and the response status are recorded later by the auth middleware
+/// and [`record_response_status`].
+pub fn make_request_span<B>(req: &Request<B>) -> Span {
+    let route = route_label(req.extensions().get::<MatchedPath>());
+    let request_id = req
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+
+    let account_id = extract_account_from_path(req.uri().path()).unwrap_or_default();
+
+    let span = tracing::info_span!(
+        REQUEST_SPAN_NAME,
+        otel.name = %format!("{} {}", req.method(), route),
+        otel.kind = "server",
+        http.request.method = %req.method(),
+        http.route = %route,
+        url.path = %req.uri().path(),
+        request_id = %request_id,
+        key_id = tracing::field::Empty,
+        account_id = %account_id,
+        http.response.status_code = tracing::field::Empty,
+    );
+
+    // Without an OpenTelemetry layer there is nothing to attach the parent to.
+    let _ = span.set_parent(extract_trace_context(req.headers()));
+
+    span
+}
+
+/// `TraceLayer::on_response` callback that records the status code on the
+/// request span before delegating to the default response logging.
+pub fn record_response_status<B>(response: &http::Response<B>, latency: Duration, span: &Span) {
+    span.record("http.response.status_code", response.status().as_u16());
+    DefaultOnResponse::default().on_response(response, latency, span);
+}
+
+/// Records the authenticated key on the current request span.
+pub fn record_key_id(key_id: &str) {
+    Span::current().record("key_id", key_id);
+}
+
+/// Emits a `db.query` client span for every statement sqlx logs.
+///
+/// sqlx reports each statement once it finishes, with its elapsed time, so
+/// the span is back-dated to its start and parented to the active context.
+pub struct SqlxQuerySpanLayer {
+    tracer: Tracer,
+}
+
+impl SqlxQuerySpanLayer {
+    pub fn new(tracer: Tracer) -> Self {
+        Self { tracer }
+    }
+}
+
+impl<S> Layer<S> for SqlxQuerySpanLayer
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    fn on_event(&self, event: &tracing::Event<'_>, _ctx: LayerContext<'_, S>) {
+        if event.metadata().target() != SQLX_QUERY_TARGET {
+            return;
+        }
+
+        let mut query = SqlxQueryFields::default();
+        event.record(&mut query);
+
+        let end = SystemTime::now();
+        let start = end
+            .checked_sub(Duration::from_secs_f64(query.elapsed_secs.max(0.0)))
+            .unwrap_or(end);
+        let statement = if query.statement.trim().is_empty() {
+            query.summary.clone()
+        } else {
+            query.statement.trim().to_string()
+        };
+
+        let mut span = self
+            .tracer
+            .span_builder("db.query")
+            .with_kind(SpanKind::Client)
+            .with_start_time(start)
+            .with_attributes(vec![
+                KeyValue::new("db.system.name", "postgresql"),
+                KeyValue::new("db.query.summary", query.summary),
+                KeyValue::new("db.query.text", statement),
+                KeyValue::new("db.response.returned_rows", query.rows_returned as i64),
+                KeyValue::new("db.response.affected_rows", query.rows_affected as i64),
+            ])
+            .start_with_context(&self.tracer, &Context::current());
+        span.end_with_timestamp(end);
+    }
+}
+
+#[derive(Default)]
+struct SqlxQueryFields {
+    summary: String,
+    statement: String,
+    elapsed_secs: f64,
+    rows_returned: u64,
+    rows_affected: u64,
+}
+
+impl Visit for SqlxQueryFields {
+    fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+        match field.name() {
+            "summary" => self.summary = value.to_string(),
+            "db.statement" => self.statement = value.to_string(),
+            _ => {}
+        }
+    }
+
+    fn record_f64(&mut self, field: &tracing::field::Field, value: f64) {
+        if field.name() == "elapsed_secs" {
+            self.elapsed_secs = value;
+        }
+    }
+
+    fn record_u64(&mut self, field: &tracing::field::Field, value: u64) {
+        match field.name() {
+            "rows_returned" => self.rows_returned = value,
+            "rows_affected" => self.rows_affected = value,
+            _ => {}
+        }
+    }
+
+    fn record_debug(&mut self, _field: &tracing::field::Field, _value: &dyn std::fmt::Debug) {}
+}
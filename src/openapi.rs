@@ -7,13 +7,28 @@ use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
 use crate::models::{
-    common::{Cursor, ErrorCode, ErrorDetail, ErrorResponse, PaginatedResponse, PaginationParams},
-    finance::{Currency, FailureReason, TransactionFilters, TransactionStatus, TransactionType},
+    balance::{BalanceHistoryPoint, BalanceHistoryResponse},
+    batch::{BatchItemResult, BatchItemStatus, BatchJobResponse, BatchJobStatus, BatchMode, BatchParams, BatchResponse},
+    export::{ExportFormat, ExportJobResponse, ExportJobStatus},
+    common::{Cursor, ErrorCode, ErrorDetail, ErrorResponse, PaginatedResponse, PaginationParams, SortOrder},
+    statement::{AccountStatement, AccountStatementFormat, StatementEntry, TransactionTypeTotal},
+    reconciliation::{CsvColumnMapping, ImportStatementRequest, ManualMatchRequest, MatchMethod, MatchStatus, ReconciliationReport, ReconciliationSummary, StatementFormat, StatementImportResponse, StatementLineResponse},
+    finance::{Currency, FailureReason, TransactionFilters, TransactionSortField, TransactionStatus, TransactionType},
     keys::Scope,
-    quota::{QuotaLimits, QuotaUsage, QuotaUsageStats, QuotaStatus},
-    requests::{CreateAccountRequest, CreateApiKeyRequest, CreateTransactionRequest, UpdateAccountRequest, UpdateApiKeyRequest},
+    lifecycle::{AccountStatus, AccountStatusChangeResponse, AccountStatusEventResponse, ChangeAccountStatusRequest},
+    limits::{AccountLimitsResponse, CurrencyLimits, SetAccountLimitsRequest},
+    disputes::{AddDisputeEvidenceRequest, DisputeDetailResponse, DisputeEvidenceResponse, DisputeOutcome, DisputeResponse, DisputeStatus, EvidenceFileInfo, EvidenceFileUpload, OpenDisputeRequest, ResolveDisputeRequest},
+    fraud::{FraudAction, FraudCondition, FraudDecision, FraudReviewResponse, FraudReviewStatus, FraudRule, FraudRuleMatch, FraudRuleRequest, ResolveFraudReviewRequest},
+    quota::{BillingCycleSettings, QuotaAlertSettings, QuotaAlertStatus, QuotaLimits, QuotaPeriod, QuotaStatus, QuotaThresholdEvent, QuotaThresholdState, QuotaUsage, QuotaUsageStats, UsageGranularity, UsageHistoryFormat, UsageHistoryParams, UsageHistoryPoint, UsageHistoryResponse},
+    fees::{AccountFeeScheduleResponse, AssignFeeScheduleRequest, FeeBreakdown, FeeCap, FeeRevenueLine, FeeRevenueParams, FeeRevenueReport, FeeRevenueTotal, FeeRule, FeeScheduleRequest, FeeScheduleResponse, FeeTier, TransactionFee},
+    metadata::{MetadataSchemaResponse, MetadataTarget, MetadataViolation, RegisterMetadataSchemaRequest},
+    payouts::{AchAccountType, BankDetails, CreatePayoutBatchRequest, CreatePayoutDestinationRequest, CreatePayoutRequest, DestinationKind, DestinationStatus, PayoutBatchDetailResponse, PayoutBatchResponse, PayoutBatchStatus, PayoutDestinationResponse, PayoutFileFormat, PayoutResponse, PayoutStatus, ReturnPayoutRequest},
+    schedules::{CreateScheduleRequest, Recurrence, RetryPolicy, ScheduleRunResponse, ScheduleRunStatus, ScheduleStatus, TransactionScheduleResponse},
+    requests::{CreateAccountRequest, CreateApiKeyRequest, CreateTransactionRequest, UpdateAccountRequest, UpdateApiKeyRequest, UpdateBillingCycleRequest, UpdateQuotaAlertsRequest},
+    reversals::{AdjustTransactionRequest, ReverseTransactionRequest, TransactionLinks},
     responses::{AccountResponse, BalanceResponse, KeyCreatedResponse, KeyInfoResponse, TransactionResponse, UsageResponse},
-    analytics::{AnalyticsResponse, EndpointStats, HourlyVolume, RequestStats, StatusCodeStats, TimeRangeFilter},
+    analytics::{AnalyticsQuery, AnalyticsResponse, EndpointStats, ErrorCodeStats, HourlyVolume, RequestStats, StatusClass, StatusCodeStats, TimeBucket, TimeRangeFilter, VolumeBucket},
+    rollups::AnalyticsSource,
 };
 
 #[derive(OpenApi)]
@@ -87,19 +102,102 @@ All errors follow a consistent format:
         // Health check endpoints
         crate::handlers::health::health_live,
         crate::handlers::health::health_ready,
+        crate::handlers::metrics::metrics,
         
         // Account endpoints
         crate::handlers::accounts::create_account,
         crate::handlers::accounts::get_account,
         crate::handlers::accounts::list_accounts,
         crate::handlers::accounts::update_account,
+        crate::handlers::accounts::change_account_status,
+        crate::handlers::accounts::admin_change_account_status,
+        crate::handlers::accounts::get_account_status_history,
+        crate::handlers::accounts::get_account_limits,
+        crate::handlers::accounts::set_account_limits,
+        crate::handlers::accounts::delete_account_limits,
         
         // Transaction endpoints
         crate::handlers::transactions::create_transaction,
+        crate::handlers::transactions::create_transaction_batch,
+        crate::handlers::transactions::get_transaction_batch_job,
+        crate::handlers::exports::export_transactions,
+        crate::handlers::exports::get_export_job,
+        crate::handlers::exports::download_export,
         crate::handlers::transactions::get_transaction,
+        crate::handlers::transactions::reverse_transaction,
+        crate::handlers::transactions::adjust_transaction,
         crate::handlers::transactions::list_transactions,
         crate::handlers::transactions::get_account_transactions,
         crate::handlers::transactions::get_account_balance,
+        crate::handlers::transactions::get_account_balance_history,
+        crate::handlers::statements::get_account_statement,
+        
+        // Fraud screening endpoints
+        crate::handlers::fraud::list_fraud_rules,
+        crate::handlers::fraud::create_fraud_rule,
+        crate::handlers::fraud::update_fraud_rule,
+        crate::handlers::fraud::delete_fraud_rule,
+        crate::handlers::fraud::list_fraud_reviews,
+        crate::handlers::fraud::approve_fraud_review,
+        crate::handlers::fraud::reject_fraud_review,
+        
+        // Schedule endpoints
+        crate::handlers::schedules::create_schedule,
+        crate::handlers::schedules::list_schedules,
+        crate::handlers::schedules::get_schedule,
+        crate::handlers::schedules::get_schedule_runs,
+        crate::handlers::schedules::pause_schedule,
+        crate::handlers::schedules::resume_schedule,
+        crate::handlers::schedules::cancel_schedule,
+        
+        // Dispute endpoints
+        crate::handlers::disputes::get_dispute,
+        crate::handlers::disputes::add_dispute_evidence,
+        crate::handlers::disputes::download_dispute_evidence,
+        crate::handlers::disputes::submit_dispute,
+        crate::handlers::disputes::accept_dispute,
+        crate::handlers::disputes::open_dispute,
+        crate::handlers::disputes::list_disputes,
+        crate::handlers::disputes::resolve_dispute,
+        
+        // Payout endpoints
+        crate::handlers::payouts::create_payout_destination,
+        crate::handlers::payouts::list_payout_destinations,
+        crate::handlers::payouts::delete_payout_destination,
+        crate::handlers::payouts::create_payout,
+        crate::handlers::payouts::get_payout,
+        crate::handlers::payouts::list_account_payouts,
+        crate::handlers::payouts::create_payout_batch,
+        crate::handlers::payouts::list_payout_batches,
+        crate::handlers::payouts::get_payout_batch,
+        crate::handlers::payouts::download_payout_batch_file,
+        crate::handlers::payouts::mark_payout_batch_sent,
+        crate::handlers::payouts::mark_payout_batch_settled,
+        crate::handlers::payouts::mark_payout_batch_returned,
+        crate::handlers::payouts::return_payout,
+        
+        // Fee endpoints
+        crate::handlers::fees::create_fee_schedule,
+        crate::handlers::fees::list_fee_schedules,
+        crate::handlers::fees::get_fee_schedule,
+        crate::handlers::fees::update_fee_schedule,
+        crate::handlers::fees::assign_fee_schedule,
+        crate::handlers::fees::unassign_fee_schedule,
+        crate::handlers::fees::get_account_fee_schedule,
+        crate::handlers::fees::fee_revenue,
+        
+        // Metadata schema endpoints
+        crate::handlers::metadata::put_metadata_schema,
+        crate::handlers::metadata::list_metadata_schemas,
+        crate::handlers::metadata::get_metadata_schema,
+        crate::handlers::metadata::delete_metadata_schema,
+        
+        // Reconciliation endpoints
+        crate::handlers::reconciliation::import_statement,
+        crate::handlers::reconciliation::get_reconciliation_report,
+        crate::handlers::reconciliation::rematch_statement,
+        crate::handlers::reconciliation::match_statement_line,
+        crate::handlers::reconciliation::unmatch_statement_line,
         
         // API Key management endpoints (Admin only)
         crate::handlers::keys::create_api_key,
@@ -111,6 +209,11 @@ All errors follow a consistent format:
         // Usage endpoints
         crate::handlers::usage::get_own_usage,
         crate::handlers::usage::get_key_usage,
+        crate::handlers::usage::update_quota_alerts,
+        crate::handlers::usage::update_billing_cycle,
+        crate::handlers::usage::list_quota_alert_events,
+        crate::handlers::usage::get_own_usage_history,
+        crate::handlers::usage::get_key_usage_history,
         
         // Analytics endpoints
         crate::handlers::analytics::get_own_analytics,
@@ -135,18 +238,140 @@ All errors follow a consistent format:
             TransactionStatus,
             FailureReason,
             TransactionFilters,
+            TransactionSortField,
+            SortOrder,
+            
+            // Batch schemas
+            BatchMode,
+            BatchParams,
+            BatchItemStatus,
+            BatchItemResult,
+            BatchResponse,
+            BatchJobStatus,
+            BatchJobResponse,
+            
+            // Export schemas
+            ExportFormat,
+            ExportJobStatus,
+            ExportJobResponse,
+            
+            // Statement schemas
+            AccountStatementFormat,
+            AccountStatement,
+            StatementEntry,
+            TransactionTypeTotal,
+            
+            // Reconciliation schemas
+            StatementFormat,
+            CsvColumnMapping,
+            ImportStatementRequest,
+            ManualMatchRequest,
+            MatchStatus,
+            MatchMethod,
+            StatementImportResponse,
+            StatementLineResponse,
+            ReconciliationSummary,
+            ReconciliationReport,
+            
+            // Account lifecycle schemas
+            AccountStatus,
+            ChangeAccountStatusRequest,
+            AccountStatusEventResponse,
+            AccountStatusChangeResponse,
+            
+            // Fraud screening schemas
+            FraudDecision,
+            FraudAction,
+            FraudCondition,
+            FraudRule,
+            FraudRuleRequest,
+            FraudRuleMatch,
+            FraudReviewStatus,
+            FraudReviewResponse,
+            ResolveFraudReviewRequest,
+            
+            // Schedule schemas
+            Recurrence,
+            RetryPolicy,
+            ScheduleStatus,
+            ScheduleRunStatus,
+            CreateScheduleRequest,
+            TransactionScheduleResponse,
+            ScheduleRunResponse,
+            
+            // Dispute schemas
+            DisputeStatus,
+            DisputeOutcome,
+            OpenDisputeRequest,
+            DisputeResponse,
+            DisputeDetailResponse,
+            EvidenceFileUpload,
+            EvidenceFileInfo,
+            AddDisputeEvidenceRequest,
+            DisputeEvidenceResponse,
+            ResolveDisputeRequest,
+            
+            // Payout schemas
+            AchAccountType,
+            BankDetails,
+            DestinationKind,
+            DestinationStatus,
+            CreatePayoutDestinationRequest,
+            PayoutDestinationResponse,
+            PayoutStatus,
+            CreatePayoutRequest,
+            PayoutResponse,
+            PayoutFileFormat,
+            PayoutBatchStatus,
+            CreatePayoutBatchRequest,
+            ReturnPayoutRequest,
+            PayoutBatchResponse,
+            PayoutBatchDetailResponse,
+            
+            // Fee schemas
+            FeeTier,
+            FeeRule,
+            FeeCap,
+            FeeBreakdown,
+            TransactionFee,
+            FeeScheduleRequest,
+            FeeScheduleResponse,
+            AssignFeeScheduleRequest,
+            AccountFeeScheduleResponse,
+            FeeRevenueParams,
+            FeeRevenueLine,
+            FeeRevenueTotal,
+            FeeRevenueReport,
+            
+            // Metadata schema schemas
+            MetadataTarget,
+            MetadataViolation,
+            RegisterMetadataSchemaRequest,
+            MetadataSchemaResponse,
+            
+            // Account limit schemas
+            CurrencyLimits,
+            SetAccountLimitsRequest,
+            AccountLimitsResponse,
             
             // Request schemas
             CreateAccountRequest,
             UpdateAccountRequest,
             CreateTransactionRequest,
+            ReverseTransactionRequest,
+            AdjustTransactionRequest,
             CreateApiKeyRequest,
             UpdateApiKeyRequest,
+            UpdateQuotaAlertsRequest,
+            UpdateBillingCycleRequest,
             
             // Response schemas
             AccountResponse,
             TransactionResponse,
+            TransactionLinks,
             BalanceResponse,
+            BalanceHistoryPoint,
+            BalanceHistoryResponse,
             KeyCreatedResponse,
             KeyInfoResponse,
             UsageResponse,
@@ -159,6 +384,17 @@ All errors follow a consistent format:
             QuotaUsage,
             QuotaUsageStats,
             QuotaStatus,
+            QuotaPeriod,
+            QuotaAlertSettings,
+            BillingCycleSettings,
+            QuotaAlertStatus,
+            QuotaThresholdState,
+            QuotaThresholdEvent,
+            UsageGranularity,
+            UsageHistoryFormat,
+            UsageHistoryParams,
+            UsageHistoryPoint,
+            UsageHistoryResponse,
             
             // Analytics schemas
             AnalyticsResponse,
@@ -166,12 +402,25 @@ All errors follow a consistent format:
             EndpointStats,
             StatusCodeStats,
             HourlyVolume,
+            ErrorCodeStats,
+            VolumeBucket,
+            TimeBucket,
+            StatusClass,
+            AnalyticsSource,
+            AnalyticsQuery,
             TimeRangeFilter,
         )
     ),
     tags(
         (name = "accounts", description = "Account management operations"),
         (name = "transactions", description = "Transaction processing and retrieval"),
+        (name = "reconciliation", description = "Bank statement import and matching"),
+        (name = "schedules", description = "Scheduled and recurring transactions"),
+        (name = "disputes", description = "Chargeback disputes, evidence and outcomes"),
+        (name = "payouts", description = "Payout destinations, payouts and settlement batches"),
+        (name = "fees", description = "Fee schedules, charged fees and fee revenue"),
+        (name = "metadata", description = "Metadata JSON Schemas and limits"),
+        (name = "fraud", description = "Fraud screening rules and review queue (Admin only)"),
         (name = "keys", description = "API key management (Admin only)"),
         (name = "usage", description = "Usage and quota monitoring"),
         (name = "analytics", description = "Request analytics and statistics"),
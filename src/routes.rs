@@ -1,17 +1,22 @@
 use axum::{
+    extract::DefaultBodyLimit,
     middleware,
-    routing::{delete, get, patch, post},
+    routing::{delete, get, patch, post, put},
     Router,
 };
 use std::sync::Arc;
 
 use crate::{
     app::AppState,
-    handlers::{accounts, health, keys, transactions, usage},
+    handlers::{
+        accounts, disputes, exports, fees, fraud, health, keys, metadata, payouts,
+        reconciliation, schedules, statements, transactions, usage,
+    },
     middleware::{
         auth::{require_admin_auth, require_client_auth},
         rate_limit::check_rate_limit_and_quota,
     },
+    models::disputes::DisputeSettings,
 };
 
 
@@ -27,14 +32,78 @@ pub fn build_routes(state: Arc<AppState>) -> Router {
         .route("/accounts", get(accounts::list_accounts))
         .route("/accounts/:account_id", get(accounts::get_account))
         .route("/accounts/:account_id", patch(accounts::update_account))
+        .route("/accounts/:account_id/status", post(accounts::change_account_status))
+        .route("/accounts/:account_id/status/history", get(accounts::get_account_status_history))
+        .route("/accounts/:account_id/limits", get(accounts::get_account_limits))
+        .route("/accounts/:account_id/limits", put(accounts::set_account_limits))
+        .route("/accounts/:account_id/limits", delete(accounts::delete_account_limits))
 
         .route("/transactions", post(transactions::create_transaction))
         .route("/transactions", get(transactions::list_transactions))
+        .route(
+            "/transactions/batch",
+            post(transactions::create_transaction_batch)
+                .layer(DefaultBodyLimit::max(state.config.batch_max_body_bytes)),
+        )
+        .route("/transactions/batch/jobs/:job_id", get(transactions::get_transaction_batch_job))
+        .route("/transactions/export", get(exports::export_transactions))
+        .route("/transactions/exports/:job_id", get(exports::get_export_job))
+        .route("/transactions/exports/:job_id/download", get(exports::download_export))
         .route("/transactions/:transaction_id", get(transactions::get_transaction))
+        .route("/transactions/:transaction_id/reverse", post(transactions::reverse_transaction))
+        .route("/transactions/:transaction_id/adjustments", post(transactions::adjust_transaction))
         .route("/accounts/:account_id/transactions", get(transactions::get_account_transactions))
         .route("/accounts/:account_id/balance", get(transactions::get_account_balance))
+        .route("/accounts/:account_id/balance/history", get(transactions::get_account_balance_history))
+        .route("/accounts/:account_id/statements", get(statements::get_account_statement))
+
+        .route("/reconciliation/imports", post(reconciliation::import_statement))
+        .route("/reconciliation/imports/:import_id", get(reconciliation::get_reconciliation_report))
+        .route("/reconciliation/imports/:import_id/rematch", post(reconciliation::rematch_statement))
+        .route("/reconciliation/lines/:line_id/match", post(reconciliation::match_statement_line))
+        .route("/reconciliation/lines/:line_id/unmatch", post(reconciliation::unmatch_statement_line))
+
+        .route("/schedules", post(schedules::create_schedule))
+        .route("/schedules", get(schedules::list_schedules))
+        .route("/schedules/:schedule_id", get(schedules::get_schedule))
+        .route("/schedules/:schedule_id/runs", get(schedules::get_schedule_runs))
+        .route("/schedules/:schedule_id/pause", post(schedules::pause_schedule))
+        .route("/schedules/:schedule_id/resume", post(schedules::resume_schedule))
+        .route("/schedules/:schedule_id/cancel", post(schedules::cancel_schedule))
+
+        .route("/disputes/:dispute_id", get(disputes::get_dispute))
+        .route(
+            "/disputes/:dispute_id/evidence",
+            post(disputes::add_dispute_evidence)
+                .layer(DefaultBodyLimit::max(
+                    DisputeSettings::from_config(&state.config).max_evidence_body_bytes(),
+                )),
+        )
+        .route(
+            "/disputes/:dispute_id/evidence/:evidence_id/file",
+            get(disputes::download_dispute_evidence),
+        )
+        .route("/disputes/:dispute_id/submit", post(disputes::submit_dispute))
+        .route("/disputes/:dispute_id/accept", post(disputes::accept_dispute))
+
+        .route("/accounts/:account_id/payout-destinations", post(payouts::create_payout_destination))
+        .route("/accounts/:account_id/payout-destinations", get(payouts::list_payout_destinations))
+        .route(
+            "/accounts/:account_id/payout-destinations/:destination_id",
+            delete(payouts::delete_payout_destination),
+        )
+        .route("/accounts/:account_id/payouts", get(payouts::list_account_payouts))
+        .route("/payouts", post(payouts::create_payout))
+        .route("/payouts/:payout_id", get(payouts::get_payout))
+        .route("/accounts/:account_id/fee-schedule", get(fees::get_account_fee_schedule))
+
+        .route("/metadata-schemas", get(metadata::list_metadata_schemas))
+        .route("/metadata-schemas/:target", put(metadata::put_metadata_schema))
+        .route("/metadata-schemas/:target", get(metadata::get_metadata_schema))
+        .route("/metadata-schemas/:target", delete(metadata::delete_metadata_schema))
 
         .route("/usage", get(usage::get_own_usage))
+        .route("/usage/history", get(usage::get_own_usage_history))
 
         .layer(middleware::from_fn_with_state(
             state.clone(),
@@ -54,7 +123,42 @@ pub fn build_routes(state: Arc<AppState>) -> Router {
         .route("/keys/:key_id", patch(keys::update_api_key))
         .route("/keys/:key_id", delete(keys::delete_api_key))
         
+        .route("/accounts/:account_id/status", post(accounts::admin_change_account_status))
+        
+        .route("/fraud/rules", get(fraud::list_fraud_rules))
+        .route("/fraud/rules", post(fraud::create_fraud_rule))
+        .route("/fraud/rules/:rule_id", put(fraud::update_fraud_rule))
+        .route("/fraud/rules/:rule_id", delete(fraud::delete_fraud_rule))
+        .route("/fraud/reviews", get(fraud::list_fraud_reviews))
+        .route("/fraud/reviews/:transaction_id/approve", post(fraud::approve_fraud_review))
+        .route("/fraud/reviews/:transaction_id/reject", post(fraud::reject_fraud_review))
+        
+        .route("/disputes", post(disputes::open_dispute))
+        .route("/disputes", get(disputes::list_disputes))
+        .route("/disputes/:dispute_id/resolve", post(disputes::resolve_dispute))
+        
+        .route("/payouts/batches", post(payouts::create_payout_batch))
+        .route("/payouts/batches", get(payouts::list_payout_batches))
+        .route("/payouts/batches/:batch_id", get(payouts::get_payout_batch))
+        .route("/payouts/batches/:batch_id/file", get(payouts::download_payout_batch_file))
+        .route("/payouts/batches/:batch_id/sent", post(payouts::mark_payout_batch_sent))
+        .route("/payouts/batches/:batch_id/settled", post(payouts::mark_payout_batch_settled))
+        .route("/payouts/batches/:batch_id/returned", post(payouts::mark_payout_batch_returned))
+        .route("/payouts/:payout_id/return", post(payouts::return_payout))
+
+        .route("/fee-schedules", post(fees::create_fee_schedule))
+        .route("/fee-schedules", get(fees::list_fee_schedules))
+        .route("/fee-schedules/:fee_schedule_id", get(fees::get_fee_schedule))
+        .route("/fee-schedules/:fee_schedule_id", put(fees::update_fee_schedule))
+        .route("/accounts/:account_id/fee-schedule", put(fees::assign_fee_schedule))
+        .route("/accounts/:account_id/fee-schedule", delete(fees::unassign_fee_schedule))
+        .route("/fees/revenue", get(fees::fee_revenue))
+        
         .route("/usage/:key_id", get(usage::get_key_usage))
+        .route("/usage/:key_id/alerts", get(usage::list_quota_alert_events))
+        .route("/usage/:key_id/alerts", patch(usage::update_quota_alerts))
+        .route("/usage/:key_id/history", get(usage::get_key_usage_history))
+        .route("/usage/:key_id/billing-cycle", patch(usage::update_billing_cycle))
 
         .layer(middleware::from_fn(require_admin_auth));
 
@@ -1,4 +1,5 @@
 use metered_finance_api::models::{
+    lifecycle::AccountStatus,
     requests::{CreateAccountRequest, UpdateAccountRequest},
     responses::AccountResponse,
 };
@@ -79,9 +80,13 @@ fn test_account_response_serialization() {
         metadata: Some(serde_json::json!({"test": "value"})),
         created_at: chrono::Utc::now(),
         updated_at: chrono::Utc::now(),
+        status: AccountStatus::Active,
+        status_reason: None,
+        status_changed_at: None,
     };
 
     let json = serde_json::to_string(&response).unwrap();
     assert!(json.contains("acc_123"));
     assert!(json.contains("metadata"));
+    assert!(json.contains("\"status\":\"active\""));
 }
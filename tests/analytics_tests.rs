@@ -0,0 +1,117 @@
+use chrono::{TimeZone, Utc};
+use metered_finance_api::models::analytics::{
+    AnalyticsQuery, RequestFilter, StatusClass, TimeBucket,
+};
+
+fn at(hour: u32) -> chrono::DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 1, 15, hour, 0, 0).unwrap()
+}
+
+#[test]
+fn test_status_class_parsing_and_range() {
+    assert_eq!("5xx".parse::<StatusClass>().unwrap(), StatusClass::ServerError);
+    assert_eq!("4XX".parse::<StatusClass>().unwrap(), StatusClass::ClientError);
+    assert!("6xx".parse::<StatusClass>().is_err());
+
+    assert_eq!(StatusClass::Success.range(), (200, 300));
+    assert_eq!(StatusClass::ServerError.to_string(), "5xx");
+
+    let parsed: StatusClass = serde_json::from_str("\"3xx\"").unwrap();
+    assert_eq!(parsed, StatusClass::Redirection);
+}
+
+#[test]
+fn test_time_bucket_count() {
+    assert_eq!("minute".parse::<TimeBucket>().unwrap(), TimeBucket::Minute);
+    assert_eq!(TimeBucket::default(), TimeBucket::Hour);
+
+    assert_eq!(TimeBucket::Hour.bucket_count(at(0), at(23)), 24);
+    assert_eq!(TimeBucket::Minute.bucket_count(at(0), at(1)), 61);
+    assert_eq!(TimeBucket::Day.bucket_count(at(0), at(0)), 1);
+}
+
+#[test]
+fn test_where_clause_with_time_range_only() {
+    let filter = RequestFilter::new(None, at(0), at(12));
+    let (clause, binds) = filter.where_clause();
+
+    assert_eq!(clause, "WHERE ts >= $1::timestamptz AND ts <= $2::timestamptz");
+    assert_eq!(binds, vec![at(0).to_rfc3339(), at(12).to_rfc3339()]);
+}
+
+#[test]
+fn test_where_clause_with_all_filters() {
+    let query = AnalyticsQuery {
+        start: Some(at(0)),
+        end: Some(at(12)),
+        path: Some("/api/accounts/*".to_string()),
+        method: Some("post".to_string()),
+        status_class: Some(StatusClass::ClientError),
+        bucket: None,
+    };
+    let filter = query.resolve(Some("key_123"), at(23)).unwrap();
+    let (clause, binds) = filter.where_clause();
+
+    assert_eq!(
+        clause,
+        "WHERE ts >= $1::timestamptz AND ts <= $2::timestamptz AND key_id = $3 \
+         AND starts_with(path, $4) AND method = $5 AND status >= $6::int AND status < $7::int"
+    );
+    assert_eq!(&binds[2..], ["key_123", "/api/accounts/", "POST", "400", "500"]);
+}
+
+#[test]
+fn test_where_clause_exact_path() {
+    let mut filter = RequestFilter::new(None, at(0), at(1));
+    filter.path = Some("/api/transactions".to_string());
+    let (clause, binds) = filter.where_clause();
+
+    assert!(clause.ends_with("AND path = $3"));
+    assert_eq!(binds[2], "/api/transactions");
+}
+
+#[test]
+fn test_query_defaults_to_last_seven_days() {
+    let filter = AnalyticsQuery::default().resolve(None, at(12)).unwrap();
+
+    assert_eq!(filter.end, at(12));
+    assert_eq!(filter.start, at(12) - chrono::Duration::days(7));
+    assert_eq!(filter.key_id, None);
+}
+
+#[test]
+fn test_query_validation() {
+    let inverted = AnalyticsQuery {
+        start: Some(at(12)),
+        end: Some(at(0)),
+        ..Default::default()
+    };
+    assert!(inverted.resolve(None, at(23)).is_err());
+
+    let bad_method = AnalyticsQuery {
+        method: Some("FETCH".to_string()),
+        ..Default::default()
+    };
+    assert!(bad_method.resolve(None, at(23)).is_err());
+
+    let bad_path = AnalyticsQuery {
+        path: Some("api/transactions".to_string()),
+        ..Default::default()
+    };
+    assert!(bad_path.resolve(None, at(23)).is_err());
+}
+
+#[test]
+fn test_query_rejects_too_many_buckets() {
+    let week_by_minute = AnalyticsQuery {
+        bucket: Some(TimeBucket::Minute),
+        ..Default::default()
+    };
+    assert!(week_by_minute.resolve(None, at(12)).is_err());
+
+    let week_by_hour = AnalyticsQuery {
+        bucket: Some(TimeBucket::Hour),
+        ..Default::default()
+    };
+    assert!(week_by_hour.resolve(None, at(12)).is_ok());
+}
@@ -0,0 +1,73 @@
+use chrono::{NaiveDate, TimeZone, Utc};
+use metered_finance_api::models::balance::{
+    daily_balances, last_snapshot_day, BalanceHistoryParams, BalanceHistoryPoint,
+    MAX_HISTORY_DAYS,
+};
+
+fn date(month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, month, day).unwrap()
+}
+
+#[test]
+fn test_history_period_defaults_to_thirty_days() {
+    let params = BalanceHistoryParams::default();
+
+    assert_eq!(params.period(date(3, 31)), Ok((date(3, 2), date(3, 31))));
+}
+
+#[test]
+fn test_history_period_validation() {
+    let reversed = BalanceHistoryParams {
+        from: Some(date(3, 10)),
+        to: Some(date(3, 9)),
+    };
+    assert!(reversed.period(date(3, 31)).is_err());
+
+    let too_long = BalanceHistoryParams {
+        from: Some(date(1, 1)),
+        to: Some(date(1, 1) + chrono::Duration::days(MAX_HISTORY_DAYS)),
+    };
+    assert!(too_long.period(date(3, 31)).is_err());
+
+    let longest = BalanceHistoryParams {
+        from: Some(date(1, 1)),
+        to: Some(date(1, 1) + chrono::Duration::days(MAX_HISTORY_DAYS - 1)),
+    };
+    assert!(longest.period(date(3, 31)).is_ok());
+}
+
+#[test]
+fn test_last_snapshot_day_waits_for_lag() {
+    let just_after_midnight = Utc.with_ymd_and_hms(2024, 3, 10, 0, 1, 0).unwrap();
+    assert_eq!(last_snapshot_day(just_after_midnight), date(3, 8));
+
+    let later = Utc.with_ymd_and_hms(2024, 3, 10, 0, 10, 0).unwrap();
+    assert_eq!(last_snapshot_day(later), date(3, 9));
+}
+
+#[test]
+fn test_daily_balances_carry_forward() {
+    let points = daily_balances(
+        date(3, 1),
+        date(3, 4),
+        100.0,
+        &[(date(3, 2), 25.0), (date(3, 4), -5.0)],
+    );
+
+    assert_eq!(
+        points,
+        vec![
+            BalanceHistoryPoint { date: date(3, 1), balance: 100.0 },
+            BalanceHistoryPoint { date: date(3, 2), balance: 125.0 },
+            BalanceHistoryPoint { date: date(3, 3), balance: 125.0 },
+            BalanceHistoryPoint { date: date(3, 4), balance: 120.0 },
+        ]
+    );
+}
+
+#[test]
+fn test_daily_balances_single_day() {
+    let points = daily_balances(date(3, 1), date(3, 1), 0.0, &[]);
+
+    assert_eq!(points, vec![BalanceHistoryPoint { date: date(3, 1), balance: 0.0 }]);
+}
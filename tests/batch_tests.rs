@@ -0,0 +1,137 @@
+use metered_finance_api::models::{
+    batch::{
+        check_accounts, decode_items, parse_batch, BatchError, BatchFormat, BatchItemStatus,
+        BatchMode, BatchResponse,
+    },
+    finance::Currency,
+};
+use serde_json::json;
+use std::collections::HashSet;
+
+fn item(account_id: &str, amount: f64) -> serde_json::Value {
+    json!({
+        "account_id": account_id,
+        "amount": amount,
+        "currency": "USD",
+        "transaction_type": "payment"
+    })
+}
+
+#[test]
+fn test_format_from_content_type() {
+    assert_eq!(BatchFormat::from_content_type(None), BatchFormat::Json);
+    assert_eq!(
+        BatchFormat::from_content_type(Some("application/json")),
+        BatchFormat::Json
+    );
+    assert_eq!(
+        BatchFormat::from_content_type(Some("application/x-ndjson; charset=utf-8")),
+        BatchFormat::Ndjson
+    );
+    assert_eq!(
+        BatchFormat::from_content_type(Some("Application/JSONL")),
+        BatchFormat::Ndjson
+    );
+}
+
+#[test]
+fn test_parse_json_array_and_ndjson() {
+    let body = json!([item("acc_1", 10.0), item("acc_2", 20.0)]).to_string();
+    let items = parse_batch(body.as_bytes(), BatchFormat::Json, 10).unwrap();
+    assert_eq!(items.len(), 2);
+
+    let body = format!("{}\n\n{}\n", item("acc_1", 10.0), item("acc_2", 20.0));
+    let items = parse_batch(body.as_bytes(), BatchFormat::Ndjson, 10).unwrap();
+    assert_eq!(items, vec![item("acc_1", 10.0), item("acc_2", 20.0)]);
+}
+
+#[test]
+fn test_parse_rejects_bad_batches() {
+    assert_eq!(
+        parse_batch(b"[]", BatchFormat::Json, 10),
+        Err(BatchError::Empty)
+    );
+    assert_eq!(
+        parse_batch(b"[1, 2, 3]", BatchFormat::Json, 2),
+        Err(BatchError::TooManyItems(2))
+    );
+    assert!(matches!(
+        parse_batch(b"{}", BatchFormat::Json, 10),
+        Err(BatchError::InvalidBody(_))
+    ));
+
+    let err = parse_batch(b"{}\n{not json}\n", BatchFormat::Ndjson, 10).unwrap_err();
+    assert!(err.to_string().contains("line 2"), "{}", err);
+}
+
+#[test]
+fn test_decode_reports_errors_per_item() {
+    let items = vec![
+        item("acc_1", 10.0),
+        item("acc_1", -5.0),
+        json!({"account_id": "acc_1"}),
+        item("acc_1", 1.234),
+    ];
+
+    let decoded = decode_items(&items);
+    assert_eq!(decoded[0].as_ref().unwrap().currency, Currency::USD);
+    assert_eq!(
+        decoded[1].as_ref().unwrap_err().message,
+        "Amount must be positive"
+    );
+    assert_eq!(decoded[2].as_ref().unwrap_err().code, "validation_error");
+    assert_eq!(
+        decoded[3].as_ref().unwrap_err().message,
+        "Amount must have at most 2 decimal places"
+    );
+}
+
+#[test]
+fn test_check_accounts_rejects_unknown_accounts() {
+    let existing: HashSet<String> = ["acc_1".to_string()].into();
+    let checked = check_accounts(
+        decode_items(&[item("acc_1", 10.0), item("acc_missing", 10.0)]),
+        &existing,
+    );
+
+    assert!(checked[0].is_ok());
+    let error = checked[1].as_ref().unwrap_err();
+    assert_eq!(error.code, "not_found");
+    assert_eq!(error.message, "Account 'acc_missing' not found");
+}
+
+#[test]
+fn test_rejected_batch_skips_valid_items() {
+    let existing: HashSet<String> = ["acc_1".to_string()].into();
+    let checked = check_accounts(
+        decode_items(&[item("acc_1", 10.0), item("acc_1", 0.0), item("acc_1", 5.0)]),
+        &existing,
+    );
+
+    let response = BatchResponse::rejected(BatchMode::AllOrNothing, &checked);
+    assert_eq!((response.total, response.succeeded, response.failed), (3, 0, 3));
+
+    let statuses: Vec<_> = response.results.iter().map(|r| r.status).collect();
+    assert_eq!(
+        statuses,
+        vec![
+            BatchItemStatus::Skipped,
+            BatchItemStatus::Failed,
+            BatchItemStatus::Skipped
+        ]
+    );
+    assert_eq!(response.results[1].index, 1);
+    assert!(response.results[1].error.is_some());
+    assert!(response.results[0].error.is_none());
+}
+
+#[test]
+fn test_batch_mode_parsing() {
+    assert_eq!(BatchMode::default(), BatchMode::BestEffort);
+    assert_eq!(
+        "all_or_nothing".parse::<BatchMode>().unwrap(),
+        BatchMode::AllOrNothing
+    );
+    assert_eq!(BatchMode::AllOrNothing.to_string(), "all_or_nothing");
+    assert!("some".parse::<BatchMode>().is_err());
+}
@@ -3,10 +3,9 @@ use metered_finance_api::models::common::{
 };
 
 #[test]
-fn test_cursor_encode_decode() {
-    let cursor = Cursor::encode("test_id_123");
-    let decoded = cursor.decode_string().unwrap();
-    assert_eq!(decoded, "test_id_123");
+fn test_cursor_serializes_as_opaque_string() {
+    let cursor = Cursor("abc.def".to_string());
+    assert_eq!(serde_json::to_string(&cursor).unwrap(), "\"abc.def\"");
 }
 
 #[test]
@@ -51,7 +50,8 @@ fn test_paginated_response() {
     let response = PaginatedResponse {
         data: data.clone(),
         has_more: true,
-        next_cursor: Some(Cursor::encode("cursor123")),
+        next_cursor: Some(Cursor("cursor123".to_string())),
+        prev_cursor: None,
     };
     
     assert_eq!(response.data, data);
@@ -0,0 +1,160 @@
+use axum::{http::StatusCode, response::IntoResponse};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::{Duration, Utc};
+use metered_finance_api::{
+    middleware::errors::AppError,
+    models::{
+        common::ErrorCode,
+        disputes::{
+            check_response_window, disputable_amount, sanitize_file_name, transition,
+            AddDisputeEvidenceRequest, DisputeAction, DisputeError, DisputeListParams,
+            DisputeOutcome, DisputeStatus, EvidenceFileUpload, OpenDisputeRequest,
+        },
+        finance::Currency,
+    },
+};
+use serde_json::json;
+
+#[test]
+fn test_merchant_transitions() {
+    assert_eq!(
+        transition(DisputeStatus::NeedsResponse, DisputeAction::Submit).unwrap(),
+        DisputeStatus::UnderReview
+    );
+    assert_eq!(
+        transition(DisputeStatus::NeedsResponse, DisputeAction::Accept).unwrap(),
+        DisputeStatus::Lost
+    );
+    assert_eq!(
+        transition(DisputeStatus::NeedsResponse, DisputeAction::Expire).unwrap(),
+        DisputeStatus::Lost
+    );
+
+    // Once submitted only an administrator decides the outcome.
+    assert!(transition(DisputeStatus::UnderReview, DisputeAction::Submit).is_err());
+    assert!(transition(DisputeStatus::UnderReview, DisputeAction::Accept).is_err());
+    assert!(transition(DisputeStatus::UnderReview, DisputeAction::Expire).is_err());
+}
+
+#[test]
+fn test_admin_resolves_only_open_disputes() {
+    for status in DisputeStatus::OPEN {
+        assert_eq!(
+            transition(status, DisputeAction::Resolve(DisputeOutcome::Won)).unwrap(),
+            DisputeStatus::Won
+        );
+        assert_eq!(
+            transition(status, DisputeAction::Resolve(DisputeOutcome::Lost)).unwrap(),
+            DisputeStatus::Lost
+        );
+    }
+
+    for status in [DisputeStatus::Won, DisputeStatus::Lost] {
+        assert!(!status.is_open());
+        let error = transition(status, DisputeAction::Resolve(DisputeOutcome::Won)).unwrap_err();
+        assert!(matches!(error, DisputeError::InvalidTransition { .. }));
+    }
+}
+
+#[test]
+fn test_response_window() {
+    let now = Utc::now();
+
+    assert!(check_response_window(DisputeStatus::NeedsResponse, now + Duration::hours(1), now).is_ok());
+    assert!(matches!(
+        check_response_window(DisputeStatus::NeedsResponse, now - Duration::seconds(1), now),
+        Err(DisputeError::DeadlinePassed(_))
+    ));
+    assert!(matches!(
+        check_response_window(DisputeStatus::UnderReview, now + Duration::hours(1), now),
+        Err(DisputeError::NotAcceptingEvidence(DisputeStatus::UnderReview))
+    ));
+}
+
+#[test]
+fn test_disputable_amount() {
+    assert_eq!(disputable_amount(100.0, 0.0, None, Currency::USD).unwrap(), 100.0);
+    assert_eq!(disputable_amount(100.0, 40.0, None, Currency::USD).unwrap(), 60.0);
+    assert_eq!(disputable_amount(100.0, 40.0, Some(25.0), Currency::USD).unwrap(), 25.0);
+
+    assert!(matches!(
+        disputable_amount(100.0, 40.0, Some(70.0), Currency::USD),
+        Err(DisputeError::AmountExceedsPayment { .. })
+    ));
+    assert!(disputable_amount(100.0, 100.0, None, Currency::USD).is_err());
+
+    let response = AppError::from(disputable_amount(100.0, 100.0, None, Currency::USD).unwrap_err())
+        .into_response();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[test]
+fn test_open_and_evidence_validation() {
+    let now = Utc::now();
+    let open: OpenDisputeRequest = serde_json::from_value(json!({
+        "transaction_id": "txn_1",
+        "reason": "Product not received"
+    }))
+    .unwrap();
+    assert!(open.validate(now).is_ok());
+
+    let mut past_deadline = open.clone();
+    past_deadline.evidence_due_by = Some(now - Duration::days(1));
+    assert!(past_deadline.validate(now).is_err());
+
+    let mut zero = open.clone();
+    zero.amount = Some(0.0);
+    assert!(zero.validate(now).is_err());
+
+    let evidence = AddDisputeEvidenceRequest {
+        description: "Delivery receipt".to_string(),
+        metadata: Some(json!({"tracking_number": "1Z999"})),
+        file: Some(EvidenceFileUpload {
+            file_name: "receipt.pdf".to_string(),
+            content_type: "application/pdf".to_string(),
+            content_base64: STANDARD.encode(b"%PDF-1.4"),
+        }),
+    };
+    assert!(evidence.validate().is_ok());
+    assert_eq!(evidence.file.as_ref().unwrap().decode(1024).unwrap(), b"%PDF-1.4");
+    assert!(evidence.file.as_ref().unwrap().decode(4).is_err());
+
+    let mut not_object = evidence.clone();
+    not_object.metadata = Some(json!(["a"]));
+    assert!(not_object.validate().is_err());
+}
+
+#[test]
+fn test_file_names_and_list_params() {
+    assert_eq!(sanitize_file_name("../../etc/passwd").as_deref(), Some("passwd"));
+    assert_eq!(sanitize_file_name("C:\\scans\\my receipt.pdf").as_deref(), Some("my_receipt.pdf"));
+    assert_eq!(sanitize_file_name(".."), None);
+    assert_eq!(sanitize_file_name(""), None);
+
+    let params = DisputeListParams::default();
+    assert_eq!(params.statuses().unwrap(), DisputeStatus::OPEN.to_vec());
+
+    let params = DisputeListParams {
+        status: Some("won, lost".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(
+        params.statuses().unwrap(),
+        vec![DisputeStatus::Won, DisputeStatus::Lost]
+    );
+
+    let params = DisputeListParams {
+        status: Some("pending".to_string()),
+        limit: Some(501),
+        ..Default::default()
+    };
+    assert!(params.statuses().is_err());
+    assert!(params.limit().is_err());
+
+    let response = AppError::from(DisputeError::NoEvidence).into_response();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    assert_eq!(
+        response.extensions().get::<ErrorCode>(),
+        Some(&ErrorCode::InvalidStatusTransition)
+    );
+}
@@ -207,4 +207,24 @@ fn test_not_found_with_specific_id() {
     if let AppError::NotFound(msg) = txn_err {
         assert!(msg.contains("txn_test_456"));
     }
-}
\ No newline at end of file
+}
+#[test]
+fn test_error_response_carries_error_code() {
+    use metered_finance_api::models::common::ErrorCode;
+
+    let response = AppError::QuotaExceeded.into_response();
+    assert_eq!(
+        response.extensions().get::<ErrorCode>(),
+        Some(&ErrorCode::QuotaExceeded)
+    );
+}
+
+#[test]
+fn test_error_code_from_status() {
+    use metered_finance_api::models::common::ErrorCode;
+
+    assert_eq!(ErrorCode::from_status(StatusCode::UNAUTHORIZED), Some(ErrorCode::Unauthorized));
+    assert_eq!(ErrorCode::from_status(StatusCode::TOO_MANY_REQUESTS), Some(ErrorCode::RateLimitExceeded));
+    assert_eq!(ErrorCode::from_status(StatusCode::BAD_GATEWAY), Some(ErrorCode::InternalError));
+    assert_eq!(ErrorCode::from_status(StatusCode::OK), None);
+}
@@ -0,0 +1,98 @@
+use chrono::{TimeZone, Utc};
+use metered_finance_api::models::{
+    export::{
+        csv_field, export_download_url, export_query, parse_export_filters, ExportFormat,
+        EXPORT_COLUMNS,
+    },
+    finance::{Currency, TransactionStatus, TransactionType},
+    responses::TransactionResponse,
+};
+use serde_json::json;
+
+fn transaction() -> TransactionResponse {
+    TransactionResponse {
+        transaction_id: "txn_1".to_string(),
+        account_id: "acc_1".to_string(),
+        amount: 12.5,
+        currency: Currency::EUR,
+        transaction_type: TransactionType::Refund,
+        status: TransactionStatus::Completed,
+        description: Some("Refund, \"partial\"".to_string()),
+        metadata: Some(json!({"order_id": "ord_42"})),
+        created_at: Utc.with_ymd_and_hms(2024, 3, 1, 12, 30, 0).unwrap(),
+        processed_at: None,
+        failure_reason: None,
+        fee: None,
+        links: None,
+    }
+}
+
+#[test]
+fn test_csv_header_matches_columns() {
+    assert_eq!(
+        ExportFormat::Csv.header(),
+        "transaction_id,account_id,amount,currency,transaction_type,status,description,metadata,created_at,processed_at\n"
+    );
+    assert_eq!(EXPORT_COLUMNS.len(), 10);
+    assert_eq!(ExportFormat::Ndjson.header(), "");
+}
+
+#[test]
+fn test_csv_row() {
+    assert_eq!(
+        ExportFormat::Csv.row(&transaction()),
+        "txn_1,acc_1,12.5,EUR,refund,completed,\"Refund, \"\"partial\"\"\",\
+         \"{\"\"order_id\"\":\"\"ord_42\"\"}\",2024-03-01T12:30:00.000000Z,\n"
+    );
+}
+
+#[test]
+fn test_csv_field_escaping() {
+    assert_eq!(csv_field("plain"), "plain");
+    assert_eq!(csv_field("a,b"), "\"a,b\"");
+    assert_eq!(csv_field("line\nbreak"), "\"line\nbreak\"");
+    assert_eq!(csv_field("=SUM(A1)"), "'=SUM(A1)");
+    assert_eq!(csv_field("-12.5"), "-12.5");
+}
+
+#[test]
+fn test_ndjson_row_is_transaction_response() {
+    let line = ExportFormat::Ndjson.row(&transaction());
+    assert!(line.ends_with('\n'));
+
+    let parsed: TransactionResponse = serde_json::from_str(line.trim_end()).unwrap();
+    assert_eq!(parsed.transaction_id, "txn_1");
+    assert_eq!(parsed.metadata, Some(json!({"order_id": "ord_42"})));
+}
+
+#[test]
+fn test_format_parsing_and_names() {
+    assert_eq!(ExportFormat::default(), ExportFormat::Csv);
+    assert_eq!("NDJSON".parse::<ExportFormat>().unwrap(), ExportFormat::Ndjson);
+    assert!("xlsx".parse::<ExportFormat>().is_err());
+    assert_eq!(ExportFormat::Ndjson.file_name("exp_1"), "exp_1.ndjson");
+    assert_eq!(ExportFormat::Csv.content_type(), "text/csv; charset=utf-8");
+    assert_eq!(
+        export_download_url("exp_1"),
+        "/api/transactions/exports/exp_1/download"
+    );
+}
+
+#[test]
+fn test_stored_query_round_trips_filters() {
+    let filters =
+        parse_export_filters("format=csv&background=true&status=pending,failed&sort_by=amount&sort_order=asc")
+            .unwrap();
+    assert_eq!(
+        filters.status,
+        Some(vec![TransactionStatus::Pending, TransactionStatus::Failed])
+    );
+
+    let (query, values) = export_query(&filters);
+    assert!(query.contains("WHERE 1=1 AND status IN ($1, $2)"), "{}", query);
+    assert!(query.contains("ORDER BY amount ASC, transaction_id ASC"), "{}", query);
+    assert!(!query.contains("LIMIT"));
+    assert_eq!(values, vec!["pending".to_string(), "failed".to_string()]);
+
+    assert!(parse_export_filters("status=bogus").is_err());
+}
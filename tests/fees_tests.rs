@@ -0,0 +1,231 @@
+use axum::{http::StatusCode, response::IntoResponse};
+use chrono::{Duration, TimeZone, Utc};
+use metered_finance_api::{
+    middleware::errors::AppError,
+    models::{
+        fees::{
+            find_rule, revenue_totals, AssignFeeScheduleRequest, FeeCap, FeeError, FeeRevenueLine,
+            FeeRevenueParams, FeeRule, FeeScheduleRequest, FeeTier, TransactionFee,
+        },
+        finance::{Currency, TransactionStatus, TransactionType},
+        responses::TransactionResponse,
+    },
+};
+use serde_json::json;
+
+fn rule(fixed: f64, percentage: f64) -> FeeRule {
+    FeeRule {
+        transaction_type: TransactionType::Payment,
+        currency: Currency::USD,
+        fixed,
+        percentage,
+        tiers: vec![],
+        min_fee: None,
+        max_fee: None,
+    }
+}
+
+fn tier(up_to: Option<f64>, fixed: f64, percentage: f64) -> FeeTier {
+    FeeTier { up_to, fixed, percentage }
+}
+
+#[test]
+fn test_fixed_plus_percentage_fee() {
+    let fee = rule(0.30, 2.9).compute(100.0).unwrap();
+    assert_eq!(fee.fixed, 0.30);
+    assert_eq!(fee.percentage_amount, 2.9);
+    assert_eq!(fee.subtotal, 3.2);
+    assert_eq!(fee.total, 3.2);
+    assert_eq!(fee.tier, None);
+    assert_eq!(fee.capped, None);
+
+    // Rounded to cents.
+    assert_eq!(rule(0.0, 2.9).compute(10.01).unwrap().total, 0.29);
+
+    // Nothing is charged when the fee rounds to zero.
+    assert!(rule(0.0, 0.0).compute(100.0).is_none());
+    assert!(rule(0.0, 1.0).compute(0.1).is_none());
+}
+
+#[test]
+fn test_fee_lowers_the_balance() {
+    let payment = 100.0;
+    let fee = rule(0.30, 2.9).compute(payment).unwrap();
+
+    assert_eq!(fee.ledger_amount(), -3.2);
+    let balance = TransactionType::Payment.ledger_amount(payment) + fee.ledger_amount();
+    assert!(balance < payment);
+    assert!((balance - 96.8).abs() < 1e-9);
+}
+
+#[test]
+fn test_min_and_max_caps() {
+    let mut capped = rule(0.0, 1.0);
+    capped.min_fee = Some(0.50);
+    capped.max_fee = Some(5.00);
+
+    let low = capped.compute(10.0).unwrap();
+    assert_eq!(low.subtotal, 0.1);
+    assert_eq!(low.total, 0.5);
+    assert_eq!(low.capped, Some(FeeCap::Min));
+
+    let high = capped.compute(1000.0).unwrap();
+    assert_eq!(high.total, 5.0);
+    assert_eq!(high.capped, Some(FeeCap::Max));
+
+    assert_eq!(capped.compute(200.0).unwrap().capped, None);
+}
+
+#[test]
+fn test_tiered_fee_prices_whole_amount_by_its_tier() {
+    let mut tiered = rule(0.0, 0.0);
+    tiered.tiers = vec![
+        tier(Some(100.0), 1.0, 0.0),
+        tier(Some(1000.0), 0.0, 1.0),
+        tier(None, 0.0, 0.5),
+    ];
+    assert!(tiered.validate().is_ok());
+
+    let small = tiered.compute(100.0).unwrap();
+    assert_eq!((small.tier, small.total), (Some(1), 1.0));
+    let middle = tiered.compute(500.0).unwrap();
+    assert_eq!((middle.tier, middle.total), (Some(2), 5.0));
+    let large = tiered.compute(5000.0).unwrap();
+    assert_eq!((large.tier, large.total), (Some(3), 25.0));
+
+    // Refund amounts are priced by their size.
+    assert_eq!(tiered.compute(-500.0).unwrap().total, 5.0);
+}
+
+#[test]
+fn test_rule_validation() {
+    assert!(rule(0.30, 2.9).validate().is_ok());
+    assert!(rule(-1.0, 0.0).validate().is_err());
+    assert!(rule(0.0, 101.0).validate().is_err());
+
+    let mut fee_on_fee = rule(1.0, 0.0);
+    fee_on_fee.transaction_type = TransactionType::Fee;
+    assert!(fee_on_fee.validate().is_err());
+
+    let mut caps = rule(0.0, 1.0);
+    caps.min_fee = Some(5.0);
+    caps.max_fee = Some(1.0);
+    assert!(caps.validate().is_err());
+
+    let mut tiers = rule(0.0, 0.0);
+    tiers.tiers = vec![tier(Some(100.0), 1.0, 0.0), tier(Some(50.0), 1.0, 0.0), tier(None, 0.0, 1.0)];
+    assert!(tiers.validate().is_err(), "bounds must ascend");
+    tiers.tiers = vec![tier(Some(100.0), 1.0, 0.0), tier(Some(200.0), 1.0, 0.0)];
+    assert!(tiers.validate().is_err(), "last tier must be unbounded");
+    tiers.tiers = vec![tier(None, 1.0, 0.0), tier(None, 0.0, 1.0)];
+    assert!(tiers.validate().is_err(), "only the last tier is unbounded");
+
+    let mut mixed = rule(1.0, 0.0);
+    mixed.tiers = vec![tier(None, 0.0, 1.0)];
+    assert!(mixed.validate().is_err());
+}
+
+#[test]
+fn test_schedule_request_and_assignment_validation() {
+    let req: FeeScheduleRequest = serde_json::from_value(json!({
+        "name": "Standard",
+        "plan": "standard",
+        "rules": [
+            {"transaction_type": "payment", "currency": "USD", "fixed": 0.30, "percentage": 2.9},
+            {"transaction_type": "payment", "currency": "EUR", "percentage": 1.4}
+        ]
+    }))
+    .unwrap();
+    assert!(req.active);
+    assert!(req.validate().is_ok());
+    assert_eq!(find_rule(&req.rules, TransactionType::Payment, Currency::EUR).unwrap().percentage, 1.4);
+    assert!(find_rule(&req.rules, TransactionType::Refund, Currency::USD).is_none());
+
+    let mut duplicate = req.clone();
+    duplicate.rules.push(rule(1.0, 0.0));
+    assert!(duplicate.validate().is_err());
+
+    let mut bad_plan = req.clone();
+    bad_plan.plan = Some("gold plan".to_string());
+    assert!(bad_plan.validate().is_err());
+
+    let mut unnamed = req;
+    unnamed.name = "  ".to_string();
+    assert!(unnamed.validate().is_err());
+
+    let by_plan: AssignFeeScheduleRequest = serde_json::from_value(json!({"plan": "standard"})).unwrap();
+    assert!(by_plan.validate().is_ok());
+    let both: AssignFeeScheduleRequest =
+        serde_json::from_value(json!({"plan": "standard", "fee_schedule_id": "fsch_1"})).unwrap();
+    assert!(both.validate().is_err());
+    let neither: AssignFeeScheduleRequest = serde_json::from_value(json!({})).unwrap();
+    assert!(neither.validate().is_err());
+}
+
+#[test]
+fn test_transaction_response_shows_fee() {
+    let mut transaction = TransactionResponse {
+        transaction_id: "txn_1".to_string(),
+        account_id: "acc_1".to_string(),
+        amount: 100.0,
+        currency: Currency::USD,
+        transaction_type: TransactionType::Payment,
+        status: TransactionStatus::Completed,
+        description: None,
+        metadata: None,
+        created_at: Utc::now(),
+        processed_at: None,
+        failure_reason: None,
+        fee: None,
+        links: None,
+    };
+    assert!(serde_json::to_value(&transaction).unwrap().get("fee").is_none());
+
+    transaction.fee = Some(TransactionFee {
+        fee_transaction_id: "txn_2".to_string(),
+        fee_schedule_id: "fsch_1".to_string(),
+        amount: 3.2,
+        currency: Currency::USD,
+        breakdown: rule(0.30, 2.9).compute(100.0).unwrap(),
+    });
+    let value = serde_json::to_value(&transaction).unwrap();
+    assert_eq!(value["fee"]["fee_transaction_id"], "txn_2");
+    assert_eq!(value["fee"]["breakdown"]["total"], 3.2);
+    assert!(value["fee"]["breakdown"].get("capped").is_none());
+}
+
+#[test]
+fn test_revenue_report_period_and_totals() {
+    let now = Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap();
+    let (from, to) = FeeRevenueParams::default().period(now).unwrap();
+    assert_eq!((from, to), (now - Duration::days(30), now));
+
+    let backwards = FeeRevenueParams {
+        from: Some(now),
+        to: Some(now - Duration::days(1)),
+        currency: None,
+    };
+    assert!(backwards.period(now).is_err());
+
+    let line = |currency, transaction_type, fee_count, fee_total| FeeRevenueLine {
+        currency,
+        transaction_type,
+        fee_schedule_id: "fsch_1".to_string(),
+        fee_count,
+        fee_total,
+        charged_volume: 0.0,
+    };
+    let totals = revenue_totals(&[
+        line(Currency::USD, TransactionType::Payment, 3, 9.6),
+        line(Currency::EUR, TransactionType::Payout, 1, 1.0),
+        line(Currency::USD, TransactionType::Payout, 2, 0.1),
+    ]);
+    assert_eq!(totals.len(), 2);
+    assert_eq!(totals[0].currency, Currency::EUR);
+    assert_eq!((totals[1].fee_count, totals[1].fee_total), (5, 9.7));
+
+    let response = AppError::from(FeeError::PlanTaken("standard".to_string())).into_response();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    let response = AppError::from(FeeError::NotFound("fsch_1".to_string())).into_response();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
@@ -101,16 +101,17 @@ fn test_account_id_generation() {
 fn test_transaction_filters() {
     let filters = TransactionFilters {
         account_id: Some("user_123".to_string()),
-        status: Some(TransactionStatus::Completed),
-        transaction_type: Some(TransactionType::Payment),
-        currency: Some(Currency::USD),
+        status: Some(vec![TransactionStatus::Completed]),
+        transaction_type: Some(vec![TransactionType::Payment]),
+        currency: Some(vec![Currency::USD]),
         created_after: None,
         created_before: None,
+        ..TransactionFilters::default()
     };
     
     assert_eq!(filters.account_id, Some("user_123".to_string()));
-    assert_eq!(filters.status, Some(TransactionStatus::Completed));
-    assert_eq!(filters.currency, Some(Currency::USD));
+    assert_eq!(filters.status, Some(vec![TransactionStatus::Completed]));
+    assert_eq!(filters.currency, Some(vec![Currency::USD]));
     assert_eq!(filters.created_after, None);
     assert_eq!(filters.created_before, None);
 }
@@ -0,0 +1,271 @@
+use axum::{http::StatusCode, response::IntoResponse};
+use chrono::{Duration, Utc};
+use metered_finance_api::{
+    middleware::errors::AppError,
+    models::{
+        common::ErrorCode,
+        finance::{Currency, TransactionType},
+        fraud::{
+            evaluate, velocity_windows, FraudAction, FraudCondition, FraudDecision, FraudRule,
+            FraudRuleRequest, ScorerVerdict, ScreeningContext,
+        },
+        requests::CreateTransactionRequest,
+    },
+};
+use serde_json::json;
+use std::collections::HashMap;
+
+fn request(amount: f64) -> CreateTransactionRequest {
+    CreateTransactionRequest {
+        account_id: "acc_1".to_string(),
+        amount,
+        currency: Currency::USD,
+        transaction_type: TransactionType::Payout,
+        description: None,
+        metadata: Some(json!({"country": "XX", "email": "fraud@example.com"})),
+    }
+}
+
+fn rule(id: &str, condition: FraudCondition, action: FraudAction) -> FraudRule {
+    FraudRule {
+        rule_id: id.to_string(),
+        name: id.to_string(),
+        condition,
+        action,
+        enabled: true,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+fn context(account_age: Duration, recent: i64) -> ScreeningContext {
+    let now = Utc::now();
+    ScreeningContext {
+        now,
+        account_created_at: Some(now - account_age),
+        recent_transactions: HashMap::from([(3600, recent)]),
+    }
+}
+
+fn decision(
+    condition: FraudCondition,
+    req: &CreateTransactionRequest,
+    ctx: &ScreeningContext,
+) -> FraudDecision {
+    evaluate(&[rule("r", condition, FraudAction::Review)], req, ctx).decision
+}
+
+#[test]
+fn test_amount_threshold() {
+    let condition = FraudCondition::AmountAtLeast {
+        amount: 1000.0,
+        currency: Some(Currency::USD),
+        transaction_types: vec![TransactionType::Payout],
+    };
+    let ctx = context(Duration::days(365), 0);
+
+    assert_eq!(
+        decision(condition.clone(), &request(1000.0), &ctx),
+        FraudDecision::Review
+    );
+    assert_eq!(
+        decision(condition.clone(), &request(999.99), &ctx),
+        FraudDecision::Allow
+    );
+
+    let mut payment = request(5000.0);
+    payment.transaction_type = TransactionType::Payment;
+    assert_eq!(
+        decision(condition.clone(), &payment, &ctx),
+        FraudDecision::Allow
+    );
+
+    let mut euros = request(5000.0);
+    euros.currency = Currency::EUR;
+    assert_eq!(decision(condition, &euros, &ctx), FraudDecision::Allow);
+}
+
+#[test]
+fn test_velocity_and_account_age() {
+    let velocity = FraudCondition::Velocity {
+        max_transactions: 5,
+        window_secs: 3600,
+    };
+    assert_eq!(
+        decision(
+            velocity.clone(),
+            &request(1.0),
+            &context(Duration::days(1), 5)
+        ),
+        FraudDecision::Review
+    );
+    assert_eq!(
+        decision(velocity, &request(1.0), &context(Duration::days(1), 4)),
+        FraudDecision::Allow
+    );
+
+    let new_account = FraudCondition::NewAccount {
+        max_age_secs: 86_400,
+        min_amount: 100.0,
+    };
+    assert_eq!(
+        decision(
+            new_account.clone(),
+            &request(100.0),
+            &context(Duration::hours(2), 0)
+        ),
+        FraudDecision::Review
+    );
+    assert_eq!(
+        decision(
+            new_account.clone(),
+            &request(50.0),
+            &context(Duration::hours(2), 0)
+        ),
+        FraudDecision::Allow
+    );
+    assert_eq!(
+        decision(new_account, &request(100.0), &context(Duration::days(2), 0)),
+        FraudDecision::Allow
+    );
+}
+
+#[test]
+fn test_metadata_and_deny_lists() {
+    let ctx = context(Duration::days(365), 0);
+
+    let metadata = FraudCondition::Metadata {
+        key: "country".to_string(),
+        values: vec![json!("XX")],
+    };
+    assert_eq!(
+        decision(metadata, &request(1.0), &ctx),
+        FraudDecision::Review
+    );
+
+    let by_email = FraudCondition::DenyList {
+        field: "metadata.email".to_string(),
+        values: vec!["fraud@example.com".to_string()],
+    };
+    assert_eq!(
+        decision(by_email, &request(1.0), &ctx),
+        FraudDecision::Review
+    );
+
+    let by_account = FraudCondition::DenyList {
+        field: "account_id".to_string(),
+        values: vec!["acc_2".to_string()],
+    };
+    assert_eq!(
+        decision(by_account, &request(1.0), &ctx),
+        FraudDecision::Allow
+    );
+}
+
+#[test]
+fn test_most_severe_enabled_rule_wins() {
+    let ctx = context(Duration::days(365), 0);
+    let amount = |amount| FraudCondition::AmountAtLeast {
+        amount,
+        currency: None,
+        transaction_types: vec![],
+    };
+    let mut disabled = rule("disabled", amount(1.0), FraudAction::Block);
+    disabled.enabled = false;
+    let rules = vec![
+        rule("small", amount(10.0), FraudAction::Review),
+        rule("large", amount(100.0), FraudAction::Block),
+        disabled,
+    ];
+
+    let result = evaluate(&rules, &request(50.0), &ctx);
+    assert_eq!(result.decision, FraudDecision::Review);
+    assert_eq!(result.matched_rules.len(), 1);
+
+    let mut result = evaluate(&rules, &request(500.0), &ctx);
+    assert_eq!(result.decision, FraudDecision::Block);
+    assert_eq!(result.matched_rules.len(), 2);
+
+    // A scorer can make the decision stricter but never looser.
+    result.merge(ScorerVerdict {
+        decision: FraudDecision::Allow,
+        score: Some(0.1),
+        reason: None,
+    });
+    assert_eq!(result.decision, FraudDecision::Block);
+    assert_eq!(result.score, Some(0.1));
+
+    assert!(evaluate(&rules, &request(1.0), &ctx)
+        .matched_rules
+        .is_empty());
+}
+
+#[test]
+fn test_velocity_windows_and_batch_context() {
+    let rules = vec![
+        rule(
+            "a",
+            FraudCondition::Velocity {
+                max_transactions: 5,
+                window_secs: 3600,
+            },
+            FraudAction::Review,
+        ),
+        rule(
+            "b",
+            FraudCondition::Velocity {
+                max_transactions: 50,
+                window_secs: 3600,
+            },
+            FraudAction::Block,
+        ),
+    ];
+    assert_eq!(velocity_windows(&rules), vec![3600]);
+
+    let mut ctx = context(Duration::days(1), 4);
+    assert_eq!(
+        evaluate(&rules, &request(1.0), &ctx).decision,
+        FraudDecision::Allow
+    );
+    ctx.record();
+    assert_eq!(
+        evaluate(&rules, &request(1.0), &ctx).decision,
+        FraudDecision::Review
+    );
+}
+
+#[test]
+fn test_rule_request_validation_and_shape() {
+    let req: FraudRuleRequest = serde_json::from_value(json!({
+        "name": "Blocked emails",
+        "action": "block",
+        "condition": {"kind": "deny_list", "field": "metadata.email", "values": ["a@example.com"]}
+    }))
+    .unwrap();
+    assert!(req.enabled);
+    assert!(req.validate().is_ok());
+
+    let mut bad_field = req.clone();
+    bad_field.condition = FraudCondition::DenyList {
+        field: "description".to_string(),
+        values: vec!["x".to_string()],
+    };
+    assert!(bad_field.validate().is_err());
+
+    let mut bad_window = req.clone();
+    bad_window.condition = FraudCondition::Velocity {
+        max_transactions: 1,
+        window_secs: 0,
+    };
+    assert!(bad_window.validate().is_err());
+
+    let response = AppError::TransactionBlocked {
+        transaction_id: "txn_1".to_string(),
+    }
+    .into_response();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(
+        response.extensions().get::<ErrorCode>(),
+        Some(&ErrorCode::TransactionBlocked)
+    );
+}
@@ -0,0 +1,128 @@
+use axum::{http::StatusCode, response::IntoResponse};
+use metered_finance_api::{
+    middleware::errors::AppError,
+    models::{
+        common::ErrorCode,
+        finance::{Currency, TransactionType},
+        lifecycle::{
+            check_account_status, check_transition, AccountStatus, ChangeAccountStatusRequest,
+            LifecycleError,
+        },
+        requests::CreateTransactionRequest,
+    },
+};
+use std::collections::HashMap;
+
+fn request(account_id: &str) -> CreateTransactionRequest {
+    CreateTransactionRequest {
+        account_id: account_id.to_string(),
+        amount: 10.0,
+        currency: Currency::USD,
+        transaction_type: TransactionType::Payment,
+        description: None,
+        metadata: None,
+    }
+}
+
+#[test]
+fn test_client_transitions() {
+    use AccountStatus::*;
+
+    assert!(check_transition(Active, Frozen, false, false).is_ok());
+    assert!(check_transition(Frozen, Active, false, false).is_ok());
+    assert!(check_transition(Active, Closed, false, false).is_ok());
+    assert!(check_transition(Frozen, Closed, false, false).is_ok());
+
+    assert!(matches!(
+        check_transition(Closed, Active, false, false),
+        Err(LifecycleError::AdminOnly(_))
+    ));
+    assert!(matches!(
+        check_transition(Frozen, Active, false, true),
+        Err(LifecycleError::AdminOnly(_))
+    ));
+    assert!(matches!(
+        check_transition(Frozen, Closed, false, true),
+        Err(LifecycleError::AdminOnly(_))
+    ));
+}
+
+#[test]
+fn test_admin_transitions() {
+    use AccountStatus::*;
+
+    assert!(check_transition(Closed, Active, true, false).is_ok());
+    assert!(check_transition(Frozen, Active, true, true).is_ok());
+    assert!(matches!(
+        check_transition(Closed, Frozen, true, false),
+        Err(LifecycleError::InvalidTransition { from: Closed, to: Frozen })
+    ));
+    assert!(matches!(
+        check_transition(Active, Active, true, false),
+        Err(LifecycleError::InvalidTransition { .. })
+    ));
+}
+
+#[test]
+fn test_only_active_accounts_accept_transactions() {
+    assert!(AccountStatus::Active.accepts_transactions());
+    assert!(!AccountStatus::Frozen.accepts_transactions());
+    assert!(!AccountStatus::Closed.accepts_transactions());
+    assert_eq!("closed".parse::<AccountStatus>(), Ok(AccountStatus::Closed));
+}
+
+#[test]
+fn test_change_request_requires_reason() {
+    let mut req: ChangeAccountStatusRequest =
+        serde_json::from_str(r#"{"status": "closed", "reason": "  "}"#).unwrap();
+    assert!(!req.settle);
+    assert!(req.validate().is_err());
+
+    req.reason = "Customer request".to_string();
+    assert!(req.validate().is_ok());
+
+    req.reason = "x".repeat(501);
+    assert!(req.validate().is_err());
+}
+
+#[test]
+fn test_batch_items_on_inactive_accounts_fail() {
+    let statuses = HashMap::from([
+        ("acc_active".to_string(), AccountStatus::Active),
+        ("acc_frozen".to_string(), AccountStatus::Frozen),
+        ("acc_closed".to_string(), AccountStatus::Closed),
+    ]);
+
+    let checked = check_account_status(
+        vec![
+            Ok(request("acc_active")),
+            Ok(request("acc_frozen")),
+            Ok(request("acc_closed")),
+            Ok(request("acc_missing")),
+        ],
+        &statuses,
+    );
+
+    assert!(checked[0].is_ok());
+    assert_eq!(checked[1].as_ref().unwrap_err().code, "account_frozen");
+    assert_eq!(checked[2].as_ref().unwrap_err().code, "account_closed");
+    assert!(checked[3].is_ok());
+}
+
+#[test]
+fn test_unavailable_account_error_codes() {
+    let response = AppError::account_unavailable("acc_1", AccountStatus::Closed).into_response();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    assert_eq!(response.extensions().get::<ErrorCode>(), Some(&ErrorCode::AccountClosed));
+
+    let response = AppError::from(LifecycleError::NonZeroBalance {
+        balance: 12.5,
+        currency: Currency::EUR,
+    })
+    .into_response();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    assert_eq!(response.extensions().get::<ErrorCode>(), Some(&ErrorCode::NonZeroBalance));
+
+    let response = AppError::from(LifecycleError::AdminOnly("no".to_string())).into_response();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
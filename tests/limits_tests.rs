@@ -0,0 +1,211 @@
+use axum::{http::StatusCode, response::IntoResponse};
+use chrono::{TimeZone, Utc};
+use metered_finance_api::{
+    middleware::errors::AppError,
+    models::{
+        common::ErrorCode,
+        finance::{Currency, FailureReason, TransactionType},
+        limits::{
+            apply_limits, check_limits, usage_windows, AccountLimitsResponse, CurrencyLimits,
+            CurrencyUsage, LimitUsage, SetAccountLimitsRequest,
+        },
+        requests::CreateTransactionRequest,
+    },
+};
+use std::collections::HashMap;
+
+fn request(amount: f64, transaction_type: TransactionType) -> CreateTransactionRequest {
+    CreateTransactionRequest {
+        account_id: "acc_1".to_string(),
+        amount,
+        currency: Currency::USD,
+        transaction_type,
+        description: None,
+        metadata: None,
+    }
+}
+
+fn limits() -> AccountLimitsResponse {
+    AccountLimitsResponse {
+        account_id: "acc_1".to_string(),
+        max_transactions_per_hour: Some(3),
+        currencies: vec![CurrencyLimits {
+            currency: Currency::USD,
+            max_amount: Some(500.0),
+            daily_outflow_limit: Some(300.0),
+            monthly_outflow_limit: Some(1000.0),
+            overdraft_limit: Some(50.0),
+        }],
+        updated_at: Utc::now(),
+    }
+}
+
+fn usage(daily: f64, monthly: f64, net: f64) -> LimitUsage {
+    LimitUsage {
+        transactions_last_hour: 0,
+        currencies: HashMap::from([(
+            Currency::USD,
+            CurrencyUsage {
+                daily_outflow: daily,
+                monthly_outflow: monthly,
+                net_position: net,
+            },
+        )]),
+    }
+}
+
+fn reason(usage: &LimitUsage, req: &CreateTransactionRequest) -> Option<FailureReason> {
+    check_limits(&limits(), usage, req).err().map(|v| v.reason)
+}
+
+#[test]
+fn test_amount_and_velocity_limits_apply_to_every_type() {
+    let big = request(600.0, TransactionType::Payment);
+    assert_eq!(
+        reason(&usage(0.0, 0.0, 0.0), &big),
+        Some(FailureReason::AmountLimitExceeded)
+    );
+
+    let mut busy = usage(0.0, 0.0, 1000.0);
+    busy.transactions_last_hour = 3;
+    assert_eq!(
+        reason(&busy, &request(10.0, TransactionType::Payment)),
+        Some(FailureReason::VelocityLimitExceeded)
+    );
+
+    busy.transactions_last_hour = 2;
+    assert_eq!(
+        reason(&busy, &request(10.0, TransactionType::Payment)),
+        None
+    );
+}
+
+#[test]
+fn test_outflow_caps_and_overdraft() {
+    let payout = request(100.0, TransactionType::Payout);
+
+    assert_eq!(reason(&usage(200.0, 200.0, 1000.0), &payout), None);
+    assert_eq!(
+        reason(&usage(250.0, 250.0, 1000.0), &payout),
+        Some(FailureReason::DailyLimitExceeded)
+    );
+    assert_eq!(
+        reason(&usage(0.0, 950.0, 1000.0), &payout),
+        Some(FailureReason::MonthlyLimitExceeded)
+    );
+    assert_eq!(reason(&usage(0.0, 0.0, 50.0), &payout), None);
+    assert_eq!(
+        reason(&usage(0.0, 0.0, 49.0), &payout),
+        Some(FailureReason::InsufficientFunds)
+    );
+
+    // Inflows never count against outflow caps or the overdraft.
+    let payment = request(100.0, TransactionType::Payment);
+    assert_eq!(reason(&usage(300.0, 1000.0, -50.0), &payment), None);
+}
+
+#[test]
+fn test_net_position_is_the_sum_of_ledger_amounts() {
+    let items = [
+        request(100.0, TransactionType::Payment),
+        request(30.0, TransactionType::Payout),
+        request(2.5, TransactionType::Fee),
+        request(10.0, TransactionType::Adjustment),
+    ];
+    let mut usage = LimitUsage::default();
+    for item in &items {
+        usage.record(item);
+    }
+
+    let ledger: Vec<f64> = items
+        .iter()
+        .map(|item| item.transaction_type.ledger_amount(item.amount))
+        .collect();
+    assert_eq!(ledger, vec![100.0, -30.0, -2.5, 10.0]);
+
+    let used = usage.currencies[&Currency::USD];
+    assert_eq!(used.net_position, ledger.iter().sum::<f64>());
+    assert_eq!(used.daily_outflow, 32.5);
+}
+
+#[test]
+fn test_currencies_without_limits_only_check_velocity() {
+    let mut req = request(10_000.0, TransactionType::Payout);
+    req.currency = Currency::EUR;
+
+    assert_eq!(reason(&LimitUsage::default(), &req), None);
+}
+
+#[test]
+fn test_batch_items_count_against_later_items() {
+    let items = vec![
+        Ok(request(200.0, TransactionType::Payout)),
+        Ok(request(200.0, TransactionType::Payout)),
+        Ok(request(20.0, TransactionType::Payment)),
+        Ok(request(20.0, TransactionType::Payment)),
+        Ok(request(20.0, TransactionType::Payment)),
+    ];
+    let limits = HashMap::from([("acc_1".to_string(), limits())]);
+    let mut usage = HashMap::from([("acc_1".to_string(), usage(0.0, 0.0, 1000.0))]);
+
+    let checked = apply_limits(items, &limits, &mut usage);
+
+    assert!(checked[0].is_ok());
+    let error = checked[1].as_ref().unwrap_err();
+    assert_eq!(error.code, "limit_exceeded");
+    assert_eq!(
+        error.details.as_ref().unwrap()["failure_reason"],
+        "daily_limit_exceeded"
+    );
+    // The rejected payout does not count towards the hourly limit of 3.
+    assert!(checked[2].is_ok() && checked[3].is_ok());
+    let error = checked[4].as_ref().unwrap_err();
+    assert_eq!(
+        error.details.as_ref().unwrap()["failure_reason"],
+        "velocity_limit_exceeded"
+    );
+    assert_eq!(usage["acc_1"].transactions_last_hour, 3);
+}
+
+#[test]
+fn test_set_limits_validation() {
+    let valid: SetAccountLimitsRequest = serde_json::from_value(serde_json::json!({
+        "max_transactions_per_hour": 10,
+        "currencies": [{"currency": "USD", "daily_outflow_limit": 100.0, "overdraft_limit": 0}]
+    }))
+    .unwrap();
+    assert!(valid.validate().is_ok());
+
+    let mut duplicate = valid.clone();
+    duplicate.currencies.push(duplicate.currencies[0].clone());
+    assert!(duplicate.validate().is_err());
+
+    let mut negative = valid.clone();
+    negative.currencies[0].monthly_outflow_limit = Some(-1.0);
+    assert!(negative.validate().is_err());
+
+    let zero_rate = SetAccountLimitsRequest {
+        max_transactions_per_hour: Some(0),
+        currencies: vec![],
+    };
+    assert!(zero_rate.validate().is_err());
+}
+
+#[test]
+fn test_usage_windows_and_error_response() {
+    let now = Utc.with_ymd_and_hms(2024, 3, 15, 10, 30, 0).unwrap();
+    let (hour, day, month) = usage_windows(now);
+    assert_eq!(hour, Utc.with_ymd_and_hms(2024, 3, 15, 9, 30, 0).unwrap());
+    assert_eq!(day, Utc.with_ymd_and_hms(2024, 3, 15, 0, 0, 0).unwrap());
+    assert_eq!(month, Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap());
+
+    let big = request(600.0, TransactionType::Payment);
+    let violation = check_limits(&limits(), &usage(0.0, 0.0, 0.0), &big).unwrap_err();
+    let response = AppError::limit_exceeded("txn_1", &violation).into_response();
+
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(
+        response.extensions().get::<ErrorCode>(),
+        Some(&ErrorCode::LimitExceeded)
+    );
+}
@@ -0,0 +1,177 @@
+use axum::http::Request;
+use metered_finance_api::observability::{
+    logging::{fmt_layer, LogFormat, RedactingMakeWriter, Redactor, RequestContextLayer, REDACTED},
+    telemetry::{make_request_span, record_key_id},
+};
+use std::{
+    io,
+    sync::{Arc, Mutex},
+};
+use tracing_subscriber::layer::SubscriberExt;
+
+const API_KEY: &str = "sk_live_1a2b3c4_AbCdEfGhIjKlMnOpQrStUvWxYz012345";
+const SECRET_HASH: &str = "$argon2id$v=19$m=19456,t=2,p=1$c2FsdHNhbHQ$aGFzaGhhc2hoYXNo";
+const ADMIN_KEY: &str = "super_secret_admin_key";
+
+fn redactor() -> Redactor {
+    Redactor::new(&["ssn".to_string(), "card_number".to_string()], &[ADMIN_KEY.to_string()])
+}
+
+#[derive(Clone, Default)]
+struct Captured(Arc<Mutex<Vec<u8>>>);
+
+impl io::Write for Captured {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Captured {
+    fn output(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+fn capture_logs(format: LogFormat, f: impl FnOnce()) -> String {
+    let captured = Captured::default();
+    let writer = {
+        let captured = captured.clone();
+        RedactingMakeWriter::new(move || captured.clone(), Arc::new(redactor()))
+    };
+    let subscriber = tracing_subscriber::registry()
+        .with(RequestContextLayer)
+        .with(fmt_layer(format, writer));
+
+    tracing::subscriber::with_default(subscriber, f);
+
+    captured.output()
+}
+
+fn log_secrets() {
+    let mut headers = http::HeaderMap::new();
+    headers.insert("x-api-key", API_KEY.parse().unwrap());
+    headers.insert("x-admin-key", ADMIN_KEY.parse().unwrap());
+
+    let metadata = serde_json::json!({ "ssn": "123-45-6789", "order_id": "ord_42" });
+
+    tracing::info!(api_key = %API_KEY, secret_hash = %SECRET_HASH, "key created");
+    tracing::warn!("rejected request headers: {:?}", headers);
+    tracing::info!(metadata = %metadata, "transaction created");
+    tracing::info!("card_number=4111111111111111 admin={}", ADMIN_KEY);
+}
+
+fn assert_no_secrets(output: &str) {
+    assert!(!output.is_empty());
+    assert!(!output.contains("AbCdEfGhIjKlMnOpQrStUvWxYz012345"), "{}", output);
+    assert!(!output.contains(SECRET_HASH), "{}", output);
+    assert!(!output.contains("aGFzaGhhc2hoYXNo"), "{}", output);
+    assert!(!output.contains(ADMIN_KEY), "{}", output);
+    assert!(!output.contains("123-45-6789"), "{}", output);
+    assert!(!output.contains("4111111111111111"), "{}", output);
+    assert!(output.contains(REDACTED));
+}
+
+#[test]
+fn test_redact_header_values() {
+    let line = redactor().redact(r#"headers: {"x-api-key": "abc123", "X-Admin-Key": "def456"}"#);
+    assert_eq!(
+        line,
+        r#"headers: {"x-api-key": "[REDACTED]", "X-Admin-Key": "[REDACTED]"}"#
+    );
+}
+
+#[test]
+fn test_redact_key_value_fields() {
+    let line = redactor().redact("secret_hash=abc password: hunter2 x_api_key=xyz");
+    assert_eq!(
+        line,
+        "secret_hash=[REDACTED] password: [REDACTED] x_api_key=[REDACTED]"
+    );
+}
+
+#[test]
+fn test_redact_api_key_keeps_prefix() {
+    let line = redactor().redact(&format!("lookup failed for {}", API_KEY));
+    assert_eq!(line, "lookup failed for sk_live_1a2b3c4_[REDACTED]");
+}
+
+#[test]
+fn test_redact_argon2_hash() {
+    let line = redactor().redact(&format!("hash {} stored", SECRET_HASH));
+    assert_eq!(line, "hash [REDACTED] stored");
+}
+
+#[test]
+fn test_redact_configured_metadata_fields_in_escaped_json() {
+    let line = redactor().redact(r#"{"metadata":"{\"ssn\":\"123-45-6789\",\"order_id\":\"ord_42\"}"}"#);
+    assert_eq!(
+        line,
+        r#"{"metadata":"{\"ssn\":\"[REDACTED]\",\"order_id\":\"ord_42\"}"}"#
+    );
+}
+
+#[test]
+fn test_redact_leaves_other_fields() {
+    let line = "account_id=acc_123 amount=100 key_id=key_abc";
+    assert_eq!(redactor().redact(line), line);
+}
+
+#[test]
+fn test_short_literals_are_ignored() {
+    let redactor = Redactor::new(&[], &["abc".to_string()]);
+    assert_eq!(redactor.redact("abc"), "abc");
+}
+
+#[test]
+fn test_log_format_from_str() {
+    assert_eq!("json".parse::<LogFormat>().unwrap(), LogFormat::Json);
+    assert_eq!("TEXT".parse::<LogFormat>().unwrap(), LogFormat::Text);
+    assert!("xml".parse::<LogFormat>().is_err());
+}
+
+#[test]
+fn test_json_logs_are_redacted() {
+    let output = capture_logs(LogFormat::Json, log_secrets);
+
+    assert_no_secrets(&output);
+    for line in output.lines() {
+        serde_json::from_str::<serde_json::Value>(line).expect("each line is valid JSON");
+    }
+}
+
+#[test]
+fn test_text_logs_are_redacted() {
+    let output = capture_logs(LogFormat::Text, log_secrets);
+
+    assert_no_secrets(&output);
+}
+
+#[test]
+fn test_json_logs_include_request_context() {
+    let output = capture_logs(LogFormat::Json, || {
+        let req = Request::get("/api/accounts/acc_123/balance")
+            .header("x-request-id", "req-logging-1")
+            .body(())
+            .unwrap();
+
+        let span = make_request_span(&req);
+        let _entered = span.enter();
+        record_key_id("key_logging");
+
+        tracing::info_span!("auth.client").in_scope(|| tracing::info!("inside nested span"));
+    });
+
+    let line: serde_json::Value = serde_json::from_str(output.lines().next().unwrap()).unwrap();
+    assert_eq!(line["message"], "inside nested span");
+    assert_eq!(line["request_id"], "req-logging-1");
+    assert_eq!(line["key_id"], "key_logging");
+    assert_eq!(line["account_id"], "acc_123");
+    assert_eq!(line["route"], "unmatched");
+    assert_eq!(line["span"], "auth.client");
+    assert_eq!(line["level"], "INFO");
+}
@@ -0,0 +1,179 @@
+use axum::{body::to_bytes, http::StatusCode, response::IntoResponse};
+use metered_finance_api::{
+    middleware::errors::AppError,
+    models::{
+        batch::{check_metadata_schema, decode_items},
+        finance::{Currency, TransactionType},
+        metadata::{
+            check_limits, check_metadata, pointer_to_path, MetadataSchema, MetadataTarget,
+            RegisterMetadataSchemaRequest, MAX_METADATA_BYTES, MAX_METADATA_DEPTH, MAX_METADATA_KEYS,
+        },
+        requests::{CreateAccountRequest, CreateTransactionRequest, UpdateAccountRequest},
+    },
+};
+use serde_json::{json, Map, Value};
+
+fn schema(value: Value) -> MetadataSchema {
+    MetadataSchema::compile(&value).unwrap()
+}
+
+fn nested(levels: usize) -> Value {
+    (0..levels).fold(json!("leaf"), |inner, _| json!({ "a": inner }))
+}
+
+fn transaction(metadata: Option<Value>) -> CreateTransactionRequest {
+    CreateTransactionRequest {
+        account_id: "acc_1".to_string(),
+        amount: 10.0,
+        currency: Currency::USD,
+        transaction_type: TransactionType::Payment,
+        description: None,
+        metadata,
+    }
+}
+
+#[test]
+fn test_metadata_limits() {
+    assert!(check_limits(&nested(MAX_METADATA_DEPTH)).is_ok());
+    let error = check_limits(&nested(MAX_METADATA_DEPTH + 1)).unwrap_err();
+    assert_eq!(error.path(), "$.metadata.a.a.a.a.a");
+
+    let arrays = json!({"items": [[[[["too deep"]]]]]});
+    assert_eq!(check_limits(&arrays).unwrap_err().path(), "$.metadata.items[0][0][0][0]");
+
+    let keys: Map<String, Value> = (0..MAX_METADATA_KEYS).map(|i| (format!("k{}", i), json!(i))).collect();
+    assert!(check_limits(&Value::Object(keys.clone())).is_ok());
+    let mut too_many = keys;
+    too_many.insert("extra".to_string(), json!({"k": 1}));
+    assert!(check_limits(&Value::Object(too_many)).is_err());
+
+    let big = json!({"note": "x".repeat(MAX_METADATA_BYTES)});
+    let error = check_limits(&big).unwrap_err();
+    assert_eq!(error.path(), "$.metadata");
+    assert!(error.to_string().contains("bytes"));
+
+    // Scalars and missing metadata are within the limits.
+    assert!(check_limits(&json!("plain")).is_ok());
+    assert!(check_metadata(None, None).is_ok());
+}
+
+#[test]
+fn test_pointer_to_path() {
+    let document = json!({"tags": ["a", "b"], "0": {"x-y": {"a/b": 1}}, "map": {"1": true}});
+
+    assert_eq!(pointer_to_path(&document, ""), "$.metadata");
+    assert_eq!(pointer_to_path(&document, "/tags/1"), "$.metadata.tags[1]");
+    assert_eq!(pointer_to_path(&document, "/0/x-y/a~1b"), "$.metadata['0']['x-y']['a/b']");
+    // Numeric object keys are not array indexes.
+    assert_eq!(pointer_to_path(&document, "/map/1"), "$.metadata.map['1']");
+    assert_eq!(pointer_to_path(&document, "/it's"), "$.metadata['it\\'s']");
+}
+
+#[test]
+fn test_schema_violations_point_to_paths() {
+    let schema = schema(json!({
+        "type": "object",
+        "required": ["order_id"],
+        "properties": {
+            "order_id": {"type": "string"},
+            "tags": {"type": "array", "items": {"type": "string"}}
+        },
+        "additionalProperties": false
+    }));
+
+    assert!(check_metadata(Some(&json!({"order_id": "ord_1", "tags": ["a"]})), Some(&schema)).is_ok());
+
+    let error = check_metadata(Some(&json!({"order_id": "ord_1", "tags": ["a", 1]})), Some(&schema)).unwrap_err();
+    assert_eq!(error.violations.len(), 1);
+    assert_eq!(error.path(), "$.metadata.tags[1]");
+    assert!(error.to_string().starts_with("Invalid metadata at $.metadata.tags[1]: "));
+
+    let error = check_metadata(Some(&json!({"order_id": 7, "extra": true})), Some(&schema)).unwrap_err();
+    let paths: Vec<&str> = error.violations.iter().map(|v| v.path.as_str()).collect();
+    assert!(paths.contains(&"$.metadata.order_id"));
+    assert!(paths.contains(&"$.metadata"));
+    assert!(error.to_string().contains("more)"));
+
+    // Missing metadata is checked as an empty object.
+    assert!(check_metadata(None, Some(&schema)).is_err());
+    assert!(check_metadata(None, Some(&self::schema(json!({"type": "object"})))).is_ok());
+}
+
+#[test]
+fn test_schema_registration_validation() {
+    let valid: RegisterMetadataSchemaRequest =
+        serde_json::from_value(json!({"schema": {"type": "object", "maxProperties": 10}})).unwrap();
+    assert!(valid.validate().is_ok());
+
+    for schema in [
+        json!({"type": "not-a-type"}),
+        json!({"properties": {"a": {"minLength": -1}}}),
+        json!(["type", "object"]),
+        json!({"enum": ["x".repeat(64 * 1024)]}),
+    ] {
+        let req = RegisterMetadataSchemaRequest { schema };
+        assert!(req.validate().is_err());
+    }
+
+    assert_eq!("account".parse::<MetadataTarget>().unwrap(), MetadataTarget::Account);
+    assert_eq!(MetadataTarget::Transaction.to_string(), "transaction");
+    assert!("accounts".parse::<MetadataTarget>().is_err());
+}
+
+#[test]
+fn test_requests_validate_metadata() {
+    let account = CreateAccountRequest {
+        account_id: "acc_1".to_string(),
+        metadata: Some(nested(MAX_METADATA_DEPTH + 1)),
+    };
+    assert!(account.validate().unwrap_err().contains("$.metadata.a"));
+
+    let update = UpdateAccountRequest { metadata: json!({"tier": "gold"}) };
+    assert!(update.validate().is_ok());
+    let tier = schema(json!({"properties": {"tier": {"enum": ["silver", "gold"]}}}));
+    assert!(update.validate_metadata(Some(&tier)).is_ok());
+    let update = UpdateAccountRequest { metadata: json!({"tier": "bronze"}) };
+    assert_eq!(update.validate_metadata(Some(&tier)).unwrap_err().path(), "$.metadata.tier");
+
+    assert!(transaction(None).validate().is_ok());
+    assert!(transaction(Some(nested(MAX_METADATA_DEPTH + 1))).validate().is_err());
+}
+
+#[tokio::test]
+async fn test_invalid_metadata_error_details() {
+    let error = check_metadata(Some(&json!({"tags": [1]})), Some(&schema(json!({
+        "properties": {"tags": {"items": {"type": "string"}}}
+    }))))
+    .unwrap_err();
+
+    let response = AppError::from(error).into_response();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let body: Value = serde_json::from_slice(&to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap();
+    let error = &body["error"];
+    assert_eq!(error["code"], "validation_error");
+    assert_eq!(error["details"]["path"], "$.metadata.tags[0]");
+    assert_eq!(error["details"]["violations"][0]["path"], "$.metadata.tags[0]");
+}
+
+#[test]
+fn test_batch_items_report_metadata_paths() {
+    let items = vec![
+        json!({"account_id": "acc_1", "amount": 10.0, "currency": "USD", "transaction_type": "payment",
+               "metadata": {"order_id": "ord_1"}}),
+        json!({"account_id": "acc_1", "amount": 10.0, "currency": "USD", "transaction_type": "payment",
+               "metadata": nested(MAX_METADATA_DEPTH + 1)}),
+        json!({"account_id": "acc_1", "amount": 10.0, "currency": "USD", "transaction_type": "payment"}),
+    ];
+
+    let decoded = decode_items(&items);
+    let error = decoded[1].as_ref().unwrap_err();
+    assert_eq!(error.code, "validation_error");
+    assert_eq!(error.details.as_ref().unwrap()["path"], "$.metadata.a.a.a.a.a");
+
+    let required = schema(json!({"required": ["order_id"]}));
+    let checked = check_metadata_schema(decoded, &required);
+    assert!(checked[0].is_ok());
+    assert_eq!(checked[1].as_ref().unwrap_err().details.as_ref().unwrap()["path"], "$.metadata.a.a.a.a.a");
+    assert_eq!(checked[2].as_ref().unwrap_err().details.as_ref().unwrap()["path"], "$.metadata");
+}
@@ -0,0 +1,63 @@
+use axum::{
+    body::Body,
+    extract::MatchedPath,
+    http::{Request, StatusCode},
+    middleware,
+    routing::get,
+    Router,
+};
+use metered_finance_api::observability::metrics::{
+    init_metrics, record_auth_failure, record_quota_rejection, route_label, track_http_metrics,
+    UNMATCHED_ROUTE,
+};
+use tower::ServiceExt;
+
+#[test]
+fn test_route_label_without_match_is_bounded() {
+    assert_eq!(route_label(None), UNMATCHED_ROUTE);
+}
+
+#[test]
+fn test_init_metrics_is_idempotent() {
+    init_metrics().unwrap();
+    let handle = init_metrics().unwrap();
+
+    record_auth_failure("unknown_key");
+    record_quota_rejection("key_metrics_test", "daily");
+
+    let rendered = handle.render();
+    assert!(rendered.contains("auth_failures_total{reason=\"unknown_key\"}"));
+    assert!(rendered.contains("quota_rejections_total"));
+    assert!(rendered.contains("period=\"daily\""));
+}
+
+#[tokio::test]
+async fn test_http_metrics_use_route_template() {
+    let handle = init_metrics().unwrap();
+
+    let app = Router::new()
+        .route(
+            "/accounts/{id}",
+            get(|path: MatchedPath| async move { path.as_str().to_string() }),
+        )
+        .layer(middleware::from_fn(track_http_metrics));
+
+    let response = app
+        .clone()
+        .oneshot(Request::get("/accounts/acc_123").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app
+        .oneshot(Request::get("/does/not/exist").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let rendered = handle.render();
+    assert!(rendered.contains("route=\"/accounts/{id}\""));
+    assert!(!rendered.contains("acc_123"));
+    assert!(!rendered.contains("/does/not/exist"));
+    assert!(rendered.contains("http_request_duration_seconds_bucket"));
+}
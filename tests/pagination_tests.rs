@@ -0,0 +1,195 @@
+use metered_finance_api::models::{
+    common::{Cursor, PaginationParams, SortOrder},
+    pagination::{
+        CursorError, CursorPosition, CursorSigner, KeysetPage, PageDirection, CURSOR_VERSION,
+    },
+};
+
+fn signer() -> CursorSigner {
+    CursorSigner::new(b"pagination-test-secret")
+}
+
+fn position(direction: PageDirection) -> CursorPosition {
+    CursorPosition {
+        version: CURSOR_VERSION,
+        resource: "accounts".to_string(),
+        sort: "created_at".to_string(),
+        order: SortOrder::Asc,
+        key: "2024-01-01T00:00:00.000000Z".to_string(),
+        id: "acc_1".to_string(),
+        direction,
+    }
+}
+
+fn params(cursor: Option<Cursor>, limit: u32) -> PaginationParams {
+    PaginationParams {
+        cursor,
+        limit: Some(limit),
+    }
+}
+
+fn page(cursor: Option<Cursor>, limit: u32) -> KeysetPage {
+    KeysetPage::new(
+        &params(cursor, limit),
+        &signer(),
+        "accounts",
+        "created_at",
+        SortOrder::Asc,
+    )
+    .unwrap()
+}
+
+/// Rows are `(sort key, id)` pairs.
+fn rows(ids: &[u32]) -> Vec<(String, String)> {
+    ids.iter()
+        .map(|id| (format!("key_{:02}", id), format!("id_{:02}", id)))
+        .collect()
+}
+
+#[test]
+fn test_sign_and_verify_round_trip() {
+    let cursor = signer().sign(&position(PageDirection::Next));
+    assert!(!cursor.0.contains("acc_1"), "cursor should be opaque");
+    assert_eq!(
+        signer().verify(&cursor).unwrap(),
+        position(PageDirection::Next)
+    );
+}
+
+#[test]
+fn test_verify_rejects_forged_cursors() {
+    let cursor = signer().sign(&position(PageDirection::Next));
+
+    let other = CursorSigner::new(b"another-secret");
+    assert_eq!(other.verify(&cursor), Err(CursorError::InvalidSignature));
+
+    // Swap in a different payload but keep the original signature.
+    let forged = signer().sign(&CursorPosition {
+        id: "acc_999".to_string(),
+        ..position(PageDirection::Next)
+    });
+    let (payload, _) = forged.0.split_once('.').unwrap();
+    let (_, signature) = cursor.0.split_once('.').unwrap();
+    let tampered = Cursor(format!("{}.{}", payload, signature));
+    assert_eq!(signer().verify(&tampered), Err(CursorError::InvalidSignature));
+
+    assert_eq!(
+        signer().verify(&Cursor("not-a-cursor".to_string())),
+        Err(CursorError::InvalidFormat)
+    );
+}
+
+#[test]
+fn test_verify_rejects_other_versions() {
+    let cursor = signer().sign(&CursorPosition {
+        version: CURSOR_VERSION + 1,
+        ..position(PageDirection::Next)
+    });
+    assert_eq!(
+        signer().verify(&cursor),
+        Err(CursorError::UnsupportedVersion(CURSOR_VERSION + 1))
+    );
+}
+
+#[test]
+fn test_cursor_bound_to_resource_and_sort() {
+    let cursor = signer().sign(&position(PageDirection::Next));
+
+    for (resource, sort, order) in [
+        ("api_keys", "created_at", SortOrder::Asc),
+        ("accounts", "amount", SortOrder::Asc),
+        ("accounts", "created_at", SortOrder::Desc),
+    ] {
+        let result = KeysetPage::new(
+            &params(Some(cursor.clone()), 10),
+            &signer(),
+            resource,
+            sort,
+            order,
+        );
+        assert_eq!(result.unwrap_err(), CursorError::Mismatch);
+    }
+}
+
+#[test]
+fn test_first_page() {
+    let page = page(None, 2);
+    assert_eq!(page.condition("created_at", "timestamptz", "account_id", 1), None);
+    assert_eq!(
+        page.order_by("created_at", "account_id"),
+        " ORDER BY created_at ASC, account_id ASC"
+    );
+    assert_eq!(page.fetch_limit(), 3);
+
+    let response = page.paginate(rows(&[1, 2, 3]), &signer(), |row| row.clone());
+    assert_eq!(response.data, rows(&[1, 2]));
+    assert!(response.has_more);
+    assert!(response.prev_cursor.is_none());
+
+    let next = signer().verify(&response.next_cursor.unwrap()).unwrap();
+    assert_eq!(next.direction, PageDirection::Next);
+    assert_eq!(next.id, "id_02");
+}
+
+#[test]
+fn test_forward_then_backward() {
+    let next = signer().sign(&CursorPosition {
+        key: "key_02".to_string(),
+        id: "id_02".to_string(),
+        ..position(PageDirection::Next)
+    });
+
+    // Second page, which is also the last.
+    let page2 = page(Some(next), 2);
+    let (condition, values) = page2
+        .condition("created_at", "timestamptz", "account_id", 3)
+        .unwrap();
+    assert_eq!(
+        condition,
+        " AND (created_at, account_id) > ($3::timestamptz, $4)"
+    );
+    assert_eq!(values, vec!["key_02".to_string(), "id_02".to_string()]);
+
+    let response = page2.paginate(rows(&[3, 4]), &signer(), |row| row.clone());
+    assert!(!response.has_more);
+    assert!(response.next_cursor.is_none());
+    let prev = response.prev_cursor.unwrap();
+    assert_eq!(signer().verify(&prev).unwrap().id, "id_03");
+
+    // Going back scans in reverse from the first row of the page.
+    let page1 = page(Some(prev), 2);
+    assert_eq!(
+        page1.order_by("created_at", "account_id"),
+        " ORDER BY created_at DESC, account_id DESC"
+    );
+    let (condition, _) = page1
+        .condition("created_at", "timestamptz", "account_id", 1)
+        .unwrap();
+    assert_eq!(
+        condition,
+        " AND (created_at, account_id) < ($1::timestamptz, $2)"
+    );
+
+    // Rows arrive newest first; the page is returned in sort order.
+    let response = page1.paginate(rows(&[2, 1]), &signer(), |row| row.clone());
+    assert_eq!(response.data, rows(&[1, 2]));
+    assert!(response.prev_cursor.is_none());
+    let next = signer().verify(&response.next_cursor.unwrap()).unwrap();
+    assert_eq!((next.id.as_str(), next.direction), ("id_02", PageDirection::Next));
+}
+
+#[test]
+fn test_backward_page_with_more_before() {
+    let prev = signer().sign(&CursorPosition {
+        key: "key_05".to_string(),
+        id: "id_05".to_string(),
+        ..position(PageDirection::Prev)
+    });
+
+    let response = page(Some(prev), 2).paginate(rows(&[4, 3, 2]), &signer(), |row| row.clone());
+    assert_eq!(response.data, rows(&[3, 4]));
+    assert!(response.has_more);
+
+    let prev = signer().verify(&response.prev_cursor.unwrap()).unwrap();
+    assert_eq!((prev.id.as_str(), prev.direction), ("id_03", PageDirection::Prev));
+}
@@ -0,0 +1,301 @@
+use axum::{http::StatusCode, response::IntoResponse};
+use chrono::{TimeZone, Utc};
+use metered_finance_api::{
+    middleware::errors::AppError,
+    models::{
+        finance::{Currency, TransactionType},
+        payouts::{
+            batch_transition, nacha_file, sepa_pain001_file, validate_iban, validate_routing_number,
+            AchAccountType, BankDetails, BatchAction, CreatePayoutDestinationRequest,
+            CreatePayoutRequest, Originator, PayoutBatchStatus, PayoutCipher, PayoutError,
+            PayoutFileFormat, PayoutResponse, PayoutStatus, SettlementEntry,
+        },
+    },
+};
+use serde_json::json;
+
+fn originator() -> Originator {
+    Originator {
+        name: "Metered Finance".to_string(),
+        company_id: "1234567890".to_string(),
+        routing_number: "011000015".to_string(),
+        iban: "DE89370400440532013000".to_string(),
+        bic: Some("COBADEFFXXX".to_string()),
+    }
+}
+
+fn ach(account_number: &str) -> BankDetails {
+    BankDetails::UsAch {
+        routing_number: "011000015".to_string(),
+        account_number: account_number.to_string(),
+        account_type: AchAccountType::Checking,
+        account_holder_name: "Jane Doe".to_string(),
+    }
+}
+
+fn sepa(iban: &str) -> BankDetails {
+    BankDetails::Sepa {
+        iban: iban.to_string(),
+        bic: None,
+        account_holder_name: "Max Müller & Söhne".to_string(),
+    }
+}
+
+fn entry(payout_id: &str, amount: f64, details: BankDetails) -> SettlementEntry {
+    SettlementEntry {
+        payout_id: payout_id.to_string(),
+        amount,
+        description: Some("Weekly settlement".to_string()),
+        details,
+    }
+}
+
+#[test]
+fn test_routing_number_and_iban_checks() {
+    assert!(validate_routing_number("011000015").is_ok());
+    assert!(validate_routing_number("011000016").is_err());
+    assert!(validate_routing_number("01100001").is_err());
+    assert!(validate_routing_number("01100001a").is_err());
+
+    assert!(validate_iban("DE89370400440532013000").is_ok());
+    assert!(validate_iban("GB82WEST12345698765432").is_ok());
+    assert!(validate_iban("DE88370400440532013000").is_err());
+    assert!(validate_iban("89DE370400440532013000").is_err());
+}
+
+#[test]
+fn test_destination_details_are_normalized() {
+    let req: CreatePayoutDestinationRequest = serde_json::from_value(json!({
+        "label": "Main",
+        "details": {"kind": "sepa", "iban": "de89 3704 0044 0532 0130 00", "bic": "cobadeffxxx", "account_holder_name": " Jane Doe "}
+    }))
+    .unwrap();
+
+    let details = req.validate().unwrap();
+    assert_eq!(
+        details,
+        BankDetails::Sepa {
+            iban: "DE89370400440532013000".to_string(),
+            bic: Some("COBADEFFXXX".to_string()),
+            account_holder_name: "Jane Doe".to_string(),
+        }
+    );
+    assert_eq!(details.last4(), "3000");
+    assert_eq!(details.kind().currency(), Currency::EUR);
+    assert_eq!(details.kind().file_format(), PayoutFileFormat::SepaPain001);
+    // Debug output never shows the full account.
+    assert!(!format!("{:?}", details).contains("DE89"));
+
+    let mut bad_account = ach("12-34");
+    assert!(bad_account.normalized().is_err());
+    bad_account = ach("123456789");
+    assert!(bad_account.normalized().is_ok());
+}
+
+#[test]
+fn test_bank_details_encryption_round_trip() {
+    let cipher = PayoutCipher::new(&[7u8; 32]).unwrap();
+    let details = ach("123456789");
+
+    let sealed = cipher.encrypt(&details, "pod_1").unwrap();
+    assert!(!sealed.windows(9).any(|window| window == b"123456789"));
+    assert_eq!(cipher.decrypt(&sealed, "pod_1").unwrap(), details);
+
+    // Ciphertext is bound to its destination and key.
+    assert!(matches!(cipher.decrypt(&sealed, "pod_2"), Err(PayoutError::Crypto(_))));
+    let other = PayoutCipher::new(&[8u8; 32]).unwrap();
+    assert!(other.decrypt(&sealed, "pod_1").is_err());
+
+    // Each encryption uses a fresh nonce.
+    assert_ne!(cipher.encrypt(&details, "pod_1").unwrap(), sealed);
+
+    assert!(PayoutCipher::new(&[7u8; 16]).is_err());
+    assert!(PayoutCipher::from_base64("not base64!").is_err());
+}
+
+#[test]
+fn test_nacha_file_layout() {
+    let now = Utc.with_ymd_and_hms(2024, 3, 4, 15, 30, 0).unwrap();
+    let entries = vec![
+        entry("po_1709566200_a1b2c3d4", 250.0, ach("123456789")),
+        entry("po_1709566201_0badf00d", 19.99, ach("987654321")),
+    ];
+
+    let file = nacha_file(&originator(), "pob_1709566200_deadbeef", &entries, now).unwrap();
+    let lines: Vec<&str> = file.lines().collect();
+
+    assert_eq!(lines.len(), 10);
+    assert!(lines.iter().all(|line| line.len() == 94));
+    assert!(lines[0].starts_with("101 011000015"));
+    assert_eq!(&lines[0][23..33], "2403041530");
+    assert!(lines[1].starts_with("5220METERED FINANCE"));
+    assert_eq!(&lines[1][50..53], "PPD");
+    assert_eq!(&lines[1][69..75], "240305");
+
+    // Entry detail: checking credit, amount in cents, trace number from the ODFI.
+    assert_eq!(&lines[2][1..3], "22");
+    assert_eq!(&lines[2][3..12], "011000015");
+    assert_eq!(&lines[2][29..39], "0000025000");
+    assert_eq!(&lines[2][39..54], "9566200A1B2C3D4");
+    assert_eq!(lines[2][54..76].trim_end(), "JANE DOE");
+    assert_eq!(&lines[2][79..94], "011000010000001");
+    assert_eq!(&lines[3][29..39], "0000001999");
+
+    // Controls: two entries, entry hash of the RDFI routing numbers, credit total.
+    assert!(lines[4].starts_with("8220000002"));
+    assert_eq!(&lines[4][10..20], "0002200002");
+    assert_eq!(&lines[4][32..44], "000000026999");
+    assert!(lines[5].starts_with("9000001000001000000020002200002"));
+    assert!(lines[6..].iter().all(|line| *line == "9".repeat(94)));
+
+    let sepa_entry = vec![entry("po_1", 1.0, sepa("DE89370400440532013000"))];
+    assert!(matches!(
+        nacha_file(&originator(), "pob_1", &sepa_entry, now),
+        Err(PayoutError::InvalidFile(_))
+    ));
+    let unconfigured = Originator::default();
+    assert!(matches!(
+        nacha_file(&unconfigured, "pob_1", &entries, now),
+        Err(PayoutError::NotConfigured(_))
+    ));
+}
+
+#[test]
+fn test_sepa_pain001_document() {
+    let now = Utc.with_ymd_and_hms(2024, 3, 4, 15, 30, 0).unwrap();
+    let entries = vec![
+        entry("po_1709566200_a1b2c3d4", 100.5, sepa("DE89370400440532013000")),
+        entry("po_1709566201_0badf00d", 0.5, sepa("GB82WEST12345698765432")),
+    ];
+
+    let xml = sepa_pain001_file(&originator(), "pob_1709566200_deadbeef", &entries, now).unwrap();
+    let document = roxmltree::Document::parse(&xml).unwrap();
+    let root = document.root_element();
+    assert_eq!(root.tag_name().namespace(), Some("urn:iso:std:iso:20022:tech:xsd:pain.001.001.03"));
+
+    let text = |name: &str| {
+        document
+            .descendants()
+            .find(|node| node.has_tag_name(name))
+            .and_then(|node| node.text())
+            .map(str::to_string)
+    };
+    assert_eq!(text("MsgId").as_deref(), Some("pob_1709566200_deadbeef"));
+    assert_eq!(text("NbOfTxs").as_deref(), Some("2"));
+    assert_eq!(text("CtrlSum").as_deref(), Some("101.00"));
+    assert_eq!(text("ReqdExctnDt").as_deref(), Some("2024-03-05"));
+
+    let amounts: Vec<&str> = document
+        .descendants()
+        .filter(|node| node.has_tag_name("InstdAmt"))
+        .map(|node| {
+            assert_eq!(node.attribute("Ccy"), Some("EUR"));
+            node.text().unwrap()
+        })
+        .collect();
+    assert_eq!(amounts, vec!["100.50", "0.50"]);
+
+    // Names are escaped, and destinations without a BIC have no creditor agent.
+    let creditors: Vec<&str> = document
+        .descendants()
+        .filter(|node| node.has_tag_name("Cdtr"))
+        .filter_map(|node| node.first_element_child().and_then(|name| name.text()))
+        .collect();
+    assert_eq!(creditors[0], "Max Müller & Söhne");
+    assert!(!document.descendants().any(|node| node.has_tag_name("CdtrAgt")));
+
+    let mut bad = originator();
+    bad.iban = "DE00".to_string();
+    assert!(sepa_pain001_file(&bad, "pob_1", &entries, now).is_err());
+}
+
+#[test]
+fn test_batch_transitions() {
+    assert_eq!(
+        batch_transition(PayoutBatchStatus::Created, BatchAction::Send).unwrap(),
+        PayoutBatchStatus::Sent
+    );
+    assert_eq!(
+        batch_transition(PayoutBatchStatus::Sent, BatchAction::Settle).unwrap(),
+        PayoutBatchStatus::Settled
+    );
+    for from in [PayoutBatchStatus::Sent, PayoutBatchStatus::Settled] {
+        assert_eq!(batch_transition(from, BatchAction::Return).unwrap(), PayoutBatchStatus::Returned);
+    }
+
+    assert!(batch_transition(PayoutBatchStatus::Created, BatchAction::Settle).is_err());
+    assert!(batch_transition(PayoutBatchStatus::Created, BatchAction::Return).is_err());
+    assert!(batch_transition(PayoutBatchStatus::Returned, BatchAction::Send).is_err());
+
+    assert!(PayoutStatus::Sent.is_returnable());
+    assert!(!PayoutStatus::Batched.is_returnable());
+
+    let response = AppError::from(
+        batch_transition(PayoutBatchStatus::Settled, BatchAction::Settle).unwrap_err(),
+    )
+    .into_response();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    let response = AppError::from(PayoutError::NoPendingPayouts(PayoutFileFormat::Nacha)).into_response();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+}
+
+#[test]
+fn test_payout_request_builds_ledger_debit() {
+    let req: CreatePayoutRequest = serde_json::from_value(json!({
+        "account_id": "user_123",
+        "destination_id": "pod_1",
+        "amount": 250.0,
+        "metadata": {"invoice": "inv_9"}
+    }))
+    .unwrap();
+    assert!(req.validate().is_ok());
+
+    let debit = req.transaction(Currency::EUR, "po_1");
+    assert_eq!(debit.transaction_type, TransactionType::Payout);
+    assert_eq!(debit.currency, Currency::EUR);
+    assert_eq!(debit.amount, 250.0);
+    assert_eq!(debit.description.as_deref(), Some("Payout po_1"));
+    assert_eq!(
+        debit.metadata,
+        Some(json!({"invoice": "inv_9", "payout_id": "po_1", "destination_id": "pod_1"}))
+    );
+
+    let mut bad = req.clone();
+    bad.amount = 1.005;
+    assert!(bad.validate().is_err());
+    bad = req.clone();
+    bad.metadata = Some(json!("x"));
+    assert!(bad.validate().is_err());
+}
+
+#[test]
+fn test_returned_payout_leaves_the_balance_unchanged() {
+    let req: CreatePayoutRequest = serde_json::from_value(json!({
+        "account_id": "user_123",
+        "destination_id": "pod_1",
+        "amount": 250.0
+    }))
+    .unwrap();
+    let debit = req.transaction(Currency::USD, "po_1");
+    let payout = PayoutResponse {
+        payout_id: "po_1".to_string(),
+        account_id: req.account_id.clone(),
+        destination_id: req.destination_id.clone(),
+        amount: req.amount,
+        currency: Currency::USD,
+        description: debit.description.clone(),
+        status: PayoutStatus::Sent,
+        transaction_id: "txn_1".to_string(),
+        batch_id: None,
+        return_reason: None,
+        return_transaction_id: None,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    };
+
+    let balance = 1000.0;
+    let after_debit = balance + debit.transaction_type.ledger_amount(debit.amount);
+    assert_eq!(after_debit, 750.0);
+    assert_eq!(payout.return_amount(), 250.0);
+    assert_eq!(after_debit + payout.return_amount(), balance);
+}
@@ -1,4 +1,18 @@
-use metered_finance_api::models::quota::{QuotaLimits, QuotaUsageStats, QuotaStatus};
+use metered_finance_api::models::quota::{
+    BillingCycleSettings, QuotaAlertSettings, QuotaAlertStatus, QuotaLimits, QuotaPeriod, QuotaState,
+    QuotaStatus, QuotaUsageStats,
+};
+use metered_finance_api::models::requests::UpdateQuotaAlertsRequest;
+
+fn no_alerts() -> QuotaAlertStatus {
+    QuotaAlertStatus {
+        soft_thresholds: vec![80, 100],
+        grace_overage_percent: 0,
+        daily_overage: 0,
+        monthly_overage: 0,
+        thresholds: vec![],
+    }
+}
 
 #[test]
 fn test_quota_limits_default() {
@@ -29,6 +43,11 @@ fn test_quota_usage_stats_calculations() {
         this_month: 5_000,
         daily_remaining: 9_500,
         monthly_remaining: 295_000,
+        timezone: "UTC".to_string(),
+        day_start: chrono::Utc::now(),
+        day_end: chrono::Utc::now(),
+        period_start: chrono::Utc::now(),
+        period_end: chrono::Utc::now(),
     };
     
     assert_eq!(stats.today, 500);
@@ -44,6 +63,11 @@ fn test_quota_usage_stats_zero_remaining() {
         this_month: 300_000,
         daily_remaining: 0,
         monthly_remaining: 0,
+        timezone: "UTC".to_string(),
+        day_start: chrono::Utc::now(),
+        day_end: chrono::Utc::now(),
+        period_start: chrono::Utc::now(),
+        period_end: chrono::Utc::now(),
     };
     
     assert_eq!(stats.daily_remaining, 0);
@@ -58,12 +82,18 @@ fn test_quota_status_structure() {
         this_month: 1_000,
         daily_remaining: 9_900,
         monthly_remaining: 299_000,
+        timezone: "UTC".to_string(),
+        day_start: chrono::Utc::now(),
+        day_end: chrono::Utc::now(),
+        period_start: chrono::Utc::now(),
+        period_end: chrono::Utc::now(),
     };
     
     let status = QuotaStatus {
         key_id: "key_test123".to_string(),
         limits: limits.clone(),
         usage: usage.clone(),
+        alerts: no_alerts(),
     };
     
     assert_eq!(status.key_id, "key_test123");
@@ -103,6 +133,11 @@ fn test_quota_usage_stats_serialization() {
         this_month: 500,
         daily_remaining: 9_950,
         monthly_remaining: 299_500,
+        timezone: "UTC".to_string(),
+        day_start: chrono::Utc::now(),
+        day_end: chrono::Utc::now(),
+        period_start: chrono::Utc::now(),
+        period_end: chrono::Utc::now(),
     };
     
     let json = serde_json::to_string(&stats).unwrap();
@@ -129,6 +164,11 @@ fn test_high_usage_scenario() {
         this_month: 299_999,
         daily_remaining: 1,
         monthly_remaining: 1,
+        timezone: "UTC".to_string(),
+        day_start: chrono::Utc::now(),
+        day_end: chrono::Utc::now(),
+        period_start: chrono::Utc::now(),
+        period_end: chrono::Utc::now(),
     };
     
     assert_eq!(stats.daily_remaining, 1);
@@ -142,6 +182,11 @@ fn test_over_quota_scenario() {
         this_month: 350_000,
         daily_remaining: 0,
         monthly_remaining: 0,
+        timezone: "UTC".to_string(),
+        day_start: chrono::Utc::now(),
+        day_end: chrono::Utc::now(),
+        period_start: chrono::Utc::now(),
+        period_end: chrono::Utc::now(),
     };
     
     assert_eq!(stats.daily_remaining, 0);
@@ -161,14 +206,402 @@ fn test_quota_status_with_different_limits() {
         this_month: 1_000_000,
         daily_remaining: 50_000,
         monthly_remaining: 1_000_000,
+        timezone: "UTC".to_string(),
+        day_start: chrono::Utc::now(),
+        day_end: chrono::Utc::now(),
+        period_start: chrono::Utc::now(),
+        period_end: chrono::Utc::now(),
     };
     
     let status = QuotaStatus {
         key_id: "key_premium".to_string(),
         limits: custom_limits,
         usage,
+        alerts: no_alerts(),
     };
     
     assert_eq!(status.limits.rate_limit_per_minute, 200);
     assert_eq!(status.usage.today, 50_000);
-}
\ No newline at end of file
+}
+#[test]
+fn test_quota_alert_settings_default() {
+    let settings = QuotaAlertSettings::default();
+
+    assert_eq!(settings.soft_thresholds, vec![80, 100]);
+    assert_eq!(settings.grace_overage_percent, 0);
+    assert!(settings.validate().is_ok());
+}
+
+#[test]
+fn test_quota_alert_settings_validation() {
+    let settings = QuotaAlertSettings {
+        soft_thresholds: vec![50, 80, 100, 120],
+        grace_overage_percent: 25,
+    };
+    assert!(settings.validate().is_ok());
+
+    let settings = QuotaAlertSettings {
+        soft_thresholds: vec![0],
+        grace_overage_percent: 0,
+    };
+    assert!(settings.validate().is_err());
+
+    let settings = QuotaAlertSettings {
+        soft_thresholds: vec![201],
+        grace_overage_percent: 0,
+    };
+    assert!(settings.validate().is_err());
+
+    let settings = QuotaAlertSettings {
+        soft_thresholds: vec![80, 80],
+        grace_overage_percent: 0,
+    };
+    assert!(settings.validate().is_err());
+
+    let settings = QuotaAlertSettings {
+        soft_thresholds: vec![80],
+        grace_overage_percent: 101,
+    };
+    assert!(settings.validate().is_err());
+}
+
+#[test]
+fn test_quota_hard_limit_with_grace() {
+    let no_grace = QuotaAlertSettings::default();
+    assert_eq!(no_grace.hard_limit(10_000), 10_000);
+
+    let grace = QuotaAlertSettings {
+        soft_thresholds: vec![80, 100],
+        grace_overage_percent: 10,
+    };
+    assert_eq!(grace.hard_limit(10_000), 11_000);
+    assert_eq!(grace.hard_limit(0), 0);
+    assert_eq!(grace.hard_limit(i32::MAX), i32::MAX);
+}
+
+#[test]
+fn test_quota_crossed_thresholds() {
+    let settings = QuotaAlertSettings {
+        soft_thresholds: vec![100, 80, 110],
+        grace_overage_percent: 10,
+    };
+
+    assert!(settings.crossed_thresholds(7_999, 10_000).is_empty());
+    assert_eq!(settings.crossed_thresholds(8_000, 10_000), vec![80]);
+    assert_eq!(settings.crossed_thresholds(10_000, 10_000), vec![80, 100]);
+    assert_eq!(settings.crossed_thresholds(11_000, 10_000), vec![80, 100, 110]);
+    assert!(settings.crossed_thresholds(500, 0).is_empty());
+}
+
+#[test]
+fn test_thresholds_fire_only_when_crossed() {
+    let settings = QuotaAlertSettings {
+        soft_thresholds: vec![80, 100],
+        grace_overage_percent: 0,
+    };
+
+    assert!(settings.thresholds_crossed_between(7_998, 7_999, 10_000).is_empty());
+    assert_eq!(settings.thresholds_crossed_between(7_999, 8_000, 10_000), vec![80]);
+    // Already past 80%: later requests do not report it again.
+    assert!(settings.thresholds_crossed_between(8_000, 8_001, 10_000).is_empty());
+    // A batch can cross several at once.
+    assert_eq!(settings.thresholds_crossed_between(7_000, 10_500, 10_000), vec![80, 100]);
+}
+
+#[test]
+fn test_quota_state_checks_and_crossings() {
+    let cycle = BillingCycleSettings::default();
+    let now = chrono::Utc::now();
+    let windows = cycle.windows(now);
+    let state = QuotaState {
+        limits: QuotaLimits {
+            rate_limit_per_minute: 60,
+            daily_quota: 100,
+            monthly_quota: 1_000,
+        },
+        settings: QuotaAlertSettings {
+            soft_thresholds: vec![80, 100],
+            grace_overage_percent: 10,
+        },
+        usage: QuotaUsageStats {
+            today: 79,
+            this_month: 799,
+            daily_remaining: 21,
+            monthly_remaining: 201,
+            timezone: cycle.timezone.clone(),
+            day_start: windows.day_start,
+            day_end: windows.day_end,
+            period_start: windows.period_start,
+            period_end: windows.period_end,
+        },
+        windows: windows.clone(),
+    };
+
+    assert!(state.within_daily_quota());
+    assert!(state.within_monthly_quota());
+    assert!(state.has_capacity(31));
+    assert!(!state.has_capacity(32));
+
+    let crossings = state.crossings(80);
+    assert_eq!(crossings.len(), 2);
+    assert_eq!(crossings[0].period, QuotaPeriod::Daily);
+    assert_eq!((crossings[0].threshold_percent, crossings[0].usage_count), (80, 80));
+    assert_eq!(crossings[0].period_start, windows.today);
+    assert_eq!(crossings[1].period, QuotaPeriod::Monthly);
+    assert_eq!((crossings[1].threshold_percent, crossings[1].usage_count), (80, 800));
+    assert_eq!(crossings[1].period_start, windows.period_start_date);
+
+    let mut past = state.clone();
+    past.usage.today = 80;
+    past.usage.this_month = 800;
+    assert!(past.crossings(81).is_empty());
+
+    past.usage.today = 110;
+    assert!(!past.within_daily_quota());
+}
+
+#[test]
+fn test_update_quota_alerts_request_apply() {
+    let req = UpdateQuotaAlertsRequest {
+        soft_thresholds: None,
+        grace_overage_percent: Some(20),
+    };
+    assert!(req.validate().is_ok());
+
+    let settings = req.apply(QuotaAlertSettings::default()).unwrap();
+    assert_eq!(settings.soft_thresholds, vec![80, 100]);
+    assert_eq!(settings.grace_overage_percent, 20);
+
+    let req = UpdateQuotaAlertsRequest {
+        soft_thresholds: Some(vec![90, 90]),
+        grace_overage_percent: None,
+    };
+    assert!(req.apply(QuotaAlertSettings::default()).is_err());
+
+    let req = UpdateQuotaAlertsRequest {
+        soft_thresholds: None,
+        grace_overage_percent: None,
+    };
+    assert!(req.validate().is_err());
+}
+
+#[test]
+fn test_quota_period_round_trip() {
+    use std::str::FromStr;
+
+    assert_eq!(QuotaPeriod::Daily.to_string(), "daily");
+    assert_eq!(QuotaPeriod::from_str("MONTHLY").unwrap(), QuotaPeriod::Monthly);
+    assert!(QuotaPeriod::from_str("weekly").is_err());
+}
+
+#[test]
+fn test_fill_usage_series_daily_zero_fill() {
+    use chrono::NaiveDate;
+    use metered_finance_api::models::quota::{fill_usage_series, UsageGranularity};
+
+    let from = NaiveDate::from_ymd_opt(2024, 1, 30).unwrap();
+    let to = NaiveDate::from_ymd_opt(2024, 2, 2).unwrap();
+    let rows = vec![(NaiveDate::from_ymd_opt(2024, 1, 31).unwrap(), 42)];
+
+    let series = fill_usage_series(from, to, UsageGranularity::Day, &rows);
+
+    assert_eq!(series.len(), 4);
+    assert_eq!(series[0].request_count, 0);
+    assert_eq!(series[1].period_start, NaiveDate::from_ymd_opt(2024, 1, 31).unwrap());
+    assert_eq!(series[1].request_count, 42);
+    assert_eq!(series[3].period_start, to);
+    assert_eq!(series[3].request_count, 0);
+}
+
+#[test]
+fn test_fill_usage_series_monthly_buckets() {
+    use chrono::NaiveDate;
+    use metered_finance_api::models::quota::{fill_usage_series, UsageGranularity};
+
+    let from = NaiveDate::from_ymd_opt(2023, 11, 15).unwrap();
+    let to = NaiveDate::from_ymd_opt(2024, 2, 3).unwrap();
+    let rows = vec![
+        (NaiveDate::from_ymd_opt(2023, 12, 1).unwrap(), 100),
+        (NaiveDate::from_ymd_opt(2024, 2, 1).unwrap(), 7),
+    ];
+
+    let series = fill_usage_series(from, to, UsageGranularity::Month, &rows);
+    let counts: Vec<i64> = series.iter().map(|p| p.request_count).collect();
+
+    assert_eq!(series[0].period_start, NaiveDate::from_ymd_opt(2023, 11, 1).unwrap());
+    assert_eq!(counts, vec![0, 100, 0, 7]);
+}
+
+#[test]
+fn test_usage_history_params_resolve() {
+    use chrono::NaiveDate;
+    use metered_finance_api::models::quota::{UsageGranularity, UsageHistoryParams};
+
+    let today = NaiveDate::from_ymd_opt(2024, 6, 15).unwrap();
+
+    let (from, to, granularity) = UsageHistoryParams::default().resolve(today).unwrap();
+    assert_eq!(granularity, UsageGranularity::Day);
+    assert_eq!(to, today);
+    assert_eq!(from, NaiveDate::from_ymd_opt(2024, 5, 17).unwrap());
+
+    let params = UsageHistoryParams {
+        granularity: Some(UsageGranularity::Month),
+        ..Default::default()
+    };
+    let (from, _, _) = params.resolve(today).unwrap();
+    assert_eq!(from, NaiveDate::from_ymd_opt(2023, 7, 1).unwrap());
+
+    let params = UsageHistoryParams {
+        from: Some(NaiveDate::from_ymd_opt(2024, 7, 1).unwrap()),
+        to: Some(today),
+        ..Default::default()
+    };
+    assert!(params.resolve(today).is_err());
+
+    let params = UsageHistoryParams {
+        from: Some(NaiveDate::from_ymd_opt(2020, 1, 1).unwrap()),
+        to: Some(today),
+        granularity: Some(UsageGranularity::Day),
+        format: None,
+    };
+    assert!(params.resolve(today).is_err());
+}
+
+#[test]
+fn test_usage_history_csv() {
+    use chrono::NaiveDate;
+    use metered_finance_api::models::quota::{
+        UsageGranularity, UsageHistoryPoint, UsageHistoryResponse,
+    };
+
+    let day = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+    let history = UsageHistoryResponse {
+        key_id: "key_csv".to_string(),
+        granularity: UsageGranularity::Month,
+        from: day,
+        to: day,
+        total_requests: 12,
+        data: vec![UsageHistoryPoint {
+            period_start: day,
+            request_count: 12,
+        }],
+    };
+
+    assert_eq!(
+        history.to_csv(),
+        "key_id,period_start,request_count\nkey_csv,2024-03-01,12\n"
+    );
+}
+
+#[test]
+fn test_billing_cycle_validation() {
+    use metered_finance_api::models::quota::BillingCycleSettings;
+
+    assert!(BillingCycleSettings::default().validate().is_ok());
+
+    let settings = BillingCycleSettings {
+        timezone: "Europe/Berlin".to_string(),
+        billing_anchor_day: 31,
+    };
+    assert!(settings.validate().is_ok());
+
+    let settings = BillingCycleSettings {
+        timezone: "Mars/Olympus".to_string(),
+        billing_anchor_day: 1,
+    };
+    assert!(settings.validate().is_err());
+
+    let settings = BillingCycleSettings {
+        timezone: "UTC".to_string(),
+        billing_anchor_day: 0,
+    };
+    assert!(settings.validate().is_err());
+}
+
+#[test]
+fn test_billing_period_with_anchor_day() {
+    use chrono::NaiveDate;
+    use metered_finance_api::models::quota::BillingCycleSettings;
+
+    let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+    let settings = BillingCycleSettings {
+        timezone: "UTC".to_string(),
+        billing_anchor_day: 15,
+    };
+
+    assert_eq!(
+        settings.billing_period(date(2024, 3, 20)),
+        (date(2024, 3, 15), date(2024, 4, 15))
+    );
+    assert_eq!(
+        settings.billing_period(date(2024, 3, 14)),
+        (date(2024, 2, 15), date(2024, 3, 15))
+    );
+    assert_eq!(
+        settings.billing_period(date(2024, 1, 1)),
+        (date(2023, 12, 15), date(2024, 1, 15))
+    );
+
+    let calendar = BillingCycleSettings::default();
+    assert_eq!(
+        calendar.billing_period(date(2024, 2, 29)),
+        (date(2024, 2, 1), date(2024, 3, 1))
+    );
+}
+
+#[test]
+fn test_billing_period_clamps_anchor_to_month_end() {
+    use chrono::NaiveDate;
+    use metered_finance_api::models::quota::BillingCycleSettings;
+
+    let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+    let settings = BillingCycleSettings {
+        timezone: "UTC".to_string(),
+        billing_anchor_day: 31,
+    };
+
+    assert_eq!(
+        settings.billing_period(date(2023, 2, 28)),
+        (date(2023, 2, 28), date(2023, 3, 31))
+    );
+    assert_eq!(
+        settings.billing_period(date(2023, 2, 27)),
+        (date(2023, 1, 31), date(2023, 2, 28))
+    );
+}
+
+#[test]
+fn test_quota_windows_use_local_timezone() {
+    use chrono::{NaiveDate, TimeZone, Utc};
+    use metered_finance_api::models::quota::BillingCycleSettings;
+
+    let settings = BillingCycleSettings {
+        timezone: "America/New_York".to_string(),
+        billing_anchor_day: 1,
+    };
+
+    // 03:00 UTC on Jan 1st is still Dec 31st in New York.
+    let now = Utc.with_ymd_and_hms(2024, 1, 1, 3, 0, 0).unwrap();
+    let windows = settings.windows(now);
+
+    assert_eq!(windows.today, NaiveDate::from_ymd_opt(2023, 12, 31).unwrap());
+    assert_eq!(windows.day_start, Utc.with_ymd_and_hms(2023, 12, 31, 5, 0, 0).unwrap());
+    assert_eq!(windows.day_end, Utc.with_ymd_and_hms(2024, 1, 1, 5, 0, 0).unwrap());
+    assert_eq!(windows.period_start, Utc.with_ymd_and_hms(2023, 12, 1, 5, 0, 0).unwrap());
+    assert_eq!(windows.period_end, Utc.with_ymd_and_hms(2024, 1, 1, 5, 0, 0).unwrap());
+}
+
+#[test]
+fn test_local_midnight_skips_dst_gap() {
+    use chrono::{NaiveDate, TimeZone, Utc};
+    use metered_finance_api::models::quota::BillingCycleSettings;
+
+    // Santiago springs forward at local midnight, so 00:00 does not exist.
+    let settings = BillingCycleSettings {
+        timezone: "America/Santiago".to_string(),
+        billing_anchor_day: 1,
+    };
+
+    let midnight = settings.local_midnight(NaiveDate::from_ymd_opt(2023, 9, 3).unwrap());
+    assert_eq!(midnight, Utc.with_ymd_and_hms(2023, 9, 3, 4, 0, 0).unwrap());
+}
@@ -0,0 +1,217 @@
+use chrono::{NaiveDate, TimeZone, Utc};
+use metered_finance_api::models::{
+    finance::Currency,
+    reconciliation::{
+        match_lines, parse_camt053, parse_csv_statement, parse_statement, CsvColumnMapping,
+        MatchCandidate, MatchMethod, MatchOutcome, MatchSettings, MatchStatus,
+        ParsedStatementLine, ReconciliationSummary, StatementError, StatementFormat,
+        StatementLineResponse,
+    },
+};
+use serde_json::json;
+
+fn mapping() -> CsvColumnMapping {
+    serde_json::from_value(json!({
+        "date": "Booking Date",
+        "amount": "Amount",
+        "reference": "Reference",
+        "description": "Text",
+        "date_format": "%d.%m.%Y",
+        "default_currency": "EUR",
+        "delimiter": ";"
+    }))
+    .unwrap()
+}
+
+fn line(day: u32, amount: f64, reference: Option<&str>) -> ParsedStatementLine {
+    ParsedStatementLine {
+        booking_date: NaiveDate::from_ymd_opt(2024, 3, day).unwrap(),
+        amount,
+        currency: Currency::EUR,
+        reference: reference.map(str::to_string),
+        description: None,
+    }
+}
+
+fn candidate(id: &str, day: u32, amount: f64, reference: Option<&str>) -> MatchCandidate {
+    MatchCandidate {
+        transaction_id: id.to_string(),
+        amount,
+        currency: Currency::EUR,
+        created_at: Utc.with_ymd_and_hms(2024, 3, day, 9, 0, 0).unwrap(),
+        metadata: reference.map(|r| json!({ "reference": r })),
+    }
+}
+
+#[test]
+fn test_parse_csv_statement_with_mapping() {
+    let content = "Booking Date;Amount;Reference;Text\n\
+                   01.03.2024;1,250.00;INV-1;Invoice 1\n\
+                   02.03.2024;(42.10);;Card fee\n";
+
+    let lines = parse_csv_statement(content, &mapping()).unwrap();
+
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0].booking_date, NaiveDate::from_ymd_opt(2024, 3, 1).unwrap());
+    assert_eq!(lines[0].amount, 1250.0);
+    assert_eq!(lines[0].currency, Currency::EUR);
+    assert_eq!(lines[0].reference.as_deref(), Some("INV-1"));
+    assert_eq!(lines[1].amount, -42.10);
+    assert_eq!(lines[1].reference, None);
+    assert_eq!(lines[1].description.as_deref(), Some("Card fee"));
+}
+
+#[test]
+fn test_parse_csv_statement_errors() {
+    let missing = parse_csv_statement("Date;Amount\n01.03.2024;1\n", &mapping());
+    assert_eq!(missing, Err(StatementError::MissingColumn("Booking Date".to_string())));
+
+    let invalid = parse_csv_statement(
+        "Booking Date;Amount;Reference;Text\n01.03.2024;abc;;\n",
+        &mapping(),
+    );
+    assert!(matches!(invalid, Err(StatementError::InvalidLine { line: 2, .. })));
+
+    assert_eq!(
+        parse_statement(StatementFormat::Csv, "a,b\n", None, 10),
+        Err(StatementError::MissingMapping)
+    );
+    assert_eq!(
+        parse_statement(StatementFormat::Csv, "Booking Date;Amount;Reference;Text\n", Some(&mapping()), 10),
+        Err(StatementError::Empty)
+    );
+}
+
+#[test]
+fn test_parse_camt053() {
+    let content = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Document xmlns="urn:iso:std:iso:20022:tech:xsd:camt.053.001.02">
+  <BkToCstmrStmt>
+    <Stmt>
+      <Ntry>
+        <Amt Ccy="EUR">100.50</Amt>
+        <CdtDbtInd>CRDT</CdtDbtInd>
+        <BookgDt><Dt>2024-03-01</Dt></BookgDt>
+        <AcctSvcrRef>BANK-001</AcctSvcrRef>
+        <NtryDtls><TxDtls><RmtInf><Ustrd>Order 42</Ustrd></RmtInf></TxDtls></NtryDtls>
+      </Ntry>
+      <Ntry>
+        <Amt Ccy="USD">20</Amt>
+        <CdtDbtInd>DBIT</CdtDbtInd>
+        <BookgDt><DtTm>2024-03-02T10:00:00</DtTm></BookgDt>
+        <NtryDtls><TxDtls><Refs><EndToEndId>E2E-7</EndToEndId></Refs></TxDtls></NtryDtls>
+      </Ntry>
+    </Stmt>
+  </BkToCstmrStmt>
+</Document>"#;
+
+    let lines = parse_camt053(content).unwrap();
+
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0].amount, 100.5);
+    assert_eq!(lines[0].reference.as_deref(), Some("BANK-001"));
+    assert_eq!(lines[0].description.as_deref(), Some("Order 42"));
+    assert_eq!(lines[1].amount, -20.0);
+    assert_eq!(lines[1].currency, Currency::USD);
+    assert_eq!(lines[1].booking_date, NaiveDate::from_ymd_opt(2024, 3, 2).unwrap());
+    assert_eq!(lines[1].reference.as_deref(), Some("E2E-7"));
+
+    assert!(matches!(parse_camt053("<Document>"), Err(StatementError::InvalidXml(_))));
+}
+
+#[test]
+fn test_match_prefers_reference() {
+    let candidates = vec![
+        candidate("txn_a", 1, 50.0, None),
+        candidate("txn_b", 2, 50.0, Some("INV-9")),
+    ];
+
+    let outcomes = match_lines(&[line(1, 50.0, Some("inv-9"))], &candidates, &MatchSettings::default());
+
+    assert_eq!(
+        outcomes,
+        vec![MatchOutcome::Matched {
+            transaction_id: "txn_b".to_string(),
+            method: MatchMethod::Reference,
+        }]
+    );
+}
+
+#[test]
+fn test_match_ambiguous_and_unmatched() {
+    let candidates = vec![
+        candidate("txn_a", 1, 50.0, None),
+        candidate("txn_b", 2, 50.0, None),
+        candidate("txn_c", 20, 75.0, None),
+    ];
+
+    let outcomes = match_lines(
+        &[line(1, 50.0, None), line(1, 75.0, None), line(1, 99.0, None)],
+        &candidates,
+        &MatchSettings::default(),
+    );
+
+    assert_eq!(
+        outcomes,
+        vec![
+            MatchOutcome::Ambiguous(vec!["txn_a".to_string(), "txn_b".to_string()]),
+            // txn_c is outside the date tolerance.
+            MatchOutcome::Unmatched,
+            MatchOutcome::Unmatched,
+        ]
+    );
+}
+
+#[test]
+fn test_match_uses_each_transaction_once() {
+    let candidates = vec![candidate("txn_a", 1, 50.0, None)];
+    let settings = MatchSettings {
+        date_tolerance_days: 0,
+        ..MatchSettings::default()
+    };
+
+    let outcomes = match_lines(&[line(1, -50.0, None), line(1, -50.0, None)], &candidates, &settings);
+
+    assert_eq!(
+        outcomes,
+        vec![
+            MatchOutcome::Matched {
+                transaction_id: "txn_a".to_string(),
+                method: MatchMethod::Amount,
+            },
+            MatchOutcome::Unmatched,
+        ]
+    );
+}
+
+#[test]
+fn test_summary_from_lines() {
+    let response = |amount: f64, status: MatchStatus| StatementLineResponse {
+        line_id: "stl_1".to_string(),
+        import_id: "stm_1".to_string(),
+        line_number: 1,
+        booking_date: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+        amount,
+        currency: Currency::EUR,
+        reference: None,
+        description: None,
+        status,
+        transaction_id: None,
+        match_method: None,
+        candidate_ids: Vec::new(),
+        matched_at: None,
+    };
+
+    let summary = ReconciliationSummary::from_lines(&[
+        response(10.0, MatchStatus::Matched),
+        response(-5.0, MatchStatus::Unmatched),
+        response(2.5, MatchStatus::Ambiguous),
+    ]);
+
+    assert_eq!(summary.total, 3);
+    assert_eq!(summary.matched, 1);
+    assert_eq!(summary.unmatched, 1);
+    assert_eq!(summary.ambiguous, 1);
+    assert_eq!(summary.matched_amount, 10.0);
+    assert_eq!(summary.open_amount, 7.5);
+}
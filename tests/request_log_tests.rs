@@ -0,0 +1,167 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use metered_finance_api::observability::{
+    metrics::init_metrics,
+    request_log::{RequestLogEntry, RequestLogSettings, RequestLogSink, RequestLogger},
+};
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::sync::Notify;
+
+/// Records every batch it is given instead of writing to the database.
+#[derive(Clone, Default)]
+struct RecordingSink {
+    batches: Arc<Mutex<Vec<Vec<String>>>>,
+    /// When set, writes wait until notified so the channel can fill up.
+    gate: Option<Arc<Notify>>,
+}
+
+impl RecordingSink {
+    fn batch_sizes(&self) -> Vec<usize> {
+        self.batches.lock().unwrap().iter().map(Vec::len).collect()
+    }
+
+    fn paths(&self) -> Vec<String> {
+        self.batches.lock().unwrap().concat()
+    }
+}
+
+#[async_trait]
+impl RequestLogSink for RecordingSink {
+    async fn write_batch(&self, batch: &[RequestLogEntry]) -> Result<(), sqlx::Error> {
+        if let Some(gate) = &self.gate {
+            gate.notified().await;
+        }
+        self.batches
+            .lock()
+            .unwrap()
+            .push(batch.iter().map(|entry| entry.path.clone()).collect());
+        Ok(())
+    }
+}
+
+fn entry(n: usize) -> RequestLogEntry {
+    RequestLogEntry {
+        key_id: Some("key_request_log_test".to_string()),
+        account_id: None,
+        path: format!("/api/accounts/acc_{}", n),
+        method: "GET".to_string(),
+        status: 200,
+        latency_ms: 3,
+        error_code: None,
+        ts: Utc::now(),
+    }
+}
+
+#[tokio::test]
+async fn test_flushes_when_batch_is_full() {
+    let sink = RecordingSink::default();
+    let logger = RequestLogger::spawn(
+        sink.clone(),
+        RequestLogSettings {
+            buffer_size: 100,
+            batch_size: 5,
+            flush_interval: Duration::from_secs(3600),
+        },
+    );
+
+    for n in 0..12 {
+        assert!(logger.log(entry(n)));
+    }
+
+    for _ in 0..100 {
+        if sink.batch_sizes().len() >= 2 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+    assert_eq!(sink.batch_sizes(), vec![5, 5]);
+
+    // The partial batch is written on shutdown.
+    logger.shutdown().await;
+    assert_eq!(sink.batch_sizes(), vec![5, 5, 2]);
+    assert_eq!(sink.paths().len(), 12);
+    assert_eq!(sink.paths()[0], "/api/accounts/acc_0");
+}
+
+#[tokio::test]
+async fn test_flushes_partial_batch_on_interval() {
+    let sink = RecordingSink::default();
+    let logger = RequestLogger::spawn(
+        sink.clone(),
+        RequestLogSettings {
+            buffer_size: 100,
+            batch_size: 500,
+            flush_interval: Duration::from_millis(50),
+        },
+    );
+
+    logger.log(entry(1));
+    logger.log(entry(2));
+    for _ in 0..100 {
+        if !sink.batch_sizes().is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    assert_eq!(sink.batch_sizes(), vec![2]);
+    logger.shutdown().await;
+    assert_eq!(sink.batch_sizes(), vec![2]);
+}
+
+#[tokio::test]
+async fn test_drops_when_buffer_is_full() {
+    let handle = init_metrics().unwrap();
+    let gate = Arc::new(Notify::new());
+    let sink = RecordingSink {
+        gate: Some(gate.clone()),
+        ..RecordingSink::default()
+    };
+    let logger = RequestLogger::spawn(
+        sink.clone(),
+        RequestLogSettings {
+            buffer_size: 4,
+            batch_size: 1,
+            flush_interval: Duration::from_secs(3600),
+        },
+    );
+
+    // The writer takes the first entry and blocks on the sink, leaving room
+    // for four more in the channel.
+    assert!(logger.log(entry(0)));
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    let accepted = (1..10).filter(|&n| logger.log(entry(n))).count();
+    assert_eq!(accepted, 4);
+    assert_eq!(logger.queue_depth(), 4);
+
+    let rendered = handle.render();
+    assert!(rendered.contains("request_log_dropped_total{reason=\"buffer_full\"}"));
+
+    let release = tokio::spawn(async move {
+        loop {
+            gate.notify_one();
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+    });
+    logger.shutdown().await;
+    release.abort();
+
+    assert_eq!(sink.paths().len(), 5);
+}
+
+#[tokio::test]
+async fn test_logging_after_shutdown_is_dropped() {
+    let sink = RecordingSink::default();
+    let logger = RequestLogger::spawn(sink.clone(), RequestLogSettings::default());
+
+    assert!(logger.log(entry(1)));
+    logger.shutdown().await;
+    logger.shutdown().await;
+
+    assert!(!logger.log(entry(2)));
+    assert_eq!(sink.paths(), vec!["/api/accounts/acc_1".to_string()]);
+}
@@ -1,7 +1,7 @@
 use metered_finance_api::models::{
     responses::{KeyCreatedResponse, KeyInfoResponse, UsageResponse},
     keys::Scope,
-    quota::{QuotaLimits, QuotaUsageStats},
+    quota::{QuotaAlertStatus, QuotaLimits, QuotaUsageStats},
 };
 
 #[test]
@@ -59,12 +59,24 @@ fn test_usage_response() {
         this_month: 5000,
         daily_remaining: 9500,
         monthly_remaining: 295000,
+        timezone: "UTC".to_string(),
+        day_start: chrono::Utc::now(),
+        day_end: chrono::Utc::now(),
+        period_start: chrono::Utc::now(),
+        period_end: chrono::Utc::now(),
     };
 
     let response = UsageResponse {
         key_id: "key_789".to_string(),
         limits,
         usage,
+        alerts: QuotaAlertStatus {
+            soft_thresholds: vec![80, 100],
+            grace_overage_percent: 0,
+            daily_overage: 0,
+            monthly_overage: 0,
+            thresholds: vec![],
+        },
     };
 
     assert_eq!(response.key_id, "key_789");
@@ -80,16 +92,29 @@ fn test_usage_response_serialization() {
         this_month: 1000,
         daily_remaining: 9900,
         monthly_remaining: 299000,
+        timezone: "UTC".to_string(),
+        day_start: chrono::Utc::now(),
+        day_end: chrono::Utc::now(),
+        period_start: chrono::Utc::now(),
+        period_end: chrono::Utc::now(),
     };
 
     let response = UsageResponse {
         key_id: "key_test".to_string(),
         limits,
         usage,
+        alerts: QuotaAlertStatus {
+            soft_thresholds: vec![80, 100],
+            grace_overage_percent: 10,
+            daily_overage: 0,
+            monthly_overage: 0,
+            thresholds: vec![],
+        },
     };
 
     let json = serde_json::to_string(&response).unwrap();
     assert!(json.contains("key_test"));
     assert!(json.contains("daily_remaining"));
     assert!(json.contains("monthly_remaining"));
+    assert!(json.contains("grace_overage_percent"));
 }
\ No newline at end of file
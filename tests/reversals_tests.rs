@@ -0,0 +1,229 @@
+use axum::{http::StatusCode, response::IntoResponse};
+use chrono::Utc;
+use metered_finance_api::{
+    middleware::errors::AppError,
+    models::{
+        disputes::{check_not_reversed, DisputeError, DisputeStatus},
+        finance::{Currency, TransactionStatus, TransactionType},
+        lifecycle::AccountStatus,
+        requests::CreateTransactionRequest,
+        responses::TransactionResponse,
+        reversals::{
+            check_correctable, check_undisputed, links_for, reversal_amount, AdjustTransactionRequest, ReversalError,
+            ReverseTransactionRequest, TransactionLink, TransactionLinkKind,
+        },
+    },
+};
+use serde_json::json;
+
+fn transaction(transaction_type: TransactionType, status: TransactionStatus) -> TransactionResponse {
+    TransactionResponse {
+        transaction_id: "txn_1".to_string(),
+        account_id: "acc_1".to_string(),
+        amount: 100.0,
+        currency: Currency::USD,
+        transaction_type,
+        status,
+        description: None,
+        metadata: None,
+        created_at: Utc::now(),
+        processed_at: None,
+        failure_reason: None,
+        fee: None,
+        links: None,
+    }
+}
+
+fn link(transaction_id: &str, original: &str, kind: TransactionLinkKind, reason: Option<&str>) -> TransactionLink {
+    TransactionLink {
+        transaction_id: transaction_id.to_string(),
+        original_transaction_id: original.to_string(),
+        kind,
+        reason: reason.map(str::to_string),
+    }
+}
+
+#[test]
+fn test_reversal_amount_undoes_the_balance_effect() {
+    assert_eq!(reversal_amount(100.0), -100.0);
+    assert_eq!(reversal_amount(-25.5), 25.5);
+
+    // Outflows are stored negative, so their reversal credits the account.
+    for (transaction_type, amount) in [
+        (TransactionType::Payment, 100.0),
+        (TransactionType::Adjustment, -25.5),
+        (TransactionType::Refund, 40.0),
+        (TransactionType::Fee, 3.2),
+        (TransactionType::Payout, 250.0),
+    ] {
+        let before = 1000.0;
+        let stored = transaction_type.ledger_amount(amount);
+        let after_original = before + stored;
+        assert_ne!(after_original, before);
+        assert_eq!(after_original + reversal_amount(stored), before);
+    }
+}
+
+#[test]
+fn test_only_completed_uncorrected_transactions_can_be_reversed() {
+    let completed = transaction(TransactionType::Payment, TransactionStatus::Completed);
+    assert!(check_correctable(&completed, None).is_ok());
+
+    let pending = transaction(TransactionType::Payment, TransactionStatus::Pending);
+    assert!(matches!(
+        check_correctable(&pending, None),
+        Err(ReversalError::NotCompleted { status: TransactionStatus::Pending, .. })
+    ));
+
+    for transaction_type in [TransactionType::Reversal, TransactionType::Chargeback] {
+        let entry = transaction(transaction_type, TransactionStatus::Completed);
+        assert!(matches!(
+            check_correctable(&entry, None),
+            Err(ReversalError::NotReversible { .. })
+        ));
+    }
+
+    match check_correctable(&completed, Some("txn_2")) {
+        Err(ReversalError::AlreadyReversed { reversed_by, .. }) => assert_eq!(reversed_by, "txn_2"),
+        other => panic!("expected AlreadyReversed, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_links_are_shown_both_ways() {
+    let links = vec![
+        link("txn_2", "txn_1", TransactionLinkKind::Adjustment, Some("Overcharged")),
+        link("txn_3", "txn_1", TransactionLinkKind::Adjustment, Some("Overcharged again")),
+        link("txn_4", "txn_1", TransactionLinkKind::Reversal, None),
+    ];
+
+    let original = links_for("txn_1", &links).unwrap();
+    assert_eq!(original.reversed_by.as_deref(), Some("txn_4"));
+    assert_eq!(original.adjusted_by, vec!["txn_2", "txn_3"]);
+    assert_eq!(original.reverses, None);
+    assert_eq!(original.reason, None);
+
+    let adjustment = links_for("txn_2", &links).unwrap();
+    assert_eq!(adjustment.adjusts.as_deref(), Some("txn_1"));
+    assert_eq!(adjustment.reason.as_deref(), Some("Overcharged"));
+    assert!(adjustment.adjusted_by.is_empty());
+
+    let reversal = links_for("txn_4", &links).unwrap();
+    assert_eq!(reversal.reverses.as_deref(), Some("txn_1"));
+
+    assert!(links_for("txn_9", &links).is_none());
+
+    let value = serde_json::to_value(&reversal).unwrap();
+    assert_eq!(value, json!({"reverses": "txn_1"}));
+}
+
+#[test]
+fn test_adjustment_requires_reason_and_signed_amount() {
+    let req: AdjustTransactionRequest =
+        serde_json::from_value(json!({"amount": -10.0, "reason": "Overcharged"})).unwrap();
+    assert!(req.validate().is_ok());
+
+    assert!(serde_json::from_value::<AdjustTransactionRequest>(json!({"amount": 5.0})).is_err());
+
+    let mut bad = req.clone();
+    bad.reason = "   ".to_string();
+    assert!(bad.validate().is_err());
+    bad = req.clone();
+    bad.amount = 0.0;
+    assert!(bad.validate().is_err());
+    bad = req.clone();
+    bad.amount = 1.005;
+    assert!(bad.validate().is_err());
+    bad = req;
+    bad.reason = "x".repeat(501);
+    assert!(bad.validate().is_err());
+
+    assert!(ReverseTransactionRequest::default().validate().is_ok());
+    let blank = ReverseTransactionRequest { reason: Some(String::new()) };
+    assert!(blank.validate().is_err());
+}
+
+#[test]
+fn test_reversal_type_cannot_be_created_directly() {
+    assert_eq!("reversal".parse::<TransactionType>().unwrap(), TransactionType::Reversal);
+    assert_eq!(TransactionType::Reversal.to_string(), "reversal");
+    assert!(!TransactionType::Reversal.is_outflow());
+
+    let req = CreateTransactionRequest {
+        account_id: "acc_1".to_string(),
+        amount: 10.0,
+        currency: Currency::USD,
+        transaction_type: TransactionType::Reversal,
+        description: None,
+        metadata: None,
+    };
+    assert!(req.validate().is_err());
+}
+
+#[test]
+fn test_reversal_errors_map_to_status_codes() {
+    let cases = [
+        (ReversalError::NotFound("txn_1".to_string()), StatusCode::NOT_FOUND),
+        (
+            ReversalError::AlreadyReversed {
+                transaction_id: "txn_1".to_string(),
+                reversed_by: "txn_2".to_string(),
+            },
+            StatusCode::CONFLICT,
+        ),
+        (
+            ReversalError::NotCompleted {
+                transaction_id: "txn_1".to_string(),
+                status: TransactionStatus::Failed,
+            },
+            StatusCode::CONFLICT,
+        ),
+        (
+            ReversalError::AccountUnavailable {
+                account_id: "acc_1".to_string(),
+                status: AccountStatus::Frozen,
+            },
+            StatusCode::CONFLICT,
+        ),
+    ];
+
+    for (error, status) in cases {
+        assert_eq!(AppError::from(error).into_response().status(), status);
+    }
+}
+
+#[test]
+fn test_transaction_response_hides_missing_links() {
+    let mut response = transaction(TransactionType::Payment, TransactionStatus::Completed);
+    assert!(serde_json::to_value(&response).unwrap().get("links").is_none());
+
+    response.links = links_for("txn_1", &[link("txn_2", "txn_1", TransactionLinkKind::Reversal, None)]);
+    let value = serde_json::to_value(&response).unwrap();
+    assert_eq!(value["links"], json!({"reversed_by": "txn_2"}));
+}
+
+#[test]
+fn test_payments_with_open_or_lost_disputes_cannot_be_reversed() {
+    assert!(check_undisputed("txn_1", &[]).is_ok());
+    assert!(check_undisputed("txn_1", &[("dsp_1".to_string(), DisputeStatus::Won)]).is_ok());
+
+    for status in [DisputeStatus::NeedsResponse, DisputeStatus::UnderReview, DisputeStatus::Lost] {
+        let disputes = [("dsp_1".to_string(), DisputeStatus::Won), ("dsp_2".to_string(), status)];
+        match check_undisputed("txn_1", &disputes) {
+            Err(error @ ReversalError::Disputed { .. }) => {
+                assert!(error.to_string().contains("dsp_2"));
+                assert_eq!(AppError::from(error).into_response().status(), StatusCode::CONFLICT);
+            }
+            other => panic!("expected Disputed, got {:?}", other),
+        }
+    }
+}
+
+#[test]
+fn test_reversed_payments_cannot_be_disputed() {
+    assert!(check_not_reversed("txn_1", None).is_ok());
+
+    let error = check_not_reversed("txn_1", Some("txn_2")).unwrap_err();
+    assert!(matches!(&error, DisputeError::Reversed { reversed_by, .. } if reversed_by == "txn_2"));
+    assert_eq!(AppError::from(error).into_response().status(), StatusCode::CONFLICT);
+}
@@ -0,0 +1,163 @@
+use chrono::{Duration, TimeZone, Utc};
+use metered_finance_api::models::{
+    analytics::TimeBucket,
+    rollups::{
+        histogram_sql, histogram_sum_sql, rollup_window, AnalyticsSource, LatencyHistogram,
+        RollupGranularity, RollupSettings, LATENCY_BOUNDS_MS,
+    },
+};
+
+fn at(day: u32, hour: u32, minute: u32) -> chrono::DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 1, day, hour, minute, 0).unwrap()
+}
+
+fn histogram(counts: &[(usize, i64)]) -> LatencyHistogram {
+    let mut buckets = vec![0; LATENCY_BOUNDS_MS.len() + 1];
+    for &(index, count) in counts {
+        buckets[index] = count;
+    }
+    LatencyHistogram(buckets)
+}
+
+#[test]
+fn test_histogram_percentile_interpolates_within_bucket() {
+    // 100 requests between 10ms and 25ms.
+    let hist = histogram(&[(2, 100)]);
+    assert_eq!(hist.total(), 100);
+    assert_eq!(hist.percentile(0.5), Some(17.5));
+    assert_eq!(hist.percentile(1.0), Some(25.0));
+
+    // 90 fast requests and 10 slow ones: p95 lands in the slow bucket.
+    let hist = histogram(&[(0, 90), (6, 10)]);
+    assert_eq!(hist.percentile(0.5).map(|p| p.round()), Some(3.0));
+    assert_eq!(hist.percentile(0.95), Some(375.0));
+}
+
+#[test]
+fn test_histogram_percentile_edge_cases() {
+    assert_eq!(histogram(&[]).percentile(0.5), None);
+
+    // Overflow bucket reports its lower bound.
+    let hist = histogram(&[(LATENCY_BOUNDS_MS.len(), 4)]);
+    assert_eq!(hist.percentile(0.99), Some(60_000.0));
+}
+
+#[test]
+fn test_histogram_sql_covers_every_bucket() {
+    let sql = histogram_sql("latency_ms");
+    assert_eq!(sql.matches("COUNT(*)").count(), LATENCY_BOUNDS_MS.len() + 1);
+    assert!(sql.starts_with("ARRAY[COUNT(*) FILTER (WHERE latency_ms < 5)"));
+    assert!(sql.contains("latency_ms >= 5 AND latency_ms < 10"));
+    assert!(sql.ends_with("COUNT(*) FILTER (WHERE latency_ms >= 60000)]::BIGINT[]"));
+
+    let sum = histogram_sum_sql("latency_histogram");
+    assert!(sum.contains("SUM(latency_histogram[1])"));
+    assert!(sum.contains(&format!("SUM(latency_histogram[{}])", LATENCY_BOUNDS_MS.len() + 1)));
+}
+
+#[test]
+fn test_rollup_window() {
+    let minute = RollupGranularity::Minute;
+
+    // First run starts from the earliest request, truncated to the bucket.
+    let window = rollup_window(
+        minute,
+        None,
+        Some(at(15, 10, 0) + Duration::seconds(42)),
+        at(15, 10, 5) + Duration::seconds(30),
+        Duration::hours(6),
+    );
+    assert_eq!(window, Some((at(15, 10, 0), at(15, 10, 5))));
+
+    // Long backlogs are processed in chunks.
+    let window = rollup_window(minute, Some(at(14, 0, 0)), None, at(15, 0, 0), Duration::hours(6));
+    assert_eq!(window, Some((at(14, 0, 0), at(14, 6, 0))));
+
+    // Caught up, or nothing logged yet.
+    assert_eq!(
+        rollup_window(minute, Some(at(15, 10, 5)), None, at(15, 10, 5), Duration::hours(6)),
+        None
+    );
+    assert_eq!(rollup_window(minute, None, None, at(15, 10, 5), Duration::hours(6)), None);
+
+    // Hours only roll up once every minute in them has been rolled up.
+    let window = rollup_window(
+        RollupGranularity::Hour,
+        Some(at(15, 8, 0)),
+        None,
+        at(15, 10, 59),
+        Duration::days(7),
+    );
+    assert_eq!(window, Some((at(15, 8, 0), at(15, 10, 0))));
+}
+
+#[test]
+fn test_analytics_source_for_range() {
+    let settings = RollupSettings::default();
+    let now = at(20, 12, 0);
+
+    assert_eq!(
+        AnalyticsSource::for_range(now - Duration::hours(1), now, None, now, &settings),
+        AnalyticsSource::Raw
+    );
+    assert_eq!(
+        AnalyticsSource::for_range(now - Duration::days(1), now, None, now, &settings),
+        AnalyticsSource::Minute
+    );
+    assert_eq!(
+        AnalyticsSource::for_range(now - Duration::days(10), now, None, now, &settings),
+        AnalyticsSource::Hour
+    );
+
+    // A per-minute series keeps minute resolution on longer ranges.
+    assert_eq!(
+        AnalyticsSource::for_range(
+            now - Duration::days(5),
+            now,
+            Some(TimeBucket::Minute),
+            now,
+            &settings
+        ),
+        AnalyticsSource::Minute
+    );
+}
+
+#[test]
+fn test_analytics_source_falls_back_after_retention() {
+    let settings = RollupSettings {
+        request_retention_days: 7,
+        minute_retention_days: 3,
+        ..RollupSettings::default()
+    };
+    let now = at(20, 12, 0);
+
+    // Short range, but raw rows and minute rollups for it have been pruned.
+    let start = now - Duration::days(8);
+    assert_eq!(
+        AnalyticsSource::for_range(start, start + Duration::hours(1), None, now, &settings),
+        AnalyticsSource::Hour
+    );
+
+    let start = now - Duration::days(2);
+    assert_eq!(
+        AnalyticsSource::for_range(start, start + Duration::hours(1), None, now, &settings),
+        AnalyticsSource::Raw
+    );
+
+    let start = now - Duration::days(5);
+    assert_eq!(
+        AnalyticsSource::for_range(start, start + Duration::days(1), None, now, &settings),
+        AnalyticsSource::Hour
+    );
+    assert_eq!(
+        AnalyticsSource::for_range(now - Duration::days(2), now, None, now, &settings),
+        AnalyticsSource::Minute
+    );
+}
+
+#[test]
+fn test_analytics_source_serialization() {
+    assert_eq!(serde_json::to_string(&AnalyticsSource::Minute).unwrap(), "\"minute\"");
+    assert_eq!(AnalyticsSource::default(), AnalyticsSource::Raw);
+    assert_eq!(RollupGranularity::Hour.to_string(), "hour");
+}
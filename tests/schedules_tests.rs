@@ -0,0 +1,246 @@
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use metered_finance_api::models::{
+    finance::{Currency, TransactionType},
+    lifecycle::AccountStatus,
+    posting::PostingError,
+    requests::CreateTransactionRequest,
+    schedules::{
+        Attempt, Clock, CreateScheduleRequest, ManualClock, Recurrence, RetryPolicy,
+        ScheduleAction, ScheduleRunStatus, ScheduleStatus, TransactionScheduleResponse,
+    },
+};
+use serde_json::json;
+
+fn at(y: i32, m: u32, d: u32, h: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(y, m, d, h, 0, 0).unwrap()
+}
+
+fn transaction() -> CreateTransactionRequest {
+    CreateTransactionRequest {
+        account_id: "acc_1".to_string(),
+        amount: 9.99,
+        currency: Currency::USD,
+        transaction_type: TransactionType::Payment,
+        description: Some("Subscription".to_string()),
+        metadata: Some(json!({"plan": "pro"})),
+    }
+}
+
+fn schedule(recurrence: Recurrence, start_at: DateTime<Utc>) -> TransactionScheduleResponse {
+    let first = recurrence.first_occurrence(start_at);
+    TransactionScheduleResponse {
+        schedule_id: "sch_1".to_string(),
+        transaction: transaction(),
+        recurrence,
+        start_at,
+        end_at: None,
+        max_occurrences: None,
+        retry: RetryPolicy {
+            max_retries: 2,
+            backoff_secs: 3600,
+        },
+        status: ScheduleStatus::Active,
+        occurrences: 0,
+        attempts: 0,
+        next_occurrence_at: Some(first),
+        next_run_at: Some(first),
+        last_error: None,
+        created_at: start_at,
+        updated_at: start_at,
+    }
+}
+
+#[test]
+fn test_monthly_recurrence_clamps_to_month_end() {
+    let monthly = Recurrence::Monthly {
+        day: 31,
+        interval: 1,
+    };
+    let start = at(2024, 1, 10, 9);
+
+    let first = monthly.first_occurrence(start);
+    assert_eq!(first, at(2024, 1, 31, 9));
+    let second = monthly.next_occurrence(start, first).unwrap();
+    assert_eq!(second, at(2024, 2, 29, 9));
+    // The day is kept even after a short month.
+    assert_eq!(monthly.next_occurrence(start, second).unwrap(), at(2024, 3, 31, 9));
+
+    // A day already past in the start month moves to the next month.
+    let fifth = Recurrence::Monthly {
+        day: 5,
+        interval: 3,
+    };
+    let first = fifth.first_occurrence(start);
+    assert_eq!(first, at(2024, 2, 5, 9));
+    assert_eq!(fifth.next_occurrence(start, first).unwrap(), at(2024, 5, 5, 9));
+}
+
+#[test]
+fn test_weekly_and_once() {
+    let start = at(2024, 3, 4, 12);
+    let weekly = Recurrence::Weekly { interval: 2 };
+    assert_eq!(weekly.first_occurrence(start), start);
+    assert_eq!(weekly.next_occurrence(start, start).unwrap(), at(2024, 3, 18, 12));
+
+    assert_eq!(Recurrence::Once.first_occurrence(start), start);
+    assert_eq!(Recurrence::Once.next_occurrence(start, start), None);
+
+    let parsed: Recurrence = serde_json::from_value(json!({"kind": "weekly"})).unwrap();
+    assert_eq!(parsed, Recurrence::Weekly { interval: 1 });
+    assert!(Recurrence::Monthly {
+        day: 32,
+        interval: 1
+    }
+    .validate()
+    .is_err());
+}
+
+#[test]
+fn test_retry_backoff_and_exhaustion() {
+    let clock = ManualClock::new(at(2024, 1, 1, 0));
+    let monthly = schedule(
+        Recurrence::Monthly {
+            day: 1,
+            interval: 1,
+        },
+        clock.now(),
+    );
+
+    let step = monthly.step(Attempt::Retryable, clock.now());
+    assert_eq!(step.run_status, ScheduleRunStatus::Retrying);
+    assert_eq!(step.attempts, 1);
+    assert_eq!(step.next_occurrence_at, monthly.next_occurrence_at);
+    assert_eq!(step.next_run_at, Some(at(2024, 1, 1, 1)));
+
+    clock.advance(Duration::hours(1));
+    let mut retried = monthly.clone();
+    retried.attempts = 1;
+    let step = retried.step(Attempt::Retryable, clock.now());
+    assert_eq!(step.next_run_at, Some(at(2024, 1, 1, 3)));
+
+    // Out of retries: the occurrence fails and the next one is scheduled.
+    retried.attempts = 2;
+    let step = retried.step(Attempt::Retryable, clock.now());
+    assert_eq!(step.run_status, ScheduleRunStatus::Failed);
+    assert_eq!(step.status, ScheduleStatus::Active);
+    assert_eq!(step.occurrences, 1);
+    assert_eq!(step.attempts, 0);
+    assert_eq!(step.next_run_at, Some(at(2024, 2, 1, 0)));
+}
+
+#[test]
+fn test_retries_never_pass_the_next_occurrence() {
+    let start = at(2024, 1, 1, 0);
+    let mut weekly = schedule(Recurrence::Weekly { interval: 1 }, start);
+    weekly.retry = RetryPolicy {
+        max_retries: 5,
+        backoff_secs: 4 * 86_400,
+    };
+    weekly.attempts = 1;
+
+    let step = weekly.step(Attempt::Retryable, start + Duration::days(4));
+    assert_eq!(step.run_status, ScheduleRunStatus::Failed);
+    assert_eq!(step.next_run_at, Some(at(2024, 1, 8, 0)));
+}
+
+#[test]
+fn test_completion_and_failure_classification() {
+    let start = at(2024, 1, 1, 0);
+
+    let once = schedule(Recurrence::Once, start);
+    let step = once.step(Attempt::Created, start);
+    assert_eq!(step.status, ScheduleStatus::Completed);
+    assert_eq!(step.next_run_at, None);
+
+    let mut limited = schedule(Recurrence::Weekly { interval: 1 }, start);
+    limited.max_occurrences = Some(2);
+    limited.occurrences = 1;
+    assert_eq!(limited.step(Attempt::Skipped, start).status, ScheduleStatus::Completed);
+
+    let mut ending = schedule(Recurrence::Weekly { interval: 1 }, start);
+    ending.end_at = Some(start + Duration::days(6));
+    assert_eq!(ending.step(Attempt::Created, start).status, ScheduleStatus::Completed);
+
+    let step = ending.step(Attempt::Fatal, start);
+    assert_eq!(step.status, ScheduleStatus::Failed);
+    assert_eq!(step.run_status, ScheduleRunStatus::Failed);
+
+    let frozen = PostingError::AccountUnavailable {
+        account_id: "acc_1".to_string(),
+        status: AccountStatus::Frozen,
+    };
+    let closed = PostingError::AccountUnavailable {
+        account_id: "acc_1".to_string(),
+        status: AccountStatus::Closed,
+    };
+    let blocked = PostingError::Blocked {
+        transaction_id: "txn_1".to_string(),
+    };
+    assert_eq!(Attempt::from_error(&frozen), Attempt::Retryable);
+    assert_eq!(Attempt::from_error(&closed), Attempt::Fatal);
+    assert_eq!(Attempt::from_error(&blocked), Attempt::Skipped);
+    // Database errors are recorded as failed runs and backed off, not retried every tick.
+    let database = PostingError::Database(sqlx::Error::PoolTimedOut);
+    assert_eq!(Attempt::from_error(&database), Attempt::Retryable);
+    assert_eq!(blocked.transaction_id(), Some("txn_1"));
+}
+
+#[test]
+fn test_pause_resume_cancel() {
+    assert_eq!(
+        ScheduleStatus::Active.apply(ScheduleAction::Pause).unwrap(),
+        ScheduleStatus::Paused
+    );
+    assert_eq!(
+        ScheduleStatus::Paused.apply(ScheduleAction::Resume).unwrap(),
+        ScheduleStatus::Active
+    );
+    assert_eq!(
+        ScheduleStatus::Paused.apply(ScheduleAction::Cancel).unwrap(),
+        ScheduleStatus::Cancelled
+    );
+    assert!(ScheduleStatus::Active.apply(ScheduleAction::Resume).is_err());
+    assert!(ScheduleStatus::Completed.apply(ScheduleAction::Cancel).is_err());
+
+    // Occurrences missed while paused are skipped on resume.
+    let start = at(2024, 1, 1, 0);
+    let weekly = schedule(Recurrence::Weekly { interval: 1 }, start);
+    assert_eq!(
+        weekly.resume_occurrence(at(2024, 1, 10, 0)),
+        Some(at(2024, 1, 15, 0))
+    );
+    assert_eq!(schedule(Recurrence::Once, start).resume_occurrence(at(2024, 1, 2, 0)), None);
+}
+
+#[test]
+fn test_create_request_validation_and_metadata() {
+    let now = at(2024, 1, 1, 0);
+    let req: CreateScheduleRequest = serde_json::from_value(json!({
+        "transaction": {"account_id": "acc_1", "amount": 9.99, "currency": "USD", "transaction_type": "payment"},
+        "recurrence": {"kind": "monthly", "day": 15},
+        "max_occurrences": 12
+    }))
+    .unwrap();
+    assert!(req.validate(now).is_ok());
+
+    let mut past = req.clone();
+    past.start_at = Some(now - Duration::days(1));
+    assert!(past.validate(now).is_err());
+
+    let mut early_end = req.clone();
+    early_end.end_at = Some(at(2024, 1, 10, 0));
+    assert!(early_end.validate(now).is_err());
+
+    let mut bad_retry = req.clone();
+    bad_retry.retry = Some(RetryPolicy {
+        max_retries: 3,
+        backoff_secs: 1,
+    });
+    assert!(bad_retry.validate(now).is_err());
+
+    let scheduled = schedule(Recurrence::Once, now).scheduled_request();
+    assert_eq!(
+        scheduled.metadata,
+        Some(json!({"plan": "pro", "schedule_id": "sch_1"}))
+    );
+}
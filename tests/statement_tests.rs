@@ -0,0 +1,148 @@
+use chrono::{NaiveDate, TimeZone, Utc};
+use metered_finance_api::models::{
+    finance::{Currency, TransactionStatus, TransactionType},
+    responses::TransactionResponse,
+    statement::{
+        html_escape, statement_filters, AccountStatement, AccountStatementFormat,
+        AccountStatementParams, TransactionTypeTotal, STATEMENT_COLUMNS,
+    },
+};
+
+fn date(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+}
+
+fn transaction(
+    id: &str,
+    amount: f64,
+    transaction_type: TransactionType,
+    status: TransactionStatus,
+) -> TransactionResponse {
+    TransactionResponse {
+        transaction_id: id.to_string(),
+        account_id: "acc_1".to_string(),
+        amount,
+        currency: Currency::USD,
+        transaction_type,
+        status,
+        description: Some("Order <42>, \"gift\"".to_string()),
+        metadata: None,
+        created_at: Utc.with_ymd_and_hms(2024, 3, 5, 10, 0, 0).unwrap(),
+        processed_at: None,
+        failure_reason: None,
+        fee: None,
+        links: None,
+    }
+}
+
+fn statement() -> AccountStatement {
+    AccountStatement::build(
+        "acc_1".to_string(),
+        Currency::USD,
+        (date(1), date(31)),
+        100.0,
+        vec![
+            transaction("txn_1", 20.0, TransactionType::Payment, TransactionStatus::Completed),
+            transaction("txn_2", 5.0, TransactionType::Fee, TransactionStatus::Failed),
+            transaction("txn_3", 30.0, TransactionType::Payment, TransactionStatus::Completed),
+            transaction("txn_4", 2.5, TransactionType::Fee, TransactionStatus::Completed),
+        ],
+        Utc.with_ymd_and_hms(2024, 4, 1, 0, 0, 0).unwrap(),
+    )
+}
+
+#[test]
+fn test_period_defaults_and_validation() {
+    let params = AccountStatementParams::default();
+    assert_eq!(params.period(date(18)), Ok((date(1), date(18))));
+
+    let params = AccountStatementParams {
+        from: Some(date(10)),
+        to: Some(date(9)),
+        format: AccountStatementFormat::Json,
+    };
+    assert!(params.period(date(18)).is_err());
+}
+
+#[test]
+fn test_statement_filters_cover_whole_days() {
+    let filters = statement_filters("acc_1", date(1), date(31));
+
+    assert_eq!(filters.account_id.as_deref(), Some("acc_1"));
+    assert_eq!(
+        filters.created_after,
+        Some(Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap())
+    );
+    assert_eq!(
+        filters.created_before.unwrap().to_rfc3339(),
+        "2024-03-31T23:59:59.999999+00:00"
+    );
+}
+
+#[test]
+fn test_running_balance_and_totals() {
+    let statement = statement();
+
+    let balances: Vec<f64> = statement
+        .transactions
+        .iter()
+        .map(|entry| entry.running_balance)
+        .collect();
+    assert_eq!(balances, vec![120.0, 120.0, 150.0, 152.5]);
+    assert_eq!(statement.opening_balance, 100.0);
+    assert_eq!(statement.closing_balance, 152.5);
+    assert_eq!(
+        statement.totals,
+        vec![
+            TransactionTypeTotal {
+                transaction_type: TransactionType::Payment,
+                count: 2,
+                amount: 50.0,
+            },
+            TransactionTypeTotal {
+                transaction_type: TransactionType::Fee,
+                count: 1,
+                amount: 2.5,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_json_flattens_transaction() {
+    let json = serde_json::to_value(statement()).unwrap();
+    let entry = &json["transactions"][0];
+
+    assert_eq!(entry["transaction_id"], "txn_1");
+    assert_eq!(entry["running_balance"], 120.0);
+    assert_eq!(json["from"], "2024-03-01");
+}
+
+#[test]
+fn test_csv_rows() {
+    let csv = statement().to_csv();
+    let lines: Vec<&str> = csv.lines().collect();
+
+    assert_eq!(lines[0], STATEMENT_COLUMNS.join(","));
+    assert_eq!(lines[1], "2024-03-01,,,,Opening balance,,100");
+    assert_eq!(
+        lines[2],
+        "2024-03-05T10:00:00Z,txn_1,payment,completed,\"Order <42>, \"\"gift\"\"\",20,120"
+    );
+    assert_eq!(lines[6], ",,payment,completed,Total (2 transactions),50,");
+    assert_eq!(lines[8], "2024-03-31,,,,Closing balance,,152.5");
+    assert_eq!(lines.len(), 9);
+}
+
+#[test]
+fn test_html_escapes_values() {
+    let html = statement().to_html();
+
+    assert!(html.contains("Order &lt;42&gt;, &quot;gift&quot;"));
+    assert!(html.contains("152.50 USD"));
+    assert_eq!(html_escape("a&b'"), "a&amp;b&#39;");
+    assert_eq!(
+        statement().file_name(AccountStatementFormat::Csv),
+        "statement_acc_1_2024-03-01_2024-03-31.csv"
+    );
+}
@@ -0,0 +1,122 @@
+use axum::{body::Bytes, extract::State, http::Request, routing::post, Router};
+use metered_finance_api::observability::telemetry::{
+    build_tracer_provider, extract_trace_context, make_request_span, otel_layers, record_key_id,
+    TRACER_NAME,
+};
+use opentelemetry::{
+    global,
+    trace::{TraceContextExt, TracerProvider as _},
+};
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use std::sync::{Arc, Mutex};
+use tracing_subscriber::layer::SubscriberExt;
+
+const TRACEPARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+const TRACE_ID: [u8; 16] = [
+    0x4b, 0xf9, 0x2f, 0x35, 0x77, 0xb3, 0x4d, 0xa6, 0xa3, 0xce, 0x92, 0x9d, 0x0e, 0x0e, 0x47, 0x36,
+];
+
+type Received = Arc<Mutex<Vec<Bytes>>>;
+
+/// Minimal stand-in for an OTLP/HTTP collector that keeps every export body.
+async fn spawn_collector() -> (String, Received) {
+    let received: Received = Arc::default();
+    let app = Router::new()
+        .route(
+            "/v1/traces",
+            post(|State(received): State<Received>, body: Bytes| async move {
+                received.lock().unwrap().push(body);
+            }),
+        )
+        .with_state(received.clone());
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    (format!("http://{}", addr), received)
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack.windows(needle.len()).any(|window| window == needle)
+}
+
+#[test]
+fn test_extract_trace_context_from_traceparent() {
+    global::set_text_map_propagator(TraceContextPropagator::new());
+
+    let mut headers = http::HeaderMap::new();
+    headers.insert("traceparent", TRACEPARENT.parse().unwrap());
+
+    let cx = extract_trace_context(&headers);
+    let span_context = cx.span().span_context().clone();
+    assert!(span_context.is_remote());
+    assert_eq!(
+        span_context.trace_id().to_string(),
+        "4bf92f3577b34da6a3ce929d0e0e4736"
+    );
+}
+
+#[test]
+fn test_extract_trace_context_without_header() {
+    global::set_text_map_propagator(TraceContextPropagator::new());
+
+    let cx = extract_trace_context(&http::HeaderMap::new());
+    assert!(!cx.span().span_context().is_valid());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_spans_exported_to_collector() {
+    global::set_text_map_propagator(TraceContextPropagator::new());
+
+    let (endpoint, received) = spawn_collector().await;
+    let provider = build_tracer_provider(&endpoint, "telemetry-test").unwrap();
+    let subscriber =
+        tracing_subscriber::registry().with(otel_layers(provider.tracer(TRACER_NAME)));
+
+    tracing::subscriber::with_default(subscriber, || {
+        let req = Request::get("/api/accounts/acc_123")
+            .header("traceparent", TRACEPARENT)
+            .header("x-request-id", "req-telemetry-123")
+            .body(())
+            .unwrap();
+
+        let span = make_request_span(&req);
+        let _entered = span.enter();
+
+        tracing::info_span!("auth.client").in_scope(|| {});
+        record_key_id("key_telemetry");
+
+        tracing::debug!(
+            target: "sqlx::query",
+            summary = "select 1",
+            db.statement = "",
+            rows_affected = 0u64,
+            rows_returned = 1u64,
+            elapsed_secs = 0.002f64,
+        );
+    });
+
+    let flush_provider = provider.clone();
+    tokio::task::spawn_blocking(move || flush_provider.force_flush())
+        .await
+        .unwrap()
+        .unwrap();
+
+    let bodies = received.lock().unwrap().concat();
+    assert!(!bodies.is_empty(), "collector received no spans");
+    assert!(contains(&bodies, b"telemetry-test"));
+    assert!(contains(&bodies, b"auth.client"));
+    assert!(contains(&bodies, b"db.query"));
+    assert!(contains(&bodies, b"select 1"));
+    assert!(contains(&bodies, b"req-telemetry-123"));
+    assert!(contains(&bodies, b"key_telemetry"));
+    assert!(contains(&bodies, &TRACE_ID));
+
+    tokio::task::spawn_blocking(move || provider.shutdown())
+        .await
+        .unwrap()
+        .unwrap();
+}
@@ -146,6 +146,9 @@ fn test_transaction_response_serialization() {
         metadata: None,
         created_at: chrono::Utc::now(),
         processed_at: Some(chrono::Utc::now()),
+        failure_reason: None,
+        fee: None,
+        links: None,
     };
 
     let json = serde_json::to_string(&response).unwrap();
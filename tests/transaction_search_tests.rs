@@ -0,0 +1,115 @@
+use axum::{extract::Query, http::Uri};
+use chrono::{TimeZone, Utc};
+use metered_finance_api::models::{
+    common::SortOrder,
+    finance::{
+        Currency, TransactionFilters, TransactionSortField, TransactionStatus, ValidationError,
+    },
+};
+
+fn parse(query: &str) -> Result<TransactionFilters, String> {
+    let uri: Uri = format!("/api/transactions?{}", query).parse().unwrap();
+    Query::<TransactionFilters>::try_from_uri(&uri)
+        .map(|Query(filters)| filters)
+        .map_err(|e| e.body_text())
+}
+
+#[test]
+fn test_parse_multi_value_filters() {
+    let filters = parse("status=pending,failed&currency=usd,EUR&min_amount=10&max_amount=250.5").unwrap();
+
+    assert_eq!(
+        filters.status,
+        Some(vec![TransactionStatus::Pending, TransactionStatus::Failed])
+    );
+    assert_eq!(filters.currency, Some(vec![Currency::USD, Currency::EUR]));
+    assert_eq!(filters.min_amount, Some(10.0));
+    assert_eq!(filters.max_amount, Some(250.5));
+    assert!(filters.validate().is_ok());
+}
+
+#[test]
+fn test_parse_errors_name_the_bad_value() {
+    let err = parse("status=pending,bogus").unwrap_err();
+    assert!(err.contains("Invalid transaction status: bogus"), "{}", err);
+
+    let err = parse("status=,").unwrap_err();
+    assert!(err.contains("expected at least one value"), "{}", err);
+
+    let err = parse("metadata=[1,2]").unwrap_err();
+    assert!(err.contains("metadata must be a JSON object"), "{}", err);
+
+    assert!(parse("sort_by=description").is_err());
+    assert!(parse("min_amount=ten").is_err());
+}
+
+#[test]
+fn test_validate_ranges_and_search() {
+    let filters = parse("min_amount=100&max_amount=10").unwrap();
+    assert_eq!(filters.validate(), Err(ValidationError::InvalidAmountRange));
+
+    let filters = parse("created_after=2024-02-01T00:00:00Z&created_before=2024-01-01T00:00:00Z").unwrap();
+    assert_eq!(filters.validate(), Err(ValidationError::InvalidDateRange));
+
+    let filters = parse("q=%20%20").unwrap();
+    assert_eq!(filters.validate(), Err(ValidationError::InvalidSearchQuery));
+
+    let filters = TransactionFilters {
+        q: Some("x".repeat(201)),
+        ..TransactionFilters::default()
+    };
+    assert_eq!(filters.validate(), Err(ValidationError::InvalidSearchQuery));
+}
+
+#[test]
+fn test_where_clause_binds_every_filter() {
+    let filters = parse(
+        "account_id=acc_1&status=pending,failed&min_amount=5&metadata=%7B%22order_id%22%3A%22ord_42%22%7D&q=50%25_off",
+    )
+    .unwrap();
+
+    let (clause, values) = filters.where_clause();
+    assert_eq!(
+        clause,
+        "WHERE 1=1 AND account_id = $1 AND status IN ($2, $3) AND amount >= $4::float8 \
+         AND metadata @> $5::jsonb AND description ILIKE $6"
+    );
+    assert_eq!(
+        values,
+        vec![
+            "acc_1".to_string(),
+            "pending".to_string(),
+            "failed".to_string(),
+            "5".to_string(),
+            r#"{"order_id":"ord_42"}"#.to_string(),
+            r"%50\%\_off%".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn test_sort_defaults_and_columns() {
+    let filters = TransactionFilters::default();
+    assert_eq!(
+        filters.sort(),
+        (TransactionSortField::CreatedAt, SortOrder::Desc)
+    );
+    assert_eq!(
+        TransactionSortField::CreatedAt.column(),
+        ("created_at", "timestamptz")
+    );
+
+    let filters = parse("sort_by=amount&sort_order=asc").unwrap();
+    assert_eq!(filters.sort(), (TransactionSortField::Amount, SortOrder::Asc));
+    assert_eq!(TransactionSortField::Amount.column(), ("amount", "float8"));
+}
+
+#[test]
+fn test_sort_value_of() {
+    let created_at = Utc.with_ymd_and_hms(2024, 3, 1, 12, 30, 0).unwrap();
+    assert_eq!(
+        TransactionSortField::CreatedAt.value_of(&created_at, 12.5),
+        "2024-03-01T12:30:00.000000Z"
+    );
+    assert_eq!(TransactionSortField::Amount.value_of(&created_at, 12.5), "12.5");
+}